@@ -0,0 +1,390 @@
+//! Point-in-polygon admin area assignment.
+//!
+//! Coordinates from GDACS events, CAP polygons or geocoded text resolve
+//! to P-coded admin areas by testing them against COD-AB boundary
+//! polygons (GeoJSON, as published on HDX). Polygon bounding boxes go in
+//! an R-tree so each lookup only runs the exact ray-casting test on the
+//! few boundaries whose box contains the point.
+
+use pyo3::exceptions::PyOSError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rayon::prelude::*;
+use rstar::primitives::{GeomWithData, Rectangle};
+use rstar::{RTree, AABB};
+use serde_json::{Map, Value};
+
+use crate::errors::{Failure, gazetteer_error, guarded, unlocked};
+
+/// Name property suffixes tried after `ADM{n}_PCODE`, in order.
+const NAME_SUFFIXES: &[&str] = &["EN", "NAME", "FR", "PT", "ES", "REF"];
+
+/// Polygon bounding box tagged with its boundary's index.
+type Entry = GeomWithData<Rectangle<[f64; 2]>, usize>;
+
+/// Rings of `[lon, lat]` points, as in GeoJSON.
+#[derive(Debug, Clone)]
+struct Polygon {
+    exterior: Vec<[f64; 2]>,
+    holes: Vec<Vec<[f64; 2]>>,
+}
+
+impl Polygon {
+    fn envelope(&self) -> AABB<[f64; 2]> {
+        AABB::from_points(self.exterior.iter())
+    }
+
+    fn contains(&self, point: [f64; 2]) -> bool {
+        ring_contains(&self.exterior, point) && !self.holes.iter().any(|h| ring_contains(h, point))
+    }
+}
+
+/// Even-odd ray casting; works for open or closed rings.
+fn ring_contains(ring: &[[f64; 2]], [x, y]: [f64; 2]) -> bool {
+    let mut inside = false;
+    let mut j = ring.len().wrapping_sub(1);
+    for (i, &[xi, yi]) in ring.iter().enumerate() {
+        let [xj, yj] = ring[j];
+        if (yi > y) != (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct AdminArea {
+    pub level: u8,
+    pub pcode: String,
+    pub name: Option<String>,
+    pub parent_pcode: Option<String>,
+}
+
+impl AdminArea {
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("level", self.level)?;
+        dict.set_item("pcode", &self.pcode)?;
+        dict.set_item("name", &self.name)?;
+        dict.set_item("parent_pcode", &self.parent_pcode)?;
+        Ok(dict)
+    }
+}
+
+/// Property lookup ignoring case (`ADM1_PCODE`, `adm1_pcode`).
+fn property<'a>(props: &'a Map<String, Value>, key: &str) -> Option<&'a str> {
+    props
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(key))
+        .and_then(|(_, v)| v.as_str())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+}
+
+/// Deepest `n` with an `ADM{n}_PCODE` property.
+fn infer_level(props: &Map<String, Value>) -> Option<u8> {
+    (0..=5).rev().find(|n| property(props, &format!("ADM{n}_PCODE")).is_some())
+}
+
+fn ring(value: &Value) -> Option<Vec<[f64; 2]>> {
+    value
+        .as_array()?
+        .iter()
+        .map(|p| {
+            let p = p.as_array()?;
+            Some([p.first()?.as_f64()?, p.get(1)?.as_f64()?])
+        })
+        .collect()
+}
+
+fn polygon(rings: &Value) -> Option<Polygon> {
+    let mut rings = rings.as_array()?.iter().map(ring);
+    let exterior = rings.next()??;
+    let holes = rings.collect::<Option<Vec<_>>>()?;
+    (exterior.len() >= 3).then_some(Polygon { exterior, holes })
+}
+
+/// Polygons of a Polygon or MultiPolygon geometry; None for other types
+/// and malformed coordinates.
+fn polygons(geometry: &Value) -> Option<Vec<Polygon>> {
+    let coordinates = geometry.get("coordinates")?;
+    match geometry.get("type")?.as_str()? {
+        "Polygon" => Some(vec![polygon(coordinates)?]),
+        "MultiPolygon" => coordinates.as_array()?.iter().map(polygon).collect(),
+        _ => None,
+    }
+}
+
+/// Property names to read features with.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Fields<'a> {
+    /// Admin level; inferred per feature from `ADM{n}_PCODE` if None.
+    pub level: Option<u8>,
+    pub pcode: Option<&'a str>,
+    pub name: Option<&'a str>,
+}
+
+/// Admin boundary polygons indexed by bounding box.
+#[pyclass(module = "moltis_rust_core")]
+#[derive(Default)]
+pub struct AdminBoundaries {
+    areas: Vec<AdminArea>,
+    polygons: Vec<(usize, Polygon)>,
+    tree: RTree<Entry>,
+}
+
+impl AdminBoundaries {
+    /// Add the features of a GeoJSON FeatureCollection; returns how many
+    /// were loaded. Features without a P-code or polygon are skipped.
+    pub(crate) fn add_geojson(&mut self, text: &str, fields: &Fields) -> Result<usize, Failure> {
+        let doc: Value = serde_json::from_str(text).map_err(|e| format!("invalid GeoJSON: {e}"))?;
+        let features = doc
+            .get("features")
+            .and_then(Value::as_array)
+            .ok_or_else(|| Failure::new("wrong_format", "GeoJSON is not a FeatureCollection"))?;
+        let empty = Map::new();
+        let mut loaded = 0;
+        for feature in features {
+            let props = feature.get("properties").and_then(Value::as_object).unwrap_or(&empty);
+            let Some(level) = fields.level.or_else(|| infer_level(props)) else { continue };
+            let pcode_key = fields.pcode.map_or_else(|| format!("ADM{level}_PCODE"), str::to_string);
+            let Some(pcode) = property(props, &pcode_key) else { continue };
+            let Some(shapes) = feature.get("geometry").and_then(polygons) else { continue };
+            let name = match fields.name {
+                Some(key) => property(props, key),
+                None => NAME_SUFFIXES
+                    .iter()
+                    .find_map(|s| property(props, &format!("ADM{level}_{s}")))
+                    .or_else(|| property(props, "name")),
+            };
+            let parent_pcode = level
+                .checked_sub(1)
+                .and_then(|parent| property(props, &format!("ADM{parent}_PCODE")))
+                .map(str::to_string);
+            let index = self.areas.len();
+            self.areas.push(AdminArea { level, pcode: pcode.to_string(), name: name.map(str::to_string), parent_pcode });
+            self.polygons.extend(shapes.into_iter().map(|p| (index, p)));
+            loaded += 1;
+        }
+        let entries = self
+            .polygons
+            .iter()
+            .enumerate()
+            .map(|(i, (_, p))| {
+                let envelope = p.envelope();
+                GeomWithData::new(Rectangle::from_corners(envelope.lower(), envelope.upper()), i)
+            })
+            .collect();
+        self.tree = RTree::bulk_load(entries);
+        Ok(loaded)
+    }
+
+    /// Areas containing the point, one per level (the first loaded wins
+    /// where boundaries overlap), ordered from level 0 down.
+    pub(crate) fn lookup(&self, lat: f64, lon: f64) -> Vec<&AdminArea> {
+        let point = [lon, lat];
+        let mut found: Vec<&AdminArea> = Vec::new();
+        let mut candidates: Vec<usize> = self
+            .tree
+            .locate_all_at_point(&point)
+            .map(|entry| entry.data)
+            .collect();
+        candidates.sort_unstable();
+        for i in candidates {
+            let (area, polygon) = &self.polygons[i];
+            let area = &self.areas[*area];
+            if found.iter().all(|a| a.level != area.level) && polygon.contains(point) {
+                found.push(area);
+            }
+        }
+        found.sort_by_key(|a| a.level);
+        found
+    }
+}
+
+#[pymethods]
+impl AdminBoundaries {
+    #[new]
+    fn py_new() -> PyResult<Self> {
+        guarded("AdminBoundaries.__new__", || Ok(Self::default()))
+    }
+
+    /// Load admin boundaries from a GeoJSON file.
+    ///
+    /// Parameters
+    /// ----------
+    /// path : str
+    ///     FeatureCollection of Polygon / MultiPolygon features, e.g. a
+    ///     COD-AB ``adm2`` layer from HDX.
+    /// level : int | None
+    ///     Admin level of the features. By default each feature's level
+    ///     is its deepest ``ADM{n}_PCODE`` property.
+    /// pcode_field : str | None
+    ///     P-code property. Default ``ADM{level}_PCODE``.
+    /// name_field : str | None
+    ///     Name property. Default the first of ``ADM{level}_EN``,
+    ///     ``ADM{level}_NAME``, ``_FR``, ``_PT``, ``_ES``, ``_REF`` or
+    ///     ``name``.
+    ///
+    /// Returns
+    /// -------
+    /// int
+    ///     Features loaded; those without a P-code or polygon geometry are
+    ///     skipped.
+    ///
+    /// Raises
+    /// ------
+    /// OSError
+    ///     If the file cannot be read.
+    /// GazetteerError
+    ///     If it isn't a GeoJSON FeatureCollection (``reason``
+    ///     ``"malformed"`` or ``"wrong_format"``). A ``ValueError``
+    ///     subclass.
+    #[pyo3(signature = (path, level=None, pcode_field=None, name_field=None))]
+    fn load(
+        &mut self,
+        path: &str,
+        level: Option<u8>,
+        pcode_field: Option<&str>,
+        name_field: Option<&str>,
+    ) -> PyResult<usize> {
+        guarded("AdminBoundaries.load", || {
+            let text = std::fs::read_to_string(path)
+                .map_err(|e| PyOSError::new_err(format!("cannot read {path}: {e}")))?;
+            let fields = Fields { level, pcode: pcode_field, name: name_field };
+            self.add_geojson(&text, &fields).map_err(|e| gazetteer_error(Some(path), e))
+        })
+    }
+
+    /// Load admin boundaries from GeoJSON text; parameters as ``load``.
+    #[pyo3(signature = (text, level=None, pcode_field=None, name_field=None))]
+    fn load_geojson(
+        &mut self,
+        text: &str,
+        level: Option<u8>,
+        pcode_field: Option<&str>,
+        name_field: Option<&str>,
+    ) -> PyResult<usize> {
+        guarded("AdminBoundaries.load_geojson", || {
+            let fields = Fields { level, pcode: pcode_field, name: name_field };
+            self.add_geojson(text, &fields).map_err(|e| gazetteer_error(None, e))
+        })
+    }
+
+    /// Admin areas containing a point, at every loaded level.
+    ///
+    /// Parameters
+    /// ----------
+    /// lat, lon : float
+    ///     WGS84 coordinates.
+    ///
+    /// Returns
+    /// -------
+    /// list[dict]
+    ///     ``{"level", "pcode", "name", "parent_pcode"}`` per level,
+    ///     ordered from the country down; empty outside every boundary.
+    fn admin_for_point(&self, py: Python<'_>, lat: f64, lon: f64) -> PyResult<Vec<Py<PyDict>>> {
+        guarded("AdminBoundaries.admin_for_point", || {
+            self.lookup(lat, lon).into_iter().map(|a| Ok(a.to_dict(py)?.unbind())).collect()
+        })
+    }
+
+    /// ``admin_for_point`` for many ``(lat, lon)`` points, in parallel.
+    fn admin_for_points(&self, py: Python<'_>, points: Vec<(f64, f64)>) -> PyResult<Vec<Vec<Py<PyDict>>>> {
+        let found: Vec<Vec<AdminArea>> = unlocked(py, "AdminBoundaries.admin_for_points", || {
+            points
+                .par_iter()
+                .map(|&(lat, lon)| self.lookup(lat, lon).into_iter().cloned().collect())
+                .collect()
+        })?;
+        found
+            .iter()
+            .map(|areas| areas.iter().map(|a| Ok(a.to_dict(py)?.unbind())).collect())
+            .collect()
+    }
+
+    fn __len__(&self) -> PyResult<usize> {
+        guarded("AdminBoundaries.__len__", || Ok(self.areas.len()))
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        guarded("AdminBoundaries.__repr__", || {
+            Ok(format!("AdminBoundaries(areas={}, polygons={})", self.areas.len(), self.polygons.len()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Two provinces side by side; a district in the first with a hole
+    // (a separately coded city) and a second, MultiPolygon district.
+    const ADM1: &str = r#"{"type": "FeatureCollection", "features": [
+        {"type": "Feature", "properties": {"ADM0_PCODE": "MZ", "ADM1_PCODE": "MZ09", "ADM1_PT": "Sofala"},
+         "geometry": {"type": "Polygon", "coordinates": [[[34, -21], [36, -21], [36, -18], [34, -18], [34, -21]]]}},
+        {"type": "Feature", "properties": {"ADM0_PCODE": "MZ", "ADM1_PCODE": "MZ04", "ADM1_PT": "Zambezia"},
+         "geometry": {"type": "Polygon", "coordinates": [[[36, -21], [38, -21], [38, -18], [36, -18], [36, -21]]]}},
+        {"type": "Feature", "properties": {"ADM1_PCODE": null},
+         "geometry": {"type": "Point", "coordinates": [35, -20]}}
+    ]}"#;
+
+    const ADM2: &str = r#"{"type": "FeatureCollection", "features": [
+        {"type": "Feature", "properties": {"ADM1_PCODE": "MZ09", "ADM2_PCODE": "MZ0901", "ADM2_PT": "Dondo"},
+         "geometry": {"type": "Polygon", "coordinates": [
+            [[34, -21], [35, -21], [35, -19], [34, -19], [34, -21]],
+            [[34.5, -20], [34.8, -20], [34.8, -19.5], [34.5, -19.5], [34.5, -20]]]}},
+        {"type": "Feature", "properties": {"ADM1_PCODE": "MZ04", "ADM2_PCODE": "MZ0407", "ADM2_PT": "Quelimane"},
+         "geometry": {"type": "MultiPolygon", "coordinates": [
+            [[[36, -21], [37, -21], [37, -20], [36, -20], [36, -21]]],
+            [[[37.5, -19], [38, -19], [38, -18.5], [37.5, -18.5], [37.5, -19]]]]}}
+    ]}"#;
+
+    fn boundaries() -> AdminBoundaries {
+        let mut b = AdminBoundaries::default();
+        assert_eq!(b.add_geojson(ADM1, &Fields::default()).unwrap(), 2);
+        assert_eq!(b.add_geojson(ADM2, &Fields::default()).unwrap(), 2);
+        b
+    }
+
+    fn pcodes(b: &AdminBoundaries, lat: f64, lon: f64) -> Vec<&str> {
+        b.lookup(lat, lon).iter().map(|a| a.pcode.as_str()).collect()
+    }
+
+    #[test]
+    fn test_lookup_levels() {
+        let b = boundaries();
+        let found = b.lookup(-20.5, 34.5);
+        assert_eq!(found.len(), 2);
+        assert_eq!((found[0].level, found[0].name.as_deref()), (1, Some("Sofala")));
+        assert_eq!(found[1].pcode, "MZ0901");
+        assert_eq!(found[1].parent_pcode.as_deref(), Some("MZ09"));
+        // Second part of a MultiPolygon
+        assert_eq!(pcodes(&b, -18.7, 37.7), vec!["MZ04", "MZ0407"]);
+        // Province only, outside any district
+        assert_eq!(pcodes(&b, -18.5, 35.5), vec!["MZ09"]);
+        assert!(b.lookup(-25.0, 32.0).is_empty());
+    }
+
+    #[test]
+    fn test_holes_excluded() {
+        let b = boundaries();
+        assert_eq!(pcodes(&b, -19.7, 34.6), vec!["MZ09"]);
+        assert!(ring_contains(&[[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]], [0.2, 0.2]));
+        assert!(!ring_contains(&[[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]], [0.8, 0.8]));
+    }
+
+    #[test]
+    fn test_fields_and_errors() {
+        let mut b = AdminBoundaries::default();
+        let custom = r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "properties": {"code": "XX01", "label": "North"},
+             "geometry": {"type": "Polygon", "coordinates": [[[0, 0], [1, 0], [1, 1], [0, 1]]]}}]}"#;
+        let fields = Fields { level: Some(1), pcode: Some("code"), name: Some("label") };
+        assert_eq!(b.add_geojson(custom, &fields).unwrap(), 1);
+        assert_eq!(b.lookup(0.5, 0.5)[0].name.as_deref(), Some("North"));
+        assert!(b.add_geojson("{\"type\": \"Feature\"}", &Fields::default()).is_err());
+        assert!(b.add_geojson("not json", &Fields::default()).is_err());
+    }
+}
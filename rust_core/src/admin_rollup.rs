@@ -0,0 +1,305 @@
+//! Roll-up of admin-area figures to one admin level.
+//!
+//! Articles attribute figures at whatever level they name: a district
+//! here, a province there, a locality elsewhere. Summing them as they
+//! come double counts (a district's deaths are in its province's toll)
+//! and listing them as they come gives mixed-level output. `roll_up_admin`
+//! resolves each record's area in the gazetteer, walks its hierarchy up to
+//! the wanted level, and combines the figures under each area of that
+//! level consistently:
+//!
+//! - repeated reports for the same area keep the highest value;
+//! - sibling areas (two districts of a province) add up;
+//! - an area reported directly and through its sub-areas keeps the larger
+//!   of its own figure and its sub-areas' sum.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::errors::{unlocked, Failure};
+use crate::gazetteer::{Gazetteer, Place};
+
+/// A record's area: name and, when known, its admin level.
+pub(crate) type AreaRef = (String, Option<u8>);
+
+/// A contributing area and the ones reported under it.
+#[derive(Debug, Default)]
+struct Node {
+    name: String,
+    /// Highest value per key reported for this area itself.
+    direct: HashMap<String, i64>,
+    children: BTreeMap<String, Node>,
+}
+
+impl Node {
+    fn add(&mut self, figures: &HashMap<String, i64>) {
+        for (key, &value) in figures {
+            let best = self.direct.entry(key.clone()).or_insert(value);
+            *best = (*best).max(value);
+        }
+    }
+
+    /// Figures per key: the larger of the area's own report and its
+    /// sub-areas' sum.
+    fn total(&self) -> HashMap<String, i64> {
+        let mut sums: HashMap<String, i64> = HashMap::new();
+        for child in self.children.values() {
+            for (key, value) in child.total() {
+                *sums.entry(key).or_default() += value;
+            }
+        }
+        for (key, &value) in &self.direct {
+            let sum = sums.entry(key.clone()).or_default();
+            *sum = (*sum).max(value);
+        }
+        sums
+    }
+
+    /// Names of the reported areas at and below this one.
+    fn areas(&self, out: &mut Vec<String>) {
+        if !self.direct.is_empty() {
+            out.push(self.name.clone());
+        }
+        for child in self.children.values() {
+            child.areas(out);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Rollup {
+    /// Gazetteer id: a P-code for HDX gazetteers, else a GeoNames id.
+    pub id: String,
+    pub name: String,
+    pub country: String,
+    pub figures: BTreeMap<String, i64>,
+    /// Reported areas rolled into this one.
+    pub areas: Vec<String>,
+    pub records: usize,
+}
+
+/// The place `area` most likely names: admin divisions at the record's
+/// level first, then as `Gazetteer::find` ranks them.
+fn resolve(gazetteer: &Gazetteer, area: &AreaRef, countries: &HashSet<String>) -> Result<Option<Place>, Failure> {
+    let candidates: Vec<Place> = gazetteer
+        .find(&area.0)?
+        .into_iter()
+        .filter(|p| countries.is_empty() || countries.contains(&p.country))
+        .map(Cow::into_owned)
+        .collect();
+    let pick = candidates.iter().position(|p| area.1.is_some() && p.admin_level == area.1).unwrap_or(0);
+    Ok(candidates.into_iter().nth(pick))
+}
+
+/// Roll `(area, figures)` records up to admin `level`; also returns the
+/// indices of records that couldn't be placed under an area of that
+/// level (unknown names, or areas above it).
+pub(crate) fn roll_up(
+    records: &[(AreaRef, HashMap<String, i64>)],
+    gazetteer: &Gazetteer,
+    level: u8,
+    countries: &HashSet<String>,
+) -> Result<(Vec<Rollup>, Vec<usize>), Failure> {
+    let mut roots: BTreeMap<String, (Place, Node, usize)> = BTreeMap::new();
+    let mut unresolved = Vec::new();
+    for (i, (area, figures)) in records.iter().enumerate() {
+        let Some(place) = resolve(gazetteer, area, countries)? else {
+            unresolved.push(i);
+            continue;
+        };
+        // Ancestors country first, then the place itself
+        let mut chain: Vec<Place> =
+            place.parents.iter().map(|p| gazetteer.place(*p).map(Cow::into_owned)).collect::<Result<_, _>>()?;
+        chain.push(place);
+        let Some(top) = chain.iter().position(|p| p.admin_level == Some(level)) else {
+            unresolved.push(i);
+            continue;
+        };
+        let root = chain[top].clone();
+        let (_, node, count) = roots.entry(root.id.clone()).or_insert_with(|| {
+            let node = Node { name: root.name.clone(), ..Node::default() };
+            (root, node, 0)
+        });
+        *count += 1;
+        let node = chain[top + 1..].iter().fold(node, |node, p| {
+            node.children.entry(p.id.clone()).or_insert_with(|| Node { name: p.name.clone(), ..Node::default() })
+        });
+        node.add(figures);
+    }
+    let rollups = roots
+        .into_values()
+        .map(|(place, node, records)| {
+            let mut areas = Vec::new();
+            node.areas(&mut areas);
+            Rollup {
+                id: place.id,
+                name: place.name,
+                country: place.country,
+                figures: node.total().into_iter().collect(),
+                areas,
+                records,
+            }
+        })
+        .collect();
+    Ok((rollups, unresolved))
+}
+
+/// Area of a record: ``admin_area`` as a name or ``(name, level)``.
+fn area_of(record: &Bound<'_, PyDict>) -> PyResult<Option<AreaRef>> {
+    let Some(area) = record.get_item("admin_area")?.filter(|a| !a.is_none()) else {
+        return Ok(None);
+    };
+    if let Ok(name) = area.extract::<String>() {
+        return Ok(Some((name, None)));
+    }
+    let (name, level): (String, i32) = area.extract()?;
+    Ok(Some((name, u8::try_from(level).ok())))
+}
+
+/// Roll records' figures up to one admin level, with P-codes.
+///
+/// Each record's ``admin_area`` is resolved in the gazetteer and its
+/// figures attributed to the ancestor at ``level`` (the area itself if
+/// it is at that level). Under each such area, repeated reports for the
+/// same place keep their highest value, sibling places add up, and a
+/// place reported both directly and through its sub-areas keeps the
+/// larger of the two.
+///
+/// Parameters
+/// ----------
+/// records : list[dict]
+///     Records with ``admin_area`` (a name, or ``(name, admin_level)`` as
+///     from ``process_article``) and ``figures`` (dict[str, int]).
+/// gazetteer : Gazetteer
+///     Loaded gazetteer; with HDX COD data the ids are P-codes.
+/// level : int
+///     Admin level to roll up to: 1 (default) for provinces, 0 for
+///     countries.
+/// countries : list[str] | None
+///     Country codes (as in the gazetteer) the records are about; names
+///     elsewhere aren't matched.
+///
+/// Returns
+/// -------
+/// dict
+///     ``areas``: one dict per area at ``level``, ordered by id — ``id``
+///     (P-code or GeoNames id), ``name``, ``country``, ``figures``
+///     (dict[str, int]), ``areas`` (names of the reported places rolled
+///     in) and ``records`` (count). ``unresolved``: indices of records
+///     with no area, an unknown one, or one above ``level``.
+///
+/// Raises
+/// ------
+/// ValueError
+///     If ``level`` is above 4.
+#[pyfunction]
+#[pyo3(signature = (records, gazetteer, level=1, countries=None))]
+pub fn roll_up_admin(
+    py: Python<'_>,
+    records: &Bound<'_, PyList>,
+    gazetteer: PyRef<'_, Gazetteer>,
+    level: u8,
+    countries: Option<Vec<String>>,
+) -> PyResult<Py<PyDict>> {
+    if level > 4 {
+        return Err(PyValueError::new_err("level must be 0 to 4"));
+    }
+    let mut areas = Vec::with_capacity(records.len());
+    let mut missing = Vec::new();
+    for (i, record) in records.iter().enumerate() {
+        let record = record.downcast::<PyDict>()?;
+        let figures = record.get_item("figures")?.map(|f| f.extract::<Option<HashMap<String, i64>>>()).transpose()?;
+        match area_of(record)? {
+            Some(area) => areas.push(((area, figures.flatten().unwrap_or_default()), i)),
+            None => missing.push(i),
+        }
+    }
+    let gazetteer = &*gazetteer;
+    let countries: HashSet<String> = countries.unwrap_or_default().iter().map(|c| c.trim().to_uppercase()).collect();
+    let (rollups, unresolved) = unlocked(py, "roll_up_admin", || {
+        let (records, indices): (Vec<_>, Vec<usize>) = areas.into_iter().unzip();
+        let (rollups, unresolved) = roll_up(&records, gazetteer, level, &countries)?;
+        let mut unresolved: Vec<usize> = unresolved.into_iter().map(|i| indices[i]).chain(missing).collect();
+        unresolved.sort_unstable();
+        Ok((rollups, unresolved))
+    })?
+    .map_err(|e| gazetteer.error(e))?;
+
+    let list = PyList::empty_bound(py);
+    for rollup in rollups {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("id", rollup.id)?;
+        dict.set_item("name", rollup.name)?;
+        dict.set_item("country", rollup.country)?;
+        dict.set_item("figures", rollup.figures)?;
+        dict.set_item("areas", rollup.areas)?;
+        dict.set_item("records", rollup.records)?;
+        list.append(dict)?;
+    }
+    let result = PyDict::new_bound(py);
+    result.set_item("areas", list)?;
+    result.set_item("unresolved", unresolved)?;
+    Ok(result.unbind())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gazetteer::Filter;
+
+    fn gazetteer() -> Gazetteer {
+        let csv = "ADM0_EN,ADM0_PCODE,ADM1_PT,ADM1_PCODE,ADM2_PT,ADM2_PCODE,ADM3_PT,ADM3_PCODE\n\
+                   Mozambique,MZ,Sofala,MZ07,Beira,MZ0701,Munhava,MZ070101\n\
+                   Mozambique,MZ,Sofala,MZ07,Dondo,MZ0705,Mafambisse,MZ070501\n\
+                   Mozambique,MZ,Zambezia,MZ09,Quelimane,MZ0901,Maquival,MZ090101\n";
+        let mut g = Gazetteer::default();
+        g.add_hdx(csv, &Filter::default()).unwrap();
+        g
+    }
+
+    fn record(area: &str, level: Option<u8>, figures: &[(&str, i64)]) -> (AreaRef, HashMap<String, i64>) {
+        ((area.to_string(), level), figures.iter().map(|(k, v)| (k.to_string(), *v)).collect())
+    }
+
+    #[test]
+    fn test_rolls_up_to_admin1() {
+        let g = gazetteer();
+        let records = vec![
+            record("Beira", Some(2), &[("deaths", 10)]),
+            // A later, higher toll for the same district
+            record("Beira", Some(2), &[("deaths", 14)]),
+            record("Munhava", Some(3), &[("deaths", 3), ("displaced", 900)]),
+            record("Dondo", Some(2), &[("deaths", 6)]),
+            record("Maquival", None, &[("displaced", 200)]),
+        ];
+        let (rollups, unresolved) = roll_up(&records, &g, 1, &HashSet::new()).unwrap();
+        assert!(unresolved.is_empty());
+        let sofala = &rollups[0];
+        assert_eq!((sofala.id.as_str(), sofala.name.as_str(), sofala.records), ("MZ07", "Sofala", 4));
+        // Beira's own 14 outweighs Munhava's 3; Dondo adds 6
+        assert_eq!(sofala.figures, BTreeMap::from([("deaths".to_string(), 20), ("displaced".to_string(), 900)]));
+        assert_eq!(sofala.areas, vec!["Beira", "Munhava", "Dondo"]);
+        assert_eq!((rollups[1].id.as_str(), rollups[1].figures["displaced"]), ("MZ09", 200));
+
+        let (countries, _) = roll_up(&records, &g, 0, &HashSet::new()).unwrap();
+        assert_eq!((countries[0].id.as_str(), countries[0].figures["deaths"]), ("MZ", 20));
+    }
+
+    #[test]
+    fn test_unresolved_records() {
+        let g = gazetteer();
+        let records = vec![
+            record("Mozambique", Some(0), &[("deaths", 50)]),
+            record("Atlantis", None, &[("deaths", 1)]),
+            record("Sofala", Some(1), &[("deaths", 8)]),
+        ];
+        let (rollups, unresolved) = roll_up(&records, &g, 1, &HashSet::new()).unwrap();
+        assert_eq!(unresolved, vec![0, 1]);
+        assert_eq!(rollups[0].figures["deaths"], 8);
+        let malawi: HashSet<String> = ["MW".to_string()].into();
+        assert_eq!(roll_up(&records, &g, 1, &malawi).unwrap().1, vec![0, 1, 2]);
+    }
+}
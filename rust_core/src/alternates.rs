@@ -0,0 +1,148 @@
+//! Canonical selection among hreflang / alternate URLs.
+//!
+//! Multilingual sources publish the same article under several URLs
+//! (`/en/...`, `/fr/...`, `?lang=es`). Picking one representative lets
+//! the mirrors collapse into a single frontier entry.
+
+use pyo3::prelude::*;
+use url::Url;
+
+use crate::url_canonical::canonicalize_url;
+use crate::url_hints::hints_for;
+use crate::errors::guarded;
+
+/// Used when the caller gives no language priority: the UN working
+/// languages our analysts read, English first.
+static DEFAULT_LANGUAGES: &[&str] = &["en", "fr", "es", "ar", "pt", "ru", "zh"];
+
+fn primary_subtag(lang: &str) -> String {
+    lang.trim()
+        .split(['-', '_'])
+        .next()
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+/// Rank of an alternate: lower is better. Listed languages rank by
+/// position, `x-default` right after them, anything else last.
+fn language_rank(lang: Option<&str>, languages: &[String]) -> usize {
+    let lang = match lang {
+        Some(l) if l.trim().eq_ignore_ascii_case("x-default") => return languages.len(),
+        Some(l) => primary_subtag(l),
+        None => return languages.len() + 1,
+    };
+    languages
+        .iter()
+        .position(|l| primary_subtag(l) == lang)
+        .unwrap_or(languages.len() + 1)
+}
+
+pub(crate) fn choose_canonical(
+    alternates: &[(String, Option<String>)],
+    canonical: Option<&str>,
+    languages: &[String],
+) -> Option<String> {
+    if let Some(c) = canonical.filter(|c| Url::parse(c.trim()).is_ok()) {
+        return Some(canonicalize_url(c));
+    }
+    alternates
+        .iter()
+        .filter(|(url, _)| Url::parse(url.trim()).is_ok())
+        .map(|(url, hreflang)| {
+            let canonical = canonicalize_url(url);
+            // Fall back to a language segment in the path ("/fr/")
+            let lang = hreflang.clone().or_else(|| hints_for(&canonical).language);
+            (language_rank(lang.as_deref(), languages), canonical)
+        })
+        .min()
+        .map(|(_, url)| url)
+}
+
+/// Pick the canonical representative of a set of alternate URLs.
+///
+/// Parameters
+/// ----------
+/// alternates : list[tuple[str, str | None]]
+///     ``(url, hreflang)`` pairs for the same article. A missing hreflang
+///     falls back to a language segment in the URL path.
+/// canonical : str | None
+///     The page's ``rel=canonical`` URL; preferred when given.
+/// languages : list[str] | None
+///     Language priority, best first. Defaults to
+///     ``["en", "fr", "es", "ar", "pt", "ru", "zh"]``; ``x-default`` ranks
+///     after the listed languages.
+///
+/// Returns
+/// -------
+/// str | None
+///     Canonicalized representative URL (ties go to the lexicographically
+///     smallest), or None if no URL is valid.
+#[pyfunction]
+#[pyo3(signature = (alternates, canonical=None, languages=None))]
+pub fn select_canonical_url(
+    alternates: Vec<(String, Option<String>)>,
+    canonical: Option<&str>,
+    languages: Option<Vec<String>>,
+) -> PyResult<Option<String>> {
+    guarded("select_canonical_url", || {
+        let languages = languages
+            .unwrap_or_else(|| DEFAULT_LANGUAGES.iter().map(|l| l.to_string()).collect());
+        Ok(choose_canonical(&alternates, canonical, &languages))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alts(pairs: &[(&str, Option<&str>)]) -> Vec<(String, Option<String>)> {
+        pairs
+            .iter()
+            .map(|(u, l)| (u.to_string(), l.map(str::to_string)))
+            .collect()
+    }
+
+    #[test]
+    fn test_rel_canonical_wins() {
+        let a = alts(&[("https://example.org/fr/story", Some("fr"))]);
+        assert_eq!(
+            select_canonical_url(a, Some("https://example.org/en/story?utm_source=x"), None).unwrap(),
+            Some("https://example.org/en/story".to_string())
+        );
+    }
+
+    #[test]
+    fn test_language_priority() {
+        let a = alts(&[
+            ("https://example.org/pt-br/story", Some("pt-BR")),
+            ("https://example.org/story", Some("x-default")),
+            ("https://example.org/fr/story", Some("fr")),
+        ]);
+        assert_eq!(
+            select_canonical_url(a.clone(), None, None).unwrap(),
+            Some("https://example.org/fr/story".to_string())
+        );
+        assert_eq!(
+            select_canonical_url(a.clone(), None, Some(vec!["pt".to_string()])).unwrap(),
+            Some("https://example.org/pt-br/story".to_string())
+        );
+        assert_eq!(
+            select_canonical_url(a, None, Some(vec!["sw".to_string()])).unwrap(),
+            Some("https://example.org/story".to_string())
+        );
+    }
+
+    #[test]
+    fn test_path_language_fallback_and_invalid() {
+        let a = alts(&[
+            ("https://example.org/es/nota", None),
+            ("https://example.org/en/note", None),
+            ("not a url", Some("en")),
+        ]);
+        assert_eq!(
+            select_canonical_url(a, None, None).unwrap(),
+            Some("https://example.org/en/note".to_string())
+        );
+        assert_eq!(select_canonical_url(alts(&[("nope", None)]), None, None).unwrap(), None);
+    }
+}
@@ -0,0 +1,419 @@
+//! Arrow / Parquet output for processed-article records.
+//!
+//! The nightly analytical exports used to build a Python dict per article
+//! and serialize millions of them. `ArticleExporter` runs the pipeline and
+//! appends each record straight into Arrow column builders, then writes
+//! Parquet or hands the record batches to pyarrow through the Arrow C
+//! stream interface (`__arrow_c_stream__`) without copying.
+
+use arrow_array::builder::{
+    BooleanBuilder, FixedSizeBinaryBuilder, Float64Builder, Int32Builder, Int64Builder,
+    ListBuilder, MapBuilder, StringBuilder, TimestampSecondBuilder,
+};
+use arrow_array::ffi_stream::FFI_ArrowArrayStream;
+use arrow_array::{ArrayRef, RecordBatch, RecordBatchIterator};
+use arrow_schema::{Field, Schema, SchemaRef};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use pyo3::exceptions::{PyOSError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyCapsule;
+use rayon::prelude::*;
+use std::ffi::CString;
+use std::fs::File;
+use std::sync::Arc;
+
+use crate::errors::{guarded, unlocked};
+use crate::pipeline::{current_time, ArticleRecord, Pipeline, PipelineConfig};
+
+/// Rows per record batch.
+const DEFAULT_BATCH_ROWS: usize = 65_536;
+
+/// Column builders for one record batch. Columns of disabled stages are
+/// null, so the schema is the same whatever the pipeline runs.
+struct RecordColumns {
+    rows: usize,
+    canonical_url: StringBuilder,
+    language: StringBuilder,
+    language_confidence: Float64Builder,
+    published: TimestampSecondBuilder,
+    published_confidence: StringBuilder,
+    figures: MapBuilder<StringBuilder, Int64Builder>,
+    impact_type: StringBuilder,
+    impact_types: ListBuilder<StringBuilder>,
+    need_types: ListBuilder<StringBuilder>,
+    severity: Int32Builder,
+    is_risk: BooleanBuilder,
+    response_actor: StringBuilder,
+    response_actor_type: StringBuilder,
+    admin_area: StringBuilder,
+    admin_level: Int32Builder,
+    storms: ListBuilder<StringBuilder>,
+    /// 128-bit fingerprint, big-endian.
+    fingerprint: FixedSizeBinaryBuilder,
+}
+
+impl Default for RecordColumns {
+    fn default() -> Self {
+        Self {
+            rows: 0,
+            canonical_url: StringBuilder::new(),
+            language: StringBuilder::new(),
+            language_confidence: Float64Builder::new(),
+            published: TimestampSecondBuilder::new().with_timezone("UTC"),
+            published_confidence: StringBuilder::new(),
+            figures: MapBuilder::new(None, StringBuilder::new(), Int64Builder::new()),
+            impact_type: StringBuilder::new(),
+            impact_types: ListBuilder::new(StringBuilder::new()),
+            need_types: ListBuilder::new(StringBuilder::new()),
+            severity: Int32Builder::new(),
+            is_risk: BooleanBuilder::new(),
+            response_actor: StringBuilder::new(),
+            response_actor_type: StringBuilder::new(),
+            admin_area: StringBuilder::new(),
+            admin_level: Int32Builder::new(),
+            storms: ListBuilder::new(StringBuilder::new()),
+            fingerprint: FixedSizeBinaryBuilder::new(16),
+        }
+    }
+}
+
+fn append_list<'a>(builder: &mut ListBuilder<StringBuilder>, items: Option<impl IntoIterator<Item = &'a str>>) {
+    match items {
+        Some(items) => {
+            for item in items {
+                builder.values().append_value(item);
+            }
+            builder.append(true);
+        }
+        None => builder.append(false),
+    }
+}
+
+impl RecordColumns {
+    fn append(&mut self, record: &ArticleRecord) {
+        self.rows += 1;
+        self.canonical_url.append_value(&record.canonical_url);
+        let language = record.language.flatten();
+        self.language.append_option(language.map(|(code, _)| code));
+        self.language_confidence.append_option(language.map(|(_, confidence)| confidence));
+        let published = record.published.as_ref().and_then(Option::as_ref);
+        self.published.append_option(published.map(|p| p.timestamp.timestamp()));
+        self.published_confidence.append_option(published.map(|p| p.confidence.as_str()));
+        match &record.figures {
+            Some(figures) => {
+                let mut pairs: Vec<_> = figures.iter().collect();
+                pairs.sort();
+                for (key, value) in pairs {
+                    self.figures.keys().append_value(key);
+                    self.figures.values().append_value(*value);
+                }
+                // Only fails when keys and values are out of step
+                self.figures.append(true).expect("figure keys and values appended in pairs");
+            }
+            None => self.figures.append(false).expect("no figure entries appended"),
+        }
+        let impacts = record.impacts.as_ref();
+        self.impact_type.append_option(impacts.map(|(dominant, _)| dominant));
+        append_list(&mut self.impact_types, impacts.map(|(_, all)| all.iter().map(String::as_str)));
+        append_list(&mut self.need_types, record.need_types.as_ref().map(|n| n.iter().map(String::as_str)));
+        self.severity.append_option(record.severity);
+        self.is_risk.append_option(record.is_risk);
+        let actor = record.response_actor.as_ref().and_then(Option::as_ref);
+        self.response_actor.append_option(actor.map(|(name, _)| name));
+        self.response_actor_type.append_option(actor.map(|(_, kind)| kind));
+        let area = record.admin_area.as_ref().and_then(Option::as_ref);
+        self.admin_area.append_option(area.map(|(name, _)| name));
+        self.admin_level.append_option(area.map(|(_, level)| *level));
+        append_list(&mut self.storms, record.storms.as_ref().map(|s| s.iter().map(|(name, _)| name.as_str())));
+        match record.fingerprint {
+            Some(fingerprint) => self
+                .fingerprint
+                .append_value(fingerprint.to_be_bytes())
+                .expect("fingerprint is 16 bytes"),
+            None => self.fingerprint.append_null(),
+        }
+    }
+
+    fn finish(&mut self) -> RecordBatch {
+        self.rows = 0;
+        let columns: Vec<(&str, ArrayRef)> = vec![
+            ("canonical_url", Arc::new(self.canonical_url.finish())),
+            ("language", Arc::new(self.language.finish())),
+            ("language_confidence", Arc::new(self.language_confidence.finish())),
+            ("published", Arc::new(self.published.finish())),
+            ("published_confidence", Arc::new(self.published_confidence.finish())),
+            ("figures", Arc::new(self.figures.finish())),
+            ("impact_type", Arc::new(self.impact_type.finish())),
+            ("impact_types", Arc::new(self.impact_types.finish())),
+            ("need_types", Arc::new(self.need_types.finish())),
+            ("severity", Arc::new(self.severity.finish())),
+            ("is_risk", Arc::new(self.is_risk.finish())),
+            ("response_actor", Arc::new(self.response_actor.finish())),
+            ("response_actor_type", Arc::new(self.response_actor_type.finish())),
+            ("admin_area", Arc::new(self.admin_area.finish())),
+            ("admin_level", Arc::new(self.admin_level.finish())),
+            ("storms", Arc::new(self.storms.finish())),
+            ("fingerprint", Arc::new(self.fingerprint.finish())),
+        ];
+        // Only the URL is always present; a fixed schema keeps batches
+        // compatible however many nulls each one has
+        let fields: Vec<Field> = columns
+            .iter()
+            .map(|(name, array)| Field::new(*name, array.data_type().clone(), *name != "canonical_url"))
+            .collect();
+        let arrays = columns.into_iter().map(|(_, array)| array).collect();
+        RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays).expect("record columns match the schema")
+    }
+}
+
+/// Schema of exported record batches.
+pub(crate) fn record_schema() -> SchemaRef {
+    RecordColumns::default().finish().schema()
+}
+
+/// Accumulates records into record batches of `batch_rows` rows.
+pub(crate) struct RecordBatches {
+    batch_rows: usize,
+    current: RecordColumns,
+    done: Vec<RecordBatch>,
+}
+
+impl RecordBatches {
+    pub(crate) fn new(batch_rows: usize) -> Self {
+        Self {
+            batch_rows: batch_rows.max(1),
+            current: RecordColumns::default(),
+            done: Vec::new(),
+        }
+    }
+
+    pub(crate) fn push(&mut self, record: &ArticleRecord) {
+        self.current.append(record);
+        if self.current.rows >= self.batch_rows {
+            self.done.push(self.current.finish());
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.done.iter().map(RecordBatch::num_rows).sum::<usize>() + self.current.rows
+    }
+
+    /// All batches so far, leaving the accumulator empty.
+    pub(crate) fn take(&mut self) -> Vec<RecordBatch> {
+        if self.current.rows > 0 {
+            self.done.push(self.current.finish());
+        }
+        std::mem::take(&mut self.done)
+    }
+}
+
+fn compression(name: &str) -> Result<Compression, String> {
+    match name.to_ascii_lowercase().as_str() {
+        "snappy" => Ok(Compression::SNAPPY),
+        "none" | "uncompressed" => Ok(Compression::UNCOMPRESSED),
+        _ => Err(format!("unsupported compression {name:?}: expected 'snappy' or 'none'")),
+    }
+}
+
+/// Write batches to a Parquet file; returns the row count.
+pub(crate) fn write_parquet(path: &str, batches: &[RecordBatch], compression: Compression) -> Result<usize, String> {
+    let file = File::create(path).map_err(|e| format!("cannot create {path}: {e}"))?;
+    let props = WriterProperties::builder().set_compression(compression).build();
+    let mut writer =
+        ArrowWriter::try_new(file, record_schema(), Some(props)).map_err(|e| format!("cannot write {path}: {e}"))?;
+    for batch in batches {
+        writer.write(batch).map_err(|e| format!("cannot write {path}: {e}"))?;
+    }
+    writer.close().map_err(|e| format!("cannot write {path}: {e}"))?;
+    Ok(batches.iter().map(RecordBatch::num_rows).sum())
+}
+
+/// Runs a pipeline and collects the records as Arrow record batches.
+///
+/// Records never become Python objects: export them with
+/// ``write_parquet`` or hand them to pyarrow (``pa.table(exporter)`` or
+/// ``pa.RecordBatchReader.from_stream(exporter)``), which takes the
+/// buffers without copying. Both drain the exporter.
+///
+/// Columns: ``canonical_url``, ``language``, ``language_confidence``,
+/// ``published`` (timestamp[s, UTC]), ``published_confidence``,
+/// ``figures`` (map<str, int64>), ``impact_type``, ``impact_types``,
+/// ``need_types`` (list<str>), ``severity``, ``is_risk``,
+/// ``response_actor``, ``response_actor_type``, ``admin_area``,
+/// ``admin_level``, ``storms`` (list of names) and ``fingerprint``
+/// (16-byte big-endian binary). Columns of disabled stages are null.
+///
+/// Parameters
+/// ----------
+/// pipeline : Pipeline | None
+///     The pipeline to run. Default: all stages, default keyword packs.
+/// batch_rows : int
+///     Rows per record batch (and Parquet row group). Default 65536.
+#[pyclass(module = "moltis_rust_core")]
+pub struct ArticleExporter {
+    config: PipelineConfig,
+    batches: RecordBatches,
+}
+
+#[pymethods]
+impl ArticleExporter {
+    #[new]
+    #[pyo3(signature = (pipeline=None, batch_rows=DEFAULT_BATCH_ROWS))]
+    fn py_new(pipeline: Option<PyRef<'_, Pipeline>>, batch_rows: usize) -> PyResult<Self> {
+        guarded("ArticleExporter.__new__", || {
+            Ok(Self {
+                config: pipeline.map(|p| PipelineConfig::clone(&p.config())).unwrap_or_default(),
+                batches: RecordBatches::new(batch_rows),
+            })
+        })
+    }
+
+    /// Process one article and add its record.
+    #[pyo3(signature = (title, body, url, published=None))]
+    fn add(&mut self, py: Python<'_>, title: &str, body: &str, url: &str, published: Option<&str>) -> PyResult<()> {
+        let now = current_time();
+        let record = unlocked(py, "ArticleExporter.add", || self.config.run(title, body, url, published, None, now))?;
+        self.batches.push(&record);
+        Ok(())
+    }
+
+    /// Process a batch of ``(title, body, url, published)`` tuples in
+    /// parallel and add their records in input order.
+    fn add_many(&mut self, py: Python<'_>, articles: Vec<(String, String, String, Option<String>)>) -> PyResult<()> {
+        let now = current_time();
+        let config = &self.config;
+        let records: Vec<ArticleRecord> = unlocked(py, "ArticleExporter.add_many", || {
+            articles
+                .par_iter()
+                .map(|(title, body, url, published)| config.run(title, body, url, published.as_deref(), None, now))
+                .collect()
+        })?;
+        for record in &records {
+            self.batches.push(record);
+        }
+        Ok(())
+    }
+
+    /// Write the collected records to a Parquet file and clear them.
+    ///
+    /// Parameters
+    /// ----------
+    /// path : str
+    ///     Output file, overwritten if it exists.
+    /// compression : str
+    ///     ``"snappy"`` (default) or ``"none"``.
+    ///
+    /// Returns
+    /// -------
+    /// int
+    ///     Rows written.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///     If the compression is unsupported.
+    /// OSError
+    ///     If the file cannot be written.
+    #[pyo3(signature = (path, compression="snappy"))]
+    fn write_parquet(&mut self, py: Python<'_>, path: &str, compression: &str) -> PyResult<usize> {
+        let compression = self::compression(compression).map_err(PyValueError::new_err)?;
+        let batches = self.batches.take();
+        unlocked(py, "ArticleExporter.write_parquet", || write_parquet(path, &batches, compression))?
+            .map_err(PyOSError::new_err)
+    }
+
+    /// Arrow PyCapsule interface: export the collected records as an
+    /// ``ArrowArrayStream`` and clear them.
+    #[pyo3(signature = (requested_schema=None))]
+    fn __arrow_c_stream__<'py>(
+        &mut self,
+        py: Python<'py>,
+        requested_schema: Option<Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyCapsule>> {
+        guarded("ArticleExporter.__arrow_c_stream__", || {
+            // Schema negotiation is optional for producers; ours is fixed
+            let _ = requested_schema;
+            let batches = self.batches.take();
+            let reader = RecordBatchIterator::new(batches.into_iter().map(Ok), record_schema());
+            let stream = FFI_ArrowArrayStream::new(Box::new(reader));
+            let name = CString::new("arrow_array_stream").expect("no interior NUL");
+            PyCapsule::new_bound(py, stream, Some(name))
+        })
+    }
+
+    fn __len__(&self) -> PyResult<usize> {
+        guarded("ArticleExporter.__len__", || Ok(self.batches.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::cast::AsArray;
+    use arrow_array::Array;
+    use arrow_array::types::{Int32Type, TimestampSecondType};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    fn record() -> ArticleRecord {
+        crate::pipeline::DEFAULT_PIPELINE.run(
+            "Floods in Sofala",
+            "Floods displaced 5,000 people and destroyed houses. WFP is distributing food.",
+            "https://example.org/a?utm_source=x",
+            Some("2025-03-14T08:00:00Z"),
+            None,
+            chrono::DateTime::UNIX_EPOCH,
+        )
+    }
+
+    #[test]
+    fn test_batches() {
+        let mut batches = RecordBatches::new(2);
+        for _ in 0..5 {
+            batches.push(&record());
+        }
+        assert_eq!(batches.len(), 5);
+        let taken = batches.take();
+        assert_eq!(taken.iter().map(RecordBatch::num_rows).collect::<Vec<_>>(), vec![2, 2, 1]);
+        assert_eq!(batches.len(), 0);
+        let batch = &taken[0];
+        assert_eq!(batch.schema(), record_schema());
+        assert_eq!(batch.column_by_name("canonical_url").unwrap().as_string::<i32>().value(0), "https://example.org/a");
+        let published = batch.column_by_name("published").unwrap().as_primitive::<TimestampSecondType>();
+        assert_eq!(published.value(0), 1_741_939_200);
+        let severity = batch.column_by_name("severity").unwrap().as_primitive::<Int32Type>();
+        assert!(!severity.is_null(0));
+    }
+
+    #[test]
+    fn test_disabled_stages_are_null() {
+        let mut batches = RecordBatches::new(10);
+        batches.push(&ArticleRecord {
+            canonical_url: "https://example.org/".into(),
+            ..Default::default()
+        });
+        let batch = batches.take().remove(0);
+        for name in ["language", "published", "figures", "impact_types", "severity", "fingerprint"] {
+            assert!(batch.column_by_name(name).unwrap().is_null(0), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_parquet_round_trip() {
+        let mut batches = RecordBatches::new(10);
+        batches.push(&record());
+        batches.push(&record());
+        let path = std::env::temp_dir().join(format!("moltis_export_{}.parquet", std::process::id()));
+        let path = path.to_str().unwrap();
+        assert_eq!(write_parquet(path, &batches.take(), Compression::SNAPPY), Ok(2));
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let rows: usize = reader.map(|b| b.unwrap().num_rows()).sum();
+        assert_eq!(rows, 2);
+        std::fs::remove_file(path).unwrap();
+        assert!(compression("lz4").is_err());
+    }
+}
@@ -0,0 +1,110 @@
+//! Single-call article pipeline.
+//!
+//! Per-article FFI round trips (canonicalize, detect language, extract
+//! figures, five classifiers, admin lookup, fingerprint) cost more than
+//! the work itself. `process_article` runs them all in one call through
+//! the default `Pipeline`: the text is joined and lowercased once and
+//! every stage reads that copy, with the GIL released throughout.
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::errors::unlocked;
+use crate::metrics::timer;
+use crate::pipeline::{current_time, DEFAULT_PIPELINE};
+use crate::text_classify::sort_admin_areas;
+
+/// Run the per-article pipeline in one call.
+///
+/// Canonicalizes the URL, identifies the language, parses the published
+/// date, extracts figures, runs the impact / need / severity / risk /
+/// actor classifiers, storm name and admin area detection, and computes
+/// the dedup fingerprint — equivalent to calling each function
+/// separately on ``title + "\n\n" + body``.
+///
+/// Parameters
+/// ----------
+/// title : str
+///     Article title (may be empty).
+/// body : str
+///     Article text.
+/// url : str
+///     Article URL.
+/// published : str | None
+///     Published date as found (meta attribute, feed timestamp or text).
+/// area_names : list[tuple[str, int]] | None
+///     Gazetteer ``(area_name, admin_level)`` pairs for admin detection.
+///
+/// Returns
+/// -------
+/// dict
+///     ``canonical_url``; ``language`` / ``language_confidence``;
+///     ``published`` (ISO 8601 UTC) / ``published_confidence`` as in
+///     ``parse_date``; ``figures`` (dict[str, int]); ``impact_type``;
+///     ``impact_types``; ``need_types``; ``severity`` (1-5); ``is_risk``;
+///     ``response_actor`` and ``admin_area`` (tuple or None); ``storms``
+///     (list of ``(name, basin)``); ``fingerprint`` (int, the body's
+///     content fingerprint as used by ``ContentIndex``).
+#[pyfunction]
+#[pyo3(signature = (title, body, url, published=None, area_names=None))]
+pub fn process_article(
+    py: Python<'_>,
+    title: &str,
+    body: &str,
+    url: &str,
+    published: Option<&str>,
+    area_names: Option<Vec<(String, i32)>>,
+) -> PyResult<Py<PyDict>> {
+    let _timer = timer("process_article");
+    let now = current_time();
+    let areas = sort_admin_areas(area_names.unwrap_or_default());
+    let record = unlocked(py, "process_article", || DEFAULT_PIPELINE.run(title, body, url, published, Some(&areas), now))?;
+    Ok(record.to_dict(py)?.unbind())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content_hash::content_fingerprint;
+    use crate::date_parse::ParsedDate;
+
+    const BODY: &str = "Tropical Cyclone Freddy destroyed houses and a bridge. At least 48,000 people \
+                        were displaced and WFP is distributing food in Quelimane.";
+
+    #[test]
+    fn test_process_article() {
+        let now = chrono::DateTime::from_timestamp(1_742_126_400, 0).unwrap();
+        let areas = sort_admin_areas(vec![("Zambezia".into(), 1), ("Quelimane".into(), 2)]);
+        let record = DEFAULT_PIPELINE.run(
+            "Cyclone Freddy: death toll rises to 59 in Zambezia",
+            BODY,
+            "https://example.org/news/freddy?utm_source=rss",
+            Some("Tue, 14 Mar 2023 08:00:00 GMT"),
+            Some(&areas),
+            now,
+        );
+        assert_eq!(record.canonical_url, "https://example.org/news/freddy");
+        assert_eq!(record.language.flatten().map(|(code, _)| code), Some("en"));
+        let published = record.published.flatten();
+        assert_eq!(published.as_ref().map(ParsedDate::iso).as_deref(), Some("2023-03-14T08:00:00Z"));
+        let figures = record.figures.unwrap();
+        assert_eq!(figures.get("deaths"), Some(&59));
+        assert_eq!(figures.get("people_affected"), Some(&48_000));
+        assert!(record.impacts.unwrap().1.contains(&"infrastructure_impact".to_string()));
+        assert!(record.need_types.unwrap().contains(&"food_security".to_string()));
+        assert_eq!(record.response_actor, Some(Some(("WFP".into(), "un_agency".into()))));
+        assert_eq!(record.admin_area, Some(Some(("Quelimane".into(), 2))));
+        assert_eq!(record.storms, Some(vec![("Freddy".into(), "south_west_indian".into())]));
+        assert_eq!(record.fingerprint, Some(content_fingerprint(BODY)));
+    }
+
+    #[test]
+    fn test_empty_article() {
+        let record = DEFAULT_PIPELINE.run("", "", "not a url", None, None, chrono::DateTime::UNIX_EPOCH);
+        assert_eq!(record.language, Some(None));
+        assert_eq!(record.published, Some(None));
+        assert_eq!(record.figures, Some(Default::default()));
+        assert_eq!(record.impacts, Some(("people_impact".into(), vec!["people_impact".into()])));
+        assert_eq!(record.severity, Some(1));
+    }
+}
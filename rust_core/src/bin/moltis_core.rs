@@ -0,0 +1,5 @@
+//! `moltis-core` command-line tool; see `moltis_rust_core::cli`.
+
+fn main() -> std::process::ExitCode {
+    moltis_rust_core::cli::main()
+}
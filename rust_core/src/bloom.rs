@@ -0,0 +1,295 @@
+//! Bloom filter — in-memory seen-URL pre-filter.
+//!
+//! Lets the crawler rule out most already-seen canonical URLs without a
+//! database round trip. Items are URL strings or the 64-bit hashes from
+//! `url_key`; bit positions come from double hashing over xxh3-128.
+//! With persistent storage the filter is saved there as one `to_bytes`
+//! snapshot on `flush()` and when dropped, and restored on open.
+
+use pyo3::exceptions::{PyOSError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use xxhash_rust::xxh3::xxh3_128;
+
+use crate::storage::{self, Storage};
+use crate::errors::guarded;
+
+const MAGIC: &[u8; 4] = b"MBF1";
+const HEADER_LEN: usize = 4 + 8 + 4 + 8;
+/// Storage key of the snapshot.
+const SNAPSHOT_KEY: &[u8] = b"bloom";
+
+/// A URL string or a precomputed 64-bit URL hash.
+#[derive(FromPyObject)]
+pub(crate) enum BloomItem {
+    Hash(u64),
+    Text(String),
+}
+
+impl BloomItem {
+    fn digest(&self) -> u128 {
+        match self {
+            BloomItem::Hash(h) => xxh3_128(&h.to_le_bytes()),
+            BloomItem::Text(s) => xxh3_128(s.as_bytes()),
+        }
+    }
+}
+
+/// Space-efficient probabilistic set with a configurable false-positive rate.
+///
+/// Parameters
+/// ----------
+/// capacity : int
+///     Expected number of items.
+/// fp_rate : float
+///     Target false-positive rate at ``capacity`` items. Default 0.01.
+/// storage : str | None
+///     ``"memory"``, ``"sqlite:PATH"`` or ``"mmap:PATH"``. A snapshot
+///     found there is restored, keeping its own size and rate. Default:
+///     the process default (see ``configure_storage``), ``bloom.db`` /
+///     ``.map``.
+#[pyclass(module = "moltis_rust_core")]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    num_bits: u64,
+    num_hashes: u32,
+    count: u64,
+    store: Option<Box<dyn Storage>>,
+}
+
+impl BloomFilter {
+    pub(crate) fn with_rate(capacity: u64, fp_rate: f64) -> Result<Self, String> {
+        if capacity == 0 {
+            return Err("capacity must be positive".into());
+        }
+        if !(fp_rate > 0.0 && fp_rate < 1.0) {
+            return Err("fp_rate must be between 0 and 1".into());
+        }
+        let ln2 = std::f64::consts::LN_2;
+        let num_bits = (-(capacity as f64) * fp_rate.ln() / (ln2 * ln2)).ceil().max(64.0) as u64;
+        let num_hashes = ((num_bits as f64 / capacity as f64) * ln2).round().max(1.0) as u32;
+        Ok(Self {
+            bits: vec![0; num_bits.div_ceil(64) as usize],
+            num_bits,
+            num_hashes,
+            count: 0,
+            store: None,
+        })
+    }
+
+    /// Save a snapshot to the store, if any.
+    fn save(&mut self) -> Result<(), String> {
+        if self.store.is_none() {
+            return Ok(());
+        }
+        let snapshot = self.to_bytes_vec();
+        let store = self.store.as_mut().unwrap();
+        store.put(SNAPSHOT_KEY, &snapshot)?;
+        store.flush()
+    }
+
+    fn positions(&self, digest: u128) -> impl Iterator<Item = u64> + '_ {
+        let h1 = digest as u64;
+        let h2 = (digest >> 64) as u64 | 1;
+        (0..self.num_hashes as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % self.num_bits)
+    }
+
+    /// Set the item's bits; returns `true` if any bit was previously unset.
+    pub(crate) fn insert_digest(&mut self, digest: u128) -> bool {
+        let positions: Vec<u64> = self.positions(digest).collect();
+        let mut added = false;
+        for pos in positions {
+            let (word, mask) = ((pos / 64) as usize, 1u64 << (pos % 64));
+            if self.bits[word] & mask == 0 {
+                self.bits[word] |= mask;
+                added = true;
+            }
+        }
+        if added {
+            self.count += 1;
+        }
+        added
+    }
+
+    pub(crate) fn contains_digest(&self, digest: u128) -> bool {
+        self.positions(digest)
+            .all(|pos| self.bits[(pos / 64) as usize] & (1u64 << (pos % 64)) != 0)
+    }
+
+    fn to_bytes_vec(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(HEADER_LEN + self.bits.len() * 8);
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&self.num_bits.to_le_bytes());
+        out.extend_from_slice(&self.num_hashes.to_le_bytes());
+        out.extend_from_slice(&self.count.to_le_bytes());
+        for word in &self.bits {
+            out.extend_from_slice(&word.to_le_bytes());
+        }
+        out
+    }
+
+    fn from_bytes_slice(data: &[u8]) -> Result<Self, String> {
+        if data.len() < HEADER_LEN || &data[..4] != MAGIC {
+            return Err("not a serialized BloomFilter".into());
+        }
+        let num_bits = u64::from_le_bytes(data[4..12].try_into().unwrap());
+        let num_hashes = u32::from_le_bytes(data[12..16].try_into().unwrap());
+        let count = u64::from_le_bytes(data[16..24].try_into().unwrap());
+        let words = num_bits.div_ceil(64) as usize;
+        let body = &data[HEADER_LEN..];
+        if num_bits == 0 || num_hashes == 0 || body.len() != words * 8 {
+            return Err("corrupt BloomFilter payload".into());
+        }
+        let bits = body
+            .chunks_exact(8)
+            .map(|c| u64::from_le_bytes(c.try_into().unwrap()))
+            .collect();
+        Ok(Self {
+            bits,
+            num_bits,
+            num_hashes,
+            count,
+            store: None,
+        })
+    }
+}
+
+impl Drop for BloomFilter {
+    fn drop(&mut self) {
+        if let Err(e) = self.save() {
+            tracing::warn!(error = %e, "Bloom filter snapshot not saved");
+        }
+    }
+}
+
+#[pymethods]
+impl BloomFilter {
+    #[new]
+    #[pyo3(signature = (capacity, fp_rate=0.01, storage=None))]
+    fn py_new(capacity: u64, fp_rate: f64, storage: Option<&str>) -> PyResult<Self> {
+        guarded("BloomFilter.__new__", || {
+            let mut filter = Self::with_rate(capacity, fp_rate).map_err(PyValueError::new_err)?;
+            if let Some(store) = storage::open_for(storage, "bloom", 1)? {
+                if let Some(snapshot) = store.get(SNAPSHOT_KEY).map_err(PyOSError::new_err)? {
+                    filter = Self::from_bytes_slice(&snapshot)
+                        .map_err(|e| PyValueError::new_err(format!("{}: {e}", store.spec())))?;
+                }
+                filter.store = Some(store);
+            }
+            Ok(filter)
+        })
+    }
+
+    /// Add an item (URL string or int hash). Returns True if it was new.
+    fn insert(&mut self, item: BloomItem) -> PyResult<bool> {
+        guarded("BloomFilter.insert", || Ok(self.insert_digest(item.digest())))
+    }
+
+    /// Return True if the item was possibly inserted (False is definitive).
+    fn contains(&self, item: BloomItem) -> PyResult<bool> {
+        guarded("BloomFilter.contains", || Ok(self.contains_digest(item.digest())))
+    }
+
+    fn __contains__(&self, item: BloomItem) -> PyResult<bool> {
+        guarded("BloomFilter.__contains__", || Ok(self.contains_digest(item.digest())))
+    }
+
+    /// Number of distinct items inserted (approximate).
+    fn __len__(&self) -> PyResult<usize> {
+        guarded("BloomFilter.__len__", || Ok(self.count as usize))
+    }
+
+    #[getter]
+    fn num_bits(&self) -> PyResult<u64> {
+        guarded("BloomFilter.num_bits", || Ok(self.num_bits))
+    }
+
+    #[getter]
+    fn num_hashes(&self) -> PyResult<u32> {
+        guarded("BloomFilter.num_hashes", || Ok(self.num_hashes))
+    }
+
+    /// Serialize to bytes for persistence between crawl runs.
+    fn to_bytes<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        guarded("BloomFilter.to_bytes", || Ok(PyBytes::new_bound(py, &self.to_bytes_vec())))
+    }
+
+    /// Save a snapshot to storage (no-op in memory).
+    fn flush(&mut self) -> PyResult<()> {
+        guarded("BloomFilter.flush", || self.save().map_err(PyOSError::new_err))
+    }
+
+    /// Storage spec, e.g. ``"sqlite:state/bloom.db"``, or ``"memory"``.
+    #[getter]
+    fn storage(&self) -> PyResult<String> {
+        guarded("BloomFilter.storage", || Ok(self.store.as_ref().map_or("memory".into(), |s| s.spec())))
+    }
+
+    /// Restore a filter produced by ``to_bytes()``.
+    #[staticmethod]
+    fn from_bytes(data: &[u8]) -> PyResult<Self> {
+        guarded("BloomFilter.from_bytes", || Self::from_bytes_slice(data).map_err(PyValueError::new_err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digest(s: &str) -> u128 {
+        BloomItem::Text(s.to_string()).digest()
+    }
+
+    #[test]
+    fn test_insert_contains() {
+        let mut bf = BloomFilter::with_rate(1000, 0.01).unwrap();
+        assert!(bf.insert_digest(digest("https://example.org/a")));
+        assert!(!bf.insert_digest(digest("https://example.org/a")));
+        assert!(bf.contains_digest(digest("https://example.org/a")));
+        assert!(!bf.contains_digest(digest("https://example.org/b")));
+        assert_eq!(bf.count, 1);
+    }
+
+    #[test]
+    fn test_false_positive_rate() {
+        let mut bf = BloomFilter::with_rate(10_000, 0.01).unwrap();
+        for i in 0..10_000u64 {
+            bf.insert_digest(BloomItem::Hash(i).digest());
+        }
+        let fp = (10_000..60_000u64)
+            .filter(|&i| bf.contains_digest(BloomItem::Hash(i).digest()))
+            .count();
+        assert!((fp as f64 / 50_000.0) < 0.02, "fp rate too high: {fp}");
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let mut bf = BloomFilter::with_rate(100, 0.001).unwrap();
+        bf.insert_digest(digest("x"));
+        let restored = BloomFilter::from_bytes_slice(&bf.to_bytes_vec()).unwrap();
+        assert!(restored.contains_digest(digest("x")));
+        assert_eq!(restored.num_hashes, bf.num_hashes);
+        assert!(BloomFilter::from_bytes_slice(b"garbage").is_err());
+    }
+
+    #[test]
+    fn test_snapshot_in_storage() {
+        let path = std::env::temp_dir().join(format!("moltis_bloom_{}.map", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let spec = format!("mmap:{}", path.display());
+        let mut bf = BloomFilter::py_new(100, 0.01, Some(&spec)).unwrap();
+        bf.insert_digest(digest("x"));
+        drop(bf);
+        // The snapshot's size wins over the arguments
+        let bf = BloomFilter::py_new(5000, 0.01, Some(&spec)).unwrap();
+        assert!(bf.contains_digest(digest("x")));
+        assert_eq!(bf.count, 1);
+        assert!(bf.num_bits < 5000);
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        assert!(BloomFilter::with_rate(0, 0.01).is_err());
+        assert!(BloomFilter::with_rate(10, 1.5).is_err());
+    }
+}
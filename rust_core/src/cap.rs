@@ -0,0 +1,305 @@
+//! Common Alerting Protocol (CAP 1.2) message parsing.
+//!
+//! National meteorological and hydrological services publish warnings as
+//! CAP. Reading the structure directly (event, severity, urgency, areas,
+//! validity window) feeds the risk pipeline with records instead of
+//! scraping the rendered warning text.
+
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyList};
+
+use crate::date_parse::parse_at;
+use crate::errors::{extraction_error, unlocked, Failure};
+use crate::pipeline::current_time;
+use crate::xml_tree::{self, Element};
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct CapArea {
+    pub description: String,
+    /// Closed rings of `(lat, lon)` points.
+    pub polygons: Vec<Vec<(f64, f64)>>,
+    /// `(lat, lon, radius_km)`.
+    pub circles: Vec<(f64, f64, f64)>,
+    /// `(valueName, value)`, e.g. `("FIPS6", "MZ09")`.
+    pub geocodes: Vec<(String, String)>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct CapInfo {
+    pub language: Option<String>,
+    pub categories: Vec<String>,
+    pub event: Option<String>,
+    pub urgency: Option<String>,
+    pub severity: Option<String>,
+    pub certainty: Option<String>,
+    pub effective: Option<String>,
+    pub onset: Option<String>,
+    pub expires: Option<String>,
+    pub sender_name: Option<String>,
+    pub headline: Option<String>,
+    pub description: Option<String>,
+    pub instruction: Option<String>,
+    pub web: Option<String>,
+    pub areas: Vec<CapArea>,
+}
+
+impl CapInfo {
+    /// CAP severity on the crate's 1-5 scale (as `severity_from_text`).
+    pub(crate) fn severity_level(&self) -> Option<i32> {
+        match self.severity.as_deref()? {
+            "Extreme" => Some(5),
+            "Severe" => Some(4),
+            "Moderate" => Some(3),
+            "Minor" => Some(2),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct CapAlert {
+    pub identifier: String,
+    pub sender: Option<String>,
+    pub sent: Option<String>,
+    pub status: Option<String>,
+    pub msg_type: Option<String>,
+    pub scope: Option<String>,
+    pub infos: Vec<CapInfo>,
+}
+
+fn text(element: &Element, name: &str) -> Option<String> {
+    element.child_text(name).map(str::to_string)
+}
+
+/// CAP timestamps normalized to UTC ISO 8601.
+fn timestamp(element: &Element, name: &str) -> Option<String> {
+    parse_at(element.child_text(name)?, None, current_time()).map(|p| p.iso())
+}
+
+fn point(pair: &str) -> Option<(f64, f64)> {
+    let (lat, lon) = pair.split_once(',')?;
+    Some((lat.trim().parse().ok()?, lon.trim().parse().ok()?))
+}
+
+fn polygon(text: &str) -> Option<Vec<(f64, f64)>> {
+    let points = text.split_whitespace().map(point).collect::<Option<Vec<_>>>()?;
+    // The spec requires at least four points, first and last equal
+    (points.len() >= 4).then_some(points)
+}
+
+fn circle(text: &str) -> Option<(f64, f64, f64)> {
+    let (centre, radius) = text.trim().split_once(char::is_whitespace)?;
+    let (lat, lon) = point(centre)?;
+    Some((lat, lon, radius.trim().parse().ok()?))
+}
+
+fn parse_area(area: &Element) -> CapArea {
+    CapArea {
+        description: area.child_text("areaDesc").unwrap_or_default().to_string(),
+        polygons: area.children_named("polygon").filter_map(|p| polygon(&p.text)).collect(),
+        circles: area.children_named("circle").filter_map(|c| circle(&c.text)).collect(),
+        geocodes: area
+            .children_named("geocode")
+            .filter_map(|g| Some((text(g, "valueName")?, text(g, "value")?)))
+            .collect(),
+    }
+}
+
+fn parse_info(info: &Element, sent: Option<&str>) -> CapInfo {
+    CapInfo {
+        language: text(info, "language"),
+        categories: info
+            .children_named("category")
+            .map(|c| c.text.trim().to_string())
+            .filter(|c| !c.is_empty())
+            .collect(),
+        event: text(info, "event"),
+        urgency: text(info, "urgency"),
+        severity: text(info, "severity"),
+        certainty: text(info, "certainty"),
+        // Effective defaults to the sent time
+        effective: timestamp(info, "effective").or_else(|| sent.map(str::to_string)),
+        onset: timestamp(info, "onset"),
+        expires: timestamp(info, "expires"),
+        sender_name: text(info, "senderName"),
+        headline: text(info, "headline"),
+        description: text(info, "description"),
+        instruction: text(info, "instruction"),
+        web: text(info, "web"),
+        areas: info.children_named("area").map(parse_area).collect(),
+    }
+}
+
+pub(crate) fn parse_cap_bytes(data: &[u8]) -> Result<CapAlert, Failure> {
+    let root = xml_tree::parse(data)?;
+    if root.name != "alert" {
+        return Err(Failure::new("wrong_format", format!("not a CAP alert: root element is <{}>", root.name)));
+    }
+    let identifier =
+        text(&root, "identifier").ok_or_else(|| Failure::new("missing_field", "CAP alert has no identifier"))?;
+    let sent = timestamp(&root, "sent");
+    let infos = root.children_named("info").map(|i| parse_info(i, sent.as_deref())).collect();
+    Ok(CapAlert {
+        identifier,
+        sender: text(&root, "sender"),
+        status: text(&root, "status"),
+        msg_type: text(&root, "msgType"),
+        scope: text(&root, "scope"),
+        sent,
+        infos,
+    })
+}
+
+fn info_dict<'py>(py: Python<'py>, info: &CapInfo) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new_bound(py);
+    dict.set_item("language", &info.language)?;
+    dict.set_item("categories", &info.categories)?;
+    dict.set_item("event", &info.event)?;
+    dict.set_item("urgency", &info.urgency)?;
+    dict.set_item("severity", &info.severity)?;
+    dict.set_item("severity_level", info.severity_level())?;
+    dict.set_item("certainty", &info.certainty)?;
+    dict.set_item("effective", &info.effective)?;
+    dict.set_item("onset", &info.onset)?;
+    dict.set_item("expires", &info.expires)?;
+    dict.set_item("sender_name", &info.sender_name)?;
+    dict.set_item("headline", &info.headline)?;
+    dict.set_item("description", &info.description)?;
+    dict.set_item("instruction", &info.instruction)?;
+    dict.set_item("web", &info.web)?;
+    let areas = PyList::empty_bound(py);
+    for area in &info.areas {
+        let item = PyDict::new_bound(py);
+        item.set_item("description", &area.description)?;
+        item.set_item("polygons", &area.polygons)?;
+        item.set_item("circles", &area.circles)?;
+        item.set_item("geocodes", &area.geocodes)?;
+        areas.append(item)?;
+    }
+    dict.set_item("areas", areas)?;
+    Ok(dict)
+}
+
+/// Parse a Common Alerting Protocol (CAP 1.2) message.
+///
+/// Parameters
+/// ----------
+/// xml : bytes | str
+///     The ``<alert>`` document.
+///
+/// Returns
+/// -------
+/// dict
+///     ``identifier``, ``sender``, ``sent``, ``status``, ``msg_type``,
+///     ``scope`` and ``infos``: one dict per ``<info>`` block with
+///     ``language``, ``categories``, ``event``, ``urgency``, ``severity``
+///     (as given, e.g. ``"Severe"``), ``severity_level`` (1-5 on the
+///     ``severity_from_text`` scale, None for ``"Unknown"``), ``certainty``,
+///     ``effective`` (defaults to ``sent``), ``onset``, ``expires``,
+///     ``sender_name``, ``headline``, ``description``, ``instruction``,
+///     ``web`` and ``areas``. Each area has ``description``, ``polygons``
+///     (lists of ``(lat, lon)``), ``circles`` (``(lat, lon, radius_km)``)
+///     and ``geocodes`` (``(name, value)``). Timestamps are UTC ISO 8601;
+///     missing values are None.
+///
+/// Raises
+/// ------
+/// ExtractionError
+///     If the document is malformed (``reason`` ``"malformed"``), not a
+///     CAP alert (``"wrong_format"``), or has no identifier
+///     (``"missing_field"``). A ``ValueError`` subclass.
+#[pyfunction]
+pub fn parse_cap(py: Python<'_>, xml: &Bound<'_, PyAny>) -> PyResult<Py<PyDict>> {
+    let alert = if let Ok(bytes) = xml.downcast::<PyBytes>() {
+        let bytes = bytes.as_bytes();
+        unlocked(py, "parse_cap", || parse_cap_bytes(bytes))?
+    } else {
+        let text = xml.extract::<String>()?;
+        unlocked(py, "parse_cap", || parse_cap_bytes(text.as_bytes()))?
+    }
+    .map_err(|e| extraction_error("cap", e))?;
+
+    let infos = PyList::empty_bound(py);
+    for info in &alert.infos {
+        infos.append(info_dict(py, info)?)?;
+    }
+    let dict = PyDict::new_bound(py);
+    dict.set_item("identifier", &alert.identifier)?;
+    dict.set_item("sender", &alert.sender)?;
+    dict.set_item("sent", &alert.sent)?;
+    dict.set_item("status", &alert.status)?;
+    dict.set_item("msg_type", &alert.msg_type)?;
+    dict.set_item("scope", &alert.scope)?;
+    dict.set_item("infos", infos)?;
+    Ok(dict.unbind())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALERT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<alert xmlns="urn:oasis:names:tc:emergency:cap:1.2">
+  <identifier>INAM-2025-0314-01</identifier>
+  <sender>alertas@inam.gov.mz</sender>
+  <sent>2025-03-14T08:00:00+02:00</sent>
+  <status>Actual</status>
+  <msgType>Alert</msgType>
+  <scope>Public</scope>
+  <info>
+    <language>pt-MZ</language>
+    <category>Met</category>
+    <event>Ciclone Tropical</event>
+    <urgency>Expected</urgency>
+    <severity>Severe</severity>
+    <certainty>Likely</certainty>
+    <expires>2025-03-16T08:00:00+02:00</expires>
+    <headline>Aviso vermelho: ciclone &amp; chuvas fortes</headline>
+    <area>
+      <areaDesc>Zambézia</areaDesc>
+      <polygon>-17.0,36.0 -17.0,38.0 -19.0,38.0 -17.0,36.0</polygon>
+      <circle>-17.88,36.89 50</circle>
+      <geocode><valueName>FIPS6</valueName><value>MZ09</value></geocode>
+    </area>
+  </info>
+  <info><language>en</language><severity>Unknown</severity><effective>2025-03-14T09:00:00Z</effective></info>
+</alert>"#;
+
+    #[test]
+    fn test_parse_alert() {
+        let alert = parse_cap_bytes(ALERT.as_bytes()).unwrap();
+        assert_eq!(alert.identifier, "INAM-2025-0314-01");
+        assert_eq!(alert.sent.as_deref(), Some("2025-03-14T06:00:00Z"));
+        assert_eq!(alert.msg_type.as_deref(), Some("Alert"));
+        let info = &alert.infos[0];
+        assert_eq!(info.event.as_deref(), Some("Ciclone Tropical"));
+        assert_eq!(info.categories, vec!["Met"]);
+        assert_eq!(info.severity_level(), Some(4));
+        assert_eq!(info.effective, alert.sent);
+        assert_eq!(info.expires.as_deref(), Some("2025-03-16T06:00:00Z"));
+        assert_eq!(info.headline.as_deref(), Some("Aviso vermelho: ciclone & chuvas fortes"));
+        let area = &info.areas[0];
+        assert_eq!(area.description, "Zambézia");
+        assert_eq!(area.polygons[0].len(), 4);
+        assert_eq!(area.polygons[0][1], (-17.0, 38.0));
+        assert_eq!(area.circles, vec![(-17.88, 36.89, 50.0)]);
+        assert_eq!(area.geocodes, vec![("FIPS6".into(), "MZ09".into())]);
+        let second = &alert.infos[1];
+        assert_eq!(second.severity_level(), None);
+        assert_eq!(second.effective.as_deref(), Some("2025-03-14T09:00:00Z"));
+    }
+
+    #[test]
+    fn test_bad_geometry_skipped() {
+        assert_eq!(polygon("1,2 3,4"), None);
+        assert_eq!(polygon("1,2 3,x 5,6 1,2"), None);
+        assert_eq!(circle("-17.8,36.8"), None);
+    }
+
+    #[test]
+    fn test_not_cap() {
+        assert!(parse_cap_bytes(b"<rss><channel/></rss>").is_err());
+        assert!(parse_cap_bytes(b"<alert><sender>x</sender></alert>").is_err());
+        assert!(parse_cap_bytes(b"<alert>").is_err());
+    }
+}
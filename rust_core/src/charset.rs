@@ -0,0 +1,268 @@
+//! Character encoding detection and transcoding to UTF-8.
+//!
+//! Government sites often serve Latin-1 or Windows-1252 pages with a
+//! wrong or missing charset, and some re-encode UTF-8 text as Latin-1
+//! ("Ã©" for "é"). Either way keyword matching downstream sees garbage.
+//! The encoding is taken from a BOM, the declared (HTTP) charset, or the
+//! page's `<meta>` / XML declaration — each checked against the bytes —
+//! and otherwise detected; double-encoded UTF-8 is then repaired.
+
+use encoding_rs::{Encoding, UTF_8, WINDOWS_1251, WINDOWS_1252};
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use regex::bytes::Regex;
+
+use crate::errors::unlocked;
+
+/// How far into the document `<meta charset>` is looked for.
+const META_SNIFF_BYTES: usize = 1024;
+/// Below this share of non-ASCII bytes next to ASCII letters, text is
+/// taken to be in a non-Latin script (Cyrillic in Windows-1251).
+const LATIN_ADJACENCY: f64 = 0.3;
+
+static META_CHARSET: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?i)<meta[^>]+charset\s*=\s*["']?\s*([a-z0-9_:.-]+)"#).unwrap());
+static XML_ENCODING: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?i)^\s*<\?xml[^>]+encoding\s*=\s*["']([a-z0-9_.-]+)"#).unwrap());
+// UTF-8 read as Windows-1252: "Ã" or "Â" followed by a continuation-range char
+static MOJIBAKE: Lazy<regex::Regex> = Lazy::new(|| {
+    regex::Regex::new(r"[ÃÂ][\u{80}-\u{BF}\u{152}\u{153}\u{160}\u{161}\u{178}\u{17D}\u{17E}\u{192}\u{2C6}\u{2DC}\u{2013}-\u{2122}]").unwrap()
+});
+
+/// Where the encoding came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Source {
+    Bom,
+    Declared,
+    Meta,
+    Detected,
+}
+
+impl Source {
+    fn as_str(self) -> &'static str {
+        match self {
+            Source::Bom => "bom",
+            Source::Declared => "declared",
+            Source::Meta => "meta",
+            Source::Detected => "detected",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Decoded {
+    pub text: String,
+    pub encoding: &'static str,
+    pub source: Source,
+    /// Invalid sequences replaced with U+FFFD.
+    pub replacements: usize,
+    pub mojibake_repaired: bool,
+}
+
+fn meta_charset(data: &[u8]) -> Option<&'static Encoding> {
+    let head = &data[..data.len().min(META_SNIFF_BYTES)];
+    let label = XML_ENCODING
+        .captures(head)
+        .or_else(|| META_CHARSET.captures(head))?
+        .get(1)?
+        .as_bytes();
+    let encoding = Encoding::for_label(label)?;
+    // A document that can declare itself in ASCII isn't UTF-16
+    Some(if encoding.is_single_byte() || encoding == UTF_8 {
+        encoding
+    } else {
+        UTF_8
+    })
+}
+
+/// Windows-1252 for Latin-script text, Windows-1251 when non-ASCII bytes
+/// form whole words on their own (Cyrillic).
+fn detect_single_byte(data: &[u8]) -> &'static Encoding {
+    let (mut high, mut adjacent) = (0usize, 0usize);
+    for (i, &b) in data.iter().enumerate() {
+        if b < 0x80 {
+            continue;
+        }
+        high += 1;
+        let prev = i.checked_sub(1).map(|j| data[j]);
+        let next = data.get(i + 1).copied();
+        if prev.is_some_and(|c| c.is_ascii_alphabetic())
+            || next.is_some_and(|c| c.is_ascii_alphabetic())
+        {
+            adjacent += 1;
+        }
+    }
+    if high > 0 && (adjacent as f64 / high as f64) < LATIN_ADJACENCY {
+        WINDOWS_1251
+    } else {
+        WINDOWS_1252
+    }
+}
+
+/// Choose the encoding and say why.
+fn choose(data: &[u8], declared: Option<&str>) -> (&'static Encoding, Source) {
+    let valid_utf8 = std::str::from_utf8(data).is_ok();
+    let has_non_ascii = !data.is_ascii();
+    let labelled = declared
+        .and_then(|label| Encoding::for_label(label.trim().as_bytes()))
+        .map(|e| (e, Source::Declared))
+        .or_else(|| meta_charset(data).map(|e| (e, Source::Meta)));
+    match labelled {
+        // A UTF-8 label on bytes that aren't UTF-8 is wrong
+        Some((e, _)) if e == UTF_8 && !valid_utf8 => (detect_single_byte(data), Source::Detected),
+        // Non-ASCII bytes that happen to be valid UTF-8 almost never are
+        // really single-byte text
+        Some((e, _)) if e.is_single_byte() && valid_utf8 && has_non_ascii => {
+            (UTF_8, Source::Detected)
+        }
+        Some(labelled) => labelled,
+        None if valid_utf8 => (UTF_8, Source::Detected),
+        None => (detect_single_byte(data), Source::Detected),
+    }
+}
+
+/// Undo UTF-8 that was decoded as Windows-1252 and re-encoded.
+fn repair_mojibake(text: &str) -> Option<String> {
+    if !MOJIBAKE.is_match(text) {
+        return None;
+    }
+    let (bytes, _, unmappable) = WINDOWS_1252.encode(text);
+    if unmappable {
+        return None;
+    }
+    String::from_utf8(bytes.into_owned()).ok()
+}
+
+pub(crate) fn decode(data: &[u8], declared: Option<&str>) -> Decoded {
+    let (encoding, source, body) = match Encoding::for_bom(data) {
+        Some((encoding, bom_len)) => (encoding, Source::Bom, &data[bom_len..]),
+        None => {
+            let (encoding, source) = choose(data, declared);
+            (encoding, source, data)
+        }
+    };
+    let (text, had_errors) = encoding.decode_without_bom_handling(body);
+    let replacements = if had_errors {
+        text.matches('\u{FFFD}').count()
+    } else {
+        0
+    };
+    let repaired = repair_mojibake(&text);
+    Decoded {
+        mojibake_repaired: repaired.is_some(),
+        text: repaired.unwrap_or_else(|| text.into_owned()),
+        encoding: encoding.name(),
+        source,
+        replacements,
+    }
+}
+
+/// Decode page bytes to UTF-8 text, detecting the encoding.
+///
+/// The encoding comes from a byte-order mark, else ``declared_charset``
+/// (the HTTP ``Content-Type`` charset), else ``<meta charset>`` or the XML
+/// declaration. A UTF-8 label on invalid UTF-8 and a single-byte label on
+/// valid non-ASCII UTF-8 are overridden by detection. Without any label,
+/// invalid UTF-8 is read as Windows-1252 (a superset of Latin-1), or
+/// Windows-1251 for Cyrillic. UTF-8 that was double-encoded through
+/// Latin-1 ("Ã©") is repaired.
+///
+/// Parameters
+/// ----------
+/// data : bytes
+///     Raw response body.
+/// declared_charset : str | None
+///     Charset label from the HTTP headers, if any.
+///
+/// Returns
+/// -------
+/// dict
+///     ``{"text": str, "encoding": str, "source": "bom" | "declared" |
+///     "meta" | "detected", "replacements": int,
+///     "mojibake_repaired": bool}``. ``encoding`` is the WHATWG name
+///     (``"windows-1252"`` for Latin-1 labels); ``replacements`` counts
+///     invalid sequences replaced with U+FFFD.
+#[pyfunction]
+#[pyo3(signature = (data, declared_charset=None))]
+pub fn decode_bytes(
+    py: Python<'_>,
+    data: &[u8],
+    declared_charset: Option<&str>,
+) -> PyResult<Py<PyDict>> {
+    let decoded = unlocked(py, "decode_bytes", || decode(data, declared_charset))?;
+    let dict = PyDict::new_bound(py);
+    dict.set_item("text", decoded.text)?;
+    dict.set_item("encoding", decoded.encoding)?;
+    dict.set_item("source", decoded.source.as_str())?;
+    dict.set_item("replacements", decoded.replacements)?;
+    dict.set_item("mojibake_repaired", decoded.mojibake_repaired)?;
+    Ok(dict.unbind())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_labels_and_bom() {
+        let latin1 = b"<p>Inunda\xe7\xf5es em Mo\xe7ambique</p>";
+        let d = decode(latin1, Some("ISO-8859-1"));
+        assert_eq!(
+            (d.text.as_str(), d.encoding, d.source),
+            (
+                "<p>Inundações em Moçambique</p>",
+                "windows-1252",
+                Source::Declared
+            )
+        );
+        let meta = b"<html><head><meta charset=\"windows-1252\"></head>caf\xe9</html>";
+        assert_eq!(decode(meta, None).source, Source::Meta);
+        let bom = b"\xef\xbb\xbfcaf\xc3\xa9";
+        assert_eq!(
+            (
+                decode(bom, Some("latin1")).text.as_str(),
+                decode(bom, None).source
+            ),
+            ("café", Source::Bom)
+        );
+    }
+
+    #[test]
+    fn test_wrong_labels_and_detection() {
+        // Declared UTF-8, actually Latin-1
+        let d = decode(b"D\xe9plac\xe9s: 1 200", Some("utf-8"));
+        assert_eq!(
+            (d.text.as_str(), d.encoding, d.source),
+            ("Déplacés: 1 200", "windows-1252", Source::Detected)
+        );
+        // Declared Latin-1, actually UTF-8
+        assert_eq!(
+            decode("Déplacés".as_bytes(), Some("iso-8859-1")).encoding,
+            "UTF-8"
+        );
+        // Unlabelled Cyrillic Windows-1251: "Наводнение"
+        let d = decode(b"\xcd\xe0\xe2\xee\xe4\xed\xe5\xed\xe8\xe5 2024", None);
+        assert_eq!(
+            (d.text.as_str(), d.encoding),
+            ("Наводнение 2024", "windows-1251")
+        );
+    }
+
+    #[test]
+    fn test_mojibake_and_replacements() {
+        let d = decode("SecÃ§Ã£o de emergÃªncia".as_bytes(), None);
+        assert_eq!(
+            (d.text.as_str(), d.mojibake_repaired),
+            ("Secção de emergência", true)
+        );
+        // Not valid UTF-8 once re-encoded: left alone
+        let d = decode("Ã‰tat ÂGE".as_bytes(), None);
+        assert_eq!((d.text.as_str(), d.mojibake_repaired), ("Ã‰tat ÂGE", false));
+        let d = decode(b"ok \xff\xfe? no", Some("utf-8"));
+        assert_eq!(d.source, Source::Detected);
+        let d = decode(b"bad \x81 byte", Some("shift_jis"));
+        assert_eq!(d.replacements, 1);
+        assert!(d.text.contains('\u{FFFD}'));
+    }
+}
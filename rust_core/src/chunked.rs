@@ -0,0 +1,262 @@
+//! Chunked iterators over batch work.
+//!
+//! `Pipeline.run` and `content_hash_batch` take and return whole lists,
+//! so a multi-million-record backfill holds every input and every result
+//! at once. The iterators here pull `chunk_size` items at a time from any
+//! Python iterable (a generator over a file or a cursor, say), process
+//! the chunk in parallel with the GIL released, and yield its results as
+//! one list. Peak memory is bounded by the chunk size, not the input.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyIterator, PyList};
+use rayon::prelude::*;
+use std::sync::Arc;
+
+use crate::columns::text;
+use crate::content_hash::{check_args, hash_text, HashAlgorithm};
+use crate::errors::{guarded, unlocked};
+use crate::metrics::timer;
+use crate::pipeline::{current_time, Pipeline, PipelineConfig, DEFAULT_PIPELINE};
+use crate::text_classify::sort_admin_areas;
+
+pub(crate) const DEFAULT_CHUNK_SIZE: usize = 1000;
+
+type Article = (String, String, String, Option<String>);
+
+/// Where each chunk of articles gets its configuration.
+enum Articles {
+    /// The default pipeline, with a fixed gazetteer.
+    Default { areas: Vec<(String, i32)> },
+    /// A `Pipeline`, asked per chunk so a long run follows its reloads.
+    Pipeline(Py<Pipeline>),
+}
+
+enum Job {
+    Articles(Articles),
+    Hashes { algorithm: HashAlgorithm, bits: u32, normalize: bool },
+}
+
+/// Iterator yielding one list of results per chunk of input.
+///
+/// Returned by ``iter_process``, ``Pipeline.iter_run`` and
+/// ``iter_content_hash``; not constructed directly.
+#[pyclass(module = "moltis_rust_core")]
+pub struct ChunkIterator {
+    source: Py<PyIterator>,
+    chunk_size: usize,
+    job: Job,
+}
+
+impl ChunkIterator {
+    fn new(source: &Bound<'_, PyAny>, chunk_size: usize, job: Job) -> PyResult<Self> {
+        if chunk_size == 0 {
+            return Err(PyValueError::new_err("chunk_size must be at least 1"));
+        }
+        Ok(Self { source: source.iter()?.unbind(), chunk_size, job })
+    }
+
+    /// Up to `chunk_size` items from the source, extracted with `extract`.
+    fn take<T>(&self, py: Python<'_>, extract: impl Fn(&Bound<'_, PyAny>) -> PyResult<T>) -> PyResult<Vec<T>> {
+        let mut source = self.source.bind(py).clone();
+        let mut items = Vec::with_capacity(self.chunk_size);
+        while items.len() < self.chunk_size {
+            match source.next() {
+                Some(item) => items.push(extract(&item?)?),
+                None => break,
+            }
+        }
+        Ok(items)
+    }
+
+    fn articles(&self, py: Python<'_>, articles: &Articles) -> PyResult<Option<PyObject>> {
+        let chunk: Vec<Article> = self.take(py, |item| item.extract())?;
+        if chunk.is_empty() {
+            return Ok(None);
+        }
+        let _timer = timer("ChunkIterator.articles");
+        let now = current_time();
+        let reloaded: Arc<PipelineConfig>;
+        let (config, areas): (&PipelineConfig, Option<&[(String, i32)]>) = match articles {
+            Articles::Default { areas } => (&DEFAULT_PIPELINE, Some(areas)),
+            Articles::Pipeline(pipeline) => {
+                reloaded = pipeline.borrow(py).config();
+                (&reloaded, None)
+            }
+        };
+        let records = unlocked(py, "ChunkIterator.articles", || {
+            chunk
+                .par_iter()
+                .map(|(title, body, url, published)| config.run(title, body, url, published.as_deref(), areas, now))
+                .collect::<Vec<_>>()
+        })?;
+        let list = PyList::empty_bound(py);
+        for record in &records {
+            list.append(record.to_dict(py)?)?;
+        }
+        Ok(Some(list.into_any().unbind()))
+    }
+
+    fn hashes(&self, py: Python<'_>, algorithm: HashAlgorithm, bits: u32, normalize: bool) -> PyResult<Option<PyObject>> {
+        let chunk: Vec<String> = self.take(py, text)?;
+        if chunk.is_empty() {
+            return Ok(None);
+        }
+        let hashes: Vec<u128> = unlocked(py, "ChunkIterator.hashes", || {
+            chunk.par_iter().map(|t| hash_text(t, algorithm, bits, normalize)).collect()
+        })?;
+        Ok(Some(PyList::new_bound(py, hashes).into_any().unbind()))
+    }
+}
+
+#[pymethods]
+impl ChunkIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyResult<PyRef<'_, Self>> {
+        guarded("ChunkIterator.__iter__", || Ok(slf))
+    }
+
+    fn __next__(&self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        guarded("ChunkIterator.__next__", || {
+            match &self.job {
+                Job::Articles(articles) => self.articles(py, articles),
+                Job::Hashes { algorithm, bits, normalize } => self.hashes(py, *algorithm, *bits, *normalize),
+            }
+        })
+    }
+
+    /// Items per chunk.
+    #[getter]
+    fn chunk_size(&self) -> PyResult<usize> {
+        guarded("ChunkIterator.chunk_size", || Ok(self.chunk_size))
+    }
+}
+
+/// Chunked ``Pipeline.iter_run``.
+pub(crate) fn pipeline_chunks(
+    pipeline: Py<Pipeline>,
+    articles: &Bound<'_, PyAny>,
+    chunk_size: usize,
+) -> PyResult<ChunkIterator> {
+    ChunkIterator::new(articles, chunk_size, Job::Articles(Articles::Pipeline(pipeline)))
+}
+
+/// Run the article pipeline over an iterable, one chunk at a time.
+///
+/// The streaming form of ``process_article``: articles are read lazily,
+/// so the input can be a generator over a file or a database cursor.
+///
+/// Parameters
+/// ----------
+/// articles : Iterable[tuple[str, str, str, str | None]]
+///     ``(title, body, url, published)`` tuples.
+/// chunk_size : int
+///     Articles processed (in parallel) per chunk. Default 1000.
+/// area_names : list[tuple[str, int]] | None
+///     Gazetteer ``(area_name, admin_level)`` pairs for admin detection.
+///
+/// Returns
+/// -------
+/// ChunkIterator
+///     Yields one ``list[dict]`` per chunk, records as for
+///     ``process_article``, in input order.
+///
+/// Raises
+/// ------
+/// ValueError
+///     If ``chunk_size`` is 0.
+/// TypeError
+///     If ``articles`` is not iterable; an item that isn't a 4-tuple
+///     raises when its chunk is reached.
+#[pyfunction]
+#[pyo3(signature = (articles, chunk_size=DEFAULT_CHUNK_SIZE, area_names=None))]
+pub fn iter_process(
+    articles: &Bound<'_, PyAny>,
+    chunk_size: usize,
+    area_names: Option<Vec<(String, i32)>>,
+) -> PyResult<ChunkIterator> {
+    guarded("iter_process", || {
+        let areas = sort_admin_areas(area_names.unwrap_or_default());
+        ChunkIterator::new(articles, chunk_size, Job::Articles(Articles::Default { areas }))
+    })
+}
+
+/// Fingerprint texts from an iterable, one chunk at a time.
+///
+/// Parameters
+/// ----------
+/// texts : Iterable[str]
+///     Texts to hash; missing values hash as empty text.
+/// chunk_size : int
+///     Texts hashed (in parallel) per chunk. Default 1000.
+/// bits, algorithm, normalize
+///     As for ``content_hash``.
+///
+/// Returns
+/// -------
+/// ChunkIterator
+///     Yields one ``list[int]`` per chunk, in input order.
+///
+/// Raises
+/// ------
+/// ValueError
+///     If ``chunk_size`` is 0, ``bits`` is not 64 or 128 or the algorithm
+///     is unknown.
+#[pyfunction]
+#[pyo3(signature = (texts, chunk_size=DEFAULT_CHUNK_SIZE, bits=128, algorithm="xxh3", normalize=true))]
+pub fn iter_content_hash(
+    texts: &Bound<'_, PyAny>,
+    chunk_size: usize,
+    bits: u32,
+    algorithm: &str,
+    normalize: bool,
+) -> PyResult<ChunkIterator> {
+    guarded("iter_content_hash", || {
+        let algorithm = check_args(bits, algorithm)?;
+        ChunkIterator::new(texts, chunk_size, Job::Hashes { algorithm, bits, normalize })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunks<'py>(iter: &Bound<'py, ChunkIterator>) -> Vec<Bound<'py, PyList>> {
+        let mut out = Vec::new();
+        while let Some(chunk) = iter.borrow().__next__(iter.py()).unwrap() {
+            out.push(chunk.into_bound(iter.py()).downcast_into::<PyList>().unwrap());
+        }
+        out
+    }
+
+    #[test]
+    fn test_hash_chunks() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let texts = py.eval_bound("(t for t in ['a', 'b', 'c', None, 'e'])", None, None).unwrap();
+            let iter = Bound::new(py, iter_content_hash(&texts, 2, 64, "xxh3", true).unwrap()).unwrap();
+            let sizes: Vec<usize> = chunks(&iter).iter().map(|c| c.len()).collect();
+            assert_eq!(sizes, vec![2, 2, 1]);
+            assert!(iter.borrow().__next__(py).unwrap().is_none());
+            assert!(iter_content_hash(&texts, 0, 64, "xxh3", true).is_err());
+        });
+    }
+
+    #[test]
+    fn test_article_chunks() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let articles = py
+                .eval_bound("[('Floods', 'Floods killed 12 people.', 'https://example.org/a', None)] * 3", None, None)
+                .unwrap();
+            let iter = Bound::new(py, iter_process(&articles, 2, None).unwrap()).unwrap();
+            let chunks = chunks(&iter);
+            assert_eq!(chunks.len(), 2);
+            let record = chunks[1].get_item(0).unwrap();
+            assert_eq!(record.get_item("canonical_url").unwrap().extract::<String>().unwrap(), "https://example.org/a");
+
+            let bad = py.eval_bound("[('only title',)]", None, None).unwrap();
+            let iter = iter_process(&bad, 2, None).unwrap();
+            assert!(iter.__next__(py).is_err());
+        });
+    }
+}
@@ -0,0 +1,389 @@
+//! `moltis-core` command-line tool.
+//!
+//! Runs the extension's extract / classify / dedupe / canonicalize paths
+//! over stdin or (optionally gzipped) JSONL files, for spot-checks and
+//! reprocessing jobs on servers without a Python environment. Output is
+//! JSONL (plain lines for `canonicalize`); counts go to stderr.
+
+use lexopt::prelude::*;
+use serde_json::{json, Map, Value};
+use std::ffi::OsString;
+use std::io::{BufRead, Write};
+use std::process::ExitCode;
+
+use crate::content_hash::content_fingerprint;
+use crate::content_index::{ContentIndex, Observation};
+use crate::figure_extraction::{figures_in, Language, NEGATED_ZERO};
+use crate::html_meta;
+use crate::html_text;
+use crate::jsonl_batch::{open_input, open_output, process_stream};
+use crate::lang_detect::detect;
+use crate::pipeline::PipelineConfig;
+use crate::url_canonical::canonicalize_url;
+use crate::url_key::url_hash128;
+
+const USAGE: &str = "\
+usage: moltis-core <command> [options] [INPUT]
+
+commands:
+  extract        HTML to text, page metadata and figures. Input lines are
+                 JSON objects with \"html\" and optionally \"url\".
+  classify       Run the article pipeline over JSONL articles (as
+                 process_jsonl), adding an \"enrichment\" record.
+  dedupe         Keep the first JSONL article per canonical URL and per
+                 content fingerprint.
+  canonicalize   Canonicalize URLs, one per line.
+
+options:
+  -o, --output PATH    Write here instead of stdout
+  --stages LIST        classify: comma-separated stages to run
+  --chunk-lines N      classify: lines per parallel chunk (default 10000)
+  --keep-updates       dedupe: also keep changed content at a seen URL
+  --lang CODE          extract: figure language, en, fr, pt or es
+                       (default: the page's language, else detected)
+  --no-negated-zero    extract, classify: leave out figure keys a
+                       statement negates instead of giving them 0
+  -h, --help           Show this help
+
+INPUT defaults to stdin (\"-\"). Paths ending in .gz are gzip-compressed.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Extract,
+    Classify,
+    Dedupe,
+    Canonicalize,
+}
+
+#[derive(Debug, PartialEq)]
+struct Args {
+    command: Command,
+    input: String,
+    output: String,
+    stages: Option<Vec<String>>,
+    chunk_lines: usize,
+    keep_updates: bool,
+    lang: Option<Language>,
+    negated_zero: bool,
+}
+
+/// Lines read and written, and lines dropped as unusable or duplicate.
+#[derive(Debug, Default, PartialEq)]
+struct Counts {
+    read: usize,
+    written: usize,
+    skipped: usize,
+}
+
+/// Parse arguments (without the program name); None if help was asked for.
+fn parse_args(args: impl IntoIterator<Item = OsString>) -> Result<Option<Args>, String> {
+    let mut parser = lexopt::Parser::from_iter(std::iter::once(OsString::from("moltis-core")).chain(args));
+    let mut command = None;
+    let mut input = None;
+    let mut output = "-".to_string();
+    let mut stages = None;
+    let mut chunk_lines = 10_000;
+    let mut keep_updates = false;
+    let mut lang = None;
+    let mut negated_zero = NEGATED_ZERO;
+    let err = |e: lexopt::Error| e.to_string();
+    while let Some(arg) = parser.next().map_err(err)? {
+        match arg {
+            Short('h') | Long("help") => return Ok(None),
+            Short('o') | Long("output") => output = parser.value().map_err(err)?.string().map_err(err)?,
+            Long("stages") => {
+                let list = parser.value().map_err(err)?.string().map_err(err)?;
+                stages = Some(list.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect());
+            }
+            Long("chunk-lines") => {
+                chunk_lines = parser.value().map_err(err)?.parse().map_err(err)?;
+                if chunk_lines == 0 {
+                    return Err("--chunk-lines must be at least 1".into());
+                }
+            }
+            Long("keep-updates") => keep_updates = true,
+            Long("lang") => {
+                let code = parser.value().map_err(err)?.string().map_err(err)?;
+                lang = Some(Language::from_code(&code).ok_or_else(|| format!("no figure patterns for --lang {code:?}"))?);
+            }
+            Long("no-negated-zero") => negated_zero = false,
+            Value(value) if command.is_none() => {
+                let name = value.string().map_err(err)?;
+                command = Some(match name.as_str() {
+                    "extract" => Command::Extract,
+                    "classify" => Command::Classify,
+                    "dedupe" => Command::Dedupe,
+                    "canonicalize" => Command::Canonicalize,
+                    _ => return Err(format!("unknown command {name:?}")),
+                });
+            }
+            Value(value) if input.is_none() => input = Some(value.string().map_err(err)?),
+            _ => return Err(arg.unexpected().to_string()),
+        }
+    }
+    let command = command.ok_or("missing command")?;
+    Ok(Some(Args {
+        command,
+        input: input.unwrap_or_else(|| "-".into()),
+        output,
+        stages,
+        chunk_lines,
+        keep_updates,
+        lang,
+        negated_zero,
+    }))
+}
+
+fn write_line(output: &mut dyn Write, line: &str) -> Result<(), String> {
+    output
+        .write_all(line.as_bytes())
+        .and_then(|_| output.write_all(b"\n"))
+        .map_err(|e| format!("write failed: {e}"))
+}
+
+/// JSON object lines of `input`, blank lines dropped; Err(()) for lines
+/// that are not objects.
+fn objects(input: impl BufRead) -> impl Iterator<Item = Result<Result<Map<String, Value>, ()>, String>> {
+    input
+        .lines()
+        .filter(|line| !matches!(line, Ok(l) if l.trim().is_empty()))
+        .map(|line| {
+            let line = line.map_err(|e| format!("read failed: {e}"))?;
+            Ok(match serde_json::from_str::<Value>(&line) {
+                Ok(Value::Object(object)) => Ok(object),
+                _ => Err(()),
+            })
+        })
+}
+
+/// Replace each object's `html` with an `extracted` object: page
+/// metadata, text and figures. Figures are read in `lang`, else the
+/// page's declared or detected language.
+fn extract(input: impl BufRead, output: &mut dyn Write, lang: Option<Language>, negated_zero: bool) -> Result<Counts, String> {
+    let mut counts = Counts::default();
+    for object in objects(input) {
+        counts.read += 1;
+        let Ok(mut object) = object? else {
+            counts.skipped += 1;
+            continue;
+        };
+        let Some(Value::String(html)) = object.remove("html") else {
+            counts.skipped += 1;
+            continue;
+        };
+        let meta = html_meta::extract(&html, object.get("url").and_then(Value::as_str));
+        let text = html_text::convert(&html);
+        let lang = lang
+            .or_else(|| meta.language.as_deref().and_then(Language::from_code))
+            .or_else(|| detect(&text, None).and_then(|(code, _)| Language::from_code(code)))
+            .unwrap_or_default();
+        let figures = figures_in(&text, lang, negated_zero);
+        object.insert(
+            "extracted".into(),
+            json!({
+                "title": meta.title,
+                "description": meta.description,
+                "published": meta.published_time,
+                "author": meta.author,
+                "site_name": meta.site_name,
+                "canonical_url": meta.canonical_url,
+                "language": meta.language,
+                "text": text,
+                "figures": figures,
+            }),
+        );
+        write_line(output, &Value::Object(object).to_string())?;
+        counts.written += 1;
+    }
+    Ok(counts)
+}
+
+/// Keep the first article per canonical URL and per content
+/// fingerprint; `keep_updates` also keeps changed content at a seen URL.
+fn dedupe(input: impl BufRead, output: &mut dyn Write, keep_updates: bool) -> Result<Counts, String> {
+    let mut index = ContentIndex::new(0);
+    let mut counts = Counts::default();
+    for object in objects(input) {
+        counts.read += 1;
+        let Ok(object) = object? else {
+            counts.skipped += 1;
+            continue;
+        };
+        let url = ["url", "link"].iter().find_map(|k| object.get(*k)?.as_str());
+        let Some(key) = url.and_then(|u| url_hash128(u).ok()) else {
+            counts.skipped += 1;
+            continue;
+        };
+        let body = ["body", "text", "content"]
+            .iter()
+            .find_map(|k| object.get(*k)?.as_str())
+            .unwrap_or_default();
+        let url = url.unwrap_or_default();
+        let keep = match index.record(key, canonicalize_url(url), content_fingerprint(body))? {
+            Observation::New => true,
+            Observation::Updated => keep_updates,
+            Observation::Unchanged | Observation::Mirror(_) => false,
+        };
+        if keep {
+            write_line(output, &Value::Object(object).to_string())?;
+            counts.written += 1;
+        } else {
+            counts.skipped += 1;
+        }
+    }
+    Ok(counts)
+}
+
+fn canonicalize(input: impl BufRead, output: &mut dyn Write) -> Result<Counts, String> {
+    let mut counts = Counts::default();
+    for line in input.lines() {
+        let line = line.map_err(|e| format!("read failed: {e}"))?;
+        let url = line.trim();
+        if url.is_empty() {
+            continue;
+        }
+        counts.read += 1;
+        write_line(output, &canonicalize_url(url))?;
+        counts.written += 1;
+    }
+    Ok(counts)
+}
+
+fn run(args: &Args) -> Result<Counts, String> {
+    let input = open_input(&args.input)?;
+    let mut output = open_output(&args.output)?;
+    let counts = match args.command {
+        Command::Extract => extract(input, &mut output, args.lang, args.negated_zero)?,
+        Command::Classify => {
+            let mut config = PipelineConfig::default();
+            config.negated_zero = args.negated_zero;
+            if let Some(stages) = &args.stages {
+                config.set_stages(stages)?;
+            }
+            let stats = process_stream(&config, input, &mut output, args.chunk_lines)?;
+            Counts { read: stats.read, written: stats.written, skipped: stats.skipped }
+        }
+        Command::Dedupe => dedupe(input, &mut output, args.keep_updates)?,
+        Command::Canonicalize => canonicalize(input, &mut output)?,
+    };
+    output.finish().map_err(|e| format!("write failed: {e}"))?;
+    Ok(counts)
+}
+
+/// Entry point for the `moltis-core` binary. Exits 0 on success, 1 if
+/// the command failed and 2 on bad arguments.
+pub fn main() -> ExitCode {
+    let args = match parse_args(std::env::args_os().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("moltis-core: {e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match run(&args) {
+        Ok(counts) => {
+            eprintln!("read {}, written {}, skipped {}", counts.read, counts.written, counts.skipped);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("moltis-core: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Result<Option<Args>, String> {
+        parse_args(list.iter().map(OsString::from))
+    }
+
+    fn output_lines(out: Vec<u8>) -> Vec<Value> {
+        String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let parsed = args(&["classify", "--stages", "figures, needs", "-o", "out.jsonl.gz", "in.jsonl"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(parsed.command, Command::Classify);
+        assert_eq!(parsed.input, "in.jsonl");
+        assert_eq!(parsed.output, "out.jsonl.gz");
+        assert_eq!(parsed.stages, Some(vec!["figures".to_string(), "needs".to_string()]));
+        let parsed = args(&["dedupe", "--keep-updates"]).unwrap().unwrap();
+        assert_eq!((parsed.input.as_str(), parsed.output.as_str(), parsed.keep_updates), ("-", "-", true));
+        assert_eq!(args(&["--help"]).unwrap(), None);
+        assert!(args(&[]).is_err());
+        assert!(args(&["summarize"]).is_err());
+        assert!(args(&["extract", "--bogus"]).is_err());
+        assert!(args(&["classify", "--chunk-lines", "0"]).is_err());
+        let parsed = args(&["extract", "--lang", "pt-MZ", "--no-negated-zero"]).unwrap().unwrap();
+        assert_eq!((parsed.lang, parsed.negated_zero), (Some(Language::Portuguese), false));
+        assert!(args(&["extract", "--lang", "de"]).is_err());
+    }
+
+    #[test]
+    fn test_extract() {
+        let input = r#"{"id": 7, "url": "https://example.org/a", "html": "<html lang=\"en\"><head><title>Floods</title></head><body><p>Floods displaced 5,000 people.</p></body></html>"}
+{"id": 8}
+"#;
+        let mut out = Vec::new();
+        let counts = extract(input.as_bytes(), &mut out, None, true).unwrap();
+        assert_eq!(counts, Counts { read: 2, written: 1, skipped: 1 });
+        let lines = output_lines(out);
+        assert_eq!(lines[0]["id"], 7);
+        assert!(lines[0].get("html").is_none());
+        assert_eq!(lines[0]["extracted"]["title"], "Floods");
+        assert_eq!(lines[0]["extracted"]["language"], "en");
+        assert_eq!(lines[0]["extracted"]["figures"]["people_affected"], 5000);
+        assert!(lines[0]["extracted"]["text"].as_str().unwrap().contains("5,000 people"));
+
+        // The page's language picks the patterns; negations give 0 unless turned off
+        let input = r#"{"html": "<html lang=\"fr\"><body><p>Au moins 45 morts. No deaths in Beira.</p></body></html>"}"#;
+        let mut out = Vec::new();
+        extract(input.as_bytes(), &mut out, None, true).unwrap();
+        assert_eq!(output_lines(out)[0]["extracted"]["figures"]["deaths"], 45);
+        let input = r#"{"html": "<p>No deaths were reported after the floods in Beira.</p>"}"#;
+        let mut out = Vec::new();
+        extract(input.as_bytes(), &mut out, Some(Language::English), true).unwrap();
+        assert_eq!(output_lines(out)[0]["extracted"]["figures"]["deaths"], 0);
+        let mut out = Vec::new();
+        extract(input.as_bytes(), &mut out, Some(Language::English), false).unwrap();
+        assert_eq!(output_lines(out)[0]["extracted"]["figures"], json!({}));
+    }
+
+    #[test]
+    fn test_dedupe() {
+        let input = r#"{"url": "https://example.org/a?utm_source=x", "body": "Floods in Beira"}
+{"url": "https://example.org/a", "body": "Floods  in beira"}
+{"url": "https://mirror.example.net/a", "body": "Floods in Beira"}
+{"url": "https://example.org/a", "body": "Floods in Beira, update"}
+{"body": "no url"}
+"#;
+        let mut out = Vec::new();
+        let counts = dedupe(input.as_bytes(), &mut out, false).unwrap();
+        assert_eq!(counts, Counts { read: 5, written: 1, skipped: 4 });
+        let mut out = Vec::new();
+        assert_eq!(dedupe(input.as_bytes(), &mut out, true).unwrap().written, 2);
+        assert_eq!(output_lines(out)[1]["body"], "Floods in Beira, update");
+    }
+
+    #[test]
+    fn test_canonicalize() {
+        let mut out = Vec::new();
+        let counts = canonicalize("https://Example.org/a?utm_source=x\n\nhttps://example.org/b\n".as_bytes(), &mut out).unwrap();
+        assert_eq!(counts.written, 2);
+        assert_eq!(String::from_utf8(out).unwrap(), "https://example.org/a\nhttps://example.org/b\n");
+    }
+}
@@ -0,0 +1,181 @@
+//! Column inputs and outputs for the batch APIs.
+//!
+//! Callers with data in pandas, Polars or numpy used to convert each
+//! column to a list of `str` before a batch call and back to an array
+//! after it. Batch functions take a [`Texts`] instead, which accepts a
+//! list or tuple as before and also anything with `tolist()` /
+//! `to_list()` (numpy arrays, pandas and Polars Series), letting the
+//! library build the Python strings in C rather than one element at a
+//! time. Missing values (`None`, NaN, `pd.NA`) read as empty text.
+//!
+//! When the input was array-like, results come back as numpy arrays:
+//! numbers from a single buffer copy (`numpy.frombuffer`), labels and
+//! 128-bit hashes as object arrays. List input still returns lists, and
+//! numpy is never imported unless the caller passed an array.
+
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+use pyo3::types::{IntoPyDict, PyByteArray, PyBytes, PyFloat, PyList, PyString, PyTuple};
+
+/// A text column: the strings, and whether they came from an array.
+pub(crate) struct Texts {
+    pub(crate) values: Vec<String>,
+    pub(crate) array: bool,
+}
+
+impl<'py> FromPyObject<'py> for Texts {
+    fn extract_bound(obj: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(list) = obj.downcast::<PyList>() {
+            return Ok(Self { values: list_texts(list)?, array: false });
+        }
+        if let Ok(tuple) = obj.downcast::<PyTuple>() {
+            let values = tuple.iter().map(|item| text(&item)).collect::<PyResult<_>>()?;
+            return Ok(Self { values, array: false });
+        }
+        if obj.is_instance_of::<PyString>() {
+            return Err(PyTypeError::new_err("expected a sequence of str, got a single str"));
+        }
+        // numpy and pandas spell it tolist, Polars to_list.
+        for method in ["tolist", "to_list"] {
+            if let Ok(list) = obj.call_method0(method) {
+                if let Ok(list) = list.downcast::<PyList>() {
+                    return Ok(Self { values: list_texts(list)?, array: true });
+                }
+            }
+        }
+        let values = obj.iter()?.map(|item| text(&item?)).collect::<PyResult<_>>()?;
+        Ok(Self { values, array: obj.hasattr("__array__")? })
+    }
+}
+
+fn list_texts(list: &Bound<'_, PyList>) -> PyResult<Vec<String>> {
+    list.iter().map(|item| text(&item)).collect()
+}
+
+pub(crate) fn text(item: &Bound<'_, PyAny>) -> PyResult<String> {
+    if let Ok(s) = item.downcast::<PyString>() {
+        return Ok(s.to_cow()?.into_owned());
+    }
+    if let Ok(b) = item.downcast::<PyBytes>() {
+        return Ok(String::from_utf8_lossy(b.as_bytes()).into_owned());
+    }
+    if item.is_none() || item.downcast::<PyFloat>().is_ok_and(|f| f.value().is_nan()) || is_pandas_na(item) {
+        return Ok(String::new());
+    }
+    Err(PyTypeError::new_err(format!(
+        "expected str, got {}",
+        item.get_type().name().map(|n| n.to_string()).unwrap_or_default()
+    )))
+}
+
+fn is_pandas_na(item: &Bound<'_, PyAny>) -> bool {
+    item.get_type().name().is_ok_and(|n| n == "NAType")
+}
+
+/// `values` as a numpy array of `dtype` (one buffer copy), or a list.
+fn numeric<T: IntoPy<PyObject> + Copy>(
+    py: Python<'_>,
+    values: &[T],
+    array: bool,
+    dtype: &str,
+    to_bytes: impl Fn(T) -> Vec<u8>,
+) -> PyResult<PyObject> {
+    if array {
+        if let Ok(numpy) = py.import_bound("numpy") {
+            let bytes: Vec<u8> = values.iter().flat_map(|v| to_bytes(*v)).collect();
+            let buffer = PyByteArray::new_bound(py, &bytes);
+            return Ok(numpy.call_method1("frombuffer", (buffer, dtype))?.unbind());
+        }
+    }
+    Ok(PyList::new_bound(py, values.iter().map(|v| v.into_py(py))).into_any().unbind())
+}
+
+pub(crate) fn f64_column(py: Python<'_>, values: &[f64], array: bool) -> PyResult<PyObject> {
+    numeric(py, values, array, "<f8", |v| v.to_le_bytes().to_vec())
+}
+
+pub(crate) fn f32_column(py: Python<'_>, values: &[f32], array: bool) -> PyResult<PyObject> {
+    numeric(py, values, array, "<f4", |v| v.to_le_bytes().to_vec())
+}
+
+/// Row-major `values` as a `(rows, dim)` float32 numpy array, or a list
+/// of row lists.
+pub(crate) fn f32_matrix(py: Python<'_>, values: &[f32], dim: usize) -> PyResult<PyObject> {
+    let rows = values.len().checked_div(dim).unwrap_or(0);
+    if py.import_bound("numpy").is_ok() {
+        let flat = f32_column(py, values, true)?;
+        return flat.call_method1(py, "reshape", (rows, dim));
+    }
+    let list = PyList::empty_bound(py);
+    for row in values.chunks(dim.max(1)) {
+        list.append(row.to_vec())?;
+    }
+    Ok(list.into_any().unbind())
+}
+
+pub(crate) fn i32_column(py: Python<'_>, values: &[i32], array: bool) -> PyResult<PyObject> {
+    numeric(py, values, array, "<i4", |v| v.to_le_bytes().to_vec())
+}
+
+pub(crate) fn u64_column(py: Python<'_>, values: &[u64], array: bool) -> PyResult<PyObject> {
+    numeric(py, values, array, "<u8", |v| v.to_le_bytes().to_vec())
+}
+
+pub(crate) fn bool_column(py: Python<'_>, values: &[bool], array: bool) -> PyResult<PyObject> {
+    numeric(py, values, array, "?", |v| vec![v as u8])
+}
+
+/// Python objects as a numpy object array, or a list.
+pub(crate) fn object_column(py: Python<'_>, values: Vec<PyObject>, array: bool) -> PyResult<PyObject> {
+    let list = PyList::new_bound(py, values);
+    if array {
+        if let Ok(numpy) = py.import_bound("numpy") {
+            let kwargs = [("dtype", "object")].into_py_dict_bound(py);
+            return Ok(numpy.call_method("array", (list,), Some(&kwargs))?.unbind());
+        }
+    }
+    Ok(list.into_any().unbind())
+}
+
+pub(crate) fn str_column(py: Python<'_>, values: &[&str], array: bool) -> PyResult<PyObject> {
+    object_column(py, values.iter().map(|v| v.into_py(py)).collect(), array)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extract(code: &str) -> PyResult<(Vec<String>, bool)> {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let value = py.eval_bound(code, None, None)?;
+            let texts: Texts = value.extract()?;
+            Ok((texts.values, texts.array))
+        })
+    }
+
+    #[test]
+    fn test_texts_from_sequences() {
+        assert_eq!(extract("['a', None, float('nan'), b'c']").unwrap(), (vec!["a".into(), "".into(), "".into(), "c".into()], false));
+        assert_eq!(extract("('a', 'b')").unwrap().0, vec!["a", "b"]);
+        assert_eq!(extract("(s for s in ['x'])").unwrap(), (vec!["x".into()], false));
+        assert!(extract("'abc'").is_err());
+        assert!(extract("['a', 1]").is_err());
+    }
+
+    #[test]
+    fn test_texts_from_array_likes() {
+        let code = "type('Series', (), {'tolist': lambda self: ['a', None], '__array__': None})()";
+        assert_eq!(extract(code).unwrap(), (vec!["a".into(), "".into()], true));
+        let code = "type('PolarsSeries', (), {'to_list': lambda self: ['b']})()";
+        assert_eq!(extract(code).unwrap(), (vec!["b".into()], true));
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            // Lists in, lists out, whether or not numpy is installed
+            let column = f64_column(py, &[0.5, 1.0], false).unwrap();
+            assert_eq!(column.extract::<Vec<f64>>(py).unwrap(), vec![0.5, 1.0]);
+            let column = str_column(py, &["x"], false).unwrap();
+            assert_eq!(column.extract::<Vec<String>>(py).unwrap(), vec!["x"]);
+        });
+    }
+}
@@ -0,0 +1,191 @@
+//! Content fingerprints — stable 64/128-bit hashes of page text.
+//!
+//! One definition of "the hash of this text" for the dedup index, the
+//! re-crawl change detector (`ContentIndex`) and `process_article`.
+//! xxh3 is the default: fast, and stable across runs and platforms.
+//! BLAKE3 is there for fingerprints shared outside the crawler, where
+//! collisions could be forced. Hashes are persisted, so these values
+//! must never change.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rayon::prelude::*;
+use xxhash_rust::xxh3::{xxh3_128, xxh3_64};
+
+use crate::columns::{object_column, u64_column, Texts};
+use crate::errors::unlocked;
+use crate::fuzzy_dedupe::normalize_text;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HashAlgorithm {
+    Xxh3,
+    Blake3,
+}
+
+impl HashAlgorithm {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "xxh3" | "xxhash" | "xxhash3" => Ok(HashAlgorithm::Xxh3),
+            "blake3" => Ok(HashAlgorithm::Blake3),
+            _ => Err(format!("unknown hash algorithm {name:?}: expected 'xxh3' or 'blake3'")),
+        }
+    }
+}
+
+/// Hash bytes to 64 or 128 bits. BLAKE3 output is truncated, read
+/// little-endian.
+pub(crate) fn hash_bytes(data: &[u8], algorithm: HashAlgorithm, bits: u32) -> u128 {
+    match (algorithm, bits) {
+        (HashAlgorithm::Xxh3, 64) => xxh3_64(data) as u128,
+        (HashAlgorithm::Xxh3, _) => xxh3_128(data),
+        (HashAlgorithm::Blake3, bits) => {
+            let digest = blake3::hash(data);
+            let mut head = [0u8; 16];
+            head.copy_from_slice(&digest.as_bytes()[..16]);
+            let full = u128::from_le_bytes(head);
+            if bits == 64 {
+                full & u64::MAX as u128
+            } else {
+                full
+            }
+        }
+    }
+}
+
+/// Fingerprint of page text; whitespace and case changes don't count.
+pub(crate) fn content_fingerprint(text: &str) -> u128 {
+    xxh3_128(normalize_text(text, false).as_bytes())
+}
+
+pub(crate) fn hash_text(text: &str, algorithm: HashAlgorithm, bits: u32, normalize: bool) -> u128 {
+    if normalize {
+        hash_bytes(normalize_text(text, false).as_bytes(), algorithm, bits)
+    } else {
+        hash_bytes(text.as_bytes(), algorithm, bits)
+    }
+}
+
+pub(crate) fn check_args(bits: u32, algorithm: &str) -> PyResult<HashAlgorithm> {
+    if bits != 64 && bits != 128 {
+        return Err(PyValueError::new_err("bits must be 64 or 128"));
+    }
+    HashAlgorithm::parse(algorithm).map_err(PyValueError::new_err)
+}
+
+/// Stable fingerprint of a text.
+///
+/// With the defaults this is the fingerprint ``ContentIndex`` and
+/// ``process_article`` use.
+///
+/// Parameters
+/// ----------
+/// text : str
+///     Page or article text.
+/// bits : int
+///     Hash width, 64 or 128. Default 128.
+/// algorithm : str
+///     ``"xxh3"`` (default) or ``"blake3"`` (truncated; for fingerprints
+///     shared with third parties).
+/// normalize : bool
+///     Collapse whitespace and lowercase before hashing, so reflowed or
+///     recased copies match. Default True.
+///
+/// Returns
+/// -------
+/// int
+///     Unsigned hash, stable across runs and platforms.
+///
+/// Raises
+/// ------
+/// ValueError
+///     If ``bits`` is not 64 or 128 or the algorithm is unknown.
+#[pyfunction]
+#[pyo3(signature = (text, bits=128, algorithm="xxh3", normalize=true))]
+pub fn content_hash(py: Python<'_>, text: &str, bits: u32, algorithm: &str, normalize: bool) -> PyResult<u128> {
+    let algorithm = check_args(bits, algorithm)?;
+    unlocked(py, "content_hash", || hash_text(text, algorithm, bits, normalize))
+}
+
+/// Fingerprint many texts in parallel, with the GIL released.
+///
+/// Parameters
+/// ----------
+/// texts : list[str] | numpy.ndarray | pandas.Series | polars.Series
+///     Texts to hash; missing values hash as empty text.
+/// bits, algorithm, normalize
+///     As for ``content_hash``.
+///
+/// Returns
+/// -------
+/// list[int] | numpy.ndarray
+///     One hash per text, in input order. Array input returns a numpy
+///     array: ``uint64`` for 64-bit hashes, ``object`` for 128-bit ones.
+///
+/// Raises
+/// ------
+/// ValueError
+///     If ``bits`` is not 64 or 128 or the algorithm is unknown.
+#[pyfunction]
+#[pyo3(signature = (texts, bits=128, algorithm="xxh3", normalize=true))]
+pub fn content_hash_batch(
+    py: Python<'_>,
+    texts: Texts,
+    bits: u32,
+    algorithm: &str,
+    normalize: bool,
+) -> PyResult<PyObject> {
+    let algorithm = check_args(bits, algorithm)?;
+    let hashes: Vec<u128> = unlocked(py, "content_hash_batch", || {
+        texts
+            .values
+            .par_iter()
+            .map(|t| hash_text(t, algorithm, bits, normalize))
+            .collect()
+    })?;
+    if bits == 64 {
+        let hashes: Vec<u64> = hashes.iter().map(|&h| h as u64).collect();
+        return u64_column(py, &hashes, texts.array);
+    }
+    object_column(py, hashes.iter().map(|h| h.into_py(py)).collect(), texts.array)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(text: &str, bits: u32, algorithm: &str, normalize: bool) -> PyResult<u128> {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| content_hash(py, text, bits, algorithm, normalize))
+    }
+
+    #[test]
+    fn test_stable_hashes() {
+        // Pinned values: hashes are persisted, so they must never change.
+        assert_eq!(hash("Floods in Beira", 64, "xxh3", false).unwrap(), 0x2e87f2b0bff8f3fb);
+        assert_eq!(hash("Floods in Beira", 128, "xxh3", true).unwrap(), 0x1c85695aa44b4a2f196a349230574287);
+        assert_eq!(hash_bytes(b"", HashAlgorithm::Blake3, 128), 0x49c9dc36ea4d40a0a6a1f9f5b94913af);
+        assert_eq!(hash_bytes(b"", HashAlgorithm::Blake3, 64), 0xa6a1f9f5b94913af);
+    }
+
+    #[test]
+    fn test_normalization_and_fingerprint() {
+        let a = hash("Floods  in\nBeira", 128, "xxh3", true).unwrap();
+        assert_eq!(a, hash("floods in beira", 128, "xxh3", true).unwrap());
+        assert_eq!(a, content_fingerprint("FLOODS in Beira"));
+        assert_ne!(a, hash("Floods  in\nBeira", 128, "xxh3", false).unwrap());
+        assert!(hash("x", 64, "blake3", true).unwrap() <= u64::MAX as u128);
+    }
+
+    #[test]
+    fn test_batch_and_bad_args() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let texts = Texts { values: vec!["a".to_string(), "b".to_string()], array: false };
+            let hashes = content_hash_batch(py, texts, 64, "BLAKE3", true).unwrap();
+            let hashes: Vec<u128> = hashes.extract(py).unwrap();
+            assert_eq!(hashes[1], hash("b", 64, "blake3", true).unwrap());
+        });
+        assert!(hash("x", 32, "xxh3", true).is_err());
+        assert!(hash("x", 64, "md5", true).is_err());
+    }
+}
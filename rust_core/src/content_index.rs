@@ -0,0 +1,279 @@
+//! Combined URL + content-hash duplicate detection.
+//!
+//! Pairs each canonical URL key with a fingerprint of the page text so a
+//! fetch can be classified as new, unchanged, an update (same URL,
+//! different content — re-crawl sooner) or a mirror (different URL, same
+//! content — suppress).
+
+use pyo3::exceptions::PyOSError;
+use pyo3::prelude::*;
+use std::collections::HashMap;
+
+use crate::content_hash::content_fingerprint;
+use crate::errors::{guarded, url_parse_error};
+use crate::storage::{self, Storage};
+use crate::url_canonical::canonicalize_url;
+use crate::url_key::url_hash128;
+
+/// Outcome of recording a fetched page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Observation {
+    New,
+    Unchanged,
+    /// Same URL, different content.
+    Updated,
+    /// Different URL, same content as the URL carried here.
+    Mirror(String),
+}
+
+impl Observation {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Observation::New => "new",
+            Observation::Unchanged => "unchanged",
+            Observation::Updated => "updated",
+            Observation::Mirror(_) => "mirror",
+        }
+    }
+}
+
+/// Storage key prefixes: URL key -> fingerprint, fingerprint -> first URL.
+const BY_URL: u8 = b'u';
+const BY_CONTENT: u8 = b'c';
+
+enum Maps {
+    Memory {
+        /// URL key hash -> content fingerprint last seen there.
+        by_url: HashMap<u128, u128>,
+        /// Content fingerprint -> canonical URL it was first seen at.
+        by_content: HashMap<u128, String>,
+    },
+    Stored(Box<dyn Storage>),
+}
+
+fn storage_key(prefix: u8, hash: u128) -> [u8; 17] {
+    let mut key = [prefix; 17];
+    key[1..].copy_from_slice(&hash.to_be_bytes());
+    key
+}
+
+/// Index of canonical URLs and the content last seen at each.
+///
+/// With persistent ``storage`` the index lives on disk instead of in
+/// memory, so it survives restarts and, with SQLite, can outgrow RAM
+/// (see ``configure_storage``).
+///
+/// Parameters
+/// ----------
+/// capacity : int
+///     Number of URLs to pre-allocate for (in-memory indexes). Default 0.
+/// storage : str | None
+///     ``"memory"``, ``"sqlite:PATH"`` or ``"mmap:PATH"``. Default: the
+///     process default, ``content_index.db`` / ``.map`` in its directory.
+/// batch_size : int
+///     Observations per committed SQLite transaction. Default 1000.
+///
+/// Raises
+/// ------
+/// ValueError
+///     If the storage spec is invalid.
+/// OSError
+///     If the storage cannot be opened.
+#[pyclass(module = "moltis_rust_core")]
+pub struct ContentIndex {
+    maps: Maps,
+}
+
+impl ContentIndex {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            maps: Maps::Memory {
+                by_url: HashMap::with_capacity(capacity),
+                by_content: HashMap::with_capacity(capacity),
+            },
+        }
+    }
+
+    fn open(capacity: usize, storage: Option<&str>, batch_size: usize) -> PyResult<Self> {
+        Ok(match storage::open_for(storage, "content_index", batch_size)? {
+            Some(store) => Self { maps: Maps::Stored(store) },
+            None => Self::new(capacity),
+        })
+    }
+
+    pub(crate) fn record(&mut self, url_key: u128, canonical: String, fingerprint: u128) -> Result<Observation, String> {
+        let (previous, first_url) = match &mut self.maps {
+            Maps::Memory { by_url, by_content } => {
+                let previous = by_url.insert(url_key, fingerprint);
+                let first_url = by_content.get(&fingerprint).cloned();
+                if first_url.is_none() {
+                    by_content.insert(fingerprint, canonical);
+                }
+                (previous, first_url)
+            }
+            Maps::Stored(store) => {
+                let url_key = storage_key(BY_URL, url_key);
+                let previous = store.get(&url_key)?.and_then(|fp| Some(u128::from_be_bytes(fp.try_into().ok()?)));
+                store.put(&url_key, &fingerprint.to_be_bytes())?;
+                let content_key = storage_key(BY_CONTENT, fingerprint);
+                let first_url = store.get(&content_key)?.map(|url| String::from_utf8_lossy(&url).into_owned());
+                if first_url.is_none() {
+                    store.put(&content_key, canonical.as_bytes())?;
+                }
+                (previous, first_url)
+            }
+        };
+        Ok(match (previous, first_url) {
+            (Some(fp), _) if fp == fingerprint => Observation::Unchanged,
+            (Some(_), _) => Observation::Updated,
+            (None, None) => Observation::New,
+            (None, Some(first)) => Observation::Mirror(first),
+        })
+    }
+
+    fn len(&self) -> Result<usize, String> {
+        match &self.maps {
+            Maps::Memory { by_url, .. } => Ok(by_url.len()),
+            Maps::Stored(store) => store.count(&[BY_URL]),
+        }
+    }
+}
+
+#[pymethods]
+impl ContentIndex {
+    #[new]
+    #[pyo3(signature = (capacity=0, storage=None, batch_size=1000))]
+    fn py_new(capacity: usize, storage: Option<&str>, batch_size: usize) -> PyResult<Self> {
+        guarded("ContentIndex.__new__", || Self::open(capacity, storage, batch_size))
+    }
+
+    /// Record a fetched page and classify it.
+    ///
+    /// Parameters
+    /// ----------
+    /// url : str
+    ///     URL the content was fetched from (canonicalized here).
+    /// content : str
+    ///     Extracted page text.
+    ///
+    /// Returns
+    /// -------
+    /// tuple[str, str | None]
+    ///     ``(status, original_url)`` where status is ``"new"``,
+    ///     ``"unchanged"``, ``"updated"`` (same URL, different content) or
+    ///     ``"mirror"`` (different URL, same content); ``original_url`` is
+    ///     the canonical URL first seen with this content for mirrors,
+    ///     otherwise None.
+    ///
+    /// Raises
+    /// ------
+    /// UrlParseError
+    ///     If the URL has no host.
+    /// OSError
+    ///     If the storage cannot be written.
+    fn observe(&mut self, url: &str, content: &str) -> PyResult<(&'static str, Option<String>)> {
+        guarded("ContentIndex.observe", || {
+            let key = url_hash128(url).map_err(|kind| url_parse_error(url, kind))?;
+            let observation = self
+                .record(key, canonicalize_url(url), content_fingerprint(content))
+                .map_err(PyOSError::new_err)?;
+            let original = match &observation {
+                Observation::Mirror(first) => Some(first.clone()),
+                _ => None,
+            };
+            Ok((observation.as_str(), original))
+        })
+    }
+
+    /// Number of distinct canonical URLs recorded.
+    fn __len__(&self) -> PyResult<usize> {
+        guarded("ContentIndex.__len__", || self.len().map_err(PyOSError::new_err))
+    }
+
+    fn clear(&mut self) -> PyResult<()> {
+        guarded("ContentIndex.clear", || {
+            match &mut self.maps {
+                Maps::Memory { by_url, by_content } => {
+                    by_url.clear();
+                    by_content.clear();
+                }
+                Maps::Stored(store) => store.clear().map_err(PyOSError::new_err)?,
+            }
+            Ok(())
+        })
+    }
+
+    /// Write pending observations to storage (no-op in memory).
+    fn flush(&mut self) -> PyResult<()> {
+        guarded("ContentIndex.flush", || {
+            match &mut self.maps {
+                Maps::Memory { .. } => Ok(()),
+                Maps::Stored(store) => store.flush().map_err(PyOSError::new_err),
+            }
+        })
+    }
+
+    /// Storage spec, e.g. ``"sqlite:state/content_index.db"``, or ``"memory"``.
+    #[getter]
+    fn storage(&self) -> PyResult<String> {
+        guarded("ContentIndex.storage", || {
+            Ok(match &self.maps {
+                Maps::Memory { .. } => "memory".into(),
+                Maps::Stored(store) => store.spec(),
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn observe(index: &mut ContentIndex, url: &str, content: &str) -> Observation {
+        let key = url_hash128(url).unwrap();
+        index.record(key, canonicalize_url(url), content_fingerprint(content)).unwrap()
+    }
+
+    #[test]
+    fn test_new_unchanged_updated() {
+        let mut index = ContentIndex::new(0);
+        let url = "https://example.org/report/1";
+        assert_eq!(observe(&mut index, url, "Floods in Beira"), Observation::New);
+        assert_eq!(
+            observe(&mut index, "http://www.example.org/report/1?utm_source=x", "floods  in beira"),
+            Observation::Unchanged
+        );
+        assert_eq!(observe(&mut index, url, "Floods in Beira, 12 dead"), Observation::Updated);
+        assert_eq!(observe(&mut index, url, "Floods in Beira, 12 dead"), Observation::Unchanged);
+    }
+
+    #[test]
+    fn test_mirror_reports_original() {
+        let mut index = ContentIndex::new(0);
+        observe(&mut index, "https://example.org/report/1", "Cyclone update");
+        assert_eq!(
+            observe(&mut index, "https://mirror.example.net/copy", "Cyclone update"),
+            Observation::Mirror("https://example.org/report/1".to_string())
+        );
+        assert_eq!(observe(&mut index, "https://other.example/x", "Different text"), Observation::New);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_persistent_index() {
+        let path = std::env::temp_dir().join(format!("moltis_content_index_{}.db", std::process::id()));
+        let spec = format!("sqlite:{}", path.display());
+        let _ = std::fs::remove_file(&path);
+        let mut index = ContentIndex::open(0, Some(&spec), 1000).unwrap();
+        observe(&mut index, "https://example.org/report/1", "Cyclone update");
+        drop(index);
+        let mut index = ContentIndex::open(0, Some(&spec), 1000).unwrap();
+        assert_eq!(index.len(), Ok(1));
+        assert_eq!(observe(&mut index, "https://example.org/report/1", "cyclone  update"), Observation::Unchanged);
+        assert_eq!(
+            observe(&mut index, "https://mirror.example.net/copy", "Cyclone update"),
+            Observation::Mirror("https://example.org/report/1".to_string())
+        );
+        assert_eq!(observe(&mut index, "https://example.org/report/1", "Cyclone update, 3 dead"), Observation::Updated);
+    }
+}
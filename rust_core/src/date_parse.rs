@@ -0,0 +1,387 @@
+//! Multilingual date normalization to ISO 8601.
+//!
+//! Article pages carry dates as feed timestamps (RFC 2822 / RFC 3339),
+//! meta attributes, and free text in English, French or Portuguese
+//! ("14 de março de 2025", "il y a 2 jours"). Every recognized form is
+//! normalized to a UTC timestamp with a confidence level saying how much
+//! of it was actually stated.
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+use regex::Regex;
+
+use crate::errors::guarded;
+use crate::frontier::now_secs;
+
+// Month names and abbreviations (EN, FR, PT); matched lowercased with any
+// trailing dot removed.
+static MONTHS: &[(&str, u32)] = &[
+    ("january", 1), ("jan", 1), ("janvier", 1), ("janv", 1), ("janeiro", 1),
+    ("february", 2), ("feb", 2), ("février", 2), ("fevrier", 2), ("févr", 2), ("fevr", 2),
+    ("fév", 2), ("fev", 2), ("fevereiro", 2),
+    ("march", 3), ("mar", 3), ("mars", 3), ("março", 3), ("marco", 3),
+    ("april", 4), ("apr", 4), ("avril", 4), ("avr", 4), ("abril", 4), ("abr", 4),
+    ("may", 5), ("mai", 5), ("maio", 5),
+    ("june", 6), ("jun", 6), ("juin", 6), ("junho", 6),
+    ("july", 7), ("jul", 7), ("juillet", 7), ("juil", 7), ("julho", 7),
+    ("august", 8), ("aug", 8), ("août", 8), ("aout", 8), ("agosto", 8), ("ago", 8),
+    ("september", 9), ("sep", 9), ("sept", 9), ("septembre", 9), ("setembro", 9), ("set", 9),
+    ("october", 10), ("oct", 10), ("octobre", 10), ("outubro", 10), ("out", 10),
+    ("november", 11), ("nov", 11), ("novembre", 11), ("novembro", 11),
+    ("december", 12), ("dec", 12), ("décembre", 12), ("decembre", 12), ("déc", 12),
+    ("dezembro", 12), ("dez", 12),
+];
+
+static EPOCH: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\d{9,10}(?:\d{3})?$").unwrap());
+static ISO: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(\d{4})-(\d{2})-(\d{2})(?:[T ](\d{2}):(\d{2})(?::(\d{2})(?:[.,]\d+)?)?\s*(Z|[+-]\d{2}:?\d{2})?)?",
+    )
+    .unwrap()
+});
+// "14 March 2025", "1er avril 2025", "14 de março de 2025"
+static NAMED_DMY: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(\d{1,2})(?:st|nd|rd|th|er|º|°)?\s+(?:de\s+)?(\p{L}+)\.?,?\s+(?:de\s+)?(\d{4})\b")
+        .unwrap()
+});
+// "March 14, 2025", "Mar. 14 2025"
+static NAMED_MDY: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(\p{L}+)\.?\s+(\d{1,2})(?:st|nd|rd|th)?,?\s+(\d{4})\b").unwrap()
+});
+// "March 2025", "março de 2025"
+static MONTH_YEAR: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\b(\p{L}+)\.?\s+(?:de\s+)?(\d{4})\b").unwrap());
+static NUMERIC: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\b(\d{1,2})[/.](\d{1,2})[/.](\d{4})\b").unwrap());
+// "08:30", "14h30", "8:30 pm", optionally followed by a zone
+static TIME: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(\d{1,2})[:h](\d{2})(?::(\d{2}))?\s*(a\.?m\.?|p\.?m\.?)?(?:\s*(z\b|utc|gmt|[+-]\d{2}:?\d{2}))?",
+    )
+    .unwrap()
+});
+static RELATIVE_DAY: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?:^|\W)(today|yesterday|aujourd'hui|aujourd’hui|avant-hier|hier|hoje|anteontem|ontem)(?:\W|$)")
+        .unwrap()
+});
+static RELATIVE_AGO: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)(?:\b(\d+|an?|one)\s+(minute|hour|day|week|month)s?\s+ago\b|\bil y a\s+(\d+|une?)\s+(minute|heure|jour|semaine|mois)s?\b|(?:^|\s)há\s+(\d+|uma?)\s+(minuto|hora|dia|semana|m[eê]s)(?:es|s)?\b)",
+    )
+    .unwrap()
+});
+
+/// How much of a parsed date was stated in the text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Confidence {
+    /// Full timestamp with an explicit zone (or epoch seconds).
+    High,
+    /// Full calendar date; time or zone missing and assumed UTC.
+    Medium,
+    /// Relative date, month precision, or ambiguous day/month order.
+    Low,
+}
+
+impl Confidence {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Confidence::High => "high",
+            Confidence::Medium => "medium",
+            Confidence::Low => "low",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct ParsedDate {
+    pub timestamp: DateTime<Utc>,
+    pub confidence: Confidence,
+}
+
+impl ParsedDate {
+    pub(crate) fn iso(&self) -> String {
+        self.timestamp.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+    }
+}
+
+fn month_number(name: &str) -> Option<u32> {
+    let name = name.trim_end_matches('.').to_lowercase();
+    MONTHS.iter().find(|(m, _)| *m == name).map(|(_, n)| *n)
+}
+
+fn date(year: &str, month: u32, day: &str) -> Option<NaiveDate> {
+    let year: i32 = year.parse().ok()?;
+    if !(1900..=2100).contains(&year) {
+        return None;
+    }
+    NaiveDate::from_ymd_opt(year, month, day.parse().ok()?)
+}
+
+/// Offset in seconds east of UTC for `Z`, `UTC`, `GMT` or `+hh:mm`.
+fn zone_offset(zone: &str) -> Option<i64> {
+    let zone = zone.to_lowercase();
+    if matches!(zone.as_str(), "z" | "utc" | "gmt") {
+        return Some(0);
+    }
+    let sign = if zone.starts_with('-') { -1 } else { 1 };
+    let digits: String = zone.chars().filter(char::is_ascii_digit).collect();
+    let (hours, minutes) = digits.split_at(2.min(digits.len()));
+    Some(sign * (hours.parse::<i64>().ok()? * 3600 + minutes.parse::<i64>().unwrap_or(0) * 60))
+}
+
+/// Timestamp from a date, optional time and optional zone.
+fn combine(date: NaiveDate, time: Option<NaiveTime>, zone: Option<&str>) -> ParsedDate {
+    let local = NaiveDateTime::new(date, time.unwrap_or(NaiveTime::MIN));
+    let offset = zone.and_then(zone_offset);
+    ParsedDate {
+        timestamp: (local - Duration::seconds(offset.unwrap_or(0))).and_utc(),
+        confidence: if time.is_some() && offset.is_some() {
+            Confidence::High
+        } else {
+            Confidence::Medium
+        },
+    }
+}
+
+/// The first clock time in the text and the zone that follows it.
+fn find_time(text: &str) -> Option<(NaiveTime, Option<String>)> {
+    let caps = TIME.captures(text)?;
+    let mut hour: u32 = caps[1].parse().ok()?;
+    let minute: u32 = caps[2].parse().ok()?;
+    let second: u32 = caps.get(3).map_or(Some(0), |s| s.as_str().parse().ok())?;
+    if let Some(meridiem) = caps.get(4) {
+        let pm = meridiem.as_str().to_lowercase().starts_with('p');
+        if hour == 0 || hour > 12 {
+            return None;
+        }
+        hour = hour % 12 + if pm { 12 } else { 0 };
+    }
+    let time = NaiveTime::from_hms_opt(hour, minute, second)?;
+    Some((time, caps.get(5).map(|z| z.as_str().to_string())))
+}
+
+fn parse_iso(text: &str) -> Option<ParsedDate> {
+    let caps = ISO.captures(text)?;
+    let month: u32 = caps[2].parse().ok()?;
+    let day = date(&caps[1], month, &caps[3])?;
+    let time = match (caps.get(4), caps.get(5)) {
+        (Some(h), Some(m)) => {
+            let second = caps.get(6).map_or("0", |s| s.as_str());
+            Some(NaiveTime::from_hms_opt(h.as_str().parse().ok()?, m.as_str().parse().ok()?, second.parse().ok()?)?)
+        }
+        _ => None,
+    };
+    Some(combine(day, time, caps.get(7).map(|z| z.as_str())))
+}
+
+fn parse_named(text: &str) -> Option<ParsedDate> {
+    let day = NAMED_DMY
+        .captures_iter(text)
+        .find_map(|c| date(&c[3], month_number(&c[2])?, &c[1]))
+        .or_else(|| {
+            NAMED_MDY
+                .captures_iter(text)
+                .find_map(|c| date(&c[3], month_number(&c[1])?, &c[2]))
+        })?;
+    let time = find_time(text);
+    Some(combine(day, time.as_ref().map(|t| t.0), time.as_ref().and_then(|t| t.1.as_deref())))
+}
+
+fn parse_numeric(text: &str, month_first: bool) -> Option<ParsedDate> {
+    let caps = NUMERIC.captures(text)?;
+    let (a, b): (u32, u32) = (caps[1].parse().ok()?, caps[2].parse().ok()?);
+    let ambiguous = a <= 12 && b <= 12 && a != b;
+    let (month, day) = if a > 12 || (b <= 12 && !month_first) { (b, a) } else { (a, b) };
+    let mut parsed = combine(date(&caps[3], month, &day.to_string())?, None, None);
+    if let Some((time, zone)) = find_time(&text[caps.get(0)?.end()..]) {
+        parsed = combine(parsed.timestamp.date_naive(), Some(time), zone.as_deref());
+    }
+    if ambiguous {
+        parsed.confidence = Confidence::Low;
+    }
+    Some(parsed)
+}
+
+fn parse_month_year(text: &str) -> Option<ParsedDate> {
+    // A stated but impossible day ("31 February") isn't month precision
+    if NAMED_DMY.captures_iter(text).any(|c| month_number(&c[2]).is_some()) {
+        return None;
+    }
+    let day = MONTH_YEAR
+        .captures_iter(text)
+        .find_map(|c| date(&c[2], month_number(&c[1])?, "1"))?;
+    Some(ParsedDate {
+        timestamp: NaiveDateTime::new(day, NaiveTime::MIN).and_utc(),
+        confidence: Confidence::Low,
+    })
+}
+
+fn parse_relative(text: &str, now: DateTime<Utc>) -> Option<ParsedDate> {
+    let midnight = |days_back: i64| (now.date_naive() - Duration::days(days_back)).and_time(NaiveTime::MIN).and_utc();
+    let low = |timestamp| Some(ParsedDate { timestamp, confidence: Confidence::Low });
+    if let Some(caps) = RELATIVE_AGO.captures(text) {
+        let groups: Vec<&str> = caps.iter().skip(1).flatten().map(|m| m.as_str()).collect();
+        let (count, unit) = (groups.first()?.to_lowercase(), groups.get(1)?.to_lowercase());
+        let count: i64 = count.parse().unwrap_or(1);
+        let seconds = match unit.as_str() {
+            "minute" | "minuto" => 60,
+            "hour" | "heure" | "hora" => 3600,
+            "day" | "jour" | "dia" => 86_400,
+            "week" | "semaine" | "semana" => 7 * 86_400,
+            _ => 30 * 86_400,
+        };
+        // "99999999999999 days ago" is out of range, not a date
+        let back = Duration::try_seconds(count.checked_mul(seconds)?)?;
+        return low(now.checked_sub_signed(back)?);
+    }
+    let word = RELATIVE_DAY.captures(text)?[1].to_lowercase();
+    match word.as_str() {
+        "today" | "hoje" => low(midnight(0)),
+        "yesterday" | "hier" | "ontem" => low(midnight(1)),
+        "avant-hier" | "anteontem" => low(midnight(2)),
+        _ => low(midnight(0)), // aujourd'hui
+    }
+}
+
+/// Parse a date from text or an attribute value. `hint_lang` only decides
+/// day/month order in ambiguous numeric dates (`en-US` is month first).
+pub(crate) fn parse_at(text: &str, hint_lang: Option<&str>, now: DateTime<Utc>) -> Option<ParsedDate> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    if EPOCH.is_match(text) {
+        let value: i64 = text.parse().ok()?;
+        let secs = if text.len() > 10 { value / 1000 } else { value };
+        return Some(ParsedDate {
+            timestamp: DateTime::from_timestamp(secs, 0)?,
+            confidence: Confidence::High,
+        });
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc2822(text) {
+        return Some(ParsedDate {
+            timestamp: dt.with_timezone(&Utc),
+            confidence: Confidence::High,
+        });
+    }
+    let month_first = hint_lang
+        .map(|l| l.trim().to_lowercase().replace('_', "-"))
+        .is_some_and(|l| l == "en-us" || l == "us");
+    parse_iso(text)
+        .or_else(|| parse_named(text))
+        .or_else(|| parse_numeric(text, month_first))
+        .or_else(|| parse_month_year(text))
+        .or_else(|| parse_relative(text, now))
+}
+
+/// Normalize a date in free text or an attribute value to ISO 8601.
+///
+/// Understands RFC 2822 / RFC 3339 feed timestamps, ISO dates, epoch
+/// seconds or milliseconds, English/French/Portuguese month names
+/// ("14 March 2025", "1er avril 2025", "14 de março de 2025"), numeric
+/// dates and relative dates ("yesterday", "il y a 2 jours", "há 3 horas").
+///
+/// Parameters
+/// ----------
+/// text : str
+///     The date text or attribute value.
+/// hint_lang : str | None
+///     Page language. Decides day/month order in ambiguous numeric dates:
+///     ``"en-US"`` is month first, everything else day first.
+/// now : float | None
+///     Unix timestamp relative dates count back from. Defaults to the
+///     current time.
+///
+/// Returns
+/// -------
+/// tuple[str, str] | None
+///     ``(timestamp, confidence)``: a UTC timestamp
+///     ``"YYYY-MM-DDTHH:MM:SSZ"`` and ``"high"`` (explicit time and zone),
+///     ``"medium"`` (full date, time or zone assumed) or ``"low"``
+///     (relative, month-only or ambiguous day/month). None if no date
+///     was recognized.
+#[pyfunction]
+#[pyo3(signature = (text, hint_lang=None, now=None))]
+pub fn parse_date(text: &str, hint_lang: Option<&str>, now: Option<f64>) -> PyResult<Option<(String, &'static str)>> {
+    guarded("parse_date", || {
+        let Some(now) = DateTime::from_timestamp(now.unwrap_or_else(now_secs) as i64, 0) else {
+            return Ok(None);
+        };
+        Ok(parse_at(text, hint_lang, now).map(|p| (p.iso(), p.confidence.as_str())))
+    })
+}
+
+/// Unix seconds from a Python date value: seconds since the epoch, or
+/// any text `parse_at` recognizes.
+pub(crate) fn timestamp_from_py(value: &Bound<'_, PyAny>) -> PyResult<i64> {
+    if let Ok(secs) = value.extract::<f64>() {
+        // Later arithmetic on timestamps assumes they're real dates
+        return DateTime::from_timestamp(secs as i64, 0)
+            .filter(|_| secs.is_finite())
+            .map(|t| t.timestamp())
+            .ok_or_else(|| pyo3::exceptions::PyValueError::new_err(format!("timestamp out of range: {secs}")));
+    }
+    let text: String = value.extract()?;
+    let now = DateTime::from_timestamp(now_secs() as i64, 0).unwrap_or_default();
+    parse_at(&text, None, now)
+        .map(|p| p.timestamp.timestamp())
+        .ok_or_else(|| pyo3::exceptions::PyValueError::new_err(format!("unrecognized date {text:?}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str, lang: Option<&str>) -> Option<(String, &'static str)> {
+        // 2025-03-16T12:00:00Z
+        let now = DateTime::from_timestamp(1_742_126_400, 0).unwrap();
+        parse_at(text, lang, now).map(|p| (p.iso(), p.confidence.as_str()))
+    }
+
+    fn iso(text: &str) -> String {
+        parse(text, None).unwrap().0
+    }
+
+    #[test]
+    fn test_feed_formats() {
+        assert_eq!(parse("Fri, 14 Mar 2025 08:00:00 +0200", None).unwrap(), ("2025-03-14T06:00:00Z".into(), "high"));
+        assert_eq!(parse("2025-03-14T08:00:00.123+02:00", None).unwrap(), ("2025-03-14T06:00:00Z".into(), "high"));
+        assert_eq!(parse("2025-03-14", None).unwrap(), ("2025-03-14T00:00:00Z".into(), "medium"));
+        assert_eq!(parse("1741939200", None).unwrap(), ("2025-03-14T08:00:00Z".into(), "high"));
+    }
+
+    #[test]
+    fn test_month_names() {
+        assert_eq!(iso("Published 14 March 2025"), "2025-03-14T00:00:00Z");
+        assert_eq!(iso("March 14th, 2025 at 8:30 pm UTC"), "2025-03-14T20:30:00Z");
+        assert_eq!(iso("le 1er avril 2025 à 14h30"), "2025-04-01T14:30:00Z");
+        assert_eq!(iso("14 de março de 2025"), "2025-03-14T00:00:00Z");
+        assert_eq!(iso("Mis à jour le 3 févr. 2025"), "2025-02-03T00:00:00Z");
+        assert_eq!(parse("março de 2025", None).unwrap(), ("2025-03-01T00:00:00Z".into(), "low"));
+    }
+
+    #[test]
+    fn test_numeric_order() {
+        assert_eq!(parse("14/03/2025", None).unwrap(), ("2025-03-14T00:00:00Z".into(), "medium"));
+        assert_eq!(parse("03/04/2025", None).unwrap(), ("2025-04-03T00:00:00Z".into(), "low"));
+        assert_eq!(parse("03/04/2025", Some("en_US")).unwrap().0, "2025-03-04T00:00:00Z");
+    }
+
+    #[test]
+    fn test_relative() {
+        assert_eq!(parse("yesterday", None).unwrap(), ("2025-03-15T00:00:00Z".into(), "low"));
+        assert_eq!(iso("il y a 2 jours"), "2025-03-14T12:00:00Z");
+        assert_eq!(iso("há 3 horas"), "2025-03-16T09:00:00Z");
+        assert_eq!(iso("an hour ago"), "2025-03-16T11:00:00Z");
+        assert_eq!(iso("Aujourd'hui"), "2025-03-16T00:00:00Z");
+    }
+
+    #[test]
+    fn test_unrecognized() {
+        assert_eq!(parse("", None), None);
+        assert_eq!(parse("no date here", None), None);
+        assert_eq!(parse("31 February 2025", None), None);
+        assert_eq!(parse("99999999999999 days ago", None), None);
+    }
+}
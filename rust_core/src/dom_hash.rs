@@ -0,0 +1,169 @@
+//! Structural fingerprints of HTML pages.
+//!
+//! `content_hash` changes whenever a word changes, so a page re-served
+//! with a different ad, "most read" box or timestamp looks new. The
+//! fingerprint here ignores text altogether: every element is labelled
+//! by its tag path (`body>div.story>p`), consecutive paths are shingled,
+//! and the shingles are folded into a 64-bit simhash. Pages built from
+//! the same template land within a few bits of each other; pages with a
+//! different layout don't. Scripts, iframes and other embeds are skipped,
+//! and digits are dropped from class names, so rotating ad slots
+//! (`ad-slot-17`) don't move the hash.
+
+use ego_tree::iter::Edge;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use scraper::{Html, Node};
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::errors::{guarded, unlocked};
+use crate::html_text::SKIPPED;
+
+pub(crate) const DEFAULT_SHINGLE_SIZE: usize = 4;
+
+/// Tag name plus its classes, sorted and without digits: `div.ad-slot-`.
+fn label(el: &scraper::node::Element) -> String {
+    let mut classes: Vec<String> = el
+        .classes()
+        .map(|c| c.chars().filter(|ch| !ch.is_ascii_digit()).collect::<String>())
+        .filter(|c| !c.is_empty())
+        .collect();
+    classes.sort();
+    classes.dedup();
+    let mut label = el.name().to_string();
+    for class in classes {
+        label.push('.');
+        label.push_str(&class);
+    }
+    label
+}
+
+/// Hash of each element's tag path, in document order.
+fn path_hashes(html: &str) -> Vec<u64> {
+    let doc = Html::parse_document(html);
+    let mut path: Vec<String> = Vec::new();
+    let mut skip_depth = 0usize;
+    let mut hashes = Vec::new();
+    for edge in doc.tree.root().traverse() {
+        match edge {
+            Edge::Open(node) => {
+                if let Node::Element(el) = node.value() {
+                    if skip_depth > 0 || SKIPPED.contains(&el.name()) {
+                        skip_depth += 1;
+                        continue;
+                    }
+                    path.push(label(el));
+                    hashes.push(xxh3_64(path.join(">").as_bytes()));
+                }
+            }
+            Edge::Close(node) => {
+                if let Node::Element(_) = node.value() {
+                    if skip_depth > 0 {
+                        skip_depth -= 1;
+                    } else {
+                        path.pop();
+                    }
+                }
+            }
+        }
+    }
+    hashes
+}
+
+pub(crate) fn shingle_hash(html: &str, shingle_size: usize) -> u64 {
+    let paths = path_hashes(html);
+    if paths.is_empty() {
+        return 0;
+    }
+    let mut votes = [0i64; 64];
+    for shingle in paths.windows(shingle_size.min(paths.len())) {
+        let bytes: Vec<u8> = shingle.iter().flat_map(|h| h.to_le_bytes()).collect();
+        let hash = xxh3_64(&bytes);
+        for (bit, vote) in votes.iter_mut().enumerate() {
+            *vote += if (hash >> bit) & 1 == 1 { 1 } else { -1 };
+        }
+    }
+    votes.iter().enumerate().filter(|(_, vote)| **vote > 0).fold(0, |acc, (bit, _)| acc | 1 << bit)
+}
+
+pub(crate) fn similarity(a: u64, b: u64) -> f64 {
+    1.0 - (a ^ b).count_ones() as f64 / 64.0
+}
+
+/// Structural fingerprint of an HTML page.
+///
+/// A 64-bit simhash of tag-path shingles. Text is ignored, as are
+/// scripts, styles, iframes and other embeds, and digits in class names,
+/// so the same template with different ads or boilerplate hashes the
+/// same or nearly so. Compare fingerprints with ``dom_similarity``.
+///
+/// Parameters
+/// ----------
+/// html : str
+///     A full document or a fragment.
+/// shingle_size : int
+///     Consecutive element paths per shingle. Larger values are stricter
+///     about element order. Default 4.
+///
+/// Returns
+/// -------
+/// int
+///     Unsigned 64-bit fingerprint.
+///
+/// Raises
+/// ------
+/// ValueError
+///     If ``shingle_size`` is 0.
+#[pyfunction]
+#[pyo3(signature = (html, shingle_size=DEFAULT_SHINGLE_SIZE))]
+pub fn dom_shingle_hash(py: Python<'_>, html: &str, shingle_size: usize) -> PyResult<u64> {
+    if shingle_size == 0 {
+        return Err(PyValueError::new_err("shingle_size must be at least 1"));
+    }
+    unlocked(py, "dom_shingle_hash", || shingle_hash(html, shingle_size))
+}
+
+/// Similarity of two ``dom_shingle_hash`` fingerprints.
+///
+/// Parameters
+/// ----------
+/// a, b : int
+///     Fingerprints.
+///
+/// Returns
+/// -------
+/// float
+///     Share of equal bits, 0-1. Pages from one template typically score
+///     above 0.9; unrelated layouts around 0.5.
+#[pyfunction]
+pub fn dom_similarity(a: u64, b: u64) -> PyResult<f64> {
+    guarded("dom_similarity", || Ok(similarity(a, b)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(ad: &str, body: &str) -> String {
+        format!(
+            "<html><body><header class=\"site\"><nav><a>Home</a><a>News</a></nav></header>\
+             <div class=\"ad-slot-{ad}\"><iframe src=\"https://ads.example/{ad}\"></iframe></div>\
+             <article class=\"story\"><h1>Title</h1>{body}</article>\
+             <footer><p>Contact</p></footer><script>var ad = {ad};</script></body></html>"
+        )
+    }
+
+    #[test]
+    fn test_same_template_matches() {
+        let paragraphs = "<p>One.</p><p>Two.</p><p>Three.</p>";
+        let a = shingle_hash(&page("17", paragraphs), DEFAULT_SHINGLE_SIZE);
+        let b = shingle_hash(&page("42", "<p>Other.</p><p>Words.</p><p>Here.</p>"), DEFAULT_SHINGLE_SIZE);
+        assert_eq!(a, b);
+        let longer = shingle_hash(&page("3", "<p>One.</p><p>Two.</p><p>Three.</p><p>Four.</p>"), DEFAULT_SHINGLE_SIZE);
+        let other = shingle_hash(
+            "<html><body><table><tr><td><b>Index</b></td></tr><tr><td><ul><li>a</li><li>b</li></ul></td></tr></table></body></html>",
+            DEFAULT_SHINGLE_SIZE,
+        );
+        assert!(similarity(a, longer) > similarity(a, other));
+    }
+}
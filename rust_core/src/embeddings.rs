@@ -0,0 +1,323 @@
+//! Embedding pooling and centroid scoring.
+//!
+//! The semantic-relevance filter pools token embeddings into sentence
+//! vectors, scores them against the stored event centroids and keeps
+//! the best matches. Done in NumPy that is several temporaries per batch
+//! and most of the worker's time. These helpers read float32 buffers in
+//! place (numpy arrays, `array('f')`, memoryviews — anything with the
+//! buffer protocol), do the arithmetic in parallel with the GIL released
+//! and return float32 numpy arrays (lists when numpy isn't installed).
+
+use pyo3::buffer::PyBuffer;
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use rayon::prelude::*;
+
+use crate::columns::{f32_column, f32_matrix};
+use crate::errors::unlocked;
+
+/// Row-major float32 matrix.
+struct Matrix {
+    data: Vec<f32>,
+    dim: usize,
+}
+
+impl Matrix {
+    fn rows(&self) -> usize {
+        self.data.len().checked_div(self.dim).unwrap_or(0)
+    }
+
+    fn row(&self, i: usize) -> &[f32] {
+        &self.data[i * self.dim..(i + 1) * self.dim]
+    }
+}
+
+/// Copy a float32 buffer into a matrix. 2-D buffers carry their shape;
+/// 1-D buffers are split into rows of `dim`, or taken as one row.
+fn matrix(obj: &Bound<'_, PyAny>, dim: Option<usize>, name: &str) -> PyResult<Matrix> {
+    let buffer = PyBuffer::<f32>::get_bound(obj).map_err(|_| {
+        PyTypeError::new_err(format!("{name} must be a float32 buffer, e.g. a numpy array with dtype=float32"))
+    })?;
+    let shape = buffer.shape().to_vec();
+    let data = buffer.to_vec(obj.py())?;
+    let dim = match (shape.as_slice(), dim) {
+        ([_, cols], Some(dim)) if *cols != dim => {
+            return Err(PyValueError::new_err(format!("{name} has {cols} columns, expected dim={dim}")));
+        }
+        ([_, cols], _) => *cols,
+        ([len], Some(dim)) if dim == 0 || len % dim != 0 => {
+            return Err(PyValueError::new_err(format!("{name} length {len} is not a multiple of dim={dim}")));
+        }
+        ([_], Some(dim)) => dim,
+        ([len], None) => *len,
+        _ => return Err(PyValueError::new_err(format!("{name} must be 1-D or 2-D, got {} dimensions", shape.len()))),
+    };
+    Ok(Matrix { data, dim })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Pooling {
+    Mean,
+    Max,
+}
+
+impl Pooling {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "mean" => Ok(Pooling::Mean),
+            "max" => Ok(Pooling::Max),
+            _ => Err(format!("unknown pooling method {name:?}: expected 'mean' or 'max'")),
+        }
+    }
+}
+
+/// Pool `rows` into one vector; zeros when there are none.
+fn pool<'a>(rows: impl ExactSizeIterator<Item = &'a [f32]>, dim: usize, method: Pooling) -> Vec<f32> {
+    let count = rows.len();
+    if count == 0 {
+        return vec![0.0; dim];
+    }
+    let init = if method == Pooling::Max { f32::NEG_INFINITY } else { 0.0 };
+    let mut out = vec![init; dim];
+    for row in rows {
+        for (acc, x) in out.iter_mut().zip(row) {
+            match method {
+                Pooling::Mean => *acc += x,
+                Pooling::Max => *acc = acc.max(*x),
+            }
+        }
+    }
+    if method == Pooling::Mean {
+        out.iter_mut().for_each(|x| *x /= count as f32);
+    }
+    out
+}
+
+/// Scale `v` to unit length; a zero vector is left as is.
+fn normalize(v: &mut [f32]) {
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        v.iter_mut().for_each(|x| *x /= norm);
+    }
+}
+
+fn normalized(m: &Matrix) -> Vec<f32> {
+    let mut data = m.data.clone();
+    data.par_chunks_mut(m.dim.max(1)).for_each(normalize);
+    data
+}
+
+/// Row-major `(a.rows, b.rows)` cosine similarities; 0 against a zero
+/// vector.
+fn cosine_matrix(a: &Matrix, b: &Matrix) -> Vec<f32> {
+    let (a_unit, b_unit) = (normalized(a), normalized(b));
+    let dim = a.dim.max(1);
+    a_unit
+        .par_chunks(dim)
+        .flat_map_iter(|row| b_unit.chunks(dim).map(move |c| row.iter().zip(c).map(|(x, y)| x * y).sum::<f32>()))
+        .collect()
+}
+
+/// Best `top_k` columns of each score row at or above `threshold`,
+/// highest first.
+fn rank(scores: &[f32], columns: usize, top_k: usize, threshold: f32) -> Vec<Vec<(usize, f32)>> {
+    scores
+        .chunks(columns.max(1))
+        .map(|row| {
+            let mut hits: Vec<(usize, f32)> = row.iter().copied().enumerate().filter(|(_, s)| *s >= threshold).collect();
+            hits.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+            hits.truncate(top_k);
+            hits
+        })
+        .collect()
+}
+
+/// Pool token embeddings into sentence (or document) vectors.
+///
+/// Parameters
+/// ----------
+/// tokens : buffer
+///     ``(n_tokens, dim)`` float32 matrix, or a flat float32 buffer with
+///     ``dim`` given.
+/// dim : int | None
+///     Embedding width for flat buffers.
+/// method : str
+///     ``"mean"`` (default) or ``"max"``.
+/// lengths : list[int] | None
+///     Token count of each consecutive segment (e.g. sentence); pools
+///     each segment separately. Must sum to ``n_tokens``. Default: pool
+///     every token into one vector.
+/// normalize : bool
+///     Scale the pooled vectors to unit length. Default False.
+///
+/// Returns
+/// -------
+/// numpy.ndarray
+///     ``(dim,)`` float32 vector, or ``(len(lengths), dim)`` with
+///     ``lengths``; an empty segment pools to zeros.
+///
+/// Raises
+/// ------
+/// TypeError
+///     If ``tokens`` is not a float32 buffer.
+/// ValueError
+///     If the shape, ``dim``, ``lengths`` or ``method`` don't fit.
+#[pyfunction]
+#[pyo3(signature = (tokens, dim=None, method="mean", lengths=None, normalize=false))]
+pub fn pool_embeddings(
+    py: Python<'_>,
+    tokens: &Bound<'_, PyAny>,
+    dim: Option<usize>,
+    method: &str,
+    lengths: Option<Vec<usize>>,
+    normalize: bool,
+) -> PyResult<PyObject> {
+    let method = Pooling::parse(method).map_err(PyValueError::new_err)?;
+    let tokens = matrix(tokens, dim, "tokens")?;
+    let rows = tokens.rows();
+    let Some(lengths) = lengths else {
+        let mut pooled = unlocked(py, "pool_embeddings", || pool((0..rows).map(|i| tokens.row(i)), tokens.dim, method))?;
+        if normalize {
+            self::normalize(&mut pooled);
+        }
+        return f32_column(py, &pooled, true);
+    };
+    if lengths.iter().sum::<usize>() != rows {
+        return Err(PyValueError::new_err(format!(
+            "lengths sum to {}, but tokens has {rows} rows",
+            lengths.iter().sum::<usize>()
+        )));
+    }
+    let starts: Vec<usize> = lengths.iter().scan(0, |start, len| Some(std::mem::replace(start, *start + len))).collect();
+    let pooled: Vec<f32> = unlocked(py, "pool_embeddings", || {
+        starts
+            .par_iter()
+            .zip(&lengths)
+            .flat_map_iter(|(start, len)| {
+                let mut v = pool((*start..start + len).map(|i| tokens.row(i)), tokens.dim, method);
+                if normalize {
+                    self::normalize(&mut v);
+                }
+                v
+            })
+            .collect()
+    })?;
+    f32_matrix(py, &pooled, tokens.dim)
+}
+
+/// Cosine similarity of every embedding to every centroid.
+///
+/// Parameters
+/// ----------
+/// embeddings : buffer
+///     ``(n, dim)`` float32 matrix (or flat with ``dim``).
+/// centroids : buffer
+///     ``(k, dim)`` float32 matrix, e.g. the stored event centroids.
+/// dim : int | None
+///     Embedding width for flat buffers.
+///
+/// Returns
+/// -------
+/// numpy.ndarray
+///     ``(n, k)`` float32 scores; 0 against a zero vector.
+///
+/// Raises
+/// ------
+/// TypeError
+///     If an input is not a float32 buffer.
+/// ValueError
+///     If the widths differ.
+#[pyfunction]
+#[pyo3(signature = (embeddings, centroids, dim=None))]
+pub fn cosine_scores(
+    py: Python<'_>,
+    embeddings: &Bound<'_, PyAny>,
+    centroids: &Bound<'_, PyAny>,
+    dim: Option<usize>,
+) -> PyResult<PyObject> {
+    let (embeddings, centroids) = pair(embeddings, centroids, dim)?;
+    let scores = unlocked(py, "cosine_scores", || cosine_matrix(&embeddings, &centroids))?;
+    f32_matrix(py, &scores, centroids.rows())
+}
+
+/// Best-matching centroids for each embedding.
+///
+/// Parameters
+/// ----------
+/// embeddings, centroids, dim
+///     As for ``cosine_scores``.
+/// top_k : int
+///     Matches kept per embedding. Default 1.
+/// threshold : float
+///     Minimum cosine similarity. Default -1 (keep everything).
+///
+/// Returns
+/// -------
+/// list[list[tuple[int, float]]]
+///     Per embedding, ``(centroid_index, score)`` pairs, best first;
+///     empty when no centroid reaches ``threshold``.
+#[pyfunction]
+#[pyo3(signature = (embeddings, centroids, dim=None, top_k=1, threshold=-1.0))]
+pub fn rank_by_centroids(
+    py: Python<'_>,
+    embeddings: &Bound<'_, PyAny>,
+    centroids: &Bound<'_, PyAny>,
+    dim: Option<usize>,
+    top_k: usize,
+    threshold: f32,
+) -> PyResult<Vec<Vec<(usize, f32)>>> {
+    let (embeddings, centroids) = pair(embeddings, centroids, dim)?;
+    unlocked(py, "rank_by_centroids", || {
+        rank(&cosine_matrix(&embeddings, &centroids), centroids.rows(), top_k, threshold)
+    })
+}
+
+fn pair(embeddings: &Bound<'_, PyAny>, centroids: &Bound<'_, PyAny>, dim: Option<usize>) -> PyResult<(Matrix, Matrix)> {
+    let embeddings = matrix(embeddings, dim, "embeddings")?;
+    let centroids = matrix(centroids, Some(dim.unwrap_or(embeddings.dim)), "centroids")?;
+    Ok((embeddings, centroids))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_and_score() {
+        let rows: [&[f32]; 2] = [&[1.0, 0.0, 2.0], &[3.0, -1.0, 0.0]];
+        assert_eq!(pool(rows.into_iter(), 3, Pooling::Mean), vec![2.0, -0.5, 1.0]);
+        assert_eq!(pool(rows.into_iter(), 3, Pooling::Max), vec![3.0, 0.0, 2.0]);
+        assert_eq!(pool([].into_iter(), 2, Pooling::Max), vec![0.0, 0.0]);
+
+        let a = Matrix { data: vec![1.0, 0.0, 0.0, 2.0, 0.0, 0.0], dim: 2 };
+        let b = Matrix { data: vec![3.0, 0.0, 1.0, 1.0], dim: 2 };
+        let scores = cosine_matrix(&a, &b);
+        assert_eq!(scores.len(), 6);
+        assert!((scores[0] - 1.0).abs() < 1e-6);
+        assert!((scores[3] - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+        assert_eq!(&scores[4..], &[0.0, 0.0]);
+        let ranked = rank(&scores, 2, 1, 0.5);
+        assert_eq!(ranked[0][0].0, 0);
+        assert_eq!(ranked[1][0].0, 1);
+        assert!(ranked[2].is_empty());
+    }
+
+    #[test]
+    fn test_buffers_from_python() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let tokens = py.eval_bound("__import__('array').array('f', [1, 2, 3, 4, 5, 6])", None, None).unwrap();
+            let pooled = pool_embeddings(py, &tokens, Some(2), "mean", Some(vec![1, 2]), false).unwrap();
+            let first: Vec<f32> = pooled.bind(py).get_item(1).unwrap().extract().unwrap();
+            assert_eq!(first, vec![4.0, 5.0]);
+            assert!(pool_embeddings(py, &tokens, Some(4), "mean", None, false).is_err());
+            assert!(pool_embeddings(py, &tokens, Some(2), "mean", Some(vec![1]), false).is_err());
+            assert!(pool_embeddings(py, &tokens, Some(2), "median", None, false).is_err());
+            let doubles = py.eval_bound("__import__('array').array('d', [1, 2])", None, None).unwrap();
+            assert!(pool_embeddings(py, &doubles, None, "mean", None, false).unwrap_err().is_instance_of::<PyTypeError>(py));
+
+            let ranked = rank_by_centroids(py, &tokens, &tokens, Some(2), 1, -1.0).unwrap();
+            assert_eq!(ranked.iter().map(|r| r[0].0).collect::<Vec<_>>(), vec![0, 1, 2]);
+        });
+    }
+}
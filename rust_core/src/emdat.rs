@@ -0,0 +1,267 @@
+//! EM-DAT hazard classification.
+//!
+//! EM-DAT codes disasters as group / subgroup / type / subtype with a
+//! classification key such as `nat-hyd-flo-fla` (natural, hydrological,
+//! flood, flash flood). Mapping the crawler's hazard labels — free text,
+//! GDACS event codes, ReliefWeb disaster types — to that scheme lets
+//! fused events be matched against EM-DAT's historical records.
+
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use regex::Regex;
+
+use crate::errors::unlocked;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct EmdatClass {
+    pub key: &'static str,
+    pub group: &'static str,
+    pub subgroup: &'static str,
+    pub kind: &'static str,
+    pub subtype: &'static str,
+}
+
+const fn class(
+    key: &'static str,
+    group: &'static str,
+    subgroup: &'static str,
+    kind: &'static str,
+    subtype: &'static str,
+) -> EmdatClass {
+    EmdatClass { key, group, subgroup, kind, subtype }
+}
+
+const N: &str = "Natural";
+const T: &str = "Technological";
+
+static CLASSES: &[EmdatClass] = &[
+    class("nat-geo-ear-gro", N, "Geophysical", "Earthquake", "Ground movement"),
+    class("nat-geo-ear-tsu", N, "Geophysical", "Earthquake", "Tsunami"),
+    class("nat-geo-vol-ash", N, "Geophysical", "Volcanic activity", "Ash fall"),
+    class("nat-geo-vol-lav", N, "Geophysical", "Volcanic activity", "Lava flow"),
+    class("nat-geo-mmd-lan", N, "Geophysical", "Mass movement (dry)", "Landslide (dry)"),
+    class("nat-met-sto-tro", N, "Meteorological", "Storm", "Tropical cyclone"),
+    class("nat-met-sto-ext", N, "Meteorological", "Storm", "Extra-tropical storm"),
+    class("nat-met-sto-tor", N, "Meteorological", "Storm", "Tornado"),
+    class("nat-met-sto-hai", N, "Meteorological", "Storm", "Hail"),
+    class("nat-met-sto-san", N, "Meteorological", "Storm", "Sand/Dust storm"),
+    class("nat-met-sto-bli", N, "Meteorological", "Storm", "Blizzard/Winter storm"),
+    class("nat-met-sto-sto", N, "Meteorological", "Storm", "Storm (General)"),
+    class("nat-met-ext-hea", N, "Meteorological", "Extreme temperature", "Heat wave"),
+    class("nat-met-ext-col", N, "Meteorological", "Extreme temperature", "Cold wave"),
+    class("nat-hyd-flo-riv", N, "Hydrological", "Flood", "Riverine flood"),
+    class("nat-hyd-flo-fla", N, "Hydrological", "Flood", "Flash flood"),
+    class("nat-hyd-flo-coa", N, "Hydrological", "Flood", "Coastal flood"),
+    class("nat-hyd-flo-flo", N, "Hydrological", "Flood", "Flood (General)"),
+    class("nat-hyd-mmw-lan", N, "Hydrological", "Mass movement (wet)", "Landslide (wet)"),
+    class("nat-hyd-mmw-mud", N, "Hydrological", "Mass movement (wet)", "Mudslide"),
+    class("nat-hyd-mmw-ava", N, "Hydrological", "Mass movement (wet)", "Avalanche (wet)"),
+    class("nat-cli-dro-dro", N, "Climatological", "Drought", "Drought"),
+    class("nat-cli-wil-for", N, "Climatological", "Wildfire", "Forest fire"),
+    class("nat-cli-wil-wil", N, "Climatological", "Wildfire", "Wildfire (General)"),
+    class("nat-cli-glo-glo", N, "Climatological", "Glacial lake outburst flood", "Glacial lake outburst flood"),
+    class("nat-bio-epi-bac", N, "Biological", "Epidemic", "Bacterial disease"),
+    class("nat-bio-epi-vir", N, "Biological", "Epidemic", "Viral disease"),
+    class("nat-bio-epi-par", N, "Biological", "Epidemic", "Parasitic disease"),
+    class("nat-bio-epi-dis", N, "Biological", "Epidemic", "Infectious disease (General)"),
+    class("nat-bio-inf-loc", N, "Biological", "Infestation", "Locust infestation"),
+    class("nat-bio-inf-inf", N, "Biological", "Infestation", "Infestation (General)"),
+    class("tec-ind-che-che", T, "Industrial accident", "Chemical spill", "Chemical spill"),
+    class("tec-ind-exp-exp", T, "Industrial accident", "Explosion (Industrial)", "Explosion (Industrial)"),
+    class("tec-ind-rad-rad", T, "Industrial accident", "Radiation", "Radiation"),
+    class("tec-ind-oil-oil", T, "Industrial accident", "Oil spill", "Oil spill"),
+    class("tec-tra-air-air", T, "Transport", "Air", "Air"),
+    class("tec-tra-roa-roa", T, "Transport", "Road", "Road"),
+    class("tec-tra-rai-rai", T, "Transport", "Rail", "Rail"),
+    class("tec-tra-wat-wat", T, "Transport", "Water", "Water"),
+    class("tec-mis-col-col", T, "Miscellaneous accident", "Collapse (Miscellaneous)", "Collapse (Miscellaneous)"),
+    class("tec-mis-exp-exp", T, "Miscellaneous accident", "Explosion (Miscellaneous)", "Explosion (Miscellaneous)"),
+    class("tec-mis-fir-fir", T, "Miscellaneous accident", "Fire (Miscellaneous)", "Fire (Miscellaneous)"),
+];
+
+// Hazard labels → classification key, matched on the lowercased label
+// with separators collapsed to spaces. Covers free-text hazards, GDACS
+// event codes and ReliefWeb disaster types.
+static HAZARD_KEYS: &[(&str, &str)] = &[
+    ("earthquake", "nat-geo-ear-gro"), ("quake", "nat-geo-ear-gro"), ("eq", "nat-geo-ear-gro"),
+    ("tsunami", "nat-geo-ear-tsu"), ("ts", "nat-geo-ear-tsu"),
+    ("volcano", "nat-geo-vol-ash"), ("volcanic eruption", "nat-geo-vol-ash"), ("eruption", "nat-geo-vol-ash"),
+    ("vo", "nat-geo-vol-ash"),
+    ("tropical cyclone", "nat-met-sto-tro"), ("cyclone", "nat-met-sto-tro"), ("hurricane", "nat-met-sto-tro"),
+    ("typhoon", "nat-met-sto-tro"), ("tropical storm", "nat-met-sto-tro"), ("tropical depression", "nat-met-sto-tro"),
+    ("tc", "nat-met-sto-tro"),
+    ("extratropical cyclone", "nat-met-sto-ext"), ("tornado", "nat-met-sto-tor"), ("hail", "nat-met-sto-hai"),
+    ("hailstorm", "nat-met-sto-hai"), ("sandstorm", "nat-met-sto-san"), ("dust storm", "nat-met-sto-san"),
+    ("blizzard", "nat-met-sto-bli"), ("winter storm", "nat-met-sto-bli"), ("storm", "nat-met-sto-sto"),
+    ("severe local storm", "nat-met-sto-sto"),
+    ("heat wave", "nat-met-ext-hea"), ("heatwave", "nat-met-ext-hea"), ("cold wave", "nat-met-ext-col"),
+    ("flood", "nat-hyd-flo-flo"), ("floods", "nat-hyd-flo-flo"), ("flooding", "nat-hyd-flo-flo"),
+    ("fl", "nat-hyd-flo-flo"), ("flash flood", "nat-hyd-flo-fla"), ("riverine flood", "nat-hyd-flo-riv"),
+    ("coastal flood", "nat-hyd-flo-coa"), ("storm surge", "nat-hyd-flo-coa"),
+    ("landslide", "nat-hyd-mmw-lan"), ("land slide", "nat-hyd-mmw-lan"), ("mudslide", "nat-hyd-mmw-mud"),
+    ("mud slide", "nat-hyd-mmw-mud"), ("avalanche", "nat-hyd-mmw-ava"), ("snow avalanche", "nat-hyd-mmw-ava"),
+    ("drought", "nat-cli-dro-dro"), ("dr", "nat-cli-dro-dro"),
+    ("wildfire", "nat-cli-wil-wil"), ("wild fire", "nat-cli-wil-wil"), ("bushfire", "nat-cli-wil-wil"),
+    ("wf", "nat-cli-wil-wil"), ("forest fire", "nat-cli-wil-for"),
+    ("glof", "nat-cli-glo-glo"), ("glacial lake outburst flood", "nat-cli-glo-glo"),
+    ("epidemic", "nat-bio-epi-dis"), ("outbreak", "nat-bio-epi-dis"), ("cholera", "nat-bio-epi-bac"),
+    ("insect infestation", "nat-bio-inf-inf"), ("infestation", "nat-bio-inf-inf"), ("locust", "nat-bio-inf-loc"),
+    ("locusts", "nat-bio-inf-loc"),
+    ("chemical spill", "tec-ind-che-che"), ("industrial explosion", "tec-ind-exp-exp"),
+    ("oil spill", "tec-ind-oil-oil"), ("radiation", "tec-ind-rad-rad"), ("plane crash", "tec-tra-air-air"),
+    ("road accident", "tec-tra-roa-roa"), ("train crash", "tec-tra-rai-rai"), ("shipwreck", "tec-tra-wat-wat"),
+    ("boat capsized", "tec-tra-wat-wat"), ("building collapse", "tec-mis-col-col"), ("collapse", "tec-mis-col-col"),
+    ("explosion", "tec-mis-exp-exp"), ("fire", "tec-mis-fir-fir"),
+];
+
+// Article text that narrows a general type to a subtype; tried in order
+// against classifications of the listed type prefix.
+static REFINEMENTS: Lazy<Vec<(&str, Regex, &str)>> = Lazy::new(|| {
+    [
+        ("nat-hyd-flo", r"(?i)\bflash[- ]floods?\b|\bcrues? soudaines?\b|\benxurradas?\b", "nat-hyd-flo-fla"),
+        ("nat-hyd-flo", r"(?i)\bstorm surge\b|\bcoastal flood|\btidal flood|\bsubmersion marine\b", "nat-hyd-flo-coa"),
+        ("nat-hyd-flo", r"(?i)\briver(?:ine)? flood|\b(?:river|rivers) (?:burst|overflow)|\bbanks? of the\b|\bcheias?\b", "nat-hyd-flo-riv"),
+        ("nat-bio-epi", r"(?i)\b(?:cholera|typhoid|meningitis|anthrax|plague|diphtheria|shigell)", "nat-bio-epi-bac"),
+        ("nat-bio-epi", r"(?i)\b(?:ebola|marburg|measles|dengue|mpox|monkeypox|covid|lassa|yellow fever|polio|chikungunya|zika|influenza)", "nat-bio-epi-vir"),
+        ("nat-bio-epi", r"(?i)\b(?:malaria|leishmaniasis|trypanosomiasis)\b", "nat-bio-epi-par"),
+        ("nat-cli-wil", r"(?i)\bforest fires?\b|\bincendies? de forêt\b|\bincêndios? florest", "nat-cli-wil-for"),
+        ("nat-met-sto", r"(?i)\btornado", "nat-met-sto-tor"),
+        ("nat-met-sto", r"(?i)\b(?:tropical cyclone|hurricane|typhoon)\b", "nat-met-sto-tro"),
+    ]
+    .into_iter()
+    .map(|(prefix, pattern, key)| (prefix, Regex::new(pattern).unwrap(), key))
+    .collect()
+});
+
+/// Table sizes, for `runtime_info`.
+pub(crate) fn inventory() -> Vec<(&'static str, usize)> {
+    vec![
+        ("emdat_classes", CLASSES.len()),
+        ("emdat_hazard_keys", HAZARD_KEYS.len()),
+        ("emdat_refinements", REFINEMENTS.len()),
+    ]
+}
+
+pub(crate) fn by_key(key: &str) -> Option<&'static EmdatClass> {
+    CLASSES.iter().find(|c| c.key == key)
+}
+
+fn normalize(label: &str) -> String {
+    label
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// EM-DAT class of a hazard label, narrowed by `text` when the label only
+/// names the type ("flood" in an article about flash floods).
+pub(crate) fn code(hazard: &str, text: Option<&str>) -> Option<&'static EmdatClass> {
+    let label = normalize(hazard);
+    let key = HAZARD_KEYS
+        .iter()
+        .find(|(name, _)| *name == label)
+        .or_else(|| {
+            // "severe flooding", "cholera outbreak": the longest known
+            // label among the words
+            HAZARD_KEYS
+                .iter()
+                .filter(|(name, _)| format!(" {label} ").contains(&format!(" {name} ")))
+                .max_by_key(|(name, _)| name.len())
+        })
+        .map(|(_, key)| *key)
+        .or_else(|| by_key(&label.replace(' ', "-")).map(|c| c.key))?;
+    // Only general classes ("-flo", "-dis", "-wil", "-sto") are refined,
+    // by the label's own words first ("cholera outbreak"), then the text
+    let general = key.ends_with("-flo") || key.ends_with("-dis") || key.ends_with("-wil") || key.ends_with("-sto");
+    let refined = [Some(hazard), text].into_iter().flatten().find_map(|text| {
+        REFINEMENTS
+            .iter()
+            .filter(|(prefix, _, _)| general && key.starts_with(prefix))
+            .find(|(_, pattern, _)| pattern.is_match(text))
+            .map(|(_, _, key)| *key)
+    });
+    by_key(refined.unwrap_or(key))
+}
+
+pub(crate) fn class_dict<'py>(py: Python<'py>, class: &EmdatClass) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new_bound(py);
+    dict.set_item("key", class.key)?;
+    dict.set_item("group", class.group)?;
+    dict.set_item("subgroup", class.subgroup)?;
+    dict.set_item("type", class.kind)?;
+    dict.set_item("subtype", class.subtype)?;
+    Ok(dict)
+}
+
+/// Map a hazard label to the EM-DAT classification.
+///
+/// Parameters
+/// ----------
+/// hazard : str
+///     Hazard label: free text (``"flood"``, ``"cholera outbreak"``,
+///     ``"Tropical Cyclone"``), a GDACS event code (``"EQ"``, ``"TC"``,
+///     ``"FL"``, ``"VO"``, ``"DR"``, ``"WF"``, ``"TS"``) or an EM-DAT
+///     classification key.
+/// text : str | None
+///     Article or event text used to narrow a general type to a subtype,
+///     e.g. flood → flash / coastal / riverine flood, epidemic →
+///     bacterial / viral / parasitic disease.
+///
+/// Returns
+/// -------
+/// dict | None
+///     ``key`` (e.g. ``"nat-hyd-flo-fla"``), ``group``, ``subgroup``,
+///     ``type`` and ``subtype`` as named by EM-DAT; None for unknown
+///     hazards.
+#[pyfunction]
+#[pyo3(signature = (hazard, text=None))]
+pub fn emdat_code(py: Python<'_>, hazard: &str, text: Option<&str>) -> PyResult<Option<Py<PyDict>>> {
+    unlocked(py, "emdat_code", || code(hazard, text))?.map(|c| Ok(class_dict(py, c)?.unbind())).transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(hazard: &str, text: Option<&str>) -> Option<&'static str> {
+        code(hazard, text).map(|c| c.key)
+    }
+
+    #[test]
+    fn test_labels_and_codes() {
+        assert_eq!(key("Tropical Cyclone", None), Some("nat-met-sto-tro"));
+        assert_eq!(key("TC", None), Some("nat-met-sto-tro"));
+        assert_eq!(key("EQ", None), Some("nat-geo-ear-gro"));
+        assert_eq!(key("severe flooding", None), Some("nat-hyd-flo-flo"));
+        assert_eq!(key("cholera outbreak", None), Some("nat-bio-epi-bac"));
+        assert_eq!(key("Flash_Flood", None), Some("nat-hyd-flo-fla"));
+        assert_eq!(key("nat-cli-dro-dro", None), Some("nat-cli-dro-dro"));
+        assert_eq!(key("sunshine", None), None);
+        let class = code("drought", None).unwrap();
+        assert_eq!((class.group, class.subgroup, class.kind), ("Natural", "Climatological", "Drought"));
+    }
+
+    #[test]
+    fn test_text_refines_subtype() {
+        assert_eq!(key("flood", Some("Flash floods swept through the valley overnight.")), Some("nat-hyd-flo-fla"));
+        assert_eq!(key("flood", Some("A storm surge inundated coastal Beira.")), Some("nat-hyd-flo-coa"));
+        assert_eq!(key("epidemic", Some("Measles cases are rising in camps.")), Some("nat-bio-epi-vir"));
+        assert_eq!(key("outbreak", Some("Malaria admissions doubled.")), Some("nat-bio-epi-par"));
+        // Specific labels aren't overridden by the text
+        assert_eq!(key("cholera", Some("Measles too.")), Some("nat-bio-epi-bac"));
+        assert_eq!(key("flood", Some("Heavy rain for days.")), Some("nat-hyd-flo-flo"));
+    }
+
+    #[test]
+    fn test_table_consistent() {
+        for (label, key) in HAZARD_KEYS {
+            assert!(by_key(key).is_some(), "{label} -> {key}");
+        }
+        for (prefix, _, key) in REFINEMENTS.iter() {
+            assert!(key.starts_with(prefix) && by_key(key).is_some(), "{key}");
+        }
+    }
+}
@@ -0,0 +1,351 @@
+//! Python exception types raised by the extension.
+//!
+//! Every error the extension raises for bad input derives from
+//! `MoltisError` and carries machine-readable attributes — ``reason`` (a
+//! stable snake_case code such as ``"malformed"`` or ``"wrong_format"``)
+//! plus the class's own context (``path``, ``line``, ``format``) — so the
+//! pipeline can choose per error whether to retry, skip or abort. Each
+//! subclass also derives from the builtin it replaces (`ValueError`,
+//! `RuntimeError`), so existing handlers keep matching.
+//!
+//! Entry points run their work through `unlocked` or `guarded`, so a Rust
+//! panic (a bug: an unexpected index, a broken regex assumption) reaches
+//! Python as a `RustPanicError` — an ordinary `RuntimeError` the worker
+//! can log and skip — instead of pyo3's `PanicException`, which derives
+//! from `BaseException` and escapes `except Exception` handlers.
+
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::sync::GILOnceCell;
+use pyo3::type_object::PyTypeInfo;
+use pyo3::types::{PyDict, PyTuple, PyType};
+use std::any::Any;
+use std::fmt;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::profiling;
+use crate::url_canonical::UrlErrorKind;
+
+create_exception!(
+    moltis_rust_core,
+    MoltisError,
+    PyException,
+    "Base class of the extension's errors; ``reason`` is a stable snake_case code."
+);
+
+/// The `type(name, (MoltisError, builtin), ...)` behind a
+/// `moltis_exception!` class.
+fn subclass(py: Python<'_>, name: &str, doc: &str, builtin: Bound<'_, PyType>) -> Py<PyType> {
+    let bases = PyTuple::new_bound(py, [py.get_type_bound::<MoltisError>(), builtin]);
+    let namespace = PyDict::new_bound(py);
+    namespace
+        .set_item("__doc__", doc)
+        .and_then(|_| namespace.set_item("__module__", "moltis_rust_core"))
+        .and_then(|_| py.get_type_bound::<PyType>().call1((name, bases, namespace)))
+        .and_then(|ty| Ok(ty.downcast_into::<PyType>()?.unbind()))
+        .expect("failed to create exception type")
+}
+
+/// `create_exception!` with two bases: `MoltisError` and `$builtin`.
+macro_rules! moltis_exception {
+    ($name:ident, $builtin:ty, $doc:expr) => {
+        #[repr(transparent)]
+        #[doc = $doc]
+        pub struct $name(PyAny);
+
+        pyo3::impl_exception_boilerplate!($name);
+        pyo3::pyobject_native_type_core!($name, $name::type_object_raw, #module = Some("moltis_rust_core"));
+
+        impl $name {
+            fn type_object_raw(py: Python<'_>) -> *mut pyo3::ffi::PyTypeObject {
+                static TYPE_OBJECT: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+                TYPE_OBJECT
+                    .get_or_init(py, || subclass(py, stringify!($name), $doc, py.get_type_bound::<$builtin>()))
+                    .as_ptr() as *mut pyo3::ffi::PyTypeObject
+            }
+        }
+    };
+}
+
+moltis_exception!(
+    UrlParseError,
+    PyValueError,
+    "A URL could not be parsed in strict mode; ``reason`` (also ``args[1]``) is the failure category, ``url`` the input."
+);
+
+moltis_exception!(
+    ConfigError,
+    PyValueError,
+    "A configuration file is invalid; ``path`` names the file, ``line`` the position where known."
+);
+
+moltis_exception!(
+    GazetteerError,
+    PyValueError,
+    "A gazetteer or boundary file is invalid; ``path`` names the file, ``line`` the position where known."
+);
+
+moltis_exception!(
+    ExtractionError,
+    PyValueError,
+    "A payload could not be parsed; ``format`` names the parser (``\"cap\"``, ``\"sitemap\"``, ...)."
+);
+
+moltis_exception!(
+    RustPanicError,
+    PyRuntimeError,
+    "Native code panicked; ``entry`` (also ``args[1]``) is the entry point, ``args[2]`` the panic message."
+);
+
+/// A load or parse failure with a machine-readable reason.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Failure {
+    /// Stable snake_case code: ``"malformed"``, ``"wrong_format"``,
+    /// ``"missing_field"``, ``"too_large"``, ...
+    pub reason: &'static str,
+    /// Human-readable; already names the line where it matters.
+    pub message: String,
+    /// 1-based line in the input, where known.
+    pub line: Option<usize>,
+}
+
+impl Failure {
+    pub(crate) fn new(reason: &'static str, message: impl Into<String>) -> Self {
+        Self { reason, message: message.into(), line: None }
+    }
+
+    pub(crate) fn at_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Unclassified parser errors (XML, JSON, CSV syntax) are malformed input.
+impl From<String> for Failure {
+    fn from(message: String) -> Self {
+        Self::new("malformed", message)
+    }
+}
+
+impl From<&str> for Failure {
+    fn from(message: &str) -> Self {
+        Self::new("malformed", message)
+    }
+}
+
+/// Build a `T` from `args` with `fields` set as attributes.
+fn structured<T: PyTypeInfo>(args: impl IntoPy<Py<PyTuple>>, fields: &[(&str, &dyn ToPyObject)]) -> PyErr {
+    Python::with_gil(|py| {
+        let build = || -> PyResult<PyErr> {
+            let value = T::type_object_bound(py).call1(args)?;
+            for (name, field) in fields {
+                value.setattr(*name, field.to_object(py))?;
+            }
+            Ok(PyErr::from_value_bound(value))
+        };
+        build().unwrap_or_else(|e| e)
+    })
+}
+
+/// Build a `UrlParseError` carrying `(message, category)` as its args.
+pub(crate) fn url_parse_error(url: &str, kind: UrlErrorKind) -> PyErr {
+    let category = kind.as_str();
+    structured::<UrlParseError>((format!("{category}: {url:?}"), category), &[("reason", &category), ("url", &url)])
+}
+
+fn located(path: Option<&str>, failure: &Failure) -> String {
+    match path {
+        Some(path) => format!("{path}: {failure}"),
+        None => failure.to_string(),
+    }
+}
+
+/// Build a `ConfigError` for the file at `path`.
+pub(crate) fn config_error(path: Option<&str>, failure: Failure) -> PyErr {
+    let message = located(path, &failure);
+    structured::<ConfigError>((message,), &[("reason", &failure.reason), ("path", &path), ("line", &failure.line)])
+}
+
+/// Build a `GazetteerError` for the file at `path`.
+pub(crate) fn gazetteer_error(path: Option<&str>, failure: Failure) -> PyErr {
+    let message = located(path, &failure);
+    structured::<GazetteerError>((message,), &[("reason", &failure.reason), ("path", &path), ("line", &failure.line)])
+}
+
+/// Build an `ExtractionError` from the `format` parser.
+pub(crate) fn extraction_error(format: &str, failure: Failure) -> PyErr {
+    structured::<ExtractionError>(
+        (failure.to_string(),),
+        &[("reason", &failure.reason), ("format", &format), ("line", &failure.line)],
+    )
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+/// Build a `RustPanicError` carrying `(message, entry, panic message)`.
+pub(crate) fn panic_error(entry: &str, payload: Box<dyn Any + Send>) -> PyErr {
+    let message = panic_message(&*payload);
+    tracing::error!(entry, message, "native panic converted to RustPanicError");
+    structured::<RustPanicError>(
+        (format!("panic in {entry}: {message}"), entry.to_string(), message),
+        &[("reason", &"panic"), ("entry", &entry)],
+    )
+}
+
+/// Run `f` with the GIL released; a panic becomes `RustPanicError`.
+pub(crate) fn unlocked<T, F>(py: Python<'_>, entry: &str, f: F) -> PyResult<T>
+where
+    F: FnOnce() -> T + Send,
+    T: Send,
+{
+    py.allow_threads(|| catch_unwind(AssertUnwindSafe(|| profiling::call(entry, f))))
+        .map_err(|payload| panic_error(entry, payload))
+}
+
+/// Run `f` with the GIL held; a panic becomes `RustPanicError`.
+pub(crate) fn guarded<T>(entry: &str, f: impl FnOnce() -> PyResult<T>) -> PyResult<T> {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| Err(panic_error(entry, payload)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_panics_become_exceptions() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let err = unlocked(py, "probe", || -> usize { Vec::<usize>::new()[1] }).unwrap_err();
+            assert!(err.is_instance_of::<RustPanicError>(py));
+            assert!(err.is_instance_of::<PyRuntimeError>(py));
+            let args = err.value_bound(py).getattr("args").unwrap();
+            assert_eq!(args.get_item(1).unwrap().extract::<String>().unwrap(), "probe");
+            let message: String = args.get_item(2).unwrap().extract().unwrap();
+            assert!(message.contains("index out of bounds"), "{message}");
+
+            let err = guarded("probe", || -> PyResult<()> { panic!("bad {}", 42) }).unwrap_err();
+            assert!(err.to_string().contains("panic in probe: bad 42"));
+            assert_eq!(unlocked(py, "probe", || 7).unwrap(), 7);
+        });
+    }
+
+    #[test]
+    fn test_structured_errors() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let err = gazetteer_error(Some("moz.txt"), Failure::new("wrong_format", "line 3: too few columns").at_line(3));
+            assert!(err.is_instance_of::<GazetteerError>(py));
+            assert!(err.is_instance_of::<MoltisError>(py));
+            assert!(err.is_instance_of::<PyValueError>(py));
+            assert_eq!(err.to_string(), "GazetteerError: moz.txt: line 3: too few columns");
+            let value = err.value_bound(py);
+            assert_eq!(value.getattr("reason").unwrap().extract::<String>().unwrap(), "wrong_format");
+            assert_eq!(value.getattr("line").unwrap().extract::<usize>().unwrap(), 3);
+
+            let err = extraction_error("cap", Failure::from("malformed XML"));
+            assert!(!err.is_instance_of::<ConfigError>(py));
+            let value = err.value_bound(py);
+            assert_eq!(value.getattr("format").unwrap().extract::<String>().unwrap(), "cap");
+            assert!(value.getattr("line").unwrap().is_none());
+
+            let err = unlocked(py, "probe", || -> usize { panic!("boom") }).unwrap_err();
+            assert!(err.is_instance_of::<MoltisError>(py));
+            assert_eq!(err.value_bound(py).getattr("entry").unwrap().extract::<String>().unwrap(), "probe");
+        });
+    }
+
+    /// The `{ ... }` block opening at or after `from`.
+    fn block(src: &str, from: usize) -> &str {
+        let start = from + src[from..].find('{').unwrap();
+        let mut depth = 0;
+        for (i, c) in src[start..].char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return &src[start..=start + i];
+                    }
+                }
+                _ => {}
+            }
+        }
+        panic!("unbalanced block");
+    }
+
+    /// Exported name -> each Rust fn behind it, and whether its body runs
+    /// through `unlocked` or `guarded`. Read from the `#[pyfunction]` and
+    /// `#[pymethods]` definitions in the sources.
+    fn wrapped_definitions() -> HashMap<String, Vec<(String, bool)>> {
+        let function = Regex::new(r#"#\[pyfunction(?:\(name = "(\w+)"\))?\]\n(?:#\[pyo3\((?:name = "(\w+)")?[^\n]*\n)?pub fn (\w+)"#).unwrap();
+        let methods = Regex::new(r"#\[pymethods\]\nimpl (\w+) ").unwrap();
+        let method = Regex::new(r"\n    (?:pub )?fn (\w+)").unwrap();
+        let wrapped = |body: &str| body.contains("unlocked(") || body.contains("guarded(");
+        let mut found: HashMap<String, Vec<(String, bool)>> = HashMap::new();
+        for entry in std::fs::read_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/src")).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|e| e != "rs") {
+                continue;
+            }
+            // Char literals would throw off the brace count
+            let src = std::fs::read_to_string(path).unwrap().replace("'{'", "").replace("'}'", "");
+            for c in function.captures_iter(&src) {
+                let rust_name = c[3].to_string();
+                let name = c.get(1).or(c.get(2)).map_or(rust_name.clone(), |n| n.as_str().to_string());
+                let body = block(&src, c.get(0).unwrap().end());
+                found.entry(name).or_default().push((rust_name, wrapped(body)));
+            }
+            for c in methods.captures_iter(&src) {
+                let class = found.entry(c[1].to_string()).or_default();
+                let imp = block(&src, c.get(0).unwrap().end());
+                for m in method.captures_iter(imp) {
+                    class.push((m[1].to_string(), wrapped(block(imp, m.get(0).unwrap().end()))));
+                }
+            }
+        }
+        found
+    }
+
+    #[test]
+    fn test_every_export_is_wrapped() {
+        let definitions = wrapped_definitions();
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new_bound(py, "moltis_rust_core").unwrap();
+            crate::moltis_rust_core(&module).unwrap();
+            let mut checked = 0;
+            for (name, value) in module.dict().iter() {
+                let name: String = name.extract().unwrap();
+                let is_exception = value
+                    .downcast::<PyType>()
+                    .is_ok_and(|t| t.is_subclass_of::<pyo3::exceptions::PyBaseException>().unwrap());
+                if name.starts_with('_') || is_exception || !value.is_callable() {
+                    continue;
+                }
+                let defs = definitions.get(&name).unwrap_or_else(|| panic!("no definition found for {name}"));
+                for (def, wrapped) in defs {
+                    assert!(wrapped, "{name}: {def} is not run through unlocked or guarded");
+                }
+                checked += 1;
+            }
+            assert!(checked > 100, "only {checked} exports checked");
+        });
+    }
+}
@@ -0,0 +1,480 @@
+//! Cross-article event fusion.
+//!
+//! Dozens of articles describe each disaster. Fusing them into event
+//! candidates — one per hazard, country and time window, or per named
+//! storm — gives the reporting layer one record per event with the
+//! highest figures reported, the worst severity seen and every source.
+//! Candidates are indexed by storm name and by `(hazard, country)`, so
+//! adding an article only compares it with the events it could join.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use url::Url;
+
+use crate::date_parse::timestamp_from_py;
+use crate::emdat::{self, class_dict};
+use crate::errors::guarded;
+use crate::public_suffix::registrable_domain_of_host;
+
+const DAY_SECS: f64 = 86_400.0;
+
+/// The fields of an enriched article record that fusion uses.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct FusionRecord {
+    pub url: String,
+    pub hazard: Option<String>,
+    pub country: Option<String>,
+    pub admin_area: Option<String>,
+    /// Unix seconds.
+    pub published: Option<i64>,
+    pub storms: Vec<String>,
+    pub figures: HashMap<String, i64>,
+    pub severity: Option<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FusedEvent {
+    pub id: usize,
+    pub hazard: Option<String>,
+    pub country: Option<String>,
+    /// Admin area → articles naming it.
+    pub admin_areas: BTreeMap<String, usize>,
+    pub storms: BTreeSet<String>,
+    /// Earliest and latest publication time, Unix seconds.
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    /// Highest value reported per figure key, and how many articles
+    /// reported it.
+    pub figures: BTreeMap<String, (i64, usize)>,
+    pub severity: Option<i32>,
+    pub articles: Vec<String>,
+    pub sources: BTreeSet<String>,
+}
+
+impl FusedEvent {
+    fn new(id: usize, record: &FusionRecord) -> Self {
+        Self {
+            id,
+            hazard: record.hazard.clone(),
+            country: record.country.clone(),
+            admin_areas: BTreeMap::new(),
+            storms: BTreeSet::new(),
+            start: None,
+            end: None,
+            figures: BTreeMap::new(),
+            severity: None,
+            articles: Vec::new(),
+            sources: BTreeSet::new(),
+        }
+    }
+
+    /// Seconds from the event's time span to `time`; 0 inside it.
+    fn distance(&self, time: i64) -> i64 {
+        match (self.start, self.end) {
+            (Some(start), Some(end)) => (start - time).max(time - end).max(0),
+            _ => 0,
+        }
+    }
+
+    /// The admin area named by the most articles.
+    pub(crate) fn admin_area(&self) -> Option<&str> {
+        self.admin_areas
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+            .map(|(area, _)| area.as_str())
+    }
+
+    fn merge(&mut self, record: &FusionRecord) {
+        if !self.articles.contains(&record.url) {
+            self.articles.push(record.url.clone());
+        }
+        if let Some(domain) = Url::parse(&record.url)
+            .ok()
+            .and_then(|u| u.host_str().and_then(registrable_domain_of_host))
+        {
+            self.sources.insert(domain);
+        }
+        self.hazard = self.hazard.take().or_else(|| record.hazard.clone());
+        self.country = self.country.take().or_else(|| record.country.clone());
+        if let Some(area) = &record.admin_area {
+            *self.admin_areas.entry(area.clone()).or_default() += 1;
+        }
+        self.storms.extend(record.storms.iter().cloned());
+        if let Some(time) = record.published {
+            self.start = Some(self.start.map_or(time, |s| s.min(time)));
+            self.end = Some(self.end.map_or(time, |e| e.max(time)));
+        }
+        for (key, value) in &record.figures {
+            let (best, reports) = self.figures.entry(key.clone()).or_insert((*value, 0));
+            *best = (*best).max(*value);
+            *reports += 1;
+        }
+        self.severity = self.severity.max(record.severity);
+    }
+}
+
+fn storm_key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+fn key_part(value: &Option<String>) -> String {
+    value.as_deref().map(|v| v.trim().to_lowercase()).unwrap_or_default()
+}
+
+/// Event candidates built up from article records.
+#[pyclass(module = "moltis_rust_core")]
+pub struct EventFuser {
+    window_secs: i64,
+    events: Vec<FusedEvent>,
+    /// Storm name → events.
+    by_storm: HashMap<String, Vec<usize>>,
+    /// `(hazard, country)` → events.
+    by_key: HashMap<(String, String), Vec<usize>>,
+}
+
+impl EventFuser {
+    pub(crate) fn new(window_days: f64) -> Self {
+        Self {
+            window_secs: (window_days * DAY_SECS) as i64,
+            events: Vec::new(),
+            by_storm: HashMap::new(),
+            by_key: HashMap::new(),
+        }
+    }
+
+    fn within_window(&self, event: &FusedEvent, record: &FusionRecord) -> bool {
+        record.published.is_none_or(|t| event.distance(t) <= self.window_secs)
+    }
+
+    /// The event a record belongs to: a named storm it shares with an
+    /// event, else the nearest event with the same hazard and country
+    /// whose admin area doesn't conflict.
+    fn find(&self, record: &FusionRecord) -> Option<usize> {
+        let nearest = |ids: &mut dyn Iterator<Item = usize>| {
+            ids.filter(|id| self.within_window(&self.events[*id], record))
+                .min_by_key(|id| record.published.map_or(0, |t| self.events[*id].distance(t)))
+        };
+        let mut by_storm = record
+            .storms
+            .iter()
+            .flat_map(|s| self.by_storm.get(&storm_key(s)).into_iter().flatten().copied());
+        if let Some(id) = nearest(&mut by_storm) {
+            return Some(id);
+        }
+        // Without a hazard there's nothing to key a non-storm event on
+        record.hazard.as_ref()?;
+        let key = (key_part(&record.hazard), key_part(&record.country));
+        let candidates = self.by_key.get(&key)?;
+        let compatible = |id: &usize| {
+            let event = &self.events[*id];
+            match &record.admin_area {
+                Some(area) => event.admin_areas.is_empty() || event.admin_areas.contains_key(area),
+                None => true,
+            }
+        };
+        // Prefer an event already naming the same admin area
+        let same_area = |id: &usize| {
+            record.admin_area.as_ref().is_some_and(|a| self.events[*id].admin_areas.contains_key(a))
+        };
+        nearest(&mut candidates.iter().copied().filter(same_area))
+            .or_else(|| nearest(&mut candidates.iter().copied().filter(compatible)))
+    }
+
+    /// Fuse a record into its event, creating one if none matches;
+    /// returns the event id.
+    pub(crate) fn fuse(&mut self, record: &FusionRecord) -> usize {
+        let id = match self.find(record) {
+            Some(id) => id,
+            None => {
+                let id = self.events.len();
+                self.events.push(FusedEvent::new(id, record));
+                if record.hazard.is_some() {
+                    let key = (key_part(&record.hazard), key_part(&record.country));
+                    self.by_key.entry(key).or_default().push(id);
+                }
+                id
+            }
+        };
+        for storm in &record.storms {
+            let ids = self.by_storm.entry(storm_key(storm)).or_default();
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+        self.events[id].merge(record);
+        id
+    }
+}
+
+fn iso(time: Option<i64>) -> Option<String> {
+    time.and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+        .map(|t| t.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+}
+
+fn event_dict<'py>(py: Python<'py>, event: &FusedEvent) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new_bound(py);
+    dict.set_item("event_id", event.id)?;
+    dict.set_item("hazard", &event.hazard)?;
+    let emdat = event.hazard.as_deref().and_then(|h| emdat::code(h, None));
+    dict.set_item("emdat", emdat.map(|c| class_dict(py, c)).transpose()?)?;
+    dict.set_item("country", &event.country)?;
+    dict.set_item("admin_area", event.admin_area())?;
+    dict.set_item("admin_areas", event.admin_areas.keys().collect::<Vec<_>>())?;
+    dict.set_item("storms", event.storms.iter().collect::<Vec<_>>())?;
+    dict.set_item("start", iso(event.start))?;
+    dict.set_item("end", iso(event.end))?;
+    let figures = PyDict::new_bound(py);
+    let reports = PyDict::new_bound(py);
+    for (key, (value, count)) in &event.figures {
+        figures.set_item(key, value)?;
+        reports.set_item(key, count)?;
+    }
+    dict.set_item("figures", figures)?;
+    dict.set_item("figure_reports", reports)?;
+    dict.set_item("severity", event.severity)?;
+    dict.set_item("articles", &event.articles)?;
+    dict.set_item("article_count", event.articles.len())?;
+    dict.set_item("sources", event.sources.iter().collect::<Vec<_>>())?;
+    Ok(dict)
+}
+
+/// Optional dict item, treating None as missing.
+fn item<'py, T: FromPyObject<'py>>(record: &Bound<'py, PyDict>, key: &str) -> PyResult<Option<T>> {
+    match record.get_item(key)? {
+        Some(value) if !value.is_none() => value
+            .extract()
+            .map(Some)
+            .map_err(|e| PyValueError::new_err(format!("record field {key:?}: {e}"))),
+        _ => Ok(None),
+    }
+}
+
+fn record_from_dict(record: &Bound<'_, PyDict>) -> PyResult<FusionRecord> {
+    let url = item::<String>(record, "canonical_url")?
+        .or(item::<String>(record, "url")?)
+        .ok_or_else(|| PyValueError::new_err("record has no canonical_url or url"))?;
+    // `("Sofala", 1)` as from process_article, or a plain name
+    let admin_area = match record.get_item("admin_area")? {
+        Some(v) if !v.is_none() => Some(v.extract::<(String, i32)>().map(|(name, _)| name).or_else(|_| v.extract())?),
+        _ => None,
+    };
+    let published = match record.get_item("published")? {
+        Some(v) if !v.is_none() => Some(timestamp_from_py(&v)?),
+        _ => None,
+    };
+    // `[("Freddy", "cyclone")]` as from detect_storm_names, or names
+    let storms = match record.get_item("storms")? {
+        Some(v) if !v.is_none() => v
+            .downcast::<PyList>()?
+            .iter()
+            .map(|s| s.extract::<(String, String)>().map(|(name, _)| name).or_else(|_| s.extract()))
+            .collect::<PyResult<_>>()?,
+        _ => Vec::new(),
+    };
+    Ok(FusionRecord {
+        url,
+        hazard: item(record, "hazard")?,
+        country: item(record, "country")?,
+        admin_area,
+        published,
+        storms,
+        figures: item(record, "figures")?.unwrap_or_default(),
+        severity: item(record, "severity")?,
+    })
+}
+
+#[pymethods]
+impl EventFuser {
+    /// Fuse enriched article records into event candidates.
+    ///
+    /// Parameters
+    /// ----------
+    /// window_days : float
+    ///     Articles published further than this from an event's time span
+    ///     start a new event. Default 14.
+    #[new]
+    #[pyo3(signature = (window_days=14.0))]
+    fn py_new(window_days: f64) -> PyResult<Self> {
+        guarded("EventFuser.__new__", || Ok(Self::new(window_days)))
+    }
+
+    /// Fuse one article record into its event.
+    ///
+    /// Parameters
+    /// ----------
+    /// record : dict
+    ///     Enriched article: ``canonical_url`` (or ``url``) is required;
+    ///     ``hazard``, ``country``, ``admin_area`` (name or
+    ///     ``(name, level)``), ``published`` (ISO date or Unix seconds),
+    ///     ``storms`` (names or ``(name, designation)`` pairs),
+    ///     ``figures`` and ``severity`` are used when present. Other keys
+    ///     are ignored, so ``process_article`` output with ``hazard`` and
+    ///     ``country`` added works as is.
+    ///
+    /// Returns
+    /// -------
+    /// int
+    ///     The event's id.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///     If the URL is missing or a field has the wrong type.
+    ///
+    /// Notes
+    /// -----
+    /// An article joins an event sharing one of its storm names, else the
+    /// nearest event with the same hazard and country whose admin areas
+    /// include or don't conflict with its own; both within the window.
+    /// Articles without a hazard or storm name form their own events.
+    fn add(&mut self, record: &Bound<'_, PyDict>) -> PyResult<usize> {
+        let record = record_from_dict(record)?;
+        guarded("EventFuser.add", || Ok(self.fuse(&record)))
+    }
+
+    /// Fuse many records in order; returns their event ids.
+    fn add_many(&mut self, records: Vec<Bound<'_, PyDict>>) -> PyResult<Vec<usize>> {
+        let records = records.iter().map(record_from_dict).collect::<PyResult<Vec<_>>>()?;
+        guarded("EventFuser.add_many", || Ok(records.iter().map(|r| self.fuse(r)).collect()))
+    }
+
+    /// Fused event candidates.
+    ///
+    /// Parameters
+    /// ----------
+    /// min_articles : int
+    ///     Only events reported by at least this many articles. Default 1.
+    ///
+    /// Returns
+    /// -------
+    /// list[dict]
+    ///     In creation order: ``event_id``, ``hazard``, ``emdat`` (EM-DAT
+    ///     classification of the hazard, see ``emdat_code``), ``country``,
+    ///     ``admin_area`` (the one most articles name), ``admin_areas``,
+    ///     ``storms``, ``start`` and ``end`` (ISO 8601 publication span),
+    ///     ``figures`` (highest value reported per key),
+    ///     ``figure_reports`` (articles reporting each key), ``severity``
+    ///     (highest), ``articles`` (URLs), ``article_count`` and
+    ///     ``sources`` (registrable domains).
+    #[pyo3(signature = (min_articles=1))]
+    fn events(&self, py: Python<'_>, min_articles: usize) -> PyResult<Vec<Py<PyDict>>> {
+        guarded("EventFuser.events", || {
+            self.events
+                .iter()
+                .filter(|e| e.articles.len() >= min_articles)
+                .map(|e| Ok(event_dict(py, e)?.unbind()))
+                .collect()
+        })
+    }
+
+    /// One event by id, or None.
+    fn event(&self, py: Python<'_>, event_id: usize) -> PyResult<Option<Py<PyDict>>> {
+        guarded("EventFuser.event", || self.events.get(event_id).map(|e| Ok(event_dict(py, e)?.unbind())).transpose())
+    }
+
+    fn __len__(&self) -> PyResult<usize> {
+        guarded("EventFuser.__len__", || Ok(self.events.len()))
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        guarded("EventFuser.__repr__", || {
+            Ok(format!("EventFuser(events={}, window_days={})", self.events.len(), self.window_secs as f64 / DAY_SECS))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86_400;
+
+    fn record(url: &str, hazard: Option<&str>, country: &str, area: Option<&str>, day: i64) -> FusionRecord {
+        FusionRecord {
+            url: url.to_string(),
+            hazard: hazard.map(str::to_string),
+            country: Some(country.to_string()),
+            admin_area: area.map(str::to_string),
+            published: Some(1_676_000_000 + day * DAY),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_fuses_by_key_and_window() {
+        let mut fuser = EventFuser::new(14.0);
+        let mut first = record("https://reliefweb.int/a", Some("flood"), "MZ", Some("Sofala"), 0);
+        first.figures = [("deaths".to_string(), 12), ("displaced".to_string(), 4000)].into();
+        first.severity = Some(3);
+        let mut second = record("https://www.bbc.co.uk/b", Some("Flood"), "mz", None, 3);
+        second.figures = [("deaths".to_string(), 20)].into();
+        second.severity = Some(4);
+        assert_eq!(fuser.fuse(&first), 0);
+        assert_eq!(fuser.fuse(&second), 0);
+        // Different admin area, another country, another hazard, too late
+        assert_eq!(fuser.fuse(&record("https://x.org/c", Some("flood"), "MZ", Some("Nampula"), 4)), 1);
+        assert_eq!(fuser.fuse(&record("https://x.org/d", Some("flood"), "MW", Some("Sofala"), 1)), 2);
+        assert_eq!(fuser.fuse(&record("https://x.org/e", Some("drought"), "MZ", Some("Sofala"), 1)), 3);
+        assert_eq!(fuser.fuse(&record("https://x.org/f", Some("flood"), "MZ", Some("Sofala"), 30)), 4);
+
+        let event = &fuser.events[0];
+        assert_eq!(event.figures["deaths"], (20, 2));
+        assert_eq!(event.figures["displaced"], (4000, 1));
+        assert_eq!(event.severity, Some(4));
+        assert_eq!(event.admin_area(), Some("Sofala"));
+        assert_eq!(event.sources, ["bbc.co.uk".to_string(), "reliefweb.int".to_string()].into());
+        assert_eq!((event.start, event.end), (Some(1_676_000_000), Some(1_676_000_000 + 3 * DAY)));
+    }
+
+    #[test]
+    fn test_storm_names_join_across_countries() {
+        let mut fuser = EventFuser::new(14.0);
+        let mut mz = record("https://a.org/1", Some("cyclone"), "MZ", Some("Zambezia"), 0);
+        mz.storms = vec!["Freddy".to_string()];
+        let mut mw = record("https://b.org/2", Some("flood"), "MW", Some("Blantyre"), 10);
+        mw.storms = vec!["freddy".to_string()];
+        assert_eq!(fuser.fuse(&mz), 0);
+        assert_eq!(fuser.fuse(&mw), 0);
+        // Chained: within the window of the extended span
+        let mut late = record("https://c.org/3", None, "MW", None, 22);
+        late.storms = vec!["Freddy".to_string()];
+        assert_eq!(fuser.fuse(&late), 0);
+        assert_eq!(fuser.events[0].articles.len(), 3);
+        // No hazard and no storm: its own event
+        assert_eq!(fuser.fuse(&record("https://d.org/4", None, "MW", None, 22)), 1);
+    }
+
+    #[test]
+    fn test_python_records() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let mut fuser = EventFuser::new(14.0);
+            let records = py
+                .eval_bound(
+                    "[{'canonical_url': 'https://reliefweb.int/r/1', 'hazard': 'cyclone', 'country': 'MZ', \
+                       'admin_area': ('Sofala', 1), 'published': '2023-03-12T08:00:00Z', \
+                       'storms': [('Freddy', 'cyclone')], 'figures': {'deaths': 53}, 'severity': 4}, \
+                      {'url': 'https://ocha.org/2', 'hazard': 'cyclone', 'country': 'MZ', \
+                       'admin_area': 'Sofala', 'published': 1678700000.0, 'figures': {'deaths': 67}}]",
+                    None,
+                    None,
+                )
+                .unwrap();
+            let records: Vec<Bound<'_, PyDict>> = records.extract().unwrap();
+            assert_eq!(fuser.add_many(records).unwrap(), vec![0, 0]);
+            let event = fuser.event(py, 0).unwrap().unwrap().into_bound(py);
+            let figures = event.get_item("figures").unwrap().unwrap();
+            assert_eq!(figures.get_item("deaths").unwrap().extract::<i64>().unwrap(), 67);
+            let start: String = event.get_item("start").unwrap().unwrap().extract().unwrap();
+            assert_eq!(start, "2023-03-12T08:00:00Z");
+            let emdat = event.get_item("emdat").unwrap().unwrap();
+            assert_eq!(emdat.get_item("key").unwrap().extract::<String>().unwrap(), "nat-met-sto-tro");
+
+            let bad = PyDict::new_bound(py);
+            bad.set_item("hazard", "flood").unwrap();
+            assert!(fuser.add(&bad).is_err());
+        });
+    }
+}
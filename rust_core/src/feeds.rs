@@ -0,0 +1,207 @@
+//! Feed discovery — find RSS/Atom/JSON feeds referenced by an HTML page.
+//!
+//! Looks at `<link rel="alternate">` declarations first, then at anchors
+//! pointing to common feed paths (`/feed`, `/rss.xml`, ...). Results are
+//! resolved against the page URL and canonicalized, so country teams
+//! adding a new source get its feeds detected automatically.
+
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use regex::Regex;
+use scraper::{Html, Selector};
+use std::collections::HashSet;
+use url::Url;
+
+use crate::errors::unlocked;
+use crate::url_canonical::canonicalize_url;
+
+static LINK_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse("link[href]").unwrap());
+static ANCHOR_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse("a[href]").unwrap());
+
+// Anchor targets that look like feeds: "/feed", "/rss", "/news/rss.xml", "atom.xml"
+static FEED_PATH: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?:/(?:feed|rss|atom)/?$|(?:^|/)[\w.-]*(?:rss|atom|feed)[\w.-]*\.(?:xml|rss|atom)$)")
+        .unwrap()
+});
+
+/// Paths worth probing when a page declares no feeds.
+static GUESSED_FEED_PATHS: &[&str] = &["/feed", "/rss", "/rss.xml", "/feed.xml", "/atom.xml", "/index.xml"];
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Feed {
+    pub url: String,
+    /// `"rss"`, `"atom"`, `"json"` or `"unknown"`.
+    pub kind: &'static str,
+    pub title: Option<String>,
+    /// `"link"` (declared), `"anchor"` or `"guess"`.
+    pub source: &'static str,
+}
+
+fn feed_kind(mime: &str) -> Option<&'static str> {
+    match mime.trim().to_lowercase().as_str() {
+        "application/rss+xml" | "application/rdf+xml" => Some("rss"),
+        "application/atom+xml" => Some("atom"),
+        "application/feed+json" | "application/json+feed" => Some("json"),
+        "application/xml" | "text/xml" => Some("unknown"),
+        _ => None,
+    }
+}
+
+fn kind_from_path(path: &str) -> &'static str {
+    let p = path.to_lowercase();
+    if p.contains("atom") {
+        "atom"
+    } else if p.contains("rss") {
+        "rss"
+    } else {
+        "unknown"
+    }
+}
+
+fn add_feed(
+    base: &Url,
+    seen: &mut HashSet<String>,
+    feeds: &mut Vec<Feed>,
+    href: &str,
+    kind: &'static str,
+    title: Option<String>,
+    source: &'static str,
+) {
+    let resolved = match base.join(href.trim()) {
+        Ok(u) if matches!(u.scheme(), "http" | "https") => u,
+        _ => return,
+    };
+    let url = canonicalize_url(resolved.as_str());
+    if seen.insert(url.clone()) {
+        feeds.push(Feed { url, kind, title, source });
+    }
+}
+
+pub(crate) fn find_feeds(html: &str, base_url: &str, include_guesses: bool) -> Vec<Feed> {
+    let base = match Url::parse(base_url.trim()) {
+        Ok(u) => u,
+        Err(e) => {
+            tracing::warn!(base_url, error = %e, "invalid base URL, no feeds resolved");
+            return Vec::new();
+        }
+    };
+    let doc = Html::parse_document(html);
+    let mut seen = HashSet::new();
+    let mut feeds = Vec::new();
+
+    for link in doc.select(&LINK_SELECTOR) {
+        let el = link.value();
+        let is_alternate = el
+            .attr("rel")
+            .is_some_and(|rel| rel.split_whitespace().any(|r| r.eq_ignore_ascii_case("alternate")));
+        let kind = el.attr("type").and_then(feed_kind);
+        if let (true, Some(kind)) = (is_alternate, kind) {
+            let title = el.attr("title").map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+            add_feed(&base, &mut seen, &mut feeds, el.attr("href").unwrap_or(""), kind, title, "link");
+        }
+    }
+
+    for anchor in doc.select(&ANCHOR_SELECTOR) {
+        let href = anchor.value().attr("href").unwrap_or("");
+        let path = href.split(['?', '#']).next().unwrap_or("");
+        if FEED_PATH.is_match(path) {
+            let text = anchor.text().collect::<String>().trim().to_string();
+            let title = Some(text).filter(|t| !t.is_empty());
+            add_feed(&base, &mut seen, &mut feeds, href, kind_from_path(path), title, "anchor");
+        }
+    }
+
+    if include_guesses && feeds.is_empty() {
+        for path in GUESSED_FEED_PATHS {
+            add_feed(&base, &mut seen, &mut feeds, path, kind_from_path(path), None, "guess");
+        }
+    }
+    feeds
+}
+
+/// Discover feed URLs referenced by an HTML page.
+///
+/// Parameters
+/// ----------
+/// html : str
+///     Page HTML.
+/// base_url : str
+///     URL the page was fetched from; relative hrefs resolve against it.
+/// include_guesses : bool
+///     When the page declares no feeds, also return well-known feed paths
+///     (``/feed``, ``/rss.xml``, ...) to probe. Default False.
+///
+/// Returns
+/// -------
+/// list[dict]
+///     One dict per canonical feed URL with ``url``, ``kind`` (``"rss"``,
+///     ``"atom"``, ``"json"`` or ``"unknown"``), ``title`` and ``source``
+///     (``"link"``, ``"anchor"`` or ``"guess"``), declared feeds first.
+#[pyfunction]
+#[pyo3(signature = (html, base_url, include_guesses=false))]
+pub fn discover_feeds(
+    py: Python<'_>,
+    html: &str,
+    base_url: &str,
+    include_guesses: bool,
+) -> PyResult<Py<PyList>> {
+    let feeds = unlocked(py, "discover_feeds", || find_feeds(html, base_url, include_guesses))?;
+    let list = PyList::empty_bound(py);
+    for feed in feeds {
+        let item = PyDict::new_bound(py);
+        item.set_item("url", feed.url)?;
+        item.set_item("kind", feed.kind)?;
+        item.set_item("title", feed.title)?;
+        item.set_item("source", feed.source)?;
+        list.append(item)?;
+    }
+    Ok(list.unbind())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<html><head>
+        <link rel="alternate" type="application/rss+xml" title="Latest updates" href="/en/rss.xml?utm_source=site">
+        <link rel="alternate" type="application/atom+xml" href="https://example.org/atom">
+        <link rel="alternate" hreflang="fr" href="/fr/">
+        <link rel="stylesheet" type="text/css" href="/style.css">
+        </head><body>
+        <a href="/en/rss.xml">RSS</a>
+        <a href="/news/feed/">News feed</a>
+        <a href="/about">About</a>
+        </body></html>"#;
+
+    #[test]
+    fn test_declared_and_anchor_feeds() {
+        let feeds = find_feeds(PAGE, "https://example.org/en/", false);
+        let urls: Vec<&str> = feeds.iter().map(|f| f.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://example.org/en/rss.xml",
+                "https://example.org/atom",
+                "https://example.org/news/feed/",
+            ]
+        );
+        assert_eq!(feeds[0].kind, "rss");
+        assert_eq!(feeds[0].title.as_deref(), Some("Latest updates"));
+        assert_eq!(feeds[1].kind, "atom");
+        assert_eq!(feeds[2].source, "anchor");
+    }
+
+    #[test]
+    fn test_guesses_only_when_nothing_declared() {
+        let feeds = find_feeds("<html><body>No feeds</body></html>", "https://example.gov.mz/", true);
+        assert_eq!(feeds[0].url, "https://example.gov.mz/feed");
+        assert!(feeds.iter().all(|f| f.source == "guess"));
+        assert!(find_feeds(PAGE, "https://example.org/", true).iter().all(|f| f.source != "guess"));
+    }
+
+    #[test]
+    fn test_bad_base_url() {
+        assert!(find_feeds(PAGE, "not a url", false).is_empty());
+    }
+}
@@ -0,0 +1,198 @@
+//! Plausibility checks on newly extracted figures.
+//!
+//! A misread digit or a figure from the wrong article can put "12 dead"
+//! after "120 dead", or more people displaced than live in the district.
+//! `flag_anomalies` compares a new extraction with the event's cleaned
+//! timeline (see `build_timeline`) and, when given, the area's
+//! population, and returns a flag per implausible figure so the record
+//! can be held back for review instead of published.
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::errors::unlocked;
+use crate::gazetteer::normalize_name;
+use crate::timeline::{build, cumulative_keys, dated_figures};
+
+/// A cumulative figure falling to this share of its last value or below
+/// is a drop.
+const MAX_DROP_SHARE: f64 = 0.1;
+/// A figure this many times its last value is a jump.
+const JUMP_FACTOR: i64 = 10;
+/// Last values below this are too small to judge changes against:
+/// 2 dead becoming 25 is an event unfolding, not a typo.
+const MIN_REFERENCE: i64 = 10;
+/// Figures counting people, which can't exceed the area's population.
+const PEOPLE_KEYS: &[&str] = &["deaths", "displaced", "injured", "missing", "people_affected", "children_affected"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Flag {
+    pub key: String,
+    /// `"drop"`, `"jump"` or `"exceeds_population"`.
+    pub kind: &'static str,
+    pub value: i64,
+    /// The last timeline value, or the population.
+    pub reference: i64,
+}
+
+impl Flag {
+    fn ratio(&self) -> f64 {
+        self.value as f64 / self.reference.max(1) as f64
+    }
+
+    fn message(&self) -> String {
+        match self.kind {
+            "drop" => format!("{} fell from {} to {}", self.key, self.reference, self.value),
+            "jump" => format!("{} rose from {} to {} ({:.0}x)", self.key, self.reference, self.value, self.ratio()),
+            _ => format!("{} of {} exceeds the area's population of {}", self.key, self.value, self.reference),
+        }
+    }
+}
+
+/// Flags for `new` figures against the event's `(unix_seconds, figures)`
+/// history and the area's population, by key.
+pub(crate) fn flags(
+    history: &[(i64, HashMap<String, i64>)],
+    new: &HashMap<String, i64>,
+    cumulative: &HashSet<String>,
+    population: Option<i64>,
+) -> Vec<Flag> {
+    let timeline = build(history, cumulative);
+    let sorted: BTreeMap<&String, i64> = new.iter().map(|(k, v)| (k, *v)).collect();
+    let mut flags = Vec::new();
+    for (key, value) in sorted {
+        let flag = |kind, reference| Flag { key: key.clone(), kind, value, reference };
+        if let Some(last) = timeline.get(key).and_then(|s| s.points.last()).map(|p| p.value) {
+            if last >= MIN_REFERENCE {
+                if cumulative.contains(key) && (value as f64) <= last as f64 * MAX_DROP_SHARE {
+                    flags.push(flag("drop", last));
+                } else if value > last.saturating_mul(JUMP_FACTOR) {
+                    flags.push(flag("jump", last));
+                }
+            }
+        }
+        if let Some(population) = population.filter(|p| *p > 0) {
+            if PEOPLE_KEYS.contains(&key.as_str()) && value > population {
+                flags.push(flag("exceeds_population", population));
+            }
+        }
+    }
+    flags
+}
+
+/// The population of `area` in a name → population table.
+fn population_of(populations: &HashMap<String, i64>, area: &str) -> Option<i64> {
+    let key = normalize_name(area);
+    populations.iter().find(|(name, _)| normalize_name(name) == key).map(|(_, p)| *p)
+}
+
+/// Flag implausible figures in a new extraction before publication.
+///
+/// Each new figure is checked against the last value of its cleaned
+/// timeline from the event's earlier records (see ``build_timeline``):
+/// a cumulative count (deaths, houses destroyed) falling by 90% or more
+/// is a ``"drop"``, and any figure rising tenfold a ``"jump"``. Last
+/// values under 10 aren't judged. With a population table, counts of
+/// people above the area's population are ``"exceeds_population"``.
+///
+/// Parameters
+/// ----------
+/// event_history : list[dict]
+///     The event's earlier records with ``published`` and ``figures``, as
+///     for ``build_timeline``.
+/// new_figures : dict[str, int]
+///     The new extraction, e.g. ``extract_figures`` output.
+/// populations : dict[str, int] | None
+///     Admin-area name → population.
+/// admin_area : str | tuple[str, int] | None
+///     Area the new figures are for, looked up in ``populations``
+///     (accents and case ignored).
+/// cumulative : list[str] | None
+///     Keys that only grow, as in ``build_timeline``.
+///
+/// Returns
+/// -------
+/// list[dict]
+///     One per flagged figure, by key: ``key``, ``kind`` (``"drop"``,
+///     ``"jump"`` or ``"exceeds_population"``), ``value``, ``reference``
+///     (the last timeline value, or the population), ``ratio``
+///     (``value / reference``) and ``message``. Empty when nothing looks
+///     wrong.
+///
+/// Raises
+/// ------
+/// ValueError
+///     If a ``published`` date isn't recognized.
+#[pyfunction]
+#[pyo3(signature = (event_history, new_figures, populations=None, admin_area=None, cumulative=None))]
+pub fn flag_anomalies(
+    py: Python<'_>,
+    event_history: &Bound<'_, PyList>,
+    new_figures: HashMap<String, i64>,
+    populations: Option<HashMap<String, i64>>,
+    admin_area: Option<&Bound<'_, PyAny>>,
+    cumulative: Option<Vec<String>>,
+) -> PyResult<Vec<Py<PyDict>>> {
+    let history = dated_figures(event_history)?;
+    let area = match admin_area.filter(|a| !a.is_none()) {
+        Some(area) => Some(match area.extract::<String>() {
+            Ok(name) => name,
+            Err(_) => area.extract::<(String, i32)>()?.0,
+        }),
+        None => None,
+    };
+    let population = populations.zip(area).and_then(|(table, area)| population_of(&table, &area));
+    let cumulative = cumulative_keys(cumulative);
+    let flags = unlocked(py, "flag_anomalies", || flags(&history, &new_figures, &cumulative, population))?;
+    flags
+        .into_iter()
+        .map(|flag| {
+            let dict = PyDict::new_bound(py);
+            dict.set_item("key", &flag.key)?;
+            dict.set_item("kind", flag.kind)?;
+            dict.set_item("value", flag.value)?;
+            dict.set_item("reference", flag.reference)?;
+            dict.set_item("ratio", flag.ratio())?;
+            dict.set_item("message", flag.message())?;
+            Ok(dict.unbind())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn figures(pairs: &[(&str, i64)]) -> HashMap<String, i64> {
+        pairs.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+    }
+
+    #[test]
+    fn test_drops_and_jumps() {
+        let cumulative = cumulative_keys(None);
+        let history = vec![
+            (1_678_600_000, figures(&[("deaths", 100), ("displaced", 5_000), ("injured", 3)])),
+            (1_678_690_000, figures(&[("deaths", 120), ("displaced", 4_000)])),
+        ];
+        let new = figures(&[("deaths", 12), ("displaced", 400), ("injured", 90), ("missing", 7)]);
+        let found = flags(&history, &new, &cumulative, None);
+        // Deaths can't fall; displaced can; injured's 3 is too small to judge
+        let found: Vec<(&str, &str, i64)> = found.iter().map(|f| (f.key.as_str(), f.kind, f.reference)).collect();
+        assert_eq!(found, vec![("deaths", "drop", 120)]);
+        let jump = flags(&history, &figures(&[("displaced", 60_000)]), &cumulative, None);
+        assert_eq!((jump[0].kind, jump[0].ratio()), ("jump", 15.0));
+        assert!(flags(&history, &figures(&[("deaths", 130)]), &cumulative, None).is_empty());
+    }
+
+    #[test]
+    fn test_population_check() {
+        let cumulative = cumulative_keys(None);
+        let new = figures(&[("displaced", 2_500_000), ("houses_affected", 900_000)]);
+        let found = flags(&[], &new, &cumulative, Some(25_000));
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].key.as_str(), found[0].kind, found[0].ratio()), ("displaced", "exceeds_population", 100.0));
+        let table = HashMap::from([("Zambézia".to_string(), 5_000_000)]);
+        assert_eq!(population_of(&table, "zambezia"), Some(5_000_000));
+    }
+}
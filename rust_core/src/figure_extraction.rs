@@ -1,51 +1,771 @@
 //! Figure extraction — 4-pattern NLP regex for humanitarian text.
 //!
-//! Extracts numeric figures (deaths, displaced, affected, houses, etc.)
-//! from evidence text using the same 4-pattern strategy as the Python
-//! implementation but compiled to native regex for ~50-100x throughput.
+//! Extracts numeric figures (deaths, displaced, affected, houses, crop
+//! hectares, livestock, bridges, road km, etc.) from evidence text using the same 4-pattern
+//! strategy as the Python implementation but compiled to native regex for
+//! ~50-100x throughput.
+//! Ranges ("30–40 houses") are matched first and reported as bounds, and
+//! percentages ("80% of households") as shares under `pct_` keys.
+//! French, Portuguese and Spanish text (`lang="fr"`, `"pt"`, `"es"`) have
+//! their own pattern sets with local labels and number formats ("12 000",
+//! "1.200 casas", "2,5 milhões").
+//! `extract_figures_detailed` returns each match with its span, pattern
+//! and sentence instead of the per-key maximum, for QA review.
+//! `configure_figure_keywords` registers keywords and patterns for keys
+//! outside the built-in taxonomy without a rebuild.
 
 use once_cell::sync::Lazy;
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
+#[cfg(feature = "python")]
 use pyo3::types::PyDict;
+#[cfg(feature = "python")]
+use rayon::prelude::*;
 use regex::Regex;
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::RwLock;
+#[cfg(feature = "python")]
+use std::collections::BTreeSet;
+#[cfg(feature = "python")]
+use unicode_segmentation::UnicodeSegmentation;
+
+#[cfg(feature = "python")]
+use crate::columns::{object_column, Texts};
+#[cfg(feature = "python")]
+use crate::errors::{config_error, guarded, unlocked, Failure};
+use crate::metrics::record_figures;
+#[cfg(feature = "python")]
+use crate::metrics::timer;
+
+/// A number with an optional magnitude word: "48,000", "1.2 million",
+/// "3.5m", "20k", "half a million". The magnitude must end at a word
+/// boundary, so "5 missing" and "4 killed" aren't read as 5m and 4k.
+pub(crate) const NUM: &str = r"(\d[\d,]*(?:\.\d+)?(?:\s*(?:thousand|million|billion|mil|bn|m|k)\b)?|(?:half\s+a|a)\s+(?:thousand|million|billion)\b)";
+
+/// Agricultural labels: crop area (converted to hectares) and livestock.
+const AGRICULTURE: &str = r"hectares?|ha\b|acres?|head\s+of\s+(?:cattle|livestock)|cattle|livestock|goats|sheep|camels";
+
+const HECTARES_PER_ACRE: f64 = 0.404_686;
+
+/// Infrastructure labels: bridges, road length (converted to km) and
+/// power assets.
+const INFRASTRUCTURE: &str = r"bridges|(?:kms?|kilomet(?:er|re)s?|miles?|mi)\s+of\s+(?:the\s+)?(?:roads?|highways?)|substations|transformers|pylons|power\s+(?:lines|stations|plants|poles)|electricity\s+poles";
+
+const KM_PER_MILE: f64 = 1.609_344;
 
 // Pattern 1: NUM + keyword (e.g. "48,000 displaced")
 static NUMBER_PATTERN: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(
-        r"(?i)(\d[\d,]*(?:\.\d+)?)\s*(people|persons|individuals|deaths|dead|killed|displaced|injured|missing|houses|homes|affected|families|households|children|schools|health\s*facilit)"
-    ).unwrap()
+    Regex::new(&format!(
+        r"(?i){NUM}\s*(people|persons|individuals|deaths|dead|killed|displaced|injured|missing|houses|homes|affected|families|households|children|schools|health\s*facilit|{AGRICULTURE}|{INFRASTRUCTURE})"
+    )).unwrap()
 });
 
 // Pattern 2: "death toll rises to NUM" / "kills NUM"
 static TOLL_PATTERN: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(
-        r"(?i)(?:death\s+toll|toll)\s+(?:rises?\s+to|hits?|reaches?|climbs?\s+to|stands?\s+at|now)\s+(\d[\d,]*)|(?:kills?|killed)\s+(\d[\d,]*)"
-    ).unwrap()
+    Regex::new(&format!(
+        r"(?i)(?:death\s+toll|toll)\s+(?:rises?\s+to|hits?|reaches?|climbs?\s+to|stands?\s+at|now)\s+{NUM}|(?:kills?|killed)\s+{NUM}"
+    )).unwrap()
 });
 
 // Pattern 3: "at least/over/more than NUM keyword"
 static ATLEAST_PATTERN: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(
-        r"(?i)(?:at\s+least|over|more\s+than|nearly|approximately|about|up\s+to|around|some)\s+(\d[\d,]*(?:\.\d+)?)\s*(people|persons|dead|killed|deaths|displaced|injured|missing|affected|houses|homes|children|families|schools|health)"
-    ).unwrap()
+    Regex::new(&format!(
+        r"(?i)(?:at\s+least|over|more\s+than|nearly|approximately|about|up\s+to|around|some)\s+{NUM}\s*(people|persons|dead|killed|deaths|displaced|injured|missing|affected|houses|homes|children|families|schools|health|{AGRICULTURE}|{INFRASTRUCTURE})"
+    )).unwrap()
 });
 
 // Pattern 4: "NUM killed/dead/deaths" at sentence level
 static SENTENCE_FIGURE_PATTERN: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"(?i)\b(\d[\d,]*)\b[^.]{0,30}\b(killed|dead|deaths|drowned|perished|fatalities)")
-        .unwrap()
+    Regex::new(&format!(r"(?i)\b{NUM}\b[^.]{{0,30}}\b(killed|dead|deaths|drowned|perished|fatalities)")).unwrap()
+});
+
+// Percentages: "80% of households", "60 percent of the district"
+static PERCENT_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(\d{1,3}(?:\.\d+)?)\s*(?:%|per\s*cent\b)\s+of\s+(?:[a-z-]+\s+){0,2}?(households|families|people|persons|population|residents|inhabitants|individuals|houses|homes|buildings|dwellings|children|schools|health\s*facilities|crops|cropland|farmland|harvest|fields|livestock|cattle|district|area|territory|province|region|land|villages?|towns?|city|municipality|county)\b",
+    )
+    .unwrap()
+});
+
+// Livestock or infrastructure right after a number: "kills 300 cattle",
+// "3,500 goats dead" and "14 bridges washed away, 3 killed" aren't death
+// tolls
+static NOT_PEOPLE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(r"(?i)^\s*(?:(?:head\s+of\s+)?(?:cattle|livestock|goats|sheep|camels)|{INFRASTRUCTURE})\b")).unwrap()
+});
+
+// Negated statements: "no deaths have been reported", "no one was
+// killed", "nobody is missing"
+static NEGATION_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(?:no|zero|nobody|not\s+a\s+single)\b(?:\s+(?:one|new|further|additional|immediate|reported|confirmed|known|reports?\s+of|person|people|was|were|has|have|had|is|are|been|yet)){0,5}\s+(deaths?|fatalities|casualties|injuries|killed|died|dead|injured|hurt|displaced|missing)\b",
+    )
+    .unwrap()
+});
+
+// Ranges: "between 50 and 70 people dead", "30–40 houses", "5 to 10 killed"
+static RANGE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?i)(?:between\s+{NUM}\s+and\s+{NUM}|{NUM}\s*(?:-|–|—|to)\s*{NUM})\s*(?:(?:people|persons)\s+)?(people|persons|individuals|deaths|dead|killed|displaced|injured|missing|houses|homes|affected|families|households|children|schools|health\s*facilit|{AGRICULTURE}|{INFRASTRUCTURE})"
+    )).unwrap()
+});
+
+// ── French ──────────────────────────────────────────────────────────
+//
+// Sahel and DRC sources: "au moins 45 morts", "12 000 personnes
+// déplacées". Numbers group thousands with spaces or dots and use a
+// decimal comma; labels take gender and number agreement.
+
+/// A French number: "12 000", "12.000", "1,5 million", "3 milliards".
+const NUM_FR: &str = r"(\b(?:\d{1,3}(?:[ \u{a0}\u{202f}.]\d{3})+|\d+)(?:[.,]\d+)?(?:\s*(?:mille|millions?|milliards?|k)\b)?)";
+
+/// French labels, after "de" for millions ("2 millions de personnes")
+/// and an optional "personnes".
+const LABELS_FR: &str = r"morte?s?|décès|tuée?s?|décédée?s?|déplacée?s?|blessée?s?|disparue?s?|maisons|habitations|logements|personnes|individus|sinistrée?s?|affectée?s?|touchée?s?|familles|ménages|enfants|écoles|centres\s+de\s+santé|formations\s+sanitaires|hectares?|ha|têtes\s+de\s+bétail|bétail|bovins|caprins|ovins|chèvres|moutons";
+
+static NUMBER_PATTERN_FR: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(r"(?i){NUM_FR}\s*(?:d(?:e\s+|['’]))?(?:personnes\s+)?({LABELS_FR})\b")).unwrap()
+});
+
+static TOLL_PATTERN_FR: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?i)(?:bilan|nombre\s+de\s+morts)\s+(?:provisoire\s+)?(?:s['’]élève\s+à|passe\s+à|atteint|est\s+de|monte\s+à)\s+{NUM_FR}|(?:tue|tuant|coûté\s+la\s+vie\s+à)\s+{NUM_FR}"
+    ))
+    .unwrap()
+});
+
+static ATLEAST_PATTERN_FR: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?i)(?:au\s+moins|plus\s+de|près\s+de|environ|quelque|jusqu['’]à|pas\s+moins\s+de)\s+{NUM_FR}\s*(?:d(?:e\s+|['’]))?(?:personnes\s+)?({LABELS_FR})\b"
+    ))
+    .unwrap()
+});
+
+static SENTENCE_FIGURE_PATTERN_FR: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?i)\b{NUM_FR}\b[^.]{{0,30}}\b(morte?s?|tuée?s?|décès|noyée?s?|décédée?s?|ont\s+péri|ont\s+perdu\s+la\s+vie)"
+    ))
+    .unwrap()
+});
+
+static PERCENT_PATTERN_FR: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(\d{1,3}(?:[.,]\d+)?)\s*(?:%|pour\s*cent\b)\s+(?:des|du|de\s+la|de\s+l['’]|de)\s*(?:[\p{L}-]+\s+){0,2}?(ménages|familles|personnes|population|habitants|maisons|habitations|logements|enfants|écoles|centres\s+de\s+santé|cultures|récoltes|champs|terres\s+agricoles|bétail|cheptel|district|territoire|zone|région|province|commune|villages?|ville|localité)\b",
+    )
+    .unwrap()
+});
+
+static LIVESTOCK_FR: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^\s*(?:têtes\s+de\s+)?(?:bétail|bovins|caprins|ovins|chèvres|moutons)\b").unwrap()
+});
+
+static RANGE_PATTERN_FR: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?i)(?:entre\s+{NUM_FR}\s+et\s+{NUM_FR}|{NUM_FR}\s*(?:-|–|—|à)\s*{NUM_FR})\s*(?:d(?:e\s+|['’]))?(?:personnes\s+)?({LABELS_FR})\b"
+    ))
+    .unwrap()
+});
+
+// "aucun mort signalé", "pas de blessés", "sans victimes"
+static NEGATION_PATTERN_FR: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(?:aucune?|pas\s+de|pas\s+d['’]|pas\s+un\s+seul|sans|zéro)\s*(?:(?:nouvea(?:u|ux)|nouvelles?|autres?)\s+)?(morte?s?|décès|tuée?s?|victimes?|blessée?s?|déplacée?s?|disparue?s?)\b",
+    )
+    .unwrap()
 });
 
-fn parse_number(raw: &str) -> Option<i64> {
-    let cleaned: String = raw.replace(',', "");
-    cleaned.parse::<f64>().ok().map(|f| f as i64)
+// ── Portuguese and Spanish ──────────────────────────────────────────
+//
+// Mozambican and Latin American sources: "1.200 casas", "2,5 milhões de
+// pessoas", "al menos 30 muertos". Numbers group thousands with dots and
+// use a decimal comma, as in French; "mil" is a thousand.
+
+const NUM_PT: &str = r"(\b(?:\d{1,3}(?:[ \u{a0}.]\d{3})+|\d+)(?:[.,]\d+)?(?:\s*(?:mil\s+milhões|milhões|milhão|milhoes|milhao|bilhões|bilhão|mil)\b)?)";
+
+const LABELS_PT: &str = r"mort[oa]s|óbitos|vítimas\s+mortais|desalojad[oa]s|deslocad[oa]s|ferid[oa]s|desaparecid[oa]s|casas|residências|habitações|pessoas|afectad[oa]s|afetad[oa]s|famílias|agregados\s+familiares|crianças|escolas|unidades\s+sanitárias|centros\s+de\s+saúde|hectares?|ha|cabeças\s+de\s+gado|gado|bovinos|caprinos|cabritos|ovinos";
+
+static NUMBER_PATTERN_PT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(r"(?i){NUM_PT}\s*(?:de\s+)?(?:pessoas\s+)?({LABELS_PT})\b")).unwrap()
+});
+
+static TOLL_PATTERN_PT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?i)(?:número\s+de\s+mortos|balanço|saldo)\s+(?:sobe\s+para|subiu\s+para|atinge|é\s+de|ascende\s+a)\s+{NUM_PT}|(?:mata|matou|matando)\s+{NUM_PT}"
+    ))
+    .unwrap()
+});
+
+static ATLEAST_PATTERN_PT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?i)(?:pelo\s+menos|mais\s+de|cerca\s+de|perto\s+de|quase|aproximadamente|até)\s+{NUM_PT}\s*(?:de\s+)?(?:pessoas\s+)?({LABELS_PT})\b"
+    ))
+    .unwrap()
+});
+
+static SENTENCE_FIGURE_PATTERN_PT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?i)\b{NUM_PT}\b[^.]{{0,30}}\b(mort[oa]s|óbitos|morreram|perderam\s+a\s+vida|afogad[oa]s)"
+    ))
+    .unwrap()
+});
+
+static PERCENT_PATTERN_PT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(\d{1,3}(?:[.,]\d+)?)\s*(?:%|por\s+cento\b)\s+(?:d[oa]s?|de)\s+(?:[\p{L}-]+\s+){0,2}?(agregados|famílias|pessoas|população|habitantes|casas|habitações|crianças|escolas|culturas|colheitas|machambas|campos|gado|distrito|província|região|área|território|município|aldeias?|cidade|localidade)\b",
+    )
+    .unwrap()
+});
+
+static LIVESTOCK_PT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^\s*(?:cabeças\s+de\s+)?(?:gado|bovinos|caprinos|cabritos|ovinos)\b").unwrap()
+});
+
+static RANGE_PATTERN_PT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?i)(?:entre\s+{NUM_PT}\s+e\s+{NUM_PT}|{NUM_PT}\s*(?:-|–|—|a)\s*{NUM_PT})\s*(?:de\s+)?(?:pessoas\s+)?({LABELS_PT})\b"
+    ))
+    .unwrap()
+});
+
+// "nenhum morto", "sem vítimas mortais", "não houve feridos"
+static NEGATION_PATTERN_PT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(?:nenhuma?|sem|zero|não\s+(?:houve|há|foram\s+registad[oa]s|foram\s+registrad[oa]s))\s+(?:(?:nov[oa]s?|outr[oa]s?|registos?\s+de|registros?\s+de)\s+)?(mort[oa]s?|óbitos?|vítimas?(?:\s+mortais)?|ferid[oa]s?|desalojad[oa]s?|deslocad[oa]s?|desaparecid[oa]s?)\b",
+    )
+    .unwrap()
+});
+
+const NUM_ES: &str = r"(\b(?:\d{1,3}(?:[ \u{a0}.]\d{3})+|\d+)(?:[.,]\d+)?(?:\s*(?:mil\s+millones|millones|millón|millon|mil)\b)?)";
+
+const LABELS_ES: &str = r"muert[oa]s|fallecid[oa]s|víctimas\s+mortales|desplazad[oa]s|evacuad[oa]s|herid[oa]s|desaparecid[oa]s|casas|viviendas|personas|afectad[oa]s|damnificad[oa]s|familias|hogares|niñ[oa]s|escuelas|centros\s+de\s+salud|hectáreas?|ha|cabezas\s+de\s+ganado|ganado|reses|bovinos";
+
+static NUMBER_PATTERN_ES: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(r"(?i){NUM_ES}\s*(?:de\s+)?(?:personas\s+)?({LABELS_ES})\b")).unwrap()
+});
+
+static TOLL_PATTERN_ES: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?i)(?:cifra\s+de\s+muertos|número\s+de\s+muertos|balance|saldo)\s+(?:sube\s+a|asciende\s+a|alcanza|llega\s+a|es\s+de)\s+{NUM_ES}|(?:mata|mató|matando)\s+(?:a\s+)?{NUM_ES}"
+    ))
+    .unwrap()
+});
+
+static ATLEAST_PATTERN_ES: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?i)(?:al\s+menos|por\s+lo\s+menos|más\s+de|cerca\s+de|casi|aproximadamente|unos|unas|hasta)\s+{NUM_ES}\s*(?:de\s+)?(?:personas\s+)?({LABELS_ES})\b"
+    ))
+    .unwrap()
+});
+
+static SENTENCE_FIGURE_PATTERN_ES: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?i)\b{NUM_ES}\b[^.]{{0,30}}\b(muert[oa]s|fallecid[oa]s|murieron|perdieron\s+la\s+vida|ahogad[oa]s)"
+    ))
+    .unwrap()
+});
+
+static PERCENT_PATTERN_ES: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(\d{1,3}(?:[.,]\d+)?)\s*(?:%|por\s+ciento\b)\s+(?:del?|de\s+la|de\s+los|de\s+las)\s+(?:[\p{L}-]+\s+){0,2}?(hogares|familias|personas|población|habitantes|casas|viviendas|niños|escuelas|cultivos|cosechas|campos|ganado|distrito|provincia|departamento|región|área|zona|territorio|municipio|comunidad(?:es)?|ciudad|localidad)\b",
+    )
+    .unwrap()
+});
+
+static LIVESTOCK_ES: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^\s*(?:cabezas\s+de\s+)?(?:ganado|reses|bovinos|cabras|ovejas)\b").unwrap()
+});
+
+static RANGE_PATTERN_ES: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?i)(?:entre\s+{NUM_ES}\s+y\s+{NUM_ES}|{NUM_ES}\s*(?:-|–|—|a)\s*{NUM_ES})\s*(?:de\s+)?(?:personas\s+)?({LABELS_ES})\b"
+    ))
+    .unwrap()
+});
+
+// "ningún muerto", "sin víctimas mortales", "no se registraron heridos"
+static NEGATION_PATTERN_ES: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(?:ningun[oa]?|ningún|sin|cero|no\s+(?:se\s+)?(?:registraron|reportaron|han\s+registrado|han\s+reportado|hubo|hay))\s+(?:(?:nuev[oa]s?|otr[oa]s?|reportes?\s+de)\s+)?(muert[oa]s?|muertes|fallecid[oa]s?|víctimas?(?:\s+mortales)?|herid[oa]s?|desplazad[oa]s?|evacuad[oa]s?|desaparecid[oa]s?)\b",
+    )
+    .unwrap()
+});
+
+/// Languages with a figure pattern set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub(crate) enum Language {
+    #[default]
+    English,
+    French,
+    Portuguese,
+    Spanish,
+}
+
+impl Language {
+    /// The language for an ISO 639-1 code or tag ("fr", "fr-CD").
+    pub(crate) fn from_code(code: &str) -> Option<Self> {
+        let primary = code.trim().split(['-', '_']).next().unwrap_or("").to_lowercase();
+        match primary.as_str() {
+            "en" => Some(Self::English),
+            "fr" => Some(Self::French),
+            "pt" => Some(Self::Portuguese),
+            "es" => Some(Self::Spanish),
+            _ => None,
+        }
+    }
+
+    fn patterns(self) -> Patterns {
+        match self {
+            Self::English => Patterns {
+                num: NUM,
+                range: &RANGE_PATTERN,
+                percent: &PERCENT_PATTERN,
+                number: &NUMBER_PATTERN,
+                toll: &TOLL_PATTERN,
+                at_least: &ATLEAST_PATTERN,
+                sentence: &SENTENCE_FIGURE_PATTERN,
+                not_people: &NOT_PEOPLE,
+                negation: &NEGATION_PATTERN,
+                negated_keys,
+                label_to_key,
+                percent_key,
+            },
+            Self::French => Patterns {
+                num: NUM_FR,
+                range: &RANGE_PATTERN_FR,
+                percent: &PERCENT_PATTERN_FR,
+                number: &NUMBER_PATTERN_FR,
+                toll: &TOLL_PATTERN_FR,
+                at_least: &ATLEAST_PATTERN_FR,
+                sentence: &SENTENCE_FIGURE_PATTERN_FR,
+                not_people: &LIVESTOCK_FR,
+                negation: &NEGATION_PATTERN_FR,
+                negated_keys: negated_keys_romance,
+                label_to_key: label_to_key_fr,
+                percent_key: percent_key_fr,
+            },
+            Self::Portuguese => Patterns {
+                num: NUM_PT,
+                range: &RANGE_PATTERN_PT,
+                percent: &PERCENT_PATTERN_PT,
+                number: &NUMBER_PATTERN_PT,
+                toll: &TOLL_PATTERN_PT,
+                at_least: &ATLEAST_PATTERN_PT,
+                sentence: &SENTENCE_FIGURE_PATTERN_PT,
+                not_people: &LIVESTOCK_PT,
+                negation: &NEGATION_PATTERN_PT,
+                negated_keys: negated_keys_romance,
+                label_to_key: |label| key_by_prefix(label, LABEL_KEYS_PT, "people_affected"),
+                percent_key: |subject| key_by_prefix(subject, PERCENT_KEYS_PT, "pct_area_affected"),
+            },
+            Self::Spanish => Patterns {
+                num: NUM_ES,
+                range: &RANGE_PATTERN_ES,
+                percent: &PERCENT_PATTERN_ES,
+                number: &NUMBER_PATTERN_ES,
+                toll: &TOLL_PATTERN_ES,
+                at_least: &ATLEAST_PATTERN_ES,
+                sentence: &SENTENCE_FIGURE_PATTERN_ES,
+                not_people: &LIVESTOCK_ES,
+                negation: &NEGATION_PATTERN_ES,
+                negated_keys: negated_keys_romance,
+                label_to_key: |label| key_by_prefix(label, LABEL_KEYS_ES, "people_affected"),
+                percent_key: |subject| key_by_prefix(subject, PERCENT_KEYS_ES, "pct_area_affected"),
+            },
+        }
+    }
+}
+
+/// One language's patterns and label mappings.
+struct Patterns {
+    /// Number syntax, for registered keywords.
+    num: &'static str,
+    range: &'static Regex,
+    percent: &'static Regex,
+    number: &'static Regex,
+    toll: &'static Regex,
+    at_least: &'static Regex,
+    sentence: &'static Regex,
+    /// Labels after a number that make it not a death toll.
+    not_people: &'static Regex,
+    /// Negated statements; the first capture group is the negated word.
+    negation: &'static Regex,
+    negated_keys: fn(&str) -> &'static [&'static str],
+    label_to_key: fn(&str) -> &'static str,
+    percent_key: fn(&str) -> &'static str,
+}
+
+impl Patterns {
+    /// The patterns figures are read with; `not_people` only filters them.
+    fn regexes(&self) -> impl Iterator<Item = &'static Regex> {
+        [self.range, self.percent, self.number, self.toll, self.at_least, self.sentence, self.negation].into_iter()
+    }
+}
+
+/// A figure pattern registered at runtime: its first capture group is the
+/// number, and every match is a figure for `key`.
+pub(crate) struct CustomPattern {
+    key: String,
+    regex: Regex,
+}
+
+/// Patterns registered with `configure_figure_keywords`, by language.
+static CUSTOM_PATTERNS: Lazy<RwLock<HashMap<Language, Vec<CustomPattern>>>> = Lazy::new(Default::default);
+
+fn check_key(key: &str) -> Result<(), String> {
+    if key.trim().is_empty() {
+        return Err("empty figure key".to_string());
+    }
+    Ok(())
+}
+
+/// "14 boats destroyed": a number in `lang`'s syntax followed by the
+/// keyword, whitespace-insensitive and ignoring case.
+fn keyword_pattern(keyword: &str, key: &str, lang: Language) -> Result<CustomPattern, String> {
+    check_key(key)?;
+    let words: Vec<String> = keyword.split_whitespace().map(regex::escape).collect();
+    if words.is_empty() {
+        return Err(format!("empty keyword for figure key {key:?}"));
+    }
+    let regex = Regex::new(&format!(r"(?i){}\s*{}\b", lang.patterns().num, words.join(r"\s+")))
+        .map_err(|e| format!("invalid keyword {keyword:?}: {e}"))?;
+    Ok(CustomPattern { key: key.to_string(), regex })
+}
+
+fn custom_pattern(key: &str, pattern: &str) -> Result<CustomPattern, String> {
+    check_key(key)?;
+    let regex = Regex::new(pattern).map_err(|e| format!("invalid figure pattern {pattern:?}: {e}"))?;
+    if regex.captures_len() < 2 {
+        return Err(format!("figure pattern {pattern:?} has no capture group for the number"));
+    }
+    Ok(CustomPattern { key: key.to_string(), regex })
+}
+
+/// Number of figure patterns per language, for `runtime_info`.
+pub(crate) fn inventory() -> Vec<(&'static str, usize)> {
+    let custom = CUSTOM_PATTERNS.read().unwrap_or_else(|e| e.into_inner());
+    vec![
+        ("figure_patterns", Language::English.patterns().regexes().count()),
+        ("figure_patterns_fr", Language::French.patterns().regexes().count()),
+        ("figure_patterns_pt", Language::Portuguese.patterns().regexes().count()),
+        ("figure_patterns_es", Language::Spanish.patterns().regexes().count()),
+        ("figure_patterns_custom", custom.values().map(Vec::len).sum()),
+    ]
+}
+
+/// Register figure keywords and patterns beyond the built-in taxonomy.
+///
+/// Registered patterns run after ranges and percentages and before the
+/// built-in patterns, which then don't read the same text again, so
+/// "120 classrooms damaged" isn't also 120 people affected. Results go
+/// through ``extract_figures`` and its variants under pattern
+/// ``"custom"``.
+///
+/// Parameters
+/// ----------
+/// keywords : dict[str, str] | None
+///     Keyword → figure key, matched after a number:
+///     ``{"boats destroyed": "boats_destroyed"}`` reads "14 boats
+///     destroyed" and, in English, "fourteen boats destroyed".
+/// patterns : list[tuple[str, str]] | None
+///     ``(key, regex)`` pairs; the regex's first capture group is the
+///     number, e.g. ``("wells_contaminated", r"(?i)(\d[\d,]*) wells? (?:were )?contaminated")``.
+/// lang : str
+///     Language the keywords and patterns apply to; keywords take its
+///     number syntax. Default ``"en"``.
+/// replace : bool
+///     Drop the language's earlier registrations first; with no
+///     keywords or patterns this clears them. Default False.
+///
+/// Raises
+/// ------
+/// ConfigError
+///     If ``lang`` has no pattern set, a key or keyword is empty, or a
+///     pattern isn't a valid regex or has no capture group (``reason``
+///     ``"invalid_value"``); nothing is registered.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (keywords=None, patterns=None, lang="en", replace=false))]
+pub fn configure_figure_keywords(
+    keywords: Option<HashMap<String, String>>,
+    patterns: Option<Vec<(String, String)>>,
+    lang: &str,
+    replace: bool,
+) -> PyResult<()> {
+    guarded("configure_figure_keywords", || {
+        let lang = language(lang)?;
+        let mut keywords: Vec<(String, String)> = keywords.into_iter().flatten().collect();
+        keywords.sort();
+        let compiled: Result<Vec<CustomPattern>, String> = keywords
+            .iter()
+            .map(|(keyword, key)| keyword_pattern(keyword, key, lang))
+            .chain(patterns.iter().flatten().map(|(key, pattern)| custom_pattern(key, pattern)))
+            .collect();
+        let compiled = compiled.map_err(|e| {
+            tracing::warn!(error = %e, "figure patterns left unchanged");
+            config_error(None, Failure::new("invalid_value", e))
+        })?;
+        let mut custom = CUSTOM_PATTERNS.write().unwrap_or_else(|e| e.into_inner());
+        let registered = custom.entry(lang).or_default();
+        if replace {
+            registered.clear();
+        }
+        registered.extend(compiled);
+        Ok(())
+    })
+}
+
+/// Value of a `NUM` match: digits (commas ignored) times any magnitude.
+pub(crate) fn parse_number(raw: &str, lang: Language) -> Option<i64> {
+    number_parts(raw, lang).map(|(base, multiplier)| (base * multiplier).round() as i64)
+}
+
+/// A `NUM` (or `NUM_FR`) match as `(base, magnitude)`: "1.2 million" →
+/// (1.2, 1e6).
+fn number_parts(raw: &str, lang: Language) -> Option<(f64, f64)> {
+    if lang != Language::English {
+        return locale_number_parts(raw, lang);
+    }
+    let lower = raw.to_lowercase();
+    let split = lower.find(|c: char| !(c.is_ascii_digit() || c == ',' || c == '.')).unwrap_or(lower.len());
+    let (digits, words) = lower.split_at(split);
+    let mut words: Vec<&str> = words.split_whitespace().collect();
+    let base = if !digits.is_empty() {
+        digits.replace(',', "").parse::<f64>().ok()?
+    } else if words.starts_with(&["half", "a"]) {
+        words.drain(..2);
+        0.5
+    } else if words.first() == Some(&"a") {
+        words.remove(0);
+        1.0
+    } else {
+        return None;
+    };
+    let multiplier = match words.as_slice() {
+        [] => 1.0,
+        ["thousand"] | ["k"] => 1e3,
+        ["million"] | ["mil"] | ["m"] => 1e6,
+        ["billion"] | ["bn"] => 1e9,
+        _ => return None,
+    };
+    Some((base, multiplier))
+}
+
+/// A French, Portuguese or Spanish number as `(base, magnitude)`:
+/// "12 000" → (12000, 1), "1,5 million" → (1.5, 1e6), "5 mil" → (5, 1e3).
+fn locale_number_parts(raw: &str, lang: Language) -> Option<(f64, f64)> {
+    use Language::*;
+    let lower = raw.to_lowercase();
+    let split = lower
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, ',' | '.' | ' ' | '\u{a0}' | '\u{202f}')))
+        .unwrap_or(lower.len());
+    let (digits, word) = lower.split_at(split);
+    let digits: String = digits.chars().filter(|c| !c.is_whitespace()).collect();
+    // Dots group thousands ("12.000") unless a decimal comma follows
+    // them or they don't set off groups of three ("2.5")
+    let grouped = digits.split(',').next().unwrap_or("").split('.').skip(1).all(|g| g.len() == 3);
+    let digits = if digits.contains(',') || grouped { digits.replace('.', "").replace(',', ".") } else { digits };
+    let base = digits.parse::<f64>().ok()?;
+    let word = word.split_whitespace().collect::<Vec<_>>().join(" ");
+    let multiplier = match (lang, word.as_str()) {
+        (_, "") => 1.0,
+        (French, "mille" | "k") | (Portuguese | Spanish, "mil") => 1e3,
+        (French, "million" | "millions")
+        | (Portuguese, "milhão" | "milhões" | "milhao" | "milhoes")
+        | (Spanish, "millón" | "millones" | "millon") => 1e6,
+        (French, "milliard" | "milliards") | (Portuguese, "bilhão" | "bilhões" | "mil milhões") | (Spanish, "mil millones") => {
+            1e9
+        }
+        _ => return None,
+    };
+    Some((base, multiplier))
+}
+
+/// Bounds of a range; a bare lower bound takes the upper's magnitude
+/// ("between 1.5 and 2 million" is 1.5 million to 2 million). None if the
+/// bounds aren't increasing (as in "2023-2022").
+fn parse_range(lower: &str, upper: &str, lang: Language) -> Option<(i64, i64)> {
+    let (low, low_magnitude) = number_parts(lower, lang)?;
+    let (high, high_magnitude) = number_parts(upper, lang)?;
+    let low_magnitude = if low_magnitude == 1.0 && low <= high { high_magnitude } else { low_magnitude };
+    let (low, high) = ((low * low_magnitude).round() as i64, (high * high_magnitude).round() as i64);
+    (low < high).then_some((low, high))
+}
+
+// ── Spelled-out numbers ─────────────────────────────────────────────
+//
+// Sitreps often write figures as words ("five hundred families
+// displaced"). Runs of number words are rewritten as digits before the
+// patterns run, so they go through the same patterns and `label_to_key`.
+
+static LETTERS: Lazy<Regex> = Lazy::new(|| Regex::new(r"[A-Za-z]+").unwrap());
+
+/// Words before a lone "one" that make it a pronoun: "no one was killed".
+const NOT_A_COUNT: &[&str] = &["no", "any", "every", "each", "the", "which", "this", "that"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NumberWord {
+    /// one to nine
+    Unit(i64),
+    /// ten to nineteen
+    Teen(i64),
+    /// twenty to ninety
+    Ten(i64),
+    Hundred,
+    /// thousand, million, billion
+    Scale(i64),
+    And,
+    /// "a hundred"
+    A,
+}
+
+fn number_word(word: &str) -> Option<NumberWord> {
+    use NumberWord::*;
+    const UNITS: [&str; 9] = ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+    const TEENS: [&str; 10] =
+        ["ten", "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen", "nineteen"];
+    const TENS: [&str; 8] = ["twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety"];
+    let word = word.to_ascii_lowercase();
+    let word = word.as_str();
+    if let Some(i) = UNITS.iter().position(|w| *w == word) {
+        return Some(Unit(i as i64 + 1));
+    }
+    if let Some(i) = TEENS.iter().position(|w| *w == word) {
+        return Some(Teen(i as i64 + 10));
+    }
+    if let Some(i) = TENS.iter().position(|w| *w == word) {
+        return Some(Ten(i as i64 * 10 + 20));
+    }
+    match word {
+        "hundred" => Some(Hundred),
+        "thousand" => Some(Scale(1_000)),
+        "million" => Some(Scale(1_000_000)),
+        "billion" => Some(Scale(1_000_000_000)),
+        "and" => Some(And),
+        "a" => Some(A),
+        _ => None,
+    }
+}
+
+/// Whether `next` can follow `prev` in a cardinal ("twenty five",
+/// "five hundred and twenty", "three million two hundred thousand").
+fn follows(prev: Option<NumberWord>, next: NumberWord, last_scale: i64) -> bool {
+    use NumberWord::*;
+    match (prev, next) {
+        (None, Unit(_) | Teen(_) | Ten(_) | A) => true,
+        (Some(A), Hundred) => true,
+        (Some(Unit(_) | Teen(_)), Hundred) => true,
+        (Some(Unit(_) | Teen(_) | Ten(_) | Hundred), Scale(scale)) => scale < last_scale,
+        (Some(Ten(_)), Unit(_)) => true,
+        (Some(Hundred | Scale(_)), Unit(_) | Teen(_) | Ten(_) | And) => true,
+        (Some(And), Unit(_) | Teen(_) | Ten(_)) => true,
+        _ => false,
+    }
+}
+
+fn cardinal(words: &[NumberWord]) -> i64 {
+    let (mut total, mut current) = (0, 0);
+    for word in words {
+        match *word {
+            NumberWord::Unit(n) | NumberWord::Teen(n) | NumberWord::Ten(n) => current += n,
+            NumberWord::A => current = 1,
+            NumberWord::Hundred => current = current.max(1) * 100,
+            NumberWord::Scale(scale) => {
+                total += current.max(1) * scale;
+                current = 0;
+            }
+            NumberWord::And => {}
+        }
+    }
+    total + current
+}
+
+/// Byte ranges of number-word rewrites: `(original, rewritten)`.
+type Rewrites = Vec<(Range<usize>, Range<usize>)>;
+
+/// `text` with runs of number words written as digits: "twelve people"
+/// → "12 people", "two hundred and fifty thousand" → "250000"; with the
+/// rewrites made, for mapping offsets back to `text`.
+fn words_to_digits(text: &str) -> (Cow<'_, str>, Rewrites) {
+    struct Run {
+        start: usize,
+        end: usize,
+        words: Vec<NumberWord>,
+        last_scale: i64,
+        after_determiner: bool,
+    }
+    let mut runs: Vec<Run> = Vec::new();
+    let mut open: Option<Run> = None;
+    let mut previous_word = "";
+    for m in LETTERS.find_iter(text) {
+        let word = number_word(m.as_str());
+        if let (Some(run), Some(word)) = (open.as_mut(), word) {
+            let gap = &text[run.end..m.start()];
+            let joined = gap.trim().is_empty() || gap == "-";
+            if joined && follows(run.words.last().copied(), word, run.last_scale) {
+                run.words.push(word);
+                run.end = m.end();
+                if let NumberWord::Scale(scale) = word {
+                    run.last_scale = scale;
+                }
+                continue;
+            }
+        }
+        runs.extend(open.take());
+        if let Some(word) = word.filter(|w| follows(None, *w, i64::MAX)) {
+            let after_determiner = NOT_A_COUNT.contains(&previous_word.to_ascii_lowercase().as_str());
+            open = Some(Run { start: m.start(), end: m.end(), words: vec![word], last_scale: i64::MAX, after_determiner });
+        }
+        previous_word = m.as_str();
+    }
+    runs.extend(open);
+
+    let mut out = String::new();
+    let mut edits = Vec::new();
+    let mut copied = 0;
+    for mut run in runs {
+        // A trailing "and" joins the next clause, not the number
+        if run.words.last() == Some(&NumberWord::And) {
+            run.words.pop();
+            run.end = text[..run.end].trim_end_matches(char::is_alphabetic).trim_end().len();
+        }
+        if run.words == [NumberWord::A] || (run.words == [NumberWord::Unit(1)] && run.after_determiner) {
+            continue;
+        }
+        out.push_str(&text[copied..run.start]);
+        let at = out.len();
+        out.push_str(&cardinal(&run.words).to_string());
+        edits.push((run.start..run.end, at..out.len()));
+        copied = run.end;
+    }
+    if copied == 0 {
+        return (Cow::Borrowed(text), edits);
+    }
+    out.push_str(&text[copied..]);
+    (Cow::Owned(out), edits)
+}
+
+/// Offset in the original text of `pos` in the rewritten one; positions
+/// inside a rewritten number snap to its start, or its end if `end`.
+fn original_offset(edits: &[(Range<usize>, Range<usize>)], pos: usize, end: bool) -> usize {
+    let mut shift = 0isize;
+    for (original, rewritten) in edits {
+        if pos <= rewritten.start {
+            break;
+        }
+        if pos < rewritten.end {
+            return if end { original.end } else { original.start };
+        }
+        shift = original.end as isize - rewritten.end as isize;
+    }
+    pos.saturating_add_signed(shift)
 }
 
 fn label_to_key(label: &str) -> &'static str {
     let l = label.to_lowercase();
     let l = l.trim();
-    match &*l {
+    match l {
         "deaths" | "dead" | "killed" => "deaths",
         "displaced" => "displaced",
         "injured" => "injured",
@@ -56,11 +776,211 @@ fn label_to_key(label: &str) -> &'static str {
         }
         "children" => "children_affected",
         "schools" => "schools_affected",
+        "cattle" | "livestock" | "goats" | "sheep" | "camels" => "livestock_lost",
+        "ha" => "crops_hectares_affected",
+        "bridges" => "bridges_damaged",
+        "substations" | "transformers" | "pylons" => "power_assets_affected",
+        _ if l.starts_with("power") || l.starts_with("electricity") => "power_assets_affected",
+        _ if l.ends_with("road") || l.ends_with("roads") || l.starts_with("highway") || l.contains(" highway") => {
+            "roads_km_damaged"
+        }
         _ if l.starts_with("health") => "health_facilities_affected",
+        _ if l.starts_with("hectare") || l.starts_with("acre") => "crops_hectares_affected",
+        _ if l.starts_with("head") => "livestock_lost",
+        _ => "people_affected",
+    }
+}
+
+/// Key for a percentage of `subject`: "80% of households" is
+/// `pct_households_affected`.
+fn percent_key(subject: &str) -> &'static str {
+    let s = subject.to_lowercase();
+    match s.as_str() {
+        "households" | "families" => "pct_households_affected",
+        "houses" | "homes" | "buildings" | "dwellings" => "pct_houses_affected",
+        "children" => "pct_children_affected",
+        "schools" => "pct_schools_affected",
+        "crops" | "cropland" | "farmland" | "harvest" | "fields" => "pct_crops_affected",
+        "livestock" | "cattle" => "pct_livestock_lost",
+        "people" | "persons" | "population" | "residents" | "inhabitants" | "individuals" => "pct_people_affected",
+        _ if s.starts_with("health") => "pct_health_facilities_affected",
+        _ => "pct_area_affected",
+    }
+}
+
+fn label_to_key_fr(label: &str) -> &'static str {
+    let l = label.to_lowercase();
+    let l = l.trim();
+    match l {
+        "décès" => "deaths",
+        "maisons" | "habitations" | "logements" => "houses_affected",
+        "enfants" => "children_affected",
+        "écoles" => "schools_affected",
+        "ha" => "crops_hectares_affected",
+        _ if l.starts_with("mort") || l.starts_with("tué") || l.starts_with("décédé") => "deaths",
+        _ if l.starts_with("déplacé") => "displaced",
+        _ if l.starts_with("blessé") => "injured",
+        _ if l.starts_with("disparu") => "missing",
+        _ if l.starts_with("centres") || l.starts_with("formations") => "health_facilities_affected",
+        _ if l.starts_with("hectare") => "crops_hectares_affected",
+        _ if ["têtes", "bétail", "bovins", "caprins", "ovins", "chèvres", "moutons"].iter().any(|w| l.starts_with(w)) => {
+            "livestock_lost"
+        }
         _ => "people_affected",
     }
 }
 
+/// Portuguese label prefix → key; other labels are people affected.
+const LABEL_KEYS_PT: &[(&str, &str)] = &[
+    ("mort", "deaths"),
+    ("óbito", "deaths"),
+    ("vítimas", "deaths"),
+    ("desaloj", "displaced"),
+    ("desloc", "displaced"),
+    ("ferid", "injured"),
+    ("desaparecid", "missing"),
+    ("casas", "houses_affected"),
+    ("residências", "houses_affected"),
+    ("habitações", "houses_affected"),
+    ("crianças", "children_affected"),
+    ("escolas", "schools_affected"),
+    ("unidades", "health_facilities_affected"),
+    ("centros", "health_facilities_affected"),
+    ("hectare", "crops_hectares_affected"),
+    ("ha", "crops_hectares_affected"),
+    ("cabeças", "livestock_lost"),
+    ("gado", "livestock_lost"),
+    ("bovinos", "livestock_lost"),
+    ("caprinos", "livestock_lost"),
+    ("cabritos", "livestock_lost"),
+    ("ovinos", "livestock_lost"),
+];
+
+const PERCENT_KEYS_PT: &[(&str, &str)] = &[
+    ("agregados", "pct_households_affected"),
+    ("famílias", "pct_households_affected"),
+    ("pessoas", "pct_people_affected"),
+    ("população", "pct_people_affected"),
+    ("habitantes", "pct_people_affected"),
+    ("casas", "pct_houses_affected"),
+    ("habitações", "pct_houses_affected"),
+    ("crianças", "pct_children_affected"),
+    ("escolas", "pct_schools_affected"),
+    ("culturas", "pct_crops_affected"),
+    ("colheitas", "pct_crops_affected"),
+    ("machambas", "pct_crops_affected"),
+    ("campos", "pct_crops_affected"),
+    ("gado", "pct_livestock_lost"),
+];
+
+/// Spanish label prefix → key; other labels are people affected.
+const LABEL_KEYS_ES: &[(&str, &str)] = &[
+    ("muert", "deaths"),
+    ("fallecid", "deaths"),
+    ("víctimas", "deaths"),
+    ("desplazad", "displaced"),
+    ("evacuad", "displaced"),
+    ("herid", "injured"),
+    ("desaparecid", "missing"),
+    ("casas", "houses_affected"),
+    ("viviendas", "houses_affected"),
+    ("niñ", "children_affected"),
+    ("escuelas", "schools_affected"),
+    ("centros", "health_facilities_affected"),
+    ("hectárea", "crops_hectares_affected"),
+    ("ha", "crops_hectares_affected"),
+    ("cabezas", "livestock_lost"),
+    ("ganado", "livestock_lost"),
+    ("reses", "livestock_lost"),
+    ("bovinos", "livestock_lost"),
+];
+
+const PERCENT_KEYS_ES: &[(&str, &str)] = &[
+    ("hogares", "pct_households_affected"),
+    ("familias", "pct_households_affected"),
+    ("personas", "pct_people_affected"),
+    ("población", "pct_people_affected"),
+    ("habitantes", "pct_people_affected"),
+    ("casas", "pct_houses_affected"),
+    ("viviendas", "pct_houses_affected"),
+    ("niños", "pct_children_affected"),
+    ("escuelas", "pct_schools_affected"),
+    ("cultivos", "pct_crops_affected"),
+    ("cosechas", "pct_crops_affected"),
+    ("campos", "pct_crops_affected"),
+    ("ganado", "pct_livestock_lost"),
+];
+
+/// Key of the first `(prefix, key)` entry `label` starts with, else
+/// `default`. "ha" must be the whole label.
+fn key_by_prefix(label: &str, table: &[(&str, &'static str)], default: &'static str) -> &'static str {
+    let label = label.trim().to_lowercase();
+    table
+        .iter()
+        .find(|(prefix, _)| if *prefix == "ha" { label == "ha" } else { label.starts_with(prefix) })
+        .map_or(default, |(_, key)| key)
+}
+
+fn percent_key_fr(subject: &str) -> &'static str {
+    let s = subject.to_lowercase();
+    match s.as_str() {
+        "ménages" | "familles" => "pct_households_affected",
+        "maisons" | "habitations" | "logements" => "pct_houses_affected",
+        "enfants" => "pct_children_affected",
+        "écoles" => "pct_schools_affected",
+        "cultures" | "récoltes" | "champs" => "pct_crops_affected",
+        "bétail" | "cheptel" => "pct_livestock_lost",
+        "personnes" | "population" | "habitants" => "pct_people_affected",
+        _ if s.starts_with("terres") => "pct_crops_affected",
+        _ if s.starts_with("centres") => "pct_health_facilities_affected",
+        _ => "pct_area_affected",
+    }
+}
+
+/// Keys a negated word says are zero: "no casualties" is no deaths and
+/// no injuries.
+fn negated_keys(word: &str) -> &'static [&'static str] {
+    match word.to_lowercase().as_str() {
+        "casualties" => &["deaths", "injured"],
+        "injuries" | "injured" | "hurt" => &["injured"],
+        "displaced" => &["displaced"],
+        "missing" => &["missing"],
+        _ => &["deaths"],
+    }
+}
+
+/// `negated_keys` for French, Portuguese and Spanish words: "aucune
+/// victime" is no deaths and no injuries, "sem vítimas mortais" no deaths.
+fn negated_keys_romance(word: &str) -> &'static [&'static str] {
+    let word = word.to_lowercase();
+    let has = |stems: &[&str]| stems.iter().any(|stem| word.starts_with(stem));
+    if word.contains("mort") {
+        &["deaths"]
+    } else if has(&["victim", "vítim", "víctim"]) {
+        &["deaths", "injured"]
+    } else if has(&["bless", "ferid", "herid"]) {
+        &["injured"]
+    } else if has(&["déplac", "desaloj", "desloc", "desplaz", "evacu"]) {
+        &["displaced"]
+    } else if has(&["dispar", "desaparec"]) {
+        &["missing"]
+    } else {
+        &["deaths"]
+    }
+}
+
+/// `value` in the unit of the label's key: acres become hectares and
+/// miles kilometres.
+fn in_key_unit(label: &str, value: i64) -> i64 {
+    let label = label.to_lowercase();
+    let factor = match label.split_whitespace().next() {
+        Some(unit) if unit.starts_with("acre") => HECTARES_PER_ACRE,
+        Some("mi" | "mile" | "miles") => KM_PER_MILE,
+        _ => return value,
+    };
+    (value as f64 * factor).round() as i64
+}
+
 fn accum(figures: &mut HashMap<String, i64>, key: &str, value: i64) {
     let entry = figures.entry(key.to_string()).or_insert(0);
     if value > *entry {
@@ -72,71 +992,368 @@ fn accum(figures: &mut HashMap<String, i64>, key: &str, value: i64) {
 ///
 /// Returns a dict mapping figure keys (deaths, displaced, people_affected, etc.)
 /// to their maximum observed integer values. Uses max() accumulation to prevent
-/// double-counting across overlapping patterns.
+/// double-counting across overlapping patterns. Magnitude words are applied:
+/// "1.2 million displaced" gives 1200000, as do "1.2m" and "1,200k". Spelled-out
+/// numbers count too: "five hundred families displaced". Ranges give bounds
+/// instead of one endpoint: "between 50 and 70 people dead" is
+/// ``{"deaths_min": 50, "deaths_max": 70}``. Shares are kept as
+/// percentages under ``pct_`` keys: "80% of households lost their homes" is
+/// ``{"pct_households_affected": 80}``. French, Portuguese and Spanish text
+/// have their own patterns and number formats ("au moins 45 morts",
+/// "1.200 casas", "2,5 milhões de pessoas") giving the same keys.
+/// Infrastructure is counted in English: "14 bridges washed away" is
+/// ``bridges_damaged``, "230 km of road damaged" ``roads_km_damaged``
+/// (miles are converted) and "three substations offline"
+/// ``power_assets_affected``. Numbers in negated statements are ignored
+/// ("2 districts reported no deaths"), and the statement itself gives an
+/// explicit 0: "no deaths have been reported", "aucun mort signalé",
+/// "nenhum morto" and "ningún muerto" are all ``{"deaths": 0}``.
 ///
 /// Parameters
 /// ----------
 /// text : str
 ///     The evidence text to extract figures from.
+/// lang : str
+///     Language of ``text``: ``"en"`` (default), ``"fr"``, ``"pt"`` or
+///     ``"es"``; region tags such as ``"pt-MZ"`` are accepted.
+/// negated_zero : bool
+///     Give 0 for keys a statement negates (default). A figure found
+///     elsewhere in the text still wins. With False, negated keys are
+///     left out.
 ///
 /// Returns
 /// -------
 /// dict[str, int]
 ///     Extracted figures, e.g. {"deaths": 59, "displaced": 16000}.
+///
+/// Raises
+/// ------
+/// ConfigError
+///     If ``lang`` has no pattern set (``reason`` ``"invalid_value"``).
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (text, lang="en", negated_zero=true))]
+pub fn extract_figures(py: Python<'_>, text: &str, lang: &str, negated_zero: bool) -> PyResult<Py<PyDict>> {
+    let _timer = timer("extract_figures");
+    let lang = language(lang)?;
+    let figures = unlocked(py, "extract_figures", || figures_in(text, lang, negated_zero))?;
+    let dict = PyDict::new_bound(py);
+    for (k, v) in &figures {
+        dict.set_item(k, *v)?;
+    }
+    Ok(dict.unbind())
+}
+
+/// Figures of many texts, in parallel with the GIL released.
+///
+/// Parameters
+/// ----------
+/// texts : list[str] | numpy.ndarray | pandas.Series | polars.Series
+///     Texts to extract from; missing values give no figures.
+/// lang, negated_zero
+///     As for ``extract_figures``, applied to every text.
+///
+/// Returns
+/// -------
+/// list[dict[str, int]] | numpy.ndarray
+///     One ``extract_figures`` dict per text, in input order; an
+///     ``object`` array when ``texts`` is array-like.
+///
+/// Raises
+/// ------
+/// ConfigError
+///     If ``lang`` has no pattern set (``reason`` ``"invalid_value"``).
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (texts, lang="en", negated_zero=true))]
+pub fn extract_figures_batch(py: Python<'_>, texts: Texts, lang: &str, negated_zero: bool) -> PyResult<PyObject> {
+    let _timer = timer("extract_figures_batch");
+    let lang = language(lang)?;
+    let figures: Vec<HashMap<String, i64>> = unlocked(py, "extract_figures_batch", || {
+        texts.values.par_iter().map(|t| figures_in(t, lang, negated_zero)).collect()
+    })?;
+    object_column(py, figures.into_iter().map(|f| f.into_py(py)).collect(), texts.array)
+}
+
+/// The pattern set for a ``lang`` argument.
+#[cfg(feature = "python")]
+pub(crate) fn language(lang: &str) -> PyResult<Language> {
+    Language::from_code(lang).ok_or_else(|| {
+        let message = format!("no figure patterns for language {lang:?}; expected \"en\", \"fr\", \"pt\" or \"es\"");
+        config_error(None, Failure::new("invalid_value", message))
+    })
+}
+
+/// An extracted figure with its provenance, from
+/// ``extract_figures_detailed``.
+///
+/// Attributes
+/// ----------
+/// key : str
+///     Figure key, as in ``extract_figures``.
+/// value : int
+/// start, end : int
+///     Character offsets of the matched text (``text[start:end]``).
+/// byte_start, byte_end : int
+///     The same span in UTF-8 bytes.
+/// pattern : str
+///     Pattern that matched: ``"range"``, ``"percentage"``, ``"number"``,
+///     ``"toll"``, ``"at_least"``, ``"sentence"``, ``"custom"`` (see
+///     ``configure_figure_keywords``), or ``"negation"`` for the 0 of a
+///     negated statement.
+/// text : str
+///     The matched text.
+/// sentence : str
+///     The sentence (or sentences) containing the match.
+#[cfg(feature = "python")]
+#[pyclass(module = "moltis_rust_core", frozen, get_all)]
+#[derive(Debug, Clone)]
+pub struct Figure {
+    key: String,
+    value: i64,
+    start: usize,
+    end: usize,
+    byte_start: usize,
+    byte_end: usize,
+    pattern: &'static str,
+    text: String,
+    sentence: String,
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl Figure {
+    fn __repr__(&self) -> PyResult<String> {
+        guarded("Figure.__repr__", || {
+            Ok(format!(
+                "Figure(key={:?}, value={}, start={}, end={}, pattern={:?})",
+                self.key, self.value, self.start, self.end, self.pattern
+            ))
+        })
+    }
+}
+
+/// Extract figures with provenance, for review.
+///
+/// Runs the same patterns as ``extract_figures`` but returns every match
+/// rather than the maximum per key, so a reviewer can see where each
+/// value came from and which pattern read it. A figure found by several
+/// patterns appears once per pattern.
+///
+/// Parameters
+/// ----------
+/// text : str
+///     The evidence text to extract figures from.
+/// lang : str
+///     Language of ``text``, as for ``extract_figures``.
+///
+/// Returns
+/// -------
+/// list[Figure]
+///     Matches in text order; spans refer to ``text`` as given, including
+///     for spelled-out numbers ("twelve people killed").
+///
+/// Raises
+/// ------
+/// ConfigError
+///     If ``lang`` has no pattern set (``reason`` ``"invalid_value"``).
+#[cfg(feature = "python")]
 #[pyfunction]
-pub fn extract_figures(py: Python<'_>, text: &str) -> PyResult<Py<PyDict>> {
+#[pyo3(signature = (text, lang="en"))]
+pub fn extract_figures_detailed(py: Python<'_>, text: &str, lang: &str) -> PyResult<Vec<Figure>> {
+    let _timer = timer("extract_figures_detailed");
+    let lang = language(lang)?;
+    unlocked(py, "extract_figures_detailed", || {
+        let mut found = matches(text, lang);
+        found.sort_by_key(|f| (f.span.start, f.span.end));
+        record_figures(&found.iter().map(|f| f.key.clone()).collect::<BTreeSet<_>>());
+        let sentences: Vec<(usize, &str)> = text.split_sentence_bound_indices().collect();
+        let sentence_at = |byte: usize| sentences.partition_point(|(start, _)| *start <= byte).saturating_sub(1);
+        found
+            .into_iter()
+            .map(|f| {
+                let (first, last) = (sentence_at(f.span.start), sentence_at(f.span.end.saturating_sub(1)));
+                let sentence_end = sentences[last].0 + sentences[last].1.len();
+                Figure {
+                    start: text[..f.span.start].chars().count(),
+                    end: text[..f.span.end].chars().count(),
+                    text: text[f.span.clone()].to_string(),
+                    sentence: text[sentences[first].0..sentence_end].trim().to_string(),
+                    byte_start: f.span.start,
+                    byte_end: f.span.end,
+                    key: f.key,
+                    value: f.value,
+                    pattern: f.pattern,
+                }
+            })
+            .collect()
+    })
+}
+
+/// One figure found in the text and where: `span` is a byte range of
+/// the original text, `pattern` the pattern that matched.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FigureMatch {
+    pub key: String,
+    pub value: i64,
+    pub span: Range<usize>,
+    pub pattern: &'static str,
+}
+
+/// Whether negated statements give 0 for their keys when a caller
+/// doesn't say; the `negated_zero=true` of the Python entry points matches.
+pub(crate) const NEGATED_ZERO: bool = true;
+
+/// Figure key → maximum value found in English `text`, negations as by
+/// default.
+pub(crate) fn figures(text: &str) -> HashMap<String, i64> {
+    figures_in(text, Language::English, NEGATED_ZERO)
+}
+
+/// Figure key → maximum value found in `text`, written in `lang`; with
+/// `negated_zero`, negated statements give 0 for their keys.
+pub(crate) fn figures_in(text: &str, lang: Language, negated_zero: bool) -> HashMap<String, i64> {
     let mut figures: HashMap<String, i64> = HashMap::new();
+    for found in matches(text, lang) {
+        if negated_zero || found.pattern != "negation" {
+            accum(&mut figures, &found.key, found.value);
+        }
+    }
+    record_figures(figures.keys());
+    figures
+}
+
+/// Every figure match in `text`, pattern by pattern.
+pub(crate) fn matches(text: &str, lang: Language) -> Vec<FigureMatch> {
+    let custom = CUSTOM_PATTERNS.read().unwrap_or_else(|e| e.into_inner());
+    matches_with(text, lang, custom.get(&lang).map_or(&[], Vec::as_slice))
+}
+
+/// `matches` with `custom` as the registered patterns.
+fn matches_with(text: &str, lang: Language, custom: &[CustomPattern]) -> Vec<FigureMatch> {
+    let p = lang.patterns();
+    // Number words are only read in English
+    let (rewritten, edits) = match lang {
+        Language::English => words_to_digits(text),
+        _ => (Cow::Borrowed(text), Vec::new()),
+    };
+    let mut text = rewritten.into_owned();
+    let negations: Vec<(Range<usize>, &[&str])> = p
+        .negation
+        .captures_iter(&text)
+        .map(|cap| (cap.get(0).unwrap().range(), (p.negated_keys)(cap.get(1).unwrap().as_str())))
+        .collect();
+    let mut found = Vec::new();
+    let mut push = |key: &str, value: i64, span: Range<usize>, pattern| {
+        found.push(FigureMatch { key: key.to_string(), value, span, pattern });
+    };
+
+    // Ranges first, as `<key>_min` / `<key>_max`, and percentages, as
+    // `pct_<subject>_...`; both are then blanked so the patterns below
+    // don't take an endpoint or a share as a count.
+    let mut blanked = Vec::new();
+    for cap in p.range.captures_iter(&text) {
+        let (lower, upper) = match (cap.get(1), cap.get(2)) {
+            (Some(lower), Some(upper)) => (lower, upper),
+            _ => (cap.get(3).unwrap(), cap.get(4).unwrap()),
+        };
+        if let Some((low, high)) = parse_range(lower.as_str(), upper.as_str(), lang) {
+            let key = (p.label_to_key)(&cap[5]);
+            let (low, high) = (in_key_unit(&cap[5], low), in_key_unit(&cap[5], high));
+            let span = cap.get(0).unwrap().range();
+            push(&format!("{key}_min"), low, span.clone(), "range");
+            push(&format!("{key}_max"), high, span.clone(), "range");
+            blanked.push(span);
+        }
+    }
+    for cap in p.percent.captures_iter(&text) {
+        let share: f64 = cap[1].replace(',', ".").parse().unwrap_or(f64::INFINITY);
+        if share <= 100.0 {
+            let span = cap.get(0).unwrap().range();
+            push((p.percent_key)(&cap[2]), share.round() as i64, span.clone(), "percentage");
+            blanked.push(span);
+        }
+    }
+    // Registered patterns, blanked too: their keywords are outside the
+    // built-in taxonomy
+    for pattern in custom {
+        for cap in pattern.regex.captures_iter(&text) {
+            if let Some(value) = cap.get(1).and_then(|num| parse_number(num.as_str(), lang)) {
+                let span = cap.get(0).unwrap().range();
+                push(&pattern.key, value, span.clone(), "custom");
+                blanked.push(span);
+            }
+        }
+    }
+    for range in blanked {
+        text.replace_range(range.clone(), &" ".repeat(range.len()));
+    }
+    let text = text.as_str();
 
     // Pattern 1: standard NUM + keyword
-    for cap in NUMBER_PATTERN.captures_iter(text) {
+    for cap in p.number.captures_iter(text) {
         if let (Some(num_match), Some(label_match)) = (cap.get(1), cap.get(2)) {
-            if let Some(value) = parse_number(num_match.as_str()) {
-                let key = label_to_key(label_match.as_str());
-                accum(&mut figures, key, value);
+            if let Some(value) = parse_number(num_match.as_str(), lang) {
+                let key = (p.label_to_key)(label_match.as_str());
+                let value = in_key_unit(label_match.as_str(), value);
+                push(key, value, cap.get(0).unwrap().range(), "number");
             }
         }
     }
 
     // Pattern 2: "death toll rises to 59" / "kills 4"
-    for cap in TOLL_PATTERN.captures_iter(text) {
+    for cap in p.toll.captures_iter(text) {
+        if p.not_people.is_match(&text[cap.get(0).unwrap().end()..]) {
+            continue;
+        }
         let raw = cap
             .get(1)
             .or_else(|| cap.get(2))
             .map(|m| m.as_str())
             .unwrap_or("");
-        if let Some(value) = parse_number(raw) {
+        if let Some(value) = parse_number(raw, lang) {
             if value > 0 {
-                accum(&mut figures, "deaths", value);
+                push("deaths", value, cap.get(0).unwrap().range(), "toll");
             }
         }
     }
 
     // Pattern 3: "at least 48,000 displaced"
-    for cap in ATLEAST_PATTERN.captures_iter(text) {
+    for cap in p.at_least.captures_iter(text) {
         if let (Some(num_match), Some(label_match)) = (cap.get(1), cap.get(2)) {
-            if let Some(value) = parse_number(num_match.as_str()) {
-                let key = label_to_key(label_match.as_str());
-                accum(&mut figures, key, value);
+            if let Some(value) = parse_number(num_match.as_str(), lang) {
+                let key = (p.label_to_key)(label_match.as_str());
+                let value = in_key_unit(label_match.as_str(), value);
+                push(key, value, cap.get(0).unwrap().range(), "at_least");
             }
         }
     }
 
     // Pattern 4: "59 killed" / "40 dead" in sentence context
-    for cap in SENTENCE_FIGURE_PATTERN.captures_iter(text) {
-        if let Some(num_match) = cap.get(1) {
-            if let Some(value) = parse_number(num_match.as_str()) {
+    for cap in p.sentence.captures_iter(text) {
+        if let Some(num_match) = cap.get(1).filter(|m| !p.not_people.is_match(&text[m.end()..])) {
+            if let Some(value) = parse_number(num_match.as_str(), lang) {
                 if value > 0 && value < 1_000_000 {
-                    accum(&mut figures, "deaths", value);
+                    push("deaths", value, cap.get(0).unwrap().range(), "sentence");
                 }
             }
         }
     }
 
-    let dict = PyDict::new_bound(py);
-    for (k, v) in &figures {
-        dict.set_item(k, *v)?;
+    // A number in a negated statement isn't a figure: "2 districts
+    // reported no deaths"
+    found.retain(|f| !negations.iter().any(|(span, _)| f.span.start < span.end && span.start < f.span.end));
+    for (span, keys) in negations {
+        for key in keys {
+            found.push(FigureMatch { key: key.to_string(), value: 0, span: span.clone(), pattern: "negation" });
+        }
     }
-    Ok(dict.unbind())
+
+    for figure in &mut found {
+        let span = &figure.span;
+        figure.span = original_offset(&edits, span.start, false)..original_offset(&edits, span.end, true);
+    }
+    found
 }
 
 #[cfg(test)]
@@ -144,46 +1361,7 @@ mod tests {
     use super::*;
 
     fn extract(text: &str) -> HashMap<String, i64> {
-        let mut figures: HashMap<String, i64> = HashMap::new();
-
-        for cap in NUMBER_PATTERN.captures_iter(text) {
-            if let (Some(num_match), Some(label_match)) = (cap.get(1), cap.get(2)) {
-                if let Some(value) = parse_number(num_match.as_str()) {
-                    let key = label_to_key(label_match.as_str());
-                    accum(&mut figures, key, value);
-                }
-            }
-        }
-        for cap in TOLL_PATTERN.captures_iter(text) {
-            let raw = cap
-                .get(1)
-                .or_else(|| cap.get(2))
-                .map(|m| m.as_str())
-                .unwrap_or("");
-            if let Some(value) = parse_number(raw) {
-                if value > 0 {
-                    accum(&mut figures, "deaths", value);
-                }
-            }
-        }
-        for cap in ATLEAST_PATTERN.captures_iter(text) {
-            if let (Some(num_match), Some(label_match)) = (cap.get(1), cap.get(2)) {
-                if let Some(value) = parse_number(num_match.as_str()) {
-                    let key = label_to_key(label_match.as_str());
-                    accum(&mut figures, key, value);
-                }
-            }
-        }
-        for cap in SENTENCE_FIGURE_PATTERN.captures_iter(text) {
-            if let Some(num_match) = cap.get(1) {
-                if let Some(value) = parse_number(num_match.as_str()) {
-                    if value > 0 && value < 1_000_000 {
-                        accum(&mut figures, "deaths", value);
-                    }
-                }
-            }
-        }
-        figures
+        figures(text)
     }
 
     #[test]
@@ -224,4 +1402,213 @@ mod tests {
         assert_eq!(r.get("deaths"), Some(&158));
         assert_eq!(r.get("displaced"), Some(&16000));
     }
+
+    #[test]
+    fn test_magnitude_words() {
+        let r = extract("1.2 million displaced and at least 3,500 killed; half a million affected");
+        assert_eq!(r.get("displaced"), Some(&1_200_000));
+        assert_eq!(r.get("deaths"), Some(&3_500));
+        assert_eq!(r.get("people_affected"), Some(&500_000));
+        assert_eq!(extract("over 20k people").get("people_affected"), Some(&20_000));
+        assert_eq!(extract("toll climbs to 1.5 thousand").get("deaths"), Some(&1_500));
+        // "m" and "k" only count as magnitudes standing alone
+        let r = extract("5 missing. 4 killed.");
+        assert_eq!((r.get("missing"), r.get("deaths")), (Some(&5), Some(&4)));
+        assert_eq!(parse_number("2.5m", Language::English), Some(2_500_000));
+        assert_eq!(parse_number("a million", Language::English), Some(1_000_000));
+    }
+
+    #[test]
+    fn test_spelled_out_numbers() {
+        let r = extract("Five hundred families displaced and twelve people killed");
+        assert_eq!(r.get("people_affected"), Some(&500));
+        assert_eq!(r.get("deaths"), Some(&12));
+        assert_eq!(extract("some two hundred and fifty thousand displaced").get("displaced"), Some(&250_000));
+        assert_eq!(extract("three million four hundred thousand affected").get("people_affected"), Some(&3_400_000));
+        assert_eq!(words_to_digits("twenty-five injured and one hundred and three missing").0, "25 injured and 103 missing");
+        // Pronouns and stray words stay as they are
+        assert_eq!(words_to_digits("No one was killed, a relief").0, "No one was killed, a relief");
+        assert!(figures_in("No one was killed", Language::English, false).is_empty());
+        assert_eq!(words_to_digits("the one hundred families").0, "the 100 families");
+    }
+
+    #[test]
+    fn test_ranges() {
+        let r = extract("between 50 and 70 people dead and 30–40 houses destroyed");
+        assert_eq!((r.get("deaths_min"), r.get("deaths_max")), (Some(&50), Some(&70)));
+        assert_eq!((r.get("houses_affected_min"), r.get("houses_affected_max")), (Some(&30), Some(&40)));
+        // Neither endpoint leaks into the single figures
+        assert_eq!((r.get("deaths"), r.get("houses_affected"), r.get("people_affected")), (None, None, None));
+        let r = extract("between 1.5 and 2 million displaced; five to ten injured");
+        assert_eq!((r["displaced_min"], r["displaced_max"]), (1_500_000, 2_000_000));
+        assert_eq!((r["injured_min"], r["injured_max"]), (5, 10));
+        assert_eq!(extract("in 2023-2022 killed").get("deaths_min"), None);
+    }
+
+    #[test]
+    fn test_negation() {
+        let zeros = |text| figures_in(text, Language::English, true);
+        assert_eq!(zeros("No deaths have been reported so far"), HashMap::from([("deaths".to_string(), 0)]));
+        // The nearby number isn't a death toll
+        let r = zeros("2 districts reported no deaths, and 300 displaced");
+        assert_eq!((r["deaths"], r["displaced"]), (0, 300));
+        assert!(!figures_in("2 districts reported no deaths", Language::English, false).contains_key("deaths"));
+        let r = zeros("No casualties in Beira; no one was reported missing");
+        assert_eq!((r["deaths"], r["injured"], r["missing"]), (0, 0, 0));
+        // A positive figure elsewhere wins
+        assert_eq!(zeros("No deaths in Beira, but 12 killed in Dondo")["deaths"], 12);
+    }
+
+    #[test]
+    fn test_negation_fr_pt_es() {
+        let zeros = |text, lang| figures_in(text, lang, true);
+        let fr = zeros("Aucun mort signalé, pas de blessés", Language::French);
+        assert_eq!((fr["deaths"], fr["injured"]), (0, 0));
+        // The nearby number isn't a death toll
+        let fr = zeros("2 districts n'ont signalé aucun mort, 300 personnes déplacées", Language::French);
+        assert_eq!((fr["deaths"], fr["displaced"]), (0, 300));
+        assert!(figures_in("2 districts n'ont signalé aucun mort", Language::French, false).is_empty());
+        let pt = zeros("Nenhum morto em Beira; sem vítimas mortais em Dondo", Language::Portuguese);
+        assert_eq!(pt, HashMap::from([("deaths".to_string(), 0)]));
+        assert_eq!(zeros("sem vítimas", Language::Portuguese)["injured"], 0);
+        let es = zeros("Ningún muerto; no se registraron heridos ni desaparecidos", Language::Spanish);
+        assert_eq!((es["deaths"], es["injured"]), (0, 0));
+        assert_eq!(zeros("sin víctimas mortales, pero 12 muertos en Lima", Language::Spanish)["deaths"], 12);
+    }
+
+    #[test]
+    fn test_percentages() {
+        let r = extract("80% of households lost their homes and 60 percent of the district flooded");
+        assert_eq!(r.get("pct_households_affected"), Some(&80));
+        assert_eq!(r.get("pct_area_affected"), Some(&60));
+        // Shares aren't counts
+        assert_eq!((r.get("people_affected"), r.get("houses_affected")), (None, None));
+        assert_eq!(extract("12.5 per cent of the affected population").get("pct_people_affected"), Some(&13));
+        assert!(!extract("70% of people killed were children").contains_key("deaths"));
+        assert!(extract("250% of the budget").is_empty());
+    }
+
+    #[test]
+    fn test_french() {
+        let fr = |text| figures_in(text, Language::French, false);
+        let r = fr("Au moins 45 morts et 12 000 personnes déplacées; 3\u{202f}500 maisons détruites");
+        assert_eq!((r.get("deaths"), r.get("displaced"), r.get("houses_affected")), (Some(&45), Some(&12_000), Some(&3_500)));
+        assert_eq!(fr("Le bilan s'élève à 1.250 décès").get("deaths"), Some(&1_250));
+        assert_eq!(fr("plus de 1,5 million de sinistrés").get("people_affected"), Some(&1_500_000));
+        assert_eq!(fr("2 millions de personnes déplacées").get("displaced"), Some(&2_000_000));
+        let r = fr("entre 50 et 70 morts; 80 % des ménages touchés");
+        assert_eq!((r["deaths_min"], r["deaths_max"], r["pct_households_affected"]), (50, 70, 80));
+        // A year before a count isn't a thousands group
+        assert_eq!(fr("en 2023 100 maisons").get("houses_affected"), Some(&100));
+        assert_eq!((Language::from_code("fr-CD"), Language::from_code("sw")), (Some(Language::French), None));
+    }
+
+    #[test]
+    fn test_portuguese_and_spanish() {
+        let pt = |text| figures_in(text, Language::Portuguese, false);
+        let r = pt("Pelo menos 45 mortos, 1.200 casas destruídas e 2,5 milhões de pessoas afectadas");
+        assert_eq!((r["deaths"], r["houses_affected"], r["people_affected"]), (45, 1_200, 2_500_000));
+        let r = pt("15 mil pessoas deslocadas; entre 10 e 20 feridos; 60% das machambas");
+        assert_eq!((r["displaced"], r["injured_min"], r["injured_max"], r["pct_crops_affected"]), (15_000, 10, 20, 60));
+        let es = |text| figures_in(text, Language::Spanish, false);
+        let r = es("al menos 30 muertos y 1.500 viviendas dañadas; 2,3 millones de damnificados");
+        assert_eq!((r["deaths"], r["houses_affected"], r["people_affected"]), (30, 1_500, 2_300_000));
+        assert_eq!(es("la cifra de muertos asciende a 1.024").get("deaths"), Some(&1_024));
+        assert_eq!(parse_number("2 mil milhões", Language::Portuguese), Some(2_000_000_000));
+    }
+
+    #[test]
+    fn test_agriculture() {
+        let r = extract("12,000 hectares of crops destroyed and 3,500 cattle lost; floods kill 200 goats");
+        assert_eq!(r.get("crops_hectares_affected"), Some(&12_000));
+        assert_eq!(r.get("livestock_lost"), Some(&3_500));
+        // Livestock deaths aren't people's
+        assert_eq!(r.get("deaths"), None);
+        assert!(!extract("3,500 cattle dead").contains_key("deaths"));
+        assert_eq!(extract("over 10,000 acres of farmland flooded").get("crops_hectares_affected"), Some(&4_047));
+        assert_eq!(extract("about 2,000 head of cattle").get("livestock_lost"), Some(&2_000));
+        assert_eq!(extract("5,000 ha flooded").get("crops_hectares_affected"), Some(&5_000));
+    }
+
+    #[test]
+    fn test_infrastructure() {
+        let r = extract("14 bridges washed away, 230 km of road damaged and three substations offline; 3 killed");
+        assert_eq!((r["bridges_damaged"], r["roads_km_damaged"], r["power_assets_affected"]), (14, 230, 3));
+        // Neither the bridges nor the substations are a death toll
+        assert_eq!(r["deaths"], 3);
+        assert_eq!(extract("over 50 miles of highways flooded").get("roads_km_damaged"), Some(&80));
+        assert_eq!(extract("120 power lines down").get("power_assets_affected"), Some(&120));
+        let r = extract("between 10 and 12 kilometres of the road washed out");
+        assert_eq!((r["roads_km_damaged_min"], r["roads_km_damaged_max"]), (10, 12));
+    }
+
+    #[cfg(feature = "python")]
+    #[test]
+    fn test_batch() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let values: Vec<String> = ["death toll rises to 59", "", "au moins 45 morts", "No deaths were reported"]
+                .map(String::from)
+                .to_vec();
+            for negated_zero in [true, false] {
+                let texts = Texts { values: values.clone(), array: false };
+                let batch = extract_figures_batch(py, texts, "en", negated_zero).unwrap();
+                let batch: Vec<HashMap<String, i64>> = batch.extract(py).unwrap();
+                let expected: Vec<_> = values.iter().map(|t| figures_in(t, Language::English, negated_zero)).collect();
+                assert_eq!(batch, expected);
+                assert_eq!(batch[3].get("deaths"), negated_zero.then_some(&0));
+            }
+            assert!(extract_figures_batch(py, Texts { values, array: false }, "xx", true).is_err());
+        });
+    }
+
+    #[test]
+    fn test_custom_patterns() {
+        let custom = [
+            keyword_pattern("boats  destroyed", "boats_destroyed", Language::English).unwrap(),
+            keyword_pattern("classrooms damaged", "classrooms_damaged", Language::English).unwrap(),
+            custom_pattern("wells_contaminated", r"(?i)(\d[\d,]*) wells? (?:were )?contaminated").unwrap(),
+        ];
+        let text = "Fourteen boats destroyed, 120 classrooms damaged and 1,200 wells were contaminated; 30 killed";
+        let found = matches_with(text, Language::English, &custom);
+        let found: Vec<(&str, i64, &str)> = found.iter().map(|f| (f.key.as_str(), f.value, f.pattern)).collect();
+        assert!(found.contains(&("boats_destroyed", 14, "custom")));
+        assert!(found.contains(&("classrooms_damaged", 120, "custom")));
+        assert!(found.contains(&("wells_contaminated", 1_200, "custom")));
+        assert!(found.contains(&("deaths", 30, "number")));
+        // Registered text isn't read again by the built-in patterns
+        assert!(!found.iter().any(|(key, _, _)| *key == "people_affected"));
+        let fr = keyword_pattern("bateaux détruits", "boats_destroyed", Language::French).unwrap();
+        assert_eq!(matches_with("1 200 bateaux détruits", Language::French, &[fr])[0].value, 1_200);
+        assert!(custom_pattern("wells", r"\d+ wells").is_err());
+        assert!(keyword_pattern(" ", "boats", Language::English).is_err());
+    }
+
+    #[cfg(feature = "python")]
+    #[test]
+    fn test_config_errors() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let patterns = vec![("wells".to_string(), r"\d+ wells".to_string())];
+            let err = configure_figure_keywords(None, Some(patterns), "en", false).unwrap_err();
+            assert!(err.is_instance_of::<crate::errors::ConfigError>(py));
+            assert_eq!(err.value_bound(py).getattr("reason").unwrap().extract::<String>().unwrap(), "invalid_value");
+            assert!(language("xx").unwrap_err().is_instance_of::<crate::errors::ConfigError>(py));
+            assert!(extract_figures(py, "12 killed", "de", true).unwrap_err().is_instance_of::<pyo3::exceptions::PyValueError>(py));
+        });
+    }
+
+    #[test]
+    fn test_match_spans() {
+        let text = "Twelve people killed. Toll rises to 59 in Beira; 30–40 houses lost";
+        let found = matches(text, Language::English);
+        let spans: Vec<(&str, i64, &str, &str)> =
+            found.iter().map(|f| (f.key.as_str(), f.value, f.pattern, &text[f.span.clone()])).collect();
+        // Spelled-out numbers map back to the words they were read from
+        assert!(spans.contains(&("deaths", 12, "sentence", "Twelve people killed")));
+        assert!(spans.contains(&("deaths", 59, "toll", "Toll rises to 59")));
+        assert!(spans.contains(&("houses_affected_max", 40, "range", "30–40 houses")));
+        assert_eq!(original_offset(&[(0..6, 0..2), (20..35, 16..19)], 17, false), 20);
+        assert_eq!(original_offset(&[(0..6, 0..2), (20..35, 16..19)], 25, true), 41);
+    }
 }
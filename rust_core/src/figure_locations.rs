@@ -0,0 +1,185 @@
+//! Figures attributed to the admin areas an article names.
+//!
+//! "34 dead in Sofala and 12 in Zambezia" gives `deaths: 34` from
+//! `extract_figures`, which keeps one value per key. Here each figure goes
+//! to the known area mentioned nearest to it in its sentence, or, in a
+//! sentence naming none, the last area named before it ("Sofala was
+//! hardest hit. 34 people died there."). A bare number continuing a list
+//! ("and 12 in Zambezia") takes the label of the figure before it.
+
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::errors::unlocked;
+use crate::figure_extraction::{matches, parse_number, FigureMatch, Language, NUM};
+use crate::text_classify::{area_mentions, sort_admin_areas};
+
+/// A number continuing a list of figures: "and 12 in", ", 5 in".
+static CONTINUATION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(&format!(r"(?i)(?:,|;|\band\b|\bwhile\b|\bwith\b)\s+{NUM}\s+(?:more\s+)?(?:in|at)\s")).unwrap());
+
+/// Patterns whose figures a continuation can take the label of.
+const CONTINUED: &[&str] = &["number", "toll", "at_least", "sentence"];
+
+/// `text` lowercased where that keeps byte offsets, so area mentions
+/// found in it line up with figure spans in `text`.
+fn lowercase_aligned(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            let lower: String = c.to_lowercase().collect();
+            if lower.len() == c.len_utf8() { lower } else { c.to_string() }
+        })
+        .collect()
+}
+
+/// Bytes between two spans; 0 when they overlap.
+fn gap(a: &Range<usize>, b: &Range<usize>) -> usize {
+    if a.end <= b.start {
+        b.start - a.end
+    } else {
+        a.start.saturating_sub(b.end)
+    }
+}
+
+/// Bare numbers continuing a list, labelled like the figure before them
+/// in the same sentence.
+fn continuations(text: &str, found: &[FigureMatch], sentences: &[Range<usize>]) -> Vec<FigureMatch> {
+    let sentence_of = |pos: usize| sentences.iter().position(|s| s.contains(&pos));
+    CONTINUATION
+        .captures_iter(text)
+        .filter_map(|cap| {
+            let number = cap.get(1)?;
+            let span = number.range();
+            if found.iter().any(|f| gap(&f.span, &span) == 0) {
+                return None;
+            }
+            let previous = found
+                .iter()
+                .filter(|f| CONTINUED.contains(&f.pattern) && f.span.end <= span.start)
+                .filter(|f| sentence_of(f.span.start) == sentence_of(span.start))
+                .max_by_key(|f| f.span.end)?;
+            let value = parse_number(number.as_str(), Language::English)?;
+            Some(FigureMatch { key: previous.key.clone(), value, span, pattern: "continuation" })
+        })
+        .collect()
+}
+
+/// Figures in `text` by the area they're attributed to; figures with no
+/// area named before or in their sentence are left out.
+pub(crate) fn by_location(
+    text: &str,
+    area_names: Vec<(String, i32)>,
+    lang: Language,
+    negated_zero: bool,
+) -> BTreeMap<String, HashMap<String, i64>> {
+    let mentions = area_mentions(&lowercase_aligned(text), &sort_admin_areas(area_names));
+    // Sentences of the original text: lowercasing hides their starts
+    let sentences: Vec<Range<usize>> = text.split_sentence_bound_indices().map(|(i, s)| i..i + s.len()).collect();
+    let mut found: Vec<FigureMatch> =
+        matches(text, lang).into_iter().filter(|f| negated_zero || f.pattern != "negation").collect();
+    if lang == Language::English {
+        let more = continuations(text, &found, &sentences);
+        found.extend(more);
+    }
+
+    let mut areas: BTreeMap<String, HashMap<String, i64>> = BTreeMap::new();
+    for figure in found {
+        let sentence = sentences.iter().find(|s| s.contains(&figure.span.start)).cloned().unwrap_or(0..text.len());
+        let nearest = mentions
+            .iter()
+            .filter(|(span, _, _)| sentence.contains(&span.start))
+            // Ties go to the area after the figure: "34 dead in Sofala"
+            .min_by_key(|(span, _, _)| (gap(span, &figure.span), span.start < figure.span.start))
+            .or_else(|| mentions.iter().rev().find(|(span, _, _)| span.end <= figure.span.start));
+        if let Some((_, name, _)) = nearest {
+            let best = areas.entry(name.clone()).or_default().entry(figure.key).or_insert(figure.value);
+            *best = (*best).max(figure.value);
+        }
+    }
+    areas
+}
+
+/// Extract figures per admin area named in the text.
+///
+/// Each figure (as ``extract_figures`` finds them) is attributed to the
+/// known area mentioned nearest to it in the same sentence, or, in a
+/// sentence naming none, to the last area named before it. A bare number
+/// continuing a list takes the label of the figure before it, so "34 dead
+/// in Sofala and 12 in Zambezia" gives 12 deaths in Zambezia. Repeated
+/// figures for one area keep their highest value.
+///
+/// Parameters
+/// ----------
+/// text : str
+///     Evidence text.
+/// area_names : list[tuple[str, int]]
+///     Known (area_name, admin_level) tuples, as for ``detect_admin_area``;
+///     country-level (0) names are ignored.
+/// lang : str
+///     Language of ``text``, as for ``extract_figures``. List
+///     continuations are only read in English.
+/// negated_zero : bool
+///     Give 0 for keys a statement negates, as in ``extract_figures``.
+///
+/// Returns
+/// -------
+/// dict[str, dict[str, int]]
+///     Area name → figures, e.g. ``{"Sofala": {"deaths": 34},
+///     "Zambezia": {"deaths": 12}}``. Figures with no area named before or
+///     in their sentence are left out.
+///
+/// Raises
+/// ------
+/// ConfigError
+///     If ``lang`` has no pattern set (``reason`` ``"invalid_value"``).
+#[pyfunction]
+#[pyo3(signature = (text, area_names, lang="en", negated_zero=true))]
+pub fn extract_figures_by_location(
+    py: Python<'_>,
+    text: &str,
+    area_names: Vec<(String, i32)>,
+    lang: &str,
+    negated_zero: bool,
+) -> PyResult<BTreeMap<String, HashMap<String, i64>>> {
+    let lang = crate::figure_extraction::language(lang)?;
+    unlocked(py, "extract_figures_by_location", || by_location(text, area_names, lang, negated_zero))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn areas() -> Vec<(String, i32)> {
+        [("Mozambique", 0), ("Sofala", 1), ("Zambezia", 1), ("Beira", 2), ("Cabo Delgado", 1)]
+            .iter()
+            .map(|(n, l)| (n.to_string(), *l))
+            .collect()
+    }
+
+    fn figures(text: &str) -> BTreeMap<String, HashMap<String, i64>> {
+        by_location(text, areas(), Language::English, true)
+    }
+
+    #[test]
+    fn test_figures_per_area() {
+        let r = figures("Cyclone Freddy left 34 dead in Sofala and 12 in Zambezia.");
+        assert_eq!((r["Sofala"]["deaths"], r["Zambezia"]["deaths"]), (34, 12));
+        let r = figures("In Sofala, 1,200 people were displaced, and in Zambezia 300 houses were destroyed.");
+        assert_eq!(r["Sofala"].get("people_affected"), Some(&1_200));
+        assert_eq!(r["Zambezia"].get("houses_affected"), Some(&300));
+        assert!(!r["Sofala"].contains_key("houses_affected"));
+    }
+
+    #[test]
+    fn test_carries_area_across_sentences() {
+        let r = figures("Beira was hardest hit. 20 people were killed. No deaths in Cabo Delgado.");
+        assert_eq!(r["Beira"]["deaths"], 20);
+        assert_eq!(r["Cabo Delgado"]["deaths"], 0);
+        // Nothing named before: left out
+        assert!(figures("5 killed overnight.").is_empty());
+    }
+}
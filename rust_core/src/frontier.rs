@@ -0,0 +1,498 @@
+//! URL frontier — priority queue with per-host interleaving.
+//!
+//! Entries are keyed by (priority, not-before time). URLs wait in a
+//! time-ordered heap until due, then move into their host's priority
+//! heap. `pop_ready` serves the host with the best head entry, breaking
+//! ties by least-recently-served host so one busy site can't starve the
+//! rest; an optional per-host delay keeps a host idle after each pop.
+//! "Host" here is the politeness key (`politeness_key`): the registrable
+//! domain, or a finer bucket for large shared platforms.
+//!
+//! With persistent storage every queued URL is also written there (URL ->
+//! priority, not-before time, sequence number) and removed when popped,
+//! so a restarted crawler reloads its queue in the original order.
+
+use pyo3::exceptions::{PyOSError, PyValueError};
+use pyo3::prelude::*;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::politeness::politeness_key_of;
+use crate::storage::{self, Storage};
+use crate::url_score::score_at;
+use crate::errors::guarded;
+
+/// `f64` with a total order, for heap keys.
+#[derive(Clone, Copy, PartialEq)]
+struct Key(f64);
+
+impl Eq for Key {}
+
+impl PartialOrd for Key {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Key {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Entry waiting for its not-before time (min-heap via `Reverse`).
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct Delayed {
+    not_before: Key,
+    seq: u64,
+    host: usize,
+    priority: Key,
+    url: String,
+}
+
+/// Due entry in a host queue: highest priority first, then FIFO.
+#[derive(PartialEq, Eq)]
+struct Ready {
+    priority: Key,
+    seq: u64,
+    url: String,
+}
+
+impl PartialOrd for Ready {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Ready {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+/// Snapshot of a host's head entry; stale once `version` moves on.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct HostSlot {
+    priority: Key,
+    last_served: Reverse<u64>,
+    host: usize,
+    version: u64,
+}
+
+struct HostQueue {
+    ready: BinaryHeap<Ready>,
+    last_served: u64,
+    version: u64,
+    cooling: bool,
+}
+
+pub(crate) fn now_secs() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0)
+}
+
+/// Priority queue of URLs to fetch, interleaved across hosts.
+///
+/// Parameters
+/// ----------
+/// host_delay : float
+///     Seconds a host stays ineligible after one of its URLs is popped.
+///     Default 0.0 (interleave only).
+/// storage : str | None
+///     ``"memory"``, ``"sqlite:PATH"`` or ``"mmap:PATH"``; queued URLs
+///     persist there and are reloaded on open. Default: the process
+///     default (see ``configure_storage``), ``frontier.db`` / ``.map``.
+///
+/// Times are Unix timestamps in seconds; ``now`` arguments default to the
+/// current time.
+#[pyclass(module = "moltis_rust_core")]
+pub struct Frontier {
+    host_delay: f64,
+    host_ids: HashMap<String, usize>,
+    hosts: Vec<HostQueue>,
+    delayed: BinaryHeap<Reverse<Delayed>>,
+    host_heap: BinaryHeap<HostSlot>,
+    cooldown: BinaryHeap<Reverse<(Key, usize)>>,
+    queued: HashSet<String>,
+    seq: u64,
+    served: u64,
+    store: Option<Box<dyn Storage>>,
+}
+
+/// Stored entry: priority, not-before time and sequence number, LE.
+fn encode_entry(priority: f64, not_before: f64, seq: u64) -> [u8; 24] {
+    let mut value = [0u8; 24];
+    value[..8].copy_from_slice(&priority.to_le_bytes());
+    value[8..16].copy_from_slice(&not_before.to_le_bytes());
+    value[16..].copy_from_slice(&seq.to_le_bytes());
+    value
+}
+
+fn decode_entry(value: &[u8]) -> Option<(f64, f64, u64)> {
+    let value: &[u8; 24] = value.try_into().ok()?;
+    let field = |i: usize| value[i..i + 8].try_into().unwrap();
+    Some((f64::from_le_bytes(field(0)), f64::from_le_bytes(field(8)), u64::from_le_bytes(field(16))))
+}
+
+impl Frontier {
+    pub(crate) fn new(host_delay: f64) -> Self {
+        Self {
+            host_delay: host_delay.max(0.0),
+            host_ids: HashMap::new(),
+            hosts: Vec::new(),
+            delayed: BinaryHeap::new(),
+            host_heap: BinaryHeap::new(),
+            cooldown: BinaryHeap::new(),
+            queued: HashSet::new(),
+            seq: 0,
+            served: 0,
+            store: None,
+        }
+    }
+
+    /// A frontier holding the URLs queued in `store`, in their old order.
+    fn with_store(host_delay: f64, store: Box<dyn Storage>) -> Result<Self, String> {
+        let mut entries: Vec<(u64, String, f64, f64)> = Vec::new();
+        for (url, value) in store.iterate(b"")? {
+            let (priority, not_before, seq) =
+                decode_entry(&value).ok_or_else(|| format!("{}: corrupt frontier entry", store.spec()))?;
+            entries.push((seq, String::from_utf8_lossy(&url).into_owned(), priority, not_before));
+        }
+        entries.sort_unstable_by_key(|e| e.0);
+        let stored_seq = entries.last().map_or(0, |e| e.0);
+        let mut frontier = Self::new(host_delay);
+        for (_, url, priority, not_before) in entries {
+            frontier.push_at(&url, priority, not_before)?;
+        }
+        // New entries are stored after every old one, not numbered from
+        // the reloaded count
+        frontier.seq = frontier.seq.max(stored_seq);
+        frontier.store = Some(store);
+        Ok(frontier)
+    }
+
+    /// `push_at`, recording a new entry in the store.
+    fn push_stored(&mut self, url: &str, priority: f64, not_before: f64) -> Result<bool, String> {
+        let added = self.push_at(url, priority, not_before)?;
+        if let (true, Some(store)) = (added, &mut self.store) {
+            store.put(url.as_bytes(), &encode_entry(priority, not_before, self.seq))?;
+        }
+        Ok(added)
+    }
+
+    fn host_id(&mut self, host: &str) -> usize {
+        if let Some(&id) = self.host_ids.get(host) {
+            return id;
+        }
+        let id = self.hosts.len();
+        self.hosts.push(HostQueue {
+            ready: BinaryHeap::new(),
+            last_served: 0,
+            version: 0,
+            cooling: false,
+        });
+        self.host_ids.insert(host.to_string(), id);
+        id
+    }
+
+    /// Re-publish a host's head entry after it changed.
+    fn refresh(&mut self, host: usize) {
+        let queue = &mut self.hosts[host];
+        queue.version += 1;
+        if queue.cooling {
+            return;
+        }
+        if let Some(head) = queue.ready.peek() {
+            self.host_heap.push(HostSlot {
+                priority: head.priority,
+                last_served: Reverse(queue.last_served),
+                host,
+                version: queue.version,
+            });
+        }
+    }
+
+    /// Move due entries into host queues and release cooled-down hosts.
+    fn promote(&mut self, now: f64) {
+        while self.delayed.peek().is_some_and(|Reverse(d)| d.not_before.0 <= now) {
+            let Reverse(d) = self.delayed.pop().unwrap();
+            self.hosts[d.host].ready.push(Ready {
+                priority: d.priority,
+                seq: d.seq,
+                url: d.url,
+            });
+            self.refresh(d.host);
+        }
+        while self.cooldown.peek().is_some_and(|Reverse((t, _))| t.0 <= now) {
+            let Reverse((_, host)) = self.cooldown.pop().unwrap();
+            self.hosts[host].cooling = false;
+            self.refresh(host);
+        }
+    }
+
+    pub(crate) fn push_at(&mut self, url: &str, priority: f64, not_before: f64) -> Result<bool, String> {
+        let host = politeness_key_of(url).ok_or_else(|| format!("frontier URL has no host: {url:?}"))?;
+        if !self.queued.insert(url.to_string()) {
+            return Ok(false);
+        }
+        let host = self.host_id(&host);
+        self.seq += 1;
+        self.delayed.push(Reverse(Delayed {
+            not_before: Key(not_before),
+            seq: self.seq,
+            host,
+            priority: Key(priority),
+            url: url.to_string(),
+        }));
+        Ok(true)
+    }
+
+    pub(crate) fn pop_at(&mut self, now: f64) -> Option<(String, f64)> {
+        self.promote(now);
+        while let Some(slot) = self.host_heap.pop() {
+            let host = slot.host;
+            if slot.version != self.hosts[host].version {
+                continue;
+            }
+            self.served += 1;
+            let queue = &mut self.hosts[host];
+            let entry = queue.ready.pop()?;
+            queue.last_served = self.served;
+            if self.host_delay > 0.0 {
+                queue.cooling = true;
+                self.cooldown.push(Reverse((Key(now + self.host_delay), host)));
+            }
+            self.refresh(host);
+            self.queued.remove(&entry.url);
+            return Some((entry.url, entry.priority.0));
+        }
+        None
+    }
+
+    fn earliest_ready(&self) -> Option<f64> {
+        let live_slot = self
+            .host_heap
+            .iter()
+            .any(|s| s.version == self.hosts[s.host].version);
+        if live_slot {
+            return Some(f64::NEG_INFINITY);
+        }
+        let delayed = self.delayed.peek().map(|Reverse(d)| d.not_before.0);
+        let cooled = self
+            .cooldown
+            .iter()
+            .filter(|Reverse((_, h))| !self.hosts[*h].ready.is_empty())
+            .map(|Reverse((t, _))| t.0)
+            .min_by(f64::total_cmp);
+        match (delayed, cooled) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+#[pymethods]
+impl Frontier {
+    #[new]
+    #[pyo3(signature = (host_delay=0.0, storage=None))]
+    fn py_new(host_delay: f64, storage: Option<&str>) -> PyResult<Self> {
+        guarded("Frontier.__new__", || {
+            match storage::open_for(storage, "frontier", 1000)? {
+                Some(store) => Self::with_store(host_delay, store).map_err(PyOSError::new_err),
+                None => Ok(Self::new(host_delay)),
+            }
+        })
+    }
+
+    /// Queue a URL. Higher ``priority`` is served first; when omitted it
+    /// is computed with ``score_url``.
+    ///
+    /// Returns False if the URL is already queued.
+    #[pyo3(signature = (url, priority=None, not_before=0.0))]
+    fn push(&mut self, url: &str, priority: Option<f64>, not_before: f64) -> PyResult<bool> {
+        guarded("Frontier.push", || {
+            let priority = priority.unwrap_or_else(|| score_at(url, now_secs()));
+            politeness_key_of(url).ok_or_else(|| PyValueError::new_err(format!("frontier URL has no host: {url:?}")))?;
+            self.push_stored(url, priority, not_before).map_err(PyOSError::new_err)
+        })
+    }
+
+    /// Pop the best due URL as ``(url, priority)``, or None if nothing is ready.
+    #[pyo3(signature = (now=None))]
+    fn pop_ready(&mut self, now: Option<f64>) -> PyResult<Option<(String, f64)>> {
+        guarded("Frontier.pop_ready", || {
+            let popped = self.pop_at(now.unwrap_or_else(now_secs));
+            if let (Some((url, _)), Some(store)) = (&popped, &mut self.store) {
+                store.remove(url.as_bytes()).map_err(PyOSError::new_err)?;
+            }
+            Ok(popped)
+        })
+    }
+
+    /// Put a popped URL back, eligible again after ``delay`` seconds.
+    #[pyo3(signature = (url, priority, delay=0.0, now=None))]
+    fn requeue(&mut self, url: &str, priority: f64, delay: f64, now: Option<f64>) -> PyResult<bool> {
+        guarded("Frontier.requeue", || {
+            let due = now.unwrap_or_else(now_secs) + delay.max(0.0);
+            politeness_key_of(url).ok_or_else(|| PyValueError::new_err(format!("frontier URL has no host: {url:?}")))?;
+            self.push_stored(url, priority, due).map_err(PyOSError::new_err)
+        })
+    }
+
+    /// Earliest time ``pop_ready`` can return a URL (-inf if one is ready
+    /// now), or None when the frontier is empty.
+    fn next_ready_time(&self) -> PyResult<Option<f64>> {
+        guarded("Frontier.next_ready_time", || Ok(self.earliest_ready()))
+    }
+
+    fn __contains__(&self, url: &str) -> PyResult<bool> {
+        guarded("Frontier.__contains__", || Ok(self.queued.contains(url)))
+    }
+
+    fn __len__(&self) -> PyResult<usize> {
+        guarded("Frontier.__len__", || Ok(self.queued.len()))
+    }
+
+    /// Number of distinct hosts (politeness keys) seen.
+    #[getter]
+    fn host_count(&self) -> PyResult<usize> {
+        guarded("Frontier.host_count", || Ok(self.hosts.len()))
+    }
+
+    /// Write pending queue changes to storage (no-op in memory).
+    fn flush(&mut self) -> PyResult<()> {
+        guarded("Frontier.flush", || {
+            match &mut self.store {
+                Some(store) => store.flush().map_err(PyOSError::new_err),
+                None => Ok(()),
+            }
+        })
+    }
+
+    /// Storage spec, e.g. ``"sqlite:state/frontier.db"``, or ``"memory"``.
+    #[getter]
+    fn storage(&self) -> PyResult<String> {
+        guarded("Frontier.storage", || Ok(self.store.as_ref().map_or("memory".into(), |s| s.spec())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain(f: &mut Frontier, now: f64) -> Vec<String> {
+        std::iter::from_fn(|| f.pop_at(now).map(|(u, _)| u)).collect()
+    }
+
+    #[test]
+    fn test_priority_order() {
+        let mut f = Frontier::new(0.0);
+        f.push_at("https://a.org/low", 1.0, 0.0).unwrap();
+        f.push_at("https://a.org/high", 5.0, 0.0).unwrap();
+        assert_eq!(drain(&mut f, 1.0), vec!["https://a.org/high", "https://a.org/low"]);
+    }
+
+    #[test]
+    fn test_host_interleaving() {
+        let mut f = Frontier::new(0.0);
+        for i in 0..3 {
+            f.push_at(&format!("https://a.org/{i}"), 1.0, 0.0).unwrap();
+        }
+        f.push_at("https://b.org/0", 1.0, 0.0).unwrap();
+        f.push_at("https://b.org/1", 1.0, 0.0).unwrap();
+        let hosts: Vec<String> = drain(&mut f, 1.0).iter().map(|u| u[8..13].to_string()).collect();
+        // Hosts alternate while both have due URLs
+        assert_ne!(hosts[0], hosts[1], "got {hosts:?}");
+        assert_ne!(hosts[1], hosts[2], "got {hosts:?}");
+        assert_ne!(hosts[2], hosts[3], "got {hosts:?}");
+    }
+
+    #[test]
+    fn test_not_before_and_dedup() {
+        let mut f = Frontier::new(0.0);
+        assert!(f.push_at("https://a.org/x", 1.0, 100.0).unwrap());
+        assert!(!f.push_at("https://a.org/x", 9.0, 0.0).unwrap());
+        assert_eq!(f.pop_at(50.0), None);
+        assert_eq!(f.earliest_ready(), Some(100.0));
+        assert_eq!(f.pop_at(100.0), Some(("https://a.org/x".to_string(), 1.0)));
+        assert_eq!(f.earliest_ready(), None);
+    }
+
+    #[test]
+    fn test_host_delay() {
+        let mut f = Frontier::new(10.0);
+        f.push_at("https://a.org/1", 1.0, 0.0).unwrap();
+        f.push_at("https://a.org/2", 1.0, 0.0).unwrap();
+        assert!(f.pop_at(0.0).is_some());
+        assert_eq!(f.pop_at(5.0), None);
+        assert_eq!(f.earliest_ready(), Some(10.0));
+        assert!(f.pop_at(10.0).is_some());
+    }
+
+    #[test]
+    fn test_requeue_after_pop() {
+        let mut f = Frontier::new(0.0);
+        f.push_at("https://a.org/1", 1.0, 0.0).unwrap();
+        let (url, _) = f.pop_at(0.0).unwrap();
+        assert!(f.push_at(&url, 2.0, 30.0).unwrap());
+        assert_eq!(f.pop_at(29.0), None);
+        assert_eq!(f.pop_at(30.0), Some((url, 2.0)));
+    }
+
+    #[test]
+    fn test_invalid_url() {
+        assert!(Frontier::new(0.0).push_at("not a url", 1.0, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_reload_from_storage() {
+        let path = std::env::temp_dir().join(format!("moltis_frontier_{}.map", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let open = || storage::open_for(Some(&format!("mmap:{}", path.display())), "frontier", 1).unwrap().unwrap();
+        let mut f = Frontier::with_store(0.0, open()).unwrap();
+        for (url, priority) in [("https://a.org/1", 1.0), ("https://a.org/2", 1.0), ("https://b.org/1", 3.0)] {
+            f.push_stored(url, priority, 0.0).unwrap();
+        }
+        assert!(!f.push_stored("https://a.org/1", 9.0, 0.0).unwrap());
+        f.push_stored("https://c.org/later", 1.0, 100.0).unwrap();
+        f.pop_ready(Some(0.0)).unwrap();
+        drop(f);
+
+        let mut f = Frontier::with_store(0.0, open()).unwrap();
+        assert_eq!(f.__len__().unwrap(), 3);
+        // FIFO order within a priority survives the reload
+        assert_eq!(drain(&mut f, 0.0), vec!["https://a.org/1", "https://a.org/2"]);
+        assert_eq!(f.earliest_ready(), Some(100.0));
+    }
+
+    #[test]
+    fn test_order_across_restarts() {
+        let path = std::env::temp_dir().join(format!("moltis_frontier_restarts_{}.map", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let open = || storage::open_for(Some(&format!("mmap:{}", path.display())), "frontier", 1).unwrap().unwrap();
+        let mut f = Frontier::with_store(0.0, open()).unwrap();
+        for n in 1..=4 {
+            f.push_stored(&format!("https://a.org/{n}"), 1.0, 0.0).unwrap();
+        }
+        drop(f);
+
+        let mut f = Frontier::with_store(0.0, open()).unwrap();
+        for n in 1..=2 {
+            assert_eq!(f.pop_ready(Some(0.0)).unwrap().unwrap().0, format!("https://a.org/{n}"));
+        }
+        drop(f);
+        let mut f = Frontier::with_store(0.0, open()).unwrap();
+        f.push_stored("https://a.org/5", 1.0, 0.0).unwrap();
+        drop(f);
+
+        // The entry pushed after a restart still comes after the older ones
+        let mut f = Frontier::with_store(0.0, open()).unwrap();
+        assert_eq!(drain(&mut f, 0.0), vec!["https://a.org/3", "https://a.org/4", "https://a.org/5"]);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
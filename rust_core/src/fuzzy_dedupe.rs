@@ -3,16 +3,43 @@
 //! Replaces Python's difflib.SequenceMatcher with optimised Rust
 //! implementation for O(n*m) string similarity and O(n^2) clustering.
 
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
+#[cfg(feature = "python")]
 use pyo3::types::PyList;
 
+#[cfg(feature = "python")]
+use crate::columns::Texts;
+#[cfg(feature = "python")]
+use crate::errors::{guarded, unlocked};
+use crate::stopwords::is_stopword;
+#[cfg(feature = "python")]
+use crate::metrics::{record_cluster_sizes, timer};
+
 /// Normalise text: casefold and collapse whitespace.
-#[pyfunction]
-pub fn normalize_text(text: &str) -> String {
-    text.split_whitespace()
-        .collect::<Vec<&str>>()
-        .join(" ")
-        .to_lowercase()
+///
+/// Parameters
+/// ----------
+/// text : str
+///     Text to normalise.
+/// drop_stopwords : bool
+///     Also drop stopwords of any built-in or configured language (see
+///     ``stopwords``). Default False.
+#[cfg(feature = "python")]
+#[pyfunction(name = "normalize_text")]
+#[pyo3(signature = (text, drop_stopwords=false))]
+pub fn py_normalize_text(text: &str, drop_stopwords: bool) -> PyResult<String> {
+    guarded("normalize_text", || Ok(normalize_text(text, drop_stopwords)))
+}
+
+pub fn normalize_text(text: &str, drop_stopwords: bool) -> String {
+    let lower = text.to_lowercase();
+    let words = lower.split_whitespace();
+    if drop_stopwords {
+        words.filter(|w| !is_stopword(w)).collect::<Vec<&str>>().join(" ")
+    } else {
+        words.collect::<Vec<&str>>().join(" ")
+    }
 }
 
 /// Compute similarity ratio between two strings (0.0 to 1.0).
@@ -20,8 +47,14 @@ pub fn normalize_text(text: &str) -> String {
 /// Uses the same algorithm as Python's SequenceMatcher.ratio():
 /// 2.0 * M / T where M = matches, T = total chars.
 /// Implemented via longest common subsequence for accuracy.
+#[cfg(feature = "python")]
 #[pyfunction]
-pub fn similarity_ratio(a: &str, b: &str) -> f64 {
+pub fn similarity_ratio(py: Python<'_>, a: &str, b: &str) -> PyResult<f64> {
+    unlocked(py, "similarity_ratio", || similarity(a, b))
+}
+
+/// Similarity ratio of two raw strings.
+pub(crate) fn similarity(a: &str, b: &str) -> f64 {
     if a.is_empty() && b.is_empty() {
         return 1.0;
     }
@@ -29,8 +62,8 @@ pub fn similarity_ratio(a: &str, b: &str) -> f64 {
         return 0.0;
     }
 
-    let a_norm = normalize_text(a);
-    let b_norm = normalize_text(b);
+    let a_norm = normalize_text(a, false);
+    let b_norm = normalize_text(b, false);
     let a_bytes = a_norm.as_bytes();
     let b_bytes = b_norm.as_bytes();
     let a_len = a_bytes.len();
@@ -72,8 +105,8 @@ fn lcs_length(a: &[u8], b: &[u8]) -> usize {
 ///
 /// Parameters
 /// ----------
-/// titles : list[str]
-///     Titles to cluster.
+/// titles : list[str] | numpy.ndarray | pandas.Series | polars.Series
+///     Titles to cluster; missing values count as empty titles.
 /// threshold : float
 ///     Similarity threshold (0.0-1.0) for clustering. Default 0.90.
 ///
@@ -81,10 +114,25 @@ fn lcs_length(a: &[u8], b: &[u8]) -> usize {
 /// -------
 /// list[list[int]]
 ///     List of clusters, each cluster is a list of original indices.
+#[cfg(feature = "python")]
 #[pyfunction]
 #[pyo3(signature = (titles, threshold=0.90))]
-pub fn cluster_titles(py: Python<'_>, titles: Vec<String>, threshold: f64) -> PyResult<Py<PyList>> {
-    let normed: Vec<String> = titles.iter().map(|t| normalize_text(t)).collect();
+pub fn cluster_titles(py: Python<'_>, titles: Texts, threshold: f64) -> PyResult<Py<PyList>> {
+    let _timer = timer("cluster_titles");
+    let clusters = unlocked(py, "cluster_titles", || cluster(&titles.values, threshold))?;
+    record_cluster_sizes(clusters.iter().map(Vec::len));
+    let outer = PyList::empty_bound(py);
+    for cluster in &clusters {
+        let inner: pyo3::Bound<'_, PyList> = PyList::new_bound(py, cluster);
+        outer.append(inner)?;
+    }
+    Ok(outer.unbind())
+}
+
+/// Greedy clusters of title indices: each title joins the first cluster
+/// whose first title is at least `threshold` similar.
+fn cluster(titles: &[String], threshold: f64) -> Vec<Vec<usize>> {
+    let normed: Vec<String> = titles.iter().map(|t| normalize_text(t, false)).collect();
     let mut clusters: Vec<Vec<usize>> = Vec::new();
 
     for (i, title) in normed.iter().enumerate() {
@@ -103,13 +151,7 @@ pub fn cluster_titles(py: Python<'_>, titles: Vec<String>, threshold: f64) -> Py
             clusters.push(vec![i]);
         }
     }
-
-    let outer = PyList::empty_bound(py);
-    for cluster in &clusters {
-        let inner: pyo3::Bound<'_, PyList> = PyList::new_bound(py, cluster);
-        outer.append(inner)?;
-    }
-    Ok(outer.unbind())
+    clusters
 }
 
 /// Internal similarity ratio on pre-normalised strings.
@@ -126,24 +168,25 @@ fn raw_similarity_ratio(a: &str, b: &str) -> f64 {
     2.0 * matches as f64 / (a_bytes.len() + b_bytes.len()) as f64
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "python"))]
 mod tests {
     use super::*;
 
     #[test]
     fn test_normalize() {
-        assert_eq!(normalize_text("  Hello   World  "), "hello world");
+        assert_eq!(normalize_text("  Hello   World  ", false), "hello world");
+        assert_eq!(normalize_text("Floods in the north of Beira", true), "floods north beira");
     }
 
     #[test]
     fn test_identical() {
-        let r = similarity_ratio("cyclone hits coast", "cyclone hits coast");
+        let r = similarity("cyclone hits coast", "cyclone hits coast");
         assert!((r - 1.0).abs() < 0.001);
     }
 
     #[test]
     fn test_similar() {
-        let r = similarity_ratio(
+        let r = similarity(
             "Cyclone Gezani hits Madagascar coast",
             "Cyclone Gezani strikes Madagascar coastline",
         );
@@ -152,14 +195,14 @@ mod tests {
 
     #[test]
     fn test_dissimilar() {
-        let r = similarity_ratio("earthquake in japan", "flooding in brazil");
+        let r = similarity("earthquake in japan", "flooding in brazil");
         assert!(r < 0.5);
     }
 
     #[test]
     fn test_empty() {
-        assert!((similarity_ratio("", "") - 1.0).abs() < 0.001);
-        assert!((similarity_ratio("abc", "")).abs() < 0.001);
+        assert!((similarity("", "") - 1.0).abs() < 0.001);
+        assert!((similarity("abc", "")).abs() < 0.001);
     }
 
     #[test]
@@ -172,7 +215,7 @@ mod tests {
             "Earthquake strikes Turkey".to_string(),
         ];
         Python::with_gil(|py| {
-            let result = cluster_titles(py, titles, 0.65).unwrap();
+            let result = cluster_titles(py, Texts { values: titles, array: false }, 0.65).unwrap();
             let bound = result.bind(py);
             // Should have 2 clusters
             assert_eq!(bound.len(), 2);
@@ -0,0 +1,753 @@
+//! GeoNames / HDX gazetteer loading and name index.
+//!
+//! Place matching needs every name a place goes by (official, ASCII,
+//! alternate and local-language forms), its admin ancestors and its
+//! population to rank ambiguous names. `Gazetteer` loads GeoNames dumps
+//! (`allCountries.txt`, `MZ.txt`, `cities500.txt`) and HDX COD gazetteer
+//! CSVs into one index, keeping only the countries and feature classes
+//! asked for so a national crawl doesn't hold the whole world in memory.
+//!
+//! A loaded index can be saved as a memory-mapped table (`save_mapped`)
+//! and opened read-only by any number of worker processes
+//! (`open_mapped`), which share its pages instead of each parsing the
+//! dumps into its own heap.
+
+use pyo3::exceptions::PyOSError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::errors::{Failure, gazetteer_error, guarded, unlocked};
+use crate::mapped::{write_table, Table};
+
+/// GeoNames dump columns.
+const GN_ID: usize = 0;
+const GN_NAME: usize = 1;
+const GN_ASCII: usize = 2;
+const GN_ALTERNATES: usize = 3;
+const GN_LAT: usize = 4;
+const GN_LON: usize = 5;
+const GN_CLASS: usize = 6;
+const GN_CODE: usize = 7;
+const GN_COUNTRY: usize = 8;
+const GN_ADMIN: [usize; 4] = [10, 11, 12, 13];
+const GN_POPULATION: usize = 14;
+
+/// HDX name column suffixes, preferred first.
+const HDX_NAME_SUFFIXES: &[&str] = &["EN", "NAME", "FR", "PT", "ES", "AR", "REF"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Place {
+    /// GeoNames id or P-code.
+    pub id: String,
+    pub name: String,
+    pub alternate_names: Vec<String>,
+    /// ISO 3166-1 alpha-2 (GeoNames) or ADM0 P-code (HDX).
+    pub country: String,
+    /// GeoNames feature class; HDX rows are `A`.
+    pub feature_class: char,
+    pub feature_code: String,
+    /// 0 for countries, 1-4 for admin divisions, None for other places.
+    pub admin_level: Option<u8>,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+    pub population: u64,
+    /// Admin codes from the country down, identifying this place's
+    /// position in the hierarchy. Only needed while loading.
+    #[serde(skip)]
+    path: Vec<String>,
+    /// Admin ancestors, country first.
+    pub parents: Vec<usize>,
+}
+
+/// Load-time filters.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Filter {
+    /// Uppercased country codes.
+    pub countries: Option<HashSet<String>>,
+    pub feature_classes: Option<HashSet<char>>,
+    pub min_population: u64,
+}
+
+impl Filter {
+    pub(crate) fn new(countries: Option<Vec<String>>, feature_classes: Option<Vec<String>>, min_population: u64) -> Self {
+        Self {
+            countries: countries.map(|c| c.iter().map(|c| c.trim().to_uppercase()).collect()),
+            feature_classes: feature_classes
+                .map(|f| f.iter().filter_map(|c| c.trim().chars().next()).map(|c| c.to_ascii_uppercase()).collect()),
+            min_population,
+        }
+    }
+
+    fn accepts(&self, country: &str, feature_class: char, population: u64, admin_level: Option<u8>) -> bool {
+        self.countries.as_ref().is_none_or(|c| c.contains(&country.to_uppercase()))
+            && self.feature_classes.as_ref().is_none_or(|f| f.contains(&feature_class))
+            // Admin divisions are kept regardless of population: GeoNames
+            // rarely records it for them, and they anchor the hierarchy
+            && (population >= self.min_population || admin_level.is_some())
+    }
+}
+
+/// Matching key for a place name: accents stripped, lowercased,
+/// punctuation to spaces. "Cabo Delgado" and "cabo-delgado" → "cabo delgado".
+pub(crate) fn normalize_name(name: &str) -> String {
+    let folded: String = name
+        .nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .flat_map(char::to_lowercase)
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+    folded.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Admin level of a GeoNames feature code.
+fn geonames_level(feature_code: &str) -> Option<u8> {
+    match feature_code {
+        "PCL" | "PCLI" | "PCLD" | "PCLF" | "PCLS" | "PCLIX" => Some(0),
+        "ADM1" => Some(1),
+        "ADM2" => Some(2),
+        "ADM3" => Some(3),
+        "ADM4" => Some(4),
+        _ => None,
+    }
+}
+
+fn reader(text: &str, delimiter: u8) -> csv::Reader<&[u8]> {
+    csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(delimiter == b',')
+        .flexible(true)
+        // GeoNames fields contain bare quotes
+        .quoting(delimiter == b',')
+        .from_reader(text.as_bytes())
+}
+
+/// Mapped-table keys: `n\0<normalized name>` holds the place indices
+/// (u32 LE each), `p\0<index>` a place as JSON, `m\0` the `MappedMeta`.
+const MAPPED_NAME: &str = "n\0";
+const MAPPED_PLACE: &str = "p\0";
+const MAPPED_META: &str = "m\0";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct MappedMeta {
+    places: usize,
+    names: usize,
+    max_words: usize,
+    /// `(name, admin_level, country)` of admin divisions, level 1 down.
+    admin_areas: Vec<(String, i32, String)>,
+}
+
+/// A gazetteer opened with `open_mapped`.
+struct MappedIndex {
+    path: String,
+    table: Table,
+    meta: MappedMeta,
+}
+
+impl MappedIndex {
+    fn candidates(&self, key: &str) -> Vec<usize> {
+        let value = self.table.get(format!("{MAPPED_NAME}{key}").as_bytes()).unwrap_or_default();
+        value.chunks_exact(4).map(|c| u32::from_le_bytes(c.try_into().unwrap()) as usize).collect()
+    }
+
+    fn place(&self, index: usize) -> Result<Place, Failure> {
+        self.table
+            .get(format!("{MAPPED_PLACE}{index:010}").as_bytes())
+            .and_then(|json| serde_json::from_slice(json).ok())
+            .ok_or_else(|| Failure::new("malformed", format!("place {index} is missing or unreadable")))
+    }
+}
+
+/// Names and places indexed for matching.
+#[pyclass(module = "moltis_rust_core")]
+#[derive(Default)]
+pub struct Gazetteer {
+    places: Vec<Place>,
+    /// Normalized name → places going by it.
+    names: HashMap<String, Vec<usize>>,
+    /// Admin path → admin division, for resolving parents.
+    admins: HashMap<Vec<String>, usize>,
+    /// HDX P-codes already loaded (one row per lowest-level area repeats
+    /// its ancestors).
+    pcodes: HashSet<String>,
+    /// Words in the longest indexed name.
+    max_words: usize,
+    /// Set by `open_mapped`: places and names are read from the file,
+    /// and the fields above stay empty.
+    mapped: Option<MappedIndex>,
+}
+
+impl Gazetteer {
+    fn push(&mut self, place: Place) {
+        let index = self.places.len();
+        let names = std::iter::once(&place.name).chain(&place.alternate_names);
+        let mut keys: Vec<String> = names.map(|n| normalize_name(n)).filter(|k| k.chars().count() >= 2).collect();
+        keys.sort_unstable();
+        keys.dedup();
+        for key in keys {
+            self.max_words = self.max_words.max(key.split(' ').count());
+            self.names.entry(key).or_default().push(index);
+        }
+        if place.admin_level.is_some() {
+            self.admins.entry(place.path.clone()).or_insert(index);
+        }
+        self.places.push(place);
+    }
+
+    /// Resolve every place's admin ancestors from path prefixes; admin
+    /// rows may come after the places under them.
+    fn resolve_parents(&mut self) {
+        for place in &mut self.places {
+            // An admin division's own path isn't its parent
+            let own = usize::from(place.admin_level.is_some());
+            place.parents = (1..=place.path.len().saturating_sub(own))
+                .filter_map(|n| self.admins.get(&place.path[..n]).copied())
+                .collect();
+        }
+    }
+
+    /// Add a GeoNames dump (tab-separated, 19 columns); returns the
+    /// number of places kept.
+    pub(crate) fn add_geonames(&mut self, text: &str, filter: &Filter) -> Result<usize, Failure> {
+        self.check_writable()?;
+        let mut kept = 0;
+        for (line, record) in reader(text, b'\t').records().enumerate() {
+            let record = record.map_err(|e| Failure::from(format!("line {}: {e}", line + 1)).at_line(line + 1))?;
+            if record.len() <= GN_POPULATION {
+                let message = format!("line {}: expected 19 GeoNames columns, found {}", line + 1, record.len());
+                return Err(Failure::new("wrong_format", message).at_line(line + 1));
+            }
+            let field = |i: usize| record.get(i).unwrap_or_default().trim();
+            let feature_class = field(GN_CLASS).chars().next().unwrap_or(' ');
+            let feature_code = field(GN_CODE);
+            let admin_level = geonames_level(feature_code);
+            let population = field(GN_POPULATION).parse().unwrap_or(0);
+            if !filter.accepts(field(GN_COUNTRY), feature_class, population, admin_level) {
+                continue;
+            }
+            let mut path = vec![field(GN_COUNTRY).to_uppercase()];
+            path.extend(GN_ADMIN.iter().map(|i| field(*i)).take_while(|c| !c.is_empty() && *c != "00").map(str::to_string));
+            if let Some(level) = admin_level {
+                path.truncate(level as usize + 1);
+            }
+            let name = field(GN_NAME).to_string();
+            let mut alternate_names: Vec<String> = std::iter::once(field(GN_ASCII))
+                .chain(field(GN_ALTERNATES).split(','))
+                .map(str::trim)
+                // Skip codes and postcodes mixed into the alternates
+                .filter(|n| !n.is_empty() && *n != name && n.chars().any(char::is_alphabetic))
+                .map(str::to_string)
+                .collect();
+            alternate_names.dedup();
+            self.push(Place {
+                id: field(GN_ID).to_string(),
+                name,
+                alternate_names,
+                country: field(GN_COUNTRY).to_uppercase(),
+                feature_class,
+                feature_code: feature_code.to_string(),
+                admin_level,
+                lat: field(GN_LAT).parse().ok(),
+                lon: field(GN_LON).parse().ok(),
+                population,
+                path,
+                parents: Vec::new(),
+            });
+            kept += 1;
+        }
+        self.resolve_parents();
+        Ok(kept)
+    }
+
+    /// Add an HDX COD gazetteer CSV (`ADM0_PCODE`, `ADM1_EN`,
+    /// `ADM1_PCODE`, `ADM1ALT1EN`, ... columns); returns the number of
+    /// admin areas added.
+    pub(crate) fn add_hdx(&mut self, text: &str, filter: &Filter) -> Result<usize, Failure> {
+        self.check_writable()?;
+        let mut rows = reader(text, b',');
+        let headers: Vec<String> = rows
+            .headers()
+            .map_err(|e| format!("invalid CSV header: {e}"))?
+            .iter()
+            .map(|h| h.trim().trim_start_matches('\u{feff}').to_uppercase())
+            .collect();
+        let column = |name: &str| headers.iter().position(|h| *h == name);
+        let levels: Vec<u8> = (0..=4).filter(|n| column(&format!("ADM{n}_PCODE")).is_some()).collect();
+        if levels.is_empty() {
+            return Err(Failure::new("wrong_format", "no ADM<n>_PCODE columns: not an HDX gazetteer"));
+        }
+        let mut added = 0;
+        for (line, record) in rows.records().enumerate() {
+            let record = record.map_err(|e| Failure::from(format!("line {}: {e}", line + 2)).at_line(line + 2))?;
+            let field = |i: Option<usize>| i.and_then(|i| record.get(i)).map(str::trim).filter(|v| !v.is_empty());
+            let mut path = Vec::new();
+            for &level in &levels {
+                let Some(pcode) = field(column(&format!("ADM{level}_PCODE"))) else { break };
+                path.push(pcode.to_string());
+                let country = path[0].clone();
+                if self.pcodes.contains(pcode) || !filter.accepts(&country, 'A', 0, Some(level)) {
+                    continue;
+                }
+                let prefix = format!("ADM{level}");
+                let mut names = HDX_NAME_SUFFIXES
+                    .iter()
+                    .filter_map(|s| field(column(&format!("{prefix}_{s}"))))
+                    .chain(
+                        headers
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, h)| h.starts_with(&format!("{prefix}ALT")))
+                            .filter_map(|(i, _)| field(Some(i))),
+                    )
+                    .map(str::to_string);
+                let name = names.next().unwrap_or_else(|| pcode.to_string());
+                let mut alternate_names: Vec<String> = names.filter(|n| *n != name).collect();
+                alternate_names.sort_unstable();
+                alternate_names.dedup();
+                self.pcodes.insert(pcode.to_string());
+                self.push(Place {
+                    id: pcode.to_string(),
+                    name,
+                    alternate_names,
+                    country,
+                    feature_class: 'A',
+                    feature_code: prefix,
+                    admin_level: Some(level),
+                    lat: None,
+                    lon: None,
+                    population: 0,
+                    path: path.clone(),
+                    parents: Vec::new(),
+                });
+                added += 1;
+            }
+        }
+        self.resolve_parents();
+        Ok(added)
+    }
+
+    fn check_writable(&self) -> Result<(), Failure> {
+        match self.mapped {
+            Some(_) => Err(Failure::new("read_only", "a mapped gazetteer is read-only")),
+            None => Ok(()),
+        }
+    }
+
+    /// Places going by `name`, admin divisions first (higher levels
+    /// first), then by population.
+    pub(crate) fn find(&self, name: &str) -> Result<Vec<Cow<'_, Place>>, Failure> {
+        let mut found: Vec<Cow<'_, Place>> =
+            self.candidates(&normalize_name(name)).iter().map(|i| self.place(*i)).collect::<Result<_, _>>()?;
+        found.sort_by_key(|p| (p.admin_level.unwrap_or(u8::MAX), std::cmp::Reverse(p.population)));
+        Ok(found)
+    }
+
+    /// Indices of the places going by an already normalized name.
+    pub(crate) fn candidates(&self, key: &str) -> Cow<'_, [usize]> {
+        match &self.mapped {
+            Some(mapped) => Cow::Owned(mapped.candidates(key)),
+            None => Cow::Borrowed(self.names.get(key).map_or(&[], Vec::as_slice)),
+        }
+    }
+
+    /// Fails only when a mapped gazetteer's file is corrupt.
+    pub(crate) fn place(&self, index: usize) -> Result<Cow<'_, Place>, Failure> {
+        match &self.mapped {
+            Some(mapped) => mapped.place(index).map(Cow::Owned),
+            None => Ok(Cow::Borrowed(&self.places[index])),
+        }
+    }
+
+    /// A `GazetteerError` for a failed read, naming the mapped file.
+    pub(crate) fn error(&self, failure: Failure) -> PyErr {
+        gazetteer_error(self.mapped.as_ref().map(|m| m.path.as_str()), failure)
+    }
+
+    pub(crate) fn max_words(&self) -> usize {
+        self.max_words
+    }
+
+    /// `(id, name, admin_level)` of a place's ancestors, country first.
+    pub(crate) fn hierarchy(&self, place: &Place) -> Result<Vec<(String, String, Option<u8>)>, Failure> {
+        place
+            .parents
+            .iter()
+            .map(|i| self.place(*i).map(|p| (p.id.clone(), p.name.clone(), p.admin_level)))
+            .collect()
+    }
+
+    fn len(&self) -> usize {
+        self.mapped.as_ref().map_or(self.places.len(), |m| m.meta.places)
+    }
+
+    fn name_count(&self) -> usize {
+        self.mapped.as_ref().map_or(self.names.len(), |m| m.meta.names)
+    }
+
+    /// Write the index as a mapped table; returns the places written.
+    pub(crate) fn save_mapped(&self, path: &str) -> Result<usize, String> {
+        if self.mapped.is_some() {
+            return Err("a mapped gazetteer is already saved".into());
+        }
+        let mut entries = BTreeMap::new();
+        for (key, indices) in &self.names {
+            let value = indices.iter().flat_map(|i| (*i as u32).to_le_bytes()).collect();
+            entries.insert(format!("{MAPPED_NAME}{key}").into_bytes(), value);
+        }
+        for (index, place) in self.places.iter().enumerate() {
+            let json = serde_json::to_vec(place).map_err(|e| e.to_string())?;
+            entries.insert(format!("{MAPPED_PLACE}{index:010}").into_bytes(), json);
+        }
+        let meta = MappedMeta {
+            places: self.places.len(),
+            names: self.names.len(),
+            max_words: self.max_words,
+            admin_areas: self
+                .places
+                .iter()
+                .filter_map(|p| p.admin_level.filter(|l| *l >= 1).map(|l| (p.name.clone(), i32::from(l), p.country.clone())))
+                .collect(),
+        };
+        entries.insert(MAPPED_META.as_bytes().to_vec(), serde_json::to_vec(&meta).map_err(|e| e.to_string())?);
+        write_table(path, &entries)?;
+        Ok(self.places.len())
+    }
+
+    /// Open a table written by `save_mapped`.
+    pub(crate) fn open_mapped(path: &str) -> PyResult<Self> {
+        let table = Table::open(path)?;
+        let meta = table
+            .get(MAPPED_META.as_bytes())
+            .and_then(|json| serde_json::from_slice::<MappedMeta>(json).ok())
+            .ok_or_else(|| gazetteer_error(Some(path), Failure::new("wrong_format", "not a mapped gazetteer")))?;
+        Ok(Self { max_words: meta.max_words, mapped: Some(MappedIndex { path: path.to_string(), table, meta }), ..Self::default() })
+    }
+
+    fn place_dict<'py>(&self, py: Python<'py>, place: &Place) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("id", &place.id)?;
+        dict.set_item("name", &place.name)?;
+        dict.set_item("alternate_names", &place.alternate_names)?;
+        dict.set_item("country", &place.country)?;
+        dict.set_item("feature_class", place.feature_class.to_string())?;
+        dict.set_item("feature_code", &place.feature_code)?;
+        dict.set_item("admin_level", place.admin_level)?;
+        dict.set_item("lat", place.lat)?;
+        dict.set_item("lon", place.lon)?;
+        dict.set_item("population", place.population)?;
+        dict.set_item("hierarchy", self.hierarchy(place).map_err(|e| self.error(e))?)?;
+        Ok(dict)
+    }
+}
+
+fn read(path: &str) -> PyResult<String> {
+    std::fs::read_to_string(path).map_err(|e| PyOSError::new_err(format!("cannot read {path}: {e}")))
+}
+
+#[pymethods]
+impl Gazetteer {
+    #[new]
+    fn py_new() -> PyResult<Self> {
+        guarded("Gazetteer.__new__", || Ok(Self::default()))
+    }
+
+    /// Load a GeoNames dump (``allCountries.txt``, ``<CC>.txt``,
+    /// ``cities*.txt``).
+    ///
+    /// Parameters
+    /// ----------
+    /// path : str
+    ///     Tab-separated GeoNames file.
+    /// countries : list[str] | None
+    ///     ISO 3166-1 alpha-2 codes to keep. Default all.
+    /// feature_classes : list[str] | None
+    ///     GeoNames feature classes to keep, e.g. ``["A", "P"]`` for
+    ///     admin divisions and populated places. Default all.
+    /// min_population : int
+    ///     Drop places below this population; admin divisions are always
+    ///     kept. Default 0.
+    ///
+    /// Returns
+    /// -------
+    /// int
+    ///     Places kept.
+    ///
+    /// Raises
+    /// ------
+    /// OSError
+    ///     If the file cannot be read.
+    /// GazetteerError
+    ///     If a line doesn't have the GeoNames columns (``reason``
+    ///     ``"wrong_format"``, with ``line``). A ``ValueError`` subclass.
+    #[pyo3(signature = (path, countries=None, feature_classes=None, min_population=0))]
+    fn load_geonames(
+        &mut self,
+        py: Python<'_>,
+        path: &str,
+        countries: Option<Vec<String>>,
+        feature_classes: Option<Vec<String>>,
+        min_population: u64,
+    ) -> PyResult<usize> {
+        let text = read(path)?;
+        let filter = Filter::new(countries, feature_classes, min_population);
+        unlocked(py, "Gazetteer.load_geonames", || self.add_geonames(&text, &filter))?
+            .map_err(|e| gazetteer_error(Some(path), e))
+    }
+
+    /// Load an HDX COD gazetteer exported as CSV.
+    ///
+    /// Every ``ADM<n>_PCODE`` column becomes an admin area at level
+    /// ``n``, named by ``ADM<n>_EN`` (or ``_NAME``, ``_FR``, ``_PT``,
+    /// ``_ES``, ``_AR``, ``_REF``) with the other languages and
+    /// ``ADM<n>ALT*`` columns as alternate names.
+    ///
+    /// Parameters
+    /// ----------
+    /// path : str
+    ///     CSV file with a header row.
+    /// countries : list[str] | None
+    ///     ADM0 P-codes to keep (usually ISO alpha-2). Default all.
+    ///
+    /// Returns
+    /// -------
+    /// int
+    ///     Admin areas added; P-codes already loaded are skipped.
+    ///
+    /// Raises
+    /// ------
+    /// OSError
+    ///     If the file cannot be read.
+    /// GazetteerError
+    ///     If the CSV has no ``ADM<n>_PCODE`` columns (``reason``
+    ///     ``"wrong_format"``). A ``ValueError`` subclass.
+    #[pyo3(signature = (path, countries=None))]
+    fn load_hdx(&mut self, py: Python<'_>, path: &str, countries: Option<Vec<String>>) -> PyResult<usize> {
+        let text = read(path)?;
+        let filter = Filter::new(countries, None, 0);
+        unlocked(py, "Gazetteer.load_hdx", || self.add_hdx(&text, &filter))?
+            .map_err(|e| gazetteer_error(Some(path), e))
+    }
+
+    /// Places going by a name (any official, ASCII or alternate form;
+    /// case and accents ignored).
+    ///
+    /// Returns
+    /// -------
+    /// list[dict]
+    ///     Admin divisions first, then by population. Each has ``id``,
+    ///     ``name``, ``alternate_names``, ``country``, ``feature_class``,
+    ///     ``feature_code``, ``admin_level`` (None for non-admin places),
+    ///     ``lat``, ``lon``, ``population`` and ``hierarchy``
+    ///     (``(id, name, admin_level)`` ancestors, country first).
+    fn lookup(&self, py: Python<'_>, name: &str) -> PyResult<Vec<Py<PyDict>>> {
+        guarded("Gazetteer.lookup", || {
+            let found = self.find(name).map_err(|e| self.error(e))?;
+            found.into_iter().map(|p| Ok(self.place_dict(py, &p)?.unbind())).collect()
+        })
+    }
+
+    /// Save the index as a memory-mapped file for ``open_mapped``.
+    ///
+    /// The file is written beside ``path`` and renamed into place, so
+    /// workers with the previous version open keep a consistent view.
+    ///
+    /// Returns
+    /// -------
+    /// int
+    ///     Places written.
+    ///
+    /// Raises
+    /// ------
+    /// OSError
+    ///     If the file cannot be written, or this gazetteer is itself
+    ///     mapped.
+    #[pyo3(name = "save_mapped")]
+    fn py_save_mapped(&self, py: Python<'_>, path: &str) -> PyResult<usize> {
+        unlocked(py, "Gazetteer.save_mapped", || self.save_mapped(path))?.map_err(PyOSError::new_err)
+    }
+
+    /// Open a file written by ``save_mapped``, read-only.
+    ///
+    /// Nothing is parsed up front: lookups read names and places straight
+    /// from the mapped file, whose pages the operating system shares
+    /// between every process that opens it. ``load_geonames`` and
+    /// ``load_hdx`` raise ``GazetteerError`` (``reason`` ``"read_only"``)
+    /// on the result, and lookups that hit a corrupt record raise it with
+    /// ``reason`` ``"malformed"``.
+    ///
+    /// Raises
+    /// ------
+    /// OSError
+    ///     If the file cannot be opened or mapped.
+    /// GazetteerError
+    ///     If it isn't a saved gazetteer (``reason`` ``"wrong_format"``).
+    #[staticmethod]
+    #[pyo3(name = "open_mapped")]
+    fn py_open_mapped(path: &str) -> PyResult<Self> {
+        guarded("Gazetteer.open_mapped", || Self::open_mapped(path))
+    }
+
+    /// Whether the index is read from a mapped file.
+    #[getter]
+    fn mapped(&self) -> PyResult<bool> {
+        guarded("Gazetteer.mapped", || Ok(self.mapped.is_some()))
+    }
+
+    /// ``(name, admin_level)`` pairs of the loaded admin divisions (level
+    /// 1 and below), as taken by ``detect_admin_area`` and
+    /// ``process_article``.
+    #[pyo3(signature = (country=None))]
+    fn admin_areas(&self, country: Option<&str>) -> PyResult<Vec<(String, i32)>> {
+        guarded("Gazetteer.admin_areas", || {
+            let country = country.map(str::to_uppercase);
+            if let Some(mapped) = &self.mapped {
+                return Ok(mapped
+                    .meta
+                    .admin_areas
+                    .iter()
+                    .filter(|(_, _, c)| country.as_ref().is_none_or(|country| country == c))
+                    .map(|(name, level, _)| (name.clone(), *level))
+                    .collect());
+            }
+            Ok(self
+                .places
+                .iter()
+                .filter(|p| country.as_ref().is_none_or(|c| *c == p.country))
+                .filter_map(|p| p.admin_level.filter(|l| *l >= 1).map(|l| (p.name.clone(), i32::from(l))))
+                .collect())
+        })
+    }
+
+    fn __len__(&self) -> PyResult<usize> {
+        guarded("Gazetteer.__len__", || Ok(self.len()))
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        guarded("Gazetteer.__repr__", || {
+            let mapped = if self.mapped.is_some() { ", mapped=True" } else { "" };
+            Ok(format!("Gazetteer(places={}, names={}{mapped})", self.len(), self.name_count()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn geonames_line(fields: &[&str]) -> String {
+        let mut row: Vec<&str> = fields.to_vec();
+        row.resize(19, "");
+        row.join("\t")
+    }
+
+    fn geonames() -> String {
+        [
+            geonames_line(&["1036973", "Mozambique", "Mozambique", "Moçambique,MZ", "-18.25", "35.0", "A", "PCLI", "MZ", "", "00", "", "", "", "30366036"]),
+            geonames_line(&["1026804", "Sofala Province", "Sofala Province", "Sofala", "-19.5", "34.75", "A", "ADM1", "MZ", "", "05", "", "", "", "0"]),
+            geonames_line(&["1052373", "Beira", "Beira", "Beira,BEW,Cidade da Beira", "-19.84", "34.84", "P", "PPLA", "MZ", "", "05", "", "", "", "530604"]),
+            geonames_line(&["1030000", "Beira Village", "Beira Village", "", "-19.9", "34.9", "P", "PPL", "MZ", "", "05", "", "", "", "120"]),
+            geonames_line(&["927967", "Lilongwe", "Lilongwe", "", "-13.97", "33.79", "P", "PPLC", "MW", "", "", "", "", "", "646750"]),
+            geonames_line(&["1040000", "Rio Pungue", "Rio Pungue", "", "-19.6", "34.5", "H", "STM", "MZ", "", "05", "", "", "", "0"]),
+        ]
+        .join("\n")
+    }
+
+    #[test]
+    fn test_geonames_index_and_hierarchy() {
+        let mut g = Gazetteer::default();
+        assert_eq!(g.add_geonames(&geonames(), &Filter::default()).unwrap(), 6);
+        let beira = g.find("BEIRA").unwrap();
+        assert_eq!(beira.len(), 1);
+        assert_eq!(beira[0].population, 530604);
+        let parents: Vec<&str> = beira[0].parents.iter().map(|i| g.places[*i].name.as_str()).collect();
+        assert_eq!(parents, vec!["Mozambique", "Sofala Province"]);
+        // Alternate names and accents
+        assert_eq!(g.find("cidade da beira").unwrap()[0].id, "1052373");
+        assert_eq!(g.find("mocambique").unwrap()[0].admin_level, Some(0));
+        assert_eq!(g.find("Sofala").unwrap()[0].parents.len(), 1);
+        assert!(g.add_geonames("1\tshort", &Filter::default()).is_err());
+    }
+
+    #[test]
+    fn test_load_filters() {
+        let mut g = Gazetteer::default();
+        let filter = Filter::new(Some(vec!["mz".into()]), Some(vec!["A".into(), "P".into()]), 1000);
+        assert_eq!(g.add_geonames(&geonames(), &filter).unwrap(), 3);
+        assert!(g.find("Lilongwe").unwrap().is_empty());
+        assert!(g.find("Beira Village").unwrap().is_empty());
+        assert!(g.find("Rio Pungue").unwrap().is_empty());
+        // Admin divisions survive the population floor
+        assert_eq!(g.find("Sofala Province").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_hdx_csv() {
+        let csv = "\u{feff}ADM0_EN,ADM0_PCODE,ADM1_PT,ADM1_PCODE,ADM2_PT,ADM2_PCODE,ADM2ALT1PT\n\
+                   Mozambique,MZ,Sofala,MZ07,Beira,MZ0701,Cidade da Beira\n\
+                   Mozambique,MZ,Sofala,MZ07,Dondo,MZ0705,\n\
+                   Mozambique,MZ,Zambézia,MZ09,Quelimane,MZ0901,\n\
+                   Malawi,MW,Southern,MW3,Blantyre,MW305,\n";
+        let mut g = Gazetteer::default();
+        let filter = Filter::new(Some(vec!["MZ".into()]), None, 0);
+        // MZ, two provinces, three districts
+        assert_eq!(g.add_hdx(csv, &filter).unwrap(), 6);
+        let quelimane = g.find("quelimane").unwrap();
+        let parents: Vec<&str> = quelimane[0].parents.iter().map(|i| g.places[*i].id.as_str()).collect();
+        assert_eq!(parents, vec!["MZ", "MZ09"]);
+        assert_eq!(g.find("zambezia").unwrap()[0].admin_level, Some(1));
+        assert_eq!(g.find("Cidade da Beira").unwrap()[0].id, "MZ0701");
+        assert!(g.find("Blantyre").unwrap().is_empty());
+        assert!(g.add_hdx("name,lat\nBeira,-19.8\n", &Filter::default()).is_err());
+    }
+
+    #[test]
+    fn test_mapped_roundtrip() {
+        let mut g = Gazetteer::default();
+        g.add_geonames(&geonames(), &Filter::default()).unwrap();
+        let path = std::env::temp_dir().join(format!("moltis_gazetteer_{}.map", std::process::id()));
+        let path = path.to_str().unwrap();
+        assert_eq!(g.save_mapped(path).unwrap(), 6);
+
+        let mut mapped = Gazetteer::open_mapped(path).unwrap();
+        assert_eq!((mapped.len(), mapped.name_count(), mapped.max_words()), (g.len(), g.name_count(), g.max_words()));
+        let beira = mapped.find("cidade da beira").unwrap();
+        assert_eq!(beira.len(), 1);
+        assert!(matches!(beira[0], Cow::Owned(_)));
+        // The load-time admin path isn't stored
+        assert_eq!(*beira[0], Place { path: Vec::new(), ..g.find("Beira").unwrap()[0].clone().into_owned() });
+        let hierarchy = mapped.hierarchy(&beira[0]).unwrap();
+        assert_eq!(hierarchy[1], ("1026804".to_string(), "Sofala Province".to_string(), Some(1)));
+        assert_eq!(mapped.admin_areas(Some("mz")).unwrap(), g.admin_areas(Some("MZ")).unwrap());
+        assert!(mapped.find("Lisbon").unwrap().is_empty());
+        assert_eq!(mapped.add_geonames(&geonames(), &Filter::default()).unwrap_err().reason, "read_only");
+        assert!(mapped.save_mapped(path).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_corrupt_mapped_place() {
+        let path = std::env::temp_dir().join(format!("moltis_gazetteer_corrupt_{}.map", std::process::id()));
+        let path = path.to_str().unwrap();
+        let meta = MappedMeta { places: 1, names: 1, max_words: 1, admin_areas: Vec::new() };
+        let entries = BTreeMap::from([
+            (MAPPED_META.as_bytes().to_vec(), serde_json::to_vec(&meta).unwrap()),
+            (format!("{MAPPED_NAME}beira").into_bytes(), 0u32.to_le_bytes().to_vec()),
+            (format!("{MAPPED_PLACE}{:010}", 0).into_bytes(), b"{not json".to_vec()),
+        ]);
+        write_table(path, &entries).unwrap();
+        let mapped = Gazetteer::open_mapped(path).unwrap();
+        assert_eq!(mapped.find("Beira").unwrap_err().reason, "malformed");
+
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let err = mapped.lookup(py, "Beira").unwrap_err();
+            assert!(err.is_instance_of::<crate::errors::GazetteerError>(py));
+            assert_eq!(err.value_bound(py).getattr("path").unwrap().extract::<String>().unwrap(), path);
+        });
+        std::fs::remove_file(path).unwrap();
+    }
+}
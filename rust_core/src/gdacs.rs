@@ -0,0 +1,366 @@
+//! GDACS alert parsing — RSS feed items and GeoJSON event payloads.
+//!
+//! GDACS (the Global Disaster Alert and Coordination System) publishes
+//! model-based alerts with exposure estimates for earthquakes, cyclones,
+//! floods, volcanoes, droughts and wildfires. Events are mapped onto the
+//! crawler's disaster types and the `process_article` record fields
+//! (`severity`, `figures`, `published`), so an alert can be fused with
+//! news about the same event.
+
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyList};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::date_parse::parse_at;
+use crate::errors::{extraction_error, unlocked, Failure};
+use crate::pipeline::current_time;
+use crate::xml_tree::{self, Element};
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct GdacsEvent {
+    /// GDACS code: EQ, TC, FL, VO, DR, WF, TS.
+    pub event_type: String,
+    pub disaster_type: Option<&'static str>,
+    pub event_id: Option<u64>,
+    pub episode_id: Option<u64>,
+    pub name: Option<String>,
+    /// Lowercased: green, orange or red.
+    pub alert_level: Option<String>,
+    /// Hazard description, e.g. "Magnitude 6.8M, Depth:10km".
+    pub hazard_severity: Option<String>,
+    pub countries: Vec<String>,
+    pub iso3: Vec<String>,
+    /// People exposed, where GDACS estimates it.
+    pub population: Option<i64>,
+    pub from_date: Option<String>,
+    pub to_date: Option<String>,
+    pub published: Option<String>,
+    pub url: Option<String>,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+}
+
+/// GDACS event code to the crawler's disaster type.
+fn disaster_type(code: &str) -> Option<&'static str> {
+    match code {
+        "EQ" => Some("earthquake"),
+        "TC" => Some("cyclone/storm"),
+        "FL" => Some("flood"),
+        "VO" => Some("volcanic eruption"),
+        "DR" => Some("drought"),
+        "WF" => Some("wildfire"),
+        "TS" => Some("tsunami"),
+        _ => None,
+    }
+}
+
+impl GdacsEvent {
+    /// Alert level on the 1-5 `severity_from_text` scale.
+    pub(crate) fn severity(&self) -> Option<i32> {
+        match self.alert_level.as_deref()? {
+            "red" => Some(4),
+            "orange" => Some(3),
+            "green" => Some(2),
+            _ => None,
+        }
+    }
+
+    /// Figures as `extract_figures` names them.
+    pub(crate) fn figures(&self) -> HashMap<String, i64> {
+        self.population
+            .map(|p| ("people_affected".to_string(), p))
+            .into_iter()
+            .collect()
+    }
+
+    /// Cyclone name without the GDACS year suffix ("FREDDY-23" -> "Freddy").
+    pub(crate) fn storm_name(&self) -> Option<String> {
+        if self.event_type != "TC" {
+            return None;
+        }
+        let name = self.name.as_deref()?;
+        let base = name.rsplit_once('-').map_or(name, |(base, _)| base).trim();
+        let mut chars = base.chars();
+        let first = chars.next()?;
+        Some(first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect())
+    }
+}
+
+fn timestamp(text: &str) -> Option<String> {
+    parse_at(text, None, current_time()).map(|p| p.iso())
+}
+
+/// "Mozambique, Malawi" or "MOZ;MWI" as a list.
+fn split_list(text: &str) -> Vec<String> {
+    text.split([',', ';'])
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn rss_item(item: &Element) -> Option<GdacsEvent> {
+    let event_type = item.child_text("eventtype")?.to_uppercase();
+    let point = item.child("Point");
+    Some(GdacsEvent {
+        disaster_type: disaster_type(&event_type),
+        event_id: item.child_text("eventid").and_then(|v| v.parse().ok()),
+        episode_id: item.child_text("episodeid").and_then(|v| v.parse().ok()),
+        name: item
+            .child_text("eventname")
+            .or_else(|| item.child_text("title"))
+            .map(str::to_string),
+        alert_level: item.child_text("alertlevel").map(str::to_lowercase),
+        hazard_severity: item.child_text("severity").map(str::to_string),
+        countries: item.child_text("country").map(split_list).unwrap_or_default(),
+        iso3: item.child_text("iso3").map(split_list).unwrap_or_default(),
+        population: item
+            .child("population")
+            .and_then(|p| p.attr("value"))
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .map(|v| v as i64),
+        from_date: item.child_text("fromdate").and_then(timestamp),
+        to_date: item.child_text("todate").and_then(timestamp),
+        published: item.child_text("pubDate").and_then(timestamp),
+        url: item.child_text("link").map(str::to_string),
+        lat: point.and_then(|p| p.child_text("lat")).and_then(|v| v.parse().ok()),
+        lon: point.and_then(|p| p.child_text("long")).and_then(|v| v.parse().ok()),
+        event_type,
+    })
+}
+
+fn parse_rss(data: &[u8]) -> Result<Vec<GdacsEvent>, Failure> {
+    let root = xml_tree::parse(data)?;
+    let channel = root
+        .child("channel")
+        .ok_or_else(|| Failure::new("wrong_format", "not a GDACS feed: no <channel>"))?;
+    Ok(channel.children_named("item").filter_map(rss_item).collect())
+}
+
+fn json_str(props: &Value, key: &str) -> Option<String> {
+    props.get(key)?.as_str().map(str::trim).filter(|s| !s.is_empty()).map(str::to_string)
+}
+
+fn json_u64(props: &Value, key: &str) -> Option<u64> {
+    let value = props.get(key)?;
+    value.as_u64().or_else(|| value.as_str()?.parse().ok())
+}
+
+fn json_feature(feature: &Value) -> Option<GdacsEvent> {
+    let props = feature.get("properties")?;
+    let event_type = json_str(props, "eventtype")?.to_uppercase();
+    let affected = props.get("affectedcountries").and_then(Value::as_array);
+    let from_affected = |key: &str| -> Vec<String> {
+        affected
+            .into_iter()
+            .flatten()
+            .filter_map(|c| json_str(c, key))
+            .collect()
+    };
+    let (mut countries, mut iso3) = (from_affected("countryname"), from_affected("iso3"));
+    if countries.is_empty() {
+        countries = json_str(props, "country").map(|c| split_list(&c)).unwrap_or_default();
+    }
+    if iso3.is_empty() {
+        iso3 = json_str(props, "iso3").map(|c| split_list(&c)).unwrap_or_default();
+    }
+    // Point geometry is [lon, lat]
+    let coordinates = feature
+        .pointer("/geometry/coordinates")
+        .and_then(Value::as_array)
+        .filter(|_| feature.pointer("/geometry/type").and_then(Value::as_str) == Some("Point"));
+    let coordinate = |i: usize| coordinates.and_then(|c| c.get(i)?.as_f64());
+    Some(GdacsEvent {
+        disaster_type: disaster_type(&event_type),
+        event_id: json_u64(props, "eventid"),
+        episode_id: json_u64(props, "episodeid"),
+        name: json_str(props, "eventname").or_else(|| json_str(props, "name")),
+        alert_level: json_str(props, "alertlevel").map(|l| l.to_lowercase()),
+        hazard_severity: props.pointer("/severitydata/severitytext").and_then(Value::as_str).map(str::to_string),
+        countries,
+        iso3,
+        population: props
+            .get("population")
+            .and_then(|p| p.as_f64().or_else(|| p.get("value")?.as_f64()))
+            .map(|p| p as i64),
+        from_date: json_str(props, "fromdate").as_deref().and_then(timestamp),
+        to_date: json_str(props, "todate").as_deref().and_then(timestamp),
+        published: json_str(props, "datemodified").as_deref().and_then(timestamp),
+        url: props
+            .pointer("/url/report")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        lat: coordinate(1),
+        lon: coordinate(0),
+        event_type,
+    })
+}
+
+fn parse_json(data: &[u8]) -> Result<Vec<GdacsEvent>, Failure> {
+    let value: Value = serde_json::from_slice(data).map_err(|e| format!("malformed GDACS JSON: {e}"))?;
+    match value.get("features").and_then(Value::as_array) {
+        Some(features) => Ok(features.iter().filter_map(json_feature).collect()),
+        None => json_feature(&value)
+            .map(|e| vec![e])
+            .ok_or_else(|| Failure::new("wrong_format", "not a GDACS payload: no features or event properties")),
+    }
+}
+
+/// Parse a GDACS RSS feed or GeoJSON event payload.
+pub(crate) fn parse_gdacs_bytes(data: &[u8]) -> Result<Vec<GdacsEvent>, Failure> {
+    match data.iter().find(|b| !b.is_ascii_whitespace()) {
+        Some(b'{') => parse_json(data),
+        _ => parse_rss(data),
+    }
+}
+
+fn event_dict<'py>(py: Python<'py>, event: &GdacsEvent) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new_bound(py);
+    dict.set_item("event_type", &event.event_type)?;
+    dict.set_item("disaster_type", event.disaster_type)?;
+    dict.set_item("event_id", event.event_id)?;
+    dict.set_item("episode_id", event.episode_id)?;
+    dict.set_item("name", &event.name)?;
+    dict.set_item("storm_name", event.storm_name())?;
+    dict.set_item("alert_level", &event.alert_level)?;
+    dict.set_item("severity", event.severity())?;
+    dict.set_item("hazard_severity", &event.hazard_severity)?;
+    dict.set_item("countries", &event.countries)?;
+    dict.set_item("iso3", &event.iso3)?;
+    dict.set_item("figures", event.figures())?;
+    dict.set_item("from_date", &event.from_date)?;
+    dict.set_item("to_date", &event.to_date)?;
+    dict.set_item("published", &event.published)?;
+    dict.set_item("url", &event.url)?;
+    dict.set_item("lat", event.lat)?;
+    dict.set_item("lon", event.lon)?;
+    Ok(dict)
+}
+
+/// Parse GDACS alerts from the RSS feed or the GeoJSON events API.
+///
+/// Items without an event type are skipped.
+///
+/// Parameters
+/// ----------
+/// data : bytes | str
+///     An RSS document (``gdacs:`` elements) or GeoJSON — a
+///     FeatureCollection or a single event feature.
+///
+/// Returns
+/// -------
+/// list[dict]
+///     One dict per event: ``event_type`` (GDACS code, e.g. ``"TC"``),
+///     ``disaster_type`` (the crawler's type, e.g. ``"cyclone/storm"``),
+///     ``event_id``, ``episode_id``, ``name``, ``storm_name`` (cyclones
+///     only, e.g. ``"Freddy"``), ``alert_level`` (``"green"`` /
+///     ``"orange"`` / ``"red"``), ``severity`` (1-5 as in
+///     ``process_article``), ``hazard_severity``, ``countries``,
+///     ``iso3``, ``figures`` (``{"people_affected": exposed}`` when
+///     estimated), ``from_date``, ``to_date``, ``published`` (UTC ISO
+///     8601), ``url``, ``lat`` and ``lon``. Missing values are None.
+///
+/// Raises
+/// ------
+/// ExtractionError
+///     If the payload is malformed (``reason`` ``"malformed"``) or neither
+///     an RSS feed nor GeoJSON (``"wrong_format"``). A ``ValueError``
+///     subclass.
+#[pyfunction]
+pub fn parse_gdacs(py: Python<'_>, data: &Bound<'_, PyAny>) -> PyResult<Py<PyList>> {
+    let events = if let Ok(bytes) = data.downcast::<PyBytes>() {
+        let bytes = bytes.as_bytes();
+        unlocked(py, "parse_gdacs", || parse_gdacs_bytes(bytes))?
+    } else {
+        let text = data.extract::<String>()?;
+        unlocked(py, "parse_gdacs", || parse_gdacs_bytes(text.as_bytes()))?
+    }
+    .map_err(|e| extraction_error("gdacs", e))?;
+
+    let list = PyList::empty_bound(py);
+    for event in &events {
+        list.append(event_dict(py, event)?)?;
+    }
+    Ok(list.unbind())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RSS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss xmlns:gdacs="http://www.gdacs.org" xmlns:geo="http://www.w3.org/2003/01/geo/wgs84_pos#" version="2.0">
+<channel>
+  <title>GDACS</title>
+  <item>
+    <title>Red alert for tropical cyclone FREDDY-23</title>
+    <link>https://www.gdacs.org/report.aspx?eventtype=TC&amp;eventid=1000948</link>
+    <pubDate>Sat, 11 Mar 2023 12:00:00 GMT</pubDate>
+    <gdacs:fromdate>Mon, 06 Feb 2023 00:00:00 GMT</gdacs:fromdate>
+    <gdacs:todate>Tue, 14 Mar 2023 06:00:00 GMT</gdacs:todate>
+    <gdacs:eventtype>TC</gdacs:eventtype>
+    <gdacs:alertlevel>Red</gdacs:alertlevel>
+    <gdacs:eventname>FREDDY-23</gdacs:eventname>
+    <gdacs:eventid>1000948</gdacs:eventid>
+    <gdacs:episodeid>55</gdacs:episodeid>
+    <gdacs:severity unit="km/h" value="213">Tropical Cyclone (maximum wind speed of 213 km/h)</gdacs:severity>
+    <gdacs:population unit="Pop74" value="2400000">2.4 million people</gdacs:population>
+    <gdacs:country>Mozambique, Madagascar</gdacs:country>
+    <gdacs:iso3>MOZ, MDG</gdacs:iso3>
+    <geo:Point><geo:lat>-17.1</geo:lat><geo:long>36.9</geo:long></geo:Point>
+  </item>
+  <item><title>No type</title></item>
+</channel>
+</rss>"#;
+
+    #[test]
+    fn test_rss() {
+        let events = parse_gdacs_bytes(RSS.as_bytes()).unwrap();
+        assert_eq!(events.len(), 1);
+        let e = &events[0];
+        assert_eq!((e.event_type.as_str(), e.disaster_type), ("TC", Some("cyclone/storm")));
+        assert_eq!((e.event_id, e.episode_id), (Some(1000948), Some(55)));
+        assert_eq!(e.storm_name().as_deref(), Some("Freddy"));
+        assert_eq!((e.alert_level.as_deref(), e.severity()), (Some("red"), Some(4)));
+        assert_eq!(e.countries, vec!["Mozambique", "Madagascar"]);
+        assert_eq!(e.iso3, vec!["MOZ", "MDG"]);
+        assert_eq!(e.figures().get("people_affected"), Some(&2_400_000));
+        assert_eq!(e.from_date.as_deref(), Some("2023-02-06T00:00:00Z"));
+        assert_eq!(e.url.as_deref(), Some("https://www.gdacs.org/report.aspx?eventtype=TC&eventid=1000948"));
+        assert_eq!((e.lat, e.lon), (Some(-17.1), Some(36.9)));
+    }
+
+    #[test]
+    fn test_geojson() {
+        let json = r#"{"type": "FeatureCollection", "features": [{
+            "type": "Feature",
+            "geometry": {"type": "Point", "coordinates": [37.2, 38.0]},
+            "properties": {
+                "eventtype": "EQ", "eventid": 1357372, "episodeid": "1453369",
+                "name": "Earthquake in Türkiye", "alertlevel": "Red",
+                "country": "Türkiye, Syria",
+                "affectedcountries": [{"iso3": "TUR", "countryname": "Türkiye"}, {"iso3": "SYR", "countryname": "Syria"}],
+                "fromdate": "2023-02-06T01:17:34", "todate": "2023-02-06T01:17:34",
+                "severitydata": {"severity": 7.8, "severitytext": "Magnitude 7.8M, Depth:10km"},
+                "url": {"report": "https://www.gdacs.org/report.aspx?eventid=1357372&eventtype=EQ"}
+            }}]}"#;
+        let events = parse_gdacs_bytes(json.as_bytes()).unwrap();
+        let e = &events[0];
+        assert_eq!(e.disaster_type, Some("earthquake"));
+        assert_eq!((e.event_id, e.episode_id), (Some(1357372), Some(1453369)));
+        assert_eq!(e.iso3, vec!["TUR", "SYR"]);
+        assert_eq!(e.hazard_severity.as_deref(), Some("Magnitude 7.8M, Depth:10km"));
+        assert_eq!(e.from_date.as_deref(), Some("2023-02-06T01:17:34Z"));
+        assert_eq!((e.lat, e.lon), (Some(38.0), Some(37.2)));
+        assert_eq!(e.storm_name(), None);
+        assert!(e.figures().is_empty());
+    }
+
+    #[test]
+    fn test_not_gdacs() {
+        assert!(parse_gdacs_bytes(b"<html><body/></html>").is_err());
+        assert!(parse_gdacs_bytes(b"{\"foo\": 1}").is_err());
+        assert!(parse_gdacs_bytes(b"{").is_err());
+    }
+}
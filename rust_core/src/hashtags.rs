@@ -0,0 +1,367 @@
+//! Crisis hashtag extraction and classification.
+//!
+//! Social posts name events in hashtags (`#CycloneFreddy`,
+//! `#MozambiqueFloods`, `#FreddyRelief`). Splitting a tag into words —
+//! camel case, digits, underscores, and dictionary word-break for
+//! all-lowercase tags like `#prayformozambique` — lets it be classified
+//! as an event, location or response tag and joined to crawled events by
+//! hazard, storm name and place.
+
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use regex::Regex;
+use std::collections::HashMap;
+
+use crate::errors::unlocked;
+use crate::storm_names::storm_names;
+
+/// All-lowercase runs shorter than this aren't word-broken.
+const MIN_BREAK_LEN: usize = 7;
+
+// "#tag" not preceded by a word character, '&' (HTML entities) or '/'
+// (URL fragments); the tag needs at least one letter.
+static HASHTAG: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:^|[^\p{L}\p{N}_&/#])[#＃]([\p{L}\p{N}_]*\p{L}[\p{L}\p{N}_]*)").unwrap()
+});
+
+// Hazard words (English, French, Portuguese, Spanish) → hazard.
+pub(crate) static HAZARDS: &[(&str, &str)] = &[
+    ("cyclone", "cyclone"), ("cyclones", "cyclone"), ("ciclone", "cyclone"), ("hurricane", "hurricane"),
+    ("typhoon", "typhoon"), ("storm", "storm"), ("tempete", "storm"), ("flood", "flood"),
+    ("floods", "flood"), ("flooding", "flood"), ("inondation", "flood"), ("inondations", "flood"),
+    ("cheias", "flood"), ("enchentes", "flood"), ("inundaciones", "flood"), ("earthquake", "earthquake"),
+    ("quake", "earthquake"), ("seisme", "earthquake"), ("sismo", "earthquake"),
+    ("terremoto", "earthquake"), ("tsunami", "tsunami"), ("drought", "drought"), ("seca", "drought"),
+    ("secheresse", "drought"), ("famine", "famine"), ("wildfire", "wildfire"),
+    ("wildfires", "wildfire"), ("bushfire", "wildfire"), ("landslide", "landslide"),
+    ("landslides", "landslide"), ("mudslide", "landslide"), ("volcano", "volcano"),
+    ("eruption", "volcano"), ("cholera", "epidemic"), ("outbreak", "epidemic"), ("ebola", "epidemic"),
+    ("mpox", "epidemic"), ("heatwave", "heatwave"),
+];
+
+static RESPONSE_WORDS: &[&str] = &[
+    "relief", "aid", "help", "donate", "support", "rescue", "response", "appeal", "solidarity",
+    "prayfor", "standwith", "volunteers", "fundraiser", "secours", "ajuda", "ayuda",
+];
+
+// Humanitarian-priority countries, as in the Python gazetteer loader.
+pub(crate) static COUNTRIES: &[&str] = &[
+    "Afghanistan", "Bangladesh", "Burkina Faso", "Burundi", "Cameroon", "Central African Republic",
+    "Chad", "Colombia", "Congo", "DRC", "Egypt", "Eritrea", "Ethiopia", "Haiti", "India",
+    "Indonesia", "Iran", "Iraq", "Kenya", "Lebanon", "Libya", "Madagascar", "Malawi", "Mali",
+    "Mauritania", "Mozambique", "Myanmar", "Nepal", "Niger", "Nigeria", "Pakistan", "Palestine",
+    "Philippines", "Rwanda", "Senegal", "Sierra Leone", "Somalia", "South Sudan", "Sudan", "Syria",
+    "Tanzania", "Turkey", "Turkiye", "Uganda", "Ukraine", "Venezuela", "Yemen", "Zambia",
+    "Zimbabwe", "Gaza",
+];
+
+// Short words that glue tag phrases together ("#PrayForMozambique").
+static GLUE_WORDS: &[&str] = &["pray", "for", "stand", "with", "in", "of", "the", "and", "save", "stop"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TagKind {
+    Event,
+    Location,
+    Response,
+    Other,
+}
+
+impl TagKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            TagKind::Event => "event",
+            TagKind::Location => "location",
+            TagKind::Response => "response",
+            TagKind::Other => "other",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Hashtag {
+    /// As first written, with the `#`.
+    pub tag: String,
+    pub words: Vec<String>,
+    pub kind: TagKind,
+    pub hazard: Option<&'static str>,
+    pub storm: Option<String>,
+    pub location: Option<String>,
+    /// Occurrences in the text, compared case-insensitively.
+    pub count: usize,
+}
+
+/// Lowercased letters and digits only: "South Sudan" → "southsudan".
+fn squash(text: &str) -> String {
+    text.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+pub(crate) fn fold_accents(word: &str) -> String {
+    word.chars()
+        .map(|c| match c {
+            'á' | 'à' | 'â' | 'ã' => 'a',
+            'é' | 'è' | 'ê' => 'e',
+            'í' | 'î' => 'i',
+            'ó' | 'ô' | 'õ' => 'o',
+            'ú' | 'û' => 'u',
+            'ç' => 'c',
+            c => c,
+        })
+        .collect()
+}
+
+/// Squashed word → display form, for word-breaking lowercase runs.
+fn vocabulary(locations: &[String]) -> HashMap<String, String> {
+    let mut vocab: HashMap<String, String> = HashMap::new();
+    let words = HAZARDS
+        .iter()
+        .map(|(w, _)| *w)
+        .chain(RESPONSE_WORDS.iter().copied().filter(|w| !w.ends_with("for") && !w.ends_with("with")))
+        .chain(GLUE_WORDS.iter().copied());
+    for word in words {
+        let mut display = word.to_string();
+        display[..1].make_ascii_uppercase();
+        vocab.insert(word.to_string(), display);
+    }
+    for place in COUNTRIES.iter().copied().chain(locations.iter().map(String::as_str)) {
+        vocab.insert(squash(place), place.to_string());
+    }
+    vocab
+}
+
+/// Break an all-lowercase run into vocabulary words, fewest pieces
+/// first; None if it can't be fully covered. Single words are left
+/// as written by the caller.
+fn word_break(run: &str, vocab: &HashMap<String, String>) -> Option<Vec<String>> {
+    let chars: Vec<(usize, char)> = run.char_indices().collect();
+    let n = chars.len();
+    let offset = |i: usize| chars.get(i).map_or(run.len(), |(o, _)| *o);
+    // best[i]: fewest pieces covering run[..i], with the previous cut
+    let mut best: Vec<Option<(usize, usize)>> = vec![None; n + 1];
+    best[0] = Some((0, 0));
+    for end in 1..=n {
+        for start in 0..end {
+            let Some((pieces, _)) = best[start] else { continue };
+            if vocab.contains_key(&run[offset(start)..offset(end)])
+                && best[end].is_none_or(|(p, _)| pieces + 1 < p)
+            {
+                best[end] = Some((pieces + 1, start));
+            }
+        }
+    }
+    best[n]?;
+    let mut words = Vec::new();
+    let mut end = n;
+    while end > 0 {
+        let (_, start) = best[end]?;
+        words.push(vocab[&run[offset(start)..offset(end)]].clone());
+        end = start;
+    }
+    words.reverse();
+    Some(words)
+}
+
+/// Split a hashtag body into words: underscores, camel case
+/// ("CycloneFreddy"), acronyms ("COVIDVaccine"), letter/digit
+/// boundaries, then dictionary word-break for long lowercase runs.
+fn split_words(body: &str, vocab: &HashMap<String, String>) -> Vec<String> {
+    let mut pieces: Vec<String> = Vec::new();
+    for part in body.split('_').filter(|p| !p.is_empty()) {
+        let chars: Vec<char> = part.chars().collect();
+        let mut current = String::new();
+        for (i, &c) in chars.iter().enumerate() {
+            if let Some(&prev) = i.checked_sub(1).and_then(|p| chars.get(p)) {
+                let next = chars.get(i + 1).copied();
+                let boundary = (prev.is_lowercase() && c.is_uppercase())
+                    || (prev.is_alphabetic() != c.is_alphabetic())
+                    || (prev.is_uppercase() && c.is_uppercase() && next.is_some_and(char::is_lowercase));
+                if boundary && !current.is_empty() {
+                    pieces.push(std::mem::take(&mut current));
+                }
+            }
+            current.push(c);
+        }
+        if !current.is_empty() {
+            pieces.push(current);
+        }
+    }
+    pieces
+        .into_iter()
+        .flat_map(|piece| {
+            let lower = piece.to_lowercase();
+            let broken = (piece == lower && lower.chars().count() >= MIN_BREAK_LEN)
+                .then(|| word_break(&lower, vocab))
+                .flatten()
+                .filter(|words| words.len() > 1);
+            broken.unwrap_or_else(|| vec![piece])
+        })
+        .collect()
+}
+
+/// First place whose squashed name equals a run of consecutive words.
+fn find_location(words: &[String], vocab_places: &[(String, &str)]) -> Option<String> {
+    let squashed: Vec<String> = words.iter().map(|w| squash(w)).collect();
+    for start in 0..squashed.len() {
+        let mut run = String::new();
+        for word in &squashed[start..] {
+            run.push_str(word);
+            if let Some((_, place)) = vocab_places.iter().find(|(s, _)| *s == run) {
+                return Some(place.to_string());
+            }
+        }
+    }
+    None
+}
+
+fn classify(tag: String, words: Vec<String>, places: &[(String, &str)]) -> Hashtag {
+    let lowered: Vec<String> = words.iter().map(|w| fold_accents(&w.to_lowercase())).collect();
+    let joined = lowered.concat();
+    let hazard = lowered
+        .iter()
+        .find_map(|w| HAZARDS.iter().find(|(h, _)| h == w).map(|(_, hazard)| *hazard));
+    let response = lowered.iter().any(|w| RESPONSE_WORDS.contains(&w.as_str()))
+        || joined.starts_with("prayfor")
+        || joined.starts_with("standwith");
+    let storm = storm_names(&words.join(" ")).into_iter().next().map(|(name, _)| name);
+    let location = find_location(&words, places);
+    let kind = if response {
+        TagKind::Response
+    } else if hazard.is_some() || storm.is_some() {
+        TagKind::Event
+    } else if location.is_some() {
+        TagKind::Location
+    } else {
+        TagKind::Other
+    };
+    Hashtag { tag, words, kind, hazard, storm, location, count: 1 }
+}
+
+/// Hashtags in `text`, deduplicated case-insensitively, in order of first
+/// appearance. `locations` adds place names to the built-in countries.
+pub(crate) fn extract(text: &str, locations: &[String]) -> Vec<Hashtag> {
+    let vocab = vocabulary(locations);
+    let places: Vec<(String, &str)> = COUNTRIES
+        .iter()
+        .copied()
+        .chain(locations.iter().map(String::as_str))
+        .map(|p| (squash(p), p))
+        .collect();
+    let mut tags: Vec<Hashtag> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for caps in HASHTAG.captures_iter(text) {
+        let body = &caps[1];
+        let key = body.to_lowercase();
+        if let Some(&i) = index.get(&key) {
+            tags[i].count += 1;
+            continue;
+        }
+        index.insert(key, tags.len());
+        tags.push(classify(format!("#{body}"), split_words(body, &vocab), &places));
+    }
+    tags
+}
+
+/// Extract hashtags and classify them as event, location or response tags.
+///
+/// Parameters
+/// ----------
+/// text : str
+///     Post or page text.
+/// locations : list[str] | None
+///     Extra place names (provinces, districts, cities) recognised as
+///     locations, e.g. from a gazetteer. Countries are built in.
+///
+/// Returns
+/// -------
+/// list[dict]
+///     One per distinct tag (case-insensitive), in order of first
+///     appearance: ``tag`` (as first written, with ``#``), ``words``
+///     (e.g. ``"Cyclone Freddy"``), ``kind`` (``"response"`` for relief /
+///     aid / solidarity tags, else ``"event"`` if a hazard or storm is
+///     named, else ``"location"``, else ``"other"``), ``hazard`` (e.g.
+///     ``"cyclone"``, ``"flood"``), ``storm`` (name per
+///     ``detect_storm_names``), ``location`` and ``count``.
+#[pyfunction]
+#[pyo3(signature = (text, locations=None))]
+pub fn extract_hashtags(py: Python<'_>, text: &str, locations: Option<Vec<String>>) -> PyResult<Vec<Py<PyDict>>> {
+    let tags = unlocked(py, "extract_hashtags", || extract(text, &locations.unwrap_or_default()))?;
+    tags.into_iter()
+        .map(|tag| {
+            let dict = PyDict::new_bound(py);
+            dict.set_item("tag", tag.tag)?;
+            dict.set_item("words", tag.words.join(" "))?;
+            dict.set_item("kind", tag.kind.as_str())?;
+            dict.set_item("hazard", tag.hazard)?;
+            dict.set_item("storm", tag.storm)?;
+            dict.set_item("location", tag.location)?;
+            dict.set_item("count", tag.count)?;
+            Ok(dict.unbind())
+        })
+        .collect()
+}
+
+/// Split a hashtag into words.
+///
+/// ``"#CycloneFreddy"`` → ``"Cyclone Freddy"``,
+/// ``"#COVID19Vaccine"`` → ``"COVID 19 Vaccine"``,
+/// ``"#prayformozambique"`` → ``"Pray For Mozambique"``. The ``#`` is
+/// optional.
+#[pyfunction]
+pub fn split_hashtag(py: Python<'_>, tag: &str) -> PyResult<String> {
+    unlocked(py, "split_hashtag", || split_tag(tag))
+}
+
+fn split_tag(tag: &str) -> String {
+    let body = tag.trim().trim_start_matches(['#', '＃']);
+    split_words(body, &vocabulary(&[])).join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(split_tag("#CycloneFreddy"), "Cyclone Freddy");
+        assert_eq!(split_tag("COVID19Vaccine"), "COVID 19 Vaccine");
+        assert_eq!(split_tag("#earthquake_turkey"), "earthquake turkey");
+        assert_eq!(split_tag("#prayformozambique"), "Pray For Mozambique");
+        assert_eq!(split_tag("#southsudanfloods"), "South Sudan Floods");
+        // Unbreakable lowercase runs stay whole
+        assert_eq!(split_tag("#blessed"), "blessed");
+        assert_eq!(split_tag("#zzzzzzzzz"), "zzzzzzzzz");
+    }
+
+    #[test]
+    fn test_classification() {
+        let text = "Thoughts with Beira #CycloneFreddy #MozambiqueFloods #FreddyRelief #Sofala #cyclonefreddy #tbt";
+        let tags = extract(text, &["Sofala".to_string()]);
+        let by_tag: HashMap<&str, &Hashtag> = tags.iter().map(|t| (t.tag.as_str(), t)).collect();
+
+        let freddy = by_tag["#CycloneFreddy"];
+        assert_eq!(freddy.kind, TagKind::Event);
+        assert_eq!(freddy.hazard, Some("cyclone"));
+        assert_eq!(freddy.storm.as_deref(), Some("Freddy"));
+        assert_eq!(freddy.count, 2);
+
+        let floods = by_tag["#MozambiqueFloods"];
+        assert_eq!((floods.kind, floods.hazard), (TagKind::Event, Some("flood")));
+        assert_eq!(floods.location.as_deref(), Some("Mozambique"));
+
+        assert_eq!(by_tag["#FreddyRelief"].kind, TagKind::Response);
+        assert_eq!(by_tag["#Sofala"].kind, TagKind::Location);
+        assert_eq!(by_tag["#tbt"].kind, TagKind::Other);
+        assert_eq!(tags.len(), 5);
+    }
+
+    #[test]
+    fn test_tag_boundaries() {
+        let tags = extract("See https://example.org/page#section and &#8217; C# plus #1 and #Mali, not #Somalia_", &[]);
+        let names: Vec<&str> = tags.iter().map(|t| t.tag.as_str()).collect();
+        assert_eq!(names, vec!["#Mali", "#Somalia_"]);
+        assert_eq!(tags[0].location.as_deref(), Some("Mali"));
+        assert_eq!(tags[1].location.as_deref(), Some("Somalia"));
+        let tags = extract("#PrayForSyria", &[]);
+        assert_eq!((tags[0].kind, tags[0].location.as_deref()), (TagKind::Response, Some("Syria")));
+    }
+}
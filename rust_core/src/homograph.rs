@@ -0,0 +1,198 @@
+//! Punycode homograph flagging.
+//!
+//! Spoofed sources register look-alikes of trusted domains — Cyrillic
+//! `і` in `reliefweb`, `rn` for `m`, an extra hyphen. Hosts are checked
+//! for labels mixing scripts and for registrable domains whose confusable
+//! skeleton (Unicode TR39) matches a known source without being it.
+
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::sync::RwLock;
+use unicode_security::{skeleton, MixedScript};
+use url::{Host, Url};
+
+use crate::errors::{guarded, unlocked};
+use crate::public_suffix::registrable_domain_of_host;
+use crate::url_canonical::canonicalize_url;
+
+/// Trusted sources most worth impersonating.
+static DEFAULT_KNOWN_DOMAINS: &[&str] = &[
+    "reliefweb.int", "unocha.org", "humdata.org", "who.int", "wfp.org", "unicef.org",
+    "unhcr.org", "iom.int", "ifrc.org", "icrc.org", "msf.org", "fews.net", "gdacs.org",
+];
+
+/// Known domain and its skeleton.
+struct KnownDomain {
+    domain: String,
+    skeleton: String,
+}
+
+impl KnownDomain {
+    fn new(domain: &str) -> Option<Self> {
+        let ascii = idna::domain_to_ascii(domain.trim().trim_end_matches('.')).ok()?;
+        if ascii.is_empty() {
+            return None;
+        }
+        Some(Self {
+            skeleton: lookalike_skeleton(&ascii),
+            domain: ascii,
+        })
+    }
+}
+
+fn default_known_domains() -> Vec<KnownDomain> {
+    DEFAULT_KNOWN_DOMAINS.iter().filter_map(|d| KnownDomain::new(d)).collect()
+}
+
+static KNOWN_DOMAINS: Lazy<RwLock<Vec<KnownDomain>>> =
+    Lazy::new(|| RwLock::new(default_known_domains()));
+
+/// Confusable skeleton of a domain in Unicode form, ignoring hyphens so
+/// `relief-web.int` collides with `reliefweb.int`.
+fn lookalike_skeleton(ascii_domain: &str) -> String {
+    let (unicode, _) = idna::domain_to_unicode(ascii_domain);
+    skeleton(&unicode.to_lowercase())
+        .filter(|c| *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Suspicion {
+    /// A host label mixes scripts (Latin with Cyrillic, ...).
+    MixedScript,
+    /// Confusable with this known domain.
+    Lookalike(String),
+}
+
+fn check_host(known: &[KnownDomain], ascii_host: &str) -> Option<Suspicion> {
+    let (unicode, _) = idna::domain_to_unicode(ascii_host);
+    if unicode.split('.').any(|label| !label.is_single_script()) {
+        return Some(Suspicion::MixedScript);
+    }
+    let domain = registrable_domain_of_host(ascii_host)?;
+    if known.iter().any(|k| k.domain == domain) {
+        return None;
+    }
+    let candidate = lookalike_skeleton(&domain);
+    known
+        .iter()
+        .find(|k| k.skeleton == candidate)
+        .map(|k| Suspicion::Lookalike(k.domain.clone()))
+}
+
+/// Number of known domains, for `runtime_info`.
+pub(crate) fn inventory() -> Vec<(&'static str, usize)> {
+    vec![("homograph_known_domains", KNOWN_DOMAINS.read().unwrap_or_else(|e| e.into_inner()).len())]
+}
+
+/// Canonical form of a URL and any homograph suspicion about its host.
+pub(crate) fn homograph_check(url_str: &str) -> (String, Option<Suspicion>) {
+    let canonical = canonicalize_url(url_str);
+    let host = match Url::parse(&canonical).ok().and_then(|u| match u.host() {
+        Some(Host::Domain(d)) => Some(d.to_string()),
+        _ => None,
+    }) {
+        Some(h) => h,
+        None => return (canonical, None),
+    };
+    let known = KNOWN_DOMAINS.read().unwrap_or_else(|e| e.into_inner());
+    let suspicion = check_host(&known, &host);
+    (canonical, suspicion)
+}
+
+/// Canonicalize a URL and flag hosts that look like spoofed sources.
+///
+/// Parameters
+/// ----------
+/// url : str
+///     The URL to check.
+///
+/// Returns
+/// -------
+/// dict
+///     ``{"canonical_url": str, "suspicious": bool,
+///     "reason": "mixed_script" | "lookalike" | None,
+///     "lookalike_of": str | None}``. ``lookalike_of`` names the known
+///     domain the host imitates.
+#[pyfunction]
+pub fn check_homograph(py: Python<'_>, url: &str) -> PyResult<Py<PyDict>> {
+    let (canonical, suspicion) = unlocked(py, "check_homograph", || homograph_check(url))?;
+    let (reason, lookalike_of) = match suspicion {
+        Some(Suspicion::MixedScript) => (Some("mixed_script"), None),
+        Some(Suspicion::Lookalike(domain)) => (Some("lookalike"), Some(domain)),
+        None => (None, None),
+    };
+    let dict = PyDict::new_bound(py);
+    dict.set_item("canonical_url", canonical)?;
+    dict.set_item("suspicious", reason.is_some())?;
+    dict.set_item("reason", reason)?;
+    dict.set_item("lookalike_of", lookalike_of)?;
+    Ok(dict.unbind())
+}
+
+/// Configure the trusted domains that look-alikes are checked against.
+///
+/// Parameters
+/// ----------
+/// domains : list[str] | None
+///     Registrable domains (Unicode or punycode). ``None`` restores the
+///     default list of core UN/NGO sources.
+/// replace : bool
+///     Replace the current list instead of adding to it. Default False.
+#[pyfunction]
+#[pyo3(signature = (domains=None, replace=false))]
+pub fn configure_known_domains(domains: Option<Vec<String>>, replace: bool) -> PyResult<()> {
+    guarded("configure_known_domains", || {
+        let mut known = KNOWN_DOMAINS.write().unwrap_or_else(|e| e.into_inner());
+        match domains {
+            None => *known = default_known_domains(),
+            Some(list) => {
+                if replace {
+                    known.clear();
+                }
+                known.extend(list.iter().filter_map(|d| KnownDomain::new(d)));
+            }
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(host: &str) -> Option<Suspicion> {
+        check_host(&default_known_domains(), &idna::domain_to_ascii(host).unwrap())
+    }
+
+    #[test]
+    fn test_known_and_unrelated_hosts_pass() {
+        assert_eq!(check("reliefweb.int"), None);
+        assert_eq!(check("www.reliefweb.int"), None);
+        assert_eq!(check("example.org"), None);
+        // Single-script non-Latin hosts are fine
+        assert_eq!(check("пример.рф"), None);
+    }
+
+    #[test]
+    fn test_mixed_script_label() {
+        // Latin with a Cyrillic "і"
+        assert_eq!(check("rel\u{456}efweb.int"), Some(Suspicion::MixedScript));
+    }
+
+    #[test]
+    fn test_lookalikes() {
+        assert_eq!(check("relief-web.int"), Some(Suspicion::Lookalike("reliefweb.int".into())));
+        assert_eq!(check("news.rnsf.org"), Some(Suspicion::Lookalike("msf.org".into())));
+        assert_eq!(check("un0cha.org"), Some(Suspicion::Lookalike("unocha.org".into())));
+    }
+
+    #[test]
+    fn test_homograph_check_canonicalizes() {
+        let (canonical, suspicion) = homograph_check("https://relief-web.int/report?utm_source=x");
+        assert_eq!(canonical, "https://relief-web.int/report");
+        assert!(suspicion.is_some());
+    }
+}
@@ -0,0 +1,307 @@
+//! Page metadata extraction — OpenGraph, JSON-LD and meta tags.
+//!
+//! One HTML parse yields the title, description, image, publication and
+//! modification times, author, canonical link and language, preferring
+//! OpenGraph, then JSON-LD article data, then plain meta tags and
+//! `<title>`.
+
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use scraper::{Html, Selector};
+use serde_json::Value;
+use url::Url;
+
+use crate::errors::unlocked;
+
+static META: Lazy<Selector> = Lazy::new(|| Selector::parse("meta[content]").unwrap());
+static TITLE: Lazy<Selector> = Lazy::new(|| Selector::parse("title").unwrap());
+static CANONICAL: Lazy<Selector> = Lazy::new(|| Selector::parse("link[rel][href]").unwrap());
+static HTML_ROOT: Lazy<Selector> = Lazy::new(|| Selector::parse("html").unwrap());
+static JSON_LD: Lazy<Selector> =
+    Lazy::new(|| Selector::parse(r#"script[type="application/ld+json"]"#).unwrap());
+
+// JSON-LD types treated as articles.
+static ARTICLE_TYPES: &[&str] = &[
+    "Article", "NewsArticle", "ReportageNewsArticle", "AnalysisNewsArticle", "BlogPosting",
+    "Report", "ScholarlyArticle", "PressRelease",
+];
+
+/// Article fields from one JSON-LD node.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct JsonLdArticle {
+    pub kind: String,
+    pub headline: Option<String>,
+    pub description: Option<String>,
+    pub date_published: Option<String>,
+    pub date_modified: Option<String>,
+    pub authors: Vec<String>,
+    pub publisher: Option<String>,
+    pub image: Option<String>,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct PageMetadata {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub published_time: Option<String>,
+    pub modified_time: Option<String>,
+    pub author: Option<String>,
+    pub site_name: Option<String>,
+    pub canonical_url: Option<String>,
+    pub language: Option<String>,
+    pub json_ld: Vec<JsonLdArticle>,
+}
+
+fn clean(text: &str) -> Option<String> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!text.is_empty()).then_some(text)
+}
+
+/// A JSON-LD value that may be a string, `{"name": ...}`, `{"url": ...}`
+/// or a list of those; returns every name found.
+fn names(value: &Value, key: &str) -> Vec<String> {
+    match value {
+        Value::String(s) => clean(s).into_iter().collect(),
+        Value::Object(map) => map.get(key).map(|v| names(v, key)).unwrap_or_default(),
+        Value::Array(items) => items.iter().flat_map(|v| names(v, key)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn first_name(value: Option<&Value>, key: &str) -> Option<String> {
+    value.and_then(|v| names(v, key).into_iter().next())
+}
+
+fn article_type(node: &Value) -> Option<String> {
+    let types = match node.get("@type")? {
+        Value::Array(items) => items.iter().filter_map(Value::as_str).collect(),
+        Value::String(s) => vec![s.as_str()],
+        _ => return None,
+    };
+    types
+        .into_iter()
+        .find(|t| ARTICLE_TYPES.contains(t))
+        .map(str::to_string)
+}
+
+/// Collect article nodes from a JSON-LD document (object, array or
+/// `@graph`).
+fn collect_articles(value: &Value, out: &mut Vec<JsonLdArticle>) {
+    match value {
+        Value::Array(items) => items.iter().for_each(|v| collect_articles(v, out)),
+        Value::Object(map) => {
+            if let Some(graph) = map.get("@graph") {
+                collect_articles(graph, out);
+            }
+            if let Some(kind) = article_type(value) {
+                out.push(JsonLdArticle {
+                    kind,
+                    headline: first_name(map.get("headline").or(map.get("name")), "name"),
+                    description: first_name(map.get("description"), "name"),
+                    date_published: first_name(map.get("datePublished"), "name"),
+                    date_modified: first_name(map.get("dateModified"), "name"),
+                    authors: map.get("author").map(|a| names(a, "name")).unwrap_or_default(),
+                    publisher: first_name(map.get("publisher"), "name"),
+                    image: first_name(map.get("image"), "url"),
+                    url: first_name(map.get("url").or(map.get("mainEntityOfPage")), "@id"),
+                });
+            }
+        }
+        _ => {}
+    }
+}
+
+pub(crate) fn extract(html: &str, base_url: Option<&str>) -> PageMetadata {
+    let doc = Html::parse_document(html);
+    let base = base_url.and_then(|b| Url::parse(b.trim()).ok());
+    let resolve = |href: &str| -> Option<String> {
+        let href = href.trim();
+        match &base {
+            Some(b) => b.join(href).ok().map(|u| u.to_string()),
+            None => clean(href),
+        }
+    };
+
+    // meta property/name (lowercased) -> first content
+    let mut metas: Vec<(String, String)> = Vec::new();
+    for el in doc.select(&META) {
+        let key = el.value().attr("property").or(el.value().attr("name"));
+        let key = key.or(el.value().attr("http-equiv"));
+        if let (Some(key), Some(content)) = (key, el.value().attr("content").and_then(clean)) {
+            metas.push((key.trim().to_lowercase(), content));
+        }
+    }
+    let meta = |keys: &[&str]| -> Option<String> {
+        keys.iter()
+            .find_map(|k| metas.iter().find(|(key, _)| key == k).map(|(_, v)| v.clone()))
+    };
+
+    let mut json_ld = Vec::new();
+    for script in doc.select(&JSON_LD) {
+        let text: String = script.text().collect();
+        match serde_json::from_str::<Value>(text.trim()) {
+            Ok(value) => collect_articles(&value, &mut json_ld),
+            Err(e) => tracing::debug!(base_url, error = %e, "skipping malformed JSON-LD"),
+        }
+    }
+    let article = json_ld.first();
+
+    let canonical_url = doc
+        .select(&CANONICAL)
+        .find(|el| {
+            el.value()
+                .attr("rel")
+                .is_some_and(|rel| rel.split_whitespace().any(|r| r.eq_ignore_ascii_case("canonical")))
+        })
+        .and_then(|el| resolve(el.value().attr("href").unwrap_or("")))
+        .or_else(|| meta(&["og:url"]).and_then(|u| resolve(&u)));
+
+    let language = doc
+        .select(&HTML_ROOT)
+        .next()
+        .and_then(|el| el.value().attr("lang").and_then(clean))
+        .or_else(|| meta(&["og:locale"]).map(|l| l.replace('_', "-")))
+        .or_else(|| meta(&["content-language", "language", "dc.language"]));
+
+    PageMetadata {
+        title: meta(&["og:title", "twitter:title"])
+            .or_else(|| article.and_then(|a| a.headline.clone()))
+            .or_else(|| doc.select(&TITLE).next().and_then(|t| clean(&t.text().collect::<String>()))),
+        description: meta(&["og:description", "twitter:description"])
+            .or_else(|| article.and_then(|a| a.description.clone()))
+            .or_else(|| meta(&["description", "dc.description"])),
+        image: meta(&["og:image", "og:image:url", "twitter:image"])
+            .or_else(|| article.and_then(|a| a.image.clone()))
+            .and_then(|i| resolve(&i)),
+        published_time: meta(&["article:published_time"])
+            .or_else(|| article.and_then(|a| a.date_published.clone()))
+            .or_else(|| meta(&["pubdate", "publishdate", "date", "dc.date", "dc.date.issued", "dcterms.created"])),
+        modified_time: meta(&["article:modified_time", "og:updated_time"])
+            .or_else(|| article.and_then(|a| a.date_modified.clone()))
+            .or_else(|| meta(&["dcterms.modified", "last-modified"])),
+        author: meta(&["article:author", "author", "dc.creator"])
+            .or_else(|| article.and_then(|a| a.authors.first().cloned())),
+        site_name: meta(&["og:site_name", "application-name"])
+            .or_else(|| article.and_then(|a| a.publisher.clone())),
+        canonical_url,
+        language,
+        json_ld,
+    }
+}
+
+fn article_dict<'py>(py: Python<'py>, a: JsonLdArticle) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new_bound(py);
+    dict.set_item("type", a.kind)?;
+    dict.set_item("headline", a.headline)?;
+    dict.set_item("description", a.description)?;
+    dict.set_item("date_published", a.date_published)?;
+    dict.set_item("date_modified", a.date_modified)?;
+    dict.set_item("authors", a.authors)?;
+    dict.set_item("publisher", a.publisher)?;
+    dict.set_item("image", a.image)?;
+    dict.set_item("url", a.url)?;
+    Ok(dict)
+}
+
+/// Extract page metadata from HTML in one parse.
+///
+/// Parameters
+/// ----------
+/// html : str
+///     Page HTML.
+/// base_url : str | None
+///     Page URL; when given, canonical and image links are resolved
+///     against it.
+///
+/// Returns
+/// -------
+/// dict
+///     ``title``, ``description``, ``image``, ``published_time``,
+///     ``modified_time``, ``author``, ``site_name``, ``canonical_url`` and
+///     ``language`` (each str or None; dates as found in the page), plus
+///     ``json_ld``: a list of article dicts (``type``, ``headline``,
+///     ``description``, ``date_published``, ``date_modified``,
+///     ``authors``, ``publisher``, ``image``, ``url``). OpenGraph wins over
+///     JSON-LD, which wins over plain meta tags and ``<title>``.
+#[pyfunction]
+#[pyo3(signature = (html, base_url=None))]
+pub fn extract_metadata(py: Python<'_>, html: &str, base_url: Option<&str>) -> PyResult<Py<PyDict>> {
+    let meta = unlocked(py, "extract_metadata", || extract(html, base_url))?;
+    let dict = PyDict::new_bound(py);
+    dict.set_item("title", meta.title)?;
+    dict.set_item("description", meta.description)?;
+    dict.set_item("image", meta.image)?;
+    dict.set_item("published_time", meta.published_time)?;
+    dict.set_item("modified_time", meta.modified_time)?;
+    dict.set_item("author", meta.author)?;
+    dict.set_item("site_name", meta.site_name)?;
+    dict.set_item("canonical_url", meta.canonical_url)?;
+    dict.set_item("language", meta.language)?;
+    let articles = PyList::empty_bound(py);
+    for article in meta.json_ld {
+        articles.append(article_dict(py, article)?)?;
+    }
+    dict.set_item("json_ld", articles)?;
+    Ok(dict.unbind())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<!doctype html><html lang="pt-MZ"><head>
+        <title>Ignored title</title>
+        <meta property="og:title" content="Cheias em Sofala">
+        <meta name="description" content="Plain description">
+        <meta property="og:image" content="/img/cheias.jpg">
+        <meta property="article:published_time" content="2025-03-14T08:00:00+02:00">
+        <link rel="canonical" href="/noticias/cheias-sofala">
+        <script type="application/ld+json">
+        {"@context": "https://schema.org", "@graph": [
+          {"@type": "WebSite", "name": "Jornal"},
+          {"@type": ["NewsArticle"], "headline": "Cheias em Sofala deixam milhares deslocados",
+           "datePublished": "2025-03-14", "dateModified": "2025-03-15",
+           "author": [{"@type": "Person", "name": "Ana Matsinhe"}, "Redação"],
+           "publisher": {"@type": "Organization", "name": "Jornal Exemplo"}}
+        ]}
+        </script></head><body><p>x</p></body></html>"#;
+
+    #[test]
+    fn test_precedence_and_resolution() {
+        let m = extract(PAGE, Some("https://jornal.example.mz/"));
+        assert_eq!(m.title.as_deref(), Some("Cheias em Sofala"));
+        assert_eq!(m.description.as_deref(), Some("Plain description"));
+        assert_eq!(m.image.as_deref(), Some("https://jornal.example.mz/img/cheias.jpg"));
+        assert_eq!(m.published_time.as_deref(), Some("2025-03-14T08:00:00+02:00"));
+        assert_eq!(m.modified_time.as_deref(), Some("2025-03-15"));
+        assert_eq!(m.canonical_url.as_deref(), Some("https://jornal.example.mz/noticias/cheias-sofala"));
+        assert_eq!(m.language.as_deref(), Some("pt-MZ"));
+        assert_eq!(m.author.as_deref(), Some("Ana Matsinhe"));
+        assert_eq!(m.site_name.as_deref(), Some("Jornal Exemplo"));
+    }
+
+    #[test]
+    fn test_json_ld_graph() {
+        let m = extract(PAGE, None);
+        assert_eq!(m.json_ld.len(), 1);
+        let a = &m.json_ld[0];
+        assert_eq!(a.kind, "NewsArticle");
+        assert_eq!(a.authors, vec!["Ana Matsinhe", "Redação"]);
+        assert_eq!(a.date_published.as_deref(), Some("2025-03-14"));
+    }
+
+    #[test]
+    fn test_fallbacks_and_bad_json() {
+        let html = r#"<html><head><title> Flood  update </title>
+            <meta property="og:locale" content="fr_FR">
+            <script type="application/ld+json">{not json</script></head></html>"#;
+        let m = extract(html, None);
+        assert_eq!(m.title.as_deref(), Some("Flood update"));
+        assert_eq!(m.language.as_deref(), Some("fr-FR"));
+        assert!(m.json_ld.is_empty());
+        assert_eq!(m.published_time, None);
+    }
+}
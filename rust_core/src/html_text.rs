@@ -0,0 +1,241 @@
+//! HTML-to-clean-text conversion.
+//!
+//! A fast pre-step before classification for pages where full content
+//! extraction isn't needed: drops scripts, styles and other non-content
+//! elements, decodes entities (the HTML5 parser does this), keeps
+//! paragraph and list structure as line breaks and normalizes whitespace.
+
+use ego_tree::iter::Edge;
+use pyo3::prelude::*;
+use scraper::{Html, Node};
+
+use crate::errors::unlocked;
+use crate::metrics::timer;
+
+// Elements whose content is never page text.
+pub(crate) static SKIPPED: &[&str] = &[
+    "script", "style", "noscript", "template", "head", "svg", "canvas", "iframe", "object",
+    "button", "select", "textarea",
+];
+
+// Elements that start a new paragraph.
+static BLOCKS: &[&str] = &[
+    "p", "div", "section", "article", "main", "header", "footer", "aside", "nav", "blockquote",
+    "h1", "h2", "h3", "h4", "h5", "h6", "ul", "ol", "dl", "table", "figure", "figcaption",
+    "form", "fieldset", "address", "hr", "pre", "details", "summary",
+];
+
+// Elements that start a new line.
+static LINES: &[&str] = &["li", "tr", "dt", "dd", "caption"];
+
+/// Accumulates text, collapsing whitespace and deferring breaks until the
+/// next text so no leading or doubled blank lines appear.
+#[derive(Default)]
+struct TextWriter {
+    out: String,
+    /// Newlines owed before the next text: 0, 1 (line) or 2 (paragraph).
+    pending_break: usize,
+    /// Space owed before the next text.
+    pending_space: bool,
+    /// Nesting depth inside `<pre>`, where whitespace is kept.
+    pre_depth: usize,
+}
+
+impl TextWriter {
+    fn request_break(&mut self, newlines: usize) {
+        self.pending_break = self.pending_break.max(newlines);
+    }
+
+    fn flush_separator(&mut self) {
+        if self.out.is_empty() {
+            self.pending_break = 0;
+        } else if self.pending_break > 0 {
+            while self.out.ends_with(' ') {
+                self.out.pop();
+            }
+            self.out.push_str(&"\n".repeat(self.pending_break));
+        } else if self.pending_space && !self.out.ends_with([' ', '\n']) {
+            self.out.push(' ');
+        }
+        self.pending_break = 0;
+        self.pending_space = false;
+    }
+
+    fn push_marker(&mut self, marker: &str) {
+        self.flush_separator();
+        self.out.push_str(marker);
+    }
+
+    fn push_text(&mut self, text: &str) {
+        if self.pre_depth > 0 {
+            if !text.is_empty() {
+                self.flush_separator();
+                self.out.push_str(text);
+            }
+            return;
+        }
+        let leading = text.starts_with(char::is_whitespace);
+        let trailing = text.ends_with(char::is_whitespace);
+        let mut words = text.split_whitespace().peekable();
+        if words.peek().is_none() {
+            self.pending_space |= !text.is_empty();
+            return;
+        }
+        self.pending_space |= leading;
+        self.flush_separator();
+        for (i, word) in words.enumerate() {
+            if i > 0 {
+                self.out.push(' ');
+            }
+            self.out.push_str(word);
+        }
+        self.pending_space = trailing;
+    }
+
+    fn finish(self) -> String {
+        self.out
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim()
+            .to_string()
+    }
+}
+
+pub(crate) fn convert(html: &str) -> String {
+    let doc = Html::parse_document(html);
+    let mut writer = TextWriter::default();
+    let mut skip_depth = 0usize;
+    // Item counters for open lists; None for unordered lists
+    let mut lists: Vec<Option<usize>> = Vec::new();
+
+    for edge in doc.tree.root().traverse() {
+        match edge {
+            Edge::Open(node) => match node.value() {
+                Node::Element(el) => {
+                    let name = el.name();
+                    if skip_depth > 0 || SKIPPED.contains(&name) {
+                        skip_depth += 1;
+                        continue;
+                    }
+                    match name {
+                        "br" => writer.request_break(1),
+                        "pre" => {
+                            writer.request_break(2);
+                            writer.pre_depth += 1;
+                        }
+                        "ul" => lists.push(None),
+                        "ol" => lists.push(Some(0)),
+                        "li" => {
+                            writer.request_break(1);
+                            let marker = match lists.last_mut() {
+                                Some(Some(n)) => {
+                                    *n += 1;
+                                    format!("{n}. ")
+                                }
+                                _ => "- ".to_string(),
+                            };
+                            writer.push_marker(&marker);
+                        }
+                        "td" | "th" => writer.pending_space = true,
+                        _ => {}
+                    }
+                    if BLOCKS.contains(&name) {
+                        writer.request_break(2);
+                    } else if LINES.contains(&name) && name != "li" {
+                        writer.request_break(1);
+                    }
+                }
+                Node::Text(text) if skip_depth == 0 => writer.push_text(text),
+                _ => {}
+            },
+            Edge::Close(node) => {
+                if let Node::Element(el) = node.value() {
+                    let name = el.name();
+                    if skip_depth > 0 {
+                        skip_depth -= 1;
+                        continue;
+                    }
+                    match name {
+                        "ul" | "ol" => {
+                            lists.pop();
+                        }
+                        "pre" => writer.pre_depth = writer.pre_depth.saturating_sub(1),
+                        _ => {}
+                    }
+                    if BLOCKS.contains(&name) {
+                        writer.request_break(2);
+                    } else if LINES.contains(&name) {
+                        writer.request_break(1);
+                    }
+                }
+            }
+        }
+    }
+    writer.finish()
+}
+
+/// Convert HTML to clean plain text.
+///
+/// Scripts, styles and form controls are dropped and entities decoded.
+/// Block elements become paragraphs separated by a blank line, list items
+/// become ``- item`` / ``1. item`` lines, ``<br>`` becomes a line break
+/// and other whitespace collapses to single spaces (kept inside ``<pre>``).
+///
+/// Parameters
+/// ----------
+/// html : str
+///     A full document or a fragment.
+///
+/// Returns
+/// -------
+/// str
+///     The text, with no leading or trailing whitespace.
+#[pyfunction]
+pub fn html_to_text(py: Python<'_>, html: &str) -> PyResult<String> {
+    let _timer = timer("html_to_text");
+    unlocked(py, "html_to_text", || convert(html))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paragraphs_and_entities() {
+        let html = "<html><head><title>x</title><style>p{}</style></head><body>\
+            <h1>Cyclone  Freddy</h1><p>Over 500&nbsp;dead &amp; 1,000\n   injured.</p>\
+            <script>var x = 1;</script><p>Aid <b>arriving</b>.</p></body></html>";
+        assert_eq!(
+            convert(html),
+            "Cyclone Freddy\n\nOver 500 dead & 1,000 injured.\n\nAid arriving."
+        );
+    }
+
+    #[test]
+    fn test_lists_and_breaks() {
+        let html = "<p>Needs:</p><ul><li>Shelter</li><li>Water<br>and sanitation</li></ul>\
+            <ol><li>Beira</li><li>Dondo</li></ol>";
+        assert_eq!(
+            convert(html),
+            "Needs:\n\n- Shelter\n- Water\nand sanitation\n\n1. Beira\n2. Dondo"
+        );
+    }
+
+    #[test]
+    fn test_inline_spacing_and_tables() {
+        assert_eq!(convert("<span>a</span><span>b</span> <i>c</i>"), "ab c");
+        assert_eq!(
+            convert("<table><tr><th>Province</th><th>Deaths</th></tr><tr><td>Zambezia</td><td>12</td></tr></table>"),
+            "Province Deaths\nZambezia 12"
+        );
+    }
+
+    #[test]
+    fn test_pre_and_empty() {
+        assert_eq!(convert("<pre>a\n  b</pre>"), "a\n  b");
+        assert_eq!(convert(""), "");
+        assert_eq!(convert("<script>only()</script>"), "");
+    }
+}
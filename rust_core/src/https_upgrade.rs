@@ -0,0 +1,211 @@
+//! Per-host HTTPS upgrade policy.
+//!
+//! Feeds and old links still point at `http://` for sites that now serve
+//! everything over HTTPS, so the same article gets counted twice.
+//! `canonicalize_url` rewrites the scheme for hosts known to support
+//! HTTPS — a configured list plus hosts learned from fetches.
+
+use once_cell::sync::Lazy;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::collections::HashSet;
+use std::sync::RwLock;
+use url::Url;
+
+#[cfg(feature = "python")]
+use crate::errors::guarded;
+
+/// Core sources that serve HTTPS everywhere.
+static DEFAULT_HTTPS_HOSTS: &[&str] = &[
+    "*.reliefweb.int", "*.unocha.org", "*.humdata.org", "*.who.int", "*.wfp.org",
+    "*.unicef.org", "*.unhcr.org", "*.iom.int", "*.ifrc.org", "*.icrc.org",
+];
+
+#[derive(Default)]
+struct HttpsHosts {
+    /// Hosts, without a leading `www.`.
+    exact: HashSet<String>,
+    /// `*.apex` patterns (apex and every subdomain), stored as the apex.
+    suffixes: HashSet<String>,
+}
+
+impl HttpsHosts {
+    fn with_defaults() -> Self {
+        let mut hosts = Self::default();
+        for host in DEFAULT_HTTPS_HOSTS {
+            hosts.insert(host);
+        }
+        hosts
+    }
+
+    fn insert(&mut self, spec: &str) {
+        let spec = spec.trim().trim_end_matches('.').to_lowercase();
+        match spec.strip_prefix("*.") {
+            Some(apex) => self.suffixes.insert(apex.to_string()),
+            None => self.exact.insert(strip_www(&spec).to_string()),
+        };
+    }
+
+    fn supports(&self, host: &str) -> bool {
+        let host = strip_www(host);
+        if self.exact.contains(host) {
+            return true;
+        }
+        // Walk up the labels: a.b.example.org, b.example.org, example.org
+        let mut rest = host;
+        loop {
+            if self.suffixes.contains(rest) {
+                return true;
+            }
+            match rest.split_once('.') {
+                Some((_, parent)) => rest = parent,
+                None => return false,
+            }
+        }
+    }
+}
+
+fn strip_www(host: &str) -> &str {
+    host.strip_prefix("www.").unwrap_or(host)
+}
+
+static HTTPS_HOSTS: Lazy<RwLock<HttpsHosts>> = Lazy::new(|| RwLock::new(HttpsHosts::with_defaults()));
+
+fn upgrade_with(hosts: &HttpsHosts, url_str: String) -> String {
+    let mut parsed = match Url::parse(&url_str) {
+        Ok(u) if u.scheme() == "http" => u,
+        _ => return url_str,
+    };
+    let supported = parsed.host_str().is_some_and(|h| hosts.supports(h));
+    // An explicit non-default port may be a plain-HTTP service
+    if !supported || parsed.port().is_some() || parsed.set_scheme("https").is_err() {
+        return url_str;
+    }
+    parsed.to_string()
+}
+
+/// Sizes of the HTTPS host lists, for `runtime_info`.
+pub(crate) fn inventory() -> Vec<(&'static str, usize)> {
+    let hosts = HTTPS_HOSTS.read().unwrap_or_else(|e| e.into_inner());
+    vec![("https_hosts", hosts.exact.len()), ("https_host_patterns", hosts.suffixes.len())]
+}
+
+/// Rewrite `http://` to `https://` for hosts known to support HTTPS.
+pub(crate) fn upgrade_scheme(url_str: String) -> String {
+    let hosts = HTTPS_HOSTS.read().unwrap_or_else(|e| e.into_inner());
+    upgrade_with(&hosts, url_str)
+}
+
+/// Configure hosts whose ``http://`` URLs ``canonicalize_url`` upgrades.
+///
+/// Parameters
+/// ----------
+/// hosts : list[str] | None
+///     Hosts (``"example.org"``, which also covers ``www.example.org``) or
+///     ``"*.example.org"`` for the apex and all subdomains. ``None``
+///     restores the default list of core UN/NGO sources.
+/// replace : bool
+///     Replace the current hosts instead of adding to them. Default False.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (hosts=None, replace=false))]
+pub fn configure_https_hosts(hosts: Option<Vec<String>>, replace: bool) -> PyResult<()> {
+    guarded("configure_https_hosts", || {
+        let mut current = HTTPS_HOSTS.write().unwrap_or_else(|e| e.into_inner());
+        match hosts {
+            None => *current = HttpsHosts::with_defaults(),
+            Some(list) => {
+                if replace {
+                    *current = HttpsHosts::default();
+                }
+                for host in list {
+                    current.insert(&host);
+                }
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Record whether a fetch showed the URL's host serving HTTPS.
+///
+/// Call with the final URL of a fetch: an ``https://`` URL that loaded
+/// means the host supports HTTPS; ``supported=False`` (e.g. a TLS error
+/// or an https→http redirect) forgets a learned host.
+///
+/// Returns
+/// -------
+/// bool
+///     True if the stored policy changed.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (url, supported=true))]
+pub fn record_https_support(url: &str, supported: bool) -> PyResult<bool> {
+    guarded("record_https_support", || {
+        let host = match Url::parse(url.trim()).ok().and_then(|u| u.host_str().map(str::to_lowercase)) {
+            Some(h) => strip_www(&h).to_string(),
+            None => return Ok(false),
+        };
+        let mut hosts = HTTPS_HOSTS.write().unwrap_or_else(|e| e.into_inner());
+        Ok(if supported {
+            hosts.exact.insert(host)
+        } else {
+            hosts.exact.remove(&host)
+        })
+    })
+}
+
+/// Hosts currently upgraded to HTTPS, for persisting learned hosts.
+///
+/// Returns
+/// -------
+/// list[str]
+///     Sorted hosts, with patterns as ``"*.apex"``.
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn https_upgrade_hosts() -> PyResult<Vec<String>> {
+    guarded("https_upgrade_hosts", || {
+        let hosts = HTTPS_HOSTS.read().unwrap_or_else(|e| e.into_inner());
+        let mut list: Vec<String> = hosts
+            .exact
+            .iter()
+            .cloned()
+            .chain(hosts.suffixes.iter().map(|apex| format!("*.{apex}")))
+            .collect();
+        list.sort();
+        Ok(list)
+    })
+}
+
+#[cfg(all(test, feature = "python"))]
+mod tests {
+    use super::*;
+
+    fn hosts(specs: &[&str]) -> HttpsHosts {
+        let mut hosts = HttpsHosts::default();
+        for spec in specs {
+            hosts.insert(spec);
+        }
+        hosts
+    }
+
+    #[test]
+    fn test_upgrade_listed_hosts() {
+        let h = hosts(&["example.org", "*.gov.example"]);
+        assert_eq!(upgrade_with(&h, "http://www.example.org/a?b=1".into()), "https://www.example.org/a?b=1");
+        assert_eq!(upgrade_with(&h, "http://ingc.gov.example/x".into()), "https://ingc.gov.example/x");
+        assert_eq!(upgrade_with(&h, "http://sub.example.org/x".into()), "http://sub.example.org/x");
+        assert_eq!(upgrade_with(&h, "http://example.org:8080/x".into()), "http://example.org:8080/x");
+    }
+
+    #[test]
+    fn test_learned_host_applies_to_canonicalization() {
+        let url = "http://learned-https.example/story?utm_source=x";
+        assert_eq!(crate::url_canonical::canonicalize_url(url), "http://learned-https.example/story");
+        assert!(record_https_support("https://www.learned-https.example/", true).unwrap());
+        assert_eq!(crate::url_canonical::canonicalize_url(url), "https://learned-https.example/story");
+        assert!(https_upgrade_hosts().unwrap().contains(&"learned-https.example".to_string()));
+        assert!(record_https_support("https://learned-https.example/", false).unwrap());
+        assert!(!record_https_support("not a url", true).unwrap());
+    }
+}
@@ -0,0 +1,283 @@
+//! HXL-tagged CSV export.
+//!
+//! HDX tools (HXL Proxy, Quick Charts, the HDX data check) read CSV whose
+//! second row holds HXL hashtags (`#date`, `#adm1+code`, `#affected`).
+//! `HxlCsvWriter` writes processed records that way, each column taken
+//! from a record field by a configurable mapping, so exports load into
+//! HDX without a manual tagging step.
+
+use once_cell::sync::Lazy;
+use pyo3::exceptions::{PyOSError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList, PyTuple};
+use regex::Regex;
+
+use crate::jsonl_batch::{open_output, Output};
+use crate::errors::guarded;
+
+// A hashtag and its attributes: "#affected+killed", "#adm1 +code".
+static HXL_TAG: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^#[A-Za-z][A-Za-z0-9_]*(?:\s*\+[A-Za-z][A-Za-z0-9_]*)*$").unwrap());
+
+/// Default `(header, hashtag, field)` mapping for `process_article`
+/// records enriched with country, P-code and date fields.
+const DEFAULT_COLUMNS: &[(&str, &str, &str)] = &[
+    ("Date", "#date+published", "published"),
+    ("Country", "#country+code", "country"),
+    ("Admin 1 P-code", "#adm1+code", "adm1_pcode"),
+    ("Admin area", "#loc+name", "admin_area.0"),
+    ("Sector", "#sector", "need_types"),
+    ("Impact type", "#impact+type", "impact_type"),
+    ("Killed", "#affected+killed", "figures.deaths"),
+    ("Injured", "#affected+injured", "figures.injured"),
+    ("Missing", "#affected+missing", "figures.missing"),
+    ("Displaced", "#affected+displaced", "figures.displaced"),
+    ("Affected", "#affected", "figures.people_affected"),
+    ("In need", "#inneed", "figures.people_in_need"),
+    ("Severity", "#severity", "severity"),
+    ("Source URL", "#meta+url", "canonical_url"),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Column {
+    pub header: String,
+    pub tag: String,
+    /// Dotted path into the record: dict keys, or indexes into lists and
+    /// tuples.
+    pub path: Vec<String>,
+}
+
+impl Column {
+    pub(crate) fn new(header: &str, tag: &str, field: &str) -> Result<Self, String> {
+        let tag = tag.trim();
+        if !HXL_TAG.is_match(tag) {
+            return Err(format!("invalid HXL hashtag {tag:?} for column {header:?}"));
+        }
+        if field.split('.').any(str::is_empty) {
+            return Err(format!("invalid field path {field:?} for column {header:?}"));
+        }
+        Ok(Self {
+            header: header.to_string(),
+            // Canonical form lowercases and drops spaces before attributes
+            tag: tag.to_lowercase().split_whitespace().collect(),
+            path: field.split('.').map(str::to_string).collect(),
+        })
+    }
+}
+
+pub(crate) fn default_columns() -> Vec<Column> {
+    DEFAULT_COLUMNS
+        .iter()
+        .map(|(header, tag, field)| Column::new(header, tag, field).expect("valid default column"))
+        .collect()
+}
+
+/// The value at `path`, or None where a step is missing.
+fn lookup<'py>(record: &Bound<'py, PyAny>, path: &[String]) -> PyResult<Option<Bound<'py, PyAny>>> {
+    let mut value = record.clone();
+    for step in path {
+        let next = if let Ok(dict) = value.downcast::<PyDict>() {
+            dict.get_item(step)?
+        } else if let (Ok(index), true) = (step.parse::<usize>(), value.is_instance_of::<PyList>() || value.is_instance_of::<PyTuple>()) {
+            (index < value.len()?).then(|| value.get_item(index)).transpose()?
+        } else {
+            None
+        };
+        match next {
+            Some(v) if !v.is_none() => value = v,
+            _ => return Ok(None),
+        }
+    }
+    Ok(Some(value))
+}
+
+/// Cell text: lists joined with `", "`, booleans as `true` / `false`.
+fn cell(value: Option<Bound<'_, PyAny>>) -> PyResult<String> {
+    let Some(value) = value else { return Ok(String::new()) };
+    if let Ok(flag) = value.extract::<bool>() {
+        return Ok(flag.to_string());
+    }
+    if let Ok(text) = value.extract::<String>() {
+        return Ok(text);
+    }
+    if value.is_instance_of::<PyList>() || value.is_instance_of::<PyTuple>() {
+        let items = value.iter()?.map(|item| cell(Some(item?))).collect::<PyResult<Vec<_>>>()?;
+        return Ok(items.join(", "));
+    }
+    Ok(value.str()?.to_string())
+}
+
+/// Streams records to an HXL-tagged CSV file.
+///
+/// The first row holds the column headers, the second their HXL
+/// hashtags; both are written when the writer is created.
+///
+/// Parameters
+/// ----------
+/// path : str
+///     Output file (``.gz`` is gzip-compressed; ``"-"`` is stdout).
+/// columns : list[tuple[str, str, str]] | None
+///     ``(header, hashtag, field)`` per column. ``field`` is a dotted path
+///     into each record: dict keys, or list/tuple indexes, e.g.
+///     ``"figures.deaths"`` or ``"admin_area.0"``. Default: date,
+///     country, ``#adm1+code`` (``adm1_pcode``), admin area, ``#sector``
+///     (``need_types``), impact type, killed / injured / missing /
+///     displaced / ``#affected`` / ``#inneed`` figures, severity and
+///     source URL.
+///
+/// Raises
+/// ------
+/// ValueError
+///     If a hashtag isn't valid HXL or a field path is empty.
+/// OSError
+///     If the file cannot be created.
+#[pyclass(module = "moltis_rust_core")]
+pub struct HxlCsvWriter {
+    columns: Vec<Column>,
+    writer: Option<csv::Writer<Output>>,
+    rows: usize,
+}
+
+impl HxlCsvWriter {
+    fn writer(&mut self) -> PyResult<&mut csv::Writer<Output>> {
+        self.writer.as_mut().ok_or_else(|| PyValueError::new_err("writer is closed"))
+    }
+}
+
+fn io_error(e: impl std::fmt::Display) -> PyErr {
+    PyOSError::new_err(e.to_string())
+}
+
+#[pymethods]
+impl HxlCsvWriter {
+    #[new]
+    #[pyo3(signature = (path, columns=None))]
+    fn py_new(path: &str, columns: Option<Vec<(String, String, String)>>) -> PyResult<Self> {
+        guarded("HxlCsvWriter.__new__", || {
+            let columns = match columns {
+                Some(columns) => columns
+                    .iter()
+                    .map(|(header, tag, field)| Column::new(header, tag, field))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(PyValueError::new_err)?,
+                None => default_columns(),
+            };
+            let mut writer = csv::Writer::from_writer(open_output(path).map_err(PyOSError::new_err)?);
+            writer.write_record(columns.iter().map(|c| &c.header)).map_err(io_error)?;
+            writer.write_record(columns.iter().map(|c| &c.tag)).map_err(io_error)?;
+            Ok(Self { columns, writer: Some(writer), rows: 0 })
+        })
+    }
+
+    /// Write one record (a dict); missing fields are left empty.
+    fn write(&mut self, record: &Bound<'_, PyAny>) -> PyResult<()> {
+        guarded("HxlCsvWriter.write", || {
+            let row = self
+                .columns
+                .iter()
+                .map(|c| cell(lookup(record, &c.path)?))
+                .collect::<PyResult<Vec<_>>>()?;
+            self.writer()?.write_record(&row).map_err(io_error)?;
+            self.rows += 1;
+            Ok(())
+        })
+    }
+
+    /// Write records in order.
+    fn write_many(&mut self, records: &Bound<'_, PyAny>) -> PyResult<()> {
+        guarded("HxlCsvWriter.write_many", || {
+            for record in records.iter()? {
+                self.write(&record?)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Flush and close the file; returns the number of data rows.
+    fn close(&mut self) -> PyResult<usize> {
+        guarded("HxlCsvWriter.close", || {
+            if let Some(writer) = self.writer.take() {
+                writer.into_inner().map_err(|e| io_error(e.error()))?.finish().map_err(io_error)?;
+            }
+            Ok(self.rows)
+        })
+    }
+
+    /// Data rows written so far.
+    #[getter]
+    fn rows(&self) -> PyResult<usize> {
+        guarded("HxlCsvWriter.rows", || Ok(self.rows))
+    }
+
+    /// ``(header, hashtag)`` per column.
+    #[getter]
+    fn columns(&self) -> PyResult<Vec<(String, String)>> {
+        guarded("HxlCsvWriter.columns", || Ok(self.columns.iter().map(|c| (c.header.clone(), c.tag.clone())).collect()))
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyResult<PyRef<'_, Self>> {
+        guarded("HxlCsvWriter.__enter__", || Ok(slf))
+    }
+
+    #[pyo3(signature = (*_args))]
+    fn __exit__(&mut self, _args: &Bound<'_, PyTuple>) -> PyResult<bool> {
+        guarded("HxlCsvWriter.__exit__", || {
+            self.close()?;
+            Ok(false)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_validation() {
+        let column = Column::new("Killed", "#Affected +Killed", "figures.deaths").unwrap();
+        assert_eq!(column.tag, "#affected+killed");
+        assert_eq!(column.path, vec!["figures", "deaths"]);
+        assert!(Column::new("Bad", "affected", "x").is_err());
+        assert!(Column::new("Bad", "#affected+", "x").is_err());
+        assert!(Column::new("Bad", "#affected", "figures.").is_err());
+        assert_eq!(default_columns().len(), DEFAULT_COLUMNS.len());
+    }
+
+    #[test]
+    fn test_writes_hxl_rows() {
+        pyo3::prepare_freethreaded_python();
+        let path = std::env::temp_dir().join(format!("moltis_hxl_{}.csv", std::process::id()));
+        let path = path.to_str().unwrap();
+        Python::with_gil(|py| {
+            let columns = vec![
+                ("Date".to_string(), "#date".to_string(), "published".to_string()),
+                ("Admin".to_string(), "#adm1+name".to_string(), "admin_area.0".to_string()),
+                ("Killed".to_string(), "#affected+killed".to_string(), "figures.deaths".to_string()),
+                ("Sector".to_string(), "#sector".to_string(), "need_types".to_string()),
+                ("Risk".to_string(), "#indicator+risk".to_string(), "is_risk".to_string()),
+            ];
+            let mut writer = HxlCsvWriter::py_new(path, Some(columns)).unwrap();
+            let records = py
+                .eval_bound(
+                    "[{'published': '2023-03-12', 'admin_area': ('Sofala', 1), 'figures': {'deaths': 53}, \
+                       'need_types': ['shelter', 'wash'], 'is_risk': False}, \
+                      {'published': None, 'admin_area': None, 'figures': {}}]",
+                    None,
+                    None,
+                )
+                .unwrap();
+            writer.write_many(&records).unwrap();
+            assert_eq!(writer.close().unwrap(), 2);
+            assert!(writer.write(&PyDict::new_bound(py)).is_err());
+        });
+        let written = std::fs::read_to_string(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(
+            written,
+            "Date,Admin,Killed,Sector,Risk\n\
+             #date,#adm1+name,#affected+killed,#sector,#indicator+risk\n\
+             2023-03-12,Sofala,53,\"shelter, wash\",false\n\
+             ,,,,\n"
+        );
+    }
+}
@@ -0,0 +1,158 @@
+//! Runtime capability and configuration introspection.
+//!
+//! A deployment can run with the wrong keyword pack, an empty gazetteer
+//! or a build missing a feature and still produce plausible output.
+//! `runtime_info` reports what this process is actually running with —
+//! compiled features, keyword pack sizes and hashes, gazetteer size,
+//! URL rule and tracking table sizes, pattern inventories — so a
+//! pipeline can assert it at startup.
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::moltis_config::MoltisConfig;
+use crate::pipeline::DEFAULT_PIPELINE;
+use crate::{
+    emdat, figure_extraction, homograph, https_upgrade, politeness, profiling, promo_filter, public_suffix, shorteners,
+    stopwords, storage, storm_names, url_blocklist, url_canonical, url_rules,
+};
+use crate::errors::guarded;
+
+/// Cargo features this build was compiled with.
+fn features() -> Vec<&'static str> {
+    [
+        ("python", cfg!(feature = "python")),
+        ("arrow", cfg!(feature = "arrow")),
+        ("sqlite", cfg!(feature = "sqlite")),
+        ("cli", cfg!(feature = "cli")),
+        ("wasm", cfg!(feature = "wasm")),
+        ("fetch", cfg!(feature = "fetch")),
+    ]
+    .into_iter()
+    .filter_map(|(name, on)| on.then_some(name))
+    .collect()
+}
+
+fn counts<'py, K: ToPyObject>(py: Python<'py>, entries: impl IntoIterator<Item = (K, usize)>) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new_bound(py);
+    for (key, count) in entries {
+        dict.set_item(key, count)?;
+    }
+    Ok(dict)
+}
+
+/// What the native layer is running with.
+///
+/// Parameters
+/// ----------
+/// config : MoltisConfig | None
+///     Report this config's keyword packs and gazetteer instead of the
+///     built-in defaults used by ``process_article``.
+///
+/// Returns
+/// -------
+/// dict
+///     ``version`` (crate version); ``features`` (compiled Cargo
+///     features); ``pipeline`` (``source`` ``"built-in"`` or
+///     ``"config"``, with ``config_path`` / ``config_generation`` for a
+///     config; ``stages``; ``impact`` / ``need`` packs as ``{"labels",
+///     "keywords", "hash"}`` and ``risk`` as ``{"keywords", "hash"}``,
+///     where equal hashes mean equal keyword contents whatever their
+///     order; ``min_impact_hits`` / ``min_need_hits``; ``admin_areas``,
+///     the gazetteer size); ``url`` (sizes of the tracking, redirect,
+///     mobile-host, rule, blocklist, HTTPS, homograph, politeness and
+///     shortener tables, plus ``public_suffix_source`` ``"embedded"`` or
+///     ``"mapped"`` and ``public_suffix_rules``); ``patterns`` (figure
+///     pattern, storm name, promotional marker and EM-DAT table sizes);
+///     ``stopwords`` (language → word count); ``storage`` (default
+///     ``backend`` and ``dir``); ``profiling`` (bool).
+#[pyfunction]
+#[pyo3(signature = (config=None))]
+pub fn runtime_info(py: Python<'_>, config: Option<PyRef<'_, MoltisConfig>>) -> PyResult<Py<PyDict>> {
+    guarded("runtime_info", || {
+        let info = PyDict::new_bound(py);
+        info.set_item("version", env!("CARGO_PKG_VERSION"))?;
+        info.set_item("features", features())?;
+
+        let pipeline = match &config {
+            Some(config) => {
+                let (generation, settings) = config.shared().snapshot();
+                let pipeline = settings.pipeline.describe(py)?;
+                pipeline.set_item("source", "config")?;
+                pipeline.set_item("config_path", config.source_path())?;
+                pipeline.set_item("config_generation", generation)?;
+                pipeline
+            }
+            None => {
+                let pipeline = DEFAULT_PIPELINE.describe(py)?;
+                pipeline.set_item("source", "built-in")?;
+                pipeline
+            }
+        };
+        info.set_item("pipeline", pipeline)?;
+
+        let url = counts(
+            py,
+            [
+                url_canonical::inventory(),
+                url_rules::inventory(),
+                url_blocklist::inventory(),
+                https_upgrade::inventory(),
+                homograph::inventory(),
+                politeness::inventory(),
+                shorteners::inventory(),
+            ]
+            .concat(),
+        )?;
+        let (source, rules) = public_suffix::rule_source();
+        url.set_item("public_suffix_source", source)?;
+        url.set_item("public_suffix_rules", rules)?;
+        info.set_item("url", url)?;
+
+        let patterns = [
+            figure_extraction::inventory(),
+            storm_names::inventory(),
+            promo_filter::inventory(),
+            emdat::inventory(),
+        ]
+        .concat();
+        info.set_item("patterns", counts(py, patterns)?)?;
+        info.set_item("stopwords", counts(py, stopwords::inventory())?)?;
+
+        let (backend, dir) = storage::default_backend();
+        let storage = PyDict::new_bound(py);
+        storage.set_item("backend", backend)?;
+        storage.set_item("dir", (backend != "memory").then(|| dir.to_string_lossy().into_owned()))?;
+        info.set_item("storage", storage)?;
+        info.set_item("profiling", profiling::enabled())?;
+        Ok(info.unbind())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_runtime_info() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let info = runtime_info(py, None).unwrap().into_bound(py);
+            let features: Vec<String> = info.get_item("features").unwrap().unwrap().extract().unwrap();
+            assert!(features.contains(&"python".to_string()));
+            let pipeline = info.get_item("pipeline").unwrap().unwrap();
+            assert_eq!(pipeline.get_item("source").unwrap().extract::<String>().unwrap(), "built-in");
+            let impact = pipeline.get_item("impact").unwrap();
+            assert_eq!(impact.get_item("labels").unwrap().extract::<usize>().unwrap(), 5);
+            assert_eq!(impact.get_item("hash").unwrap().extract::<String>().unwrap().len(), 16);
+            let url = info.get_item("url").unwrap().unwrap();
+            assert!(url.get_item("tracking_keys").unwrap().extract::<usize>().unwrap() > 0);
+            assert!(url.get_item("public_suffix_rules").unwrap().extract::<usize>().unwrap() > 1000);
+            let patterns = info.get_item("patterns").unwrap().unwrap();
+            let count = |name| patterns.get_item(name).unwrap().extract::<usize>().unwrap();
+            assert_eq!((count("figure_patterns"), count("figure_patterns_fr")), (7, 7));
+            let stopwords = info.get_item("stopwords").unwrap().unwrap();
+            assert!(stopwords.get_item("sw").unwrap().extract::<usize>().unwrap() > 0);
+        });
+    }
+}
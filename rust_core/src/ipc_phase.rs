@@ -0,0 +1,337 @@
+//! Explicit IPC / Cadre Harmonisé phase mentions.
+//!
+//! Food security reporting quotes classification results directly:
+//! "1.2 million people in IPC Phase 3 or above", "Phase 3+ population of
+//! 1.2 million", "Cadre Harmonisé phase 3 (juin-août 2024)". Unlike the
+//! keyword ladder in `severity_from_text`, these are published figures;
+//! `extract_ipc_phases` reads the phase, the population quoted with it and
+//! the analysis period.
+
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use regex::Regex;
+
+use crate::errors::unlocked;
+
+// "IPC Phase 4", "CH phase 3", "IPC 3+", "Phases 3-5", "Phase 3 or above",
+// "phase 3 à 5"
+static PHASE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(?:(?P<sys>IPC|CH|Cadre\s+Harmonis[ée])(?:\s*/\s*(?:IPC|CH))?\s*(?:acute\s+food\s+insecurity\s+|AFI\s+)?)?(?P<kw>phases?\s*)?(?P<phase>[1-5])\b(?P<range>\s*\+|\s+(?:or|and)\s+(?:above|higher|worse|more)\b|\s*(?:-|–|to|à|a)\s*(?P<upper>[1-5])\b|\s+(?:ou|et)\s+plus\b)?",
+    )
+    .unwrap()
+});
+
+// Context that makes a bare "Phase 3" an IPC / CH phase.
+static FOOD_SECURITY: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\bIPC\b|\bCH\b|cadre harmonis|food insecurity|insécurité alimentaire|insegurança alimentar|famine|\bcrisis\b|\bemergency\b|\bcrise\b|\burgence\b").unwrap()
+});
+
+static CADRE_HARMONISE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\bCH\b|cadre harmonis").unwrap());
+static IPC: Lazy<Regex> = Lazy::new(|| Regex::new(r"\bIPC\b").unwrap());
+
+// "1.2 million people", "1,200,000", "1 200 000 personnes", "300k"
+static POPULATION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(?P<num>\d{1,3}(?:[,\u{a0} ]\d{3})+|\d+(?:[.,]\d+)?)(?:\s*(?P<scale>millions?|m|thousand|k|mille)\b)?(?:\s+(?:de\s+)?(?P<people>people|persons|individuals|personnes|pessoas|inhabitants|children))?",
+    )
+    .unwrap()
+});
+
+static POPULATION_LEAD: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)(?:population(?:\s+of)?|estimated|total(?:\s+of)?|:)\s*$").unwrap());
+
+const MONTHS: &str = r"jan(?:uary|vier)?|feb(?:ruary)?|févr(?:ier)?|fevr(?:ier)?|mar(?:ch|s)?|apr(?:il)?|avr(?:il)?|may|mai|june?|juin|july?|juil(?:let)?|aug(?:ust)?|août|aout|sep(?:t(?:ember|embre)?)?|oct(?:ober|obre)?|nov(?:ember|embre)?|dec(?:ember)?|déc(?:embre)?";
+
+// "June–August 2024", "Oct 2024 - Feb 2025", "from March to May 2025",
+// "octobre 2024"
+static PERIOD: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?i)\b(?P<m1>{MONTHS})\.?(?:\s+(?P<y1>(?:19|20)\d\d))?(?:\s*(?:-|–|—|to|through|until|and|à|a)\s*(?P<m2>{MONTHS})\.?)?\s+(?P<y2>(?:19|20)\d\d)\b"
+    ))
+    .unwrap()
+});
+
+static SENTENCE_END: Lazy<Regex> = Lazy::new(|| Regex::new(r"[.!?;]\s+|\n+").unwrap());
+
+static PROJECTED: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\bproject(?:ed|ion)|\bprojet[ée]|\bprojeç|\bexpected\b|\bforecast").unwrap());
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PhaseMention {
+    /// "IPC" or "CH".
+    pub system: &'static str,
+    pub phase: u8,
+    /// Highest phase covered: 5 for "Phase 3+", 4 for "Phases 3-4".
+    pub phase_max: u8,
+    pub population: Option<i64>,
+    pub period: Option<String>,
+    /// `YYYY-MM` bounds of the period.
+    pub period_start: Option<String>,
+    pub period_end: Option<String>,
+    pub projected: bool,
+    /// Byte range of the phase mention.
+    pub start: usize,
+    pub end: usize,
+}
+
+fn month_number(name: &str) -> u32 {
+    let name = name.to_lowercase();
+    const PREFIXES: &[(&str, u32)] = &[
+        ("jan", 1), ("feb", 2), ("fév", 2), ("fev", 2), ("mar", 3), ("apr", 4), ("avr", 4), ("may", 5), ("mai", 5),
+        ("juin", 6), ("jun", 6), ("juil", 7), ("jul", 7), ("aug", 8), ("aoû", 8), ("aou", 8), ("sep", 9), ("oct", 10),
+        ("nov", 11), ("dec", 12), ("déc", 12),
+    ];
+    PREFIXES.iter().find(|(p, _)| name.starts_with(p)).map_or(1, |(_, m)| *m)
+}
+
+fn parse_population(caps: &regex::Captures<'_>) -> Option<i64> {
+    let num = &caps["num"];
+    let scale = match caps.name("scale").map(|m| m.as_str().to_lowercase()) {
+        Some(s) if s.starts_with('m') && s != "mille" => 1_000_000.0,
+        Some(_) => 1_000.0,
+        None => 1.0,
+    };
+    let grouped = num.len() > 4 && num.chars().rev().nth(3).is_some_and(|c| !c.is_ascii_digit());
+    let value: f64 = if grouped {
+        num.chars().filter(char::is_ascii_digit).collect::<String>().parse().ok()?
+    } else {
+        num.replace(',', ".").parse().ok()?
+    };
+    Some((value * scale).round() as i64)
+}
+
+/// `(start, end, value)` of population figures in a sentence.
+fn populations(sentence: &str) -> Vec<(usize, usize, i64)> {
+    POPULATION
+        .captures_iter(sentence)
+        .filter(|caps| {
+            caps.name("scale").is_some()
+                || caps.name("people").is_some()
+                || POPULATION_LEAD.is_match(&sentence[..caps.get(0).unwrap().start()])
+        })
+        .filter_map(|caps| {
+            let m = caps.get(0).unwrap();
+            Some((m.start(), m.end(), parse_population(&caps)?))
+        })
+        .collect()
+}
+
+fn periods(sentence: &str) -> Vec<(usize, usize, String, String, String)> {
+    PERIOD
+        .captures_iter(sentence)
+        .map(|caps| {
+            let m = caps.get(0).unwrap();
+            let y2: i32 = caps["y2"].parse().unwrap();
+            let m1 = month_number(&caps["m1"]);
+            let m2 = caps.name("m2").map_or(m1, |m| month_number(m.as_str()));
+            // "October to February 2025" starts the year before
+            let y1 = caps.name("y1").map_or(if m1 > m2 { y2 - 1 } else { y2 }, |y| y.as_str().parse().unwrap());
+            let text = m.as_str().trim().to_string();
+            (m.start(), m.end(), text, format!("{y1:04}-{m1:02}"), format!("{y2:04}-{m2:02}"))
+        })
+        .collect()
+}
+
+/// Distance between two byte ranges (0 when they overlap).
+fn gap(a: (usize, usize), b: (usize, usize)) -> usize {
+    if a.1 <= b.0 {
+        b.0 - a.1
+    } else {
+        a.0.saturating_sub(b.1)
+    }
+}
+
+fn sentence_mentions(text: &str, offset: usize, sentence: &str) -> Vec<PhaseMention> {
+    let context = FOOD_SECURITY.is_match(sentence);
+    // Bare phases take the system named in the sentence, else the one the
+    // text uses throughout
+    let system = if CADRE_HARMONISE.is_match(sentence) {
+        "CH"
+    } else if IPC.is_match(sentence) || IPC.is_match(text) || !CADRE_HARMONISE.is_match(text) {
+        "IPC"
+    } else {
+        "CH"
+    };
+    let mut mentions: Vec<PhaseMention> = PHASE
+        .captures_iter(sentence)
+        .filter_map(|caps| {
+            let sys = caps.name("sys").map(|m| m.as_str().to_uppercase());
+            let keyword = caps.name("kw").is_some();
+            let range = caps.name("range").map(|m| m.as_str());
+            let accepted = match sys.as_deref() {
+                Some("IPC") => true,
+                Some(_) => keyword,
+                // A bare "Phase 3+" or "Phase 3" in food security context
+                None => keyword && (context || range.is_some_and(|r| r.trim() == "+")),
+            };
+            if !accepted {
+                return None;
+            }
+            let phase: u8 = caps["phase"].parse().unwrap();
+            let phase_max = match (range, caps.name("upper")) {
+                (_, Some(upper)) => upper.as_str().parse::<u8>().unwrap().max(phase),
+                (Some(_), None) => 5,
+                (None, None) => phase,
+            };
+            let system = match sys.as_deref() {
+                Some("IPC") => "IPC",
+                Some(_) => "CH",
+                None => system,
+            };
+            let m = caps.get(0).unwrap();
+            Some(PhaseMention {
+                system,
+                phase,
+                phase_max,
+                population: None,
+                period: None,
+                period_start: None,
+                period_end: None,
+                projected: PROJECTED.is_match(sentence),
+                start: offset + m.start(),
+                end: offset + m.end(),
+            })
+        })
+        .collect();
+    if mentions.is_empty() {
+        return mentions;
+    }
+
+    // Pair mentions and figures one-to-one, closest first, so "1.2 million
+    // in Phase 3 and 300,000 in Phase 4" gives each phase its own figure
+    let spans: Vec<(usize, usize)> = mentions.iter().map(|m| (m.start - offset, m.end - offset)).collect();
+    let figures: Vec<_> = populations(sentence)
+        .into_iter()
+        .filter(|(s, e, _)| spans.iter().all(|span| gap(*span, (*s, *e)) > 0))
+        .collect();
+    let mut pairs: Vec<(usize, usize, usize)> = spans
+        .iter()
+        .enumerate()
+        .flat_map(|(i, span)| figures.iter().enumerate().map(move |(j, f)| (gap(*span, (f.0, f.1)), i, j)))
+        .collect();
+    pairs.sort_unstable();
+    let mut used = vec![false; figures.len()];
+    for (_, i, j) in pairs {
+        if mentions[i].population.is_none() && !used[j] {
+            mentions[i].population = Some(figures[j].2);
+            used[j] = true;
+        }
+    }
+
+    let periods = periods(sentence);
+    for (mention, span) in mentions.iter_mut().zip(&spans) {
+        if let Some((_, _, text, start, end)) = periods.iter().min_by_key(|p| gap(*span, (p.0, p.1))) {
+            mention.period = Some(text.clone());
+            mention.period_start = Some(start.clone());
+            mention.period_end = Some(end.clone());
+        }
+    }
+    mentions
+}
+
+/// IPC / CH phase mentions in text order.
+pub(crate) fn extract(text: &str) -> Vec<PhaseMention> {
+    let mut mentions = Vec::new();
+    let mut start = 0;
+    let ends = SENTENCE_END.find_iter(text).map(|m| (m.start(), m.end())).chain([(text.len(), text.len())]);
+    for (end, next) in ends {
+        if end > start {
+            mentions.extend(sentence_mentions(text, start, &text[start..end]));
+        }
+        start = next;
+    }
+    mentions
+}
+
+/// Extract explicit IPC / Cadre Harmonisé phase classifications.
+///
+/// Reads phase references such as "IPC Phase 4", "Phase 3+ population of
+/// 1.2 million", "Phases 3 to 5" or "Cadre Harmonisé phase 3", with the
+/// population and analysis period quoted in the same sentence. A bare
+/// "Phase 3" only counts in food-security context (IPC, CH, food
+/// insecurity, crisis/emergency) or with a "+". Unlike
+/// ``severity_from_text``, nothing is inferred from wording.
+///
+/// Parameters
+/// ----------
+/// text : str
+///     Report or article text (English or French).
+///
+/// Returns
+/// -------
+/// list[dict]
+///     In text order: ``system`` (``"IPC"`` or ``"CH"``), ``phase``,
+///     ``phase_max`` (5 for "Phase 3+" / "or above", the upper bound for
+///     ranges, else ``phase``), ``population`` (int or None), ``period``
+///     (as written, e.g. ``"June-August 2024"``, or None),
+///     ``period_start`` / ``period_end`` (``"YYYY-MM"``), ``projected``
+///     (the sentence describes a projection) and ``start`` / ``end``
+///     (character offsets of the phase reference).
+#[pyfunction]
+pub fn extract_ipc_phases(py: Python<'_>, text: &str) -> PyResult<Vec<Py<PyDict>>> {
+    let mentions = unlocked(py, "extract_ipc_phases", || extract(text))?;
+    let mut char_pos = (0usize, 0usize);
+    let mut chars_at = |byte: usize| {
+        char_pos.1 += text[char_pos.0..byte].chars().count();
+        char_pos.0 = byte;
+        char_pos.1
+    };
+    mentions
+        .into_iter()
+        .map(|mention| {
+            let dict = PyDict::new_bound(py);
+            dict.set_item("system", mention.system)?;
+            dict.set_item("phase", mention.phase)?;
+            dict.set_item("phase_max", mention.phase_max)?;
+            dict.set_item("population", mention.population)?;
+            dict.set_item("period", mention.period)?;
+            dict.set_item("period_start", mention.period_start)?;
+            dict.set_item("period_end", mention.period_end)?;
+            dict.set_item("projected", mention.projected)?;
+            dict.set_item("start", chars_at(mention.start))?;
+            dict.set_item("end", chars_at(mention.end))?;
+            Ok(dict.unbind())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phase_forms() {
+        let text = "An estimated 1.2 million people face Crisis or worse (IPC Phase 3 or above) between June and \
+                    August 2024. Phase 3+ population of 850,000. Cadre Harmonisé phase 4 in Diffa. \
+                    Phase 2 of the road project starts in May 2025.";
+        let mentions = extract(text);
+        assert_eq!(mentions.len(), 3);
+        let m = &mentions[0];
+        assert_eq!((m.system, m.phase, m.phase_max, m.population), ("IPC", 3, 5, Some(1_200_000)));
+        assert_eq!(m.period.as_deref(), Some("June and August 2024"));
+        assert_eq!((m.period_start.as_deref(), m.period_end.as_deref()), (Some("2024-06"), Some("2024-08")));
+        assert_eq!((mentions[1].phase, mentions[1].phase_max, mentions[1].population), (3, 5, Some(850_000)));
+        assert_eq!((mentions[2].system, mentions[2].phase, mentions[2].population), ("CH", 4, None));
+        assert_eq!(&text[m.start..m.end], "IPC Phase 3 or above");
+    }
+
+    #[test]
+    fn test_populations_paired() {
+        let mentions = extract("In Somalia, 1,2 million personnes en IPC phase 3 et 300 000 personnes en phase 4 (octobre 2024 - février 2025), selon les projections.");
+        assert_eq!(mentions.len(), 2);
+        assert_eq!(mentions[0].population, Some(1_200_000));
+        assert_eq!(mentions[1].population, Some(300_000));
+        assert!(mentions.iter().all(|m| m.projected));
+        assert_eq!(mentions[1].period_start.as_deref(), Some("2024-10"));
+        assert_eq!(mentions[1].period_end.as_deref(), Some("2025-02"));
+    }
+
+    #[test]
+    fn test_ranges_and_years() {
+        let mentions = extract("Food insecurity: Phases 3-4 affect 2.3m people in 2024; IPC 5 (Catastrophe) for 45k.");
+        assert_eq!((mentions[0].phase, mentions[0].phase_max, mentions[0].population), (3, 4, Some(2_300_000)));
+        assert_eq!((mentions[1].phase, mentions[1].phase_max, mentions[1].population), (5, 5, Some(45_000)));
+        assert!(mentions[0].period.is_none());
+    }
+}
@@ -0,0 +1,280 @@
+//! JSONL batch processing for backfills.
+//!
+//! Reprocessing an archive through `Pipeline.run` still builds a Python
+//! dict per article. `process_jsonl` reads raw articles from a JSONL file,
+//! runs the pipeline over fixed-size chunks in parallel and writes each
+//! article back with its record, never holding more than one chunk.
+//! `.gz` paths are read and written gzip-compressed; `-` is stdin/stdout.
+
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use pyo3::exceptions::{PyOSError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rayon::prelude::*;
+use serde_json::{Map, Value};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+
+use crate::errors::unlocked;
+use crate::metrics::timer;
+use crate::pipeline::{current_time, Pipeline, PipelineConfig};
+
+/// Lines processed per parallel chunk.
+const DEFAULT_CHUNK_LINES: usize = 10_000;
+/// Key the record is written under in each output object.
+const RECORD_KEY: &str = "enrichment";
+
+// Accepted input field names, first present wins
+const TITLE_FIELDS: &[&str] = &["title", "headline"];
+const BODY_FIELDS: &[&str] = &["body", "text", "content"];
+const URL_FIELDS: &[&str] = &["url", "link"];
+const PUBLISHED_FIELDS: &[&str] = &["published", "published_at", "date"];
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct BatchStats {
+    pub read: usize,
+    pub written: usize,
+    /// Malformed JSON, non-objects, or no body and no URL.
+    pub skipped: usize,
+}
+
+fn field<'a>(article: &'a Map<String, Value>, names: &[&str]) -> Option<&'a str> {
+    names.iter().find_map(|name| article.get(*name)?.as_str())
+}
+
+/// Enrich one input line; None if it can't be processed.
+fn process_line(config: &PipelineConfig, line: &str, now: chrono::DateTime<chrono::Utc>) -> Option<String> {
+    let Ok(Value::Object(mut article)) = serde_json::from_str::<Value>(line) else {
+        return None;
+    };
+    let body = field(&article, BODY_FIELDS);
+    let url = field(&article, URL_FIELDS);
+    if body.is_none() && url.is_none() {
+        return None;
+    }
+    let record = config.run(
+        field(&article, TITLE_FIELDS).unwrap_or_default(),
+        body.unwrap_or_default(),
+        url.unwrap_or_default(),
+        field(&article, PUBLISHED_FIELDS),
+        None,
+        now,
+    );
+    article.insert(RECORD_KEY.into(), record.to_json());
+    Some(Value::Object(article).to_string())
+}
+
+pub(crate) fn open_input(path: &str) -> Result<Box<dyn BufRead + Send>, String> {
+    if path == "-" {
+        return Ok(Box::new(BufReader::new(std::io::stdin())));
+    }
+    let file = File::open(path).map_err(|e| format!("cannot open {path}: {e}"))?;
+    Ok(if path.ends_with(".gz") {
+        Box::new(BufReader::new(MultiGzDecoder::new(file)))
+    } else {
+        Box::new(BufReader::new(file))
+    })
+}
+
+pub(crate) enum Output {
+    Stdout(BufWriter<std::io::Stdout>),
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+}
+
+impl Output {
+    /// Flush, writing the gzip trailer if compressed.
+    pub(crate) fn finish(self) -> std::io::Result<()> {
+        match self {
+            Output::Stdout(mut w) => w.flush(),
+            Output::Plain(mut w) => w.flush(),
+            Output::Gzip(w) => w.finish()?.flush(),
+        }
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Output::Stdout(w) => w.write(buf),
+            Output::Plain(w) => w.write(buf),
+            Output::Gzip(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Output::Stdout(w) => w.flush(),
+            Output::Plain(w) => w.flush(),
+            Output::Gzip(w) => w.flush(),
+        }
+    }
+}
+
+pub(crate) fn open_output(path: &str) -> Result<Output, String> {
+    if path == "-" {
+        return Ok(Output::Stdout(BufWriter::new(std::io::stdout())));
+    }
+    let file = BufWriter::new(File::create(path).map_err(|e| format!("cannot create {path}: {e}"))?);
+    Ok(if path.ends_with(".gz") {
+        Output::Gzip(GzEncoder::new(file, Compression::default()))
+    } else {
+        Output::Plain(file)
+    })
+}
+
+/// Stream `input` through the pipeline into `output`, `chunk_lines` lines
+/// at a time, keeping input order.
+pub(crate) fn process_stream(
+    config: &PipelineConfig,
+    input: impl BufRead,
+    output: &mut dyn Write,
+    chunk_lines: usize,
+) -> Result<BatchStats, String> {
+    let now = current_time();
+    let mut stats = BatchStats::default();
+    let mut lines = input.lines();
+    loop {
+        let chunk = lines
+            .by_ref()
+            .take(chunk_lines.max(1))
+            .collect::<Result<Vec<String>, _>>()
+            .map_err(|e| format!("read failed: {e}"))?;
+        if chunk.is_empty() {
+            break;
+        }
+        let chunk: Vec<&String> = chunk.iter().filter(|l| !l.trim().is_empty()).collect();
+        let enriched: Vec<Option<String>> = chunk.par_iter().map(|line| process_line(config, line, now)).collect();
+        stats.read += chunk.len();
+        for line in enriched {
+            match line {
+                Some(line) => {
+                    output.write_all(line.as_bytes()).map_err(|e| format!("write failed: {e}"))?;
+                    output.write_all(b"\n").map_err(|e| format!("write failed: {e}"))?;
+                    stats.written += 1;
+                }
+                None => stats.skipped += 1,
+            }
+        }
+    }
+    Ok(stats)
+}
+
+/// Run the article pipeline over a JSONL file and write enriched JSONL.
+///
+/// Each input line is a JSON object with ``title`` (or ``headline``),
+/// ``body`` (or ``text`` / ``content``), ``url`` (or ``link``) and
+/// optionally ``published`` (or ``published_at`` / ``date``). It is
+/// written back with its fields unchanged plus an ``"enrichment"`` key, the
+/// ``process_article`` record (``fingerprint`` as a 32-digit hex
+/// string). Lines are processed in parallel chunks with the GIL
+/// released; output keeps input order. Paths ending in ``.gz`` are
+/// gzip-compressed.
+///
+/// Parameters
+/// ----------
+/// input_path : str
+///     JSONL file of raw articles.
+/// output_path : str
+///     Destination, overwritten if it exists.
+/// config : Pipeline | None
+///     Pipeline to run. Default: all stages, default keyword packs.
+/// chunk_lines : int
+///     Lines held in memory and processed per parallel chunk.
+///     Default 10000.
+///
+/// Returns
+/// -------
+/// dict
+///     ``{"read": int, "written": int, "skipped": int}``; lines that are
+///     not JSON objects or have neither body nor URL are skipped.
+///
+/// Raises
+/// ------
+/// ValueError
+///     If ``chunk_lines`` is 0.
+/// OSError
+///     If a file cannot be read or written.
+#[pyfunction]
+#[pyo3(signature = (input_path, output_path, config=None, chunk_lines=DEFAULT_CHUNK_LINES))]
+pub fn process_jsonl(
+    py: Python<'_>,
+    input_path: &str,
+    output_path: &str,
+    config: Option<PyRef<'_, Pipeline>>,
+    chunk_lines: usize,
+) -> PyResult<Py<PyDict>> {
+    if chunk_lines == 0 {
+        return Err(PyValueError::new_err("chunk_lines must be at least 1"));
+    }
+    let _timer = timer("process_jsonl");
+    let config = config.map(|p| PipelineConfig::clone(&p.config())).unwrap_or_default();
+    let stats = unlocked(py, "process_jsonl", || {
+        let input = open_input(input_path)?;
+        let mut output = open_output(output_path)?;
+        let stats = process_stream(&config, input, &mut output, chunk_lines)?;
+        output.finish().map_err(|e| format!("write failed: {e}"))?;
+        Ok::<_, String>(stats)
+    })?
+    .map_err(PyOSError::new_err)?;
+    if stats.skipped > 0 {
+        tracing::warn!(input_path, skipped = stats.skipped, read = stats.read, "skipped unusable JSONL lines");
+    }
+    let dict = PyDict::new_bound(py);
+    dict.set_item("read", stats.read)?;
+    dict.set_item("written", stats.written)?;
+    dict.set_item("skipped", stats.skipped)?;
+    Ok(dict.unbind())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = r#"{"id": 1, "title": "Floods in Sofala", "body": "Floods displaced 5,000 people.", "url": "https://example.org/a?utm_source=x"}
+not json
+
+{"id": 2, "text": "Cyclone kills 12 in Beira", "link": "https://example.org/b", "published": "2025-03-14"}
+{"id": 3, "title": "no body or url"}
+[1, 2]
+"#;
+
+    #[test]
+    fn test_process_stream() {
+        let mut out = Vec::new();
+        let stats = process_stream(&PipelineConfig::default(), INPUT.as_bytes(), &mut out, 2).unwrap();
+        assert_eq!(stats, BatchStats { read: 5, written: 2, skipped: 3 });
+        let lines: Vec<Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines[0]["id"], 1);
+        assert_eq!(lines[0]["enrichment"]["canonical_url"], "https://example.org/a");
+        assert_eq!(lines[0]["enrichment"]["figures"]["people_affected"], 5000);
+        assert_eq!(lines[1]["id"], 2);
+        assert_eq!(lines[1]["enrichment"]["published"], "2025-03-14T00:00:00Z");
+        assert_eq!(lines[1]["enrichment"]["fingerprint"].as_str().unwrap().len(), 32);
+    }
+
+    #[test]
+    fn test_gzip_files() {
+        let dir = std::env::temp_dir();
+        let input = dir.join(format!("moltis_in_{}.jsonl.gz", std::process::id()));
+        let output = dir.join(format!("moltis_out_{}.jsonl.gz", std::process::id()));
+        let (input, output) = (input.to_str().unwrap(), output.to_str().unwrap());
+        let mut writer = open_output(input).unwrap();
+        writer.write_all(INPUT.as_bytes()).unwrap();
+        writer.finish().unwrap();
+        let mut out = open_output(output).unwrap();
+        let stats = process_stream(&PipelineConfig::default(), open_input(input).unwrap(), &mut out, 100).unwrap();
+        out.finish().unwrap();
+        assert_eq!(stats.written, 2);
+        assert_eq!(open_input(output).unwrap().lines().count(), 2);
+        std::fs::remove_file(input).unwrap();
+        std::fs::remove_file(output).unwrap();
+        assert!(open_input("/nonexistent/in.jsonl").is_err());
+    }
+}
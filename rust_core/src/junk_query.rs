@@ -0,0 +1,173 @@
+//! Junk-query heuristics — crawl traps in query strings.
+//!
+//! Faceted search, calendars and opaque state blobs generate endless
+//! distinct URLs for the same few pages. These heuristics score how
+//! crawl-worthy a URL looks so such traps don't fill the frontier.
+
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+use regex::Regex;
+use std::collections::HashMap;
+use url::Url;
+
+use crate::url_canonical::canonicalize_url;
+use crate::errors::guarded;
+
+/// Parameter counts above this start to cost.
+const MANY_PARAMS: usize = 5;
+const MANY_PARAMS_PENALTY: f64 = 0.08;
+const BASE64_MIN_LEN: usize = 40;
+const BASE64_PENALTY: f64 = 0.4;
+const FACET_PENALTY: f64 = 0.5;
+const CALENDAR_PENALTY: f64 = 0.6;
+const LONG_URL: usize = 300;
+const LONG_URL_PENALTY: f64 = 0.2;
+
+// Filter / sort / view keys typical of faceted search.
+static FACET_KEYS: &[&str] = &[
+    "filter", "filters", "facet", "facets", "fq", "refine", "sort", "sort_by", "order", "orderby",
+    "dir", "view", "display", "limit", "per_page", "pagesize", "items_per_page", "f",
+];
+static CALENDAR_KEYS: &[&str] = &[
+    "date", "day", "month", "year", "week", "date_from", "date_to", "start_date", "end_date",
+    "calendar", "ical", "tribe-bar-date", "eventdisplay",
+];
+
+static BASE64_VALUE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[A-Za-z0-9+/_%-]+=*$").unwrap());
+// "/calendar/2025/03/", "/events/2025-03-14/"
+static CALENDAR_PATH: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)/(?:calendar|events?|agenda)/(?:19|20)\d{2}(?:[/-]\d{1,2}){0,2}/?").unwrap()
+});
+// Array-style facet keys: "f[0]", "filter[type]"
+static ARRAY_KEY: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[\w-]+(?:\[[^\]]*\]|%5B[^%]*%5D)").unwrap());
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Worthiness {
+    /// 1.0 for a clean URL, down to 0.0 for an obvious trap.
+    pub score: f64,
+    pub reasons: Vec<&'static str>,
+}
+
+fn looks_base64(value: &str) -> bool {
+    if value.len() < BASE64_MIN_LEN || !BASE64_VALUE.is_match(value) {
+        return false;
+    }
+    let has = |f: fn(&char) -> bool| value.chars().any(|c| f(&c));
+    has(char::is_ascii_uppercase) && has(char::is_ascii_lowercase) && has(char::is_ascii_digit)
+}
+
+pub(crate) fn assess(url_str: &str) -> Worthiness {
+    let canonical = canonicalize_url(url_str);
+    let parsed = match Url::parse(&canonical) {
+        Ok(u) => u,
+        Err(_) => return Worthiness::default(),
+    };
+    let pairs: Vec<(&str, &str)> = parsed
+        .query()
+        .unwrap_or("")
+        .split('&')
+        .filter(|p| !p.is_empty())
+        .map(|p| p.split_once('=').unwrap_or((p, "")))
+        .collect();
+    let mut key_counts: HashMap<String, usize> = HashMap::new();
+    for (key, _) in &pairs {
+        *key_counts.entry(key.to_lowercase()).or_default() += 1;
+    }
+
+    let mut penalty = 0.0;
+    let mut reasons = Vec::new();
+    if pairs.len() > MANY_PARAMS {
+        penalty += MANY_PARAMS_PENALTY * (pairs.len() - MANY_PARAMS) as f64;
+        reasons.push("many_params");
+    }
+    if pairs.iter().any(|(_, v)| looks_base64(v)) {
+        penalty += BASE64_PENALTY;
+        reasons.push("base64_value");
+    }
+    let facet_keys = pairs
+        .iter()
+        .filter(|(k, _)| FACET_KEYS.contains(&k.to_lowercase().as_str()) || ARRAY_KEY.is_match(k))
+        .count();
+    let repeated_keys = key_counts.values().any(|&n| n > 1);
+    if facet_keys >= 2 || (facet_keys >= 1 && repeated_keys) {
+        penalty += FACET_PENALTY;
+        reasons.push("faceted_search");
+    }
+    let calendar_query = key_counts.keys().any(|k| CALENDAR_KEYS.contains(&k.as_str()));
+    if CALENDAR_PATH.is_match(parsed.path()) || calendar_query {
+        penalty += CALENDAR_PENALTY;
+        reasons.push("calendar");
+    }
+    if canonical.len() > LONG_URL {
+        penalty += LONG_URL_PENALTY;
+        reasons.push("long_url");
+    }
+    Worthiness {
+        score: (1.0 - penalty).clamp(0.0, 1.0),
+        reasons,
+    }
+}
+
+/// Score how crawl-worthy a URL's query string looks.
+///
+/// Flags excessive parameters, long base64-looking values, faceted-search
+/// filter/sort combinations and calendar pages. Tracking parameters are
+/// stripped first and don't count.
+///
+/// Parameters
+/// ----------
+/// url : str
+///     The URL to assess.
+///
+/// Returns
+/// -------
+/// tuple[float, list[str]]
+///     ``(score, reasons)``: score from 1.0 (clean) to 0.0 (trap), and the
+///     heuristics that fired (``"many_params"``, ``"base64_value"``,
+///     ``"faceted_search"``, ``"calendar"``, ``"long_url"``). Unparseable
+///     URLs score 0.0 with no reasons.
+#[pyfunction]
+pub fn crawl_worthiness(url: &str) -> PyResult<(f64, Vec<&'static str>)> {
+    guarded("crawl_worthiness", || {
+        let w = assess(url);
+        Ok((w.score, w.reasons))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_url() {
+        let w = assess("https://example.org/report?id=42&utm_source=a&utm_medium=b&utm_campaign=c&fbclid=d");
+        assert_eq!(w.score, 1.0);
+        assert!(w.reasons.is_empty());
+    }
+
+    #[test]
+    fn test_faceted_search() {
+        let w = assess("https://example.org/reports?sort=date&filter=flood&view=grid");
+        assert!(w.reasons.contains(&"faceted_search"));
+        assert!(w.score <= 0.5);
+        let arrays = assess("https://example.org/updates?f[0]=country:MOZ&f[1]=theme:health");
+        assert!(arrays.reasons.contains(&"faceted_search"));
+    }
+
+    #[test]
+    fn test_calendar_and_base64() {
+        assert!(assess("https://example.org/events/2025-03/").reasons.contains(&"calendar"));
+        assert!(assess("https://example.org/agenda?month=2025-04").reasons.contains(&"calendar"));
+        let blob = "eyJhbGciOiJIUzI1NiJ9eyJzdGF0ZSI6InBhZ2UtMiIsIm4iOjQyfQ";
+        let w = assess(&format!("https://example.org/list?state={blob}"));
+        assert_eq!(w.reasons, vec!["base64_value"]);
+    }
+
+    #[test]
+    fn test_many_params_compound() {
+        let url = "https://example.org/s?a=1&b=2&c=3&d=4&e=5&g=6&h=7&i=8&sort=x&order=y";
+        let w = assess(url);
+        assert!(w.reasons.contains(&"many_params") && w.reasons.contains(&"faceted_search"));
+        assert!(w.score < 0.2);
+    }
+}
@@ -0,0 +1,146 @@
+//! Keyphrase extraction — RAKE (Rapid Automatic Keyword Extraction).
+//!
+//! Candidate phrases are the runs of words between stopwords and
+//! punctuation. Each word scores degree / frequency (words that appear in
+//! longer phrases score higher) and a phrase scores the sum of its words.
+//! Feeds event tagging and the trending-topics view.
+
+use pyo3::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+use crate::errors::unlocked;
+use crate::stopwords::is_stopword_in;
+use crate::tokenize::tokenize;
+
+/// Longer candidate runs are split; they are rarely real phrases.
+const MAX_PHRASE_WORDS: usize = 4;
+
+fn is_phrase_word(word: &str, lang: Option<&str>) -> bool {
+    !is_stopword_in(word, lang) && word.chars().any(char::is_alphabetic)
+}
+
+/// Candidate phrases (lowercased words) in text order.
+fn candidates(text: &str, lang: Option<&str>) -> Vec<Vec<String>> {
+    let tokens = tokenize(text, None);
+    let mut phrases: Vec<Vec<String>> = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+    let mut prev_end = 0;
+    for token in tokens {
+        // Anything but whitespace between words (punctuation) ends a phrase
+        let gap_breaks = !text[prev_end..token.start].chars().all(char::is_whitespace);
+        prev_end = token.end;
+        let word = token.text.to_lowercase();
+        let phrase_word = is_phrase_word(&word, lang);
+        let breaks = gap_breaks || !phrase_word || current.len() == MAX_PHRASE_WORDS;
+        if breaks && !current.is_empty() {
+            phrases.push(std::mem::take(&mut current));
+        }
+        if phrase_word {
+            current.push(word);
+        }
+    }
+    if !current.is_empty() {
+        phrases.push(current);
+    }
+    phrases
+}
+
+/// Top `top_k` phrases by RAKE score, highest first.
+pub(crate) fn rake(text: &str, top_k: usize, lang: Option<&str>) -> Vec<(String, f64)> {
+    let phrases = candidates(text, lang);
+    let mut frequency: HashMap<&str, usize> = HashMap::new();
+    let mut degree: HashMap<&str, usize> = HashMap::new();
+    for phrase in &phrases {
+        for word in phrase {
+            *frequency.entry(word).or_default() += 1;
+            *degree.entry(word).or_default() += phrase.len();
+        }
+    }
+    let mut scored: Vec<(String, f64)> = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    for phrase in &phrases {
+        let joined = phrase.join(" ");
+        if !seen.insert(joined.clone()) {
+            continue;
+        }
+        let score = phrase
+            .iter()
+            .map(|w| degree[w.as_str()] as f64 / frequency[w.as_str()] as f64)
+            .sum();
+        scored.push((joined, score));
+    }
+    // Highest first; first occurrence wins ties
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.truncate(top_k);
+    scored
+}
+
+/// Extract scored keyphrases from text (RAKE).
+///
+/// Phrases are runs of up to four words between stopwords (see
+/// ``stopwords``) and punctuation. Each word scores its degree over
+/// its frequency and a phrase the sum of its words, so specific
+/// multi-word phrases ("cholera treatment centres") rank above single
+/// common words.
+///
+/// Parameters
+/// ----------
+/// text : str
+///     Article text.
+/// top_k : int
+///     Number of phrases to return. Default 10.
+/// lang : str | None
+///     Split on this language's stopwords only. Default: every
+///     language's.
+///
+/// Returns
+/// -------
+/// list[tuple[str, float]]
+///     ``(phrase, score)`` pairs, lowercased, highest score first.
+#[pyfunction]
+#[pyo3(signature = (text, top_k=10, lang=None))]
+pub fn extract_keyphrases(py: Python<'_>, text: &str, top_k: usize, lang: Option<&str>) -> PyResult<Vec<(String, f64)>> {
+    unlocked(py, "extract_keyphrases", || rake(text, top_k, lang))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidates_split_on_stopwords_and_punctuation() {
+        assert_eq!(
+            candidates("Cholera treatment centres opened in Beira, and flood waters receded.", None),
+            vec![
+                vec!["cholera", "treatment", "centres", "opened"],
+                vec!["beira"],
+                vec!["flood", "waters", "receded"],
+            ]
+        );
+        // Numbers alone don't make phrases
+        assert_eq!(candidates("at 12 sites", None), vec![vec!["sites"]]);
+    }
+
+    #[test]
+    fn test_rake_scores() {
+        let text = "Cholera outbreak spreads in Nampula. The cholera outbreak has killed 40 people. \
+                    Health authorities confirmed new cases.";
+        let phrases = rake(text, 3, None);
+        assert_eq!(phrases[0].0, "health authorities confirmed");
+        assert!(phrases.iter().any(|(p, _)| p == "cholera outbreak spreads"));
+        assert_eq!(phrases.len(), 3);
+        assert!(phrases.windows(2).all(|w| w[0].1 >= w[1].1));
+    }
+
+    #[test]
+    fn test_multilingual_and_empty() {
+        let phrases = rake("As cheias em Moçambique afetaram milhares de famílias.", 5, None);
+        assert_eq!(phrases[0].0, "moçambique afetaram milhares");
+        assert!(phrases.iter().any(|(p, _)| p == "famílias"));
+        assert!(rake("", 5, None).is_empty());
+        // Restricted to one language's stopwords
+        let text = "Inundaciones según autoridades locales";
+        assert_eq!(rake(text, 5, Some("es")).len(), 2);
+        assert_eq!(rake(text, 5, Some("en")).len(), 1);
+    }
+}
@@ -0,0 +1,144 @@
+//! Language identification — trigram profiles via `whatlang`.
+//!
+//! Routes documents to the right keyword packs and date parser. Covers
+//! ~70 languages; codes are ISO 639-1 where one exists (`"fr"`, `"pt"`),
+//! otherwise ISO 639-3.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use whatlang::{Detector, Lang};
+
+use crate::errors::unlocked;
+
+/// Only the start of long documents is profiled; a few KB is plenty.
+const MAX_SAMPLE_BYTES: usize = 4096;
+
+// whatlang's ISO 639-3 codes → ISO 639-1
+static ISO_639_1: &[(&str, &str)] = &[
+    ("afr", "af"), ("aka", "ak"), ("amh", "am"), ("ara", "ar"), ("aze", "az"), ("bel", "be"),
+    ("ben", "bn"), ("bul", "bg"), ("cat", "ca"), ("ces", "cs"), ("cmn", "zh"), ("dan", "da"),
+    ("deu", "de"), ("ell", "el"), ("eng", "en"), ("epo", "eo"), ("est", "et"), ("fin", "fi"),
+    ("fra", "fr"), ("guj", "gu"), ("heb", "he"), ("hin", "hi"), ("hrv", "hr"), ("hun", "hu"),
+    ("hye", "hy"), ("ind", "id"), ("ita", "it"), ("jav", "jv"), ("jpn", "ja"), ("kan", "kn"),
+    ("kat", "ka"), ("khm", "km"), ("kor", "ko"), ("lat", "la"), ("lav", "lv"), ("lit", "lt"),
+    ("mal", "ml"), ("mar", "mr"), ("mkd", "mk"), ("mya", "my"), ("nep", "ne"), ("nld", "nl"),
+    ("nob", "nb"), ("ori", "or"), ("pan", "pa"), ("pes", "fa"), ("pol", "pl"), ("por", "pt"),
+    ("ron", "ro"), ("rus", "ru"), ("sin", "si"), ("slk", "sk"), ("slv", "sl"), ("sna", "sn"),
+    ("spa", "es"), ("srp", "sr"), ("swe", "sv"), ("tam", "ta"), ("tel", "te"), ("tgl", "tl"),
+    ("tha", "th"), ("tuk", "tk"), ("tur", "tr"), ("ukr", "uk"), ("urd", "ur"), ("uzb", "uz"),
+    ("vie", "vi"), ("yid", "yi"), ("zul", "zu"),
+];
+
+fn short_code(lang: Lang) -> &'static str {
+    let code = lang.code();
+    ISO_639_1
+        .iter()
+        .find(|(three, _)| *three == code)
+        .map_or(code, |(_, two)| two)
+}
+
+/// Language for an ISO 639-1 or 639-3 code.
+fn lang_for_code(code: &str) -> Option<Lang> {
+    let code = code.trim().to_lowercase();
+    let code = code.split(['-', '_']).next().unwrap_or("");
+    let three = ISO_639_1
+        .iter()
+        .find(|(_, two)| *two == code)
+        .map_or(code, |(three, _)| three);
+    Lang::from_code(three)
+}
+
+fn sample(text: &str) -> &str {
+    if text.len() <= MAX_SAMPLE_BYTES {
+        return text;
+    }
+    let mut end = MAX_SAMPLE_BYTES;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+/// Language code and confidence, or `None` when the text carries too
+/// little signal.
+pub(crate) fn detect(text: &str, candidates: Option<&[Lang]>) -> Option<(&'static str, f64)> {
+    let detector = match candidates {
+        Some(list) => Detector::with_allowlist(list.to_vec()),
+        None => Detector::new(),
+    };
+    let info = detector.detect(sample(text))?;
+    Some((short_code(info.lang()), info.confidence()))
+}
+
+/// Identify the language of a text.
+///
+/// Parameters
+/// ----------
+/// text : str
+///     The text; only the first 4 KB are examined.
+/// candidates : list[str] | None
+///     Restrict the answer to these languages (ISO 639-1 or 639-3 codes,
+///     e.g. ``["en", "fr", "pt"]``). Default: all ~70 supported.
+///
+/// Returns
+/// -------
+/// tuple[str, float] | None
+///     ``(code, confidence)`` with an ISO 639-1 code where one exists
+///     (``"fr"``; ISO 639-3 otherwise) and confidence in ``[0, 1]``.
+///     None if the text is empty or has no letters.
+///
+/// Raises
+/// ------
+/// ValueError
+///     If a candidate code is not a supported language.
+#[pyfunction]
+#[pyo3(signature = (text, candidates=None))]
+pub fn detect_language(
+    py: Python<'_>,
+    text: &str,
+    candidates: Option<Vec<String>>,
+) -> PyResult<Option<(&'static str, f64)>> {
+    let candidates = match candidates {
+        None => None,
+        Some(codes) => Some(
+            codes
+                .iter()
+                .map(|c| lang_for_code(c).ok_or_else(|| PyValueError::new_err(format!("unsupported language {c:?}"))))
+                .collect::<PyResult<Vec<Lang>>>()?,
+        ),
+    };
+    unlocked(py, "detect_language", || detect(text, candidates.as_deref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lang(text: &str) -> Option<&'static str> {
+        detect(text, None).map(|(code, _)| code)
+    }
+
+    #[test]
+    fn test_common_languages() {
+        assert_eq!(lang("Heavy rains caused flooding across the southern provinces, displacing thousands of families."), Some("en"));
+        assert_eq!(lang("Les fortes pluies ont provoqué des inondations dans les provinces du sud, déplaçant des milliers de familles."), Some("fr"));
+        assert_eq!(lang("As chuvas intensas provocaram cheias nas províncias do sul, deslocando milhares de famílias."), Some("pt"));
+        assert_eq!(lang("تسببت الأمطار الغزيرة في فيضانات في المقاطعات الجنوبية"), Some("ar"));
+    }
+
+    #[test]
+    fn test_candidates_and_codes() {
+        assert_eq!(lang_for_code("pt-BR"), Some(Lang::Por));
+        assert_eq!(lang_for_code("fra"), Some(Lang::Fra));
+        assert_eq!(lang_for_code("xx"), None);
+        let (code, _) = detect("Cheias em Moçambique", Some(&[Lang::Por, Lang::Spa])).unwrap();
+        assert_eq!(code, "pt");
+    }
+
+    #[test]
+    fn test_no_signal() {
+        assert_eq!(detect("", None), None);
+        assert_eq!(detect("12345 !!!", None), None);
+        assert_eq!(sample(&"é".repeat(5000)).len(), MAX_SAMPLE_BYTES);
+    }
+}
@@ -1,29 +1,269 @@
 //! Performance-critical Rust extensions for Moltis humanitarian crawler.
 //!
-//! Accelerates four hot paths:
-//! 1. Figure extraction (4-pattern NLP regex)
+//! The crawler's hot paths and the tools built around them:
+//! 1. Figure extraction (English, French, Portuguese and Spanish patterns)
 //! 2. Text classification (keyword matching for impacts/needs/severity)
 //! 3. Fuzzy deduplication (string similarity scoring)
 //! 4. URL canonicalization (tracking param stripping)
+//! 5. Registrable domain lookup (public suffix list)
+//! 6. URL keys (SURT form + stable hash)
+//! 7. Seen-URL Bloom filter
+//! 8. URL frontier (priority queue with per-host interleaving)
+//! 9. Sitemap parsing (urlset / sitemapindex, gzip)
+//! 10. URL path hints (publication date, language)
+//! 11. Feed discovery from HTML
+//! 12. Crawl priority scoring for URLs
+//! 13. Canonical selection among hreflang alternates
+//! 14. URL + content-hash duplicate detection (updates vs mirrors)
+//! 15. Non-article URL blocklist
+//! 16. Source reputation tiers
+//! 17. Tracking metadata extraction (utm_* before stripping)
+//! 18. Slug-based URL similarity
+//! 19. Per-host HTTPS upgrade policy
+//! 20. Punycode homograph flagging
+//! 21. Politeness keys for rate limiting
+//! 22. Junk-query heuristics (crawl-worthiness)
+//! 23. HTML-to-clean-text conversion
+//! 24. Page metadata extraction (OpenGraph, JSON-LD, meta tags)
+//! 25. Multilingual date normalization (ISO 8601)
+//! 26. Unicode-aware word tokenizer
+//! 27. Language identification
+//! 28. Tropical storm name recognition
+//! 29. Single-call article pipeline
+//! 30. Configurable batch pipeline
+//! 31. Extractive summarization
+//! 32. Keyphrase extraction (RAKE)
+//! 33. Character encoding detection and transcoding
+//! 34. CAP alert parsing
+//! 35. GDACS alert parsing
+//! 36. WHO Disease Outbreak News parsing
+//! 37. Content hashing (xxh3 / BLAKE3)
+//! 38. Arrow / Parquet record export (feature `arrow`)
+//! 39. JSONL batch processing
+//! 40. `moltis-core` command-line tool (feature `cli`)
+//! 41. Shared configuration with hot reload
+//! 42. Metrics counters and histograms
+//! 43. Native log events forwarded to Python `logging`
+//! 44. Text quality and boilerplate scoring
+//! 45. Press release / advertorial / fundraising filter
+//! 46. Crisis hashtag extraction and classification
+//! 47. Point-in-polygon admin boundary lookup
+//! 48. GeoNames / HDX gazetteer index
+//! 49. Place-name entity linking
+//! 50. Cross-article event fusion
+//! 51. Event figure timelines and severity trends
+//! 52. HXL-tagged CSV export
+//! 53. EM-DAT hazard classification
+//! 54. IPC / Cadre Harmonisé phase mentions
+//! 55. Memory-mapped lookup tables
+//! 56. Per-language stopword lists
+//! 57. Pluggable storage for stateful components (memory, SQLite, mmap)
+//! 58. WebAssembly exports of the text functions (feature `wasm`)
+//! 59. numpy / pandas / Polars columns in batch APIs
+//! 60. Chunked iterators with bounded memory
+//! 61. Opt-in profiling of calls and pipeline stages
+//! 62. Runtime capability and configuration introspection
+//! 63. Embedding pooling and centroid scoring
+//! 64. Crawl-worthiness scoring of discovered links
+//! 65. Structural (DOM) fingerprints for template duplicates
+//! 66. Per-host rate limiting with crawl delays and jitter
+//! 67. Native concurrent fetching (optional `fetch` feature)
+//! 68. Conditional re-crawl cache (validators and adaptive intervals)
+//! 69. WARC reading and writing
+//! 70. Telegram and X post parsing
+//! 71. 3W (who / what / where) operational-presence records
+//! 72. Admin hierarchy roll-up of figures (P-codes)
+//! 73. Anomaly flags on extracted figures
+//! 74. Per-location figure attribution
+//! 75. Runtime figure keywords and patterns
+//!
+//! The Python bindings sit behind the default `python` feature; building
+//! with `--no-default-features --features wasm` leaves only the pure text
+//! core and its wasm-bindgen exports.
+
+// PyO3 0.22's `#[pyfunction]` expansion wraps `PyResult` returns in a
+// no-op `.into()`, which newer clippy flags on every exported function.
+#![allow(clippy::useless_conversion)]
+// Without the Python layer, helpers only its wrappers call go unused.
+#![cfg_attr(not(feature = "python"), allow(dead_code))]
 
+#[cfg(feature = "python")]
+mod errors;
+#[cfg(feature = "python")]
+mod columns;
 mod figure_extraction;
 mod text_classify;
 mod fuzzy_dedupe;
 mod url_canonical;
+mod url_rules;
+mod shorteners;
+mod public_suffix;
+#[cfg(feature = "python")]
+mod url_key;
+#[cfg(feature = "python")]
+mod bloom;
+#[cfg(feature = "python")]
+mod frontier;
+#[cfg(feature = "python")]
+mod rate_limit;
+#[cfg(feature = "python")]
+mod sitemap;
+#[cfg(feature = "python")]
+mod url_hints;
+#[cfg(feature = "python")]
+mod feeds;
+#[cfg(feature = "python")]
+mod url_score;
+#[cfg(feature = "python")]
+mod alternates;
+#[cfg(feature = "python")]
+mod content_index;
+#[cfg(feature = "python")]
+mod recrawl;
+#[cfg(feature = "python")]
+mod url_blocklist;
+#[cfg(feature = "python")]
+mod reputation;
+#[cfg(feature = "python")]
+mod tracking;
+#[cfg(feature = "python")]
+mod url_slug;
+mod https_upgrade;
+#[cfg(feature = "python")]
+mod homograph;
+#[cfg(feature = "python")]
+mod politeness;
+#[cfg(feature = "python")]
+mod junk_query;
+#[cfg(feature = "python")]
+mod html_text;
+#[cfg(feature = "python")]
+mod html_meta;
+#[cfg(feature = "python")]
+mod date_parse;
+mod tokenize;
+#[cfg(feature = "python")]
+mod lang_detect;
+#[cfg(feature = "python")]
+mod storm_names;
+#[cfg(feature = "python")]
+mod article;
+#[cfg(feature = "python")]
+mod pipeline;
+#[cfg(feature = "python")]
+mod chunked;
+#[cfg(feature = "python")]
+mod summarize;
+#[cfg(feature = "python")]
+mod keyphrases;
+#[cfg(feature = "python")]
+mod charset;
+#[cfg(feature = "python")]
+mod xml_tree;
+#[cfg(feature = "python")]
+mod cap;
+#[cfg(feature = "python")]
+mod gdacs;
+#[cfg(feature = "python")]
+mod who_don;
+#[cfg(feature = "python")]
+mod social;
+#[cfg(feature = "python")]
+mod content_hash;
+#[cfg(feature = "python")]
+mod jsonl_batch;
+#[cfg(feature = "python")]
+mod warc;
+#[cfg(feature = "python")]
+mod moltis_config;
+mod metrics;
+#[cfg(feature = "python")]
+mod profiling;
+#[cfg(feature = "python")]
+mod introspect;
+#[cfg(feature = "python")]
+mod embeddings;
+#[cfg(feature = "python")]
+mod relevance;
+#[cfg(feature = "python")]
+mod dom_hash;
+#[cfg(feature = "python")]
+mod log_bridge;
+#[cfg(feature = "python")]
+mod text_quality;
+#[cfg(feature = "python")]
+mod promo_filter;
+#[cfg(feature = "python")]
+mod hashtags;
+#[cfg(feature = "python")]
+mod admin_boundaries;
+#[cfg(feature = "python")]
+mod gazetteer;
+#[cfg(feature = "python")]
+mod place_linking;
+#[cfg(feature = "python")]
+mod three_w;
+#[cfg(feature = "python")]
+mod admin_rollup;
+#[cfg(feature = "python")]
+mod event_fusion;
+#[cfg(feature = "python")]
+mod timeline;
+#[cfg(feature = "python")]
+mod figure_anomalies;
+#[cfg(feature = "python")]
+mod figure_locations;
+#[cfg(feature = "python")]
+mod hxl_export;
+#[cfg(feature = "python")]
+mod emdat;
+#[cfg(feature = "python")]
+mod ipc_phase;
+mod mapped;
+mod stopwords;
+#[cfg(feature = "python")]
+mod storage;
+#[cfg(feature = "arrow")]
+mod arrow_export;
+#[cfg(feature = "fetch")]
+mod fetch;
+#[cfg(feature = "cli")]
+pub mod cli;
 
+#[cfg(feature = "wasm")]
+mod wasm;
+
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
 
 /// Moltis Rust Core — native accelerator for humanitarian text processing.
+#[cfg(feature = "python")]
 #[pymodule]
 fn moltis_rust_core(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Exceptions
+    m.add("MoltisError", m.py().get_type_bound::<errors::MoltisError>())?;
+    m.add("UrlParseError", m.py().get_type_bound::<errors::UrlParseError>())?;
+    m.add("ConfigError", m.py().get_type_bound::<errors::ConfigError>())?;
+    m.add("GazetteerError", m.py().get_type_bound::<errors::GazetteerError>())?;
+    m.add("ExtractionError", m.py().get_type_bound::<errors::ExtractionError>())?;
+    m.add("RustPanicError", m.py().get_type_bound::<errors::RustPanicError>())?;
+
     // Figure extraction
     m.add_function(wrap_pyfunction!(figure_extraction::extract_figures, m)?)?;
+    m.add_function(wrap_pyfunction!(figure_extraction::extract_figures_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(figure_extraction::extract_figures_detailed, m)?)?;
+    m.add_class::<figure_extraction::Figure>()?;
+    m.add_function(wrap_pyfunction!(figure_extraction::configure_figure_keywords, m)?)?;
+    m.add_function(wrap_pyfunction!(figure_locations::extract_figures_by_location, m)?)?;
 
     // Text classification
     m.add_function(wrap_pyfunction!(text_classify::classify_impact_type, m)?)?;
+    m.add_function(wrap_pyfunction!(text_classify::classify_impact_type_batch, m)?)?;
     m.add_function(wrap_pyfunction!(text_classify::classify_all_impact_types, m)?)?;
     m.add_function(wrap_pyfunction!(text_classify::classify_need_types, m)?)?;
     m.add_function(wrap_pyfunction!(text_classify::severity_from_text, m)?)?;
+    m.add_function(wrap_pyfunction!(text_classify::severity_from_text_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(ipc_phase::extract_ipc_phases, m)?)?;
     m.add_function(wrap_pyfunction!(text_classify::is_risk_text, m)?)?;
     m.add_function(wrap_pyfunction!(text_classify::detect_response_actor, m)?)?;
     m.add_function(wrap_pyfunction!(text_classify::detect_admin_area, m)?)?;
@@ -31,11 +271,168 @@ fn moltis_rust_core(m: &Bound<'_, PyModule>) -> PyResult<()> {
     // Fuzzy deduplication
     m.add_function(wrap_pyfunction!(fuzzy_dedupe::similarity_ratio, m)?)?;
     m.add_function(wrap_pyfunction!(fuzzy_dedupe::cluster_titles, m)?)?;
-    m.add_function(wrap_pyfunction!(fuzzy_dedupe::normalize_text, m)?)?;
+    m.add_function(wrap_pyfunction!(url_slug::slug_similarity, m)?)?;
+    m.add_function(wrap_pyfunction!(fuzzy_dedupe::py_normalize_text, m)?)?;
+
+    // URL canonicalization
+    m.add_function(wrap_pyfunction!(url_canonical::py_canonicalize_url, m)?)?;
+    m.add_function(wrap_pyfunction!(url_canonical::py_strip_tracking_params, m)?)?;
+    m.add_function(wrap_pyfunction!(url_canonical::normalize_host, m)?)?;
+    m.add_function(wrap_pyfunction!(url_canonical::display_url, m)?)?;
+    m.add_function(wrap_pyfunction!(url_canonical::configure_mobile_hosts, m)?)?;
+    m.add_function(wrap_pyfunction!(url_canonical::configure_redirect_hosts, m)?)?;
+    m.add_function(wrap_pyfunction!(https_upgrade::configure_https_hosts, m)?)?;
+    m.add_function(wrap_pyfunction!(https_upgrade::record_https_support, m)?)?;
+    m.add_function(wrap_pyfunction!(https_upgrade::https_upgrade_hosts, m)?)?;
+    m.add_function(wrap_pyfunction!(url_rules::load_url_rules, m)?)?;
+    m.add_function(wrap_pyfunction!(url_rules::clear_url_rules, m)?)?;
+    m.add_function(wrap_pyfunction!(shorteners::is_shortened_url, m)?)?;
+    m.add_function(wrap_pyfunction!(shorteners::cache_expansion, m)?)?;
+    m.add_function(wrap_pyfunction!(shorteners::clear_expansion_cache, m)?)?;
+    m.add_function(wrap_pyfunction!(shorteners::expand_url, m)?)?;
+    m.add_function(wrap_pyfunction!(tracking::extract_tracking_metadata, m)?)?;
+
+    // Registrable domain
+    m.add_function(wrap_pyfunction!(public_suffix::registrable_domain, m)?)?;
+    m.add_function(wrap_pyfunction!(public_suffix::compile_public_suffix_list, m)?)?;
+    m.add_function(wrap_pyfunction!(public_suffix::load_public_suffix_table, m)?)?;
+
+    // URL keys
+    m.add_function(wrap_pyfunction!(url_key::url_key, m)?)?;
+    m.add_class::<url_key::CanonicalUrlSet>()?;
+    m.add_function(wrap_pyfunction!(storage::configure_storage, m)?)?;
+
+    // Seen-URL set
+    m.add_class::<bloom::BloomFilter>()?;
+
+    // URL frontier
+    m.add_class::<frontier::Frontier>()?;
+    m.add_class::<rate_limit::RateLimiter>()?;
+    m.add_function(wrap_pyfunction!(politeness::politeness_key, m)?)?;
+    m.add_function(wrap_pyfunction!(politeness::configure_politeness, m)?)?;
+
+    // Sitemaps
+    m.add_function(wrap_pyfunction!(sitemap::parse_sitemap, m)?)?;
+
+    // URL hints
+    m.add_function(wrap_pyfunction!(url_hints::url_hints, m)?)?;
+
+    // Feed discovery
+    m.add_function(wrap_pyfunction!(feeds::discover_feeds, m)?)?;
+
+    // HTML
+    m.add_function(wrap_pyfunction!(html_text::html_to_text, m)?)?;
+    m.add_function(wrap_pyfunction!(html_meta::extract_metadata, m)?)?;
+
+    // Dates
+    m.add_function(wrap_pyfunction!(date_parse::parse_date, m)?)?;
+
+    // Tokenizer
+    m.add_function(wrap_pyfunction!(tokenize::tokenize_text, m)?)?;
+
+    // Language identification
+    m.add_function(wrap_pyfunction!(lang_detect::detect_language, m)?)?;
+
+    // Storm names
+    m.add_function(wrap_pyfunction!(storm_names::detect_storm_names, m)?)?;
+    m.add_function(wrap_pyfunction!(storm_names::configure_storm_names, m)?)?;
+
+    // Hashtags
+    m.add_function(wrap_pyfunction!(hashtags::extract_hashtags, m)?)?;
+    m.add_function(wrap_pyfunction!(hashtags::split_hashtag, m)?)?;
+
+    // Admin boundaries
+    m.add_class::<admin_boundaries::AdminBoundaries>()?;
+    m.add_class::<gazetteer::Gazetteer>()?;
+    m.add_function(wrap_pyfunction!(place_linking::link_places, m)?)?;
+    m.add_function(wrap_pyfunction!(three_w::extract_3w, m)?)?;
+    m.add_function(wrap_pyfunction!(admin_rollup::roll_up_admin, m)?)?;
+
+    // Event fusion
+    m.add_class::<event_fusion::EventFuser>()?;
+    m.add_function(wrap_pyfunction!(timeline::build_timeline, m)?)?;
+    m.add_function(wrap_pyfunction!(timeline::severity_trend, m)?)?;
+    m.add_function(wrap_pyfunction!(figure_anomalies::flag_anomalies, m)?)?;
+    m.add_function(wrap_pyfunction!(emdat::emdat_code, m)?)?;
+
+    // Article pipeline
+    m.add_function(wrap_pyfunction!(article::process_article, m)?)?;
+    m.add_class::<pipeline::Pipeline>()?;
+    m.add_function(wrap_pyfunction!(chunked::iter_process, m)?)?;
+    m.add_class::<chunked::ChunkIterator>()?;
+    m.add_class::<moltis_config::MoltisConfig>()?;
+    m.add_class::<mapped::MappedTable>()?;
+    m.add_function(wrap_pyfunction!(jsonl_batch::process_jsonl, m)?)?;
+    m.add_class::<warc::WarcReader>()?;
+    m.add_class::<warc::WarcWriter>()?;
+    #[cfg(feature = "arrow")]
+    m.add_class::<arrow_export::ArticleExporter>()?;
+    #[cfg(feature = "fetch")]
+    m.add_function(wrap_pyfunction!(fetch::fetch_batch, m)?)?;
+    m.add_class::<hxl_export::HxlCsvWriter>()?;
+
+    // Summarization
+    m.add_function(wrap_pyfunction!(summarize::summarize, m)?)?;
+
+    // Keyphrases
+    m.add_function(wrap_pyfunction!(keyphrases::extract_keyphrases, m)?)?;
+    m.add_function(wrap_pyfunction!(stopwords::stopwords, m)?)?;
+    m.add_function(wrap_pyfunction!(stopwords::py_is_stopword, m)?)?;
+    m.add_function(wrap_pyfunction!(stopwords::configure_stopwords, m)?)?;
+
+    // Text quality
+    m.add_function(wrap_pyfunction!(text_quality::text_quality, m)?)?;
+    m.add_function(wrap_pyfunction!(promo_filter::classify_promotional, m)?)?;
+
+    // Encoding
+    m.add_function(wrap_pyfunction!(charset::decode_bytes, m)?)?;
+
+    // Alert feeds
+    m.add_function(wrap_pyfunction!(cap::parse_cap, m)?)?;
+    m.add_function(wrap_pyfunction!(gdacs::parse_gdacs, m)?)?;
+    m.add_function(wrap_pyfunction!(who_don::parse_who_don, m)?)?;
+    m.add_function(wrap_pyfunction!(social::parse_social_posts, m)?)?;
+
+    // Crawl priority
+    m.add_function(wrap_pyfunction!(url_score::score_url, m)?)?;
+    m.add_function(wrap_pyfunction!(url_score::score_url_batch, m)?)?;
+
+    // Alternate URLs
+    m.add_function(wrap_pyfunction!(alternates::select_canonical_url, m)?)?;
+
+    // Content duplicates
+    m.add_class::<content_index::ContentIndex>()?;
+    m.add_class::<recrawl::RecrawlCache>()?;
+    m.add_function(wrap_pyfunction!(content_hash::content_hash, m)?)?;
+    m.add_function(wrap_pyfunction!(content_hash::content_hash_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(chunked::iter_content_hash, m)?)?;
+
+    // URL blocklist
+    m.add_function(wrap_pyfunction!(url_blocklist::url_skip_reason, m)?)?;
+    m.add_function(wrap_pyfunction!(url_blocklist::configure_url_blocklist, m)?)?;
+    m.add_function(wrap_pyfunction!(junk_query::crawl_worthiness, m)?)?;
+
+    // Source reputation
+    m.add_class::<reputation::SourceRegistry>()?;
+    m.add_function(wrap_pyfunction!(homograph::check_homograph, m)?)?;
+    m.add_function(wrap_pyfunction!(homograph::configure_known_domains, m)?)?;
+
+    // Metrics
+    m.add_function(wrap_pyfunction!(metrics::get_metrics, m)?)?;
+    m.add_function(wrap_pyfunction!(metrics::reset_metrics, m)?)?;
+    m.add_function(wrap_pyfunction!(profiling::enable_profiling, m)?)?;
+    m.add_function(wrap_pyfunction!(profiling::profiling_report, m)?)?;
+    m.add_function(wrap_pyfunction!(introspect::runtime_info, m)?)?;
+    m.add_function(wrap_pyfunction!(embeddings::pool_embeddings, m)?)?;
+    m.add_function(wrap_pyfunction!(embeddings::cosine_scores, m)?)?;
+    m.add_function(wrap_pyfunction!(embeddings::rank_by_centroids, m)?)?;
+    m.add_function(wrap_pyfunction!(relevance::relevance_score, m)?)?;
+    m.add_function(wrap_pyfunction!(dom_hash::dom_shingle_hash, m)?)?;
+    m.add_function(wrap_pyfunction!(dom_hash::dom_similarity, m)?)?;
 
-    // URL canonicalization  
-    m.add_function(wrap_pyfunction!(url_canonical::canonicalize_url, m)?)?;
-    m.add_function(wrap_pyfunction!(url_canonical::strip_tracking_params, m)?)?;
+    // Logging
+    log_bridge::install();
+    m.add_function(wrap_pyfunction!(log_bridge::configure_native_logging, m)?)?;
 
     Ok(())
 }
@@ -0,0 +1,202 @@
+//! Forward native `tracing` events to Python's `logging`.
+//!
+//! The extension reports problems it works around — invalid patterns,
+//! failed reloads, skipped input — as `tracing` events. This subscriber,
+//! installed when the module is imported, hands WARN and ERROR events to
+//! the `moltis_rust_core.<module>` logger so they reach the crawler's
+//! existing log handlers. Events are forwarded synchronously under the
+//! GIL; parallel work runs inside `allow_threads`, so a worker thread can
+//! always take it.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU8, Ordering};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Metadata, Subscriber};
+
+use crate::errors::guarded;
+
+/// Most verbose level forwarded: 0 off, 1 error, 2 warn, 3 info, 4 debug.
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(2);
+
+fn level_rank(level: &Level) -> u8 {
+    match *level {
+        Level::ERROR => 1,
+        Level::WARN => 2,
+        Level::INFO => 3,
+        Level::DEBUG => 4,
+        Level::TRACE => 5,
+    }
+}
+
+/// Python `logging` level number for a tracing level.
+fn python_level(level: &Level) -> u8 {
+    match *level {
+        Level::ERROR => 40,
+        Level::WARN => 30,
+        Level::INFO => 20,
+        _ => 10,
+    }
+}
+
+fn parse_level(name: &str) -> Result<u8, String> {
+    match name.to_ascii_uppercase().as_str() {
+        "OFF" | "NONE" => Ok(0),
+        "ERROR" | "CRITICAL" => Ok(1),
+        "WARN" | "WARNING" => Ok(2),
+        "INFO" => Ok(3),
+        "DEBUG" => Ok(4),
+        _ => Err(format!("unknown log level {name:?}: expected DEBUG, INFO, WARNING, ERROR or OFF")),
+    }
+}
+
+/// `moltis_rust_core::url_rules` → `moltis_rust_core.url_rules`.
+fn logger_name(target: &str) -> String {
+    target.replace("::", ".")
+}
+
+/// The event's message followed by its other fields as `key=value`.
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={value:?}", field.name());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            let _ = write!(self.fields, " {}={value:?}", field.name());
+        }
+    }
+}
+
+impl MessageVisitor {
+    fn finish(self) -> String {
+        self.message + &self.fields
+    }
+}
+
+/// Subscriber that forwards events to Python; spans are ignored.
+struct PythonLogBridge;
+
+impl Subscriber for PythonLogBridge {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        level_rank(metadata.level()) <= MAX_LEVEL.load(Ordering::Relaxed)
+    }
+
+    fn new_span(&self, _: &Attributes<'_>) -> Id {
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let metadata = event.metadata();
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let message = visitor.finish();
+        let logger = logger_name(metadata.target());
+        Python::with_gil(|py| {
+            let forwarded = py
+                .import_bound("logging")
+                .and_then(|logging| logging.call_method1("getLogger", (logger,)))
+                .and_then(|logger| logger.call_method1("log", (python_level(metadata.level()), message)));
+            if let Err(e) = forwarded {
+                e.write_unraisable_bound(py, None);
+            }
+        });
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+/// Install the bridge as the process-wide subscriber, unless the host
+/// application already set one.
+pub(crate) fn install() {
+    let _ = tracing::subscriber::set_global_default(PythonLogBridge);
+}
+
+/// Set which native log events are forwarded to Python ``logging``.
+///
+/// Events go to the ``moltis_rust_core.<module>`` loggers, so Python-side
+/// levels and handlers still apply on top of this.
+///
+/// Parameters
+/// ----------
+/// level : str
+///     ``"DEBUG"``, ``"INFO"``, ``"WARNING"`` (default), ``"ERROR"`` or
+///     ``"OFF"``.
+///
+/// Raises
+/// ------
+/// ValueError
+///     If the level is unknown.
+#[pyfunction]
+#[pyo3(signature = (level="WARNING"))]
+pub fn configure_native_logging(level: &str) -> PyResult<()> {
+    guarded("configure_native_logging", || {
+        let level = parse_level(level).map_err(PyValueError::new_err)?;
+        MAX_LEVEL.store(level, Ordering::Relaxed);
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels() {
+        assert_eq!(parse_level("warning").unwrap(), 2);
+        assert_eq!(parse_level("OFF").unwrap(), 0);
+        assert!(parse_level("verbose").is_err());
+        assert_eq!(python_level(&Level::ERROR), 40);
+        assert_eq!(logger_name("moltis_rust_core::url_rules"), "moltis_rust_core.url_rules");
+    }
+
+    #[test]
+    fn test_forwards_to_python_logging() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            py.run_bound(
+                "import logging\n\
+                 records = []\n\
+                 class Capture(logging.Handler):\n    \
+                     def emit(self, record):\n        \
+                         records.append((record.name, record.levelno, record.getMessage()))\n\
+                 logging.getLogger('moltis_rust_core.log_bridge').addHandler(Capture())\n",
+                None,
+                None,
+            )
+            .unwrap();
+        });
+        tracing::subscriber::with_default(PythonLogBridge, || {
+            tracing::warn!(pattern = "[", "invalid pattern");
+            tracing::debug!("not forwarded at the default level");
+        });
+        Python::with_gil(|py| {
+            let main = py.import_bound("__main__").unwrap();
+            let records: Vec<(String, u8, String)> = main.getattr("records").unwrap().extract().unwrap();
+            assert_eq!(
+                records,
+                vec![("moltis_rust_core.log_bridge.tests".to_string(), 30, "invalid pattern pattern=\"[\"".to_string())]
+            );
+        });
+    }
+}
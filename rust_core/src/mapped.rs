@@ -0,0 +1,251 @@
+//! Memory-mapped lookup tables.
+//!
+//! A national gazetteer or a world-wide alternate-names index runs to
+//! hundreds of MB once parsed, and every worker process used to build its
+//! own copy. A mapped table is compiled once into a read-only file — an
+//! `fst` map of sorted keys followed by the values — and opened with
+//! `mmap`, so workers share the operating system's page cache and only
+//! the pages a lookup touches are ever read. Opening is O(1).
+//!
+//! File layout: the magic `MOLTMAP1`, the key index length (u64 LE), the
+//! `fst` key index mapping each key to a value offset, then the values,
+//! each a u32 LE length followed by its bytes.
+
+use fst::{IntoStreamer, Map, MapBuilder, Streamer};
+use memmap2::Mmap;
+#[cfg(feature = "python")]
+use pyo3::exceptions::{PyKeyError, PyOSError};
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Write};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+
+#[cfg(feature = "python")]
+use crate::errors::{guarded, unlocked};
+
+const MAGIC: &[u8; 8] = b"MOLTMAP1";
+const HEADER_LEN: usize = 16;
+
+/// A range of the mapping, so the `fst` map can own a view into it.
+#[derive(Clone)]
+struct Region {
+    mmap: Arc<Mmap>,
+    range: Range<usize>,
+}
+
+impl AsRef<[u8]> for Region {
+    fn as_ref(&self) -> &[u8] {
+        &self.mmap[self.range.clone()]
+    }
+}
+
+/// A read-only key → bytes table backed by a mapped file.
+pub(crate) struct Table {
+    keys: Map<Region>,
+    values: Region,
+}
+
+impl Table {
+    pub(crate) fn open(path: &str) -> io::Result<Self> {
+        let context = |e: io::Error| io::Error::new(e.kind(), format!("cannot open {path}: {e}"));
+        let file = File::open(path).map_err(context)?;
+        // SAFETY: tables are written to a temporary file and renamed into
+        // place, so a mapped file is never modified underneath us.
+        let mmap = unsafe { Mmap::map(&file) }.map_err(context)?;
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, format!("{path}: {message}"));
+        if mmap.len() < HEADER_LEN || &mmap[..8] != MAGIC {
+            return Err(invalid("not a mapped table".into()));
+        }
+        let keys_len = u64::from_le_bytes(mmap[8..16].try_into().unwrap());
+        let keys_end = usize::try_from(keys_len)
+            .ok()
+            .and_then(|n| n.checked_add(HEADER_LEN))
+            .filter(|end| *end <= mmap.len())
+            .ok_or_else(|| invalid("truncated key index".into()))?;
+        let mmap = Arc::new(mmap);
+        let keys = Map::new(Region { mmap: mmap.clone(), range: HEADER_LEN..keys_end })
+            .map_err(|e| invalid(format!("invalid key index: {e}")))?;
+        let values = Region { range: keys_end..mmap.len(), mmap };
+        Ok(Self { keys, values })
+    }
+
+    /// The value stored under `key`.
+    pub(crate) fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        let offset = usize::try_from(self.keys.get(key)?).ok()?;
+        let values = self.values.as_ref();
+        let len = values.get(offset..offset + 4)?;
+        let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+        values.get(offset + 4..offset + 4 + len)
+    }
+
+    pub(crate) fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key.as_bytes()).and_then(|v| std::str::from_utf8(v).ok())
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Keys starting with `prefix`, in sorted order.
+    pub(crate) fn keys_with_prefix(&self, prefix: &[u8]) -> Vec<Vec<u8>> {
+        let mut stream = self.keys.range().ge(prefix).into_stream();
+        let mut keys = Vec::new();
+        while let Some((key, _)) = stream.next() {
+            if !key.starts_with(prefix) {
+                break;
+            }
+            keys.push(key.to_vec());
+        }
+        keys
+    }
+}
+
+/// Write `entries` as a mapped table at `path`; returns the entry count.
+///
+/// The table is written beside `path` and renamed over it, so processes
+/// that have the old file mapped keep reading a consistent copy.
+pub(crate) fn write_table(path: &str, entries: &BTreeMap<Vec<u8>, Vec<u8>>) -> Result<usize, String> {
+    let mut keys = MapBuilder::memory();
+    let mut values = Vec::new();
+    for (key, value) in entries {
+        let len = u32::try_from(value.len()).map_err(|_| "value exceeds 4 GiB".to_string())?;
+        keys.insert(key, values.len() as u64).map_err(|e| e.to_string())?;
+        values.extend_from_slice(&len.to_le_bytes());
+        values.extend_from_slice(value);
+    }
+    let keys = keys.into_inner().map_err(|e| e.to_string())?;
+
+    let tmp = format!("{path}.tmp{}", std::process::id());
+    let write = || -> std::io::Result<()> {
+        let mut file = File::create(&tmp)?;
+        file.write_all(MAGIC)?;
+        file.write_all(&(keys.len() as u64).to_le_bytes())?;
+        file.write_all(&keys)?;
+        file.write_all(&values)?;
+        file.sync_all()?;
+        std::fs::rename(&tmp, Path::new(path))
+    };
+    write().map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        format!("cannot write {path}: {e}")
+    })?;
+    Ok(entries.len())
+}
+
+/// A read-only string lookup table shared between processes.
+///
+/// Opens a file written by ``MappedTable.build`` with ``mmap``: opening
+/// is instant, memory is shared through the page cache by every worker
+/// that opens the same file, and only the pages lookups touch are read.
+/// Suits large keyword packs, alias lists and similar lookup data.
+///
+/// Parameters
+/// ----------
+/// path : str
+///     Table file.
+///
+/// Raises
+/// ------
+/// OSError
+///     If the file cannot be opened or isn't a mapped table.
+#[cfg(feature = "python")]
+#[pyclass(module = "moltis_rust_core", frozen)]
+pub struct MappedTable {
+    table: Table,
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl MappedTable {
+    #[new]
+    fn py_new(path: &str) -> PyResult<Self> {
+        guarded("MappedTable.__new__", || Ok(Self { table: Table::open(path)? }))
+    }
+
+    /// Write ``entries`` to a table file; returns the entry count.
+    ///
+    /// The file is written beside ``path`` and renamed into place, so
+    /// workers with the previous version open keep a consistent view.
+    ///
+    /// Raises
+    /// ------
+    /// OSError
+    ///     If the file cannot be written.
+    #[staticmethod]
+    fn build(py: Python<'_>, path: &str, entries: BTreeMap<String, String>) -> PyResult<usize> {
+        let entries = entries.into_iter().map(|(k, v)| (k.into_bytes(), v.into_bytes())).collect();
+        unlocked(py, "MappedTable.build", || write_table(path, &entries))?.map_err(PyOSError::new_err)
+    }
+
+    /// The value for ``key``, or ``default``.
+    #[pyo3(signature = (key, default=None))]
+    fn get(&self, key: &str, default: Option<String>) -> PyResult<Option<String>> {
+        guarded("MappedTable.get", || Ok(self.table.get_str(key).map(str::to_string).or(default)))
+    }
+
+    /// Keys starting with ``prefix``, sorted.
+    #[pyo3(signature = (prefix=""))]
+    fn keys(&self, prefix: &str) -> PyResult<Vec<String>> {
+        guarded("MappedTable.keys", || {
+            Ok(self.table
+                .keys_with_prefix(prefix.as_bytes())
+                .into_iter()
+                .map(|k| String::from_utf8_lossy(&k).into_owned())
+                .collect())
+        })
+    }
+
+    fn __getitem__(&self, key: &str) -> PyResult<String> {
+        guarded("MappedTable.__getitem__", || self.get(key, None)?.ok_or_else(|| PyKeyError::new_err(key.to_string())))
+    }
+
+    fn __contains__(&self, key: &str) -> PyResult<bool> {
+        guarded("MappedTable.__contains__", || Ok(self.table.get(key.as_bytes()).is_some()))
+    }
+
+    fn __len__(&self) -> PyResult<usize> {
+        guarded("MappedTable.__len__", || Ok(self.table.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("moltis_mapped_{name}_{}.bin", std::process::id()));
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_write_and_open() {
+        let path = temp_path("table");
+        let entries: BTreeMap<Vec<u8>, Vec<u8>> = [("beira", "MZ"), ("bei'an", "CN"), ("dondo", "MZ"), ("empty", "")]
+            .iter()
+            .map(|(k, v)| (k.as_bytes().to_vec(), v.as_bytes().to_vec()))
+            .collect();
+        assert_eq!(write_table(&path, &entries).unwrap(), 4);
+        let table = Table::open(&path).unwrap();
+        assert_eq!(table.len(), 4);
+        assert_eq!(table.get_str("beira"), Some("MZ"));
+        assert_eq!(table.get_str("empty"), Some(""));
+        assert_eq!(table.get_str("bei"), None);
+        assert_eq!(table.keys_with_prefix(b"bei"), vec![b"bei'an".to_vec(), b"beira".to_vec()]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_rejects_other_files() {
+        let path = temp_path("bad");
+        std::fs::write(&path, b"MOLTMAP1\xff\xff\xff\xff\xff\xff\xff\x7f").unwrap();
+        assert_eq!(Table::open(&path).err().unwrap().kind(), io::ErrorKind::InvalidData);
+        std::fs::write(&path, "beira\tMZ\n").unwrap();
+        assert!(Table::open(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(Table::open(&path).err().unwrap().kind(), io::ErrorKind::NotFound);
+    }
+}
@@ -0,0 +1,247 @@
+//! Metrics counters for the native layer.
+//!
+//! Lock-free counters and fixed-bucket histograms updated as the
+//! extension runs, read by the crawler's Prometheus exporter through
+//! `get_metrics`. Recording is a few relaxed atomic adds; histogram
+//! buckets are reported cumulatively, as Prometheus expects.
+
+use once_cell::sync::Lazy;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+#[cfg(feature = "python")]
+use pyo3::types::PyDict;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+
+#[cfg(feature = "python")]
+use crate::errors::guarded;
+
+/// Latency bucket upper bounds in nanoseconds (100µs .. 10s).
+const LATENCY_BOUNDS_NS: &[u64] = &[
+    100_000, 500_000, 1_000_000, 5_000_000, 10_000_000, 50_000_000, 100_000_000, 500_000_000,
+    1_000_000_000, 10_000_000_000,
+];
+/// Cluster size bucket upper bounds.
+const CLUSTER_BOUNDS: &[u64] = &[1, 2, 3, 5, 10, 20, 50, 100];
+
+/// Histogram over integer observations; `scale` converts them to the
+/// reported unit.
+pub(crate) struct Histogram {
+    bounds: &'static [u64],
+    scale: f64,
+    /// One per bound plus the +Inf bucket; not cumulative.
+    buckets: Vec<AtomicU64>,
+    sum: AtomicU64,
+    count: AtomicU64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [u64], scale: f64) -> Self {
+        Self {
+            bounds,
+            scale,
+            buckets: (0..=bounds.len()).map(|_| AtomicU64::new(0)).collect(),
+            sum: AtomicU64::new(0),
+            count: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn observe(&self, value: u64) {
+        let bucket = self.bounds.partition_point(|b| *b < value);
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.sum.fetch_add(value, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Cumulative `(upper_bound, count)` pairs ending at +Inf, the sum
+    /// and the count, in the reported unit.
+    fn snapshot(&self) -> (Vec<(f64, u64)>, f64, u64) {
+        let mut total = 0;
+        let buckets = self
+            .buckets
+            .iter()
+            .enumerate()
+            .map(|(i, count)| {
+                total += count.load(Ordering::Relaxed);
+                let bound = self.bounds.get(i).map_or(f64::INFINITY, |b| *b as f64 * self.scale);
+                (bound, total)
+            })
+            .collect();
+        let sum = self.sum.load(Ordering::Relaxed) as f64 * self.scale;
+        (buckets, sum, self.count.load(Ordering::Relaxed))
+    }
+
+    fn reset(&self) {
+        for bucket in &self.buckets {
+            bucket.store(0, Ordering::Relaxed);
+        }
+        self.sum.store(0, Ordering::Relaxed);
+        self.count.store(0, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "python")]
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let (buckets, sum, count) = self.snapshot();
+        let dict = PyDict::new_bound(py);
+        dict.set_item("buckets", buckets)?;
+        dict.set_item("sum", sum)?;
+        dict.set_item("count", count)?;
+        Ok(dict)
+    }
+}
+
+static TEXTS_PROCESSED: AtomicU64 = AtomicU64::new(0);
+static FIGURES_FOUND: Lazy<RwLock<HashMap<String, AtomicU64>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+static CLUSTER_SIZES: Lazy<Histogram> = Lazy::new(|| Histogram::new(CLUSTER_BOUNDS, 1.0));
+static LATENCIES: Lazy<RwLock<HashMap<&'static str, Arc<Histogram>>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Count one article run through the pipeline.
+pub(crate) fn record_text() {
+    TEXTS_PROCESSED.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Count the figure keys found in one text.
+pub(crate) fn record_figures<'a>(keys: impl IntoIterator<Item = &'a String>) {
+    for key in keys {
+        let found = FIGURES_FOUND.read().unwrap_or_else(|e| e.into_inner());
+        if let Some(counter) = found.get(key) {
+            counter.fetch_add(1, Ordering::Relaxed);
+            continue;
+        }
+        drop(found);
+        let mut found = FIGURES_FOUND.write().unwrap_or_else(|e| e.into_inner());
+        found.entry(key.clone()).or_default().fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+pub(crate) fn record_cluster_sizes(sizes: impl IntoIterator<Item = usize>) {
+    for size in sizes {
+        CLUSTER_SIZES.observe(size as u64);
+    }
+}
+
+fn latency(name: &'static str) -> Arc<Histogram> {
+    if let Some(histogram) = LATENCIES.read().unwrap_or_else(|e| e.into_inner()).get(name) {
+        return histogram.clone();
+    }
+    let mut latencies = LATENCIES.write().unwrap_or_else(|e| e.into_inner());
+    latencies
+        .entry(name)
+        .or_insert_with(|| Arc::new(Histogram::new(LATENCY_BOUNDS_NS, 1e-9)))
+        .clone()
+}
+
+/// Records the time until it is dropped under a function name.
+pub(crate) struct Timer {
+    name: &'static str,
+    start: Instant,
+}
+
+pub(crate) fn timer(name: &'static str) -> Timer {
+    Timer { name, start: Instant::now() }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed().as_nanos().min(u64::MAX as u128) as u64;
+        latency(self.name).observe(elapsed);
+    }
+}
+
+/// Snapshot of the native layer's metrics.
+///
+/// Counters are totals since import (or the last ``reset_metrics``).
+/// Histograms are ``{"buckets": [(upper_bound, count), ...], "sum":
+/// float, "count": int}`` with cumulative bucket counts ending at
+/// ``inf``, ready for a Prometheus histogram.
+///
+/// Returns
+/// -------
+/// dict
+///     ``texts_processed`` (articles run through ``process_article``,
+///     ``Pipeline``, ``process_jsonl`` or ``ArticleExporter``);
+///     ``figures_found`` (dict[str, int], texts each figure key was
+///     found in); ``cluster_sizes`` (histogram of ``cluster_titles``
+///     cluster sizes); ``latency_seconds`` (dict of function name →
+///     histogram of call durations).
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn get_metrics(py: Python<'_>) -> PyResult<Py<PyDict>> {
+    guarded("get_metrics", || {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("texts_processed", TEXTS_PROCESSED.load(Ordering::Relaxed))?;
+        let figures = PyDict::new_bound(py);
+        for (key, count) in FIGURES_FOUND.read().unwrap_or_else(|e| e.into_inner()).iter() {
+            figures.set_item(key, count.load(Ordering::Relaxed))?;
+        }
+        dict.set_item("figures_found", figures)?;
+        dict.set_item("cluster_sizes", CLUSTER_SIZES.to_dict(py)?)?;
+        let latencies = PyDict::new_bound(py);
+        for (name, histogram) in LATENCIES.read().unwrap_or_else(|e| e.into_inner()).iter() {
+            latencies.set_item(*name, histogram.to_dict(py)?)?;
+        }
+        dict.set_item("latency_seconds", latencies)?;
+        Ok(dict.unbind())
+    })
+}
+
+/// Reset every counter and histogram to zero.
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn reset_metrics() -> PyResult<()> {
+    guarded("reset_metrics", || {
+        TEXTS_PROCESSED.store(0, Ordering::Relaxed);
+        FIGURES_FOUND.write().unwrap_or_else(|e| e.into_inner()).clear();
+        CLUSTER_SIZES.reset();
+        for histogram in LATENCIES.read().unwrap_or_else(|e| e.into_inner()).values() {
+            histogram.reset();
+        }
+        Ok(())
+    })
+}
+
+#[cfg(all(test, feature = "python"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_buckets() {
+        let histogram = Histogram::new(CLUSTER_BOUNDS, 1.0);
+        for size in [1, 1, 2, 4, 500] {
+            histogram.observe(size);
+        }
+        let (buckets, sum, count) = histogram.snapshot();
+        assert_eq!(buckets[0], (1.0, 2));
+        assert_eq!(buckets[1], (2.0, 3));
+        assert_eq!(buckets[3], (5.0, 4));
+        assert_eq!(*buckets.last().unwrap(), (f64::INFINITY, 5));
+        assert_eq!((sum, count), (508.0, 5));
+        histogram.reset();
+        assert_eq!(histogram.snapshot().2, 0);
+    }
+
+    #[test]
+    fn test_recording_and_snapshot() {
+        let before = TEXTS_PROCESSED.load(Ordering::Relaxed);
+        record_text();
+        assert!(TEXTS_PROCESSED.load(Ordering::Relaxed) > before);
+        record_figures(&["metrics_test_key".to_string()]);
+        record_figures(&["metrics_test_key".to_string()]);
+        drop(timer("metrics_test_fn"));
+
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let metrics = get_metrics(py).unwrap().into_bound(py);
+            let figures = metrics.get_item("figures_found").unwrap().unwrap();
+            assert_eq!(figures.get_item("metrics_test_key").unwrap().extract::<u64>().unwrap(), 2);
+            let latency = metrics.get_item("latency_seconds").unwrap().unwrap();
+            let histogram = latency.get_item("metrics_test_fn").unwrap();
+            assert_eq!(histogram.get_item("count").unwrap().extract::<u64>().unwrap(), 1);
+            let buckets: Vec<(f64, u64)> = histogram.get_item("buckets").unwrap().extract().unwrap();
+            assert_eq!(buckets[0].0, 0.0001);
+            assert_eq!(buckets.len(), LATENCY_BOUNDS_NS.len() + 1);
+        });
+    }
+}
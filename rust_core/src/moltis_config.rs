@@ -0,0 +1,412 @@
+//! Shared crawler configuration with hot reload.
+//!
+//! Long-running workers shouldn't need a restart to pick up a new keyword
+//! pack or tracking parameter. A `MoltisConfig` loads keyword packs,
+//! thresholds, gazetteer files and tracking-parameter rules from one TOML
+//! file; pipelines built from it follow `reload()` on their next call,
+//! and the tracking rules apply to URL canonicalization process-wide.
+//!
+//! The keyword tables use the `config/nlp_keywords.toml` layout, so that
+//! file is a valid config on its own:
+//!
+//! ```toml
+//! [impact]
+//! people_impact = ["deaths", "displaced"]
+//! [need]
+//! health = ["cholera", "clinic"]
+//! [risk]
+//! keywords = ["forecast", "warning"]
+//! [thresholds]
+//! min_impact_hits = 1
+//! min_need_hits = 2
+//! [tracking]
+//! params = ["mc_cid", "mc_eid"]
+//! prefixes = ["pk_"]
+//! [gazetteers]
+//! paths = ["gazetteers/moz.json"]   # relative to this file
+//! [storage]
+//! backend = "sqlite"                # or "mmap"; default "memory"
+//! dir = "state"                     # relative to this file
+//! ```
+
+use pyo3::exceptions::PyOSError;
+use pyo3::prelude::*;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use crate::errors::{Failure, config_error, guarded, unlocked};
+use crate::pipeline::PipelineConfig;
+use crate::storage::{self, Backend};
+use crate::url_canonical::set_extra_tracking;
+
+#[derive(Debug, Default, Deserialize)]
+struct ConfigFile {
+    impact: Option<HashMap<String, Vec<String>>>,
+    need: Option<HashMap<String, Vec<String>>>,
+    risk: Option<RiskSection>,
+    #[serde(default)]
+    thresholds: Thresholds,
+    #[serde(default)]
+    tracking: TrackingSection,
+    #[serde(default)]
+    gazetteers: GazetteerSection,
+    storage: Option<StorageSection>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RiskSection {
+    keywords: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct Thresholds {
+    min_impact_hits: usize,
+    min_need_hits: usize,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self { min_impact_hits: 1, min_need_hits: 1 }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct TrackingSection {
+    params: Vec<String>,
+    prefixes: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct GazetteerSection {
+    paths: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StorageSection {
+    backend: String,
+    #[serde(default)]
+    dir: String,
+}
+
+/// Country gazetteer in the `config/gazetteers/<iso3>.json` format.
+#[derive(Debug, Deserialize)]
+struct GazetteerFile {
+    admin1: BTreeMap<String, Vec<String>>,
+}
+
+/// One loaded configuration.
+#[derive(Debug, Default)]
+pub(crate) struct Settings {
+    /// All stages enabled; pipelines keep their own stage selection.
+    pub pipeline: PipelineConfig,
+    tracking_params: Vec<String>,
+    tracking_prefixes: Vec<String>,
+    /// Default storage for stateful components, when configured.
+    storage: Option<(Backend, PathBuf)>,
+}
+
+fn parse_file(text: &str) -> Result<ConfigFile, Failure> {
+    toml::from_str(text).map_err(|e| {
+        let failure = Failure::from(format!("invalid config file: {e}"));
+        match e.span() {
+            Some(span) => failure.at_line(text[..span.start].matches('\n').count() + 1),
+            None => failure,
+        }
+    })
+}
+
+/// `(area_name, admin_level)` pairs: provinces at level 1, districts at 2.
+fn parse_gazetteer(text: &str) -> Result<Vec<(String, i32)>, Failure> {
+    let file: GazetteerFile = serde_json::from_str(text)
+        .map_err(|e| Failure::from(format!("invalid gazetteer: {e}")).at_line(e.line()))?;
+    let mut areas = Vec::new();
+    for (admin1, districts) in file.admin1 {
+        areas.extend(districts.into_iter().map(|d| (d, 2)));
+        areas.push((admin1, 1));
+    }
+    Ok(areas)
+}
+
+impl Settings {
+    fn build(file: ConfigFile, admin_areas: Vec<(String, i32)>, storage: Option<(Backend, PathBuf)>) -> Self {
+        let mut pipeline = PipelineConfig::default();
+        pipeline.configure(
+            file.impact,
+            file.need,
+            file.risk.map(|r| r.keywords),
+            file.thresholds.min_impact_hits,
+            file.thresholds.min_need_hits,
+            admin_areas,
+        );
+        Self {
+            pipeline,
+            tracking_params: file.tracking.params,
+            tracking_prefixes: file.tracking.prefixes,
+            storage,
+        }
+    }
+
+    fn load(path: &str) -> PyResult<Self> {
+        let read = |path: &Path| {
+            std::fs::read_to_string(path)
+                .map_err(|e| PyOSError::new_err(format!("cannot read {}: {e}", path.display())))
+        };
+        let file = parse_file(&read(Path::new(path))?).map_err(|e| config_error(Some(path), e))?;
+        let base = Path::new(path).parent().unwrap_or(Path::new(""));
+        let mut admin_areas = Vec::new();
+        for gazetteer in &file.gazetteers.paths {
+            let gazetteer = base.join(gazetteer);
+            let areas = parse_gazetteer(&read(&gazetteer)?)
+                .map_err(|e| config_error(Some(&gazetteer.display().to_string()), e))?;
+            admin_areas.extend(areas);
+        }
+        let storage = match &file.storage {
+            Some(section) => {
+                let backend = Backend::parse(&section.backend)
+                    .map_err(|e| config_error(Some(path), Failure::new("invalid_value", e)))?;
+                let dir = base.join(&section.dir);
+                if backend != Backend::Memory {
+                    std::fs::create_dir_all(&dir)
+                        .map_err(|e| PyOSError::new_err(format!("cannot create {}: {e}", dir.display())))?;
+                }
+                Some((backend, dir))
+            }
+            None => None,
+        };
+        Ok(Self::build(file, admin_areas, storage))
+    }
+
+    /// Apply the process-wide parts: tracking rules and default storage.
+    fn apply_globals(&self) {
+        set_extra_tracking(&self.tracking_params, &self.tracking_prefixes);
+        if let Some((backend, dir)) = &self.storage {
+            storage::set_default(*backend, dir);
+        }
+    }
+}
+
+/// The current settings and a generation counter bumped on each reload.
+#[derive(Debug)]
+pub(crate) struct SharedConfig {
+    current: RwLock<(u64, Arc<Settings>)>,
+}
+
+impl SharedConfig {
+    fn new(settings: Settings) -> Self {
+        Self { current: RwLock::new((1, Arc::new(settings))) }
+    }
+
+    pub(crate) fn snapshot(&self) -> (u64, Arc<Settings>) {
+        let current = self.current.read().unwrap_or_else(|e| e.into_inner());
+        (current.0, current.1.clone())
+    }
+
+    fn replace(&self, settings: Settings) -> u64 {
+        let mut current = self.current.write().unwrap_or_else(|e| e.into_inner());
+        *current = (current.0 + 1, Arc::new(settings));
+        current.0
+    }
+}
+
+/// Shared crawler configuration that can be reloaded in place.
+///
+/// Holds the keyword packs, hit thresholds and gazetteer used by
+/// ``Pipeline(config=...)`` and the extra tracking parameters stripped by
+/// ``canonicalize_url``. Pipelines sharing a config pick up a
+/// ``reload()`` on their next call; tracking rules and the default
+/// storage are process-wide, so the most recently loaded config's apply.
+///
+/// Parameters
+/// ----------
+/// path : str | None
+///     TOML file with ``[impact]`` / ``[need]`` keyword tables,
+///     ``[risk] keywords``, ``[thresholds] min_impact_hits`` /
+///     ``min_need_hits``, ``[tracking] params`` / ``prefixes``,
+///     ``[gazetteers] paths`` (gazetteer JSON files, relative to the
+///     config file) and ``[storage] backend`` / ``dir`` (the default for
+///     ``configure_storage``; ``dir`` relative to the config file).
+///     Missing sections keep the defaults. None uses the defaults
+///     throughout.
+///
+/// Raises
+/// ------
+/// OSError
+///     If the config or a gazetteer file cannot be read.
+/// ConfigError
+///     If a file is not valid TOML / JSON of the expected shape
+///     (``reason`` ``"malformed"``; ``path`` and ``line`` locate it), or
+///     names an unknown storage backend (``"invalid_value"``). A
+///     ``ValueError`` subclass.
+#[pyclass(module = "moltis_rust_core")]
+pub struct MoltisConfig {
+    shared: Arc<SharedConfig>,
+    path: Option<String>,
+}
+
+impl MoltisConfig {
+    pub(crate) fn shared(&self) -> Arc<SharedConfig> {
+        self.shared.clone()
+    }
+
+    pub(crate) fn source_path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+}
+
+#[pymethods]
+impl MoltisConfig {
+    #[new]
+    #[pyo3(signature = (path=None))]
+    fn py_new(path: Option<String>) -> PyResult<Self> {
+        guarded("MoltisConfig.__new__", || {
+            let settings = match &path {
+                Some(path) => Settings::load(path)?,
+                None => Settings::default(),
+            };
+            settings.apply_globals();
+            Ok(Self { shared: Arc::new(SharedConfig::new(settings)), path })
+        })
+    }
+
+    /// Reload the configuration, atomically for every sharing pipeline.
+    ///
+    /// On error the current configuration stays in place.
+    ///
+    /// Parameters
+    /// ----------
+    /// path : str | None
+    ///     File to load; it becomes this config's path. Default: the
+    ///     path it was last loaded from.
+    ///
+    /// Returns
+    /// -------
+    /// int
+    ///     The new generation number.
+    ///
+    /// Raises
+    /// ------
+    /// ConfigError
+    ///     If no path is given and none was loaded before (``reason``
+    ///     ``"no_path"``), or the file is invalid (``"malformed"``).
+    /// OSError
+    ///     If a file cannot be read.
+    #[pyo3(signature = (path=None))]
+    fn reload(&mut self, py: Python<'_>, path: Option<String>) -> PyResult<u64> {
+        let Some(path) = path.or_else(|| self.path.clone()) else {
+            return Err(config_error(None, Failure::new("no_path", "no config path to reload from")));
+        };
+        let settings = unlocked(py, "MoltisConfig.reload", || Settings::load(&path))?.inspect_err(|e| {
+            tracing::warn!(path, error = %e, "config reload failed, keeping the current settings");
+        })?;
+        settings.apply_globals();
+        self.path = Some(path);
+        Ok(self.shared.replace(settings))
+    }
+
+    /// Path the configuration was last loaded from.
+    #[getter]
+    fn path(&self) -> PyResult<Option<String>> {
+        guarded("MoltisConfig.path", || Ok(self.path.clone()))
+    }
+
+    /// Incremented on every successful reload, starting at 1.
+    #[getter]
+    fn generation(&self) -> PyResult<u64> {
+        guarded("MoltisConfig.generation", || Ok(self.shared.snapshot().0))
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        guarded("MoltisConfig.__repr__", || {
+            let path = self.path.as_ref().map_or("None".to_string(), |p| format!("{p:?}"));
+            Ok(format!("MoltisConfig(path={path}, generation={})", self.shared.snapshot().0))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+[need]
+health = ["Cholera"]
+
+[thresholds]
+min_need_hits = 2
+
+[tracking]
+params = ["moltis_cfg_test"]
+
+[gazetteers]
+paths = ["gaz.json"]
+"#;
+
+    #[test]
+    fn test_parse_config_and_gazetteer() {
+        let file = parse_file(CONFIG).unwrap();
+        assert_eq!(file.thresholds.min_need_hits, 2);
+        assert_eq!(file.thresholds.min_impact_hits, 1);
+        assert_eq!(file.tracking.params, vec!["moltis_cfg_test"]);
+        assert!(file.impact.is_none());
+        assert!(parse_file("[thresholds]\nmin_need_hits = \"two\"").is_err());
+        // The shipped keyword file is itself a valid config
+        assert!(parse_file(include_str!("../../config/nlp_keywords.toml")).unwrap().impact.is_some());
+
+        let areas = parse_gazetteer(r#"{"country": "Mozambique", "admin1": {"Sofala": ["Beira", "Dondo"]}}"#).unwrap();
+        assert_eq!(areas, vec![("Beira".into(), 2), ("Dondo".into(), 2), ("Sofala".into(), 1)]);
+        assert!(parse_gazetteer("[]").is_err());
+    }
+
+    #[test]
+    fn test_load_and_reload() {
+        let dir = std::env::temp_dir().join(format!("moltis_cfg_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("moltis.toml");
+        std::fs::write(dir.join("gaz.json"), r#"{"admin1": {"Sofala": ["Beira"]}}"#).unwrap();
+        std::fs::write(&path, CONFIG).unwrap();
+        let path = path.to_str().unwrap().to_string();
+
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let mut config = MoltisConfig::py_new(Some(path.clone())).unwrap();
+            assert_eq!(config.generation().unwrap(), 1);
+            assert_eq!(
+                crate::url_canonical::canonicalize_url("https://example.org/a?moltis_cfg_test=1&id=2"),
+                "https://example.org/a?id=2"
+            );
+            let record = config.shared().snapshot().1.pipeline.run(
+                "Floods in Beira",
+                "Cholera cases rise.",
+                "https://example.org/a",
+                None,
+                None,
+                chrono::DateTime::UNIX_EPOCH,
+            );
+            // One cholera hit is below min_need_hits = 2
+            assert_eq!(record.need_types, Some(vec![]));
+            assert_eq!(record.admin_area, Some(Some(("Beira".into(), 2))));
+
+            std::fs::write(&path, "[thresholds]\nmin_need_hits = 1\n[need]\nhealth = [\"cholera\"]\n").unwrap();
+            assert_eq!(config.reload(py, None).unwrap(), 2);
+            let settings = config.shared().snapshot().1;
+            assert_eq!(
+                settings.pipeline.run("", "Cholera cases rise.", "https://example.org/a", None, None, chrono::DateTime::UNIX_EPOCH).need_types,
+                Some(vec!["health".to_string()])
+            );
+
+            // A failed reload keeps the current settings
+            std::fs::write(&path, "not toml [").unwrap();
+            let err = config.reload(py, None).unwrap_err();
+            assert!(err.is_instance_of::<crate::errors::ConfigError>(py));
+            assert_eq!(err.value_bound(py).getattr("line").unwrap().extract::<usize>().unwrap(), 1);
+            assert_eq!(config.generation().unwrap(), 2);
+            assert!(MoltisConfig::py_new(None).unwrap().reload(py, None).is_err());
+        });
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
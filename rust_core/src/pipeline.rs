@@ -0,0 +1,714 @@
+//! Configurable article pipeline.
+//!
+//! Country programs need different subsets of the per-article stages and
+//! different keyword packs. A `Pipeline` fixes the enabled stages,
+//! keyword tables, hit thresholds and gazetteer at construction and then
+//! runs batches across threads. `process_article` is the default
+//! pipeline applied to one article. A pipeline built from a
+//! `MoltisConfig` keeps its stages but picks up the config's keyword
+//! packs, thresholds and gazetteer again after each reload.
+
+use once_cell::sync::Lazy;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use rayon::prelude::*;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::chunked::{pipeline_chunks, ChunkIterator, DEFAULT_CHUNK_SIZE};
+use crate::content_hash::content_fingerprint;
+use crate::date_parse::{parse_at, ParsedDate};
+use crate::errors::{guarded, unlocked};
+use crate::figure_extraction::{figures_in, Language, NEGATED_ZERO};
+use crate::frontier::now_secs;
+use crate::lang_detect::detect;
+use crate::metrics::{record_text, timer};
+use crate::moltis_config::{MoltisConfig, SharedConfig};
+use crate::profiling;
+use crate::storm_names::storm_names;
+use crate::text_classify::{
+    admin_area_in, default_impact_keywords, default_need_keywords, default_risk_keywords,
+    keyword_scores, ranked_labels, response_actor, severity, sort_admin_areas,
+};
+use crate::url_canonical::canonicalize_url;
+
+/// An optional pipeline stage. URL canonicalization always runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Stage {
+    Language,
+    Published,
+    Figures,
+    Impacts,
+    Needs,
+    Severity,
+    Risk,
+    Actors,
+    Admin,
+    Storms,
+    Fingerprints,
+}
+
+const ALL_STAGES: &[Stage] = &[
+    Stage::Language,
+    Stage::Published,
+    Stage::Figures,
+    Stage::Impacts,
+    Stage::Needs,
+    Stage::Severity,
+    Stage::Risk,
+    Stage::Actors,
+    Stage::Admin,
+    Stage::Storms,
+    Stage::Fingerprints,
+];
+
+impl Stage {
+    fn name(self) -> &'static str {
+        match self {
+            Stage::Language => "language",
+            Stage::Published => "published",
+            Stage::Figures => "figures",
+            Stage::Impacts => "impacts",
+            Stage::Needs => "needs",
+            Stage::Severity => "severity",
+            Stage::Risk => "risk",
+            Stage::Actors => "actors",
+            Stage::Admin => "admin",
+            Stage::Storms => "storms",
+            Stage::Fingerprints => "fingerprints",
+        }
+    }
+
+    fn parse(name: &str) -> Result<Self, String> {
+        let name = name.trim().to_lowercase();
+        ALL_STAGES
+            .iter()
+            .copied()
+            .find(|s| s.name() == name)
+            .ok_or_else(|| format!("unknown pipeline stage {name:?}"))
+    }
+}
+
+/// One article's results; `None` for stages that are disabled.
+#[derive(Debug, Default)]
+pub(crate) struct ArticleRecord {
+    pub canonical_url: String,
+    pub language: Option<Option<(&'static str, f64)>>,
+    pub published: Option<Option<ParsedDate>>,
+    pub figures: Option<HashMap<String, i64>>,
+    /// Dominant impact type and all impact types by score.
+    pub impacts: Option<(String, Vec<String>)>,
+    pub need_types: Option<Vec<String>>,
+    pub severity: Option<i32>,
+    pub is_risk: Option<bool>,
+    pub response_actor: Option<Option<(String, String)>>,
+    pub admin_area: Option<Option<(String, i32)>>,
+    pub storms: Option<Vec<(String, String)>>,
+    pub fingerprint: Option<u128>,
+}
+
+impl ArticleRecord {
+    pub(crate) fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("canonical_url", &self.canonical_url)?;
+        if let Some(language) = self.language {
+            dict.set_item("language", language.map(|(code, _)| code))?;
+            dict.set_item("language_confidence", language.map(|(_, confidence)| confidence))?;
+        }
+        if let Some(published) = &self.published {
+            dict.set_item("published", published.as_ref().map(ParsedDate::iso))?;
+            dict.set_item("published_confidence", published.as_ref().map(|p| p.confidence.as_str()))?;
+        }
+        if let Some(figures) = &self.figures {
+            dict.set_item("figures", figures)?;
+        }
+        if let Some((dominant, all)) = &self.impacts {
+            dict.set_item("impact_type", dominant)?;
+            dict.set_item("impact_types", all)?;
+        }
+        if let Some(needs) = &self.need_types {
+            dict.set_item("need_types", needs)?;
+        }
+        if let Some(severity) = self.severity {
+            dict.set_item("severity", severity)?;
+        }
+        if let Some(is_risk) = self.is_risk {
+            dict.set_item("is_risk", is_risk)?;
+        }
+        if let Some(actor) = &self.response_actor {
+            dict.set_item("response_actor", actor.clone())?;
+        }
+        if let Some(area) = &self.admin_area {
+            dict.set_item("admin_area", area.clone())?;
+        }
+        if let Some(storms) = &self.storms {
+            dict.set_item("storms", storms.clone())?;
+        }
+        if let Some(fingerprint) = self.fingerprint {
+            dict.set_item("fingerprint", fingerprint)?;
+        }
+        Ok(dict)
+    }
+
+    /// The `to_dict` record as JSON. The fingerprint is a 32-digit hex
+    /// string, since JSON numbers can't hold 128 bits portably.
+    pub(crate) fn to_json(&self) -> serde_json::Value {
+        let mut map = serde_json::Map::new();
+        map.insert("canonical_url".into(), json!(self.canonical_url));
+        if let Some(language) = self.language {
+            map.insert("language".into(), json!(language.map(|(code, _)| code)));
+            map.insert("language_confidence".into(), json!(language.map(|(_, confidence)| confidence)));
+        }
+        if let Some(published) = &self.published {
+            map.insert("published".into(), json!(published.as_ref().map(ParsedDate::iso)));
+            map.insert("published_confidence".into(), json!(published.as_ref().map(|p| p.confidence.as_str())));
+        }
+        if let Some(figures) = &self.figures {
+            map.insert("figures".into(), json!(figures));
+        }
+        if let Some((dominant, all)) = &self.impacts {
+            map.insert("impact_type".into(), json!(dominant));
+            map.insert("impact_types".into(), json!(all));
+        }
+        if let Some(needs) = &self.need_types {
+            map.insert("need_types".into(), json!(needs));
+        }
+        if let Some(severity) = self.severity {
+            map.insert("severity".into(), json!(severity));
+        }
+        if let Some(is_risk) = self.is_risk {
+            map.insert("is_risk".into(), json!(is_risk));
+        }
+        if let Some(actor) = &self.response_actor {
+            map.insert("response_actor".into(), json!(actor));
+        }
+        if let Some(area) = &self.admin_area {
+            map.insert("admin_area".into(), json!(area));
+        }
+        if let Some(storms) = &self.storms {
+            map.insert("storms".into(), json!(storms));
+        }
+        if let Some(fingerprint) = self.fingerprint {
+            map.insert("fingerprint".into(), json!(format!("{fingerprint:032x}")));
+        }
+        serde_json::Value::Object(map)
+    }
+}
+
+/// Stage selection and configuration.
+#[derive(Debug, Clone)]
+pub(crate) struct PipelineConfig {
+    stages: Vec<Stage>,
+    impact_keywords: Vec<(String, Vec<String>)>,
+    need_keywords: Vec<(String, Vec<String>)>,
+    risk_keywords: Vec<String>,
+    min_impact_hits: usize,
+    min_need_hits: usize,
+    /// Sorted by `sort_admin_areas`.
+    admin_areas: Vec<(String, i32)>,
+    /// Negated statements give 0 in the figures stage.
+    pub(crate) negated_zero: bool,
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        Self {
+            stages: ALL_STAGES.to_vec(),
+            impact_keywords: default_impact_keywords(),
+            need_keywords: default_need_keywords(),
+            risk_keywords: default_risk_keywords(),
+            min_impact_hits: 1,
+            min_need_hits: 1,
+            admin_areas: Vec::new(),
+            negated_zero: NEGATED_ZERO,
+        }
+    }
+}
+
+pub(crate) static DEFAULT_PIPELINE: Lazy<PipelineConfig> = Lazy::new(PipelineConfig::default);
+
+fn lowercase_table(table: HashMap<String, Vec<String>>) -> Vec<(String, Vec<String>)> {
+    let mut table: Vec<(String, Vec<String>)> = table
+        .into_iter()
+        .map(|(label, keywords)| (label, keywords.iter().map(|k| k.trim().to_lowercase()).collect()))
+        .collect();
+    // Dicts carry no reliable order across the FFI; sort for stable ties
+    table.sort();
+    table
+}
+
+/// Stable hash of a keyword table, independent of label and keyword
+/// order, so a pack loaded from config matches the built-in one when
+/// their contents do.
+fn table_hash<'a>(table: impl IntoIterator<Item = (&'a str, &'a [String])>) -> String {
+    let mut lines: Vec<String> = table
+        .into_iter()
+        .flat_map(|(label, keywords)| keywords.iter().map(move |k| format!("{label}\t{k}")))
+        .collect();
+    lines.sort_unstable();
+    lines.dedup();
+    format!("{:016x}", xxh3_64(lines.join("\n").as_bytes()))
+}
+
+impl PipelineConfig {
+    fn enabled(&self, stage: Stage) -> bool {
+        self.stages.contains(&stage)
+    }
+
+    /// Stages, keyword packs (label and keyword counts plus a content
+    /// hash), thresholds and gazetteer size, for `runtime_info`.
+    pub(crate) fn describe<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let pack = |table: &[(String, Vec<String>)]| -> PyResult<Bound<'py, PyDict>> {
+            let dict = PyDict::new_bound(py);
+            dict.set_item("labels", table.len())?;
+            dict.set_item("keywords", table.iter().map(|(_, k)| k.len()).sum::<usize>())?;
+            dict.set_item("hash", table_hash(table.iter().map(|(l, k)| (l.as_str(), k.as_slice()))))?;
+            Ok(dict)
+        };
+        let dict = PyDict::new_bound(py);
+        dict.set_item("stages", self.stages.iter().map(|s| s.name()).collect::<Vec<_>>())?;
+        dict.set_item("impact", pack(&self.impact_keywords)?)?;
+        dict.set_item("need", pack(&self.need_keywords)?)?;
+        let risk = PyDict::new_bound(py);
+        risk.set_item("keywords", self.risk_keywords.len())?;
+        risk.set_item("hash", table_hash([("risk", self.risk_keywords.as_slice())]))?;
+        dict.set_item("risk", risk)?;
+        dict.set_item("min_impact_hits", self.min_impact_hits)?;
+        dict.set_item("min_need_hits", self.min_need_hits)?;
+        dict.set_item("admin_areas", self.admin_areas.len())?;
+        Ok(dict)
+    }
+
+    /// Replace the keyword packs (None keeps the defaults), hit thresholds
+    /// and gazetteer.
+    pub(crate) fn configure(
+        &mut self,
+        impact_keywords: Option<HashMap<String, Vec<String>>>,
+        need_keywords: Option<HashMap<String, Vec<String>>>,
+        risk_keywords: Option<Vec<String>>,
+        min_impact_hits: usize,
+        min_need_hits: usize,
+        admin_areas: Vec<(String, i32)>,
+    ) {
+        if let Some(table) = impact_keywords {
+            self.impact_keywords = lowercase_table(table);
+        }
+        if let Some(table) = need_keywords {
+            self.need_keywords = lowercase_table(table);
+        }
+        if let Some(list) = risk_keywords {
+            self.risk_keywords = list.iter().map(|k| k.trim().to_lowercase()).collect();
+        }
+        self.min_impact_hits = min_impact_hits;
+        self.min_need_hits = min_need_hits;
+        self.admin_areas = sort_admin_areas(admin_areas);
+    }
+
+    /// Enable only the named stages.
+    pub(crate) fn set_stages<S: AsRef<str>>(&mut self, names: &[S]) -> Result<(), String> {
+        self.stages = names.iter().map(|n| Stage::parse(n.as_ref())).collect::<Result<_, _>>()?;
+        Ok(())
+    }
+
+    /// Run the enabled stages on one article; `areas` overrides the
+    /// configured gazetteer when given.
+    pub(crate) fn run(
+        &self,
+        title: &str,
+        body: &str,
+        url: &str,
+        published: Option<&str>,
+        areas: Option<&[(String, i32)]>,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> ArticleRecord {
+        record_text();
+        let text = if title.is_empty() {
+            body.to_string()
+        } else {
+            format!("{title}\n\n{body}")
+        };
+        let lower = text.to_lowercase();
+        let mut record = ArticleRecord {
+            canonical_url: profiling::stage("pipeline.canonical_url", |_| 0, || canonicalize_url(url)),
+            ..Default::default()
+        };
+        // Detected for the date parser and figure patterns even when not
+        // reported
+        let language = if self.enabled(Stage::Language) || self.enabled(Stage::Published) || self.enabled(Stage::Figures) {
+            profiling::stage("pipeline.language", profiling::found, || detect(&text, None))
+        } else {
+            None
+        };
+        if self.enabled(Stage::Language) {
+            record.language = Some(language);
+        }
+        if self.enabled(Stage::Published) {
+            let hint_lang = language.map(|(code, _)| code);
+            record.published = Some(profiling::stage("pipeline.published", profiling::found, || {
+                published.and_then(|p| parse_at(p, hint_lang, now))
+            }));
+        }
+        if self.enabled(Stage::Figures) {
+            // Languages without a pattern set are read with the English one
+            let lang = language.and_then(|(code, _)| Language::from_code(code)).unwrap_or(Language::English);
+            record.figures =
+                Some(profiling::stage("pipeline.figures", HashMap::len, || figures_in(&text, lang, self.negated_zero)));
+        }
+        if self.enabled(Stage::Impacts) {
+            let table = self.impact_keywords.iter().map(|(l, k)| (l.as_str(), k.as_slice()));
+            let all: Vec<String> = profiling::stage("pipeline.impacts", Vec::len, || {
+                ranked_labels(keyword_scores(&lower, table), self.min_impact_hits)
+                    .into_iter()
+                    .map(str::to_string)
+                    .collect()
+            });
+            // Nothing matched: the first label of the table (people_impact)
+            let fallback = self.impact_keywords.first().map(|(l, _)| l.clone()).unwrap_or_default();
+            let all = if all.is_empty() { vec![fallback] } else { all };
+            record.impacts = Some((all[0].clone(), all));
+        }
+        if self.enabled(Stage::Needs) {
+            let table = self.need_keywords.iter().map(|(l, k)| (l.as_str(), k.as_slice()));
+            let needs = profiling::stage("pipeline.needs", Vec::len, || {
+                keyword_scores(&lower, table)
+                    .into_iter()
+                    .filter(|&(_, hits)| hits >= self.min_need_hits.max(1))
+                    .map(|(label, _)| label.to_string())
+                    .collect()
+            });
+            record.need_types = Some(needs);
+        }
+        if self.enabled(Stage::Severity) {
+            // Phase 1 is the no-keyword default
+            record.severity = Some(profiling::stage("pipeline.severity", |s| usize::from(*s > 1), || severity(&lower)));
+        }
+        if self.enabled(Stage::Risk) {
+            record.is_risk = Some(profiling::stage("pipeline.risk", |r| usize::from(*r), || {
+                self.risk_keywords.iter().any(|kw| lower.contains(kw.as_str()))
+            }));
+        }
+        if self.enabled(Stage::Actors) {
+            record.response_actor = Some(profiling::stage("pipeline.actors", profiling::found, || response_actor(&lower)));
+        }
+        if self.enabled(Stage::Admin) {
+            record.admin_area = Some(profiling::stage("pipeline.admin", profiling::found, || {
+                admin_area_in(&lower, areas.unwrap_or(&self.admin_areas))
+            }));
+        }
+        if self.enabled(Stage::Storms) {
+            record.storms = Some(profiling::stage("pipeline.storms", Vec::len, || storm_names(&text)));
+        }
+        if self.enabled(Stage::Fingerprints) {
+            record.fingerprint = Some(profiling::stage("pipeline.fingerprints", |_| 1, || content_fingerprint(body)));
+        }
+        record
+    }
+}
+
+pub(crate) fn current_time() -> chrono::DateTime<chrono::Utc> {
+    chrono::DateTime::from_timestamp(now_secs() as i64, 0).unwrap_or_default()
+}
+
+/// An article pipeline with a fixed set of stages.
+///
+/// Parameters
+/// ----------
+/// stages : list[str] | None
+///     Stages to run: ``"language"``, ``"published"``, ``"figures"``,
+///     ``"impacts"``, ``"needs"``, ``"severity"``, ``"risk"``,
+///     ``"actors"``, ``"admin"``, ``"storms"``, ``"fingerprints"``.
+///     Default: all. The URL is always canonicalized.
+/// impact_keywords : dict[str, list[str]] | None
+///     Impact label → keywords, replacing the pack from
+///     ``config/nlp_keywords.toml``.
+/// need_keywords : dict[str, list[str]] | None
+///     Need label → keywords, replacing the default pack.
+/// risk_keywords : list[str] | None
+///     Risk / forecast phrases, replacing the default list.
+/// min_impact_hits : int
+///     Keyword hits an impact type needs to be reported. Default 1.
+/// min_need_hits : int
+///     Keyword hits a need type needs to be reported. Default 1.
+/// admin_areas : list[tuple[str, int]] | None
+///     Gazetteer ``(area_name, admin_level)`` pairs for the admin stage.
+/// negated_zero : bool
+///     As for ``extract_figures``, in the figures stage, which reads each
+///     article with the patterns of its detected language. Default True.
+/// config : MoltisConfig | None
+///     Shared configuration supplying the keyword packs, thresholds and
+///     gazetteer instead of the arguments above; the pipeline follows
+///     its reloads.
+///
+/// Raises
+/// ------
+/// ValueError
+///     If a stage name is unknown, or ``config`` is combined with keyword
+///     tables or ``admin_areas``.
+#[pyclass(module = "moltis_rust_core")]
+pub struct Pipeline {
+    config: RwLock<Arc<PipelineConfig>>,
+    /// Config followed across reloads, and the generation `config` was
+    /// last built from.
+    shared: Option<Arc<SharedConfig>>,
+    generation: AtomicU64,
+}
+
+impl Pipeline {
+    /// The configuration to run with, rebuilt if the shared config was
+    /// reloaded since the last call.
+    pub(crate) fn config(&self) -> Arc<PipelineConfig> {
+        let current = self.config.read().unwrap_or_else(|e| e.into_inner()).clone();
+        let Some(shared) = &self.shared else {
+            return current;
+        };
+        let (generation, settings) = shared.snapshot();
+        if generation == self.generation.load(Ordering::Acquire) {
+            return current;
+        }
+        let rebuilt = Arc::new(PipelineConfig {
+            stages: current.stages.clone(),
+            negated_zero: current.negated_zero,
+            ..settings.pipeline.clone()
+        });
+        *self.config.write().unwrap_or_else(|e| e.into_inner()) = rebuilt.clone();
+        self.generation.store(generation, Ordering::Release);
+        rebuilt
+    }
+}
+
+#[pymethods]
+impl Pipeline {
+    #[new]
+    #[pyo3(signature = (
+        stages=None,
+        impact_keywords=None,
+        need_keywords=None,
+        risk_keywords=None,
+        min_impact_hits=1,
+        min_need_hits=1,
+        admin_areas=None,
+        negated_zero=true,
+        config=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn py_new(
+        stages: Option<Vec<String>>,
+        impact_keywords: Option<HashMap<String, Vec<String>>>,
+        need_keywords: Option<HashMap<String, Vec<String>>>,
+        risk_keywords: Option<Vec<String>>,
+        min_impact_hits: usize,
+        min_need_hits: usize,
+        admin_areas: Option<Vec<(String, i32)>>,
+        negated_zero: bool,
+        config: Option<PyRef<'_, MoltisConfig>>,
+    ) -> PyResult<Self> {
+        guarded("Pipeline.__new__", || {
+            let shared = config.map(|c| c.shared());
+            let (mut pipeline, generation) = match &shared {
+                Some(shared) => {
+                    if impact_keywords.is_some() || need_keywords.is_some() || risk_keywords.is_some() || admin_areas.is_some() {
+                        return Err(PyValueError::new_err(
+                            "pass keyword tables and admin_areas either directly or through config, not both",
+                        ));
+                    }
+                    let (generation, settings) = shared.snapshot();
+                    (settings.pipeline.clone(), generation)
+                }
+                None => {
+                    let mut pipeline = PipelineConfig::default();
+                    pipeline.configure(
+                        impact_keywords,
+                        need_keywords,
+                        risk_keywords,
+                        min_impact_hits,
+                        min_need_hits,
+                        admin_areas.unwrap_or_default(),
+                    );
+                    (pipeline, 0)
+                }
+            };
+            if let Some(names) = stages {
+                pipeline.set_stages(&names).map_err(PyValueError::new_err)?;
+            }
+            pipeline.negated_zero = negated_zero;
+            Ok(Self {
+                config: RwLock::new(Arc::new(pipeline)),
+                shared,
+                generation: AtomicU64::new(generation),
+            })
+        })
+    }
+
+    /// Enabled stage names, in pipeline order.
+    #[getter]
+    fn stages(&self) -> PyResult<Vec<&'static str>> {
+        guarded("Pipeline.stages", || {
+            let config = self.config();
+            Ok(ALL_STAGES
+                .iter()
+                .filter(|s| config.enabled(**s))
+                .map(|s| s.name())
+                .collect())
+        })
+    }
+
+    /// Run the pipeline on one article.
+    ///
+    /// Returns
+    /// -------
+    /// dict
+    ///     ``canonical_url`` plus the keys of the enabled stages, as
+    ///     documented for ``process_article``.
+    #[pyo3(signature = (title, body, url, published=None))]
+    fn process(
+        &self,
+        py: Python<'_>,
+        title: &str,
+        body: &str,
+        url: &str,
+        published: Option<&str>,
+    ) -> PyResult<Py<PyDict>> {
+        let _timer = timer("Pipeline.process");
+        let now = current_time();
+        let config = self.config();
+        let record = unlocked(py, "Pipeline.process", || config.run(title, body, url, published, None, now))?;
+        Ok(record.to_dict(py)?.unbind())
+    }
+
+    /// Run the pipeline on a batch of articles in parallel.
+    ///
+    /// Parameters
+    /// ----------
+    /// articles : list[tuple[str, str, str, str | None]]
+    ///     ``(title, body, url, published)`` tuples.
+    ///
+    /// Returns
+    /// -------
+    /// list[dict]
+    ///     One record per article, in input order.
+    fn run(&self, py: Python<'_>, articles: Vec<(String, String, String, Option<String>)>) -> PyResult<Py<PyList>> {
+        let _timer = timer("Pipeline.run");
+        let now = current_time();
+        let config = self.config();
+        let records: Vec<ArticleRecord> = unlocked(py, "Pipeline.run", || {
+            articles
+                .par_iter()
+                .map(|(title, body, url, published)| {
+                    config.run(title, body, url, published.as_deref(), None, now)
+                })
+                .collect()
+        })?;
+        let list = PyList::empty_bound(py);
+        for record in &records {
+            list.append(record.to_dict(py)?)?;
+        }
+        Ok(list.unbind())
+    }
+
+    /// Run the pipeline over an iterable of articles, one chunk at a time.
+    ///
+    /// Like ``run``, but reads ``articles`` lazily and yields results as
+    /// it goes, so memory stays bounded by ``chunk_size`` however long
+    /// the input. Each chunk uses the configuration current when it
+    /// starts, so a long backfill follows ``MoltisConfig`` reloads.
+    ///
+    /// Parameters
+    /// ----------
+    /// articles : Iterable[tuple[str, str, str, str | None]]
+    ///     ``(title, body, url, published)`` tuples.
+    /// chunk_size : int
+    ///     Articles processed (in parallel) per chunk. Default 1000.
+    ///
+    /// Returns
+    /// -------
+    /// ChunkIterator
+    ///     Yields one ``list[dict]`` per chunk, in input order.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///     If ``chunk_size`` is 0.
+    #[pyo3(signature = (articles, chunk_size=DEFAULT_CHUNK_SIZE))]
+    fn iter_run(slf: PyRef<'_, Self>, articles: &Bound<'_, PyAny>, chunk_size: usize) -> PyResult<ChunkIterator> {
+        guarded("Pipeline.iter_run", || pipeline_chunks(slf.into(), articles, chunk_size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &str = "Floods destroyed houses and the main bridge. Cholera cases rise; \
+                        health clinics need medicine. UNICEF is responding.";
+
+    fn run(config: &PipelineConfig) -> ArticleRecord {
+        config.run("Floods in Sofala", BODY, "https://example.org/a?utm_source=x", None, None, chrono::DateTime::UNIX_EPOCH)
+    }
+
+    #[test]
+    fn test_stage_toggles() {
+        let config = PipelineConfig {
+            stages: vec![Stage::Figures, Stage::Needs],
+            ..Default::default()
+        };
+        let record = run(&config);
+        assert_eq!(record.canonical_url, "https://example.org/a");
+        assert!(record.figures.is_some() && record.need_types.is_some());
+        assert!(record.language.is_none() && record.impacts.is_none() && record.fingerprint.is_none());
+        assert!(Stage::parse("sentiment").is_err());
+    }
+
+    #[test]
+    fn test_custom_keywords_and_thresholds() {
+        let mut config = PipelineConfig {
+            impact_keywords: lowercase_table(HashMap::from([("water_impact".to_string(), vec!["Flood".to_string()])])),
+            need_keywords: lowercase_table(HashMap::from([
+                ("health".to_string(), vec!["cholera".into(), "medicine".into(), "clinic".into()]),
+                ("shelter".to_string(), vec!["houses".into()]),
+            ])),
+            ..Default::default()
+        };
+        let record = run(&config);
+        assert_eq!(record.impacts.unwrap().0, "water_impact");
+        assert_eq!(record.need_types.unwrap(), vec!["health", "shelter"]);
+        config.min_need_hits = 2;
+        assert_eq!(run(&config).need_types.unwrap(), vec!["health"]);
+    }
+
+    #[test]
+    fn test_default_matches_single_functions() {
+        let record = run(&PipelineConfig {
+            admin_areas: sort_admin_areas(vec![("Sofala".into(), 1)]),
+            ..Default::default()
+        });
+        let (dominant, all) = record.impacts.unwrap();
+        let lower = format!("Floods in Sofala\n\n{BODY}").to_lowercase();
+        assert_eq!(dominant, crate::text_classify::dominant_impact_type(&lower));
+        assert_eq!(all, crate::text_classify::impact_types(&lower));
+        assert_eq!(record.admin_area, Some(Some(("Sofala".into(), 1))));
+        assert_eq!(record.fingerprint, Some(content_fingerprint(BODY)));
+    }
+
+    #[test]
+    fn test_figures_in_detected_language() {
+        let mut config = PipelineConfig {
+            stages: vec![Stage::Figures],
+            ..Default::default()
+        };
+        let figures = |config: &PipelineConfig, title: &str, body: &str| {
+            config.run(title, body, "https://example.org/a", None, None, chrono::DateTime::UNIX_EPOCH).figures.unwrap()
+        };
+        let french = "Le cyclone a fait au moins 45 morts et détruit 1.200 maisons dans la province de Sofala, selon les autorités.";
+        let found = figures(&config, "Cyclone au Mozambique", french);
+        assert_eq!(found, figures_in(&format!("Cyclone au Mozambique\n\n{french}"), Language::French, true));
+        assert_eq!(found.get("deaths"), Some(&45));
+        let negated = "No deaths were reported after the floods in Beira.";
+        assert_eq!(figures(&config, "", negated).get("deaths"), Some(&0));
+        config.negated_zero = false;
+        assert!(figures(&config, "", negated).is_empty());
+    }
+}
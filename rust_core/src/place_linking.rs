@@ -0,0 +1,331 @@
+//! Place-name entity linking against a `Gazetteer`.
+//!
+//! Finding place names is the easy half; most are ambiguous ("Beira" is a
+//! Mozambican city and a Portuguese region, "Sofala" a province and a
+//! town). Each capitalised name in the text is looked up, then its
+//! candidates are scored on three kinds of evidence: the countries the
+//! text is about, other places in the text that are a candidate's
+//! ancestors or descendants, and population. The best candidate is linked
+//! with a confidence reflecting both its score and its margin over the
+//! alternatives.
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::collections::{HashMap, HashSet};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::errors::{unlocked, Failure};
+use crate::gazetteer::{normalize_name, Gazetteer};
+use crate::stopwords::is_stopword;
+
+/// Longest place name tried, in words.
+const MAX_NAME_WORDS: usize = 6;
+/// Evidence weights; the remainder is a base score every candidate gets.
+const COUNTRY_WEIGHT: f64 = 0.3;
+const HIERARCHY_WEIGHT: f64 = 0.3;
+const POPULATION_WEIGHT: f64 = 0.15;
+const ADMIN_WEIGHT: f64 = 0.05;
+const BASE_SCORE: f64 = 0.2;
+/// Population earning the full population prior.
+const FULL_POPULATION: f64 = 10_000_000.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PlaceLink {
+    /// The name as written.
+    pub text: String,
+    /// Character offsets of the mention.
+    pub start: usize,
+    pub end: usize,
+    /// Gazetteer index of the linked place.
+    pub place: usize,
+    pub confidence: f64,
+    /// Number of places going by the name.
+    pub candidates: usize,
+}
+
+struct Mention {
+    start: usize,
+    end: usize,
+    candidates: Vec<usize>,
+}
+
+/// Longest gazetteer names in the text, left to right, not overlapping.
+/// Mentions must start with a capital and single words can't be stopwords,
+/// so "said" or "the" aren't linked to places that happen to share them.
+fn find_mentions(text: &str, gazetteer: &Gazetteer) -> Vec<Mention> {
+    let words: Vec<(usize, &str)> = text.unicode_word_indices().collect();
+    let max_words = gazetteer.max_words().min(MAX_NAME_WORDS);
+    let mut mentions = Vec::new();
+    let mut i = 0;
+    while i < words.len() {
+        let (start, first) = words[i];
+        let capitalised = first.chars().next().is_some_and(char::is_uppercase);
+        let found = capitalised
+            .then(|| {
+                (1..=max_words.min(words.len() - i)).rev().find_map(|n| {
+                    let (last_start, last) = words[i + n - 1];
+                    // Names don't span sentences or lines
+                    let span = &text[start..last_start + last.len()];
+                    if span.contains(['.', '\n', ';', ':', '(', ')']) {
+                        return None;
+                    }
+                    let key = normalize_name(span);
+                    if n == 1 && (is_stopword(&key) || key.chars().count() < 3) {
+                        return None;
+                    }
+                    let candidates = gazetteer.candidates(&key);
+                    (!candidates.is_empty()).then(|| (n, last_start + last.len(), candidates.to_vec()))
+                })
+            })
+            .flatten();
+        match found {
+            Some((n, end, candidates)) => {
+                mentions.push(Mention { start, end, candidates });
+                i += n;
+            }
+            None => i += 1,
+        }
+    }
+    mentions
+}
+
+/// Scores of each mention's candidates given the current picks of the
+/// other mentions (None before the first pass).
+fn score_candidates(
+    gazetteer: &Gazetteer,
+    mentions: &[Mention],
+    picks: Option<&[usize]>,
+    countries: &HashSet<String>,
+) -> Result<Vec<Vec<f64>>, Failure> {
+    // Countries named in the text, or voted for by names that only exist
+    // in one country
+    let mut votes: HashMap<String, usize> = HashMap::new();
+    for mention in mentions {
+        let mut in_countries: Vec<String> =
+            mention.candidates.iter().map(|i| Ok(gazetteer.place(*i)?.country.clone())).collect::<Result<_, Failure>>()?;
+        in_countries.sort_unstable();
+        in_countries.dedup();
+        if let [country] = &mut in_countries[..] {
+            *votes.entry(std::mem::take(country)).or_default() += 1;
+        }
+    }
+    let max_votes = votes.values().copied().max().unwrap_or(0).max(1) as f64;
+
+    mentions
+        .iter()
+        .enumerate()
+        .map(|(m, mention)| {
+            // Places the other mentions may refer to
+            let others: HashSet<usize> = mentions
+                .iter()
+                .enumerate()
+                .filter(|(o, _)| *o != m)
+                .flat_map(|(o, other)| match picks {
+                    Some(picks) => vec![picks[o]],
+                    None => other.candidates.clone(),
+                })
+                .collect();
+            mention
+                .candidates
+                .iter()
+                .map(|&c| {
+                    let place = gazetteer.place(c)?;
+                    let country = if countries.contains(&place.country) {
+                        1.0
+                    } else {
+                        0.5 * votes.get(place.country.as_str()).copied().unwrap_or(0) as f64 / max_votes
+                    };
+                    // An ancestor named nearby, or a named place inside this one
+                    let ancestor = place.parents.iter().any(|p| others.contains(p));
+                    let descendant = others
+                        .iter()
+                        .try_fold(false, |found, o| Ok::<_, Failure>(found || gazetteer.place(*o)?.parents.contains(&c)))?;
+                    let hierarchy = match (ancestor, descendant) {
+                        (true, _) => 1.0,
+                        (false, true) => 0.7,
+                        _ => 0.0,
+                    };
+                    let population = ((place.population as f64 + 1.0).log10() / FULL_POPULATION.log10()).min(1.0);
+                    let admin = if place.admin_level.is_some() { 1.0 } else { 0.0 };
+                    Ok(BASE_SCORE
+                        + COUNTRY_WEIGHT * country
+                        + HIERARCHY_WEIGHT * hierarchy
+                        + POPULATION_WEIGHT * population
+                        + ADMIN_WEIGHT * admin)
+                })
+                .collect::<Result<Vec<f64>, Failure>>()
+        })
+        .collect()
+}
+
+fn best(scores: &[f64]) -> usize {
+    scores
+        .iter()
+        .enumerate()
+        .fold(0, |best, (i, s)| if *s > scores[best] { i } else { best })
+}
+
+/// Link the place names in `text` to gazetteer entries. `countries`
+/// (uppercased codes) are known to be what the text is about.
+pub(crate) fn link(text: &str, gazetteer: &Gazetteer, countries: &HashSet<String>) -> Result<Vec<PlaceLink>, Failure> {
+    let mentions = find_mentions(text, gazetteer);
+    let first = score_candidates(gazetteer, &mentions, None, countries)?;
+    let picks: Vec<usize> = mentions.iter().zip(&first).map(|(m, s)| m.candidates[best(s)]).collect();
+    // Second pass: hierarchy evidence from the other mentions' picks only
+    let scores = score_candidates(gazetteer, &mentions, Some(&picks), countries)?;
+
+    let mut char_pos = (0usize, 0usize);
+    let mut chars_at = |byte: usize| {
+        char_pos.1 += text[char_pos.0..byte].chars().count();
+        char_pos.0 = byte;
+        char_pos.1
+    };
+    Ok(mentions
+        .iter()
+        .zip(&scores)
+        .map(|(mention, scores)| {
+            let top = best(scores);
+            let share = scores[top] / scores.iter().sum::<f64>();
+            PlaceLink {
+                text: text[mention.start..mention.end].to_string(),
+                start: chars_at(mention.start),
+                end: chars_at(mention.end),
+                place: mention.candidates[top],
+                confidence: (0.5 * share + 0.5 * scores[top]).min(1.0),
+                candidates: mention.candidates.len(),
+            }
+        })
+        .collect())
+}
+
+/// Find place names in text and link each to its most likely gazetteer
+/// entry.
+///
+/// Ambiguous names are resolved from context: the countries the text is
+/// about (given, named in it, or implied by names that exist in only one
+/// country), other places in the text that contain or lie within a
+/// candidate (a province named alongside its district), and population.
+///
+/// Parameters
+/// ----------
+/// text : str
+///     Article text.
+/// gazetteer : Gazetteer
+///     Loaded gazetteer.
+/// countries : list[str] | None
+///     Country codes (as in the gazetteer) the text is known to be about,
+///     e.g. the crawl's target country.
+/// min_confidence : float
+///     Drop links below this confidence. Default 0.0.
+///
+/// Returns
+/// -------
+/// list[dict]
+///     In text order: ``text`` (the mention), ``start`` and ``end``
+///     (character offsets), ``id``, ``name``, ``country``,
+///     ``admin_level``, ``feature_code``, ``lat``, ``lon``,
+///     ``hierarchy`` (``(id, name, admin_level)`` ancestors, country
+///     first), ``confidence`` (0-1) and ``candidates`` (places going by
+///     the name; 1 means unambiguous).
+#[pyfunction]
+#[pyo3(signature = (text, gazetteer, countries=None, min_confidence=0.0))]
+pub fn link_places(
+    py: Python<'_>,
+    text: &str,
+    gazetteer: PyRef<'_, Gazetteer>,
+    countries: Option<Vec<String>>,
+    min_confidence: f64,
+) -> PyResult<Vec<Py<PyDict>>> {
+    let gazetteer = &*gazetteer;
+    let countries: HashSet<String> = countries.unwrap_or_default().iter().map(|c| c.trim().to_uppercase()).collect();
+    let links = unlocked(py, "link_places", || link(text, gazetteer, &countries))?.map_err(|e| gazetteer.error(e))?;
+    links
+        .into_iter()
+        .filter(|l| l.confidence >= min_confidence)
+        .map(|link| {
+            let place = gazetteer.place(link.place).map_err(|e| gazetteer.error(e))?;
+            let dict = PyDict::new_bound(py);
+            dict.set_item("text", link.text)?;
+            dict.set_item("start", link.start)?;
+            dict.set_item("end", link.end)?;
+            dict.set_item("id", &place.id)?;
+            dict.set_item("name", &place.name)?;
+            dict.set_item("country", &place.country)?;
+            dict.set_item("admin_level", place.admin_level)?;
+            dict.set_item("feature_code", &place.feature_code)?;
+            dict.set_item("lat", place.lat)?;
+            dict.set_item("lon", place.lon)?;
+            dict.set_item("hierarchy", gazetteer.hierarchy(&place).map_err(|e| gazetteer.error(e))?)?;
+            dict.set_item("confidence", link.confidence)?;
+            dict.set_item("candidates", link.candidates)?;
+            Ok(dict.unbind())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gazetteer::Filter;
+
+    fn line(fields: &[&str]) -> String {
+        let mut row: Vec<&str> = fields.to_vec();
+        row.resize(19, "");
+        row.join("\t")
+    }
+
+    fn gazetteer() -> Gazetteer {
+        let dump = [
+            line(&["1", "Mozambique", "Mozambique", "", "-18.2", "35.0", "A", "PCLI", "MZ", "", "", "", "", "", "30000000"]),
+            line(&["2", "Portugal", "Portugal", "", "39.5", "-8.0", "A", "PCLI", "PT", "", "", "", "", "", "10000000"]),
+            line(&["3", "Sofala", "Sofala", "", "-19.5", "34.7", "A", "ADM1", "MZ", "", "05", "", "", "", "0"]),
+            line(&["4", "Beira", "Beira", "", "-19.8", "34.8", "P", "PPLA", "MZ", "", "05", "", "", "", "530000"]),
+            line(&["5", "Beira", "Beira", "", "40.5", "-7.5", "A", "ADM1", "PT", "", "09", "", "", "", "0"]),
+            line(&["6", "Guarda", "Guarda", "", "40.5", "-7.3", "P", "PPLA", "PT", "", "09", "", "", "", "40000"]),
+            line(&["7", "Said", "Said", "", "0", "0", "P", "PPL", "EG", "", "", "", "", "", "100"]),
+            line(&["8", "Cabo Delgado", "Cabo Delgado", "", "-12.3", "39.3", "A", "ADM1", "MZ", "", "02", "", "", "", "0"]),
+        ]
+        .join("\n");
+        let mut g = Gazetteer::default();
+        g.add_geonames(&dump, &Filter::default()).unwrap();
+        g
+    }
+
+    fn linked(g: &Gazetteer, links: &[PlaceLink]) -> Vec<[String; 2]> {
+        links.iter().map(|l| [g.place(l.place).unwrap().id.clone(), g.place(l.place).unwrap().country.clone()]).collect()
+    }
+
+    #[test]
+    fn test_disambiguates_by_context() {
+        let g = gazetteer();
+        let links = link("Cyclone Freddy hit Beira in Sofala province, officials said.", &g, &HashSet::new()).unwrap();
+        assert_eq!(linked(&g, &links), vec![["4", "MZ"], ["3", "MZ"]]);
+        assert_eq!((links[0].start, links[0].end, links[0].candidates), (19, 24, 2));
+
+        let links = link("Fires near Guarda spread across Beira, Portugal.", &g, &HashSet::new()).unwrap();
+        assert_eq!(linked(&g, &links), vec![["6", "PT"], ["5", "PT"], ["2", "PT"]]);
+        // A given country context decides a lone mention
+        let mz: HashSet<String> = ["MZ".to_string()].into();
+        assert_eq!(linked(&g, &link("Flooding in Beira.", &g, &mz).unwrap()), vec![["4", "MZ"]]);
+    }
+
+    #[test]
+    fn test_confidence() {
+        let g = gazetteer();
+        let links = link("Displacement in Cabo Delgado and Beira, Mozambique.", &g, &HashSet::new()).unwrap();
+        assert_eq!(links[0].text, "Cabo Delgado");
+        let (unambiguous, ambiguous) = (links[0].confidence, links[1].confidence);
+        assert!(unambiguous > ambiguous, "{links:?}");
+        let lone = link("Flooding in Beira.", &g, &HashSet::new()).unwrap();
+        assert!(lone[0].confidence < ambiguous, "{lone:?}");
+    }
+
+    #[test]
+    fn test_mentions_need_capitals() {
+        let g = gazetteer();
+        assert!(link("the minister said beira was flooded", &g, &HashSet::new()).unwrap().is_empty());
+        // Stopwords aren't linked even when capitalised
+        assert!(link("Said the minister.", &g, &HashSet::new()).unwrap().is_empty());
+        assert!(link("", &g, &HashSet::new()).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,173 @@
+//! Politeness keys — the bucket a URL counts against for rate limiting.
+//!
+//! Throttling per registrable domain keeps `www.` / `news.` / `m.`
+//! subdomains of one site from multiplying its fetch rate. Large shared
+//! platforms are the exception: each blog or account there is its own
+//! origin, so they are bucketed by full host or by host plus first path
+//! segment. The scheduler and the frontier both use this key.
+
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use url::{Host, Url};
+
+use crate::public_suffix::registrable_domain_of_host;
+use crate::errors::{config_error, guarded, Failure};
+
+/// Bucket granularity for a registrable domain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Granularity {
+    Domain,
+    Host,
+    /// Host plus the first path segment (`medium.com/@unicef`).
+    PathPrefix,
+}
+
+impl Granularity {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name.trim().to_lowercase().as_str() {
+            "domain" => Ok(Granularity::Domain),
+            "host" => Ok(Granularity::Host),
+            "path" => Ok(Granularity::PathPrefix),
+            other => Err(format!("unknown politeness granularity {other:?}")),
+        }
+    }
+}
+
+static DEFAULT_PLATFORMS: &[(&str, Granularity)] = &[
+    ("google.com", Granularity::Host),
+    ("wordpress.com", Granularity::Host),
+    ("substack.com", Granularity::Host),
+    ("medium.com", Granularity::PathPrefix),
+    ("facebook.com", Granularity::PathPrefix),
+    ("x.com", Granularity::PathPrefix),
+    ("twitter.com", Granularity::PathPrefix),
+];
+
+fn default_platforms() -> HashMap<String, Granularity> {
+    DEFAULT_PLATFORMS
+        .iter()
+        .map(|(domain, g)| (domain.to_string(), *g))
+        .collect()
+}
+
+static PLATFORMS: Lazy<RwLock<HashMap<String, Granularity>>> =
+    Lazy::new(|| RwLock::new(default_platforms()));
+
+fn key_with(platforms: &HashMap<String, Granularity>, url: &Url) -> Option<String> {
+    let host = url.host_str()?.trim_end_matches('.').to_lowercase();
+    // IP addresses and hosts without a public suffix bucket by host
+    let domain = match url.host() {
+        Some(Host::Domain(_)) => registrable_domain_of_host(&host),
+        _ => None,
+    };
+    let domain = match domain {
+        Some(d) => d,
+        None => return Some(host),
+    };
+    Some(match platforms.get(&domain).copied().unwrap_or(Granularity::Domain) {
+        Granularity::Domain => domain,
+        Granularity::Host => host,
+        Granularity::PathPrefix => match url.path_segments().and_then(|mut s| s.next()) {
+            Some(first) if !first.is_empty() => format!("{host}/{}", first.to_lowercase()),
+            _ => host,
+        },
+    })
+}
+
+/// Number of platform rules, for `runtime_info`.
+pub(crate) fn inventory() -> Vec<(&'static str, usize)> {
+    vec![("politeness_platforms", PLATFORMS.read().unwrap_or_else(|e| e.into_inner()).len())]
+}
+
+/// Politeness key of a URL, or `None` if it has no host.
+pub(crate) fn politeness_key_of(url_str: &str) -> Option<String> {
+    let url = Url::parse(url_str.trim()).ok()?;
+    let platforms = PLATFORMS.read().unwrap_or_else(|e| e.into_inner());
+    key_with(&platforms, &url)
+}
+
+/// Rate-limiting bucket for a URL.
+///
+/// Returns
+/// -------
+/// str | None
+///     The registrable domain (``"reliefweb.int"``) by default; the full
+///     host or host plus first path segment (``"medium.com/@unicef"``)
+///     for configured platforms; the host for IP addresses. None if the
+///     URL has no host.
+#[pyfunction]
+pub fn politeness_key(url: &str) -> PyResult<Option<String>> {
+    guarded("politeness_key", || Ok(politeness_key_of(url)))
+}
+
+/// Configure per-platform politeness granularity.
+///
+/// Parameters
+/// ----------
+/// platforms : dict[str, str] | None
+///     Registrable domain → ``"domain"``, ``"host"`` or ``"path"``.
+///     Merged into the current table; ``None`` restores the defaults.
+///
+/// Raises
+/// ------
+/// ConfigError
+///     If a granularity is unknown (``reason`` ``"invalid_value"``); the
+///     table is left unchanged.
+#[pyfunction]
+#[pyo3(signature = (platforms=None))]
+pub fn configure_politeness(platforms: Option<HashMap<String, String>>) -> PyResult<()> {
+    guarded("configure_politeness", || {
+        let parsed = match platforms {
+            None => None,
+            Some(map) => Some(
+                map.into_iter()
+                    .map(|(domain, g)| Ok((domain.trim().to_lowercase(), Granularity::parse(&g)?)))
+                    .collect::<Result<Vec<_>, String>>()
+                    .map_err(|e| config_error(None, Failure::new("invalid_value", e)))?,
+            ),
+        };
+        let mut table = PLATFORMS.write().unwrap_or_else(|e| e.into_inner());
+        match parsed {
+            None => *table = default_platforms(),
+            Some(entries) => table.extend(entries),
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(url: &str) -> Option<String> {
+        key_with(&default_platforms(), &Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn test_registrable_domain_default() {
+        assert_eq!(key("https://www.reliefweb.int/report/1").as_deref(), Some("reliefweb.int"));
+        assert_eq!(key("https://feeds.bbc.co.uk/news").as_deref(), Some("bbc.co.uk"));
+        assert_eq!(key("http://10.0.0.5:8080/x").as_deref(), Some("10.0.0.5"));
+    }
+
+    #[test]
+    fn test_platform_granularity() {
+        assert_eq!(key("https://news.google.com/rss").as_deref(), Some("news.google.com"));
+        assert_eq!(key("https://medium.com/@unicef/story-1").as_deref(), Some("medium.com/@unicef"));
+        assert_eq!(key("https://medium.com/").as_deref(), Some("medium.com"));
+    }
+
+    #[test]
+    fn test_invalid_granularity() {
+        assert!(Granularity::parse("subdomain").is_err());
+        assert_eq!(politeness_key("not a url").unwrap(), None);
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let platforms = HashMap::from([("example.org".to_string(), "subdomain".to_string())]);
+            let err = configure_politeness(Some(platforms)).unwrap_err();
+            assert!(err.is_instance_of::<crate::errors::ConfigError>(py));
+        });
+    }
+}
@@ -0,0 +1,204 @@
+//! Opt-in profiling of the native layer.
+//!
+//! `get_metrics` histograms say how long each call took; they don't say
+//! which pipeline stage the time went to, or how much each stage found.
+//! With profiling on, every GIL-released call records its wall time
+//! under its entry name, and every pipeline stage records wall time and
+//! match count (figure keys, labels, places...) under `pipeline.<stage>`.
+//! A test harness can enable it, run a fixed corpus and compare
+//! `profiling_report()` against a baseline to catch regressions.
+//!
+//! Off by default: when disabled, each stage costs one relaxed atomic
+//! load.
+
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::errors::guarded;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Default)]
+struct Totals {
+    calls: u64,
+    nanos: u64,
+    matches: u64,
+}
+
+#[derive(Debug, Default)]
+struct Profile {
+    /// Since profiling was last enabled or reset.
+    started: Option<Instant>,
+    calls: BTreeMap<String, Totals>,
+    stages: BTreeMap<&'static str, Totals>,
+}
+
+static PROFILE: Lazy<Mutex<Profile>> = Lazy::new(|| Mutex::new(Profile::default()));
+
+pub(crate) fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+fn add(totals: &mut Totals, nanos: u64, matches: u64) {
+    totals.calls += 1;
+    totals.nanos += nanos;
+    totals.matches += matches;
+}
+
+fn elapsed_nanos(start: Instant) -> u64 {
+    start.elapsed().as_nanos().min(u64::MAX as u128) as u64
+}
+
+/// Run `f`, recording its wall time under the entry `name` when enabled.
+pub(crate) fn call<T>(name: &str, f: impl FnOnce() -> T) -> T {
+    if !enabled() {
+        return f();
+    }
+    let start = Instant::now();
+    let value = f();
+    let nanos = elapsed_nanos(start);
+    let mut profile = PROFILE.lock().unwrap_or_else(|e| e.into_inner());
+    match profile.calls.get_mut(name) {
+        Some(totals) => add(totals, nanos, 0),
+        None => {
+            let mut totals = Totals::default();
+            add(&mut totals, nanos, 0);
+            profile.calls.insert(name.to_string(), totals);
+        }
+    }
+    value
+}
+
+/// Run pipeline stage `name`, recording its wall time and `matches` of
+/// its result when enabled.
+pub(crate) fn stage<T>(name: &'static str, matches: impl FnOnce(&T) -> usize, f: impl FnOnce() -> T) -> T {
+    if !enabled() {
+        return f();
+    }
+    let start = Instant::now();
+    let value = f();
+    let nanos = elapsed_nanos(start);
+    let found = matches(&value) as u64;
+    let mut profile = PROFILE.lock().unwrap_or_else(|e| e.into_inner());
+    add(profile.stages.entry(name).or_default(), nanos, found);
+    value
+}
+
+/// Match count of an optional result: 1 when present.
+pub(crate) fn found<T>(value: &Option<T>) -> usize {
+    usize::from(value.is_some())
+}
+
+fn reset(profile: &mut Profile) {
+    profile.calls.clear();
+    profile.stages.clear();
+    profile.started = enabled().then(Instant::now);
+}
+
+/// Turn profiling on or off, process-wide.
+///
+/// Enabling clears the previous report. Profiling adds a lock per call
+/// and per pipeline stage, so leave it off in production.
+///
+/// Parameters
+/// ----------
+/// enabled : bool
+///     Default True.
+#[pyfunction]
+#[pyo3(signature = (enabled=true))]
+pub fn enable_profiling(enabled: bool) -> PyResult<()> {
+    guarded("enable_profiling", || {
+        let was = ENABLED.swap(enabled, Ordering::Relaxed);
+        if enabled && !was {
+            reset(&mut PROFILE.lock().unwrap_or_else(|e| e.into_inner()));
+        }
+        Ok(())
+    })
+}
+
+/// Report of the time spent since profiling was enabled.
+///
+/// Parameters
+/// ----------
+/// reset : bool
+///     Clear the totals after reading them. Default False.
+///
+/// Returns
+/// -------
+/// dict
+///     ``enabled`` (bool); ``elapsed_seconds`` (wall time since enabled
+///     or reset, or None); ``calls`` (entry name → ``{"calls", "seconds",
+///     "mean_seconds"}`` for every GIL-released call, e.g.
+///     ``Pipeline.run``); ``stages`` (``pipeline.<stage>`` → the same
+///     plus ``matches``, the results the stage found: figure keys,
+///     labels, storms, or 1 per detected language / date / actor /
+///     area).
+#[pyfunction]
+#[pyo3(signature = (reset=false))]
+pub fn profiling_report(py: Python<'_>, reset: bool) -> PyResult<Py<PyDict>> {
+    guarded("profiling_report", || {
+        let mut profile = PROFILE.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = |totals: &Totals, with_matches: bool| -> PyResult<Bound<'_, PyDict>> {
+            let dict = PyDict::new_bound(py);
+            let seconds = totals.nanos as f64 * 1e-9;
+            dict.set_item("calls", totals.calls)?;
+            dict.set_item("seconds", seconds)?;
+            dict.set_item("mean_seconds", seconds / totals.calls.max(1) as f64)?;
+            if with_matches {
+                dict.set_item("matches", totals.matches)?;
+            }
+            Ok(dict)
+        };
+        let report = PyDict::new_bound(py);
+        report.set_item("enabled", enabled())?;
+        report.set_item("elapsed_seconds", profile.started.map(|s| s.elapsed().as_secs_f64()))?;
+        let calls = PyDict::new_bound(py);
+        for (name, totals) in &profile.calls {
+            calls.set_item(name, entry(totals, false)?)?;
+        }
+        report.set_item("calls", calls)?;
+        let stages = PyDict::new_bound(py);
+        for (name, totals) in &profile.stages {
+            stages.set_item(*name, entry(totals, true)?)?;
+        }
+        report.set_item("stages", stages)?;
+        if reset {
+            self::reset(&mut profile);
+        }
+        Ok(report.unbind())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiling_records_when_enabled() {
+        // Only this test toggles profiling; the names are its own.
+        assert_eq!(call("profiling_test_off", || 1), 1);
+        enable_profiling(true).unwrap();
+        call("profiling_test", || ());
+        call("profiling_test", || ());
+        stage("profiling_test_stage", Vec::len, || vec![1, 2, 3]);
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let report = profiling_report(py, true).unwrap().into_bound(py);
+            let calls = report.get_item("calls").unwrap().unwrap();
+            assert_eq!(calls.get_item("profiling_test").unwrap().get_item("calls").unwrap().extract::<u64>().unwrap(), 2);
+            assert!(calls.get_item("profiling_test_off").is_err());
+            let stages = report.get_item("stages").unwrap().unwrap();
+            let stage = stages.get_item("profiling_test_stage").unwrap();
+            assert_eq!(stage.get_item("matches").unwrap().extract::<u64>().unwrap(), 3);
+            enable_profiling(false).unwrap();
+            let report = profiling_report(py, false).unwrap().into_bound(py);
+            assert!(!report.get_item("enabled").unwrap().unwrap().extract::<bool>().unwrap());
+            assert!(report.get_item("calls").unwrap().unwrap().downcast::<PyDict>().unwrap().get_item("profiling_test").unwrap().is_none());
+        });
+    }
+}
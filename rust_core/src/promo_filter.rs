@@ -0,0 +1,218 @@
+//! Press release and advertorial filter.
+//!
+//! Corporate press releases, sponsored content and donation pages mention
+//! disasters without reporting on them; kept in the evidence base they
+//! inflate counts and crowd out field reporting. Each category has a set
+//! of weighted markers (wire-service credits, stock tickers, "sponsored
+//! content", donation calls, ...); a page is flagged for the category
+//! whose matched markers reach `FLAG_SCORE`.
+
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+use regex::Regex;
+use url::Url;
+
+use crate::errors::unlocked;
+use crate::figure_extraction::figures;
+
+/// Category score at which a page is flagged.
+const FLAG_SCORE: f64 = 0.6;
+/// Figure keys that show a page reports on the situation itself.
+const SITUATION_FIGURES: &[&str] = &["deaths", "displaced", "injured", "missing", "people_affected"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Category {
+    PressRelease,
+    Advertorial,
+    Fundraising,
+}
+
+impl Category {
+    fn as_str(self) -> &'static str {
+        match self {
+            Category::PressRelease => "press_release",
+            Category::Advertorial => "advertorial",
+            Category::Fundraising => "fundraising",
+        }
+    }
+}
+
+/// In discriminant order, indexing the per-category scores.
+const CATEGORIES: [Category; 3] = [Category::PressRelease, Category::Advertorial, Category::Fundraising];
+
+struct Marker {
+    category: Category,
+    reason: &'static str,
+    weight: f64,
+    pattern: Regex,
+}
+
+fn marker(category: Category, reason: &'static str, weight: f64, pattern: &str) -> Marker {
+    Marker { category, reason, weight, pattern: Regex::new(pattern).unwrap() }
+}
+
+static TEXT_MARKERS: Lazy<Vec<Marker>> = Lazy::new(|| {
+    use Category::*;
+    vec![
+        marker(PressRelease, "wire_service", 0.6, r"(?i)\b(?:PR ?Newswire|Business ?Wire|GlobeNewswire|ACCESSWIRE|EIN Presswire|PRWeb)\b"),
+        marker(PressRelease, "stock_ticker", 0.5, r"\((?:NYSE|NASDAQ|Nasdaq|LSE|TSX|ASX|OTC[A-Z]*)\s*:\s*[A-Z][A-Z.]{0,5}\)"),
+        marker(PressRelease, "safe_harbor", 0.5, r"(?i)\bforward-looking statements\b"),
+        marker(PressRelease, "release_header", 0.4, r"(?i)\b(?:for immediate release|embargoed until)\b"),
+        marker(PressRelease, "announcement", 0.3, r"(?i)\b(?:today announced|announced today|is (?:pleased|proud|excited|thrilled) to announce)\b"),
+        marker(PressRelease, "media_contact", 0.3, r"(?i)\b(?:media|press) (?:contacts?|inquiries|enquiries)\b"),
+        marker(PressRelease, "about_company", 0.3, r"(?m)^\s*About\s+[A-Z][^\n]{0,60}\b(?:Inc|Ltd|LLC|Corp|Corporation|PLC|plc|Group|Company|S\.A)\.?\s*$"),
+        marker(PressRelease, "release_end", 0.2, r"(?m)^\s*(?:###|[-–—]\s*(?:ENDS|ends)\s*[-–—])\s*$"),
+        marker(Advertorial, "sponsored_label", 0.6, r"(?i)\b(?:sponsored (?:content|post|article)|paid (?:partnership|post|content)|advertorial|promoted content|partner content)\b"),
+        marker(Advertorial, "affiliate", 0.4, r"(?i)\b(?:affiliate (?:links?|commission)|we may earn a commission)\b"),
+        marker(Advertorial, "call_to_buy", 0.3, r"(?i)\b(?:buy now|shop now|order now|add to cart|get yours today)\b"),
+        marker(Advertorial, "discount_offer", 0.3, r"(?i)(?:\b\d{1,2}% off\b|\blimited[- ]time offer\b|\b(?:promo|discount) code\b|\bfree shipping\b|\bfree trial\b)"),
+        marker(Advertorial, "superlatives", 0.2, r"(?i)\b(?:best-in-class|industry-leading|world-class|cutting-edge|game-changing)\b"),
+        marker(Fundraising, "donation_call", 0.4, r"(?i)\b(?:donate (?:now|today)|make a (?:donation|gift)|give (?:now|today)|please (?:give|donate))\b"),
+        marker(Fundraising, "gift_impact", 0.3, r"(?i)(?:[$€£]\s?\d[\d,]*|\b\d[\d,]*\s?(?:USD|EUR|GBP|dollars|euros|pounds))\s+(?:can|could|will)\s+(?:provide|buy|feed|help|give|supply)\b"),
+        marker(Fundraising, "tax_deductible", 0.3, r"(?i)\b(?:tax[- ]deductible|gift aid)\b"),
+        marker(Fundraising, "matched_giving", 0.2, r"(?i)\b(?:match(?:ed|ing)? (?:your )?(?:gifts?|donations?)|double your (?:gift|donation|impact))\b"),
+        marker(Fundraising, "recurring_giving", 0.2, r"(?i)\b(?:monthly (?:donor|gift|giving)|become a (?:monthly )?(?:donor|supporter))\b"),
+    ]
+});
+
+static URL_MARKERS: Lazy<Vec<Marker>> = Lazy::new(|| {
+    use Category::*;
+    vec![
+        marker(PressRelease, "wire_service_url", 0.6, r"(?i)^(?:www\.)?(?:prnewswire|businesswire|globenewswire|accesswire|einpresswire|prweb)\.com/"),
+        marker(PressRelease, "press_release_url", 0.2, r"(?i)/press[-_]?releases?(?:/|$)"),
+        marker(Advertorial, "sponsored_url", 0.4, r"(?i)/(?:sponsored|partner-content|advertorial)(?:/|$)"),
+        marker(Fundraising, "donation_url", 0.3, r"(?i)/(?:donate|donation|give|appeal)(?:/|$|-)"),
+    ]
+});
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Assessment {
+    /// The flagged category, if any.
+    pub category: Option<Category>,
+    /// Score of the highest-scoring category, 0-1.
+    pub score: f64,
+    pub reasons: Vec<&'static str>,
+}
+
+/// Marker counts, for `runtime_info`.
+pub(crate) fn inventory() -> Vec<(&'static str, usize)> {
+    vec![("promo_text_markers", TEXT_MARKERS.len()), ("promo_url_markers", URL_MARKERS.len())]
+}
+
+pub(crate) fn assess(title: &str, text: &str, url: Option<&str>) -> Assessment {
+    let full = if title.is_empty() { text.to_string() } else { format!("{title}\n{text}") };
+    // Host and path, so URL patterns can anchor on the host
+    let location = url
+        .and_then(|u| Url::parse(u.trim()).ok())
+        .map(|u| format!("{}{}", u.host_str().unwrap_or_default(), u.path()));
+
+    let mut scores = [0.0f64; 3];
+    let mut reasons = Vec::new();
+    let text_hits = TEXT_MARKERS.iter().filter(|m| m.pattern.is_match(&full));
+    let url_hits = URL_MARKERS
+        .iter()
+        .filter(|m| location.as_deref().is_some_and(|l| m.pattern.is_match(l)));
+    for m in text_hits.chain(url_hits) {
+        scores[m.category as usize] += m.weight;
+        reasons.push(m.reason);
+    }
+    for score in &mut scores {
+        *score = score.min(1.0);
+    }
+    // Appeals that also report casualties or displacement are evidence
+    let fundraising = &mut scores[Category::Fundraising as usize];
+    if *fundraising > 0.0 && figures(&full).keys().any(|k| SITUATION_FIGURES.contains(&k.as_str())) {
+        *fundraising /= 2.0;
+        reasons.push("reports_situation");
+    }
+
+    let (best, score) = scores
+        .iter()
+        .enumerate()
+        .fold((0, 0.0f64), |best, (i, s)| if *s > best.1 { (i, *s) } else { best });
+    Assessment {
+        category: (score >= FLAG_SCORE).then_some(CATEGORIES[best]),
+        score,
+        reasons,
+    }
+}
+
+/// Flag corporate press releases, advertorials and fundraising-only pages.
+///
+/// Parameters
+/// ----------
+/// text : str
+///     Extracted page text.
+/// title : str
+///     Page title. Default empty.
+/// url : str | None
+///     Page URL; wire-service hosts and ``/press-release/``,
+///     ``/sponsored/`` or ``/donate/`` paths add evidence.
+///
+/// Returns
+/// -------
+/// tuple[str | None, float, list[str]]
+///     ``(category, score, reasons)``: category is ``"press_release"``,
+///     ``"advertorial"``, ``"fundraising"`` or None when the page looks
+///     like reporting; score is the strongest category's evidence (0-1,
+///     flagged from 0.6); reasons name the markers found, e.g.
+///     ``"wire_service"``, ``"stock_ticker"``, ``"sponsored_label"``,
+///     ``"donation_call"``. Donation pages that also report casualties
+///     or displacement score half (reason ``"reports_situation"``).
+#[pyfunction]
+#[pyo3(signature = (text, title="", url=None))]
+pub fn classify_promotional(
+    py: Python<'_>,
+    text: &str,
+    title: &str,
+    url: Option<&str>,
+) -> PyResult<(Option<&'static str>, f64, Vec<&'static str>)> {
+    let assessment = unlocked(py, "classify_promotional", || assess(title, text, url))?;
+    Ok((assessment.category.map(Category::as_str), assessment.score, assessment.reasons))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_corporate_press_release() {
+        let text = "FOR IMMEDIATE RELEASE\n\nNEW YORK, March 14, 2025 /PRNewswire/ -- Acme Logistics \
+                    (NYSE: ACME) today announced a donation of trucks to cyclone relief efforts.\n\n\
+                    About Acme Logistics Inc.\nAcme is an industry-leading provider.\n\n\
+                    This release contains forward-looking statements.\n\nMedia contact: press@acme.example\n###";
+        let a = assess("Acme supports cyclone relief", text, Some("https://www.prnewswire.com/news-releases/acme-123.html"));
+        assert_eq!(a.category, Some(Category::PressRelease));
+        assert_eq!(a.score, 1.0);
+        for reason in ["wire_service", "stock_ticker", "safe_harbor", "about_company", "release_end", "wire_service_url"] {
+            assert!(a.reasons.contains(&reason), "{reason}: {:?}", a.reasons);
+        }
+    }
+
+    #[test]
+    fn test_advertorial_and_fundraising() {
+        let ad = assess("", "Sponsored content. Stay safe in storm season: shop now and get 20% off our solar lanterns.", None);
+        assert_eq!(ad.category, Some(Category::Advertorial));
+
+        let appeal = "Donate now. $50 can provide a family with clean water for a month. \
+                      Your gift is tax-deductible and will be matched: double your impact today.";
+        let a = assess("Cyclone appeal", appeal, Some("https://charity.example/donate/cyclone"));
+        assert_eq!(a.category, Some(Category::Fundraising));
+        // The same appeal reporting the toll stays in
+        let reporting = format!("The cyclone killed 120 people and displaced 48,000. {appeal}");
+        let a = assess("Cyclone appeal", &reporting, None);
+        assert_eq!(a.category, None);
+        assert!(a.reasons.contains(&"reports_situation"));
+    }
+
+    #[test]
+    fn test_reporting_not_flagged() {
+        let text = "Tropical Cyclone Freddy made landfall near Quelimane. OCHA said 48,000 people were \
+                    displaced; WFP is distributing food and UNICEF is supporting cholera response.";
+        let a = assess("Cyclone Freddy: situation report", text, Some("https://reliefweb.int/report/mozambique/x"));
+        assert_eq!(a, Assessment::default());
+        // A single promotional marker isn't enough
+        let a = assess("", "The ministry is pleased to announce new shelters in Beira.", None);
+        assert!(a.category.is_none() && a.score > 0.0);
+    }
+}
@@ -0,0 +1,314 @@
+//! Registrable domain extraction — public suffix list lookup.
+//!
+//! Groups hosts by their registrable domain ("eTLD+1") using the Mozilla
+//! public suffix list embedded from `config/public_suffix_list.dat`, so
+//! `news.example.co.mz` groups under `example.co.mz` and each
+//! `*.blogspot.com` blog is its own source.
+//!
+//! A newer list can be compiled into a memory-mapped table
+//! (`compile_public_suffix_list`) and switched to at runtime
+//! (`load_public_suffix_table`); workers opening the same table share its
+//! pages rather than each parsing the list.
+
+use once_cell::sync::Lazy;
+#[cfg(feature = "python")]
+use pyo3::exceptions::PyOSError;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::{Arc, RwLock};
+#[cfg(feature = "python")]
+use url::{Host, Url};
+
+#[cfg(feature = "python")]
+use crate::errors::{guarded, unlocked};
+#[cfg(feature = "python")]
+use crate::mapped::write_table;
+use crate::mapped::Table;
+
+static PSL_DATA: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../config/public_suffix_list.dat"
+));
+
+/// Parsed suffix rules, stored in ASCII (punycode) form to match `url` hosts.
+struct SuffixRules {
+    exact: HashSet<String>,
+    /// `*.ck` is stored as `ck`.
+    wildcard: HashSet<String>,
+    /// `!www.ck` is stored as `www.ck`.
+    exception: HashSet<String>,
+}
+
+/// Key prefixes of a mapped suffix table, one per rule kind.
+const EXACT: char = '=';
+const WILDCARD: char = '*';
+const EXCEPTION: char = '!';
+
+impl SuffixRules {
+    fn parse(text: &str) -> Self {
+        let mut rules = SuffixRules {
+            exact: HashSet::new(),
+            wildcard: HashSet::new(),
+            exception: HashSet::new(),
+        };
+        for line in text.lines() {
+            let rule = match line.split_whitespace().next() {
+                Some(r) if !r.starts_with("//") => r,
+                _ => continue,
+            };
+            if let Some(rest) = rule.strip_prefix('!') {
+                rules.exception.insert(to_ascii(rest));
+            } else if let Some(rest) = rule.strip_prefix("*.") {
+                rules.wildcard.insert(to_ascii(rest));
+            } else {
+                rules.exact.insert(to_ascii(rule));
+            }
+        }
+        rules
+    }
+
+    /// Rules as mapped-table keys: the kind prefix, then the rule.
+    fn keys(&self) -> BTreeMap<Vec<u8>, Vec<u8>> {
+        [(EXACT, &self.exact), (WILDCARD, &self.wildcard), (EXCEPTION, &self.exception)]
+            .into_iter()
+            .flat_map(|(kind, rules)| rules.iter().map(move |r| (format!("{kind}{r}").into_bytes(), Vec::new())))
+            .collect()
+    }
+}
+
+static RULES: Lazy<SuffixRules> = Lazy::new(|| SuffixRules::parse(PSL_DATA));
+
+/// A compiled table set by `load_public_suffix_table`, consulted instead
+/// of the embedded list.
+static MAPPED: RwLock<Option<Arc<Table>>> = RwLock::new(None);
+
+enum Rules<'a> {
+    Embedded(&'a SuffixRules),
+    Mapped(&'a Table),
+}
+
+impl Rules<'_> {
+    fn has(&self, kind: char, candidate: &str) -> bool {
+        match self {
+            Rules::Embedded(rules) => match kind {
+                EXACT => rules.exact.contains(candidate),
+                WILDCARD => rules.wildcard.contains(candidate),
+                _ => rules.exception.contains(candidate),
+            },
+            Rules::Mapped(table) => table.get(format!("{kind}{candidate}").as_bytes()).is_some(),
+        }
+    }
+}
+
+fn to_ascii(rule: &str) -> String {
+    idna::domain_to_ascii(rule).unwrap_or_else(|_| rule.to_lowercase())
+}
+
+/// Where public suffix rules come from and how many there are, for
+/// `runtime_info`.
+pub(crate) fn rule_source() -> (&'static str, usize) {
+    match MAPPED.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        Some(table) => ("mapped", table.len()),
+        None => ("embedded", RULES.exact.len() + RULES.wildcard.len() + RULES.exception.len()),
+    }
+}
+
+/// Index of the first label of the public suffix within `labels`.
+///
+/// Follows the PSL algorithm: exception rules win, otherwise the longest
+/// matching rule, otherwise the implicit `*` rule (the last label).
+fn suffix_start(labels: &[&str]) -> usize {
+    let mapped = MAPPED.read().unwrap_or_else(|e| e.into_inner()).clone();
+    match &mapped {
+        Some(table) => suffix_start_in(&Rules::Mapped(table), labels),
+        None => suffix_start_in(&Rules::Embedded(&RULES), labels),
+    }
+}
+
+fn suffix_start_in(rules: &Rules<'_>, labels: &[&str]) -> usize {
+    for i in 0..labels.len() {
+        let candidate = labels[i..].join(".");
+        if rules.has(EXCEPTION, &candidate) {
+            return i + 1;
+        }
+        if rules.has(EXACT, &candidate) {
+            return i;
+        }
+        if i + 1 < labels.len() && rules.has(WILDCARD, &labels[i + 1..].join(".")) {
+            return i;
+        }
+    }
+    labels.len().saturating_sub(1)
+}
+
+/// Registrable domain for a bare (ASCII, lowercase) host name.
+///
+/// Returns `None` when the host is itself a public suffix (e.g. `co.uk`).
+pub(crate) fn registrable_domain_of_host(host: &str) -> Option<String> {
+    let host = host.trim_end_matches('.');
+    let labels: Vec<&str> = host.split('.').filter(|l| !l.is_empty()).collect();
+    let start = suffix_start(&labels);
+    if start == 0 {
+        return None;
+    }
+    Some(labels[start - 1..].join("."))
+}
+
+/// Return the registrable domain (eTLD+1) of a URL or bare host.
+///
+/// Parameters
+/// ----------
+/// url_str : str
+///     An absolute URL, or a bare host name such as ``news.example.co.mz``.
+///
+/// Returns
+/// -------
+/// str | None
+///     e.g. ``"example.co.mz"``. IP hosts are returned unchanged; ``None``
+///     if the input has no host or the host is itself a public suffix.
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn registrable_domain(url_str: &str) -> PyResult<Option<String>> {
+    guarded("registrable_domain", || Ok(registrable_domain_of_url(url_str)))
+}
+
+/// Registrable domain of a URL or bare host; IP hosts are returned as is.
+#[cfg(feature = "python")]
+fn registrable_domain_of_url(url_str: &str) -> Option<String> {
+    let raw = url_str.trim();
+    let parsed = Url::parse(raw)
+        .ok()
+        .filter(|u| u.has_host())
+        .or_else(|| Url::parse(&format!("http://{raw}")).ok())?;
+    match parsed.host()? {
+        Host::Domain(domain) => registrable_domain_of_host(domain),
+        ip => Some(ip.to_string()),
+    }
+}
+
+/// Compile a public suffix list into a memory-mapped table.
+///
+/// Parameters
+/// ----------
+/// path : str
+///     Table file to write, for ``load_public_suffix_table``.
+/// source : str | None
+///     ``public_suffix_list.dat`` to compile. Default: the embedded list.
+///
+/// Returns
+/// -------
+/// int
+///     Rules compiled.
+///
+/// Raises
+/// ------
+/// OSError
+///     If the source cannot be read or the table written.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (path, source=None))]
+pub fn compile_public_suffix_list(py: Python<'_>, path: &str, source: Option<&str>) -> PyResult<usize> {
+    let text = match source {
+        Some(source) => std::fs::read_to_string(source)
+            .map_err(|e| PyOSError::new_err(format!("cannot read {source}: {e}")))?,
+        None => PSL_DATA.to_string(),
+    };
+    unlocked(py, "compile_public_suffix_list", || write_table(path, &SuffixRules::parse(&text).keys()))?
+        .map_err(PyOSError::new_err)
+}
+
+/// Use a compiled suffix table for every registrable-domain lookup.
+///
+/// The table is memory-mapped, so workers loading the same file share
+/// it. Applies process-wide: ``registrable_domain``, URL keys, source
+/// reputation and politeness grouping all follow it.
+///
+/// Parameters
+/// ----------
+/// path : str | None
+///     File written by ``compile_public_suffix_list``. ``None`` restores
+///     the embedded list.
+///
+/// Returns
+/// -------
+/// int
+///     Rules in the table now in use.
+///
+/// Raises
+/// ------
+/// OSError
+///     If the file cannot be opened or isn't a mapped table; the current
+///     rules stay in use.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (path=None))]
+pub fn load_public_suffix_table(path: Option<&str>) -> PyResult<usize> {
+    guarded("load_public_suffix_table", || {
+        let table = path.map(Table::open).transpose()?.map(Arc::new);
+        let count = table.as_ref().map_or_else(|| RULES.keys().len(), |t| t.len());
+        *MAPPED.write().unwrap_or_else(|e| e.into_inner()) = table;
+        Ok(count)
+    })
+}
+
+#[cfg(all(test, feature = "python"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multi_label_suffix() {
+        assert_eq!(
+            registrable_domain_of_url("https://news.example.co.mz/article/1"),
+            Some("example.co.mz".to_string())
+        );
+    }
+
+    #[test]
+    fn test_private_suffix() {
+        assert_eq!(
+            registrable_domain_of_url("https://reliefblog.blogspot.com/2025/03/post.html"),
+            Some("reliefblog.blogspot.com".to_string())
+        );
+    }
+
+    #[test]
+    fn test_bare_host() {
+        assert_eq!(registrable_domain_of_url("www.reliefweb.int"), Some("reliefweb.int".to_string()));
+    }
+
+    #[test]
+    fn test_suffix_only() {
+        assert_eq!(registrable_domain_of_url("co.uk"), None);
+    }
+
+    #[test]
+    fn test_wildcard_and_exception() {
+        assert_eq!(registrable_domain_of_host("a.b.ck"), Some("a.b.ck".to_string()));
+        assert_eq!(registrable_domain_of_host("www.ck"), Some("www.ck".to_string()));
+    }
+
+    #[test]
+    fn test_ip_host() {
+        assert_eq!(registrable_domain_of_url("http://10.0.0.1/feed"), Some("10.0.0.1".to_string()));
+    }
+
+    #[test]
+    fn test_mapped_rules() {
+        let path = std::env::temp_dir().join(format!("moltis_psl_{}.map", std::process::id()));
+        let path = path.to_str().unwrap();
+        let rules = SuffixRules::parse("// comment\nmz\nco.mz\n*.ck\n!www.ck\nрф\n");
+        write_table(path, &rules.keys()).unwrap();
+        let table = Table::open(path).unwrap();
+        let mapped = Rules::Mapped(&table);
+        let start = |host: &str| {
+            let labels: Vec<&str> = host.split('.').collect();
+            (suffix_start_in(&mapped, &labels), suffix_start_in(&Rules::Embedded(&rules), &labels))
+        };
+        assert_eq!(start("news.example.co.mz"), (2, 2));
+        assert_eq!(start("a.b.ck"), (1, 1));
+        assert_eq!(start("www.ck"), (1, 1));
+        assert_eq!(start("xn--80aswg.xn--p1ai"), (1, 1));
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,250 @@
+//! Per-host rate limiting — token buckets with robots.txt crawl delays.
+//!
+//! Each politeness key (`politeness_key`) gets a bucket refilled at
+//! `rate` requests per second and holding up to `burst` tokens. A
+//! robots.txt `Crawl-delay` for a host caps its rate at one request per
+//! delay, with no burst. Jitter holds an emptied bucket for a random
+//! extra share of its interval, so fetches from many workers don't fall
+//! into lockstep against one server.
+//!
+//! Decisions never block: `acquire` takes a token or says no, and
+//! `next_available` says when to ask again, so an asyncio scheduler can
+//! sleep exactly that long instead of contending on a Python lock.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::collections::HashMap;
+
+use crate::frontier::now_secs;
+use crate::politeness::politeness_key_of;
+use crate::errors::guarded;
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    /// When `tokens` was last brought up to date.
+    updated: f64,
+    /// Jitter hold: no token is handed out before this.
+    not_before: f64,
+}
+
+/// Token-bucket rate limiter keyed by host.
+///
+/// Parameters
+/// ----------
+/// rate : float
+///     Requests per second per host. Default 1.0.
+/// burst : int
+///     Tokens a host can save up while idle. Default 1 (no bursts).
+/// jitter : float
+///     Extra random hold after a host's bucket empties, as a share of its
+///     interval: 0.25 waits up to 25% longer. Default 0.0.
+/// seed : int | None
+///     Seed for the jitter, for reproducible schedules.
+///
+/// Hosts are bucketed by politeness key: ``news.example.org`` and
+/// ``www.example.org/page`` share ``example.org``'s bucket. Times are
+/// Unix timestamps in seconds; ``now`` arguments default to the current
+/// time.
+///
+/// Raises
+/// ------
+/// ValueError
+///     If ``rate`` is not positive, ``burst`` is 0 or ``jitter`` is
+///     negative.
+#[pyclass(module = "moltis_rust_core")]
+pub struct RateLimiter {
+    rate: f64,
+    burst: f64,
+    jitter: f64,
+    rng: u64,
+    crawl_delays: HashMap<String, f64>,
+    buckets: HashMap<String, Bucket>,
+}
+
+/// Bucket key for a host name or URL.
+fn host_key(host: &str) -> PyResult<String> {
+    let host = host.trim();
+    let key = if host.contains("://") {
+        politeness_key_of(host)
+    } else {
+        politeness_key_of(&format!("http://{host}/"))
+    };
+    key.ok_or_else(|| PyValueError::new_err(format!("rate limiter host is invalid: {host:?}")))
+}
+
+impl RateLimiter {
+    pub(crate) fn new(rate: f64, burst: u32, jitter: f64, seed: u64) -> Result<Self, String> {
+        if !(rate > 0.0 && rate.is_finite()) {
+            return Err("rate must be positive".into());
+        }
+        if burst == 0 {
+            return Err("burst must be at least 1".into());
+        }
+        if !(jitter >= 0.0 && jitter.is_finite()) {
+            return Err("jitter must be non-negative".into());
+        }
+        Ok(Self {
+            rate,
+            burst: f64::from(burst),
+            jitter,
+            rng: seed,
+            crawl_delays: HashMap::new(),
+            buckets: HashMap::new(),
+        })
+    }
+
+    /// Rate and capacity for `key`, after any crawl delay.
+    fn limits(&self, key: &str) -> (f64, f64) {
+        match self.crawl_delays.get(key) {
+            Some(&delay) if delay > 0.0 => (self.rate.min(1.0 / delay), 1.0),
+            _ => (self.rate, self.burst),
+        }
+    }
+
+    /// `key`'s bucket refilled up to `now`; new hosts start full.
+    fn bucket(&self, key: &str, now: f64) -> Bucket {
+        let (rate, burst) = self.limits(key);
+        match self.buckets.get(key) {
+            Some(b) => Bucket {
+                tokens: (b.tokens + (now - b.updated).max(0.0) * rate).min(burst),
+                updated: now.max(b.updated),
+                not_before: b.not_before,
+            },
+            None => Bucket { tokens: burst, updated: now, not_before: f64::NEG_INFINITY },
+        }
+    }
+
+    /// Uniform in [0, 1), splitmix64.
+    fn random(&mut self) -> f64 {
+        self.rng = self.rng.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.rng;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    pub(crate) fn acquire_at(&mut self, key: &str, now: f64) -> bool {
+        let mut bucket = self.bucket(key, now);
+        if bucket.tokens < 1.0 || now < bucket.not_before {
+            return false;
+        }
+        bucket.tokens -= 1.0;
+        if bucket.tokens < 1.0 && self.jitter > 0.0 {
+            let (rate, _) = self.limits(key);
+            bucket.not_before = now + (1.0 - bucket.tokens) / rate + self.random() * self.jitter / rate;
+        }
+        self.buckets.insert(key.to_string(), bucket);
+        true
+    }
+
+    pub(crate) fn next_available_at(&self, key: &str, now: f64) -> f64 {
+        let bucket = self.bucket(key, now);
+        let (rate, _) = self.limits(key);
+        let refilled = if bucket.tokens >= 1.0 { now } else { now + (1.0 - bucket.tokens) / rate };
+        refilled.max(bucket.not_before)
+    }
+}
+
+#[pymethods]
+impl RateLimiter {
+    #[new]
+    #[pyo3(signature = (rate=1.0, burst=1, jitter=0.0, seed=None))]
+    fn py_new(rate: f64, burst: u32, jitter: f64, seed: Option<u64>) -> PyResult<Self> {
+        guarded("RateLimiter.__new__", || {
+            let seed = seed.unwrap_or_else(|| now_secs().to_bits());
+            Self::new(rate, burst, jitter, seed).map_err(PyValueError::new_err)
+        })
+    }
+
+    /// Take a token for ``host`` (a host name or URL) if one is free.
+    ///
+    /// Returns True if the request may go ahead now; False leaves the
+    /// bucket untouched (see ``next_available``).
+    #[pyo3(signature = (host, now=None))]
+    fn acquire(&mut self, host: &str, now: Option<f64>) -> PyResult<bool> {
+        guarded("RateLimiter.acquire", || {
+            let key = host_key(host)?;
+            Ok(self.acquire_at(&key, now.unwrap_or_else(now_secs)))
+        })
+    }
+
+    /// Earliest time ``acquire(host)`` can succeed; ``now`` if it can
+    /// already.
+    #[pyo3(signature = (host, now=None))]
+    fn next_available(&self, host: &str, now: Option<f64>) -> PyResult<f64> {
+        guarded("RateLimiter.next_available", || {
+            let key = host_key(host)?;
+            let now = now.unwrap_or_else(now_secs);
+            Ok(self.next_available_at(&key, now))
+        })
+    }
+
+    /// Apply a robots.txt ``Crawl-delay`` (seconds) to ``host``'s bucket,
+    /// or clear it with None. The delay caps the host at one request per
+    /// ``seconds`` with no burst; it never speeds a host up.
+    ///
+    /// Raises ValueError if ``seconds`` is negative.
+    #[pyo3(signature = (host, seconds))]
+    fn set_crawl_delay(&mut self, host: &str, seconds: Option<f64>) -> PyResult<()> {
+        guarded("RateLimiter.set_crawl_delay", || {
+            let key = host_key(host)?;
+            match seconds {
+                Some(s) if !(s >= 0.0 && s.is_finite()) => {
+                    return Err(PyValueError::new_err("crawl delay must be non-negative"));
+                }
+                Some(s) => self.crawl_delays.insert(key, s),
+                None => self.crawl_delays.remove(&key),
+            };
+            Ok(())
+        })
+    }
+
+    /// The crawl delay set for ``host``, or None.
+    fn crawl_delay(&self, host: &str) -> PyResult<Option<f64>> {
+        guarded("RateLimiter.crawl_delay", || Ok(self.crawl_delays.get(&host_key(host)?).copied()))
+    }
+
+    /// Number of hosts (politeness keys) with a bucket.
+    #[getter]
+    fn host_count(&self) -> PyResult<usize> {
+        guarded("RateLimiter.host_count", || Ok(self.buckets.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buckets_refill_per_host() {
+        let mut limiter = RateLimiter::new(2.0, 2, 0.0, 1).unwrap();
+        assert!(limiter.acquire_at("example.org", 100.0));
+        assert!(limiter.acquire_at("example.org", 100.0));
+        assert!(!limiter.acquire_at("example.org", 100.0));
+        assert!(limiter.acquire_at("reliefweb.int", 100.0));
+        assert_eq!(limiter.next_available_at("example.org", 100.0), 100.5);
+        assert!(limiter.acquire_at("example.org", 100.5));
+        assert!(RateLimiter::new(0.0, 1, 0.0, 1).is_err());
+    }
+
+    #[test]
+    fn test_crawl_delay_and_jitter() {
+        let mut limiter = RateLimiter::new(10.0, 5, 0.5, 7).unwrap();
+        limiter.crawl_delays.insert("example.org".into(), 4.0);
+        assert!(limiter.acquire_at("example.org", 0.0));
+        assert!(!limiter.acquire_at("example.org", 0.0));
+        let next = limiter.next_available_at("example.org", 0.0);
+        assert!((4.0..=6.0).contains(&next), "{next}");
+        assert!(!limiter.acquire_at("example.org", next - 0.01));
+        assert!(limiter.acquire_at("example.org", next));
+    }
+
+    #[test]
+    fn test_host_keys() {
+        assert_eq!(host_key("news.example.org").unwrap(), "example.org");
+        assert_eq!(host_key("https://www.example.org/a").unwrap(), "example.org");
+        assert!(host_key("").is_err());
+    }
+}
@@ -0,0 +1,410 @@
+//! Conditional re-crawl cache — validators and schedule per canonical URL.
+//!
+//! Remembers each URL's `ETag`, `Last-Modified` and content fingerprint
+//! from its last fetch, so a re-crawl can send `If-None-Match` /
+//! `If-Modified-Since` and skip unchanged pages cheaply. The revisit
+//! interval adapts: it doubles each time a page comes back unchanged (up
+//! to `max_interval`) and drops to `min_interval` when it changes, so
+//! live situation reports are polled often and static pages rarely.
+
+use pyo3::exceptions::{PyOSError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::collections::HashMap;
+
+use crate::content_hash::content_fingerprint;
+use crate::errors::{guarded, url_parse_error};
+use crate::frontier::now_secs;
+use crate::storage::{self, Storage};
+use crate::url_key::url_hash128;
+
+/// What a fetch found, compared with the last one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Change {
+    New,
+    Unchanged,
+    Updated,
+}
+
+impl Change {
+    fn as_str(self) -> &'static str {
+        match self {
+            Change::New => "new",
+            Change::Unchanged => "unchanged",
+            Change::Updated => "updated",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Entry {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    fingerprint: Option<u128>,
+    fetched_at: f64,
+    /// Seconds until the next fetch is due.
+    interval: f64,
+}
+
+const HAS_FINGERPRINT: u8 = 1;
+const HAS_ETAG: u8 = 2;
+const HAS_LAST_MODIFIED: u8 = 4;
+
+impl Entry {
+    fn due(&self) -> f64 {
+        self.fetched_at + self.interval
+    }
+
+    /// Fetch time and interval (f64 LE), flags, then the fingerprint (BE)
+    /// and length-prefixed validators that are present.
+    fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(64);
+        out.extend_from_slice(&self.fetched_at.to_le_bytes());
+        out.extend_from_slice(&self.interval.to_le_bytes());
+        let mut flags = 0;
+        for (present, flag) in [
+            (self.fingerprint.is_some(), HAS_FINGERPRINT),
+            (self.etag.is_some(), HAS_ETAG),
+            (self.last_modified.is_some(), HAS_LAST_MODIFIED),
+        ] {
+            if present {
+                flags |= flag;
+            }
+        }
+        out.push(flags);
+        if let Some(fp) = self.fingerprint {
+            out.extend_from_slice(&fp.to_be_bytes());
+        }
+        for value in [&self.etag, &self.last_modified].into_iter().flatten() {
+            out.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            out.extend_from_slice(value.as_bytes());
+        }
+        out
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        let mut rest = bytes;
+        let mut take = |n: usize| -> Option<&[u8]> {
+            let (head, tail) = rest.split_at_checked(n)?;
+            rest = tail;
+            Some(head)
+        };
+        let fetched_at = f64::from_le_bytes(take(8)?.try_into().ok()?);
+        let interval = f64::from_le_bytes(take(8)?.try_into().ok()?);
+        let flags = take(1)?[0];
+        let fingerprint = match flags & HAS_FINGERPRINT {
+            0 => None,
+            _ => Some(u128::from_be_bytes(take(16)?.try_into().ok()?)),
+        };
+        let mut text = |flag: u8| -> Option<Option<String>> {
+            if flags & flag == 0 {
+                return Some(None);
+            }
+            let len = u32::from_le_bytes(take(4)?.try_into().ok()?) as usize;
+            Some(Some(String::from_utf8_lossy(take(len)?).into_owned()))
+        };
+        let etag = text(HAS_ETAG)?;
+        let last_modified = text(HAS_LAST_MODIFIED)?;
+        Some(Self { etag, last_modified, fingerprint, fetched_at, interval })
+    }
+}
+
+enum Entries {
+    Memory(HashMap<u128, Entry>),
+    Stored(Box<dyn Storage>),
+}
+
+/// Validators and revisit schedule for fetched URLs.
+///
+/// Parameters
+/// ----------
+/// min_interval : float
+///     Seconds before a new or changed page is due again. Default 3600.
+/// max_interval : float
+///     Cap on the interval, which doubles each time a page is unchanged.
+///     Default 604800 (a week).
+/// storage : str | None
+///     ``"memory"``, ``"sqlite:PATH"`` or ``"mmap:PATH"``. Default: the
+///     process default, ``recrawl.db`` / ``.map`` in its directory.
+/// batch_size : int
+///     Records per committed SQLite transaction. Default 1000.
+///
+/// URLs are canonicalized, so tracking-parameter variants share an entry.
+/// Times are Unix timestamps in seconds; ``now`` arguments default to the
+/// current time.
+///
+/// Raises
+/// ------
+/// ValueError
+///     If the intervals are not positive and ordered, or the storage spec
+///     is invalid.
+/// OSError
+///     If the storage cannot be opened.
+#[pyclass(module = "moltis_rust_core")]
+pub struct RecrawlCache {
+    min_interval: f64,
+    max_interval: f64,
+    entries: Entries,
+}
+
+/// Case-insensitive header lookup.
+fn header<'a>(headers: &'a HashMap<String, String>, name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.trim())
+        .filter(|v| !v.is_empty())
+}
+
+impl RecrawlCache {
+    pub(crate) fn new(min_interval: f64, max_interval: f64) -> Result<Self, String> {
+        if !(min_interval > 0.0 && min_interval <= max_interval && max_interval.is_finite()) {
+            return Err("intervals must be positive with min_interval <= max_interval".into());
+        }
+        Ok(Self { min_interval, max_interval, entries: Entries::Memory(HashMap::new()) })
+    }
+
+    pub(crate) fn get(&self, key: u128) -> Result<Option<Entry>, String> {
+        match &self.entries {
+            Entries::Memory(map) => Ok(map.get(&key).cloned()),
+            Entries::Stored(store) => Ok(store.get(&key.to_be_bytes())?.and_then(|v| Entry::decode(&v))),
+        }
+    }
+
+    fn put(&mut self, key: u128, entry: Entry) -> Result<(), String> {
+        match &mut self.entries {
+            Entries::Memory(map) => {
+                map.insert(key, entry);
+            }
+            Entries::Stored(store) => {
+                store.put(&key.to_be_bytes(), &entry.encode())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Record a fetch of `key`. `not_modified` is a 304 response.
+    pub(crate) fn record(
+        &mut self,
+        key: u128,
+        etag: Option<String>,
+        last_modified: Option<String>,
+        fingerprint: Option<u128>,
+        not_modified: bool,
+        now: f64,
+    ) -> Result<Change, String> {
+        let previous = self.get(key)?;
+        let change = match &previous {
+            None => Change::New,
+            Some(_) if not_modified => Change::Unchanged,
+            Some(prev) => {
+                // Strongest evidence first: content, then validators.
+                let same = match (prev.fingerprint, fingerprint) {
+                    (Some(a), Some(b)) => a == b,
+                    _ => match (&prev.etag, &etag, &prev.last_modified, &last_modified) {
+                        (Some(a), Some(b), _, _) => a == b,
+                        (_, _, Some(a), Some(b)) => a == b,
+                        _ => false,
+                    },
+                };
+                if same { Change::Unchanged } else { Change::Updated }
+            }
+        };
+        let interval = match (&previous, change) {
+            (Some(prev), Change::Unchanged) => (prev.interval * 2.0).clamp(self.min_interval, self.max_interval),
+            _ => self.min_interval,
+        };
+        // A 304 carries no body and may omit validators: keep the old ones.
+        let (etag, last_modified, fingerprint) = match (&previous, not_modified) {
+            (Some(prev), true) => (
+                etag.or_else(|| prev.etag.clone()),
+                last_modified.or_else(|| prev.last_modified.clone()),
+                fingerprint.or(prev.fingerprint),
+            ),
+            _ => (etag, last_modified, fingerprint),
+        };
+        self.put(key, Entry { etag, last_modified, fingerprint, fetched_at: now, interval })?;
+        Ok(change)
+    }
+}
+
+fn url_key(url: &str) -> PyResult<u128> {
+    url_hash128(url).map_err(|kind| url_parse_error(url, kind))
+}
+
+#[pymethods]
+impl RecrawlCache {
+    #[new]
+    #[pyo3(signature = (min_interval=3600.0, max_interval=604800.0, storage=None, batch_size=1000))]
+    fn py_new(min_interval: f64, max_interval: f64, storage: Option<&str>, batch_size: usize) -> PyResult<Self> {
+        guarded("RecrawlCache.__new__", || {
+            let mut cache = Self::new(min_interval, max_interval).map_err(PyValueError::new_err)?;
+            if let Some(store) = storage::open_for(storage, "recrawl", batch_size)? {
+                cache.entries = Entries::Stored(store);
+            }
+            Ok(cache)
+        })
+    }
+
+    /// Record a fetch and compare it with the previous one.
+    ///
+    /// Parameters
+    /// ----------
+    /// url : str
+    ///     URL fetched (canonicalized here).
+    /// status : int
+    ///     HTTP status; 304 counts as unchanged and keeps the stored
+    ///     validators. Default 200.
+    /// headers : dict[str, str] | None
+    ///     Response headers; ``ETag`` and ``Last-Modified`` are kept (names
+    ///     are matched case-insensitively).
+    /// content : str | None
+    ///     Extracted page text, fingerprinted as by ``content_hash``. When
+    ///     given on both fetches it decides the comparison; otherwise the
+    ///     validators do.
+    /// now : float | None
+    ///     Fetch time.
+    ///
+    /// Returns
+    /// -------
+    /// str
+    ///     ``"new"``, ``"unchanged"`` or ``"updated"``.
+    ///
+    /// Raises
+    /// ------
+    /// UrlParseError
+    ///     If the URL has no host.
+    /// OSError
+    ///     If the storage cannot be written.
+    #[pyo3(signature = (url, status=200, headers=None, content=None, now=None))]
+    fn record_fetch(
+        &mut self,
+        url: &str,
+        status: u16,
+        headers: Option<HashMap<String, String>>,
+        content: Option<&str>,
+        now: Option<f64>,
+    ) -> PyResult<&'static str> {
+        guarded("RecrawlCache.record_fetch", || {
+            let key = url_key(url)?;
+            let headers = headers.unwrap_or_default();
+            let etag = header(&headers, "etag").map(String::from);
+            let last_modified = header(&headers, "last-modified").map(String::from);
+            let change = self
+                .record(key, etag, last_modified, content.map(content_fingerprint), status == 304, now.unwrap_or_else(now_secs))
+                .map_err(PyOSError::new_err)?;
+            Ok(change.as_str())
+        })
+    }
+
+    /// Whether ``url`` is due for a fetch: never fetched, or its
+    /// interval has passed.
+    #[pyo3(signature = (url, now=None))]
+    fn needs_refetch(&self, url: &str, now: Option<f64>) -> PyResult<bool> {
+        guarded("RecrawlCache.needs_refetch", || {
+            let entry = self.get(url_key(url)?).map_err(PyOSError::new_err)?;
+            Ok(entry.is_none_or(|e| now.unwrap_or_else(now_secs) >= e.due()))
+        })
+    }
+
+    /// Headers for a conditional re-fetch of ``url``:
+    /// ``If-None-Match`` and/or ``If-Modified-Since``, or an empty dict.
+    fn conditional_headers(&self, url: &str) -> PyResult<HashMap<&'static str, String>> {
+        guarded("RecrawlCache.conditional_headers", || {
+            let mut headers = HashMap::new();
+            if let Some(entry) = self.get(url_key(url)?).map_err(PyOSError::new_err)? {
+                if let Some(etag) = entry.etag {
+                    headers.insert("If-None-Match", etag);
+                }
+                if let Some(last_modified) = entry.last_modified {
+                    headers.insert("If-Modified-Since", last_modified);
+                }
+            }
+            Ok(headers)
+        })
+    }
+
+    /// What is stored for ``url``: ``etag``, ``last_modified``,
+    /// ``content_hash``, ``fetched_at``, ``interval`` and ``due``; None if
+    /// it was never recorded.
+    fn get_entry(&self, py: Python<'_>, url: &str) -> PyResult<Option<Py<PyDict>>> {
+        guarded("RecrawlCache.get_entry", || {
+            let Some(entry) = self.get(url_key(url)?).map_err(PyOSError::new_err)? else {
+                return Ok(None);
+            };
+            let dict = PyDict::new_bound(py);
+            dict.set_item("etag", &entry.etag)?;
+            dict.set_item("last_modified", &entry.last_modified)?;
+            dict.set_item("content_hash", entry.fingerprint)?;
+            dict.set_item("fetched_at", entry.fetched_at)?;
+            dict.set_item("interval", entry.interval)?;
+            dict.set_item("due", entry.due())?;
+            Ok(Some(dict.unbind()))
+        })
+    }
+
+    /// Number of URLs recorded.
+    fn __len__(&self) -> PyResult<usize> {
+        guarded("RecrawlCache.__len__", || {
+            match &self.entries {
+                Entries::Memory(map) => Ok(map.len()),
+                Entries::Stored(store) => store.count(&[]).map_err(PyOSError::new_err),
+            }
+        })
+    }
+
+    /// Write pending records to storage (no-op in memory).
+    fn flush(&mut self) -> PyResult<()> {
+        guarded("RecrawlCache.flush", || {
+            match &mut self.entries {
+                Entries::Memory(_) => Ok(()),
+                Entries::Stored(store) => store.flush().map_err(PyOSError::new_err),
+            }
+        })
+    }
+
+    /// Storage spec, e.g. ``"sqlite:state/recrawl.db"``, or ``"memory"``.
+    #[getter]
+    fn storage(&self) -> PyResult<String> {
+        guarded("RecrawlCache.storage", || {
+            Ok(match &self.entries {
+                Entries::Memory(_) => "memory".into(),
+                Entries::Stored(store) => store.spec(),
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intervals_adapt_to_changes() {
+        let mut cache = RecrawlCache::new(100.0, 350.0).unwrap();
+        let key = url_hash128("https://example.org/sitrep").unwrap();
+        let etag = || Some("\"v1\"".to_string());
+        assert_eq!(cache.record(key, etag(), None, Some(1), false, 0.0), Ok(Change::New));
+        assert_eq!(cache.record(key, None, None, None, true, 100.0), Ok(Change::Unchanged));
+        let entry = cache.get(key).unwrap().unwrap();
+        assert_eq!((entry.etag.clone(), entry.fingerprint, entry.due()), (etag(), Some(1), 300.0));
+        assert_eq!(cache.record(key, etag(), None, Some(1), false, 300.0), Ok(Change::Unchanged));
+        assert_eq!(cache.get(key).unwrap().unwrap().interval, 350.0);
+        assert_eq!(cache.record(key, Some("\"v2\"".into()), None, Some(2), false, 650.0), Ok(Change::Updated));
+        assert_eq!(cache.get(key).unwrap().unwrap().interval, 100.0);
+        assert!(RecrawlCache::new(10.0, 5.0).is_err());
+    }
+
+    #[test]
+    fn test_entry_encoding_round_trips() {
+        let entry = Entry {
+            etag: None,
+            last_modified: Some("Tue, 01 Oct 2024 10:00:00 GMT".into()),
+            fingerprint: Some(u128::MAX - 7),
+            fetched_at: 1.7e9,
+            interval: 3600.0,
+        };
+        assert_eq!(Entry::decode(&entry.encode()), Some(entry.clone()));
+        assert_eq!(Entry::decode(&entry.encode()[..20]), None);
+    }
+}
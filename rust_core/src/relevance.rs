@@ -0,0 +1,110 @@
+//! Crawl-worthiness of a discovered link, from its title and snippet.
+//!
+//! Fetching a full article costs a request against the source's
+//! politeness budget, so links are scored first on what the listing page
+//! already shows: hazard words, impact and need keywords, a country
+//! mention and stated figures. Each signal is capped, so a title that
+//! repeats "flood" five times scores no better than one that says it
+//! once.
+
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+use crate::errors::unlocked;
+use crate::figure_extraction::figures;
+use crate::hashtags::{fold_accents, COUNTRIES, HAZARDS};
+use crate::text_classify::humanitarian_keyword_hits;
+
+const HAZARD_WEIGHT: f64 = 0.35;
+const KEYWORD_WEIGHT: f64 = 0.25;
+const PLACE_WEIGHT: f64 = 0.2;
+const FIGURE_WEIGHT: f64 = 0.2;
+/// Distinct hazards beyond this add nothing.
+const MAX_HAZARDS: usize = 2;
+/// Keyword hits beyond this add nothing.
+const MAX_KEYWORD_HITS: usize = 3;
+
+static HAZARD_WORDS: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| HAZARDS.iter().copied().collect());
+
+static COUNTRY_PHRASES: Lazy<Vec<String>> = Lazy::new(|| COUNTRIES.iter().map(|name| phrase(name)).collect());
+
+/// Lowercase, accent-folded words of `text`, space-padded so a phrase
+/// matches only on word boundaries.
+fn phrase(text: &str) -> String {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| fold_accents(&w.to_lowercase()))
+        .collect();
+    format!(" {} ", words.join(" "))
+}
+
+pub(crate) fn score(title: &str, snippet: &str, locations: &[String]) -> f64 {
+    let text = format!("{title}\n{snippet}");
+    let words = phrase(&text);
+
+    let hazards: HashSet<&str> = words.split(' ').filter_map(|w| HAZARD_WORDS.get(w).copied()).collect();
+    let hazard = hazards.len().min(MAX_HAZARDS) as f64 / MAX_HAZARDS as f64;
+
+    let hits = humanitarian_keyword_hits(&text.to_lowercase());
+    let keyword = hits.min(MAX_KEYWORD_HITS) as f64 / MAX_KEYWORD_HITS as f64;
+
+    let place = COUNTRY_PHRASES.iter().any(|name| words.contains(name.as_str()))
+        || locations.iter().map(|name| phrase(name)).any(|name| name.trim().len() > 1 && words.contains(&name));
+
+    let figure = !figures(&text).is_empty();
+
+    let score = HAZARD_WEIGHT * hazard
+        + KEYWORD_WEIGHT * keyword
+        + PLACE_WEIGHT * f64::from(u8::from(place))
+        + FIGURE_WEIGHT * f64::from(u8::from(figure));
+    score.clamp(0.0, 1.0)
+}
+
+/// Crawl-worthiness of a discovered link, 0-1.
+///
+/// Combines hazard words (English, French, Portuguese, Spanish),
+/// humanitarian impact and need keywords, a country or location mention,
+/// and stated figures in the link's title and snippet. Use it to decide
+/// whether to fetch the full article.
+///
+/// Parameters
+/// ----------
+/// title : str
+///     Link or headline text.
+/// snippet : str
+///     Teaser or description shown with the link, if any.
+/// locations : list[str] | None
+///     Extra place names (provinces, districts, cities) counted as
+///     location mentions, e.g. from a gazetteer. Countries are built in.
+///
+/// Returns
+/// -------
+/// float
+///     0.35 × hazards (two distinct ones score in full) + 0.25 ×
+///     keyword hits (three score in full) + 0.2 if a place is named +
+///     0.2 if a figure is stated.
+#[pyfunction]
+#[pyo3(signature = (title, snippet="", locations=None))]
+pub fn relevance_score(py: Python<'_>, title: &str, snippet: &str, locations: Option<Vec<String>>) -> PyResult<f64> {
+    unlocked(py, "relevance_score", || score(title, snippet, &locations.unwrap_or_default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scores_crisis_reports_above_other_links() {
+        let report = score("Floods and landslides in Mozambique", "Heavy rain killed 12 people and displaced 3,000.", &[]);
+        let vague = score("Floods expected", "", &[]);
+        let other = score("Annual report 2023", "Read our latest newsletter.", &[]);
+        assert!(report > 0.9, "{report}");
+        assert!(vague > other && vague < 0.5, "{vague}");
+        assert_eq!(other, 0.0);
+        assert!(score("Inondations à Beira", "", &["Beira".into()]) > score("Inondations", "", &[]));
+        // Whole words only: no "Mali" in "Somaliland"
+        assert_eq!(score("Animal welfare in Somaliland", "", &[]), 0.0);
+    }
+}
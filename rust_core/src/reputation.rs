@@ -0,0 +1,271 @@
+//! Source reputation tiers by registrable domain.
+//!
+//! Maps domains to a reputation tier (official, established media,
+//! aggregator, unknown, blocked) loaded from a TOML file — see
+//! `config/source_reputation.example.toml` — and answers per URL in the
+//! same pass as canonicalization, for trust-weighting downstream.
+
+use pyo3::exceptions::{PyOSError, PyValueError};
+use pyo3::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use url::Url;
+
+use crate::errors::{config_error, guarded, url_parse_error, Failure};
+use crate::public_suffix::registrable_domain_of_host;
+use crate::url_canonical::{canonical_key_form, canonicalize_url, check_url};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Tier {
+    Official,
+    EstablishedMedia,
+    Aggregator,
+    Unknown,
+    Blocked,
+}
+
+impl Tier {
+    fn as_str(self) -> &'static str {
+        match self {
+            Tier::Official => "official",
+            Tier::EstablishedMedia => "established_media",
+            Tier::Aggregator => "aggregator",
+            Tier::Unknown => "unknown",
+            Tier::Blocked => "blocked",
+        }
+    }
+
+    fn parse(name: &str) -> Result<Self, String> {
+        match name.trim().to_lowercase().as_str() {
+            "official" => Ok(Tier::Official),
+            "established_media" => Ok(Tier::EstablishedMedia),
+            "aggregator" => Ok(Tier::Aggregator),
+            "unknown" => Ok(Tier::Unknown),
+            "blocked" => Ok(Tier::Blocked),
+            other => Err(format!("unknown reputation tier {other:?}")),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ReputationFile {
+    #[serde(default)]
+    tiers: HashMap<Tier, Vec<String>>,
+}
+
+/// Domain → tier table.
+///
+/// Build with ``SourceRegistry.from_file(path)`` or
+/// ``SourceRegistry.from_toml(text)``; an empty registry rates every
+/// domain ``"unknown"``.
+#[pyclass(module = "moltis_rust_core")]
+#[derive(Default)]
+pub struct SourceRegistry {
+    /// Hosts and registrable domains.
+    exact: HashMap<String, Tier>,
+    /// `*.suffix` patterns, stored without the `*.`.
+    suffixes: Vec<(String, Tier)>,
+}
+
+impl SourceRegistry {
+    pub(crate) fn parse(text: &str) -> Result<Self, String> {
+        let file: ReputationFile =
+            toml::from_str(text).map_err(|e| format!("invalid reputation file: {e}"))?;
+        let mut registry = Self::default();
+        for (tier, domains) in file.tiers {
+            for domain in domains {
+                registry.insert(&domain, tier);
+            }
+        }
+        Ok(registry)
+    }
+
+    fn insert(&mut self, domain: &str, tier: Tier) {
+        let domain = domain.trim().trim_end_matches('.').to_lowercase();
+        match domain.strip_prefix("*.") {
+            Some(suffix) => {
+                self.suffixes.retain(|(s, _)| s != suffix);
+                self.suffixes.push((suffix.to_string(), tier));
+                // Longest pattern first
+                self.suffixes.sort_by_key(|(s, _)| std::cmp::Reverse(s.len()));
+            }
+            None => {
+                self.exact.insert(domain, tier);
+            }
+        }
+    }
+
+    pub(crate) fn tier_for_host(&self, host: &str) -> Tier {
+        let host = host.trim_end_matches('.').to_lowercase();
+        let host = host.strip_prefix("www.").unwrap_or(&host);
+        if let Some(tier) = self.exact.get(host) {
+            return *tier;
+        }
+        if let Some(tier) = registrable_domain_of_host(host).and_then(|d| self.exact.get(&d)) {
+            return *tier;
+        }
+        self.suffixes
+            .iter()
+            .find(|(suffix, _)| {
+                host.strip_suffix(suffix.as_str())
+                    .is_some_and(|rest| rest.ends_with('.'))
+            })
+            .map_or(Tier::Unknown, |(_, tier)| *tier)
+    }
+}
+
+#[pymethods]
+impl SourceRegistry {
+    #[new]
+    fn py_new() -> PyResult<Self> {
+        guarded("SourceRegistry.__new__", || Ok(Self::default()))
+    }
+
+    /// Load a registry from a TOML file.
+    ///
+    /// Raises
+    /// ------
+    /// OSError
+    ///     If the file cannot be read.
+    /// ConfigError
+    ///     If the TOML is invalid or names an unknown tier (``reason``
+    ///     ``"malformed"``, ``path`` the file).
+    #[staticmethod]
+    fn from_file(path: &str) -> PyResult<Self> {
+        guarded("SourceRegistry.from_file", || {
+            let text = std::fs::read_to_string(path)
+                .map_err(|e| PyOSError::new_err(format!("cannot read {path}: {e}")))?;
+            Self::parse(&text).map_err(|e| config_error(Some(path), Failure::from(e)))
+        })
+    }
+
+    /// Build a registry from TOML text.
+    ///
+    /// Raises
+    /// ------
+    /// ConfigError
+    ///     As for ``from_file``, with no ``path``.
+    #[staticmethod]
+    fn from_toml(text: &str) -> PyResult<Self> {
+        guarded("SourceRegistry.from_toml", || Self::parse(text).map_err(|e| config_error(None, Failure::from(e))))
+    }
+
+    /// Assign a tier to a domain or ``*.suffix`` pattern.
+    fn set_tier(&mut self, domain: &str, tier: &str) -> PyResult<()> {
+        guarded("SourceRegistry.set_tier", || {
+            let tier = Tier::parse(tier).map_err(PyValueError::new_err)?;
+            self.insert(domain, tier);
+            Ok(())
+        })
+    }
+
+    /// Reputation tier of a URL's source.
+    ///
+    /// Returns ``"unknown"`` for unlisted domains and unparseable URLs.
+    fn tier(&self, url: &str) -> PyResult<&'static str> {
+        guarded("SourceRegistry.tier", || {
+            let canonical = canonicalize_url(url);
+            Ok(Url::parse(&canonical)
+                .ok()
+                .and_then(|u| u.host_str().map(|h| self.tier_for_host(h)))
+                .unwrap_or(Tier::Unknown)
+                .as_str())
+        })
+    }
+
+    /// Canonicalize a URL and rate its source in one pass.
+    ///
+    /// Parameters
+    /// ----------
+    /// url : str
+    ///     The URL to classify.
+    /// key_form : bool
+    ///     Return the strict key form of the canonical URL (see
+    ///     ``canonicalize_url``). Default False.
+    ///
+    /// Returns
+    /// -------
+    /// tuple[str, str | None, str]
+    ///     ``(canonical_url, registrable_domain, tier)``.
+    ///
+    /// Raises
+    /// ------
+    /// UrlParseError
+    ///     If the URL is not an absolute http(s) URL.
+    #[pyo3(signature = (url, key_form=false))]
+    fn classify(&self, url: &str, key_form: bool) -> PyResult<(String, Option<String>, &'static str)> {
+        guarded("SourceRegistry.classify", || {
+            check_url(url).map_err(|kind| url_parse_error(url, kind))?;
+            let canonical = if key_form {
+                canonical_key_form(url).map_err(|kind| url_parse_error(url, kind))?
+            } else {
+                canonicalize_url(url)
+            };
+            let host = Url::parse(&canonical)
+                .ok()
+                .and_then(|u| u.host_str().map(str::to_string))
+                .unwrap_or_default();
+            let domain = registrable_domain_of_host(&host);
+            let tier = self.tier_for_host(&host).as_str();
+            Ok((canonical, domain, tier))
+        })
+    }
+
+    fn __len__(&self) -> PyResult<usize> {
+        guarded("SourceRegistry.__len__", || Ok(self.exact.len() + self.suffixes.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOML: &str = r#"
+        [tiers]
+        official = ["reliefweb.int", "*.gov.mz"]
+        established_media = ["bbc.co.uk"]
+        aggregator = ["news.google.com"]
+        blocked = ["spam.example"]
+    "#;
+
+    #[test]
+    fn test_tier_lookup() {
+        let registry = SourceRegistry::parse(TOML).unwrap();
+        assert_eq!(registry.tier_for_host("www.reliefweb.int"), Tier::Official);
+        assert_eq!(registry.tier_for_host("ingc.gov.mz"), Tier::Official);
+        assert_eq!(registry.tier_for_host("feeds.bbc.co.uk"), Tier::EstablishedMedia);
+        assert_eq!(registry.tier_for_host("news.google.com"), Tier::Aggregator);
+        assert_eq!(registry.tier_for_host("www.google.com"), Tier::Unknown);
+        assert_eq!(registry.tier_for_host("cdn.spam.example"), Tier::Blocked);
+        assert_eq!(registry.tier_for_host("gov.mz"), Tier::Unknown);
+    }
+
+    #[test]
+    fn test_classify_canonicalizes() {
+        let registry = SourceRegistry::parse(TOML).unwrap();
+        let (url, domain, tier) = registry
+            .classify("https://m.reliefweb.int/report/1?utm_source=x", false)
+            .unwrap();
+        assert_eq!(url, "https://reliefweb.int/report/1");
+        assert_eq!(domain.as_deref(), Some("reliefweb.int"));
+        assert_eq!(tier, "official");
+    }
+
+    #[test]
+    fn test_invalid_tier() {
+        assert!(SourceRegistry::parse("[tiers]\nfamous = [\"a.org\"]").is_err());
+        assert!(Tier::parse("trusted").is_err());
+
+        let path = std::env::temp_dir().join(format!("moltis_reputation_{}.toml", std::process::id()));
+        let path = path.to_str().unwrap();
+        std::fs::write(path, "[tiers]\nfamous = [\"a.org\"]").unwrap();
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let err = SourceRegistry::from_file(path).err().unwrap();
+            assert!(err.is_instance_of::<crate::errors::ConfigError>(py));
+            assert_eq!(err.value_bound(py).getattr("path").unwrap().extract::<String>().unwrap(), path);
+        });
+        std::fs::remove_file(path).unwrap();
+    }
+}
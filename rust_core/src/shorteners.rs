@@ -0,0 +1,183 @@
+//! URL shorteners — detection and expansion hooks.
+//!
+//! Links from social sources are often bit.ly / t.co wrappers. We don't
+//! fetch them here; instead callers resolve them (HTTP HEAD, their own
+//! cache) and hand the result back, either up front via
+//! `cache_expansion` or lazily through a resolver callback. Once a
+//! short link is known, `canonicalize_url` canonicalizes its target.
+
+use once_cell::sync::Lazy;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use url::Url;
+
+#[cfg(feature = "python")]
+use crate::errors::guarded;
+#[cfg(feature = "python")]
+use crate::url_canonical::canonicalize_url;
+
+static SHORTENER_HOSTS: &[&str] = &[
+    "bit.ly", "t.co", "goo.gl", "ow.ly", "tinyurl.com", "buff.ly", "is.gd", "dlvr.it",
+    "trib.al", "lnkd.in", "fb.me", "rb.gy", "cutt.ly", "shorturl.at", "tiny.cc",
+];
+
+static EXPANSIONS: Lazy<RwLock<HashMap<String, String>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Cache key for a shortened URL (`host/path`), or `None` if the URL is
+/// not on a known shortener host.
+fn shortener_key(url_str: &str) -> Option<String> {
+    let parsed = Url::parse(url_str.trim()).ok()?;
+    let host = parsed.host_str()?.to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    if !SHORTENER_HOSTS.contains(&host) {
+        return None;
+    }
+    Some(format!("{host}{}", parsed.path()))
+}
+
+/// Shortener hosts and cached expansions, for `runtime_info`.
+pub(crate) fn inventory() -> Vec<(&'static str, usize)> {
+    vec![
+        ("shortener_hosts", SHORTENER_HOSTS.len()),
+        ("shortener_expansions", EXPANSIONS.read().unwrap_or_else(|e| e.into_inner()).len()),
+    ]
+}
+
+/// Previously registered expansion target for a short URL.
+pub(crate) fn cached_expansion(url_str: &str) -> Option<String> {
+    let key = shortener_key(url_str)?;
+    EXPANSIONS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&key)
+        .cloned()
+}
+
+/// Return True if the URL is on a known link-shortener host.
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn is_shortened_url(url: &str) -> PyResult<bool> {
+    guarded("is_shortened_url", || Ok(shortener_key(url).is_some()))
+}
+
+/// Record the resolved target of a short URL.
+///
+/// Subsequent ``canonicalize_url`` calls on the short URL return the
+/// canonical form of ``resolved_url``. Returns False (and records
+/// nothing) if ``short_url`` is not on a known shortener host.
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn cache_expansion(short_url: &str, resolved_url: &str) -> PyResult<bool> {
+    guarded("cache_expansion", || {
+        Ok(match shortener_key(short_url) {
+            Some(key) => {
+                EXPANSIONS
+                    .write()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .insert(key, resolved_url.trim().to_string());
+                true
+            }
+            None => false,
+        })
+    })
+}
+
+/// Forget all recorded short-URL expansions.
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn clear_expansion_cache() -> PyResult<()> {
+    guarded("clear_expansion_cache", || {
+        EXPANSIONS.write().unwrap_or_else(|e| e.into_inner()).clear();
+        Ok(())
+    })
+}
+
+/// Canonicalize a URL, expanding it first if it is a short link.
+///
+/// Parameters
+/// ----------
+/// url : str
+///     The URL to canonicalize.
+/// resolver : Callable[[str], str | None] | None
+///     Called with short URLs missing from the expansion cache; a returned
+///     target is cached and canonicalized.
+///
+/// Returns
+/// -------
+/// tuple[str, bool]
+///     ``(canonical_url, needs_expansion)``. ``needs_expansion`` is True
+///     when the URL is a short link that could not be resolved, in which
+///     case the short URL itself is returned.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (url, resolver=None))]
+pub fn expand_url(url: &str, resolver: Option<&Bound<'_, PyAny>>) -> PyResult<(String, bool)> {
+    guarded("expand_url", || {
+        if shortener_key(url).is_none() || cached_expansion(url).is_some() {
+            return Ok((canonicalize_url(url), false));
+        }
+        if let Some(resolver) = resolver {
+            if let Some(target) = resolver.call1((url,))?.extract::<Option<String>>()? {
+                cache_expansion(url, &target)?;
+                return Ok((canonicalize_url(&target), false));
+            }
+        }
+        Ok((url.trim().to_string(), true))
+    })
+}
+
+#[cfg(all(test, feature = "python"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detection() {
+        assert!(shortener_key("https://bit.ly/3xYz").is_some());
+        assert!(shortener_key("http://T.CO/abc").is_some());
+        assert!(shortener_key("https://reliefweb.int/report/x").is_none());
+        assert!(shortener_key("not a url").is_none());
+    }
+
+    #[test]
+    fn test_unresolved_needs_expansion() {
+        let (url, needs) = expand_url("https://ow.ly/unresolved1", None).unwrap();
+        assert_eq!(url, "https://ow.ly/unresolved1");
+        assert!(needs);
+    }
+
+    #[test]
+    fn test_cached_expansion_canonicalizes_target() {
+        assert!(cache_expansion(
+            "https://bit.ly/cyclone42",
+            "https://www.example.org/story?id=42&utm_source=twitter"
+        )
+        .unwrap());
+        assert_eq!(
+            canonicalize_url("http://bit.ly/cyclone42"),
+            "https://www.example.org/story?id=42"
+        );
+        assert_eq!(
+            expand_url("https://bit.ly/cyclone42", None).unwrap(),
+            ("https://www.example.org/story?id=42".to_string(), false)
+        );
+        assert!(!cache_expansion("https://example.org/x", "https://example.org/y").unwrap());
+    }
+
+    #[test]
+    fn test_resolver_callback() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let resolver = py
+                .eval_bound("lambda u: 'https://example.org/flood?fbclid=1'", None, None)
+                .unwrap();
+            let result = expand_url("https://t.co/resolveme", Some(&resolver)).unwrap();
+            assert_eq!(result, ("https://example.org/flood".to_string(), false));
+            let none_resolver = py.eval_bound("lambda u: None", None, None).unwrap();
+            let result = expand_url("https://t.co/unknown", Some(&none_resolver)).unwrap();
+            assert!(result.1);
+        });
+    }
+}
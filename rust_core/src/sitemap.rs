@@ -0,0 +1,252 @@
+//! Sitemap parsing — urlset and sitemapindex documents.
+//!
+//! Drives discovery on government and UN sites that publish sitemaps.
+//! Gzip payloads (`sitemap.xml.gz`) are detected by magic bytes and
+//! inflated up to the protocol's 50 MiB limit.
+
+use flate2::read::GzDecoder;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyList};
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::borrow::Cow;
+use std::io::Read;
+
+use crate::errors::{extraction_error, unlocked, Failure};
+
+/// Sitemaps protocol cap on uncompressed size.
+const MAX_SITEMAP_BYTES: u64 = 50 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum SitemapKind {
+    UrlSet,
+    Index,
+}
+
+impl SitemapKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            SitemapKind::UrlSet => "urlset",
+            SitemapKind::Index => "sitemapindex",
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct SitemapEntry {
+    pub loc: String,
+    pub lastmod: Option<String>,
+    pub changefreq: Option<String>,
+    pub priority: Option<f64>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Sitemap {
+    pub kind: SitemapKind,
+    pub entries: Vec<SitemapEntry>,
+}
+
+fn inflate(data: &[u8]) -> Result<Cow<'_, [u8]>, Failure> {
+    if !data.starts_with(&[0x1f, 0x8b]) {
+        return Ok(Cow::Borrowed(data));
+    }
+    let mut out = Vec::new();
+    GzDecoder::new(data)
+        .take(MAX_SITEMAP_BYTES + 1)
+        .read_to_end(&mut out)
+        .map_err(|e| format!("invalid gzip sitemap: {e}"))?;
+    if out.len() as u64 > MAX_SITEMAP_BYTES {
+        return Err(Failure::new("too_large", "sitemap exceeds 50 MiB uncompressed"));
+    }
+    Ok(Cow::Owned(out))
+}
+
+/// Parse a (possibly gzipped) sitemap document.
+pub(crate) fn parse_sitemap_bytes(data: &[u8]) -> Result<Sitemap, Failure> {
+    let xml = inflate(data)?;
+    let mut reader = Reader::from_reader(xml.as_ref());
+    let mut buf = Vec::new();
+
+    let mut kind = None;
+    let mut entries = Vec::new();
+    let mut current: Option<SitemapEntry> = None;
+    let mut field: Option<Vec<u8>> = None;
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => {
+                let name = e.local_name().as_ref().to_vec();
+                match (kind, name.as_slice()) {
+                    (None, b"urlset") => kind = Some(SitemapKind::UrlSet),
+                    (None, b"sitemapindex") => kind = Some(SitemapKind::Index),
+                    (None, _) => return Err(Failure::new("wrong_format", "not a sitemap: unexpected root element")),
+                    (Some(_), b"url" | b"sitemap") => current = Some(SitemapEntry::default()),
+                    (Some(_), b"loc" | b"lastmod" | b"changefreq" | b"priority") if current.is_some() => {
+                        field = Some(name);
+                        text.clear();
+                    }
+                    _ => {}
+                }
+            }
+            // A self-closed root is an empty sitemap: `<urlset xmlns="..."/>`
+            Ok(Event::Empty(e)) if kind.is_none() => match e.local_name().as_ref() {
+                b"urlset" => kind = Some(SitemapKind::UrlSet),
+                b"sitemapindex" => kind = Some(SitemapKind::Index),
+                _ => return Err(Failure::new("wrong_format", "not a sitemap: unexpected root element")),
+            },
+            Ok(Event::Text(t)) if field.is_some() => {
+                text.push_str(&t.decode().map_err(|e| e.to_string())?);
+            }
+            Ok(Event::CData(t)) if field.is_some() => {
+                text.push_str(&t.decode().map_err(|e| e.to_string())?);
+            }
+            Ok(Event::GeneralRef(r)) if field.is_some() => {
+                if let Some(ch) = r.resolve_char_ref().map_err(|e| e.to_string())? {
+                    text.push(ch);
+                } else if let Some(s) = resolve_predefined_entity(&r.decode().map_err(|e| e.to_string())?) {
+                    text.push_str(s);
+                }
+            }
+            Ok(Event::End(e)) => {
+                let name = e.local_name();
+                if field.as_deref() == Some(name.as_ref()) {
+                    let value = text.trim().to_string();
+                    if let Some(entry) = current.as_mut() {
+                        match name.as_ref() {
+                            b"loc" => entry.loc = value,
+                            b"lastmod" => entry.lastmod = Some(value),
+                            b"changefreq" => entry.changefreq = Some(value.to_lowercase()),
+                            _ => entry.priority = value.parse().ok(),
+                        }
+                    }
+                    field = None;
+                } else if matches!(name.as_ref(), b"url" | b"sitemap") {
+                    if let Some(entry) = current.take().filter(|e| !e.loc.is_empty()) {
+                        entries.push(entry);
+                    }
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(format!("malformed sitemap XML: {e}").into()),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    let kind = kind.ok_or_else(|| Failure::new("wrong_format", "not a sitemap: empty document"))?;
+    Ok(Sitemap { kind, entries })
+}
+
+/// Parse a sitemap or sitemap index.
+///
+/// Parameters
+/// ----------
+/// data : bytes | str
+///     The document; gzip-compressed bytes are inflated automatically.
+///
+/// Returns
+/// -------
+/// dict
+///     ``{"kind": "urlset" | "sitemapindex", "entries": [...]}`` where each
+///     entry has ``loc``, ``lastmod``, ``changefreq`` and ``priority``
+///     (missing values are None).
+///
+/// Raises
+/// ------
+/// ExtractionError
+///     If the payload is malformed (``reason`` ``"malformed"``), not a
+///     sitemap (``"wrong_format"``) or over 50 MiB uncompressed
+///     (``"too_large"``). A ``ValueError`` subclass.
+#[pyfunction]
+pub fn parse_sitemap(py: Python<'_>, data: &Bound<'_, PyAny>) -> PyResult<Py<PyDict>> {
+    let sitemap = if let Ok(bytes) = data.downcast::<PyBytes>() {
+        let bytes = bytes.as_bytes();
+        unlocked(py, "parse_sitemap", || parse_sitemap_bytes(bytes))?
+    } else {
+        let text = data.extract::<String>()?;
+        unlocked(py, "parse_sitemap", || parse_sitemap_bytes(text.as_bytes()))?
+    }
+    .map_err(|e| extraction_error("sitemap", e))?;
+
+    let entries = PyList::empty_bound(py);
+    for entry in &sitemap.entries {
+        let item = PyDict::new_bound(py);
+        item.set_item("loc", &entry.loc)?;
+        item.set_item("lastmod", &entry.lastmod)?;
+        item.set_item("changefreq", &entry.changefreq)?;
+        item.set_item("priority", entry.priority)?;
+        entries.append(item)?;
+    }
+    let dict = PyDict::new_bound(py);
+    dict.set_item("kind", sitemap.kind.as_str())?;
+    dict.set_item("entries", entries)?;
+    Ok(dict.unbind())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    const URLSET: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url>
+    <loc>https://reliefweb.int/report/mozambique?a=1&amp;b=2</loc>
+    <lastmod>2025-03-14</lastmod>
+    <changefreq>Daily</changefreq>
+    <priority>0.8</priority>
+  </url>
+  <url><loc><![CDATA[https://reliefweb.int/report/malawi]]></loc></url>
+  <url><lastmod>2025-01-01</lastmod></url>
+</urlset>"#;
+
+    #[test]
+    fn test_urlset() {
+        let sm = parse_sitemap_bytes(URLSET.as_bytes()).unwrap();
+        assert_eq!(sm.kind, SitemapKind::UrlSet);
+        assert_eq!(sm.entries.len(), 2);
+        assert_eq!(sm.entries[0].loc, "https://reliefweb.int/report/mozambique?a=1&b=2");
+        assert_eq!(sm.entries[0].lastmod.as_deref(), Some("2025-03-14"));
+        assert_eq!(sm.entries[0].changefreq.as_deref(), Some("daily"));
+        assert_eq!(sm.entries[0].priority, Some(0.8));
+        assert_eq!(sm.entries[1].loc, "https://reliefweb.int/report/malawi");
+        assert_eq!(sm.entries[1].priority, None);
+    }
+
+    #[test]
+    fn test_sitemap_index() {
+        let xml = r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+            <sitemap><loc>https://example.org/sitemap-1.xml.gz</loc><lastmod>2025-02-01T10:00:00Z</lastmod></sitemap>
+        </sitemapindex>"#;
+        let sm = parse_sitemap_bytes(xml.as_bytes()).unwrap();
+        assert_eq!(sm.kind, SitemapKind::Index);
+        assert_eq!(sm.entries[0].loc, "https://example.org/sitemap-1.xml.gz");
+    }
+
+    #[test]
+    fn test_empty_sitemap() {
+        let sm = parse_sitemap_bytes(br#"<?xml version="1.0"?><urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"/>"#).unwrap();
+        assert_eq!((sm.kind, sm.entries.len()), (SitemapKind::UrlSet, 0));
+        assert_eq!(parse_sitemap_bytes(b"<sitemapindex/>").unwrap().kind, SitemapKind::Index);
+        assert_eq!(parse_sitemap_bytes(b"<html/>").unwrap_err().reason, "wrong_format");
+    }
+
+    #[test]
+    fn test_gzip_payload() {
+        let mut enc = GzEncoder::new(Vec::new(), Compression::default());
+        enc.write_all(URLSET.as_bytes()).unwrap();
+        let sm = parse_sitemap_bytes(&enc.finish().unwrap()).unwrap();
+        assert_eq!(sm.entries.len(), 2);
+    }
+
+    #[test]
+    fn test_not_a_sitemap() {
+        assert!(parse_sitemap_bytes(b"<html><body/></html>").is_err());
+        assert!(parse_sitemap_bytes(b"").is_err());
+        assert!(parse_sitemap_bytes(&[0x1f, 0x8b, 0x00]).is_err());
+    }
+}
@@ -0,0 +1,443 @@
+//! Telegram channel exports and X (Twitter) API payloads as articles.
+//!
+//! Crisis channels (ministries, Red Cross societies, local reporters)
+//! post first on Telegram and X. Posts are mapped onto the pipeline's
+//! article fields — title, body, URL, published — plus the links,
+//! hashtags and media flags the post carried, so they go through
+//! `process_article` like any crawled page. Forwards and retweets keep
+//! the original text (X truncates it in the retweet itself) and name the
+//! original source.
+//!
+//! Formats: Telegram Desktop's `result.json` export, X API v2 responses
+//! (`data` with `includes`) and v1.1 tweet objects or lists of them.
+
+use chrono::{DateTime, Utc};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+use crate::errors::{extraction_error, unlocked, Failure};
+
+/// Titles are the post's first line, cut at a word boundary past this.
+const MAX_TITLE_CHARS: usize = 120;
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Post {
+    pub platform: &'static str,
+    pub post_id: String,
+    pub title: String,
+    pub body: String,
+    pub url: String,
+    pub published: Option<String>,
+    pub author: Option<String>,
+    pub links: Vec<String>,
+    pub hashtags: Vec<String>,
+    pub media_types: Vec<String>,
+    pub is_forward: bool,
+    pub forwarded_from: Option<String>,
+    pub original_url: Option<String>,
+}
+
+fn title_of(text: &str) -> String {
+    let line = text.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or("");
+    if line.chars().count() <= MAX_TITLE_CHARS {
+        return line.to_string();
+    }
+    let cut: String = line.chars().take(MAX_TITLE_CHARS).collect();
+    let cut = match cut.rfind(char::is_whitespace) {
+        Some(i) if i > MAX_TITLE_CHARS / 2 => &cut[..i],
+        _ => &cut,
+    };
+    format!("{}…", cut.trim_end())
+}
+
+fn iso(time: DateTime<Utc>) -> String {
+    time.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+fn str_field<'a>(object: &'a Value, key: &str) -> Option<&'a str> {
+    object.get(key).and_then(Value::as_str).filter(|s| !s.is_empty())
+}
+
+fn push_unique(list: &mut Vec<String>, value: String) {
+    if !list.contains(&value) {
+        list.push(value);
+    }
+}
+
+fn finish(mut post: Post) -> Post {
+    post.title = title_of(&post.body);
+    post
+}
+
+// --- Telegram ---------------------------------------------------------
+
+/// Telegram export text: a string, or a list of strings and entity
+/// objects (`{"type": "link", "text": ...}`).
+fn telegram_text(value: Option<&Value>, post: &mut Post) -> String {
+    let parts = match value {
+        Some(Value::String(s)) => return s.clone(),
+        Some(Value::Array(parts)) => parts,
+        _ => return String::new(),
+    };
+    let mut text = String::new();
+    for part in parts {
+        match part {
+            Value::String(s) => text.push_str(s),
+            Value::Object(entity) => {
+                let shown = entity.get("text").and_then(Value::as_str).unwrap_or("");
+                text.push_str(shown);
+                match entity.get("type").and_then(Value::as_str) {
+                    Some("link") => push_unique(&mut post.links, shown.to_string()),
+                    Some("text_link") => {
+                        if let Some(href) = entity.get("href").and_then(Value::as_str) {
+                            push_unique(&mut post.links, href.to_string());
+                        }
+                    }
+                    Some("hashtag") => push_unique(&mut post.hashtags, shown.trim_start_matches('#').to_string()),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+    text
+}
+
+fn telegram_post(message: &Value, channel_id: Option<i64>, channel: Option<&str>, name: Option<&str>) -> Option<Post> {
+    if message.get("type").and_then(Value::as_str) != Some("message") {
+        return None;
+    }
+    let id = message.get("id").and_then(Value::as_i64)?;
+    let mut post = Post { platform: "telegram", post_id: id.to_string(), ..Post::default() };
+    post.body = telegram_text(message.get("text"), &mut post);
+    if let Some(Value::String(caption)) = message.get("caption") {
+        post.body = format!("{}\n{caption}", post.body).trim().to_string();
+    }
+    post.url = match (channel, channel_id) {
+        (Some(username), _) => format!("https://t.me/{}/{id}", username.trim_start_matches('@')),
+        (None, Some(cid)) => format!("https://t.me/c/{cid}/{id}"),
+        (None, None) => format!("https://t.me/c/0/{id}"),
+    };
+    // `date` is in the exporter's local time; `date_unixtime` is UTC.
+    post.published = str_field(message, "date_unixtime")
+        .and_then(|s| s.parse::<i64>().ok())
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+        .map(iso);
+    post.author = str_field(message, "from").or(name).map(String::from);
+    if message.get("photo").is_some() {
+        post.media_types.push("photo".into());
+    }
+    if let Some(kind) = str_field(message, "media_type") {
+        push_unique(&mut post.media_types, kind.to_string());
+    } else if message.get("file").is_some() {
+        push_unique(&mut post.media_types, "file".into());
+    }
+    if let Some(source) = str_field(message, "forwarded_from") {
+        post.is_forward = true;
+        post.forwarded_from = Some(source.to_string());
+    }
+    (!post.body.is_empty() || !post.media_types.is_empty()).then(|| finish(post))
+}
+
+fn parse_telegram(export: &Map<String, Value>, channel: Option<&str>) -> Vec<Post> {
+    let root = Value::Object(export.clone());
+    let name = str_field(&root, "name");
+    let channel_id = root.get("id").and_then(Value::as_i64);
+    let messages = root.get("messages").and_then(Value::as_array).map(Vec::as_slice).unwrap_or_default();
+    messages.iter().filter_map(|m| telegram_post(m, channel_id, channel, name)).collect()
+}
+
+// --- X / Twitter ------------------------------------------------------
+
+fn tweet_url(username: Option<&str>, id: &str) -> String {
+    match username {
+        Some(user) => format!("https://x.com/{user}/status/{id}"),
+        None => format!("https://x.com/i/web/status/{id}"),
+    }
+}
+
+fn tweet_time(raw: Option<&str>) -> Option<String> {
+    let raw = raw?;
+    DateTime::parse_from_rfc3339(raw)
+        .or_else(|_| DateTime::parse_from_str(raw, "%a %b %d %H:%M:%S %z %Y"))
+        .ok()
+        .map(|t| iso(t.with_timezone(&Utc)))
+}
+
+/// Expanded links and hashtags from a tweet's `entities`.
+fn tweet_entities(entities: Option<&Value>, post: &mut Post) {
+    let Some(entities) = entities else { return };
+    for link in entities.get("urls").and_then(Value::as_array).into_iter().flatten() {
+        if let Some(url) = str_field(link, "unwound_url").or(str_field(link, "expanded_url")).or(str_field(link, "url")) {
+            // Links to the tweet's own media aren't outbound links.
+            if !url.contains("/photo/") && !url.contains("/video/") {
+                push_unique(&mut post.links, url.to_string());
+            }
+        }
+    }
+    for tag in entities.get("hashtags").and_then(Value::as_array).into_iter().flatten() {
+        if let Some(tag) = str_field(tag, "tag").or(str_field(tag, "text")) {
+            push_unique(&mut post.hashtags, tag.to_string());
+        }
+    }
+}
+
+/// Lookups from a v2 response's `includes`.
+#[derive(Default)]
+struct Includes<'a> {
+    users: HashMap<&'a str, &'a str>,
+    tweets: HashMap<&'a str, &'a Value>,
+    media: HashMap<&'a str, &'a str>,
+}
+
+impl<'a> Includes<'a> {
+    fn new(includes: Option<&'a Value>) -> Self {
+        let mut out = Self::default();
+        let Some(includes) = includes else { return out };
+        let list = |key: &str| includes.get(key).and_then(Value::as_array).into_iter().flatten();
+        for user in list("users") {
+            if let (Some(id), Some(name)) = (str_field(user, "id"), str_field(user, "username")) {
+                out.users.insert(id, name);
+            }
+        }
+        for tweet in list("tweets") {
+            if let Some(id) = str_field(tweet, "id") {
+                out.tweets.insert(id, tweet);
+            }
+        }
+        for media in list("media") {
+            if let (Some(key), Some(kind)) = (str_field(media, "media_key"), str_field(media, "type")) {
+                out.media.insert(key, kind);
+            }
+        }
+        out
+    }
+}
+
+fn v2_text(tweet: &Value) -> &str {
+    tweet.get("note_tweet").and_then(|n| str_field(n, "text")).or(str_field(tweet, "text")).unwrap_or("")
+}
+
+fn v2_post(tweet: &Value, includes: &Includes<'_>) -> Option<Post> {
+    let id = str_field(tweet, "id")?;
+    let author = str_field(tweet, "author_id").and_then(|a| includes.users.get(a).copied());
+    let mut post = Post {
+        platform: "x",
+        post_id: id.to_string(),
+        url: tweet_url(author, id),
+        published: tweet_time(str_field(tweet, "created_at")),
+        author: author.map(String::from),
+        body: v2_text(tweet).to_string(),
+        ..Post::default()
+    };
+    tweet_entities(tweet.get("entities"), &mut post);
+    let keys = tweet.get("attachments").and_then(|a| a.get("media_keys")).and_then(Value::as_array);
+    for key in keys.into_iter().flatten().filter_map(Value::as_str) {
+        push_unique(&mut post.media_types, includes.media.get(key).copied().unwrap_or("media").to_string());
+    }
+    let references = tweet.get("referenced_tweets").and_then(Value::as_array).into_iter().flatten();
+    for reference in references {
+        if str_field(reference, "type") != Some("retweeted") {
+            continue;
+        }
+        let original_id = str_field(reference, "id")?;
+        post.is_forward = true;
+        let original = includes.tweets.get(original_id);
+        let original_author = original
+            .and_then(|t| str_field(t, "author_id"))
+            .and_then(|a| includes.users.get(a).copied());
+        post.forwarded_from = original_author.map(String::from);
+        post.original_url = Some(tweet_url(original_author, original_id));
+        if let Some(original) = original {
+            post.body = v2_text(original).to_string();
+            tweet_entities(original.get("entities"), &mut post);
+        }
+    }
+    Some(finish(post))
+}
+
+fn v1_post(tweet: &Value) -> Option<Post> {
+    let id = str_field(tweet, "id_str")
+        .map(String::from)
+        .or_else(|| tweet.get("id").and_then(Value::as_u64).map(|id| id.to_string()))?;
+    let user = tweet.get("user").and_then(|u| str_field(u, "screen_name"));
+    let text = |t: &Value| -> String {
+        t.get("extended_tweet")
+            .and_then(|e| str_field(e, "full_text"))
+            .or(str_field(t, "full_text"))
+            .or(str_field(t, "text"))
+            .unwrap_or("")
+            .to_string()
+    };
+    let mut post = Post {
+        platform: "x",
+        url: tweet_url(user, &id),
+        post_id: id,
+        published: tweet_time(str_field(tweet, "created_at")),
+        author: user.map(String::from),
+        body: text(tweet),
+        ..Post::default()
+    };
+    let source = match tweet.get("retweeted_status") {
+        Some(original) if original.is_object() => {
+            let original_user = original.get("user").and_then(|u| str_field(u, "screen_name"));
+            post.is_forward = true;
+            post.forwarded_from = original_user.map(String::from);
+            post.original_url = str_field(original, "id_str").map(|oid| tweet_url(original_user, oid));
+            post.body = text(original);
+            original
+        }
+        _ => tweet,
+    };
+    tweet_entities(source.get("entities"), &mut post);
+    let media = source
+        .get("extended_entities")
+        .or(source.get("entities"))
+        .and_then(|e| e.get("media"))
+        .and_then(Value::as_array);
+    for item in media.into_iter().flatten() {
+        push_unique(&mut post.media_types, str_field(item, "type").unwrap_or("media").to_string());
+    }
+    Some(finish(post))
+}
+
+pub(crate) fn parse_posts(text: &str, channel: Option<&str>) -> Result<Vec<Post>, Failure> {
+    let value: Value = serde_json::from_str(text).map_err(|e| format!("malformed post JSON: {e}"))?;
+    match &value {
+        Value::Object(export) if export.get("messages").is_some_and(Value::is_array) => Ok(parse_telegram(export, channel)),
+        Value::Object(response) if response.contains_key("data") => {
+            let includes = Includes::new(response.get("includes"));
+            Ok(match &response["data"] {
+                Value::Array(tweets) => tweets.iter().filter_map(|t| v2_post(t, &includes)).collect(),
+                tweet => v2_post(tweet, &includes).into_iter().collect(),
+            })
+        }
+        Value::Object(tweet) if tweet.contains_key("id_str") || tweet.contains_key("user") => {
+            Ok(v1_post(&value).into_iter().collect())
+        }
+        Value::Array(tweets) => Ok(tweets.iter().filter_map(v1_post).collect()),
+        _ => Err(Failure::new(
+            "wrong_format",
+            "not a Telegram export or X payload: no messages, data or tweet fields",
+        )),
+    }
+}
+
+fn post_dict<'py>(py: Python<'py>, post: &Post) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new_bound(py);
+    dict.set_item("title", &post.title)?;
+    dict.set_item("body", &post.body)?;
+    dict.set_item("url", &post.url)?;
+    dict.set_item("published", &post.published)?;
+    dict.set_item("platform", post.platform)?;
+    dict.set_item("post_id", &post.post_id)?;
+    dict.set_item("author", &post.author)?;
+    dict.set_item("links", &post.links)?;
+    dict.set_item("hashtags", &post.hashtags)?;
+    dict.set_item("has_media", !post.media_types.is_empty())?;
+    dict.set_item("media_types", &post.media_types)?;
+    dict.set_item("is_forward", post.is_forward)?;
+    dict.set_item("forwarded_from", &post.forwarded_from)?;
+    dict.set_item("original_url", &post.original_url)?;
+    Ok(dict)
+}
+
+/// Parse a Telegram channel export or X API payload into article records.
+///
+/// Accepts Telegram Desktop's JSON export (``result.json``), X API v2
+/// responses (``{"data": ..., "includes": ...}``) and v1.1 tweet objects
+/// or lists. Service messages are skipped. Forwards and retweets carry
+/// the original post's full text, with ``is_forward`` set.
+///
+/// Parameters
+/// ----------
+/// text : str
+///     The JSON payload.
+/// channel : str | None
+///     Public username of an exported Telegram channel, for ``t.me``
+///     post links; without it links use the private ``t.me/c/<id>`` form.
+///
+/// Returns
+/// -------
+/// list[dict]
+///     One dict per post, in payload order: ``title`` (first line of the
+///     text, shortened), ``body``, ``url``, ``published`` (UTC ISO 8601)
+///     — the ``process_article`` arguments — plus ``platform``
+///     (``"telegram"`` or ``"x"``), ``post_id``, ``author``, ``links``
+///     (expanded), ``hashtags`` (without ``#``), ``has_media``,
+///     ``media_types`` (e.g. ``["photo"]``), ``is_forward``,
+///     ``forwarded_from`` (channel name or username) and
+///     ``original_url`` (the retweeted post). Missing values are None.
+///
+/// Raises
+/// ------
+/// ExtractionError
+///     If the JSON is malformed (``reason`` ``"malformed"``) or neither
+///     format (``"wrong_format"``). A ``ValueError`` subclass.
+#[pyfunction]
+#[pyo3(signature = (text, channel=None))]
+pub fn parse_social_posts(py: Python<'_>, text: &str, channel: Option<&str>) -> PyResult<Py<PyList>> {
+    let posts = unlocked(py, "parse_social_posts", || parse_posts(text, channel))?
+        .map_err(|e| extraction_error("social", e))?;
+    let list = PyList::empty_bound(py);
+    for post in &posts {
+        list.append(post_dict(py, post)?)?;
+    }
+    Ok(list.unbind())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_telegram_export() {
+        let export = r##"{"name": "Mozambique Red Cross", "type": "public_channel", "id": 1234, "messages": [
+            {"id": 1, "type": "service", "action": "create_channel"},
+            {"id": 2, "type": "message", "date": "2023-03-12T12:00:00", "date_unixtime": "1678615200",
+             "from": "Mozambique Red Cross", "photo": "photos/1.jpg",
+             "text": ["Cyclone Freddy: 5,000 people displaced in Quelimane. ",
+                      {"type": "hashtag", "text": "#Freddy"}, " ",
+                      {"type": "text_link", "text": "Appeal", "href": "https://ifrc.org/appeal"}]},
+            {"id": 3, "type": "message", "date_unixtime": "1678618800", "forwarded_from": "INGD Moçambique",
+             "text": "Alerta vermelho"}
+        ]}"##;
+        let posts = parse_posts(export, Some("@cvmoz")).unwrap();
+        assert_eq!(posts.len(), 2);
+        let post = &posts[0];
+        assert_eq!(post.url, "https://t.me/cvmoz/2");
+        assert_eq!(post.title, "Cyclone Freddy: 5,000 people displaced in Quelimane. #Freddy Appeal");
+        assert_eq!(post.published.as_deref(), Some("2023-03-12T10:00:00Z"));
+        assert_eq!((post.links.clone(), post.hashtags.clone()), (vec!["https://ifrc.org/appeal".to_string()], vec!["Freddy".to_string()]));
+        assert_eq!(post.media_types, vec!["photo"]);
+        assert!(posts[1].is_forward && posts[1].forwarded_from.as_deref() == Some("INGD Moçambique"));
+        assert_eq!(parse_posts(export, None).unwrap()[1].url, "https://t.me/c/1234/3");
+    }
+
+    #[test]
+    fn test_x_payloads() {
+        let v2 = r#"{"data": [{"id": "20", "text": "RT @ocha: Floods in Beira…", "author_id": "1",
+              "created_at": "2024-01-05T08:30:00.000Z", "referenced_tweets": [{"type": "retweeted", "id": "10"}]}],
+            "includes": {"users": [{"id": "1", "username": "relief"}, {"id": "2", "username": "ocha"}],
+              "tweets": [{"id": "10", "author_id": "2", "text": "Floods in Beira: 12 dead, 3,000 displaced https://t.co/x",
+                "entities": {"urls": [{"url": "https://t.co/x", "expanded_url": "https://reliefweb.int/r/1"}]},
+                "attachments": {"media_keys": ["m1"]}}],
+              "media": [{"media_key": "m1", "type": "photo"}]}}"#;
+        let post = &parse_posts(v2, None).unwrap()[0];
+        assert_eq!(post.url, "https://x.com/relief/status/20");
+        assert_eq!(post.body, "Floods in Beira: 12 dead, 3,000 displaced https://t.co/x");
+        assert_eq!(post.original_url.as_deref(), Some("https://x.com/ocha/status/10"));
+        assert_eq!(post.links, vec!["https://reliefweb.int/r/1"]);
+        assert_eq!(post.published.as_deref(), Some("2024-01-05T08:30:00Z"));
+
+        let v1 = r#"[{"id_str": "30", "created_at": "Wed Oct 10 20:19:24 +0000 2018", "user": {"screen_name": "wfp"},
+            "full_text": "Food distributions resumed", "extended_entities": {"media": [{"type": "video"}]}}]"#;
+        let post = &parse_posts(v1, None).unwrap()[0];
+        assert_eq!((post.url.as_str(), post.published.as_deref()), ("https://x.com/wfp/status/30", Some("2018-10-10T20:19:24Z")));
+        assert_eq!(post.media_types, vec!["video"]);
+        assert_eq!(parse_posts("{\"foo\": 1}", None).unwrap_err().reason, "wrong_format");
+        assert_eq!(parse_posts("{", None).unwrap_err().reason, "malformed");
+    }
+}
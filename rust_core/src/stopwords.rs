@@ -0,0 +1,261 @@
+//! Per-language stopword lists.
+//!
+//! Keyphrase extraction, slug similarity, place linking, text quality and
+//! summarization all need to tell function words from content words.
+//! They share these lists — English, French, Portuguese, Spanish and
+//! Swahili, each with the reporting words common in news copy — instead
+//! of each carrying its own. `configure_stopwords` extends or replaces a
+//! language's list at runtime (or adds a language).
+//!
+//! Words that are also common place names ("Leo", "Kama", "Mali") are
+//! left out, since place linking skips single-word stopword mentions.
+
+use once_cell::sync::Lazy;
+#[cfg(feature = "python")]
+use pyo3::exceptions::PyValueError;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::RwLock;
+
+#[cfg(feature = "python")]
+use crate::errors::guarded;
+
+const ENGLISH: &[&str] = &[
+    "a", "about", "above", "after", "again", "against", "all", "also", "am", "an", "and",
+    "any", "are", "as", "at", "be", "because", "been", "before", "being", "below",
+    "between", "both", "but", "by", "can", "could", "did", "do", "does", "doing", "down",
+    "during", "each", "few", "for", "from", "further", "had", "has", "have", "having", "he",
+    "her", "here", "hers", "him", "his", "how", "i", "if", "in", "into", "is", "it", "its",
+    "itself", "just", "last", "least", "many", "may", "more", "most", "much", "must", "my",
+    "near", "new", "no", "nor", "not", "now", "of", "off", "on", "once", "one", "only",
+    "or", "other", "our", "out", "over", "own", "per", "said", "same", "says", "she",
+    "should", "since", "so", "some", "such", "than", "that", "the", "their", "them",
+    "then", "there", "these", "they", "this", "those", "through", "to", "too", "under",
+    "until", "up", "very", "was", "we", "were", "what", "when", "where", "which", "while",
+    "who", "whom", "why", "will", "with", "within", "would", "you", "your", "according",
+    "including", "reported", "week", "year", "yesterday", "today",
+];
+
+const FRENCH: &[&str] = &[
+    "à", "au", "aux", "avec", "ce", "ces", "cette", "dans", "de", "des", "du", "elle",
+    "elles", "en", "est", "et", "été", "être", "il", "ils", "la", "le", "les", "leur",
+    "leurs", "lui", "mais", "ne", "ni", "nous", "on", "ont", "ou", "où", "par", "pas",
+    "plus", "pour", "qu", "que", "qui", "sa", "sans", "se", "selon", "ses", "son", "sont",
+    "sur", "un", "une", "vers", "y", "aussi", "depuis", "entre", "fait",
+];
+
+const PORTUGUESE: &[&str] = &[
+    "ao", "aos", "as", "com", "como", "da", "das", "do", "dos", "e", "ela", "elas", "ele",
+    "eles", "em", "entre", "era", "foi", "foram", "há", "isso", "já", "mais", "mas", "na",
+    "nas", "no", "nos", "num", "numa", "o", "os", "ou", "para", "pela", "pelas", "pelo",
+    "pelos", "por", "que", "se", "segundo", "sem", "ser", "seu", "seus", "sua", "suas",
+    "são", "também", "um", "uma", "à", "às", "é", "está", "estão",
+];
+
+const SPANISH: &[&str] = &[
+    "a", "al", "ante", "año", "ayer", "con", "cuando", "de", "del", "desde", "dijo",
+    "donde", "durante", "el", "él", "ella", "ellas", "ellos", "en", "entre", "es", "esa",
+    "esas", "ese", "esos", "esta", "está", "están", "estas", "este", "estos", "fue",
+    "fueron", "ha", "han", "hasta", "hay", "hoy", "la", "las", "le", "les", "lo", "los",
+    "más", "muy", "ni", "no", "nos", "o", "otra", "otras", "otro", "otros", "para", "pero",
+    "por", "porque", "que", "se", "según", "sin", "sobre", "son", "su", "sus", "también",
+    "todo", "todos", "tras", "un", "una", "unas", "unos", "y", "ya",
+];
+
+const SWAHILI: &[&str] = &[
+    "alisema", "amesema", "ambao", "ambayo", "ambaye", "ambazo", "au", "baada", "bado",
+    "cha", "hata", "hii", "hiyo", "hizi", "hizo", "hivyo", "huku", "ili", "jana", "juu",
+    "kabla", "katika", "kati", "kila", "kuna", "kuwa", "kwa", "kwamba", "kwenye", "la",
+    "lakini", "mwaka", "na", "ndani", "ni", "nje", "pamoja", "pia", "sana", "si", "siku",
+    "tangu", "tu", "vya", "wa", "wakati", "walisema", "wamesema", "wote", "ya", "yote",
+    "za", "zaidi", "zote",
+];
+
+/// Built-in languages, ISO 639-1.
+const BUILT_IN: &[(&str, &[&str])] =
+    &[("en", ENGLISH), ("fr", FRENCH), ("pt", PORTUGUESE), ("es", SPANISH), ("sw", SWAHILI)];
+
+#[derive(Debug, Default)]
+struct Lists {
+    by_lang: BTreeMap<String, HashSet<String>>,
+    /// Union of every language's list.
+    all: HashSet<String>,
+}
+
+impl Lists {
+    fn built_in() -> Self {
+        let mut lists = Self::default();
+        for lang in BUILT_IN.iter().map(|(lang, _)| *lang) {
+            lists.by_lang.insert(lang.to_string(), built_in(lang).unwrap_or_default());
+        }
+        lists.rebuild();
+        lists
+    }
+
+    fn rebuild(&mut self) {
+        self.all = self.by_lang.values().flatten().cloned().collect();
+    }
+}
+
+fn built_in(lang: &str) -> Option<HashSet<String>> {
+    BUILT_IN
+        .iter()
+        .find(|(l, _)| *l == lang)
+        .map(|(_, words)| words.iter().map(|w| w.to_string()).collect())
+}
+
+static LISTS: Lazy<RwLock<Lists>> = Lazy::new(|| RwLock::new(Lists::built_in()));
+
+/// True for a lowercased stopword of any language.
+pub(crate) fn is_stopword(word: &str) -> bool {
+    LISTS.read().unwrap_or_else(|e| e.into_inner()).all.contains(word)
+}
+
+/// True for a lowercased stopword of `lang`, or of any language when
+/// `lang` is None or has no list.
+pub(crate) fn is_stopword_in(word: &str, lang: Option<&str>) -> bool {
+    let lists = LISTS.read().unwrap_or_else(|e| e.into_inner());
+    match lang.and_then(|l| lists.by_lang.get(l)) {
+        Some(words) => words.contains(word),
+        None => lists.all.contains(word),
+    }
+}
+
+/// Stopword count per language, for `runtime_info`.
+pub(crate) fn inventory() -> Vec<(String, usize)> {
+    let lists = LISTS.read().unwrap_or_else(|e| e.into_inner());
+    lists.by_lang.iter().map(|(lang, words)| (lang.clone(), words.len())).collect()
+}
+
+fn normalize_lang(lang: &str) -> String {
+    lang.trim().to_lowercase()
+}
+
+/// Stopwords of a language, sorted.
+///
+/// Parameters
+/// ----------
+/// lang : str | None
+///     ISO 639-1 code (built in: ``en``, ``fr``, ``pt``, ``es``, ``sw``).
+///     None returns every language's stopwords together.
+///
+/// Returns
+/// -------
+/// list[str]
+///     Lowercased words; empty for a language without a list.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (lang=None))]
+pub fn stopwords(lang: Option<&str>) -> PyResult<Vec<String>> {
+    guarded("stopwords", || {
+        let lists = LISTS.read().unwrap_or_else(|e| e.into_inner());
+        let mut words: Vec<String> = match lang {
+            Some(lang) => lists.by_lang.get(&normalize_lang(lang)).into_iter().flatten().cloned().collect(),
+            None => lists.all.iter().cloned().collect(),
+        };
+        words.sort_unstable();
+        Ok(words)
+    })
+}
+
+/// Whether a word is a stopword (case-insensitive).
+///
+/// Parameters
+/// ----------
+/// word : str
+///     The word.
+/// lang : str | None
+///     Check this language's list only. Default: any language.
+#[cfg(feature = "python")]
+#[pyfunction(name = "is_stopword")]
+#[pyo3(signature = (word, lang=None))]
+pub fn py_is_stopword(word: &str, lang: Option<&str>) -> PyResult<bool> {
+    guarded("is_stopword", || Ok(is_stopword_in(&word.trim().to_lowercase(), lang.map(normalize_lang).as_deref())))
+}
+
+/// Extend or replace a language's stopword list, process-wide.
+///
+/// Parameters
+/// ----------
+/// lang : str
+///     ISO 639-1 code; a new code adds a language.
+/// words : list[str] | None
+///     Words to add (lowercased). ``None`` restores the built-in list
+///     (or removes a language that has none).
+/// replace : bool
+///     Replace the language's list instead of adding to it. Default False.
+///
+/// Raises
+/// ------
+/// ValueError
+///     If the language code or a word is empty; the lists are left
+///     unchanged.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (lang, words=None, replace=false))]
+pub fn configure_stopwords(lang: &str, words: Option<Vec<String>>, replace: bool) -> PyResult<()> {
+    guarded("configure_stopwords", || {
+        let lang = normalize_lang(lang);
+        if lang.is_empty() {
+            return Err(PyValueError::new_err("empty language code"));
+        }
+        if words.iter().flatten().any(|w| w.trim().is_empty()) {
+            return Err(PyValueError::new_err(format!("empty stopword for {lang:?}")));
+        }
+        let mut lists = LISTS.write().unwrap_or_else(|e| e.into_inner());
+        match words {
+            None => match built_in(&lang) {
+                Some(words) => {
+                    lists.by_lang.insert(lang, words);
+                }
+                None => {
+                    lists.by_lang.remove(&lang);
+                }
+            },
+            Some(words) => {
+                let list = lists.by_lang.entry(lang).or_default();
+                if replace {
+                    list.clear();
+                }
+                list.extend(words.iter().map(|w| w.trim().to_lowercase()));
+            }
+        }
+        lists.rebuild();
+        Ok(())
+    })
+}
+
+#[cfg(all(test, feature = "python"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_built_in_lists() {
+        let lists = Lists::built_in();
+        assert_eq!(lists.by_lang.len(), 5);
+        for (lang, word) in [("en", "the"), ("fr", "selon"), ("pt", "também"), ("es", "según"), ("sw", "kwamba")] {
+            assert!(lists.by_lang[lang].contains(word), "{lang}: {word}");
+            assert!(lists.all.contains(word));
+        }
+        assert!(!lists.by_lang["en"].contains("selon"));
+        assert!(!lists.all.contains("mali"));
+    }
+
+    #[test]
+    fn test_configure_and_query() {
+        // A language of its own, so other tests' lists are untouched
+        configure_stopwords("XX", Some(vec!["Foo".into(), "bar ".into()]), false).unwrap();
+        assert!(py_is_stopword("FOO", Some("xx")).unwrap());
+        assert!(is_stopword("bar"));
+        assert!(!is_stopword_in("the", Some("xx")));
+        assert!(is_stopword_in("the", Some("zz")));
+        configure_stopwords("xx", Some(vec!["baz".into()]), true).unwrap();
+        assert_eq!(stopwords(Some("xx")).unwrap(), vec!["baz"]);
+        assert!(configure_stopwords("xx", Some(vec![" ".into()]), false).is_err());
+        assert!(configure_stopwords("", None, false).is_err());
+        configure_stopwords("xx", None, false).unwrap();
+        assert!(stopwords(Some("xx")).unwrap().is_empty());
+        assert!(!is_stopword("baz"));
+    }
+}
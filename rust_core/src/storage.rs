@@ -0,0 +1,590 @@
+//! Pluggable storage for stateful components.
+//!
+//! `CanonicalUrlSet`, `ContentIndex`, `Frontier` and `BloomFilter` keep
+//! their state in memory by default, so a restarted crawler forgets what
+//! it had seen and queued. Each takes a storage spec instead:
+//!
+//! - `memory`: the component's own in-memory structures (the default);
+//! - `sqlite:PATH`: a SQLite database in WAL mode, so readers in other
+//!   processes aren't blocked and state can outgrow RAM. Writes are
+//!   grouped into transactions of `batch_size` that commit when full, on
+//!   `flush()` and when the component is dropped. An open batch holds the
+//!   database's write lock, so each component wants a file of its own;
+//! - `mmap:PATH`: a memory-mapped table (see `mapped`), shared through
+//!   the page cache and opened instantly. Changes are held in memory and
+//!   written back as a new table on `flush()`, so it suits state that is
+//!   read far more than written.
+//!
+//! When a component is given no spec it uses the process default set by
+//! `configure_storage` or a config file's `[storage]` section: a backend
+//! and a directory, with one file per component kind. Deployments switch
+//! persistence model in configuration rather than in code.
+//!
+//! Every backend implements `Storage`, an ordered byte-key / byte-value
+//! map. Hashes used as keys are 16-byte big-endian blobs.
+
+use once_cell::sync::Lazy;
+use pyo3::exceptions::{PyOSError, PyValueError};
+use pyo3::prelude::*;
+#[cfg(feature = "sqlite")]
+use rusqlite::{Connection, OptionalExtension};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+#[cfg(feature = "sqlite")]
+use std::time::Duration;
+
+use crate::mapped::{write_table, Table};
+use crate::errors::guarded;
+
+/// A stored key and its value.
+pub(crate) type Entry = (Vec<u8>, Vec<u8>);
+
+/// Byte-keyed map behind a stateful component.
+pub(crate) trait Storage: Send {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, String>;
+
+    /// Store `value` under `key`; true if the key was new.
+    fn put(&mut self, key: &[u8], value: &[u8]) -> Result<bool, String>;
+
+    /// Remove `key`; true if it was present.
+    fn remove(&mut self, key: &[u8]) -> Result<bool, String>;
+
+    fn contains(&self, key: &[u8]) -> Result<bool, String> {
+        Ok(self.get(key)?.is_some())
+    }
+
+    /// Entries whose key starts with `prefix`, in key order.
+    fn iterate(&self, prefix: &[u8]) -> Result<Vec<Entry>, String>;
+
+    /// Number of entries whose key starts with `prefix`.
+    fn count(&self, prefix: &[u8]) -> Result<usize, String> {
+        Ok(self.iterate(prefix)?.len())
+    }
+
+    fn clear(&mut self) -> Result<(), String>;
+
+    /// Make writes so far durable.
+    fn flush(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// The spec this storage was opened from.
+    fn spec(&self) -> String;
+}
+
+/// In-memory map; contents end with the process.
+#[derive(Default)]
+pub(crate) struct MemoryStorage {
+    entries: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+impl Storage for MemoryStorage {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, String> {
+        Ok(self.entries.get(key).cloned())
+    }
+
+    fn put(&mut self, key: &[u8], value: &[u8]) -> Result<bool, String> {
+        Ok(self.entries.insert(key.to_vec(), value.to_vec()).is_none())
+    }
+
+    fn remove(&mut self, key: &[u8]) -> Result<bool, String> {
+        Ok(self.entries.remove(key).is_some())
+    }
+
+    fn iterate(&self, prefix: &[u8]) -> Result<Vec<Entry>, String> {
+        Ok(self
+            .entries
+            .range(prefix.to_vec()..)
+            .take_while(|(k, _)| k.starts_with(prefix))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect())
+    }
+
+    fn clear(&mut self) -> Result<(), String> {
+        self.entries.clear();
+        Ok(())
+    }
+
+    fn spec(&self) -> String {
+        "memory".into()
+    }
+}
+
+/// A mapped table plus the changes made since it was written; `None`
+/// marks a removed key.
+pub(crate) struct MappedStorage {
+    path: String,
+    base: Option<Table>,
+    changes: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+    /// The base no longer counts (after `clear`).
+    cleared: bool,
+}
+
+impl MappedStorage {
+    pub(crate) fn open(path: &str) -> Result<Self, String> {
+        let base = match Table::open(path) {
+            Ok(table) => Some(table),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.to_string()),
+        };
+        Ok(Self { path: path.to_string(), base, changes: BTreeMap::new(), cleared: false })
+    }
+
+    fn base(&self) -> Option<&Table> {
+        self.base.as_ref().filter(|_| !self.cleared)
+    }
+}
+
+impl Storage for MappedStorage {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, String> {
+        Ok(match self.changes.get(key) {
+            Some(change) => change.clone(),
+            None => self.base().and_then(|t| t.get(key)).map(<[u8]>::to_vec),
+        })
+    }
+
+    fn put(&mut self, key: &[u8], value: &[u8]) -> Result<bool, String> {
+        let new = !self.contains(key)?;
+        self.changes.insert(key.to_vec(), Some(value.to_vec()));
+        Ok(new)
+    }
+
+    fn remove(&mut self, key: &[u8]) -> Result<bool, String> {
+        let present = self.contains(key)?;
+        if present {
+            self.changes.insert(key.to_vec(), None);
+        }
+        Ok(present)
+    }
+
+    fn iterate(&self, prefix: &[u8]) -> Result<Vec<Entry>, String> {
+        let mut merged: BTreeMap<Vec<u8>, Vec<u8>> = BTreeMap::new();
+        if let Some(table) = self.base() {
+            for key in table.keys_with_prefix(prefix) {
+                let value = table.get(&key).unwrap_or_default().to_vec();
+                merged.insert(key, value);
+            }
+        }
+        let changes = self.changes.range(prefix.to_vec()..).take_while(|(k, _)| k.starts_with(prefix));
+        for (key, change) in changes {
+            match change {
+                Some(value) => merged.insert(key.clone(), value.clone()),
+                None => merged.remove(key),
+            };
+        }
+        Ok(merged.into_iter().collect())
+    }
+
+    fn count(&self, prefix: &[u8]) -> Result<usize, String> {
+        let base = self.base();
+        let mut count = match base {
+            Some(table) if prefix.is_empty() => table.len(),
+            Some(table) => table.keys_with_prefix(prefix).len(),
+            None => 0,
+        };
+        let changes = self.changes.range(prefix.to_vec()..).take_while(|(k, _)| k.starts_with(prefix));
+        for (key, change) in changes {
+            match (base.is_some_and(|t| t.get(key).is_some()), change.is_some()) {
+                (false, true) => count += 1,
+                (true, false) => count -= 1,
+                _ => {}
+            }
+        }
+        Ok(count)
+    }
+
+    fn clear(&mut self) -> Result<(), String> {
+        self.changes.clear();
+        self.cleared = true;
+        Ok(())
+    }
+
+    /// Write the merged contents as a new table and map it.
+    fn flush(&mut self) -> Result<(), String> {
+        if self.changes.is_empty() && !self.cleared {
+            return Ok(());
+        }
+        let entries = self.iterate(b"")?.into_iter().collect();
+        write_table(&self.path, &entries)?;
+        self.base = Some(Table::open(&self.path).map_err(|e| e.to_string())?);
+        self.changes.clear();
+        self.cleared = false;
+        Ok(())
+    }
+
+    fn spec(&self) -> String {
+        format!("mmap:{}", self.path)
+    }
+}
+
+impl Drop for MappedStorage {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            tracing::warn!(path = %self.path, error = %e, "storage writes lost on close");
+        }
+    }
+}
+
+/// How long to wait for another process's batch to commit.
+#[cfg(feature = "sqlite")]
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[cfg(feature = "sqlite")]
+const SQLITE_SCHEMA: &str = "CREATE TABLE IF NOT EXISTS entries (key BLOB PRIMARY KEY, value BLOB NOT NULL) WITHOUT ROWID;";
+
+/// A SQLite database in WAL mode with batched writes.
+#[cfg(feature = "sqlite")]
+pub(crate) struct SqliteStorage {
+    conn: Connection,
+    path: String,
+    batch_size: usize,
+    /// Writes in the open transaction.
+    pending: usize,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteStorage {
+    /// Open (or create) the database at `path`.
+    pub(crate) fn open(path: &str, batch_size: usize) -> Result<Self, String> {
+        let fail = |e: rusqlite::Error| format!("{path}: {e}");
+        let conn = Connection::open(path).map_err(fail)?;
+        conn.busy_timeout(BUSY_TIMEOUT).map_err(fail)?;
+        // journal_mode answers with the mode in effect, so it is a query
+        let mode: String = conn.query_row("PRAGMA journal_mode = WAL", [], |row| row.get(0)).map_err(fail)?;
+        if !mode.eq_ignore_ascii_case("wal") && path != ":memory:" {
+            return Err(format!("{path}: cannot enable WAL mode (journal mode is {mode})"));
+        }
+        conn.execute_batch("PRAGMA synchronous = NORMAL;").map_err(fail)?;
+        conn.execute_batch(SQLITE_SCHEMA).map_err(fail)?;
+        Ok(Self { conn, path: path.to_string(), batch_size: batch_size.max(1), pending: 0 })
+    }
+
+    fn fail(&self, e: rusqlite::Error) -> String {
+        format!("{}: {e}", self.path)
+    }
+
+    /// Run a read. Uncommitted writes of the open batch are visible.
+    fn read<T>(&self, f: impl FnOnce(&Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+        f(&self.conn).map_err(|e| self.fail(e))
+    }
+
+    /// Run one write inside the current batch, committing it once full.
+    fn write<T>(&mut self, f: impl FnOnce(&Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+        if self.conn.is_autocommit() {
+            self.conn.execute_batch("BEGIN").map_err(|e| self.fail(e))?;
+        }
+        let result = f(&self.conn).map_err(|e| self.fail(e))?;
+        self.pending += 1;
+        if self.pending >= self.batch_size {
+            Storage::flush(self)?;
+        }
+        Ok(result)
+    }
+}
+
+/// The smallest key greater than every key starting with `prefix`, if any.
+#[cfg(feature = "sqlite")]
+fn prefix_end(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut end = prefix.to_vec();
+    while let Some(last) = end.pop() {
+        if last < u8::MAX {
+            end.push(last + 1);
+            return Some(end);
+        }
+    }
+    None
+}
+
+#[cfg(feature = "sqlite")]
+impl Storage for SqliteStorage {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, String> {
+        self.read(|conn| {
+            conn.prepare_cached("SELECT value FROM entries WHERE key = ?1")?
+                .query_row([key], |row| row.get(0))
+                .optional()
+        })
+    }
+
+    fn put(&mut self, key: &[u8], value: &[u8]) -> Result<bool, String> {
+        self.write(|conn| {
+            let new = conn
+                .prepare_cached("SELECT 1 FROM entries WHERE key = ?1")?
+                .query_row([key], |_| Ok(()))
+                .optional()?
+                .is_none();
+            conn.prepare_cached("INSERT OR REPLACE INTO entries VALUES (?1, ?2)")?
+                .execute([key, value])?;
+            Ok(new)
+        })
+    }
+
+    fn remove(&mut self, key: &[u8]) -> Result<bool, String> {
+        self.write(|conn| Ok(conn.prepare_cached("DELETE FROM entries WHERE key = ?1")?.execute([key])? == 1))
+    }
+
+    fn iterate(&self, prefix: &[u8]) -> Result<Vec<Entry>, String> {
+        self.read(|conn| {
+            let row = |row: &rusqlite::Row<'_>| Ok((row.get(0)?, row.get(1)?));
+            match prefix_end(prefix) {
+                Some(end) => conn
+                    .prepare_cached("SELECT key, value FROM entries WHERE key >= ?1 AND key < ?2 ORDER BY key")?
+                    .query_map([prefix, &end], row)?
+                    .collect(),
+                None => conn
+                    .prepare_cached("SELECT key, value FROM entries WHERE key >= ?1 ORDER BY key")?
+                    .query_map([prefix], row)?
+                    .collect(),
+            }
+        })
+    }
+
+    fn count(&self, prefix: &[u8]) -> Result<usize, String> {
+        self.read(|conn| match prefix_end(prefix) {
+            Some(end) => conn
+                .prepare_cached("SELECT COUNT(*) FROM entries WHERE key >= ?1 AND key < ?2")?
+                .query_row([prefix, &end], |row| row.get(0)),
+            None => conn
+                .prepare_cached("SELECT COUNT(*) FROM entries WHERE key >= ?1")?
+                .query_row([prefix], |row| row.get(0)),
+        })
+    }
+
+    fn clear(&mut self) -> Result<(), String> {
+        self.write(|conn| conn.execute("DELETE FROM entries", [])).map(drop)
+    }
+
+    /// Commit the open batch, if any.
+    fn flush(&mut self) -> Result<(), String> {
+        if !self.conn.is_autocommit() {
+            self.conn.execute_batch("COMMIT").map_err(|e| self.fail(e))?;
+        }
+        self.pending = 0;
+        Ok(())
+    }
+
+    fn spec(&self) -> String {
+        format!("sqlite:{}", self.path)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl Drop for SqliteStorage {
+    fn drop(&mut self) {
+        if let Err(e) = Storage::flush(self) {
+            tracing::warn!(path = %self.path, error = %e, "storage writes lost on close");
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Backend {
+    Memory,
+    Sqlite,
+    Mapped,
+}
+
+impl Backend {
+    pub(crate) fn parse(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "memory" => Ok(Backend::Memory),
+            "sqlite" => Ok(Backend::Sqlite),
+            "mmap" => Ok(Backend::Mapped),
+            other => Err(format!("unknown storage backend {other:?} (expected memory, sqlite or mmap)")),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Backend::Memory => "memory",
+            Backend::Sqlite => "sqlite",
+            Backend::Mapped => "mmap",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Backend::Memory => "",
+            Backend::Sqlite => "db",
+            Backend::Mapped => "map",
+        }
+    }
+}
+
+/// Backend and directory used when a component is given no spec.
+static DEFAULT: Lazy<RwLock<(Backend, PathBuf)>> = Lazy::new(|| RwLock::new((Backend::Memory, PathBuf::new())));
+
+/// Set the process default; `dir` is where per-component files go.
+pub(crate) fn set_default(backend: Backend, dir: &Path) {
+    *DEFAULT.write().unwrap_or_else(|e| e.into_inner()) = (backend, dir.to_path_buf());
+}
+
+/// The process default's backend name and directory.
+pub(crate) fn default_backend() -> (&'static str, PathBuf) {
+    let (backend, dir) = DEFAULT.read().unwrap_or_else(|e| e.into_inner()).clone();
+    (backend.name(), dir)
+}
+
+/// Resolve a component's spec (`None` for the process default) to a
+/// backend and path. `kind` names the component's file under the
+/// default directory.
+pub(crate) fn resolve(spec: Option<&str>, kind: &str) -> Result<(Backend, Option<String>), String> {
+    let Some(spec) = spec.map(str::trim) else {
+        let (backend, dir) = DEFAULT.read().unwrap_or_else(|e| e.into_inner()).clone();
+        if backend == Backend::Memory {
+            return Ok((backend, None));
+        }
+        let path = dir.join(format!("{kind}.{}", backend.extension()));
+        return Ok((backend, Some(path.to_string_lossy().into_owned())));
+    };
+    match spec.split_once(':') {
+        None => match Backend::parse(spec)? {
+            Backend::Memory => Ok((Backend::Memory, None)),
+            backend => Err(format!(
+                "storage spec {spec:?} needs a path, e.g. \"{spec}:state/{kind}.{}\"",
+                backend.extension()
+            )),
+        },
+        Some((backend, path)) => {
+            let backend = Backend::parse(backend)?;
+            if backend == Backend::Memory || path.is_empty() {
+                return Err(format!("invalid storage spec {spec:?}"));
+            }
+            Ok((backend, Some(path.to_string())))
+        }
+    }
+}
+
+/// Open the storage a spec names. `batch_size` applies to SQLite.
+fn open(backend: Backend, path: Option<&str>, batch_size: usize) -> Result<Box<dyn Storage>, String> {
+    match (backend, path) {
+        (Backend::Memory, _) | (_, None) => Ok(Box::new(MemoryStorage::default())),
+        (Backend::Mapped, Some(path)) => Ok(Box::new(MappedStorage::open(path)?)),
+        #[cfg(feature = "sqlite")]
+        (Backend::Sqlite, Some(path)) => Ok(Box::new(SqliteStorage::open(path, batch_size)?)),
+        #[cfg(not(feature = "sqlite"))]
+        (Backend::Sqlite, Some(_)) => {
+            let _ = batch_size;
+            Err("built without SQLite support".into())
+        }
+    }
+}
+
+/// Open a component's storage, or `None` when it keeps its state in its
+/// own in-memory structures.
+pub(crate) fn open_for(spec: Option<&str>, kind: &str, batch_size: usize) -> PyResult<Option<Box<dyn Storage>>> {
+    let (backend, path) = resolve(spec, kind).map_err(PyValueError::new_err)?;
+    if backend == Backend::Memory {
+        return Ok(None);
+    }
+    open(backend, path.as_deref(), batch_size).map(Some).map_err(PyOSError::new_err)
+}
+
+/// Set where stateful components keep their state by default.
+///
+/// Applies to ``CanonicalUrlSet``, ``ContentIndex``, ``Frontier``,
+/// ``BloomFilter`` and ``RecrawlCache`` created afterwards without a
+/// ``storage`` argument. A config file's ``[storage]`` section sets the
+/// same default.
+///
+/// Parameters
+/// ----------
+/// backend : str
+///     ``"memory"`` (nothing persists), ``"sqlite"`` (a database per
+///     component, WAL mode, batched writes) or ``"mmap"`` (a memory-mapped
+///     table per component, written back on ``flush()``). Default
+///     ``"memory"``.
+/// dir : str | None
+///     Directory for the files: ``url_set``, ``content_index``,
+///     ``frontier``, ``bloom`` and ``recrawl``, with a ``.db`` or ``.map``
+///     extension.
+///     Created if missing. Default: the working directory.
+///
+/// Raises
+/// ------
+/// ValueError
+///     If the backend is unknown.
+/// OSError
+///     If the directory cannot be created.
+#[pyfunction]
+#[pyo3(signature = (backend="memory", dir=None))]
+pub fn configure_storage(backend: &str, dir: Option<&str>) -> PyResult<()> {
+    guarded("configure_storage", || {
+        let backend = Backend::parse(backend).map_err(PyValueError::new_err)?;
+        let dir = Path::new(dir.unwrap_or(""));
+        if backend != Backend::Memory && !dir.as_os_str().is_empty() {
+            std::fs::create_dir_all(dir)?;
+        }
+        set_default(backend, dir);
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("moltis_storage_{name}_{}", std::process::id()));
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{suffix}", path.display()));
+        }
+        path.to_str().unwrap().to_string()
+    }
+
+    /// The contract every backend keeps; leaves keys `a/1` and `b\xff`.
+    fn exercise(storage: &mut dyn Storage) {
+        assert!(storage.put(b"a/1", b"x").unwrap());
+        assert!(!storage.put(b"a/1", b"y").unwrap());
+        assert!(storage.put(b"a/2", b"").unwrap());
+        assert!(storage.put(b"b\xff", b"z").unwrap());
+        assert_eq!(storage.get(b"a/1").unwrap(), Some(b"y".to_vec()));
+        assert!(storage.contains(b"a/2").unwrap());
+        assert!(!storage.contains(b"a").unwrap());
+        let keys: Vec<Vec<u8>> = storage.iterate(b"a/").unwrap().into_iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, vec![b"a/1".to_vec(), b"a/2".to_vec()]);
+        assert_eq!(storage.iterate(b"b\xff").unwrap().len(), 1);
+        assert!(storage.remove(b"a/2").unwrap());
+        assert!(!storage.remove(b"a/2").unwrap());
+        assert_eq!(storage.count(b"").unwrap(), 2);
+        assert_eq!(storage.count(b"a").unwrap(), 1);
+        storage.flush().unwrap();
+    }
+
+    #[test]
+    fn test_backends_share_contract() {
+        exercise(&mut MemoryStorage::default());
+
+        let path = temp_path("map");
+        exercise(&mut MappedStorage::open(&path).unwrap());
+        let mut mapped = MappedStorage::open(&path).unwrap();
+        assert_eq!(mapped.count(b"").unwrap(), 2);
+        mapped.clear().unwrap();
+        assert_eq!(mapped.get(b"a/1").unwrap(), None);
+        mapped.put(b"c", b"1").unwrap();
+        drop(mapped);
+        assert_eq!(MappedStorage::open(&path).unwrap().iterate(b"").unwrap(), vec![(b"c".to_vec(), b"1".to_vec())]);
+
+        #[cfg(feature = "sqlite")]
+        {
+            let path = temp_path("db");
+            exercise(&mut SqliteStorage::open(&path, 2).unwrap());
+            let storage = SqliteStorage::open(&path, 2).unwrap();
+            assert_eq!(storage.get(b"b\xff").unwrap(), Some(b"z".to_vec()));
+            assert_eq!(storage.count(b"b").unwrap(), 1);
+            assert!(SqliteStorage::open("/nonexistent/dir/x.db", 2).is_err());
+            assert_eq!(prefix_end(b"a\xff"), Some(b"b".to_vec()));
+            assert_eq!(prefix_end(b"\xff"), None);
+        }
+    }
+
+    #[test]
+    fn test_resolve_specs() {
+        assert_eq!(resolve(Some("memory"), "frontier"), Ok((Backend::Memory, None)));
+        assert_eq!(resolve(Some("sqlite:/tmp/x.db"), "frontier"), Ok((Backend::Sqlite, Some("/tmp/x.db".into()))));
+        assert_eq!(resolve(Some("mmap:C:/state/x.map"), "bloom"), Ok((Backend::Mapped, Some("C:/state/x.map".into()))));
+        assert!(resolve(Some("sqlite"), "bloom").is_err());
+        assert!(resolve(Some("redis:x"), "bloom").is_err());
+        assert!(resolve(Some("memory:x"), "bloom").is_err());
+    }
+}
@@ -0,0 +1,241 @@
+//! Tropical storm name recognizer.
+//!
+//! The storm name plus its basin is the join key for cyclone event fusion.
+//! Names are recognized after a designator ("Cyclone Freddy", "Typhoon
+//! Haiyan", "ciclone Idai", "Cyclones Idai and Kenneth") and looked up in
+//! a per-basin table seeded from `config/storm_names.toml`. The designator
+//! picks the basin when a name is used in several.
+
+use once_cell::sync::Lazy;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use crate::errors::{guarded, unlocked};
+
+static STORM_NAMES_TOML: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../config/storm_names.toml"
+));
+
+// Designators in EN/FR/PT/ES; longer forms first so "cyclone tropical
+// intense" wins over "cyclone".
+static DESIGNATOR: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"(?i)\b(super\s+typhoons?|typhoons?|typhons?|tufões|tufão|tifones|tifón|hurricanes?|ouragans?|furacões|furacão|huracanes|huracán|severe\s+tropical\s+cyclones?|very\s+severe\s+cyclonic\s+storms?|severe\s+cyclonic\s+storms?|cyclonic\s+storms?|tropical\s+cyclones?|cyclones?\s+tropicaux\s+intenses|cyclones?\s+tropica(?:l|ux)(?:\s+intenses?)?|cyclones?|ciclones?(?:\s+tropica(?:l|is))?|tropical\s+storms?|tropical\s+depressions?|tempêtes?\s+tropicales?|tempêtes?|dépressions?\s+tropicales?|tempestades?\s+tropica(?:l|is)|tempestades?|depressões\s+tropicais|depressão\s+tropical|tormentas?\s+tropicales?|storms?|tc|sts|ts)\s+["“«']?\s*(\p{Lu}\p{L}*(?:-\p{L}+)?)"#,
+    )
+    .unwrap()
+});
+// Further names in a list: "Cyclones Idai, Kenneth and Eloise"
+static CONTINUATION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"^\s*(?:,\s*|\s+(?:and|et|e|y)\s+|\s*&\s*)["“«']?(\p{Lu}\p{L}*(?:-\p{L}+)?)"#).unwrap()
+});
+
+/// Basins a designator points to, most likely first.
+fn designator_basins(designator: &str) -> &'static [&'static str] {
+    let d = designator.to_lowercase();
+    if ["typh", "tufõ", "tufã", "tifó", "tifo"].iter().any(|k| d.contains(k)) {
+        &["western_pacific"]
+    } else if ["hurr", "ourag", "furac", "hurac"].iter().any(|k| d.contains(k)) {
+        &["north_atlantic", "eastern_pacific"]
+    } else if d.contains("cyclon") || d.contains("ciclon") || d == "tc" {
+        &["south_west_indian", "north_indian", "australian", "south_pacific"]
+    } else {
+        &[]
+    }
+}
+
+#[derive(Deserialize)]
+struct StormNameFile {
+    basins: HashMap<String, Vec<String>>,
+}
+
+/// Lowercased name → (display name, basin) entries, in table order.
+type NameTable = HashMap<String, Vec<(String, String)>>;
+
+fn add_name(table: &mut NameTable, name: &str, basin: &str) {
+    let (name, basin) = (name.trim(), basin.trim().to_lowercase());
+    if name.is_empty() || basin.is_empty() {
+        return;
+    }
+    let entries = table.entry(name.to_lowercase()).or_default();
+    if !entries.iter().any(|(_, b)| *b == basin) {
+        entries.push((name.to_string(), basin));
+    }
+}
+
+fn default_table() -> NameTable {
+    let file: StormNameFile = toml::from_str(STORM_NAMES_TOML).expect("invalid config/storm_names.toml");
+    let mut basins: Vec<_> = file.basins.into_iter().collect();
+    basins.sort();
+    let mut table = NameTable::new();
+    for (basin, names) in basins {
+        for name in names {
+            add_name(&mut table, &name, &basin);
+        }
+    }
+    table
+}
+
+static NAMES: Lazy<RwLock<NameTable>> = Lazy::new(|| RwLock::new(default_table()));
+
+fn resolve<'t>(table: &'t NameTable, name: &str, designator: &str) -> Option<&'t (String, String)> {
+    let entries = table.get(&name.to_lowercase())?;
+    let preferred = designator_basins(designator);
+    preferred
+        .iter()
+        .find_map(|basin| entries.iter().find(|(_, b)| b == basin))
+        .or_else(|| entries.first())
+}
+
+/// Named storms in `text` as `(name, basin)`, in order of first mention.
+pub(crate) fn find_storms(table: &NameTable, text: &str) -> Vec<(String, String)> {
+    let mut found: Vec<(String, String)> = Vec::new();
+    let mut push = |entry: Option<&(String, String)>| {
+        if let Some(entry) = entry {
+            if !found.contains(entry) {
+                found.push(entry.clone());
+            }
+        }
+    };
+    for caps in DESIGNATOR.captures_iter(text) {
+        let designator = &caps[1];
+        push(resolve(table, &caps[2], designator));
+        let mut end = caps.get(0).map_or(0, |m| m.end());
+        while let Some(more) = CONTINUATION.captures(&text[end..]) {
+            push(resolve(table, &more[1], designator));
+            end += more.get(0).map_or(0, |m| m.end());
+        }
+    }
+    found
+}
+
+/// Recognize named tropical storms in text.
+///
+/// A name counts when it follows a storm designator in English, French,
+/// Portuguese or Spanish ("Cyclone Freddy", "Tropical Storm Ana",
+/// "ciclone Idai", "Cyclones Idai and Kenneth") and is in the name table,
+/// so common words like "Don" or "Karen" don't fire on their own.
+///
+/// Parameters
+/// ----------
+/// text : str
+///     Article title or body.
+///
+/// Returns
+/// -------
+/// list[tuple[str, str]]
+///     ``(name, basin)`` pairs in order of first mention, e.g.
+///     ``("Freddy", "south_west_indian")``. Basins: ``south_west_indian``,
+///     ``north_indian``, ``australian``, ``south_pacific``,
+///     ``western_pacific``, ``north_atlantic``, ``eastern_pacific`` (plus
+///     any configured ones). For names used in several basins the
+///     designator decides ("hurricane" → Atlantic / eastern Pacific).
+#[pyfunction]
+pub fn detect_storm_names(py: Python<'_>, text: &str) -> PyResult<Vec<(String, String)>> {
+    unlocked(py, "detect_storm_names", || storm_names(text))
+}
+
+/// Size of the storm name table, for `runtime_info`.
+pub(crate) fn inventory() -> Vec<(&'static str, usize)> {
+    let names = NAMES.read().unwrap_or_else(|e| e.into_inner());
+    vec![("storm_names", names.values().map(Vec::len).sum())]
+}
+
+/// Storms named in `text`, from the configured table.
+pub(crate) fn storm_names(text: &str) -> Vec<(String, String)> {
+    let table = NAMES.read().unwrap_or_else(|e| e.into_inner());
+    find_storms(&table, text)
+}
+
+/// Update the storm name table.
+///
+/// Parameters
+/// ----------
+/// names : dict[str, str] | None
+///     Storm name → basin (e.g. ``{"Jude": "south_west_indian"}``).
+///     ``None`` restores the table from ``config/storm_names.toml``.
+/// replace : bool
+///     Replace the current table instead of adding to it. Default False.
+///
+/// Raises
+/// ------
+/// ValueError
+///     If a name or basin is empty; the table is left unchanged.
+#[pyfunction]
+#[pyo3(signature = (names=None, replace=false))]
+pub fn configure_storm_names(names: Option<HashMap<String, String>>, replace: bool) -> PyResult<()> {
+    guarded("configure_storm_names", || {
+        if let Some((name, basin)) = names
+            .iter()
+            .flatten()
+            .find(|(n, b)| n.trim().is_empty() || b.trim().is_empty())
+        {
+            return Err(PyValueError::new_err(format!("empty storm name or basin: {name:?} → {basin:?}")));
+        }
+        let mut table = NAMES.write().unwrap_or_else(|e| e.into_inner());
+        match names {
+            None => *table = default_table(),
+            Some(map) => {
+                if replace {
+                    table.clear();
+                }
+                for (name, basin) in map {
+                    add_name(&mut table, &name, &basin);
+                }
+            }
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn storms(text: &str) -> Vec<(String, String)> {
+        find_storms(&default_table(), text)
+    }
+
+    fn pair(name: &str, basin: &str) -> (String, String) {
+        (name.to_string(), basin.to_string())
+    }
+
+    #[test]
+    fn test_designated_names() {
+        assert_eq!(
+            storms("Tropical Cyclone Freddy made landfall near Quelimane; Freddy weakened inland."),
+            vec![pair("Freddy", "south_west_indian")]
+        );
+        assert_eq!(storms("Le cyclone tropical intense Batsirai"), vec![pair("Batsirai", "south_west_indian")]);
+        assert_eq!(storms("Typhoon Kong-rey hit Taiwan"), vec![pair("Kong-rey", "western_pacific")]);
+    }
+
+    #[test]
+    fn test_lists_and_unknown_words() {
+        assert_eq!(
+            storms("Cyclones Idai and Kenneth struck Mozambique in 2019"),
+            vec![pair("Idai", "south_west_indian"), pair("Kenneth", "south_west_indian")]
+        );
+        // Designator followed by a non-name, and a name without a designator
+        assert!(storms("Storm Warning issued; Karen said aid is coming").is_empty());
+    }
+
+    #[test]
+    fn test_designator_picks_basin() {
+        assert_eq!(storms("Tropical Storm Ana"), vec![pair("Ana", "south_pacific")]);
+        assert_eq!(storms("Cyclone Ana hit Malawi"), vec![pair("Ana", "south_west_indian")]);
+        assert_eq!(storms("Hurricane Kenneth"), vec![pair("Kenneth", "eastern_pacific")]);
+    }
+
+    #[test]
+    fn test_added_names() {
+        let mut table = default_table();
+        assert!(find_storms(&table, "Cyclone Zafira").is_empty());
+        add_name(&mut table, "Zafira", "south_west_indian");
+        assert_eq!(find_storms(&table, "ciclone Zafira"), vec![pair("Zafira", "south_west_indian")]);
+    }
+}
@@ -0,0 +1,204 @@
+//! Extractive summarization — the per-article evidence summary.
+//!
+//! Sentences are scored on how central their words are to the article,
+//! humanitarian keyword density, whether they state figures, and their
+//! position (news leads carry the facts). The best few are returned in
+//! article order.
+
+use pyo3::prelude::*;
+use std::collections::{HashMap, HashSet};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::errors::unlocked;
+use crate::figure_extraction::{figures_in, language, Language, NEGATED_ZERO};
+use crate::text_classify::humanitarian_keyword_hits;
+use crate::stopwords::is_stopword;
+use crate::tokenize::tokenize;
+
+const CENTRALITY_WEIGHT: f64 = 0.35;
+const KEYWORD_WEIGHT: f64 = 0.3;
+const FIGURE_WEIGHT: f64 = 0.2;
+const POSITION_WEIGHT: f64 = 0.15;
+/// Keyword hits beyond this add nothing.
+const MAX_KEYWORD_HITS: usize = 3;
+/// Words shorter than this don't count towards centrality, stopword or not.
+const MIN_CONTENT_WORD_CHARS: usize = 3;
+const SHORT_SENTENCE_WORDS: usize = 5;
+const LONG_SENTENCE_WORDS: usize = 50;
+/// Sentences sharing more of their words than this with a chosen one are
+/// skipped as repeats.
+const MAX_OVERLAP: f64 = 0.7;
+
+struct Sentence {
+    text: String,
+    words: HashSet<String>,
+    word_count: usize,
+}
+
+fn split_sentences(text: &str) -> Vec<Sentence> {
+    text.split_sentence_bounds()
+        .filter_map(|raw| {
+            let text = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+            let tokens = tokenize(&text, None);
+            if tokens.is_empty() {
+                return None;
+            }
+            let words = tokens
+                .iter()
+                .map(|t| t.text.to_lowercase())
+                .filter(|w| w.chars().count() >= MIN_CONTENT_WORD_CHARS && !is_stopword(w))
+                .collect();
+            Some(Sentence {
+                word_count: tokens.len(),
+                text,
+                words,
+            })
+        })
+        .collect()
+}
+
+fn overlap(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let smaller = a.len().min(b.len());
+    if smaller == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / smaller as f64
+}
+
+/// Indices of the chosen sentences, in article order; figures are read
+/// in `lang`.
+fn choose(sentences: &[Sentence], max_sentences: usize, lang: Language) -> Vec<usize> {
+    let mut frequency: HashMap<&str, usize> = HashMap::new();
+    for sentence in sentences {
+        for word in &sentence.words {
+            *frequency.entry(word.as_str()).or_default() += 1;
+        }
+    }
+    // Words repeated across sentences mark what the article is about
+    let centrality: Vec<f64> = sentences
+        .iter()
+        .map(|s| {
+            let shared: usize = s.words.iter().map(|w| frequency[w.as_str()] - 1).sum();
+            shared as f64 / (s.word_count as f64).sqrt()
+        })
+        .collect();
+    let max_centrality = centrality.iter().cloned().fold(0.0, f64::max);
+
+    let mut scored: Vec<(usize, f64)> = sentences
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            let lower = s.text.to_lowercase();
+            let keywords = humanitarian_keyword_hits(&lower).min(MAX_KEYWORD_HITS) as f64 / MAX_KEYWORD_HITS as f64;
+            let figure = if figures_in(&s.text, lang, NEGATED_ZERO).is_empty() { 0.0 } else { 1.0 };
+            let central = if max_centrality > 0.0 { centrality[i] / max_centrality } else { 0.0 };
+            let mut score = CENTRALITY_WEIGHT * central
+                + KEYWORD_WEIGHT * keywords
+                + FIGURE_WEIGHT * figure
+                + POSITION_WEIGHT / (1.0 + i as f64);
+            if s.word_count < SHORT_SENTENCE_WORDS {
+                score *= 0.3;
+            } else if s.word_count > LONG_SENTENCE_WORDS {
+                score *= 0.7;
+            }
+            (i, score)
+        })
+        .collect();
+    // Best first; earlier sentences win ties
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let mut chosen: Vec<usize> = Vec::new();
+    for (i, _) in scored {
+        if chosen.len() >= max_sentences {
+            break;
+        }
+        if chosen.iter().all(|&c| overlap(&sentences[c].words, &sentences[i].words) <= MAX_OVERLAP) {
+            chosen.push(i);
+        }
+    }
+    chosen.sort_unstable();
+    chosen
+}
+
+pub(crate) fn summarize_text(text: &str, max_sentences: usize, lang: Language) -> String {
+    let sentences = split_sentences(text);
+    choose(&sentences, max_sentences, lang)
+        .into_iter()
+        .map(|i| sentences[i].text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Extractive summary of an article.
+///
+/// Scores each sentence on word centrality, humanitarian keyword density,
+/// stated figures and lead position, drops near-repeats, and keeps the
+/// best ones in their original order.
+///
+/// Parameters
+/// ----------
+/// text : str
+///     Article text.
+/// max_sentences : int
+///     Maximum sentences in the summary. Default 3.
+/// lang : str
+///     Language the figures are read in, as for ``extract_figures``.
+///     Default ``"en"``.
+///
+/// Returns
+/// -------
+/// str
+///     The chosen sentences joined by spaces, with whitespace collapsed;
+///     empty if the text has no words.
+///
+/// Raises
+/// ------
+/// ConfigError
+///     If ``lang`` has no figure pattern set (``reason``
+///     ``"invalid_value"``).
+#[pyfunction]
+#[pyo3(signature = (text, max_sentences=3, lang="en"))]
+pub fn summarize(py: Python<'_>, text: &str, max_sentences: usize, lang: &str) -> PyResult<String> {
+    let lang = language(lang)?;
+    unlocked(py, "summarize", || summarize_text(text, max_sentences, lang))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARTICLE: &str = "Tropical Cyclone Freddy made a second landfall in Mozambique on Saturday. \
+        The weather was sunny in the capital last week. \
+        At least 48,000 people were displaced and 21 killed in Zambezia province as floods destroyed houses. \
+        Officials said the event was notable. \
+        Humanitarian partners are scaling up food and shelter assistance for displaced families in Zambezia. \
+        More updates will follow.";
+
+    #[test]
+    fn test_picks_informative_sentences_in_order() {
+        let summary = summarize_text(ARTICLE, 2, Language::English);
+        assert_eq!(
+            summary,
+            "At least 48,000 people were displaced and 21 killed in Zambezia province as floods destroyed houses. \
+             Humanitarian partners are scaling up food and shelter assistance for displaced families in Zambezia."
+        );
+        let longer = summarize_text(ARTICLE, 3, Language::English);
+        assert!(longer.starts_with("Tropical Cyclone Freddy made a second landfall"));
+        assert!(!longer.contains("sunny"));
+    }
+
+    #[test]
+    fn test_skips_repeats() {
+        let text = "Floods displaced 5,000 people in Beira. Floods displaced 5,000 people in Beira city. \
+                    Cholera cases are rising in the camps.";
+        let summary = summarize_text(text, 2, Language::English);
+        assert_eq!(summary, "Floods displaced 5,000 people in Beira. Cholera cases are rising in the camps.");
+    }
+
+    #[test]
+    fn test_short_and_empty() {
+        assert_eq!(summarize_text("", 3, Language::English), "");
+        assert_eq!(summarize_text("  Only one   sentence here.  ", 3, Language::English), "Only one sentence here.");
+        assert_eq!(summarize_text(ARTICLE, 0, Language::English), "");
+    }
+}
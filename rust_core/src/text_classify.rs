@@ -4,9 +4,19 @@
 //! by `build.rs`; this file includes the generated constants via `include!`.
 //! This ensures Rust and Python always share the same keyword definitions.
 
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
+#[cfg(feature = "python")]
 use pyo3::types::PyList;
-use regex::Regex;
+#[cfg(feature = "python")]
+use rayon::prelude::*;
+use std::ops::Range;
+
+#[cfg(feature = "python")]
+use crate::columns::{i32_column, str_column, Texts};
+#[cfg(feature = "python")]
+use crate::errors::unlocked;
+use crate::tokenize::is_word_char;
 
 // ── Generated keyword data (from config/nlp_keywords.toml via build.rs) ─────
 //
@@ -39,7 +49,7 @@ static RESPONSE_ACTORS: &[(&str, &str)] = &[
     ("cluster", "cluster"),
 ];
 
-// ── Word-boundary regex builder ─────────────────────────────────────
+// ── Word-boundary matching (boundaries as in `tokenize`) ────────────
 
 fn contains_keyword(haystack: &str, keyword: &str) -> bool {
     // Multi-word phrases: simple substring (already specific enough)
@@ -49,33 +59,74 @@ fn contains_keyword(haystack: &str, keyword: &str) -> bool {
     // Single-word: require a word-START boundary so "road" doesn't match
     // "railroad", but allow any suffix so "bridge" matches "bridges".
     if let Some(pos) = haystack.find(keyword) {
-        pos == 0 || !haystack.as_bytes()[pos - 1].is_ascii_alphanumeric()
+        !haystack[..pos].chars().next_back().is_some_and(is_word_char)
     } else {
         false
     }
 }
 
+/// Whole-word match: the keyword must be bounded by non-alphanumerics on
+/// both sides, so the short acronym "un" doesn't fire on "unicef".
+fn contains_word(haystack: &str, keyword: &str) -> bool {
+    word_matches(haystack, keyword).next().is_some()
+}
+
+/// Byte offsets of `keyword`'s whole-word occurrences in `haystack`.
+fn word_matches<'a>(haystack: &'a str, keyword: &'a str) -> impl Iterator<Item = usize> + 'a {
+    haystack.match_indices(keyword).map(|(pos, _)| pos).filter(move |&pos| {
+        let end = pos + keyword.len();
+        !haystack[..pos].chars().next_back().is_some_and(is_word_char)
+            && !haystack[end..].chars().next().is_some_and(is_word_char)
+    })
+}
+
 /// Classify the *dominant* impact type from text (single-label).
 ///
 /// Returns one of: `"people_impact"`, `"housing_lc_impact"`,
 /// `"infrastructure_impact"`, `"services_impact"`, `"systems_impact"`.
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn classify_impact_type(py: Python<'_>, text: &str) -> PyResult<String> {
+    unlocked(py, "classify_impact_type", || dominant_impact_type(&text.to_lowercase()).to_string())
+}
+
+/// Dominant impact type of many texts, in parallel with the GIL released.
+///
+/// Parameters
+/// ----------
+/// texts : list[str] | numpy.ndarray | pandas.Series | polars.Series
+///     Texts to classify; missing values classify as empty text.
+///
+/// Returns
+/// -------
+/// list[str] | numpy.ndarray
+///     One label per text (see ``classify_impact_type``); an ``object``
+///     array when ``texts`` is array-like.
+#[cfg(feature = "python")]
 #[pyfunction]
-pub fn classify_impact_type(text: &str) -> String {
-    let haystack = text.to_lowercase();
+pub fn classify_impact_type_batch(py: Python<'_>, texts: Texts) -> PyResult<PyObject> {
+    let labels: Vec<&str> = unlocked(py, "classify_impact_type_batch", || {
+        texts.values.par_iter().map(|t| dominant_impact_type(&t.to_lowercase())).collect()
+    })?;
+    str_column(py, &labels, texts.array)
+}
+
+/// Dominant impact type of lowercased text.
+pub(crate) fn dominant_impact_type(haystack: &str) -> &'static str {
     let mut best_label = "people_impact";
     let mut best_score = 0i32;
 
     for &(label, keywords) in IMPACT_KEYWORD_DATA {
         let score = keywords
             .iter()
-            .filter(|&&kw| contains_keyword(&haystack, kw))
+            .filter(|&&kw| contains_keyword(haystack, kw))
             .count() as i32;
         if score > best_score {
             best_score = score;
             best_label = label;
         }
     }
-    best_label.to_string()
+    best_label
 }
 
 /// Find **all** impact types with keyword matches, ordered by score (multi-label).
@@ -84,55 +135,120 @@ pub fn classify_impact_type(text: &str) -> String {
 /// (infrastructure), and damaged clinics (services).  This function returns all
 /// matching types so callers can create one `ImpactObservation` per type.
 /// Falls back to `["people_impact"]` when nothing matches.
+#[cfg(feature = "python")]
 #[pyfunction]
 pub fn classify_all_impact_types(py: Python<'_>, text: &str) -> PyResult<Py<PyList>> {
-    let haystack = text.to_lowercase();
-    let mut scored: Vec<(&str, i32)> = Vec::new();
+    let labels = unlocked(py, "classify_all_impact_types", || impact_types(&text.to_lowercase()))?;
+    let list = PyList::new_bound(py, labels);
+    Ok(list.unbind())
+}
 
-    for &(label, keywords) in IMPACT_KEYWORD_DATA {
-        let score = keywords
-            .iter()
-            .filter(|&&kw| contains_keyword(&haystack, kw))
-            .count() as i32;
-        if score > 0 {
-            scored.push((label, score));
-        }
+/// All impact types of lowercased text, by descending score.
+pub(crate) fn impact_types(haystack: &str) -> Vec<&'static str> {
+    let table = IMPACT_KEYWORD_DATA.iter().map(|&(label, keywords)| (label, keywords));
+    let labels = ranked_labels(keyword_scores(haystack, table), 1);
+    if labels.is_empty() {
+        return vec!["people_impact"];
     }
+    labels
+}
 
-    if scored.is_empty() {
-        let list = PyList::new_bound(py, &["people_impact"]);
-        return Ok(list.unbind());
-    }
+/// Keyword hits per label of a `(label, keywords)` table, in table order.
+pub(crate) fn keyword_scores<'t, K: AsRef<str> + 't>(
+    haystack: &str,
+    table: impl IntoIterator<Item = (&'t str, &'t [K])>,
+) -> Vec<(&'t str, usize)> {
+    table
+        .into_iter()
+        .map(|(label, keywords)| {
+            let score = keywords
+                .iter()
+                .filter(|kw| contains_keyword(haystack, kw.as_ref()))
+                .count();
+            (label, score)
+        })
+        .collect()
+}
 
-    // Descending by score; stable insertion order for ties
-    scored.sort_by(|a, b| b.1.cmp(&a.1));
-    let labels: Vec<&str> = scored.iter().map(|(label, _)| *label).collect();
-    let list = PyList::new_bound(py, labels);
-    Ok(list.unbind())
+/// Labels with at least `min_hits` hits, by descending score; stable
+/// insertion order for ties.
+pub(crate) fn ranked_labels(mut scored: Vec<(&str, usize)>, min_hits: usize) -> Vec<&str> {
+    scored.retain(|&(_, score)| score >= min_hits.max(1));
+    scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+    scored.into_iter().map(|(label, _)| label).collect()
+}
+
+/// The generated impact keyword table as owned data.
+pub(crate) fn default_impact_keywords() -> Vec<(String, Vec<String>)> {
+    owned_table(IMPACT_KEYWORD_DATA)
+}
+
+/// The generated need keyword table as owned data.
+pub(crate) fn default_need_keywords() -> Vec<(String, Vec<String>)> {
+    owned_table(NEED_KEYWORD_DATA)
+}
+
+pub(crate) fn default_risk_keywords() -> Vec<String> {
+    RISK_KEYWORD_DATA.iter().map(|kw| kw.to_string()).collect()
+}
+
+fn owned_table(table: &[(&str, &[&str])]) -> Vec<(String, Vec<String>)> {
+    table
+        .iter()
+        .map(|(label, keywords)| (label.to_string(), keywords.iter().map(|kw| kw.to_string()).collect()))
+        .collect()
 }
 
 /// Find all need types mentioned in text (multi-label).
 ///
 /// Returns a list of need type strings, e.g. `["food_security", "wash"]`.
+#[cfg(feature = "python")]
 #[pyfunction]
 pub fn classify_need_types(py: Python<'_>, text: &str) -> PyResult<Py<PyList>> {
-    let haystack = text.to_lowercase();
-    let mut found: Vec<&str> = Vec::new();
-
-    for &(label, keywords) in NEED_KEYWORD_DATA {
-        if keywords.iter().any(|&kw| contains_keyword(&haystack, kw)) {
-            found.push(label);
-        }
-    }
-
-    let list = PyList::new_bound(py, found);
+    let labels = unlocked(py, "classify_need_types", || need_types(&text.to_lowercase()))?;
+    let list = PyList::new_bound(py, labels);
     Ok(list.unbind())
 }
 
+/// Need types mentioned in lowercased text.
+pub(crate) fn need_types(haystack: &str) -> Vec<&'static str> {
+    NEED_KEYWORD_DATA
+        .iter()
+        .filter(|(_, keywords)| keywords.iter().any(|&kw| contains_keyword(haystack, kw)))
+        .map(|&(label, _)| label)
+        .collect()
+}
+
 /// Estimate IPC-like severity phase (1-5) from text keywords.
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn severity_from_text(py: Python<'_>, text: &str) -> PyResult<i32> {
+    unlocked(py, "severity_from_text", || severity(&text.to_lowercase()))
+}
+
+/// Severity phase (1-5) of many texts, in parallel with the GIL released.
+///
+/// Parameters
+/// ----------
+/// texts : list[str] | numpy.ndarray | pandas.Series | polars.Series
+///     Texts to score; missing values score as empty text.
+///
+/// Returns
+/// -------
+/// list[int] | numpy.ndarray
+///     One phase per text; an ``int32`` array when ``texts`` is
+///     array-like.
+#[cfg(feature = "python")]
 #[pyfunction]
-pub fn severity_from_text(text: &str) -> i32 {
-    let h = text.to_lowercase();
+pub fn severity_from_text_batch(py: Python<'_>, texts: Texts) -> PyResult<PyObject> {
+    let phases: Vec<i32> = unlocked(py, "severity_from_text_batch", || {
+        texts.values.par_iter().map(|t| severity(&t.to_lowercase())).collect()
+    })?;
+    i32_column(py, &phases, texts.array)
+}
+
+/// Severity phase of lowercased text.
+pub(crate) fn severity(h: &str) -> i32 {
     if ["catastroph", "famine", "system collapse", "mass casualty"]
         .iter()
         .any(|k| h.contains(k))
@@ -166,24 +282,53 @@ pub fn severity_from_text(text: &str) -> i32 {
 }
 
 /// Return `true` if text contains risk or forecast language.
+#[cfg(feature = "python")]
 #[pyfunction]
-pub fn is_risk_text(text: &str) -> bool {
-    let h = text.to_lowercase();
+pub fn is_risk_text(py: Python<'_>, text: &str) -> PyResult<bool> {
+    unlocked(py, "is_risk_text", || is_risk(&text.to_lowercase()))
+}
+
+pub(crate) fn is_risk(h: &str) -> bool {
     RISK_KEYWORD_DATA.iter().any(|&kw| h.contains(kw))
 }
 
+/// Impact and need keywords found in lowercased text.
+pub(crate) fn humanitarian_keyword_hits(h: &str) -> usize {
+    IMPACT_KEYWORD_DATA
+        .iter()
+        .chain(NEED_KEYWORD_DATA)
+        .flat_map(|(_, keywords)| keywords.iter())
+        .filter(|kw| contains_keyword(h, kw))
+        .count()
+}
+
 /// Detect a response actor from text.
 ///
 /// Returns (actor_name, actor_type) tuple or None.
+#[cfg(feature = "python")]
 #[pyfunction]
-pub fn detect_response_actor(text: &str) -> Option<(String, String)> {
-    let h = text.to_lowercase();
-    for &(keyword, actor_type) in RESPONSE_ACTORS {
-        if contains_keyword(&h, keyword) {
-            return Some((keyword.to_uppercase(), actor_type.to_string()));
-        }
-    }
-    None
+pub fn detect_response_actor(py: Python<'_>, text: &str) -> PyResult<Option<(String, String)>> {
+    unlocked(py, "detect_response_actor", || response_actor(&text.to_lowercase()))
+}
+
+pub(crate) fn response_actor(h: &str) -> Option<(String, String)> {
+    RESPONSE_ACTORS
+        .iter()
+        .find(|(keyword, _)| contains_word(h, keyword))
+        .map(|(keyword, actor_type)| (keyword.to_uppercase(), actor_type.to_string()))
+}
+
+/// Every response actor mention in lowercased text, as `(byte offset,
+/// actor, actor_type)` in text order.
+pub(crate) fn actor_mentions(h: &str) -> Vec<(usize, String, &'static str)> {
+    let mut mentions: Vec<_> = RESPONSE_ACTORS
+        .iter()
+        .flat_map(|&(keyword, actor_type)| {
+            word_matches(h, keyword).map(move |pos| (pos, keyword.to_uppercase(), actor_type))
+        })
+        .collect();
+    mentions.sort_by_key(|&(pos, _, _)| pos);
+    mentions
 }
 
 /// Detect an admin area name in text from a list of known areas.
@@ -199,43 +344,77 @@ pub fn detect_response_actor(text: &str) -> Option<(String, String)> {
 /// -------
 /// tuple[str, int] | None
 ///     (matched_area_name, admin_level) or None.
+#[cfg(feature = "python")]
 #[pyfunction]
-pub fn detect_admin_area(text: &str, area_names: Vec<(String, i32)>) -> Option<(String, i32)> {
-    let h = text.to_lowercase();
+pub fn detect_admin_area(py: Python<'_>, text: &str, area_names: Vec<(String, i32)>) -> PyResult<Option<(String, i32)>> {
+    unlocked(py, "detect_admin_area", || admin_area(&text.to_lowercase(), area_names))
+}
 
-    // Sort by admin level descending (prefer more specific matches)
-    let mut sorted_areas = area_names;
-    sorted_areas.sort_by(|a, b| b.1.cmp(&a.1));
+/// Most specific known admin area named in lowercased text.
+pub(crate) fn admin_area(h: &str, area_names: Vec<(String, i32)>) -> Option<(String, i32)> {
+    admin_area_in(h, &sort_admin_areas(area_names))
+}
+
+/// Sort by admin level descending (prefer more specific matches).
+pub(crate) fn sort_admin_areas(mut area_names: Vec<(String, i32)>) -> Vec<(String, i32)> {
+    area_names.sort_by_key(|&(_, level)| std::cmp::Reverse(level));
+    area_names
+}
 
-    for (name, level) in &sorted_areas {
+/// `admin_area` over areas already sorted by `sort_admin_areas`.
+pub(crate) fn admin_area_in(h: &str, sorted_areas: &[(String, i32)]) -> Option<(String, i32)> {
+    for (name, level) in sorted_areas {
         if *level < 1 {
             continue;
         }
-        let lower_name = name.to_lowercase();
         // Word-boundary match
-        let pattern = format!(r"(?<!\w){}(?!\w)", regex::escape(&lower_name));
-        if let Ok(re) = Regex::new(&pattern) {
-            if Regex::is_match(&re, &h) {
-                return Some((name.clone(), *level));
-            }
+        if contains_word(h, &name.to_lowercase()) {
+            return Some((name.clone(), *level));
         }
     }
     None
 }
 
-#[cfg(test)]
+/// Whole-word mentions of known areas (level 1 and below) in lowercased
+/// text, as `(byte range, name, level)` in text order. Where mentions
+/// overlap, the longer name wins, then the more specific level.
+pub(crate) fn area_mentions(h: &str, area_names: &[(String, i32)]) -> Vec<(Range<usize>, String, i32)> {
+    let mut all: Vec<(Range<usize>, String, i32)> = area_names
+        .iter()
+        .filter(|(_, level)| *level >= 1)
+        .flat_map(|(name, level)| {
+            let key = name.to_lowercase();
+            word_matches(h, &key).map(|pos| (pos..pos + key.len(), name.clone(), *level)).collect::<Vec<_>>()
+        })
+        .collect();
+    all.sort_by_key(|(span, _, level)| (span.start, std::cmp::Reverse(span.end), std::cmp::Reverse(*level)));
+    let mut mentions: Vec<(Range<usize>, String, i32)> = Vec::new();
+    for mention in all {
+        if mentions.last().is_none_or(|last| last.0.end <= mention.0.start) {
+            mentions.push(mention);
+        }
+    }
+    mentions
+}
+
+#[cfg(all(test, feature = "python"))]
 mod tests {
     use super::*;
 
+    fn with_py<T>(f: impl FnOnce(Python<'_>) -> PyResult<T>) -> T {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(f).unwrap()
+    }
+
     #[test]
     fn test_classify_impact_people() {
-        assert_eq!(classify_impact_type("52 deaths confirmed"), "people_impact");
+        assert_eq!(with_py(|py| classify_impact_type(py, "52 deaths confirmed")), "people_impact");
     }
 
     #[test]
     fn test_classify_impact_housing() {
         assert_eq!(
-            classify_impact_type("houses destroyed and homes damaged"),
+            with_py(|py| classify_impact_type(py, "houses destroyed and homes damaged")),
             "housing_lc_impact"
         );
     }
@@ -278,21 +457,31 @@ mod tests {
 
     #[test]
     fn test_severity() {
-        assert_eq!(severity_from_text("catastrophic flooding"), 5);
-        assert_eq!(severity_from_text("state of emergency declared"), 4);
-        assert_eq!(severity_from_text("major damage reported"), 3);
-        assert_eq!(severity_from_text("routine update"), 1);
+        assert_eq!(with_py(|py| severity_from_text(py, "catastrophic flooding")), 5);
+        assert_eq!(with_py(|py| severity_from_text(py, "state of emergency declared")), 4);
+        assert_eq!(with_py(|py| severity_from_text(py, "major damage reported")), 3);
+        assert_eq!(with_py(|py| severity_from_text(py, "routine update")), 1);
     }
 
     #[test]
     fn test_risk_text() {
-        assert!(is_risk_text("forecast shows continued rainfall"));
-        assert!(!is_risk_text("the damage has been assessed"));
+        assert!(with_py(|py| is_risk_text(py, "forecast shows continued rainfall")));
+        assert!(!with_py(|py| is_risk_text(py, "the damage has been assessed")));
     }
 
     #[test]
     fn test_detect_actor() {
-        let result = detect_response_actor("UNICEF is deploying supplies");
+        let result = with_py(|py| detect_response_actor(py, "UNICEF is deploying supplies"));
         assert_eq!(result, Some(("UNICEF".to_string(), "un_agency".to_string())));
     }
+
+    #[test]
+    fn test_detect_admin_area() {
+        let areas = vec![("Sofala".to_string(), 1), ("Beira".to_string(), 2), ("Niassa".to_string(), 1)];
+        assert_eq!(
+            with_py(|py| detect_admin_area(py, "Flooding in Beira, Sofala province", areas.clone())),
+            Some(("Beira".to_string(), 2))
+        );
+        assert_eq!(with_py(|py| detect_admin_area(py, "Beiral market reopened", areas)), None);
+    }
 }
@@ -0,0 +1,241 @@
+//! Text quality and boilerplate scoring.
+//!
+//! Navigation menus, cookie banners and link lists that slip through
+//! extraction skew the classifiers and collide in dedup. Prose is mostly
+//! full sentences with a steady share of function words; navigation soup
+//! is short fragments, link text and stock phrases. `text_quality`
+//! measures those signals and folds them into one score.
+
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use scraper::{Html, Selector};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::errors::unlocked;
+use crate::html_text::convert;
+use crate::stopwords::is_stopword;
+use crate::tokenize::tokenize;
+
+/// Scores below this mark a page as navigation soup.
+const LOW_QUALITY_SCORE: f64 = 0.4;
+/// Sentences under this many words count as fragments.
+const SHORT_SENTENCE_WORDS: usize = 5;
+/// Stopword share of ordinary prose; higher earns no extra credit.
+const PROSE_STOPWORD_RATIO: f64 = 0.35;
+/// Link-text share at which the link component reaches zero.
+const MAX_LINK_TEXT_RATIO: f64 = 0.6;
+/// Mean sentence length range (words) of news prose.
+const PROSE_SENTENCE_WORDS: (f64, f64) = (10.0, 35.0);
+/// Texts shorter than this (words) are scored down.
+const MIN_WORDS: f64 = 50.0;
+
+// Stock phrases of site chrome, matched on lowercased text.
+static BOILERPLATE: &[&str] = &[
+    "cookie", "privacy policy", "terms of use", "terms and conditions", "all rights reserved",
+    "subscribe", "newsletter", "sign in", "log in", "sign up", "skip to content",
+    "skip to main content", "follow us", "share this", "share on", "read more", "click here",
+    "enable javascript", "accept all", "back to top", "related articles", "advertisement",
+    "politique de confidentialité", "tous droits réservés", "política de privacidade",
+    "todos os direitos reservados",
+];
+
+static LINKS: Lazy<Selector> = Lazy::new(|| Selector::parse("a").unwrap());
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct TextQuality {
+    pub word_count: usize,
+    pub stopword_ratio: f64,
+    /// Share of the text inside links; 0 for plain text.
+    pub link_text_ratio: f64,
+    pub sentence_count: usize,
+    pub mean_sentence_words: f64,
+    pub median_sentence_words: f64,
+    pub short_sentence_ratio: f64,
+    pub boilerplate_hits: usize,
+    /// 0 (navigation soup) to 1 (clean prose).
+    pub score: f64,
+}
+
+/// Word counts of the sentences in `text`; line breaks end sentences,
+/// so menu items count as fragments.
+fn sentence_lengths(text: &str) -> Vec<usize> {
+    text.lines()
+        .flat_map(|line| line.split_sentence_bounds())
+        .map(|sentence| tokenize(sentence, None).len())
+        .filter(|words| *words > 0)
+        .collect()
+}
+
+fn median(sorted: &[usize]) -> f64 {
+    match sorted.len() {
+        0 => 0.0,
+        n if n % 2 == 1 => sorted[n / 2] as f64,
+        n => (sorted[n / 2 - 1] + sorted[n / 2]) as f64 / 2.0,
+    }
+}
+
+fn sentence_score(mean: f64, short_ratio: f64) -> f64 {
+    let (low, high) = PROSE_SENTENCE_WORDS;
+    let length = if mean < low {
+        mean / low
+    } else if mean > high {
+        high / mean
+    } else {
+        1.0
+    };
+    0.5 * length + 0.5 * (1.0 - short_ratio)
+}
+
+/// `link_chars` is None when links weren't measured (plain text).
+fn assess_with_links(text: &str, link_chars: Option<usize>) -> TextQuality {
+    let tokens = tokenize(text, None);
+    let word_count = tokens.len();
+    if word_count == 0 {
+        return TextQuality::default();
+    }
+    let stopwords = tokens.iter().filter(|t| is_stopword(&t.text.to_lowercase())).count();
+    let stopword_ratio = stopwords as f64 / word_count as f64;
+    let text_chars = text.chars().filter(|c| !c.is_whitespace()).count().max(1);
+    let link_text_ratio = link_chars.map_or(0.0, |n| (n as f64 / text_chars as f64).min(1.0));
+
+    let mut lengths = sentence_lengths(text);
+    lengths.sort_unstable();
+    let sentence_count = lengths.len();
+    let mean_sentence_words = word_count as f64 / sentence_count.max(1) as f64;
+    let short = lengths.iter().filter(|n| **n < SHORT_SENTENCE_WORDS).count();
+    let short_sentence_ratio = short as f64 / sentence_count.max(1) as f64;
+
+    let lower = text.to_lowercase();
+    let boilerplate_hits = BOILERPLATE.iter().map(|p| lower.matches(p).count()).sum::<usize>();
+
+    let stop = (stopword_ratio / PROSE_STOPWORD_RATIO).min(1.0);
+    let sentences = sentence_score(mean_sentence_words, short_sentence_ratio);
+    // Hits per 100 words, floored at 100 words so one stray phrase in a
+    // short text isn't fatal
+    let boiler = 1.0 / (1.0 + boilerplate_hits as f64 * 100.0 / (word_count as f64).max(100.0));
+    let length = (word_count as f64 / MIN_WORDS).min(1.0);
+    let mut weighted = 0.35 * stop + 0.25 * sentences + 0.15 * boiler;
+    let mut weights = 0.75;
+    if link_chars.is_some() {
+        weighted += 0.25 * (1.0 - (link_text_ratio / MAX_LINK_TEXT_RATIO).min(1.0));
+        weights += 0.25;
+    }
+    let score = weighted / weights * (0.5 + 0.5 * length);
+
+    TextQuality {
+        word_count,
+        stopword_ratio,
+        link_text_ratio,
+        sentence_count,
+        mean_sentence_words,
+        median_sentence_words: median(&lengths),
+        short_sentence_ratio,
+        boilerplate_hits,
+        score,
+    }
+}
+
+pub(crate) fn assess(text: &str) -> TextQuality {
+    assess_with_links(text, None)
+}
+
+/// Assess a page's visible text, measuring how much of it is link text.
+pub(crate) fn assess_html(html: &str) -> TextQuality {
+    let doc = Html::parse_document(html);
+    let link_chars = doc
+        .select(&LINKS)
+        .flat_map(|a| a.text())
+        .map(|t| t.chars().filter(|c| !c.is_whitespace()).count())
+        .sum();
+    assess_with_links(&convert(html), Some(link_chars))
+}
+
+/// Measure how much a text reads like prose rather than site chrome.
+///
+/// Parameters
+/// ----------
+/// text : str
+///     Extracted page text, or the page HTML with ``html=True``.
+/// html : bool
+///     Treat ``text`` as HTML: score its visible text and measure the
+///     share inside links. Default False.
+///
+/// Returns
+/// -------
+/// dict
+///     ``word_count``; ``stopword_ratio`` (function words / words; prose
+///     is around 0.35-0.5); ``link_text_ratio`` (0.0 unless ``html``);
+///     ``sentence_count``, ``mean_sentence_words``,
+///     ``median_sentence_words`` and ``short_sentence_ratio`` (sentences
+///     under 5 words; lines count as sentence breaks);
+///     ``boilerplate_hits`` (cookie banners, "read more", "all rights
+///     reserved", ...); ``score`` from 0.0 (navigation soup) to 1.0
+///     (clean prose); ``low_quality`` (score below 0.4).
+#[pyfunction]
+#[pyo3(signature = (text, html=false))]
+pub fn text_quality(py: Python<'_>, text: &str, html: bool) -> PyResult<Py<PyDict>> {
+    let quality = unlocked(py, "text_quality", || if html { assess_html(text) } else { assess(text) })?;
+    let dict = PyDict::new_bound(py);
+    dict.set_item("word_count", quality.word_count)?;
+    dict.set_item("stopword_ratio", quality.stopword_ratio)?;
+    dict.set_item("link_text_ratio", quality.link_text_ratio)?;
+    dict.set_item("sentence_count", quality.sentence_count)?;
+    dict.set_item("mean_sentence_words", quality.mean_sentence_words)?;
+    dict.set_item("median_sentence_words", quality.median_sentence_words)?;
+    dict.set_item("short_sentence_ratio", quality.short_sentence_ratio)?;
+    dict.set_item("boilerplate_hits", quality.boilerplate_hits)?;
+    dict.set_item("score", quality.score)?;
+    dict.set_item("low_quality", quality.score < LOW_QUALITY_SCORE)?;
+    Ok(dict.unbind())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARTICLE: &str = "Tropical Cyclone Freddy made landfall near Quelimane on Saturday, \
+        bringing heavy rain and strong winds to the central provinces. According to the \
+        national disaster agency, at least 48,000 people have been displaced and more than \
+        2,000 houses were destroyed or damaged. Humanitarian partners are scaling up food \
+        distributions, and the health cluster has warned of a rising risk of cholera in the \
+        flooded districts. Access remains difficult because several roads and a bridge on \
+        the main highway were washed away by the floods.";
+
+    const NAVIGATION: &str = "Home\nNews\nSport\nWeather\nSign in\nSubscribe to our newsletter\n\
+        Privacy policy\nTerms of use\nCookie settings\nAccept all cookies\nFollow us\n\
+        Read more\nRelated articles\nBack to top\n© 2025 Example Media. All rights reserved.";
+
+    #[test]
+    fn test_prose_scores_high() {
+        let q = assess(ARTICLE);
+        assert!(q.score > 0.7, "{q:?}");
+        assert!(q.stopword_ratio > 0.25);
+        assert_eq!(q.sentence_count, 4);
+        assert_eq!(q.short_sentence_ratio, 0.0);
+        assert_eq!(q.boilerplate_hits, 0);
+    }
+
+    #[test]
+    fn test_navigation_scores_low() {
+        let q = assess(NAVIGATION);
+        assert!(q.score < LOW_QUALITY_SCORE, "{q:?}");
+        assert!(q.boilerplate_hits >= 10);
+        assert!(q.short_sentence_ratio > 0.8);
+        assert_eq!(assess(""), TextQuality::default());
+    }
+
+    #[test]
+    fn test_link_text_ratio() {
+        let links: String = ["Home", "World", "Africa", "Floods", "Health", "Contact"]
+            .iter()
+            .map(|s| format!("<li><a href=\"/{s}\">{s}</a></li>"))
+            .collect();
+        let page = format!("<html><body><ul>{links}</ul><p>{ARTICLE}</p></body></html>");
+        let q = assess_html(&page);
+        assert!(q.link_text_ratio > 0.05 && q.link_text_ratio < 0.2, "{q:?}");
+        let menu = assess_html(&format!("<html><body><ul>{links}</ul></body></html>"));
+        assert!(menu.link_text_ratio > 0.8, "{menu:?}");
+        assert!(menu.score < q.score);
+    }
+}
@@ -0,0 +1,344 @@
+//! 3W (Who does What Where) records from operational-presence statements.
+//!
+//! Sentences like "WFP is distributing food in Mocuba district" say who
+//! is responding, with what, and where. Each sentence is read in one
+//! pass: response actors (as in `detect_response_actor`), an activity
+//! verb shortly after them, the sector of what's being done (the need
+//! clusters of `classify_need_types`), and the admin area from a
+//! `Gazetteer` or a list of known area names.
+//!
+//! An actor only owns the verb that follows it before the next actor, so
+//! "the government said WFP is distributing" credits WFP alone;
+//! coordinated actors ("WFP and UNICEF are ...") each get a record.
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::collections::HashSet;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::errors::{gazetteer_error, unlocked, Failure};
+use crate::gazetteer::Gazetteer;
+use crate::place_linking::link;
+use crate::text_classify::{actor_mentions, admin_area_in, need_types, sort_admin_areas};
+use crate::tokenize::is_word_char;
+
+/// Activity verb stems (matched at a word start, any ending) with the
+/// sector they imply when the rest of the activity names none.
+const ACTIVITIES: &[(&str, Option<&str>)] = &[
+    ("distribut", None),
+    ("deliver", None),
+    ("provid", None),
+    ("deploy", None),
+    ("supply", None),
+    ("supplie", None),
+    ("support", None),
+    ("assist", None),
+    ("respond", None),
+    ("operating", None),
+    ("running", None),
+    ("establish", None),
+    ("set up", None),
+    ("setting up", None),
+    ("scaling up", None),
+    ("scaled up", None),
+    ("rehabilitat", None),
+    ("repair", None),
+    ("construct", None),
+    ("vaccinat", Some("health")),
+    ("immuniz", Some("health")),
+    ("immunis", Some("health")),
+    ("treating", Some("health")),
+    ("truck", Some("wash")),
+    ("chlorinat", Some("wash")),
+    ("evacuat", Some("logistics")),
+    ("airlift", Some("logistics")),
+    ("relocat", Some("shelter")),
+    ("register", Some("protection")),
+    ("feeding", Some("food_security")),
+];
+/// Most words allowed between an actor and its activity verb ("WFP and
+/// its partners have been distributing").
+const MAX_GAP_WORDS: usize = 5;
+/// Activities are cut to this many words.
+const MAX_ACTIVITY_WORDS: usize = 8;
+/// Where an activity phrase ends: the location or a new clause.
+const ACTIVITY_ENDS: &[&str] = &[
+    " in ", " across ", " throughout ", " at ", " within ", " near ", " after ", " following ", " since ", " as ", ",",
+    ";", ".", ":",
+];
+/// Words joining coordinated actors.
+const CONJUNCTIONS: &[&str] = &["and", ",", ", and", "&", "with"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ThreeW {
+    pub actor: String,
+    pub actor_type: &'static str,
+    pub activity: String,
+    pub sector: Option<&'static str>,
+    pub admin_area: Option<String>,
+    pub admin_level: Option<i32>,
+    /// Gazetteer id (GeoNames id or P-code) of the area.
+    pub place_id: Option<String>,
+    pub sentence: String,
+}
+
+/// Where area names come from.
+pub(crate) enum Places<'a> {
+    Gazetteer(&'a Gazetteer, HashSet<String>),
+    /// `(name, admin_level)`, sorted by `sort_admin_areas`.
+    Names(Vec<(String, i32)>),
+    None,
+}
+
+type Area = (String, Option<i32>, Option<String>);
+
+impl Places<'_> {
+    /// The most specific admin area named in `sentence`.
+    fn area(&self, sentence: &str, lower: &str) -> Result<Option<Area>, Failure> {
+        Ok(match self {
+            Places::Gazetteer(gazetteer, countries) => {
+                let links = link(sentence, gazetteer, countries)?;
+                let places: Vec<_> = links.iter().map(|l| gazetteer.place(l.place)).collect::<Result<_, _>>()?;
+                // Deepest admin division, else a settlement, else the country
+                let place = places
+                    .iter()
+                    .filter(|p| p.admin_level.is_some_and(|level| level > 0))
+                    .max_by_key(|p| p.admin_level)
+                    .or_else(|| places.iter().find(|p| p.admin_level.is_none()))
+                    .or_else(|| places.first());
+                place.map(|place| (place.name.clone(), place.admin_level.map(i32::from), Some(place.id.clone())))
+            }
+            Places::Names(areas) => admin_area_in(lower, areas).map(|(name, level)| (name, Some(level), None)),
+            Places::None => None,
+        })
+    }
+}
+
+/// Byte offset and sector hint of the first activity verb in `h` at or
+/// after `from`.
+fn activity_at(h: &str, from: usize) -> Option<(usize, Option<&'static str>)> {
+    ACTIVITIES
+        .iter()
+        .filter_map(|&(stem, sector)| {
+            h[from..]
+                .match_indices(stem)
+                .map(|(pos, _)| from + pos)
+                .find(|&pos| !h[..pos].chars().next_back().is_some_and(is_word_char))
+                .map(|pos| (pos, sector))
+        })
+        .min_by_key(|&(pos, _)| pos)
+}
+
+/// The activity phrase starting at `start`: up to the location or the
+/// end of the clause.
+fn activity_phrase(text: &str, start: usize) -> String {
+    let rest = &text[start..];
+    let lower = rest.to_lowercase();
+    let end = ACTIVITY_ENDS
+        .iter()
+        .filter_map(|stop| lower.find(stop))
+        .min()
+        .filter(|_| lower.len() == rest.len())
+        .unwrap_or(rest.len());
+    rest[..end].split_whitespace().take(MAX_ACTIVITY_WORDS).collect::<Vec<_>>().join(" ")
+}
+
+fn sentence_records(sentence: &str, places: &Places<'_>) -> Result<Vec<ThreeW>, Failure> {
+    let lower = sentence.to_lowercase();
+    // Offsets into `lower` are only valid in `sentence` if lowercasing
+    // kept every length ("İ" doesn't).
+    let source = if lower.len() == sentence.len() { sentence } else { lower.as_str() };
+    let mentions = actor_mentions(&lower);
+    // Runs of coordinated actors: "WFP and UNICEF", "IFRC, MSF and CARE"
+    let mut runs: Vec<Vec<&(usize, String, &'static str)>> = Vec::new();
+    for mention in &mentions {
+        let joined = runs.last().is_some_and(|run| {
+            let (pos, actor, _) = run[run.len() - 1];
+            let end = (pos + actor.len()).min(mention.0);
+            CONJUNCTIONS.contains(&lower[end..mention.0].trim())
+        });
+        match runs.last_mut() {
+            Some(run) if joined => run.push(mention),
+            _ => runs.push(vec![mention]),
+        }
+    }
+    let mut records = Vec::new();
+    let mut area: Option<Option<Area>> = None;
+    for (i, run) in runs.iter().enumerate() {
+        let (pos, actor, _) = run[run.len() - 1];
+        let after = pos + actor.len();
+        let next_run = runs.get(i + 1).map_or(lower.len(), |r| r[0].0);
+        let Some((verb, hint)) = activity_at(&lower, after).filter(|&(verb, _)| verb < next_run) else {
+            continue;
+        };
+        if lower[after..verb].unicode_words().count() > MAX_GAP_WORDS {
+            continue;
+        }
+        let activity = activity_phrase(source, verb);
+        let activity_lower = activity.to_lowercase();
+        let sector = need_types(&activity_lower)
+            .first()
+            .copied()
+            .or(hint)
+            .or_else(|| need_types(&lower).first().copied());
+        // Looked up once, and only for sentences with a 3W statement
+        let area = match &mut area {
+            Some(area) => area,
+            None => area.insert(places.area(sentence, &lower)?),
+        };
+        let (admin_area, admin_level, place_id) = match area {
+            Some((name, level, id)) => (Some(name.clone()), *level, id.clone()),
+            None => (None, None, None),
+        };
+        for &(_, actor, actor_type) in run {
+            records.push(ThreeW {
+                actor: actor.clone(),
+                actor_type,
+                activity: activity.clone(),
+                sector,
+                admin_area: admin_area.clone(),
+                admin_level,
+                place_id: place_id.clone(),
+                sentence: sentence.to_string(),
+            });
+        }
+    }
+    Ok(records)
+}
+
+/// 3W records from every sentence of `text`, in text order.
+pub(crate) fn extract(text: &str, places: &Places<'_>) -> Result<Vec<ThreeW>, Failure> {
+    let mut records = Vec::new();
+    for sentence in text
+        .split_sentence_bounds()
+        .map(|raw| raw.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|sentence| !sentence.is_empty())
+    {
+        records.extend(sentence_records(&sentence, places)?);
+    }
+    Ok(records)
+}
+
+/// Extract 3W (who, what, where) records from operational-presence
+/// statements.
+///
+/// Finds sentences where a response actor is doing something somewhere —
+/// "WFP is distributing food in Mocuba district" — and returns who
+/// (actor), what (activity and sector) and where (admin area). Actors
+/// joined by "and" share one activity.
+///
+/// Parameters
+/// ----------
+/// text : str
+///     Article or report text.
+/// gazetteer : Gazetteer | None
+///     Links place names to admin areas (see ``link_places``); the
+///     deepest admin division named in the sentence is the area.
+/// area_names : list[tuple[str, int]] | None
+///     ``(name, admin_level)`` areas to look for instead, as in
+///     ``detect_admin_area``. Ignored when ``gazetteer`` is given.
+/// countries : list[str] | None
+///     Country codes the text is about, for the gazetteer's
+///     disambiguation.
+///
+/// Returns
+/// -------
+/// list[dict]
+///     In text order: ``actor`` and ``actor_type`` (as in
+///     ``detect_response_actor``), ``activity`` (the verb phrase, e.g.
+///     ``"distributing food"``), ``sector`` (a need cluster such as
+///     ``"food_security"``, or None), ``admin_area``, ``admin_level`` and
+///     ``place_id`` (gazetteer id; None with ``area_names``) — None when
+///     no area is named — and ``sentence``.
+#[pyfunction]
+#[pyo3(signature = (text, gazetteer=None, area_names=None, countries=None))]
+pub fn extract_3w(
+    py: Python<'_>,
+    text: &str,
+    gazetteer: Option<PyRef<'_, Gazetteer>>,
+    area_names: Option<Vec<(String, i32)>>,
+    countries: Option<Vec<String>>,
+) -> PyResult<Vec<Py<PyDict>>> {
+    let places = match (&gazetteer, area_names) {
+        (Some(gazetteer), _) => {
+            let countries = countries.unwrap_or_default().iter().map(|c| c.trim().to_uppercase()).collect();
+            Places::Gazetteer(gazetteer, countries)
+        }
+        (None, Some(areas)) => Places::Names(sort_admin_areas(areas)),
+        (None, None) => Places::None,
+    };
+    let records = unlocked(py, "extract_3w", || extract(text, &places))?.map_err(|e| match &gazetteer {
+        Some(gazetteer) => gazetteer.error(e),
+        None => gazetteer_error(None, e),
+    })?;
+    records
+        .into_iter()
+        .map(|record| {
+            let dict = PyDict::new_bound(py);
+            dict.set_item("actor", record.actor)?;
+            dict.set_item("actor_type", record.actor_type)?;
+            dict.set_item("activity", record.activity)?;
+            dict.set_item("sector", record.sector)?;
+            dict.set_item("admin_area", record.admin_area)?;
+            dict.set_item("admin_level", record.admin_level)?;
+            dict.set_item("place_id", record.place_id)?;
+            dict.set_item("sentence", record.sentence)?;
+            Ok(dict.unbind())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gazetteer::Filter;
+
+    fn who_what_where(records: &[ThreeW]) -> Vec<(&str, &str, Option<&str>, Option<&str>)> {
+        records
+            .iter()
+            .map(|r| (r.actor.as_str(), r.activity.as_str(), r.sector, r.admin_area.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn test_presence_statements() {
+        let areas = Places::Names(sort_admin_areas(vec![("Zambezia".into(), 1), ("Mocuba".into(), 2)]));
+        let text = "WFP is distributing food in Mocuba district, Zambezia. \
+                    UNICEF and MSF have been trucking safe water to camps in Zambezia. \
+                    The government said IFRC is evacuating families. \
+                    Oxfam welcomed the decision.";
+        let records = extract(text, &areas).unwrap();
+        assert_eq!(
+            who_what_where(&records),
+            vec![
+                ("WFP", "distributing food", Some("food_security"), Some("Mocuba")),
+                ("UNICEF", "trucking safe water to camps", Some("wash"), Some("Zambezia")),
+                ("MSF", "trucking safe water to camps", Some("wash"), Some("Zambezia")),
+                ("IFRC", "evacuating families", Some("logistics"), None),
+            ]
+        );
+        assert_eq!((records[0].actor_type, records[0].admin_level), ("un_agency", Some(2)));
+        assert!(extract("Aid was delivered in Mocuba.", &areas).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_gazetteer_areas() {
+        let line = |fields: &[&str]| {
+            let mut row = fields.to_vec();
+            row.resize(19, "");
+            row.join("\t")
+        };
+        let dump = [
+            line(&["1", "Mozambique", "Mozambique", "", "-18.2", "35.0", "A", "PCLI", "MZ", "", "", "", "", "", "30000000"]),
+            line(&["2", "Sofala", "Sofala", "", "-19.5", "34.7", "A", "ADM1", "MZ", "", "05", "", "", "", "0"]),
+            line(&["3", "Buzi", "Buzi", "", "-19.9", "34.6", "A", "ADM2", "MZ", "", "05", "0503", "", "", "0"]),
+        ]
+        .join("\n");
+        let mut g = Gazetteer::default();
+        g.add_geonames(&dump, &Filter::default()).unwrap();
+        let places = Places::Gazetteer(&g, HashSet::new());
+        let records = extract("In Sofala, the Red Cross is providing emergency shelter kits in Buzi, Mozambique.", &places).unwrap();
+        assert_eq!(who_what_where(&records), vec![("RED CROSS", "providing emergency shelter kits", Some("shelter"), Some("Buzi"))]);
+        assert_eq!((records[0].admin_level, records[0].place_id.as_deref()), (Some(2), Some("3")));
+    }
+}
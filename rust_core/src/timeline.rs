@@ -0,0 +1,485 @@
+//! Per-event figure timelines.
+//!
+//! Articles about one event report its figures over days or weeks, often
+//! repeating stale numbers, mistyping a digit or quoting a regional total.
+//! `build_timeline` turns the dated figures into one series per key: the
+//! best value per day, wild values flagged instead of plotted, and
+//! cumulative counts (deaths, houses destroyed) kept from going down when
+//! a later article repeats an older figure.
+//!
+//! `severity_trend` reads the same records as a trend: whether the latest
+//! reports show the event escalating, stable or de-escalating against the
+//! earlier ones, for deciding when an alert is worth sending again.
+
+use chrono::{DateTime, NaiveDate};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::date_parse::timestamp_from_py;
+use crate::errors::unlocked;
+
+/// Figure keys that only grow over an event.
+const CUMULATIVE_KEYS: &[&str] = &[
+    "deaths",
+    "injured",
+    "houses_affected",
+    "schools_affected",
+    "health_facilities_affected",
+    "crops_hectares_affected",
+    "livestock_lost",
+    "bridges_damaged",
+    "roads_km_damaged",
+];
+/// Values this many times above or below their neighbours' median are
+/// outliers.
+const OUTLIER_FACTOR: i64 = 10;
+/// Neighbours are reports within this many days.
+const NEIGHBOUR_DAYS: i64 = 3;
+/// Reports needed around a value before it can be judged an outlier.
+const MIN_NEIGHBOURS: usize = 3;
+
+const DAY_SECS: i64 = 86_400;
+/// Trend score per severity phase of change.
+const SEVERITY_WEIGHT: f64 = 0.5;
+/// Figure changes are relative and capped at this either way, so one
+/// count going from 1 to 40 doesn't swamp everything else.
+const MAX_FIGURE_CHANGE: f64 = 1.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Point {
+    pub date: NaiveDate,
+    pub value: i64,
+    /// Non-outlier reports that day.
+    pub reports: usize,
+    /// Raised to the previous day's value to keep a cumulative series
+    /// from decreasing.
+    pub adjusted: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Series {
+    pub cumulative: bool,
+    pub points: Vec<Point>,
+    pub outliers: Vec<(NaiveDate, i64)>,
+}
+
+fn day(time: i64) -> NaiveDate {
+    DateTime::from_timestamp(time, 0).unwrap_or_default().date_naive()
+}
+
+fn median(values: &mut [i64]) -> i64 {
+    values.sort_unstable();
+    values[values.len() / 2]
+}
+
+fn is_outlier(value: i64, neighbours: &mut [i64]) -> bool {
+    if neighbours.len() < MIN_NEIGHBOURS {
+        return false;
+    }
+    let m = median(neighbours);
+    m > 0 && (value > m.saturating_mul(OUTLIER_FACTOR) || value.saturating_mul(OUTLIER_FACTOR) < m)
+}
+
+/// One series from `(unix_seconds, value)` reports.
+pub(crate) fn series(mut reports: Vec<(i64, i64)>, cumulative: bool) -> Series {
+    reports.sort_unstable();
+    let mut by_day: BTreeMap<NaiveDate, Vec<i64>> = BTreeMap::new();
+    let mut outliers = Vec::new();
+    for (i, &(time, value)) in reports.iter().enumerate() {
+        let mut neighbours: Vec<i64> = reports
+            .iter()
+            .enumerate()
+            .filter(|(j, (t, _))| *j != i && (t - time).abs() <= NEIGHBOUR_DAYS * DAY_SECS)
+            .map(|(_, (_, v))| *v)
+            .collect();
+        if is_outlier(value, &mut neighbours) {
+            outliers.push((day(time), value));
+        } else {
+            by_day.entry(day(time)).or_default().push(value);
+        }
+    }
+    let mut running = i64::MIN;
+    let points = by_day
+        .into_iter()
+        .map(|(date, mut values)| {
+            // The latest figure is usually the highest for a count that
+            // only grows; otherwise take the day's consensus
+            let best = if cumulative { *values.iter().max().unwrap() } else { median(&mut values) };
+            let adjusted = cumulative && best < running;
+            let value = if adjusted { running } else { best };
+            running = running.max(value);
+            Point { date, value, reports: values.len(), adjusted }
+        })
+        .collect();
+    Series { cumulative, points, outliers }
+}
+
+/// Series per figure key from `(unix_seconds, figures)` records.
+pub(crate) fn build(records: &[(i64, HashMap<String, i64>)], cumulative: &HashSet<String>) -> BTreeMap<String, Series> {
+    let mut reports: BTreeMap<String, Vec<(i64, i64)>> = BTreeMap::new();
+    for (time, figures) in records {
+        for (key, value) in figures {
+            reports.entry(key.clone()).or_default().push((*time, *value));
+        }
+    }
+    reports
+        .into_iter()
+        .map(|(key, reports)| {
+            let series = series(reports, cumulative.contains(&key));
+            (key, series)
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct FigureDelta {
+    pub before: i64,
+    pub after: i64,
+}
+
+impl FigureDelta {
+    /// Relative change, capped at `MAX_FIGURE_CHANGE` either way.
+    fn change(&self) -> f64 {
+        let change = (self.after - self.before) as f64 / self.before.max(1) as f64;
+        change.clamp(-MAX_FIGURE_CHANGE, MAX_FIGURE_CHANGE)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Trend {
+    pub trend: &'static str,
+    pub score: f64,
+    /// Mean severity of the recent reports less that of the earlier ones.
+    pub severity_delta: f64,
+    pub figures: BTreeMap<String, FigureDelta>,
+}
+
+fn mean(values: impl Iterator<Item = i32>) -> Option<f64> {
+    let (sum, n) = values.fold((0.0, 0usize), |(sum, n), v| (sum + f64::from(v), n + 1));
+    (n > 0).then(|| sum / n as f64)
+}
+
+/// Trend of `(unix_seconds, severity, figures)` records: the reports in
+/// the last `window_days` before the latest against those before them.
+/// When every report is recent, the earliest one is the baseline.
+pub(crate) fn trend(
+    records: &[(i64, Option<i32>, HashMap<String, i64>)],
+    cumulative: &HashSet<String>,
+    window_days: f64,
+    threshold: f64,
+) -> Trend {
+    let mut sorted: Vec<&(i64, Option<i32>, HashMap<String, i64>)> = records.iter().collect();
+    sorted.sort_by_key(|(time, _, _)| *time);
+    let latest = sorted.last().map_or(0, |(time, _, _)| *time);
+    let cutoff = latest - (window_days * DAY_SECS as f64) as i64;
+    let split = match sorted.iter().position(|(time, _, _)| *time > cutoff) {
+        Some(0) | None => 1.min(sorted.len()),
+        Some(i) => i,
+    };
+    let (earlier, recent) = sorted.split_at(split);
+    let severity_delta = match (
+        mean(earlier.iter().filter_map(|(_, severity, _)| *severity)),
+        mean(recent.iter().filter_map(|(_, severity, _)| *severity)),
+    ) {
+        (Some(before), Some(after)) => after - before,
+        _ => 0.0,
+    };
+
+    let dated: Vec<(i64, HashMap<String, i64>)> = sorted.iter().map(|(time, _, figures)| (*time, figures.clone())).collect();
+    let baseline = earlier.last().map_or(NaiveDate::MIN, |(time, _, _)| day(*time));
+    let figures: BTreeMap<String, FigureDelta> = build(&dated, cumulative)
+        .into_iter()
+        .filter_map(|(key, series)| {
+            let after = series.points.last()?;
+            let before = series.points.iter().rev().find(|p| p.date <= baseline).or(series.points.first())?;
+            (before.date < after.date).then_some((key, FigureDelta { before: before.value, after: after.value }))
+        })
+        .collect();
+
+    let figure_score = if figures.is_empty() {
+        0.0
+    } else {
+        figures.values().map(FigureDelta::change).sum::<f64>() / figures.len() as f64
+    };
+    let score = SEVERITY_WEIGHT * severity_delta + figure_score;
+    let trend = if score >= threshold {
+        "escalating"
+    } else if score <= -threshold {
+        "de-escalating"
+    } else {
+        "stable"
+    };
+    Trend { trend, score, severity_delta, figures }
+}
+
+/// `(unix_seconds, figures)` of the records with both ``published`` and
+/// ``figures``.
+pub(crate) fn dated_figures(records: &Bound<'_, PyList>) -> PyResult<Vec<(i64, HashMap<String, i64>)>> {
+    let mut dated = Vec::with_capacity(records.len());
+    for record in records.iter() {
+        let record = record.downcast::<PyDict>()?;
+        let (Some(published), Some(figures)) = (record.get_item("published")?, record.get_item("figures")?) else {
+            continue;
+        };
+        if published.is_none() || figures.is_none() {
+            continue;
+        }
+        dated.push((timestamp_from_py(&published)?, figures.extract::<HashMap<String, i64>>()?));
+    }
+    Ok(dated)
+}
+
+/// The given cumulative keys, or `CUMULATIVE_KEYS`.
+pub(crate) fn cumulative_keys(keys: Option<Vec<String>>) -> HashSet<String> {
+    match keys {
+        Some(keys) => keys.into_iter().collect(),
+        None => CUMULATIVE_KEYS.iter().map(|k| k.to_string()).collect(),
+    }
+}
+
+/// Build cleaned per-figure time series for one event.
+///
+/// Each day gets one value per figure key: the highest report for
+/// cumulative keys, the median otherwise. Reports more than 10x above or
+/// below the median of the reports within 3 days (when there are at
+/// least 3) are flagged as outliers and left out. Cumulative series never
+/// decrease: a day reporting less than an earlier day carries the earlier
+/// value forward.
+///
+/// Parameters
+/// ----------
+/// records : list[dict]
+///     Article records for the event with ``published`` (ISO date or Unix
+///     seconds) and ``figures`` (dict[str, int]), e.g. ``process_article``
+///     output. Records without either are skipped.
+/// cumulative : list[str] | None
+///     Keys that only grow. Default ``deaths``, ``injured``,
+///     ``houses_affected``, ``schools_affected``,
+///     ``health_facilities_affected``, ``crops_hectares_affected``,
+///     ``livestock_lost``, ``bridges_damaged`` and ``roads_km_damaged``;
+///     counts like ``displaced`` or ``missing``, or power assets coming
+///     back online, can fall.
+///
+/// Returns
+/// -------
+/// dict[str, dict]
+///     Per figure key: ``cumulative`` (bool), ``points`` (list of
+///     ``{"date": "YYYY-MM-DD", "value", "reports", "adjusted"}`` in date
+///     order; ``adjusted`` marks values carried forward) and ``outliers``
+///     (list of ``{"date", "value"}``).
+///
+/// Raises
+/// ------
+/// ValueError
+///     If a ``published`` date isn't recognized.
+#[pyfunction]
+#[pyo3(signature = (records, cumulative=None))]
+pub fn build_timeline(
+    py: Python<'_>,
+    records: &Bound<'_, PyList>,
+    cumulative: Option<Vec<String>>,
+) -> PyResult<Py<PyDict>> {
+    let dated = dated_figures(records)?;
+    let cumulative = cumulative_keys(cumulative);
+    let timeline = unlocked(py, "build_timeline", || build(&dated, &cumulative))?;
+
+    let result = PyDict::new_bound(py);
+    for (key, series) in timeline {
+        let points = PyList::empty_bound(py);
+        for point in &series.points {
+            let dict = PyDict::new_bound(py);
+            dict.set_item("date", point.date.to_string())?;
+            dict.set_item("value", point.value)?;
+            dict.set_item("reports", point.reports)?;
+            dict.set_item("adjusted", point.adjusted)?;
+            points.append(dict)?;
+        }
+        let outliers = PyList::empty_bound(py);
+        for (date, value) in &series.outliers {
+            let dict = PyDict::new_bound(py);
+            dict.set_item("date", date.to_string())?;
+            dict.set_item("value", value)?;
+            outliers.append(dict)?;
+        }
+        let entry = PyDict::new_bound(py);
+        entry.set_item("cumulative", series.cumulative)?;
+        entry.set_item("points", points)?;
+        entry.set_item("outliers", outliers)?;
+        result.set_item(key, entry)?;
+    }
+    Ok(result.unbind())
+}
+
+/// Classify an event's severity trend from its time-ordered reports.
+///
+/// The reports within ``window_days`` of the latest are compared with
+/// the ones before (or, if all are that recent, with the earliest):
+/// the change in mean severity, and each figure's change between its
+/// cleaned timeline value at the baseline and its latest value (see
+/// ``build_timeline``). The score is half the severity change plus the
+/// mean relative figure change, each figure's capped at ±100%.
+///
+/// Parameters
+/// ----------
+/// records : list[dict]
+///     Article records for the event with ``published`` (ISO date or Unix
+///     seconds) and ``severity`` (1-5) and/or ``figures`` (dict[str,
+///     int]), e.g. ``process_article`` output. Records without
+///     ``published`` are skipped.
+/// window_days : float
+///     How far back from the latest report counts as recent. Default 3.
+/// threshold : float
+///     Score at or beyond which the event is escalating or de-escalating.
+///     Default 0.25: one severity phase, or figures up 25% on average.
+/// cumulative : list[str] | None
+///     Keys that only grow, as in ``build_timeline``.
+///
+/// Returns
+/// -------
+/// dict
+///     ``trend`` (``"escalating"``, ``"stable"`` or ``"de-escalating"``),
+///     ``score``, ``severity_delta`` (recent mean less earlier mean, 0.0
+///     without severities on both sides) and ``figure_deltas``: per figure
+///     key with values on two different days, ``{"before", "after",
+///     "delta", "change"}`` (``change`` relative to ``before``).
+///
+/// Raises
+/// ------
+/// ValueError
+///     If a ``published`` date isn't recognized, or ``window_days`` or
+///     ``threshold`` is negative.
+#[pyfunction]
+#[pyo3(signature = (records, window_days=3.0, threshold=0.25, cumulative=None))]
+pub fn severity_trend(
+    py: Python<'_>,
+    records: &Bound<'_, PyList>,
+    window_days: f64,
+    threshold: f64,
+    cumulative: Option<Vec<String>>,
+) -> PyResult<Py<PyDict>> {
+    let non_negative = |x: f64| x >= 0.0 && x.is_finite();
+    if !non_negative(window_days) || !non_negative(threshold) {
+        return Err(PyValueError::new_err("window_days and threshold must be non-negative"));
+    }
+    let mut dated = Vec::with_capacity(records.len());
+    for record in records.iter() {
+        let record = record.downcast::<PyDict>()?;
+        let Some(published) = record.get_item("published")?.filter(|p| !p.is_none()) else {
+            continue;
+        };
+        let severity = record.get_item("severity")?.map(|s| s.extract::<Option<i32>>()).transpose()?.flatten();
+        let figures = record.get_item("figures")?.map(|f| f.extract::<Option<HashMap<String, i64>>>()).transpose()?;
+        dated.push((timestamp_from_py(&published)?, severity, figures.flatten().unwrap_or_default()));
+    }
+    let cumulative = cumulative_keys(cumulative);
+    let trend = unlocked(py, "severity_trend", || trend(&dated, &cumulative, window_days, threshold))?;
+
+    let figure_deltas = PyDict::new_bound(py);
+    for (key, delta) in &trend.figures {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("before", delta.before)?;
+        dict.set_item("after", delta.after)?;
+        dict.set_item("delta", delta.after - delta.before)?;
+        dict.set_item("change", delta.change())?;
+        figure_deltas.set_item(key, dict)?;
+    }
+    let result = PyDict::new_bound(py);
+    result.set_item("trend", trend.trend)?;
+    result.set_item("score", trend.score)?;
+    result.set_item("severity_delta", trend.severity_delta)?;
+    result.set_item("figure_deltas", figure_deltas)?;
+    Ok(result.unbind())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const T0: i64 = 1_678_600_000; // 2023-03-12
+
+    fn at(days: i64, hours: i64) -> i64 {
+        T0 + days * DAY_SECS + hours * 3600
+    }
+
+    #[test]
+    fn test_cumulative_series() {
+        let reports = vec![
+            (at(0, 0), 20),
+            (at(0, 5), 27),
+            (at(1, 0), 53),
+            // A late article repeating the first toll
+            (at(2, 0), 20),
+            (at(3, 0), 67),
+        ];
+        let s = series(reports, true);
+        let values: Vec<(i64, bool)> = s.points.iter().map(|p| (p.value, p.adjusted)).collect();
+        assert_eq!(values, vec![(27, false), (53, false), (53, true), (67, false)]);
+        assert_eq!(s.points[0].reports, 2);
+        assert_eq!(s.points[0].date.to_string(), "2023-03-12");
+    }
+
+    #[test]
+    fn test_outliers_flagged() {
+        // 48,000 displaced, one report with an extra zero and one missing
+        // a digit
+        let reports = vec![
+            (at(0, 0), 48_000),
+            (at(0, 1), 500_000),
+            (at(1, 0), 50_000),
+            (at(1, 2), 4_900),
+            (at(2, 0), 51_000),
+            (at(2, 3), 47_000),
+        ];
+        let s = series(reports, false);
+        assert_eq!(s.outliers.len(), 2);
+        assert!(s.outliers.iter().all(|(_, v)| *v == 500_000 || *v == 4_900));
+        let values: Vec<i64> = s.points.iter().map(|p| p.value).collect();
+        // Non-cumulative: median per day, free to fall
+        assert_eq!(values, vec![48_000, 50_000, 51_000]);
+        // Too few neighbours to judge: nothing is flagged
+        assert!(series(vec![(at(0, 0), 10), (at(1, 0), 1000)], false).outliers.is_empty());
+    }
+
+    #[test]
+    fn test_build_by_key() {
+        let records = vec![
+            (at(0, 0), HashMap::from([("deaths".to_string(), 10), ("missing".to_string(), 40)])),
+            (at(1, 0), HashMap::from([("deaths".to_string(), 8), ("missing".to_string(), 12)])),
+        ];
+        let cumulative: HashSet<String> = CUMULATIVE_KEYS.iter().map(|k| k.to_string()).collect();
+        let timeline = build(&records, &cumulative);
+        assert_eq!(timeline.keys().collect::<Vec<_>>(), vec!["deaths", "missing"]);
+        assert!(timeline["deaths"].cumulative);
+        assert_eq!(timeline["deaths"].points[1].value, 10);
+        assert_eq!(timeline["missing"].points[1].value, 12);
+    }
+
+    #[test]
+    fn test_severity_trend() {
+        let cumulative: HashSet<String> = CUMULATIVE_KEYS.iter().map(|k| k.to_string()).collect();
+        let figures = |deaths: i64| HashMap::from([("deaths".to_string(), deaths)]);
+        let rising = vec![
+            (at(0, 0), Some(2), figures(10)),
+            (at(1, 0), Some(3), figures(12)),
+            (at(5, 0), Some(4), figures(40)),
+            (at(6, 0), Some(4), figures(45)),
+        ];
+        let t = trend(&rising, &cumulative, 3.0, 0.25);
+        assert_eq!(t.trend, "escalating");
+        assert_eq!(t.severity_delta, 1.5);
+        assert_eq!(t.figures["deaths"], FigureDelta { before: 12, after: 45 });
+
+        let easing = vec![
+            (at(0, 0), Some(4), HashMap::from([("displaced".to_string(), 20_000)])),
+            (at(4, 0), Some(3), HashMap::from([("displaced".to_string(), 9_000)])),
+        ];
+        assert_eq!(trend(&easing, &cumulative, 3.0, 0.25).trend, "de-escalating");
+        // A repeated toll a day later changes nothing
+        let flat = vec![(at(0, 0), Some(3), figures(100)), (at(1, 0), Some(3), figures(101))];
+        let t = trend(&flat, &cumulative, 3.0, 0.25);
+        assert_eq!((t.trend, t.severity_delta), ("stable", 0.0));
+        assert_eq!(trend(&flat[..1], &cumulative, 3.0, 0.25).figures.len(), 0);
+    }
+}
@@ -0,0 +1,203 @@
+//! Unicode-aware word tokenizer.
+//!
+//! UAX-29 word segmentation plus two rules it doesn't cover: hyphenated
+//! compounds stay one token (`cholera-affected`, `porte-parole`) and
+//! elided articles / possessives are split off per language (`l'eau` →
+//! `eau`, `Malawi's` → `Malawi`). The keyword matcher, text normalizer and
+//! dedup shingling share it so they all agree on what a word is.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use unicode_segmentation::UnicodeSegmentation;
+
+#[cfg(feature = "python")]
+use crate::errors::unlocked;
+
+// Hyphens that join compounds: ASCII hyphen-minus and U+2010 HYPHEN.
+const HYPHENS: &[char] = &['-', '\u{2010}'];
+const APOSTROPHES: &[char] = &['\'', '\u{2019}'];
+
+// Elided articles and pronouns split off the word they attach to.
+static FRENCH_ELISIONS: &[&str] = &[
+    "l", "d", "j", "m", "n", "s", "t", "c", "qu", "jusqu", "lorsqu", "puisqu", "quoiqu",
+];
+static ITALIAN_ELISIONS: &[&str] = &["l", "d", "un", "dell", "all", "dall", "nell", "sull", "quest"];
+
+/// A word and its byte span in the source text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Token<'a> {
+    pub text: &'a str,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Whether `c` can be part of a word; word boundaries are the edges
+/// between these and anything else.
+pub(crate) fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric()
+}
+
+fn elisions(lang: Option<&str>) -> &'static [&'static str] {
+    let primary = lang
+        .map(|l| l.trim().to_lowercase())
+        .and_then(|l| l.split(['-', '_']).next().map(str::to_string));
+    match primary.as_deref() {
+        Some("fr") => FRENCH_ELISIONS,
+        Some("it") => ITALIAN_ELISIONS,
+        _ => &[],
+    }
+}
+
+fn is_english(lang: Option<&str>) -> bool {
+    lang.is_none_or(|l| l.trim().to_lowercase().starts_with("en"))
+}
+
+/// Apply elision and possessive rules to one UAX-29 word.
+fn trim_word<'a>(token: Token<'a>, elided: &[&str], english: bool) -> Token<'a> {
+    let mut token = token;
+    if let Some(pos) = token.text.find(APOSTROPHES) {
+        let (prefix, rest) = token.text.split_at(pos);
+        let rest_start = pos + rest.chars().next().map_or(0, char::len_utf8);
+        if elided.contains(&prefix.to_lowercase().as_str()) && rest_start < token.text.len() {
+            token = Token {
+                text: &token.text[rest_start..],
+                start: token.start + rest_start,
+                end: token.end,
+            };
+        }
+    }
+    if english {
+        for suffix in ["'s", "\u{2019}s"] {
+            if token.text.len() > suffix.len() && token.text.to_lowercase().ends_with(suffix) {
+                let len = token.text.len() - suffix.len();
+                token.text = &token.text[..len];
+                token.end = token.start + len;
+                break;
+            }
+        }
+    }
+    token
+}
+
+/// Word tokens of `text` with byte offsets.
+///
+/// `lang` is a language tag (`"fr"`, `"pt-BR"`); it selects the elision
+/// rules. `None` applies the English possessive rule only.
+pub(crate) fn tokenize<'a>(text: &'a str, lang: Option<&str>) -> Vec<Token<'a>> {
+    let elided = elisions(lang);
+    let english = is_english(lang);
+    let mut tokens: Vec<Token<'a>> = Vec::new();
+    for (start, word) in text.unicode_word_indices() {
+        let token = Token {
+            text: word,
+            start,
+            end: start + word.len(),
+        };
+        // Join "word-word" when a single hyphen separates letters
+        if let Some(prev) = tokens.last_mut() {
+            let gap = &text[prev.end..start];
+            let mut gap_chars = gap.chars();
+            let joined = matches!((gap_chars.next(), gap_chars.next()), (Some(h), None) if HYPHENS.contains(&h))
+                && prev.text.chars().last().is_some_and(char::is_alphabetic)
+                && word.chars().next().is_some_and(char::is_alphabetic);
+            if joined {
+                prev.end = token.end;
+                prev.text = &text[prev.start..prev.end];
+                continue;
+            }
+        }
+        tokens.push(token);
+    }
+    tokens
+        .into_iter()
+        .map(|t| trim_word(t, elided, english))
+        .collect()
+}
+
+/// Split text into word tokens with offsets.
+///
+/// Uses Unicode (UAX-29) word boundaries, keeps hyphenated compounds
+/// together and splits off elided articles (``l'eau`` → ``eau`` for
+/// French and Italian) and English possessives (``Malawi's`` →
+/// ``Malawi``). Punctuation and whitespace produce no tokens; numbers
+/// like ``1,000`` and ``12.5`` stay whole.
+///
+/// Parameters
+/// ----------
+/// text : str
+///     The text to split.
+/// lang : str | None
+///     Language tag (``"en"``, ``"fr"``, ``"pt-BR"``) selecting the
+///     elision rules. ``None`` applies the English rules.
+///
+/// Returns
+/// -------
+/// list[tuple[str, int, int]]
+///     ``(token, start, end)`` in text order, with ``text[start:end] ==
+///     token`` (offsets are Python string indices).
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "tokenize", signature = (text, lang=None))]
+pub fn tokenize_text(py: Python<'_>, text: &str, lang: Option<&str>) -> PyResult<Vec<(String, usize, usize)>> {
+    unlocked(py, "tokenize", || tokens_with_offsets(text, lang))
+}
+
+/// `(token, start, end)` with character offsets.
+fn tokens_with_offsets(text: &str, lang: Option<&str>) -> Vec<(String, usize, usize)> {
+    let tokens = tokenize(text, lang);
+    // Convert byte offsets to character offsets in one pass
+    let mut result = Vec::with_capacity(tokens.len());
+    let (mut byte_pos, mut char_pos) = (0usize, 0usize);
+    let mut advance = |to: usize| {
+        char_pos += text[byte_pos..to].chars().count();
+        byte_pos = to;
+        char_pos
+    };
+    for token in tokens {
+        let start = advance(token.start);
+        let end = advance(token.end);
+        result.push((token.text.to_string(), start, end));
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words<'a>(text: &'a str, lang: Option<&str>) -> Vec<&'a str> {
+        tokenize(text, lang).into_iter().map(|t| t.text).collect()
+    }
+
+    #[test]
+    fn test_words_and_numbers() {
+        assert_eq!(
+            words("Over 1,000 people (12.5%) displaced — Beira.", None),
+            vec!["Over", "1,000", "people", "12.5", "displaced", "Beira"]
+        );
+        assert_eq!(words("Inondações em Moçambique", Some("pt")), vec!["Inondações", "em", "Moçambique"]);
+    }
+
+    #[test]
+    fn test_hyphens_and_apostrophes() {
+        assert_eq!(
+            words("cholera-affected areas - Malawi's south", Some("en")),
+            vec!["cholera-affected", "areas", "Malawi", "south"]
+        );
+        assert_eq!(words("l’accès à l'eau aujourd'hui", Some("fr-FR")), vec!["accès", "à", "eau", "aujourd'hui"]);
+        // Elision only applies to the configured language
+        assert_eq!(words("l'eau", Some("pt")), vec!["l'eau"]);
+    }
+
+    #[test]
+    fn test_offsets() {
+        let text = "Sécheresse: l'eau manque";
+        for t in tokenize(text, Some("fr")) {
+            assert_eq!(&text[t.start..t.end], t.text);
+        }
+        assert_eq!(
+            tokens_with_offsets(text, Some("fr")),
+            vec![("Sécheresse".to_string(), 0, 10), ("eau".to_string(), 14, 17), ("manque".to_string(), 18, 24)]
+        );
+    }
+}
@@ -0,0 +1,121 @@
+//! Tracking metadata — campaign parameters captured before stripping.
+//!
+//! `canonicalize_url` throws utm_* and click IDs away; analysts tracing
+//! how a report spread (which newsletter, which social post) want them,
+//! so `extract_tracking_metadata` returns them alongside the canonical URL.
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use url::Url;
+
+use crate::errors::unlocked;
+use crate::url_canonical::{canonicalize_url, extract_redirect_target, is_tracking_key};
+
+static UTM_FIELDS: &[&str] = &["utm_source", "utm_medium", "utm_campaign", "utm_term", "utm_content"];
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct TrackingMetadata {
+    pub canonical_url: String,
+    /// Values for `UTM_FIELDS`, in that order.
+    pub utm: [Option<String>; 5],
+    /// Other tracking params (click IDs, `utm_id`, ...), in URL order.
+    pub other: Vec<(String, String)>,
+}
+
+/// Tracking params of a URL and of any redirect target it wraps; the
+/// first value seen for a key wins.
+pub(crate) fn tracking_metadata(url_str: &str) -> TrackingMetadata {
+    let raw = url_str.trim();
+    let mut meta = TrackingMetadata {
+        canonical_url: canonicalize_url(raw),
+        ..Default::default()
+    };
+    let target = extract_redirect_target(raw);
+    for url in std::iter::once(raw).chain(target.as_deref()) {
+        let parsed = match Url::parse(url) {
+            Ok(u) => u,
+            Err(_) => continue,
+        };
+        for (key, value) in parsed.query_pairs() {
+            let key = key.to_lowercase();
+            let value = value.trim().to_string();
+            if value.is_empty() || !is_tracking_key(&key) {
+                continue;
+            }
+            match UTM_FIELDS.iter().position(|f| *f == key) {
+                Some(i) => {
+                    meta.utm[i].get_or_insert(value);
+                }
+                None if !meta.other.iter().any(|(k, _)| *k == key) => meta.other.push((key, value)),
+                None => {}
+            }
+        }
+    }
+    meta
+}
+
+/// Return a URL's tracking metadata along with its canonical form.
+///
+/// Parameters
+/// ----------
+/// url : str
+///     The URL to inspect. Tracking params inside a wrapped redirect
+///     target are included; session IDs are not.
+///
+/// Returns
+/// -------
+/// dict
+///     ``{"canonical_url": str, "utm_source": str | None,
+///     "utm_medium": ..., "utm_campaign": ..., "utm_term": ...,
+///     "utm_content": ..., "other": dict[str, str]}`` where ``other``
+///     holds remaining tracking params such as ``fbclid`` and ``gclid``.
+#[pyfunction]
+pub fn extract_tracking_metadata(py: Python<'_>, url: &str) -> PyResult<Py<PyDict>> {
+    let meta = unlocked(py, "extract_tracking_metadata", || tracking_metadata(url))?;
+    let dict = PyDict::new_bound(py);
+    dict.set_item("canonical_url", meta.canonical_url)?;
+    for (field, value) in UTM_FIELDS.iter().zip(meta.utm) {
+        dict.set_item(*field, value)?;
+    }
+    let other = PyDict::new_bound(py);
+    for (key, value) in meta.other {
+        other.set_item(key, value)?;
+    }
+    dict.set_item("other", other)?;
+    Ok(dict.unbind())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utm_fields_and_canonical() {
+        let meta = tracking_metadata(
+            "https://example.org/story?id=4&utm_source=newsletter&utm_medium=email&utm_campaign=cyclone%20freddy&fbclid=AbC",
+        );
+        assert_eq!(meta.canonical_url, "https://example.org/story?id=4");
+        assert_eq!(meta.utm[0].as_deref(), Some("newsletter"));
+        assert_eq!(meta.utm[2].as_deref(), Some("cyclone freddy"));
+        assert_eq!(meta.utm[3], None);
+        assert_eq!(meta.other, vec![("fbclid".to_string(), "AbC".to_string())]);
+    }
+
+    #[test]
+    fn test_params_inside_redirect_target() {
+        let meta = tracking_metadata(
+            "https://news.google.com/articles?url=https%3A%2F%2Fexample.org%2Fa%3Futm_source%3Dgnews&oc=5",
+        );
+        assert_eq!(meta.canonical_url, "https://example.org/a");
+        assert_eq!(meta.utm[0].as_deref(), Some("gnews"));
+        assert_eq!(meta.other, vec![("oc".to_string(), "5".to_string())]);
+    }
+
+    #[test]
+    fn test_no_tracking() {
+        let meta = tracking_metadata("https://example.org/report?PHPSESSID=1");
+        assert_eq!(meta.canonical_url, "https://example.org/report");
+        assert!(meta.utm.iter().all(Option::is_none));
+        assert!(meta.other.is_empty());
+    }
+}
@@ -0,0 +1,168 @@
+//! URL pattern blocklist for non-article pages.
+//!
+//! Login forms, tag and category indexes, site search and event
+//! calendars burn fetch budget without yielding reports. Each pattern
+//! carries a reason so the crawler can log why a URL was skipped.
+
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+use regex::Regex;
+use std::sync::RwLock;
+use url::Url;
+
+use crate::url_canonical::canonicalize_url;
+use crate::errors::{config_error, guarded, Failure};
+
+/// (reason, pattern) pairs matched against the canonical URL's
+/// lowercased path and query (`/search?q=flood`).
+static DEFAULT_PATTERNS: &[(&str, &str)] = &[
+    ("account", r"/(?:log-?in|log-?out|sign-?in|sign-?up|register|account|my-account|wp-admin|wp-login\.php)(?:[/?.]|$)"),
+    ("taxonomy", r"/(?:tag|tags|category|categories|author|topics?)/"),
+    ("search", r"(?:/search(?:[/?]|$)|[?&](?:s|search|keywords?)=)"),
+    ("calendar", r"(?:/(?:calendar|events?-calendar|agenda)(?:[/?]|$)|\.ics$|[?&](?:ical|month|date)=)"),
+    ("share", r"/(?:share|print|email-?a-?friend)(?:[/?]|$)"),
+];
+
+struct BlockPattern {
+    reason: String,
+    regex: Regex,
+}
+
+fn compile(patterns: &[(impl AsRef<str>, impl AsRef<str>)]) -> Result<Vec<BlockPattern>, String> {
+    patterns
+        .iter()
+        .map(|(reason, pattern)| {
+            let pattern = pattern.as_ref();
+            Ok(BlockPattern {
+                reason: reason.as_ref().to_string(),
+                regex: Regex::new(pattern)
+                    .map_err(|e| format!("invalid blocklist pattern {pattern:?}: {e}"))?,
+            })
+        })
+        .collect()
+}
+
+static BLOCKLIST: Lazy<RwLock<Vec<BlockPattern>>> =
+    Lazy::new(|| RwLock::new(compile(DEFAULT_PATTERNS).expect("default blocklist compiles")));
+
+/// The part of a URL the blocklist looks at: lowercased path and query.
+fn match_target(url_str: &str) -> Option<String> {
+    let parsed = Url::parse(&canonicalize_url(url_str)).ok()?;
+    let mut target = parsed.path().to_lowercase();
+    if let Some(query) = parsed.query() {
+        target.push('?');
+        target.push_str(&query.to_lowercase());
+    }
+    Some(target)
+}
+
+fn reason_in(patterns: &[BlockPattern], url_str: &str) -> Option<String> {
+    let target = match_target(url_str)?;
+    patterns
+        .iter()
+        .find(|p| p.regex.is_match(&target))
+        .map(|p| p.reason.clone())
+}
+
+/// Number of blocklist patterns, for `runtime_info`.
+pub(crate) fn inventory() -> Vec<(&'static str, usize)> {
+    vec![("blocklist_patterns", BLOCKLIST.read().unwrap_or_else(|e| e.into_inner()).len())]
+}
+
+/// Why a URL should not be fetched under the configured blocklist.
+pub(crate) fn skip_reason(url_str: &str) -> Option<String> {
+    let patterns = BLOCKLIST.read().unwrap_or_else(|e| e.into_inner());
+    reason_in(&patterns, url_str)
+}
+
+/// Reason to skip a URL as a non-article page, if any.
+///
+/// The URL is canonicalized first; patterns match its lowercased path and
+/// query.
+///
+/// Returns
+/// -------
+/// str | None
+///     The matching pattern's reason (defaults: ``"account"``,
+///     ``"taxonomy"``, ``"search"``, ``"calendar"``, ``"share"``), or None
+///     if the URL should be fetched.
+#[pyfunction]
+pub fn url_skip_reason(url: &str) -> PyResult<Option<String>> {
+    guarded("url_skip_reason", || Ok(skip_reason(url)))
+}
+
+/// Configure the non-article URL blocklist.
+///
+/// Parameters
+/// ----------
+/// patterns : list[tuple[str, str]] | None
+///     ``(reason, regex)`` pairs checked in order against the canonical
+///     URL's lowercased path and query (``"/search?q=flood"``). ``None``
+///     restores the defaults.
+/// extend : bool
+///     Append to the current patterns instead of replacing them.
+///     Default False.
+///
+/// Raises
+/// ------
+/// ConfigError
+///     If a pattern is not a valid regex (``reason`` ``"invalid_value"``);
+///     the blocklist is left unchanged.
+#[pyfunction]
+#[pyo3(signature = (patterns=None, extend=false))]
+pub fn configure_url_blocklist(patterns: Option<Vec<(String, String)>>, extend: bool) -> PyResult<()> {
+    guarded("configure_url_blocklist", || {
+        let compiled = match &patterns {
+            Some(list) => compile(list),
+            None => compile(DEFAULT_PATTERNS),
+        }
+        .map_err(|e| {
+            tracing::warn!(error = %e, "blocklist left unchanged");
+            config_error(None, Failure::new("invalid_value", e))
+        })?;
+        let mut blocklist = BLOCKLIST.write().unwrap_or_else(|e| e.into_inner());
+        if extend && patterns.is_some() {
+            blocklist.extend(compiled);
+        } else {
+            *blocklist = compiled;
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reason(url: &str) -> Option<String> {
+        reason_in(&compile(DEFAULT_PATTERNS).unwrap(), url)
+    }
+
+    #[test]
+    fn test_default_reasons() {
+        assert_eq!(reason("https://example.org/user/login?next=/x").as_deref(), Some("account"));
+        assert_eq!(reason("https://example.org/wp-login.php").as_deref(), Some("account"));
+        assert_eq!(reason("https://example.org/tag/floods/").as_deref(), Some("taxonomy"));
+        assert_eq!(reason("https://example.org/search?q=cyclone").as_deref(), Some("search"));
+        assert_eq!(reason("https://example.org/?s=cyclone").as_deref(), Some("search"));
+        assert_eq!(reason("https://example.org/events/calendar/").as_deref(), Some("calendar"));
+    }
+
+    #[test]
+    fn test_articles_pass() {
+        assert_eq!(reason("https://example.org/news/2025/03/cyclone-freddy-flash-update"), None);
+        assert_eq!(reason("https://example.org/research-on-search-and-rescue"), None);
+        assert_eq!(reason("https://example.org/catalogue/login-free-report"), None);
+        assert_eq!(reason("not a url"), None);
+    }
+
+    #[test]
+    fn test_custom_patterns() {
+        let patterns = compile(&[("archive", r"^/archive/\d{4}/$")]).unwrap();
+        assert_eq!(
+            reason_in(&patterns, "https://example.org/archive/2019/?utm_source=x").as_deref(),
+            Some("archive")
+        );
+        assert!(compile(&[("bad", "(")]).is_err());
+    }
+}
@@ -1,19 +1,373 @@
 //! URL canonicalization — tracking parameter stripping.
 //!
-//! Cleans tracking params (utm_*, fbclid, gclid, etc.) from URLs
-//! and extracts Google News redirect targets.
+//! Cleans tracking params (utm_*, fbclid, gclid, etc.) and session
+//! identifiers from URLs and unwraps Google News and other redirect targets.
+//! Internationalized hosts are keyed in punycode and shown in Unicode,
+//! and mobile hosts (`m.`, `mobile.`, `touch.`) fold into the desktop host.
 
+use once_cell::sync::Lazy;
+use percent_encoding::{percent_decode_str, percent_encode, AsciiSet, NON_ALPHANUMERIC};
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
-use url::Url;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::RwLock;
+#[cfg(feature = "python")]
+use url::Position;
+use url::{Host, ParseError, Url};
+
+#[cfg(feature = "python")]
+use crate::errors::{guarded, unlocked, url_parse_error};
+use crate::https_upgrade::upgrade_scheme;
+use crate::public_suffix::registrable_domain_of_host;
+use crate::shorteners::cached_expansion;
+use crate::url_rules::{apply_rules, fragment_policy, index_document_override, FragmentPolicy};
 
 static TRACKING_QUERY_PREFIXES: &[&str] = &["utm_"];
 static TRACKING_QUERY_KEYS: &[&str] = &["fbclid", "gclid", "oc", "ved", "cid"];
+static SESSION_QUERY_KEYS: &[&str] = &["jsessionid", "phpsessid", "sid", "sessionid"];
+static REDIRECT_QUERY_KEYS: &[&str] = &[
+    "redirect", "redirect_url", "redirect_uri", "target", "dest", "destination", "next", "r",
+];
+/// Dedicated redirect endpoints, checked before the generic
+/// `REDIRECT_QUERY_KEYS`: (host pattern, path prefix, target keys). A
+/// host ending in `.*` matches any public suffix (`google.co.mz`).
+static DEFAULT_REDIRECT_ENDPOINTS: &[(&str, &str, &[&str])] = &[
+    ("news.google.*", "/", &["url", "u", "q"]),
+    ("google.*", "/url", &["q", "url"]),
+    ("www.google.*", "/url", &["q", "url"]),
+    ("google.*", "/sorry/", &["continue"]),
+    ("www.google.*", "/sorry/", &["continue"]),
+];
+/// Chains of wrappers nested deeper than this are left wrapped; unwrapping
+/// them part way would let a second canonicalization pass go further.
+const MAX_REDIRECT_DEPTH: usize = 3;
 
-/// Strip tracking parameters from a URL.
+// Servlet-style path parameters, e.g. "/story.jsp;jsessionid=0A1B2C"
+static SESSION_PATH_PARAM: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i);(?:jsessionid|phpsessid|sessionid|sid)=[^/;]*").unwrap()
+});
+
+/// Directory-index documents folded into the directory URL; per-domain
+/// rules can replace the list with `index_documents`.
+static DEFAULT_INDEX_DOCUMENTS: &[&str] = &[
+    "index.html", "index.htm", "index.shtml", "index.php", "index.asp", "index.aspx",
+    "index.jsp", "default.htm", "default.html", "default.asp", "default.aspx",
+];
+
+static DEFAULT_MOBILE_PREFIXES: &[&str] = &["m.", "mobile.", "touch."];
+
+/// Mobile → desktop host folding, adjustable from Python via
+/// `configure_mobile_hosts`.
+struct MobileHostConfig {
+    prefixes: Vec<String>,
+    /// Explicit overrides, e.g. `m.bbc.co.uk` → `www.bbc.co.uk`.
+    host_map: HashMap<String, String>,
+}
+
+impl Default for MobileHostConfig {
+    fn default() -> Self {
+        MobileHostConfig {
+            prefixes: DEFAULT_MOBILE_PREFIXES.iter().map(|p| p.to_string()).collect(),
+            host_map: HashMap::new(),
+        }
+    }
+}
+
+static MOBILE_HOSTS: Lazy<RwLock<MobileHostConfig>> = Lazy::new(Default::default);
+
+struct RedirectEndpoint {
+    /// Exact host, or `name.*` for `name` under any public suffix.
+    host: String,
+    path_prefix: String,
+    keys: Vec<String>,
+}
+
+impl RedirectEndpoint {
+    fn new(host: &str, path_prefix: &str, keys: &[impl AsRef<str>]) -> Self {
+        Self {
+            host: host.trim().to_lowercase(),
+            path_prefix: path_prefix.to_string(),
+            keys: keys.iter().map(|k| k.as_ref().to_lowercase()).collect(),
+        }
+    }
+
+    fn matches(&self, host: &str, path: &str) -> bool {
+        if !path.starts_with(&self.path_prefix) {
+            return false;
+        }
+        match self.host.strip_suffix(".*") {
+            Some(name) => host
+                .strip_prefix(name)
+                .and_then(|rest| rest.strip_prefix('.'))
+                .is_some_and(|suffix| is_public_suffix_under(name, suffix)),
+            None => host == self.host,
+        }
+    }
+}
+
+/// True if `suffix` is a public suffix, judged by `name.suffix` being
+/// registrable exactly at its last label (`google` + `co.mz`).
+fn is_public_suffix_under(name: &str, suffix: &str) -> bool {
+    let label = name.rsplit('.').next().unwrap_or(name);
+    let candidate = format!("{label}.{suffix}");
+    registrable_domain_of_host(&candidate).is_some_and(|d| d == candidate)
+}
+
+fn default_redirect_endpoints() -> Vec<RedirectEndpoint> {
+    DEFAULT_REDIRECT_ENDPOINTS
+        .iter()
+        .map(|(host, path, keys)| RedirectEndpoint::new(host, path, keys))
+        .collect()
+}
+
+static REDIRECT_ENDPOINTS: Lazy<RwLock<Vec<RedirectEndpoint>>> =
+    Lazy::new(|| RwLock::new(default_redirect_endpoints()));
+
+/// Configure the redirect endpoints unwrapped by `canonicalize_url`.
 ///
-/// Removes utm_*, fbclid, gclid, oc, ved, cid query parameters
-/// and the fragment.
+/// Endpoints are checked in order before the generic ``?redirect=`` /
+/// ``?next=`` keys; the first whose host and path prefix match decides
+/// which query keys hold the target.
+///
+/// Parameters
+/// ----------
+/// endpoints : list[tuple[str, str, list[str]]] | None
+///     ``(host, path_prefix, keys)`` triples. ``host`` is exact, or
+///     ``"name.*"`` to match ``name`` under any public suffix
+///     (``"google.*"`` covers ``google.com`` and ``google.co.mz``).
+///     ``None`` restores the defaults: Google News, ``google.*/url`` and
+///     ``google.*/sorry/`` interstitials.
+#[cfg(feature = "python")]
 #[pyfunction]
+#[pyo3(signature = (endpoints=None))]
+pub fn configure_redirect_hosts(endpoints: Option<Vec<(String, String, Vec<String>)>>) -> PyResult<()> {
+    guarded("configure_redirect_hosts", || {
+        let endpoints = match endpoints {
+            Some(list) => list
+                .iter()
+                .map(|(host, path, keys)| RedirectEndpoint::new(host, path, keys))
+                .collect(),
+            None => default_redirect_endpoints(),
+        };
+        *REDIRECT_ENDPOINTS.write().unwrap_or_else(|e| e.into_inner()) = endpoints;
+        Ok(())
+    })
+}
+
+/// Desktop host for a mobile host, or `None` if the host isn't mobile.
+///
+/// Prefix folding only applies when what remains is still a registrable
+/// domain, so `m.co.uk` is left alone.
+fn fold_mobile_host(host: &str) -> Option<String> {
+    desktop_host_in(&MOBILE_HOSTS.read().unwrap_or_else(|e| e.into_inner()), host)
+}
+
+fn desktop_host_in(config: &MobileHostConfig, host: &str) -> Option<String> {
+    if let Some(desktop) = config.host_map.get(host) {
+        return Some(desktop.clone());
+    }
+    config.prefixes.iter().find_map(|prefix| {
+        let rest = host.strip_prefix(prefix.as_str())?;
+        registrable_domain_of_host(rest).map(|_| rest.to_string())
+    })
+}
+
+/// Configure mobile host folding used by `canonicalize_url`.
+///
+/// Parameters
+/// ----------
+/// prefixes : list[str] | None
+///     Host prefixes to strip (default ``["m.", "mobile.", "touch."]``).
+///     ``None`` keeps the current list.
+/// host_map : dict[str, str] | None
+///     Explicit mobile → desktop host mappings, checked before prefixes.
+///     ``None`` keeps the current mapping.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (prefixes=None, host_map=None))]
+pub fn configure_mobile_hosts(
+    prefixes: Option<Vec<String>>,
+    host_map: Option<HashMap<String, String>>,
+) -> PyResult<()> {
+    guarded("configure_mobile_hosts", || {
+        let mut config = MOBILE_HOSTS.write().unwrap_or_else(|e| e.into_inner());
+        if let Some(prefixes) = prefixes {
+            config.prefixes = prefixes.iter().map(|p| p.to_lowercase()).collect();
+        }
+        if let Some(host_map) = host_map {
+            config.host_map = host_map
+                .into_iter()
+                .map(|(k, v)| (k.to_lowercase(), v.to_lowercase()))
+                .collect();
+        }
+        Ok(())
+    })
+}
+
+/// Why a URL was rejected in strict mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum UrlErrorKind {
+    Empty,
+    RelativeUrl,
+    InvalidHost,
+    InvalidPort,
+    UnsupportedScheme,
+    Malformed,
+}
+
+impl UrlErrorKind {
+    /// Category string reported to Python.
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            UrlErrorKind::Empty => "empty",
+            UrlErrorKind::RelativeUrl => "relative_url",
+            UrlErrorKind::InvalidHost => "invalid_host",
+            UrlErrorKind::InvalidPort => "invalid_port",
+            UrlErrorKind::UnsupportedScheme => "unsupported_scheme",
+            UrlErrorKind::Malformed => "malformed",
+        }
+    }
+
+    fn from_parse_error(err: ParseError) -> Self {
+        match err {
+            ParseError::EmptyHost
+            | ParseError::IdnaError
+            | ParseError::InvalidIpv4Address
+            | ParseError::InvalidIpv6Address
+            | ParseError::InvalidDomainCharacter => UrlErrorKind::InvalidHost,
+            ParseError::InvalidPort => UrlErrorKind::InvalidPort,
+            ParseError::RelativeUrlWithoutBase | ParseError::RelativeUrlWithCannotBeABaseBase => {
+                UrlErrorKind::RelativeUrl
+            }
+            _ => UrlErrorKind::Malformed,
+        }
+    }
+}
+
+/// Check that `url_str` is an absolute http(s) URL with a host.
+pub(crate) fn check_url(url_str: &str) -> Result<(), UrlErrorKind> {
+    let raw = url_str.trim();
+    if raw.is_empty() {
+        return Err(UrlErrorKind::Empty);
+    }
+    let parsed = Url::parse(raw).map_err(UrlErrorKind::from_parse_error)?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(UrlErrorKind::UnsupportedScheme);
+    }
+    if parsed.host().is_none() {
+        return Err(UrlErrorKind::InvalidHost);
+    }
+    Ok(())
+}
+
+/// Characters left unescaped in query keys and values: RFC 3986 unreserved.
+const QUERY_COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+fn is_unreserved(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~')
+}
+
+/// Re-encode a raw query key or value in one canonical spelling.
+///
+/// `+` and `%20` both become `%20`, escapes of unreserved characters are
+/// decoded and all other escapes use uppercase hex.
+fn normalize_query_component(raw: &str) -> String {
+    let spaced = raw.replace('+', " ");
+    let bytes: Vec<u8> = percent_decode_str(&spaced).collect();
+    percent_encode(&bytes, QUERY_COMPONENT).to_string()
+}
+
+/// Uppercase percent-escape hex in a path and decode unreserved characters.
+fn normalize_path_escapes(path: &str) -> String {
+    if !path.contains('%') {
+        return path.to_string();
+    }
+    let bytes = path.as_bytes();
+    let hex = |b: u8| (b as char).to_digit(16);
+    let mut out = String::with_capacity(path.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let (Some(hi), Some(lo)) = (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                let decoded = (hi * 16 + lo) as u8;
+                if is_unreserved(decoded) {
+                    out.push(decoded as char);
+                } else {
+                    out.push_str(&format!("%{decoded:02X}"));
+                }
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i] as char);
+        i += 1;
+    }
+    out
+}
+
+/// Tracking keys and key prefixes added by `MoltisConfig`, on top of
+/// the built-in lists.
+#[derive(Default)]
+struct ExtraTracking {
+    keys: Vec<String>,
+    prefixes: Vec<String>,
+}
+
+static EXTRA_TRACKING: Lazy<RwLock<ExtraTracking>> = Lazy::new(|| RwLock::new(ExtraTracking::default()));
+
+/// Replace the configured extra tracking keys and prefixes.
+pub(crate) fn set_extra_tracking(keys: &[String], prefixes: &[String]) {
+    let mut extra = EXTRA_TRACKING.write().unwrap_or_else(|e| e.into_inner());
+    extra.keys = keys.iter().map(|k| k.trim().to_lowercase()).collect();
+    extra.prefixes = prefixes.iter().map(|p| p.trim().to_lowercase()).filter(|p| !p.is_empty()).collect();
+}
+
+/// Sizes of the tracking and canonicalization tables, for
+/// `runtime_info`.
+pub(crate) fn inventory() -> Vec<(&'static str, usize)> {
+    let extra = EXTRA_TRACKING.read().unwrap_or_else(|e| e.into_inner());
+    let mobile = MOBILE_HOSTS.read().unwrap_or_else(|e| e.into_inner());
+    vec![
+        ("tracking_keys", TRACKING_QUERY_KEYS.len()),
+        ("tracking_prefixes", TRACKING_QUERY_PREFIXES.len()),
+        ("config_tracking_keys", extra.keys.len()),
+        ("config_tracking_prefixes", extra.prefixes.len()),
+        ("session_keys", SESSION_QUERY_KEYS.len()),
+        ("redirect_keys", REDIRECT_QUERY_KEYS.len()),
+        ("redirect_endpoints", REDIRECT_ENDPOINTS.read().unwrap_or_else(|e| e.into_inner()).len()),
+        ("mobile_prefixes", mobile.prefixes.len()),
+        ("mobile_host_overrides", mobile.host_map.len()),
+    ]
+}
+
+/// True for a lowercased query key that only carries tracking data
+/// (`utm_*`, `fbclid`, ...).
+pub(crate) fn is_tracking_key(lowercase_key: &str) -> bool {
+    if TRACKING_QUERY_KEYS.contains(&lowercase_key)
+        || TRACKING_QUERY_PREFIXES.iter().any(|p| lowercase_key.starts_with(p))
+    {
+        return true;
+    }
+    let extra = EXTRA_TRACKING.read().unwrap_or_else(|e| e.into_inner());
+    extra.keys.iter().any(|k| k == lowercase_key) || extra.prefixes.iter().any(|p| lowercase_key.starts_with(p.as_str()))
+}
+
+/// Remove session path parameters (`;jsessionid=...`) from a URL path.
+fn strip_session_path(path: &str) -> String {
+    SESSION_PATH_PARAM.replace_all(path, "").into_owned()
+}
+
+/// Strip tracking parameters from a URL.
+///
+/// Removes utm_*, fbclid, gclid, oc, ved, cid query parameters,
+/// session identifiers (jsessionid, PHPSESSID, sid, sessionid — in the
+/// query or as `;jsessionid=` path parameters) and the fragment.
+/// Unparseable input is returned unchanged.
 pub fn strip_tracking_params(url_str: &str) -> String {
     let parsed = match Url::parse(url_str) {
         Ok(u) => u,
@@ -21,20 +375,26 @@ pub fn strip_tracking_params(url_str: &str) -> String {
     };
 
     let mut clean = parsed.clone();
-    // Collect clean query pairs
+    let mut path = normalize_path_escapes(parsed.path());
+    if path.contains(';') {
+        path = strip_session_path(&path);
+    }
+    clean.set_path(&path);
+
+    // Collect clean query pairs, re-encoded in one consistent form
     let clean_pairs: Vec<(String, String)> = parsed
-        .query_pairs()
+        .query()
+        .unwrap_or("")
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+            (normalize_query_component(k), normalize_query_component(v))
+        })
         .filter(|(key, _)| {
-            let lk = key.to_lowercase();
-            if TRACKING_QUERY_KEYS.contains(&lk.as_str()) {
-                return false;
-            }
-            if TRACKING_QUERY_PREFIXES.iter().any(|p| lk.starts_with(p)) {
-                return false;
-            }
-            true
+            let lk = percent_decode_str(key).decode_utf8_lossy().to_lowercase();
+            !is_tracking_key(&lk) && !SESSION_QUERY_KEYS.contains(&lk.as_str())
         })
-        .map(|(k, v)| (k.to_string(), v.to_string()))
         .collect();
 
     // Rebuild query string
@@ -59,53 +419,313 @@ pub fn strip_tracking_params(url_str: &str) -> String {
     clean.to_string()
 }
 
-/// Canonicalize a URL: extract Google News targets and strip tracking params.
+/// Strip tracking parameters from a URL.
+///
+/// Parameters
+/// ----------
+/// url_str : str
+///     The URL to clean.
+/// strict : bool
+///     Raise ``UrlParseError`` instead of returning malformed input
+///     unchanged. Default False.
+///
+/// Raises
+/// ------
+/// UrlParseError
+///     In strict mode, if the input is not an absolute http(s) URL.
+///     ``args[1]`` is the failure category (``"empty"``,
+///     ``"relative_url"``, ``"invalid_host"``, ``"invalid_port"``,
+///     ``"unsupported_scheme"`` or ``"malformed"``).
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "strip_tracking_params", signature = (url_str, strict=false))]
+pub fn py_strip_tracking_params(py: Python<'_>, url_str: &str, strict: bool) -> PyResult<String> {
+    if strict {
+        check_url(url_str).map_err(|kind| url_parse_error(url_str, kind))?;
+    }
+    unlocked(py, "strip_tracking_params", || strip_tracking_params(url_str))
+}
+
+/// Canonicalize a URL: expand known short links, unwrap Google News and
+/// other redirect wrappers, strip tracking params, fold mobile hosts into
+/// their desktop host, upgrade known HTTPS hosts and apply any per-domain
+/// rules loaded with `load_url_rules`.
+pub fn canonicalize_url(url_str: &str) -> String {
+    let raw = url_str.trim();
+    if raw.is_empty() {
+        return raw.to_string();
+    }
+    let expanded = cached_expansion(raw);
+    let raw = expanded.as_deref().unwrap_or(raw);
+
+    let (raw, kept_fragment) = match Url::parse(raw) {
+        Ok(parsed) if parsed.fragment().is_some() => {
+            let policy = fragment_policy(parsed.host_str().unwrap_or(""));
+            prepare_fragment(&parsed, policy)
+        }
+        _ => (raw.to_string(), None),
+    };
+
+    // Unwrap Google News and other redirect wrappers
+    let stripped = match extract_redirect_target(&raw) {
+        Some(target) => strip_tracking_params(&target),
+        None => strip_tracking_params(&raw),
+    };
+    let canonical = apply_rules(strip_index_document(upgrade_scheme(fold_mobile_url(stripped))));
+    // Only keep the fragment if the final host keeps fragments too, so a
+    // second pass over the result makes the same choice.
+    match kept_fragment {
+        Some(fragment) if keeps_fragment(&canonical) => reattach_fragment(canonical, &fragment),
+        _ => canonical,
+    }
+}
+
+fn keeps_fragment(url_str: &str) -> bool {
+    Url::parse(url_str).is_ok_and(|u| fragment_policy(u.host_str().unwrap_or("")) == FragmentPolicy::Keep)
+}
+
+/// Apply a fragment policy ahead of the generic cleanup, which always
+/// drops fragments. Returns the URL to clean and any fragment to put back.
+fn prepare_fragment(parsed: &Url, policy: FragmentPolicy) -> (String, Option<String>) {
+    let fragment = parsed.fragment().unwrap_or("");
+    match policy {
+        FragmentPolicy::Strip => (parsed.to_string(), None),
+        FragmentPolicy::Keep => (parsed.to_string(), Some(fragment.to_string())),
+        FragmentPolicy::HashbangToPath => {
+            // "#!/article/123" is absolute, "#!article/123" relative to the path
+            let routed = fragment
+                .strip_prefix('!')
+                .filter(|route| !route.is_empty())
+                .and_then(|route| parsed.join(route).ok());
+            (routed.unwrap_or_else(|| parsed.clone()).to_string(), None)
+        }
+    }
+}
+
+fn reattach_fragment(url_str: String, fragment: &str) -> String {
+    match Url::parse(&url_str) {
+        Ok(mut u) => {
+            u.set_fragment(Some(fragment));
+            u.to_string()
+        }
+        Err(_) => url_str,
+    }
+}
+
+/// Strict canonical form for database keys: the canonical URL with its
+/// fragment dropped and query pairs sorted, or why the URL has none.
+pub(crate) fn canonical_key_form(url_str: &str) -> Result<String, UrlErrorKind> {
+    check_url(url_str)?;
+    let canonical = canonicalize_url(url_str);
+    let mut parsed = Url::parse(&canonical).map_err(UrlErrorKind::from_parse_error)?;
+    parsed.set_fragment(None);
+    if let Some(query) = parsed.query() {
+        let mut pairs: Vec<&str> = query.split('&').filter(|p| !p.is_empty()).collect();
+        pairs.sort_unstable();
+        let sorted = pairs.join("&");
+        parsed.set_query(if sorted.is_empty() { None } else { Some(&sorted) });
+    }
+    Ok(parsed.to_string())
+}
+
+/// Canonicalize a URL: expand known short links, unwrap redirect
+/// wrappers, strip tracking params, fold mobile hosts, upgrade known HTTPS
+/// hosts and apply per-domain rules.
+///
+/// The result is a fixed point: canonicalizing it again returns it
+/// unchanged.
 ///
 /// Parameters
 /// ----------
 /// url_str : str
 ///     The URL to canonicalize.
+/// strict : bool
+///     Raise ``UrlParseError`` instead of returning malformed input
+///     unchanged. Default False.
+/// key_form : bool
+///     Return the strict canonical form for use as a database key: implies
+///     ``strict``, always drops the fragment and sorts query parameters.
+///     Default False.
 ///
 /// Returns
 /// -------
 /// str
 ///     The canonicalized URL.
+///
+/// Raises
+/// ------
+/// UrlParseError
+///     In strict mode, if the input is not an absolute http(s) URL.
+#[cfg(feature = "python")]
 #[pyfunction]
-pub fn canonicalize_url(url_str: &str) -> String {
-    let raw = url_str.trim();
-    if raw.is_empty() {
-        return raw.to_string();
+#[pyo3(name = "canonicalize_url", signature = (url_str, strict=false, key_form=false))]
+pub fn py_canonicalize_url(py: Python<'_>, url_str: &str, strict: bool, key_form: bool) -> PyResult<String> {
+    if key_form {
+        return unlocked(py, "canonicalize_url", || canonical_key_form(url_str))?
+            .map_err(|kind| url_parse_error(url_str, kind));
+    }
+    if strict {
+        check_url(url_str).map_err(|kind| url_parse_error(url_str, kind))?;
+    }
+    unlocked(py, "canonicalize_url", || canonicalize_url(url_str))
+}
+
+/// Fold a trailing directory-index document (`/news/index.php`) into the
+/// directory form (`/news/`).
+fn strip_index_document(url_str: String) -> String {
+    let mut parsed = match Url::parse(&url_str) {
+        Ok(u) => u,
+        Err(_) => return url_str,
+    };
+    let (dir, file_name) = match parsed.path().rsplit_once('/') {
+        Some((dir, name)) if !name.is_empty() => (format!("{dir}/"), name.to_string()),
+        _ => return url_str,
+    };
+    let host = parsed.host_str().unwrap_or("");
+    let is_index = index_document_override(host, &file_name).unwrap_or_else(|| {
+        DEFAULT_INDEX_DOCUMENTS
+            .iter()
+            .any(|d| d.eq_ignore_ascii_case(&file_name))
+    });
+    if !is_index {
+        return url_str;
     }
+    parsed.set_path(&dir);
+    parsed.to_string()
+}
 
-    // Try to extract Google News target URL
-    if let Some(target) = extract_google_target(raw) {
-        return strip_tracking_params(&target);
+/// Rewrite a mobile host (`m.example.com`) to its desktop host.
+fn fold_mobile_url(url_str: String) -> String {
+    let mut parsed = match Url::parse(&url_str) {
+        Ok(u) => u,
+        Err(_) => return url_str,
+    };
+    let desktop = match parsed.host() {
+        Some(Host::Domain(domain)) => fold_mobile_host(domain),
+        _ => None,
+    };
+    match desktop {
+        Some(host) if parsed.set_host(Some(&host)).is_ok() => parsed.to_string(),
+        _ => url_str,
     }
+}
 
-    strip_tracking_params(raw)
+/// Follow redirect wrappers (Google News, `?redirect=`, `?next=`, ...) to
+/// the innermost target URL, up to `MAX_REDIRECT_DEPTH` levels.
+pub(crate) fn extract_redirect_target(url_str: &str) -> Option<String> {
+    let mut target = None;
+    for _ in 0..MAX_REDIRECT_DEPTH {
+        match redirect_target_once(target.as_deref().unwrap_or(url_str)) {
+            Some(next) => target = Some(next),
+            None => return target,
+        }
+    }
+    // Still wrapped after the last level: too deep to unwrap
+    match target {
+        Some(ref innermost) if redirect_target_once(innermost).is_some() => None,
+        _ => target,
+    }
 }
 
-/// Extract the real target URL from a Google News redirect.
-fn extract_google_target(url_str: &str) -> Option<String> {
+/// Target URL embedded in one level of redirect wrapper.
+fn redirect_target_once(url_str: &str) -> Option<String> {
     let parsed = Url::parse(url_str).ok()?;
-    let host = parsed.host_str()?;
-    if !host.contains("news.google.") {
+    let host = parsed.host_str()?.to_lowercase();
+    let endpoints = REDIRECT_ENDPOINTS.read().unwrap_or_else(|e| e.into_inner());
+    let pairs: Vec<(String, String)> = parsed
+        .query_pairs()
+        .map(|(k, v)| (k.to_lowercase(), v.trim().to_string()))
+        .collect();
+
+    // Dedicated endpoints: their keys, in priority order
+    if let Some(endpoint) = endpoints.iter().find(|e| e.matches(&host, parsed.path())) {
+        return endpoint.keys.iter().find_map(|key| {
+            pairs
+                .iter()
+                .filter(|(k, _)| k == key)
+                .find_map(|(_, v)| safe_redirect_target(v))
+        });
+    }
+    pairs
+        .iter()
+        .filter(|(k, _)| REDIRECT_QUERY_KEYS.contains(&k.as_str()))
+        .find_map(|(_, v)| safe_redirect_target(v))
+}
+
+/// Accept only absolute http(s) targets with a host and no credentials.
+fn safe_redirect_target(candidate: &str) -> Option<String> {
+    if !(candidate.starts_with("http://") || candidate.starts_with("https://")) {
         return None;
     }
+    let target = Url::parse(candidate).ok()?;
+    if target.host().is_none() || !target.username().is_empty() || target.password().is_some() {
+        return None;
+    }
+    Some(target.to_string())
+}
 
-    for (key, value) in parsed.query_pairs() {
-        let lk = key.to_lowercase();
-        if matches!(lk.as_str(), "url" | "u" | "q") {
-            let candidate = value.trim().to_string();
-            if candidate.starts_with("http://") || candidate.starts_with("https://") {
-                return Some(candidate);
-            }
-        }
+/// Normalize an internationalized host name.
+///
+/// Unicode and `xn--` spellings of the same host map to one form:
+/// punycode (the key form, matching what `canonicalize_url` emits) or,
+/// with ``for_display=True``, Unicode.
+///
+/// Returns
+/// -------
+/// str | None
+///     The normalized host, or None if it is not a valid domain name.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (host, for_display=false))]
+pub fn normalize_host(host: &str, for_display: bool) -> PyResult<Option<String>> {
+    guarded("normalize_host", || Ok(host_form(host, for_display)))
+}
+
+/// Punycode or, `for_display`, Unicode form of a host name.
+#[cfg(feature = "python")]
+fn host_form(host: &str, for_display: bool) -> Option<String> {
+    let ascii = idna::domain_to_ascii(host.trim().trim_end_matches('.')).ok()?;
+    if ascii.is_empty() {
+        return None;
+    }
+    if for_display {
+        let (unicode, result) = idna::domain_to_unicode(&ascii);
+        return result.ok().map(|_| unicode);
+    }
+    Some(ascii)
+}
+
+/// Render a URL for display, with its host in Unicode form.
+///
+/// Unparseable input is returned unchanged.
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn display_url(url_str: &str) -> PyResult<String> {
+    guarded("display_url", || Ok(display_form(url_str)))
+}
+
+#[cfg(feature = "python")]
+fn display_form(url_str: &str) -> String {
+    let parsed = match Url::parse(url_str.trim()) {
+        Ok(u) => u,
+        Err(_) => return url_str.to_string(),
+    };
+    let unicode_host = match parsed.host() {
+        Some(Host::Domain(domain)) => host_form(domain, true),
+        _ => None,
+    };
+    match unicode_host {
+        Some(host) => format!(
+            "{}{}{}",
+            &parsed[..Position::BeforeHost],
+            host,
+            &parsed[Position::AfterHost..]
+        ),
+        None => parsed.to_string(),
     }
-    None
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "python"))]
 mod tests {
     use super::*;
 
@@ -125,6 +745,36 @@ mod tests {
         assert_eq!(result, "https://example.com/news?page=1");
     }
 
+    #[test]
+    fn test_strip_session_query() {
+        let result = strip_tracking_params(
+            "https://example.org/report?PHPSESSID=9f8e7d&id=7&sid=abc&sessionid=x1",
+        );
+        assert_eq!(result, "https://example.org/report?id=7");
+    }
+
+    #[test]
+    fn test_strip_jsessionid_path() {
+        let result = strip_tracking_params(
+            "https://example.org/news/story.jsp;jsessionid=0A1B2C3D?id=42",
+        );
+        assert_eq!(result, "https://example.org/news/story.jsp?id=42");
+    }
+
+    #[test]
+    fn test_percent_encoding_normalization() {
+        let a = strip_tracking_params("https://example.org/a%2db/%7euser?q=flood+relief&x=%e2%9c%93");
+        let b = strip_tracking_params("https://example.org/a-b/~user?q=flood%20relief&x=%E2%9C%93");
+        assert_eq!(a, "https://example.org/a-b/~user?q=flood%20relief&x=%E2%9C%93");
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_encoded_ampersand_preserved() {
+        let result = strip_tracking_params("https://example.org/search?q=food%26water&utm_medium=x");
+        assert_eq!(result, "https://example.org/search?q=food%26water");
+    }
+
     #[test]
     fn test_no_params() {
         let result = strip_tracking_params("https://example.com/article");
@@ -139,9 +789,194 @@ mod tests {
         assert_eq!(result, "https://example.com/story");
     }
 
+    #[test]
+    fn test_idn_variants_share_canonical_form() {
+        let unicode = canonicalize_url("https://München.de/nachrichten");
+        let puny = canonicalize_url("https://XN--MNCHEN-3YA.de/nachrichten");
+        assert_eq!(unicode, "https://xn--mnchen-3ya.de/nachrichten");
+        assert_eq!(unicode, puny);
+    }
+
+    #[test]
+    fn test_normalize_host() {
+        assert_eq!(host_form("München.DE.", false).as_deref(), Some("xn--mnchen-3ya.de"));
+        assert_eq!(host_form("xn--mnchen-3ya.de", true).as_deref(), Some("münchen.de"));
+        assert_eq!(
+            display_form("https://xn--mnchen-3ya.de/a?b=1"),
+            "https://münchen.de/a?b=1"
+        );
+    }
+
+    #[test]
+    fn test_mobile_host_folding() {
+        assert_eq!(
+            canonicalize_url("https://m.example.org/news/flood?id=3"),
+            "https://example.org/news/flood?id=3"
+        );
+        assert_eq!(
+            canonicalize_url("https://touch.example.co.mz/a"),
+            "https://example.co.mz/a"
+        );
+        // Would leave a bare public suffix — not folded
+        assert_eq!(canonicalize_url("https://m.co.uk/a"), "https://m.co.uk/a");
+    }
+
+    #[test]
+    fn test_mobile_host_map() {
+        let mut config = MobileHostConfig::default();
+        config.host_map.insert("mobile.relief.test".to_string(), "www.relief.test".to_string());
+        assert_eq!(desktop_host_in(&config, "mobile.relief.test").as_deref(), Some("www.relief.test"));
+        // Prefixes still apply to hosts without an override
+        assert_eq!(desktop_host_in(&config, "m.example.org").as_deref(), Some("example.org"));
+        assert_eq!(desktop_host_in(&config, "www.example.org"), None);
+    }
+
+    #[test]
+    fn test_nested_redirect_params() {
+        assert_eq!(
+            canonicalize_url(
+                "https://portal.gov.mz/out?dest=https%3A%2F%2Fexample.org%2Fnews%2F1%3Futm_source%3Dportal"
+            ),
+            "https://example.org/news/1"
+        );
+        // Wrapper inside a wrapper
+        let inner = "https%3A%2F%2Fexample.org%2Fstory%3Fid%3D9";
+        let outer = format!(
+            "https://a.gov/r?target=https%3A%2F%2Fb.gov%2Fgo%3Fredirect%3D{}",
+            inner.replace('%', "%25")
+        );
+        assert_eq!(canonicalize_url(&outer), "https://example.org/story?id=9");
+    }
+
+    #[test]
+    fn test_google_redirect_endpoints() {
+        assert_eq!(
+            redirect_target_once("https://www.google.com/url?sa=t&q=https://example.org/a&ved=x").as_deref(),
+            Some("https://example.org/a")
+        );
+        assert_eq!(
+            redirect_target_once("https://news.google.co.mz/articles?url=https://example.org/b").as_deref(),
+            Some("https://example.org/b")
+        );
+        assert_eq!(
+            redirect_target_once("https://www.google.com.br/sorry/index?continue=https://example.org/c").as_deref(),
+            Some("https://example.org/c")
+        );
+        // Search results and look-alike hosts are not redirects
+        assert_eq!(redirect_target_once("https://www.google.com/search?q=https://example.org/"), None);
+        assert_eq!(redirect_target_once("https://google.evil.test/url?q=https://example.org/"), None);
+    }
+
+    #[test]
+    fn test_redirect_endpoint_patterns() {
+        let endpoint = RedirectEndpoint::new("l.facebook.com", "/l.php", &["u"]);
+        assert!(endpoint.matches("l.facebook.com", "/l.php"));
+        assert!(!endpoint.matches("l.facebook.com", "/other"));
+        let wildcard = RedirectEndpoint::new("google.*", "/url", &["q"]);
+        assert!(wildcard.matches("google.co.uk", "/url"));
+        assert!(!wildcard.matches("google.example.co.uk", "/url"));
+    }
+
+    #[test]
+    fn test_redirect_safety_checks() {
+        // Relative targets, other schemes and credentials are ignored
+        assert_eq!(redirect_target_once("https://a.gov/login?next=/account"), None);
+        assert_eq!(redirect_target_once("https://a.gov/go?r=javascript:alert(1)"), None);
+        assert_eq!(redirect_target_once("https://a.gov/go?r=https://user:pw@evil.test/"), None);
+        // Search queries on ordinary hosts are not redirects
+        assert_eq!(redirect_target_once("https://a.gov/search?q=https://b.gov/"), None);
+    }
+
+    #[test]
+    fn test_fragment_policies() {
+        let spa = Url::parse("https://spa.example/app/#!/article/123?lang=fr").unwrap();
+        assert_eq!(
+            prepare_fragment(&spa, FragmentPolicy::HashbangToPath).0,
+            "https://spa.example/article/123?lang=fr"
+        );
+        let relative = Url::parse("https://spa.example/news/#!story-9").unwrap();
+        assert_eq!(
+            prepare_fragment(&relative, FragmentPolicy::HashbangToPath).0,
+            "https://spa.example/news/story-9"
+        );
+        let anchor = Url::parse("https://docs.example/page?utm_source=x#section-2").unwrap();
+        let (url, kept) = prepare_fragment(&anchor, FragmentPolicy::Keep);
+        let canonical = reattach_fragment(strip_tracking_params(&url), kept.as_deref().unwrap());
+        assert_eq!(canonical, "https://docs.example/page#section-2");
+        assert_eq!(prepare_fragment(&anchor, FragmentPolicy::Strip).1, None);
+    }
+
+    #[test]
+    fn test_check_url_categories() {
+        assert_eq!(check_url("https://example.org/a"), Ok(()));
+        assert_eq!(check_url("  "), Err(UrlErrorKind::Empty));
+        assert_eq!(check_url("/relative/path"), Err(UrlErrorKind::RelativeUrl));
+        assert_eq!(check_url("http://exa mple.org/"), Err(UrlErrorKind::InvalidHost));
+        assert_eq!(check_url("http://example.org:99999/"), Err(UrlErrorKind::InvalidPort));
+        assert_eq!(check_url("javascript:void(0)"), Err(UrlErrorKind::UnsupportedScheme));
+    }
+
+    #[test]
+    fn test_strict_mode() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let err = py_strip_tracking_params(py, "not a url", true).unwrap_err();
+            assert!(err.is_instance_of::<crate::errors::UrlParseError>(py));
+            assert!(err.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+            let args = err.value_bound(py).getattr("args").unwrap();
+            let category: String = args.get_item(1).unwrap().extract().unwrap();
+            assert_eq!(category, "relative_url");
+            assert_eq!(py_strip_tracking_params(py, "not a url", false).unwrap(), "not a url");
+        });
+    }
+
     #[test]
     fn test_empty() {
         assert_eq!(canonicalize_url(""), "");
         assert_eq!(canonicalize_url("  "), "");
     }
+
+    #[test]
+    fn test_idempotent() {
+        let cases = [
+            "https://example.org/a%2db/%7euser?q=flood+relief&x=%e2%9c%93",
+            "https://example.org/search?q=food%26water&a=b=c&&utm_medium=x",
+            "https://example.org/p?q=1%2B1&empty=&flag",
+            "https://example.org/a/%2E%2E/b/./c?%61=1",
+            "https://example.org/caf\u{e9}/na\u{ef}ve path?t=\u{e9}t\u{e9}",
+            "HTTPS://WWW.Example.ORG:443/Path?B=2&a=1#frag",
+            "https://m.example.co.uk/news/story.jsp;jsessionid=0A1B?id=42",
+            "https://news.google.com/rss/articles?url=https%3A%2F%2Fexample.com%2Fstory%3Fq%3Da%2Bb&oc=5",
+            "https://a.gov/r?target=https%3A%2F%2Fb.gov%2Fgo%3Fredirect%3Dhttps%253A%252F%252Fc.gov%252Fgo%253Fnext%253Dhttps%25253A%25252F%25252Fd.gov%25252Fnext%25253Fr%25253Dhttps%2525253A%2525252F%2525252Fexample.org%2525252Fdeep",
+            "https://xn--mnchen-3ya.example/stra\u{df}e",
+            "http://example.org/?",
+            "https://example.org/%zz?%zz=%",
+        ];
+        for case in cases {
+            let once = canonicalize_url(case);
+            assert_eq!(canonicalize_url(&once), once, "not idempotent for {case:?}");
+        }
+    }
+
+    #[test]
+    fn test_strip_index_document() {
+        assert_eq!(
+            canonicalize_url("https://www.ingc.gov.example/noticias/index.php?id=12&utm_source=x"),
+            "https://www.ingc.gov.example/noticias/?id=12"
+        );
+        assert_eq!(canonicalize_url("https://example.org/Default.aspx"), "https://example.org/");
+        assert_eq!(
+            canonicalize_url("https://example.org/docs/index.html.bak"),
+            "https://example.org/docs/index.html.bak"
+        );
+        assert_eq!(canonicalize_url("https://example.org/index.php/news/"), "https://example.org/index.php/news/");
+    }
+
+    #[test]
+    fn test_key_form() {
+        let key = canonical_key_form("https://example.org/r?b=2&utm_source=x&a=1#top").unwrap();
+        assert_eq!(key, "https://example.org/r?a=1&b=2");
+        assert_eq!(canonical_key_form(&key).unwrap(), key);
+        assert_eq!(canonical_key_form("/relative"), Err(UrlErrorKind::RelativeUrl));
+    }
 }
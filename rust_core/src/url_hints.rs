@@ -0,0 +1,198 @@
+//! URL hints — publication dates and languages embedded in URL paths.
+//!
+//! News and agency sites often encode the publication date
+//! (`/2025/03/14/...`, `/news/20250314-...`) and language (`/fr/`,
+//! `/pt-br/`) in the path; the scheduler uses these for recency scoring
+//! before the page is fetched.
+
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use regex::Regex;
+use url::Url;
+
+use crate::errors::unlocked;
+
+// ISO 639-1 codes likely to appear as path segments on our sources.
+static LANGUAGE_CODES: &[&str] = &[
+    "en", "fr", "es", "pt", "ar", "ru", "zh", "sw", "am", "so", "ha", "ti", "ur", "fa", "ps",
+    "bn", "hi", "id", "my", "km", "ne", "si", "ta", "uk", "de", "it", "tr", "ja", "ko", "vi",
+];
+
+// Codes that are also English path words (`/user/id/123`, `/my/account`,
+// `/news/uk/`); they only count with a region (`/id-id/`, `/my-mm/`).
+static AMBIGUOUS_CODES: &[&str] = &["id", "my", "so", "am", "it", "hi", "ha", "ne", "uk"];
+
+// "/2025/03/14/"
+static DATE_SLASHED: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"/((?:19|20)\d{2})/(\d{1,2})/(\d{1,2})(?:/|$)").unwrap());
+// "/2025-03-14-slug" or "_2025-03-14."
+static DATE_DASHED: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:^|[/_-])((?:19|20)\d{2})-(\d{2})-(\d{2})(?:[/_.-]|$)").unwrap()
+});
+// "/news/20250314-slug"
+static DATE_COMPACT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:^|[/_-])((?:19|20)\d{2})(\d{2})(\d{2})(?:[/_.-]|$)").unwrap()
+});
+// "/2025/03/" (month precision)
+static DATE_MONTH: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"/((?:19|20)\d{2})/(\d{1,2})(?:/|$)").unwrap());
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct UrlHints {
+    /// ISO 8601 date (`YYYY-MM-DD` or `YYYY-MM`).
+    pub date: Option<String>,
+    /// `"day"` or `"month"`.
+    pub date_precision: Option<&'static str>,
+    pub language: Option<String>,
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn path_date(path: &str) -> Option<(String, &'static str)> {
+    for re in [&*DATE_SLASHED, &*DATE_DASHED, &*DATE_COMPACT] {
+        for cap in re.captures_iter(path) {
+            let year: u32 = cap[1].parse().ok()?;
+            let month: u32 = cap[2].parse().ok()?;
+            let day: u32 = cap[3].parse().ok()?;
+            if (1..=12).contains(&month) && day >= 1 && day <= days_in_month(year, month) {
+                return Some((format!("{year:04}-{month:02}-{day:02}"), "day"));
+            }
+        }
+    }
+    DATE_MONTH.captures_iter(path).find_map(|cap| {
+        let year: u32 = cap[1].parse().ok()?;
+        let month: u32 = cap[2].parse().ok()?;
+        (1..=12)
+            .contains(&month)
+            .then(|| (format!("{year:04}-{month:02}"), "month"))
+    })
+}
+
+fn path_language(path: &str) -> Option<String> {
+    path.split('/').find_map(|segment| {
+        let segment = segment.to_lowercase();
+        let (lang, region) = match segment.split_once(['-', '_']) {
+            Some((l, r)) if r.len() == 2 && r.chars().all(|c| c.is_ascii_alphabetic()) => {
+                (l.to_string(), Some(r))
+            }
+            Some(_) => return None,
+            None => (segment.clone(), None),
+        };
+        if !LANGUAGE_CODES.contains(&lang.as_str()) || region.is_none() && AMBIGUOUS_CODES.contains(&lang.as_str()) {
+            return None;
+        }
+        Some(match region {
+            Some(r) => format!("{lang}-{}", r.to_uppercase()),
+            None => lang,
+        })
+    })
+}
+
+pub(crate) fn hints_for(url_str: &str) -> UrlHints {
+    let path = match Url::parse(url_str.trim()) {
+        Ok(u) => u.path().to_string(),
+        Err(_) => return UrlHints::default(),
+    };
+    let (date, date_precision) = match path_date(&path) {
+        Some((d, p)) => (Some(d), Some(p)),
+        None => (None, None),
+    };
+    UrlHints {
+        date,
+        date_precision,
+        language: path_language(&path),
+    }
+}
+
+/// Extract publication-date and language hints from a URL path.
+///
+/// Parameters
+/// ----------
+/// url_str : str
+///     The URL to inspect.
+///
+/// Returns
+/// -------
+/// dict
+///     ``{"date": "2025-03-14" | "2025-03" | None,
+///     "date_precision": "day" | "month" | None,
+///     "language": "fr" | "pt-BR" | None}``.
+#[pyfunction]
+pub fn url_hints(py: Python<'_>, url_str: &str) -> PyResult<Py<PyDict>> {
+    let hints = unlocked(py, "url_hints", || hints_for(url_str))?;
+    let dict = PyDict::new_bound(py);
+    dict.set_item("date", hints.date)?;
+    dict.set_item("date_precision", hints.date_precision)?;
+    dict.set_item("language", hints.language)?;
+    Ok(dict.unbind())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slashed_date() {
+        let h = hints_for("https://example.org/2025/03/14/cyclone-update");
+        assert_eq!(h.date.as_deref(), Some("2025-03-14"));
+        assert_eq!(h.date_precision, Some("day"));
+    }
+
+    #[test]
+    fn test_compact_and_dashed_date() {
+        assert_eq!(
+            hints_for("https://example.org/news/20250314-floods").date.as_deref(),
+            Some("2025-03-14")
+        );
+        assert_eq!(
+            hints_for("https://example.org/story/2024-02-29-quake.html").date.as_deref(),
+            Some("2024-02-29")
+        );
+    }
+
+    #[test]
+    fn test_month_only_and_invalid_dates() {
+        let h = hints_for("https://example.org/archive/2025/03/");
+        assert_eq!(h.date.as_deref(), Some("2025-03"));
+        assert_eq!(h.date_precision, Some("month"));
+        // Feb 30 and article IDs are not dates
+        assert_eq!(hints_for("https://example.org/2025/02/30/x").date.as_deref(), Some("2025-02"));
+        assert_eq!(hints_for("https://example.org/article/12345678").date, None);
+    }
+
+    #[test]
+    fn test_language_segment() {
+        assert_eq!(
+            hints_for("https://www.unicef.org/mozambique/fr/recits").language.as_deref(),
+            Some("fr")
+        );
+        assert_eq!(
+            hints_for("https://example.org/pt-br/noticias").language.as_deref(),
+            Some("pt-BR")
+        );
+        assert_eq!(hints_for("https://example.org/reports/flood").language, None);
+    }
+
+    #[test]
+    fn test_ambiguous_language_words() {
+        for url in [
+            "https://example.org/user/id/123",
+            "https://example.org/my/account",
+            "https://example.org/so/what",
+            "https://example.org/news/uk/floods",
+            "https://example.org/it/helpdesk",
+        ] {
+            assert_eq!(hints_for(url).language, None, "{url}");
+        }
+        assert_eq!(hints_for("https://example.org/id-id/berita").language.as_deref(), Some("id-ID"));
+        assert_eq!(hints_for("https://example.org/so-so/war").language.as_deref(), Some("so-SO"));
+    }
+}
@@ -0,0 +1,306 @@
+//! URL keys — SURT-ordered canonical form and stable hash.
+//!
+//! Produces a compact dedup key for the frontier database: the canonical
+//! URL rewritten in SURT order (`org,reliefweb)/report/x?id=1`) plus an
+//! xxh3 hash of that form, which is stable across runs and platforms.
+//! `CanonicalUrlSet` keeps only those hashes for long-crawl dedup.
+
+use pyo3::exceptions::{PyOSError, PyValueError};
+use pyo3::prelude::*;
+use std::collections::HashSet;
+use url::{Host, Url};
+use xxhash_rust::xxh3::{xxh3_128, xxh3_64};
+
+use crate::columns::{bool_column, Texts};
+use crate::errors::{guarded, url_parse_error};
+use crate::storage::{self, Storage};
+use crate::url_canonical::{canonicalize_url, check_url, UrlErrorKind};
+
+/// Build the SURT form of a URL after canonicalization.
+///
+/// The scheme and a leading `www.` are dropped so http/https and www/bare
+/// variants share a key; query pairs are sorted so parameter order doesn't
+/// matter. Returns `None` for URLs without a host.
+pub(crate) fn surt(url_str: &str) -> Option<String> {
+    let canonical = canonicalize_url(url_str);
+    let parsed = Url::parse(&canonical).ok()?;
+
+    let mut key = match parsed.host()? {
+        Host::Domain(domain) => {
+            let domain = domain.to_lowercase();
+            let domain = domain.strip_prefix("www.").unwrap_or(&domain);
+            domain.split('.').rev().collect::<Vec<_>>().join(",")
+        }
+        ip => ip.to_string(),
+    };
+    if let Some(port) = parsed.port() {
+        key.push_str(&format!(":{port}"));
+    }
+    key.push(')');
+    key.push_str(parsed.path());
+
+    if let Some(query) = parsed.query() {
+        let mut pairs: Vec<&str> = query.split('&').filter(|p| !p.is_empty()).collect();
+        if !pairs.is_empty() {
+            pairs.sort_unstable();
+            key.push('?');
+            key.push_str(&pairs.join("&"));
+        }
+    }
+    Some(key)
+}
+
+/// 64-bit xxh3 hash of a SURT key.
+pub(crate) fn surt_hash64(surt_key: &str) -> u64 {
+    xxh3_64(surt_key.as_bytes())
+}
+
+/// Compute the SURT-ordered canonical key and its stable hash.
+///
+/// Parameters
+/// ----------
+/// url_str : str
+///     The URL to key.
+/// bits : int
+///     Hash width, 64 or 128. Default 64.
+///
+/// Returns
+/// -------
+/// tuple[str, int]
+///     ``(surt_key, hash)``, e.g. ``("int,reliefweb)/report/x", 1234...)``.
+///
+/// Raises
+/// ------
+/// ValueError
+///     If the URL cannot be parsed or ``bits`` is not 64 or 128.
+#[pyfunction]
+#[pyo3(signature = (url_str, bits=64))]
+pub fn url_key(url_str: &str, bits: u32) -> PyResult<(String, u128)> {
+    guarded("url_key", || {
+        let key = surt(url_str)
+            .ok_or_else(|| PyValueError::new_err(format!("cannot build URL key for {url_str:?}")))?;
+        let hash = match bits {
+            64 => surt_hash64(&key) as u128,
+            128 => xxh3_128(key.as_bytes()),
+            _ => return Err(PyValueError::new_err("bits must be 64 or 128")),
+        };
+        Ok((key, hash))
+    })
+}
+
+/// 128-bit hash of a URL's SURT key, or why the URL has none.
+pub(crate) fn url_hash128(url_str: &str) -> Result<u128, UrlErrorKind> {
+    match surt(url_str) {
+        Some(key) => Ok(xxh3_128(key.as_bytes())),
+        None => Err(check_url(url_str).err().unwrap_or(UrlErrorKind::Malformed)),
+    }
+}
+
+enum Hashes {
+    Memory(HashSet<u128>),
+    Stored(Box<dyn Storage>),
+}
+
+/// Set of canonical URLs, stored as 128-bit hashes of their SURT keys.
+///
+/// URLs are canonicalized on the way in, so tracking-param, scheme and
+/// ``www.`` variants of a URL count as one member. Roughly 16 bytes per
+/// URL instead of a full Python string.
+///
+/// With persistent ``storage`` the hashes live on disk instead of in
+/// memory, so the set survives restarts and, with SQLite, can outgrow
+/// RAM (see ``configure_storage``).
+///
+/// Parameters
+/// ----------
+/// capacity : int
+///     Number of URLs to pre-allocate for (in-memory sets). Default 0.
+/// storage : str | None
+///     ``"memory"``, ``"sqlite:PATH"`` or ``"mmap:PATH"``. Default: the
+///     process default, ``url_set.db`` / ``url_set.map`` in its directory.
+/// batch_size : int
+///     Additions per committed SQLite transaction. Default 1000.
+///
+/// Raises
+/// ------
+/// ValueError
+///     If the storage spec is invalid.
+/// OSError
+///     If the storage cannot be opened.
+#[pyclass(module = "moltis_rust_core")]
+pub struct CanonicalUrlSet {
+    hashes: Hashes,
+}
+
+impl CanonicalUrlSet {
+    fn open(capacity: usize, storage: Option<&str>, batch_size: usize) -> PyResult<Self> {
+        let hashes = match storage::open_for(storage, "url_set", batch_size)? {
+            Some(store) => Hashes::Stored(store),
+            None => Hashes::Memory(HashSet::with_capacity(capacity)),
+        };
+        Ok(Self { hashes })
+    }
+
+    fn insert_hash(&mut self, hash: u128) -> Result<bool, String> {
+        match &mut self.hashes {
+            Hashes::Memory(set) => Ok(set.insert(hash)),
+            Hashes::Stored(store) => store.put(&hash.to_be_bytes(), b""),
+        }
+    }
+
+    fn contains_hash(&self, hash: u128) -> Result<bool, String> {
+        match &self.hashes {
+            Hashes::Memory(set) => Ok(set.contains(&hash)),
+            Hashes::Stored(store) => store.contains(&hash.to_be_bytes()),
+        }
+    }
+
+    fn len(&self) -> Result<usize, String> {
+        match &self.hashes {
+            Hashes::Memory(set) => Ok(set.len()),
+            Hashes::Stored(store) => store.count(b""),
+        }
+    }
+}
+
+#[pymethods]
+impl CanonicalUrlSet {
+    #[new]
+    #[pyo3(signature = (capacity=0, storage=None, batch_size=1000))]
+    fn py_new(capacity: usize, storage: Option<&str>, batch_size: usize) -> PyResult<Self> {
+        guarded("CanonicalUrlSet.__new__", || Self::open(capacity, storage, batch_size))
+    }
+
+    /// Add a URL. Returns True if its canonical form was not yet present.
+    ///
+    /// Raises ``UrlParseError`` if the URL has no host.
+    fn add(&mut self, url: &str) -> PyResult<bool> {
+        guarded("CanonicalUrlSet.add", || {
+            let hash = url_hash128(url).map_err(|kind| url_parse_error(url, kind))?;
+            self.insert_hash(hash).map_err(PyOSError::new_err)
+        })
+    }
+
+    /// Add several URLs; returns one "was new" flag per input.
+    ///
+    /// ``urls`` may also be a numpy array or a pandas / Polars Series,
+    /// in which case the flags come back as a ``bool`` numpy array.
+    fn add_many(&mut self, py: Python<'_>, urls: Texts) -> PyResult<PyObject> {
+        guarded("CanonicalUrlSet.add_many", || {
+            let added = urls.values.iter().map(|u| self.add(u)).collect::<PyResult<Vec<bool>>>()?;
+            bool_column(py, &added, urls.array)
+        })
+    }
+
+    fn __contains__(&self, url: &str) -> PyResult<bool> {
+        guarded("CanonicalUrlSet.__contains__", || {
+            match url_hash128(url) {
+                Ok(hash) => self.contains_hash(hash).map_err(PyOSError::new_err),
+                Err(_) => Ok(false),
+            }
+        })
+    }
+
+    fn __len__(&self) -> PyResult<usize> {
+        guarded("CanonicalUrlSet.__len__", || self.len().map_err(PyOSError::new_err))
+    }
+
+    fn clear(&mut self) -> PyResult<()> {
+        guarded("CanonicalUrlSet.clear", || {
+            match &mut self.hashes {
+                Hashes::Memory(set) => set.clear(),
+                Hashes::Stored(store) => store.clear().map_err(PyOSError::new_err)?,
+            }
+            Ok(())
+        })
+    }
+
+    /// Write pending additions to storage (no-op in memory).
+    fn flush(&mut self) -> PyResult<()> {
+        guarded("CanonicalUrlSet.flush", || {
+            match &mut self.hashes {
+                Hashes::Memory(_) => Ok(()),
+                Hashes::Stored(store) => store.flush().map_err(PyOSError::new_err),
+            }
+        })
+    }
+
+    /// Storage spec, e.g. ``"sqlite:state/url_set.db"``, or ``"memory"``.
+    #[getter]
+    fn storage(&self) -> PyResult<String> {
+        guarded("CanonicalUrlSet.storage", || {
+            Ok(match &self.hashes {
+                Hashes::Memory(_) => "memory".into(),
+                Hashes::Stored(store) => store.spec(),
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_surt_basic() {
+        assert_eq!(
+            surt("https://www.ReliefWeb.int/report/mozambique?id=7&utm_source=x").as_deref(),
+            Some("int,reliefweb)/report/mozambique?id=7")
+        );
+    }
+
+    #[test]
+    fn test_scheme_and_param_order_collapse() {
+        let a = url_key("http://example.com/a?b=2&a=1", 64).unwrap();
+        let b = url_key("https://www.example.com/a?a=1&b=2#top", 64).unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_port_and_ip() {
+        assert_eq!(surt("http://example.com:8080/x").as_deref(), Some("com,example:8080)/x"));
+        assert_eq!(surt("http://10.0.0.1/feed").as_deref(), Some("10.0.0.1)/feed"));
+    }
+
+    #[test]
+    fn test_stable_hash() {
+        // Pinned values: hashes are persisted, so they must never change.
+        let (_, h64) = url_key("https://example.com/", 64).unwrap();
+        let (_, h128) = url_key("https://example.com/", 128).unwrap();
+        assert_eq!(h64, 0x4565591ca8058664);
+        assert_eq!(h128, 0x51e0a850552edd8cb863ab685fbb0e82);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(surt("not a url").is_none());
+    }
+
+    #[test]
+    fn test_canonical_url_set() {
+        let mut set = CanonicalUrlSet::open(0, None, 1000).unwrap();
+        let mut add = |url: &str| set.insert_hash(url_hash128(url).unwrap()).unwrap();
+        assert!(add("https://example.org/a?id=1"));
+        assert!(!add("http://www.example.org/a?id=1&utm_source=x"));
+        assert!(add("https://example.org/b"));
+        assert!(set.__contains__("https://example.org/b#section").unwrap());
+        assert_eq!(set.len(), Ok(2));
+        assert_eq!(url_hash128("/relative"), Err(UrlErrorKind::RelativeUrl));
+    }
+
+    #[test]
+    fn test_persistent_url_set() {
+        let path = std::env::temp_dir().join(format!("moltis_url_set_{}.map", std::process::id()));
+        let spec = format!("mmap:{}", path.display());
+        let _ = std::fs::remove_file(&path);
+        let mut set = CanonicalUrlSet::open(0, Some(&spec), 2).unwrap();
+        for url in ["https://example.org/a", "https://www.example.org/a", "https://example.org/b"] {
+            set.insert_hash(url_hash128(url).unwrap()).unwrap();
+        }
+        drop(set);
+        let set = CanonicalUrlSet::open(0, Some(&spec), 2).unwrap();
+        assert_eq!(set.len(), Ok(2));
+        assert!(set.__contains__("http://example.org/b?utm_source=x").unwrap());
+        assert!(!set.__contains__("https://example.org/c").unwrap());
+    }
+}
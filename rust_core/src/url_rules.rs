@@ -0,0 +1,370 @@
+//! Per-domain canonicalization rules.
+//!
+//! Site-specific quirks (print views, `?output=json`, pagination markers)
+//! are described in a TOML file — see `config/url_rules.example.toml` —
+//! and applied by `canonicalize_url` after the generic cleanup, so new
+//! quirks don't need a rebuild of the crate.
+//!
+//! ```toml
+//! [[rule]]
+//! host = "*.example.org"
+//! fragment = "hashbang_to_path"   # or "strip" (default) / "keep"
+//! index_documents = ["index.cfm"] # replaces the default list; [] disables
+//! actions = [
+//!   { action = "rewrite", pattern = "/print/?$", replace = "" },
+//!   { action = "strip_params", pattern = "^(output|page)$" },
+//! ]
+//! ```
+
+use once_cell::sync::Lazy;
+#[cfg(feature = "python")]
+use pyo3::exceptions::PyOSError;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use regex::Regex;
+use serde::Deserialize;
+use std::sync::RwLock;
+use url::Url;
+
+#[cfg(feature = "python")]
+use crate::errors::{config_error, guarded, Failure};
+
+#[derive(Deserialize)]
+struct RulesFile {
+    #[serde(default, rename = "rule")]
+    rules: Vec<RuleSpec>,
+}
+
+#[derive(Deserialize)]
+struct RuleSpec {
+    host: String,
+    #[serde(default)]
+    fragment: Option<FragmentPolicy>,
+    #[serde(default)]
+    index_documents: Option<Vec<String>>,
+    #[serde(default)]
+    actions: Vec<ActionSpec>,
+}
+
+/// What `canonicalize_url` does with a URL fragment.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum FragmentPolicy {
+    /// Drop the fragment (the default for every host).
+    #[default]
+    Strip,
+    /// Preserve in-page anchors the source relies on.
+    Keep,
+    /// Rewrite hashbang routes (`#!/article/123`) into the path.
+    HashbangToPath,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum ActionSpec {
+    /// Regex replace on the URL path.
+    Rewrite { pattern: String, replace: String },
+    /// Drop query parameters whose key matches the regex.
+    StripParams { pattern: String },
+}
+
+enum Action {
+    Rewrite(Regex, String),
+    StripParams(Regex),
+}
+
+/// `example.org` matches that host only; `*.example.org` matches the
+/// apex and every subdomain.
+enum HostPattern {
+    Exact(String),
+    Suffix(String),
+}
+
+impl HostPattern {
+    fn parse(spec: &str) -> Self {
+        let spec = spec.trim().to_lowercase();
+        match spec.strip_prefix("*.") {
+            Some(apex) => HostPattern::Suffix(apex.to_string()),
+            None => HostPattern::Exact(spec),
+        }
+    }
+
+    fn matches(&self, host: &str) -> bool {
+        match self {
+            HostPattern::Exact(h) => host == h,
+            HostPattern::Suffix(apex) => {
+                host == apex
+                    || (host.ends_with(apex.as_str())
+                        && host[..host.len() - apex.len()].ends_with('.'))
+            }
+        }
+    }
+}
+
+struct Rule {
+    host: HostPattern,
+    fragment: Option<FragmentPolicy>,
+    /// Lowercased directory-index file names, overriding the default list.
+    index_documents: Option<Vec<String>>,
+    actions: Vec<Action>,
+}
+
+static RULES: Lazy<RwLock<Vec<Rule>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+fn compile(pattern: &str) -> Result<Regex, String> {
+    Regex::new(pattern).map_err(|e| format!("invalid rule pattern {pattern:?}: {e}"))
+}
+
+/// Parse and compile a rules document.
+fn parse_rules(text: &str) -> Result<Vec<Rule>, String> {
+    let file: RulesFile = toml::from_str(text).map_err(|e| format!("invalid rules file: {e}"))?;
+    file.rules
+        .into_iter()
+        .map(|spec| {
+            let actions = spec
+                .actions
+                .into_iter()
+                .map(|action| match action {
+                    ActionSpec::Rewrite { pattern, replace } => {
+                        Ok(Action::Rewrite(compile(&pattern)?, replace))
+                    }
+                    ActionSpec::StripParams { pattern } => Ok(Action::StripParams(compile(&pattern)?)),
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            Ok(Rule {
+                host: HostPattern::parse(&spec.host),
+                fragment: spec.fragment,
+                index_documents: spec
+                    .index_documents
+                    .map(|names| names.iter().map(|n| n.trim().to_lowercase()).collect()),
+                actions,
+            })
+        })
+        .collect()
+}
+
+fn apply_action(url: &mut Url, action: &Action) {
+    match action {
+        Action::Rewrite(re, replace) => {
+            let path = re.replace_all(url.path(), replace.as_str()).into_owned();
+            url.set_path(if path.is_empty() { "/" } else { &path });
+        }
+        Action::StripParams(re) => {
+            let kept: Vec<String> = match url.query() {
+                Some(q) => q
+                    .split('&')
+                    .filter(|pair| {
+                        let key = pair.split('=').next().unwrap_or("");
+                        !pair.is_empty() && !re.is_match(key)
+                    })
+                    .map(str::to_string)
+                    .collect(),
+                None => return,
+            };
+            if kept.is_empty() {
+                url.set_query(None);
+            } else {
+                url.set_query(Some(&kept.join("&")));
+            }
+        }
+    }
+}
+
+fn apply_rule_list(rules: &[Rule], url_str: String) -> String {
+    let mut parsed = match Url::parse(&url_str) {
+        Ok(u) => u,
+        Err(_) => return url_str,
+    };
+    let host = match parsed.host_str() {
+        Some(h) => h.to_string(),
+        None => return url_str,
+    };
+    let mut changed = false;
+    for rule in rules.iter().filter(|r| r.host.matches(&host)) {
+        for action in &rule.actions {
+            apply_action(&mut parsed, action);
+        }
+        changed = true;
+    }
+    if changed {
+        parsed.to_string()
+    } else {
+        url_str
+    }
+}
+
+/// Fragment policy from the first matching rule that sets one.
+fn fragment_policy_in(rules: &[Rule], host: &str) -> FragmentPolicy {
+    rules
+        .iter()
+        .filter(|r| r.host.matches(host))
+        .find_map(|r| r.fragment)
+        .unwrap_or_default()
+}
+
+/// Number of configured rules, for `runtime_info`.
+pub(crate) fn inventory() -> Vec<(&'static str, usize)> {
+    vec![("url_rules", RULES.read().unwrap_or_else(|e| e.into_inner()).len())]
+}
+
+/// Fragment policy for a host under the loaded rules.
+pub(crate) fn fragment_policy(host: &str) -> FragmentPolicy {
+    let rules = RULES.read().unwrap_or_else(|e| e.into_inner());
+    fragment_policy_in(&rules, host)
+}
+
+/// Index-document override from the first matching rule that sets one:
+/// `Some(true)` if `file_name` is listed there, `None` without an override.
+fn index_document_in(rules: &[Rule], host: &str, file_name: &str) -> Option<bool> {
+    let names = rules
+        .iter()
+        .filter(|r| r.host.matches(host))
+        .find_map(|r| r.index_documents.as_ref())?;
+    let file_name = file_name.to_lowercase();
+    Some(names.contains(&file_name))
+}
+
+/// Whether the loaded rules say `file_name` is a directory index on `host`.
+pub(crate) fn index_document_override(host: &str, file_name: &str) -> Option<bool> {
+    let rules = RULES.read().unwrap_or_else(|e| e.into_inner());
+    index_document_in(&rules, host, file_name)
+}
+
+/// Apply the loaded per-domain rules to a canonicalized URL.
+pub(crate) fn apply_rules(url_str: String) -> String {
+    let rules = RULES.read().unwrap_or_else(|e| e.into_inner());
+    if rules.is_empty() {
+        return url_str;
+    }
+    apply_rule_list(&rules, url_str)
+}
+
+/// Load per-domain canonicalization rules from a TOML file.
+///
+/// Replaces any previously loaded rules. Rules apply in file order; every
+/// rule whose host pattern matches runs its actions in order.
+///
+/// Parameters
+/// ----------
+/// path : str
+///     Path to the rules file (see ``config/url_rules.example.toml``).
+///
+/// Returns
+/// -------
+/// int
+///     Number of rules loaded.
+///
+/// Raises
+/// ------
+/// OSError
+///     If the file cannot be read.
+/// ConfigError
+///     If the TOML or a regex pattern is invalid (``reason``
+///     ``"malformed"``, ``path`` the file); the loaded rules are kept.
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn load_url_rules(path: &str) -> PyResult<usize> {
+    guarded("load_url_rules", || {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| PyOSError::new_err(format!("cannot read {path}: {e}")))?;
+        let rules = parse_rules(&text).map_err(|e| {
+            tracing::warn!(path, error = %e, "URL rules not loaded");
+            config_error(Some(path), Failure::from(e))
+        })?;
+        let count = rules.len();
+        *RULES.write().unwrap_or_else(|e| e.into_inner()) = rules;
+        Ok(count)
+    })
+}
+
+/// Remove all loaded per-domain canonicalization rules.
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn clear_url_rules() -> PyResult<()> {
+    guarded("clear_url_rules", || {
+        RULES.write().unwrap_or_else(|e| e.into_inner()).clear();
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES_TOML: &str = r#"
+        [[rule]]
+        host = "*.example.org"
+        actions = [
+          { action = "rewrite", pattern = "/print/?$", replace = "" },
+          { action = "strip_params", pattern = "^(output|page)$" },
+        ]
+
+        [[rule]]
+        host = "news.example.net"
+        actions = [{ action = "rewrite", pattern = "/page/\\d+/?$", replace = "/" }]
+    "#;
+
+    fn apply(url: &str) -> String {
+        let rules = parse_rules(RULES_TOML).unwrap();
+        apply_rule_list(&rules, url.to_string())
+    }
+
+    #[test]
+    fn test_print_view_and_params() {
+        assert_eq!(
+            apply("https://www.example.org/story/42/print?output=json&id=1"),
+            "https://www.example.org/story/42?id=1"
+        );
+    }
+
+    #[test]
+    fn test_exact_host_only() {
+        assert_eq!(
+            apply("https://news.example.net/floods/page/3"),
+            "https://news.example.net/floods/"
+        );
+        assert_eq!(
+            apply("https://other.example.net/floods/page/3"),
+            "https://other.example.net/floods/page/3"
+        );
+    }
+
+    #[test]
+    fn test_suffix_pattern_boundary() {
+        assert!(HostPattern::parse("*.example.org").matches("example.org"));
+        assert!(!HostPattern::parse("*.example.org").matches("badexample.org"));
+    }
+
+    #[test]
+    fn test_fragment_policy() {
+        let rules = parse_rules(
+            "[[rule]]\nhost = \"*.spa.example\"\nfragment = \"hashbang_to_path\"\n\n\
+             [[rule]]\nhost = \"docs.example\"\nfragment = \"keep\"",
+        )
+        .unwrap();
+        assert_eq!(fragment_policy_in(&rules, "www.spa.example"), FragmentPolicy::HashbangToPath);
+        assert_eq!(fragment_policy_in(&rules, "docs.example"), FragmentPolicy::Keep);
+        assert_eq!(fragment_policy_in(&rules, "other.example"), FragmentPolicy::Strip);
+    }
+
+    #[test]
+    fn test_index_document_override() {
+        let rules = parse_rules(
+            "[[rule]]\nhost = \"*.gov.example\"\nindex_documents = [\"Index.cfm\"]\n\n\
+             [[rule]]\nhost = \"legacy.example\"\nindex_documents = []",
+        )
+        .unwrap();
+        assert_eq!(index_document_in(&rules, "www.gov.example", "index.CFM"), Some(true));
+        assert_eq!(index_document_in(&rules, "www.gov.example", "index.html"), Some(false));
+        assert_eq!(index_document_in(&rules, "legacy.example", "index.html"), Some(false));
+        assert_eq!(index_document_in(&rules, "other.example", "index.html"), None);
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        let err = parse_rules(
+            "[[rule]]\nhost = \"a.org\"\nactions = [{ action = \"rewrite\", pattern = \"(\", replace = \"\" }]",
+        );
+        assert!(err.is_err());
+    }
+}
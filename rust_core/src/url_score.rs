@@ -0,0 +1,241 @@
+//! Crawl priority scoring for URLs.
+//!
+//! Combines disaster keywords in the path, the kind of page the URL
+//! points at, path depth and the publication date hint into a single
+//! priority for the frontier, so every caller ranks URLs the same way.
+
+use pyo3::prelude::*;
+use rayon::prelude::*;
+use url::Url;
+
+use crate::columns::{f64_column, Texts};
+use crate::errors::{guarded, unlocked};
+use crate::frontier::now_secs;
+use crate::url_hints::hints_for;
+
+/// Score for a URL with no signal either way.
+const BASE_SCORE: f64 = 0.5;
+const KEYWORD_WEIGHT: f64 = 0.15;
+const MAX_KEYWORD_HITS: usize = 3;
+/// Segments beyond this depth cost `DEPTH_PENALTY` each.
+const FREE_DEPTH: usize = 4;
+const DEPTH_PENALTY: f64 = 0.05;
+/// Recency bonus halves every `RECENCY_HALF_LIFE_DAYS`.
+const RECENCY_WEIGHT: f64 = 0.3;
+const RECENCY_HALF_LIFE_DAYS: f64 = 30.0;
+
+// Token prefixes (English, French, Spanish, Portuguese) matched against
+// path tokens, so "flood" also covers "floods" and "flooding".
+static DISASTER_STEMS: &[&str] = &[
+    "disaster", "flood", "cyclone", "hurricane", "typhoon", "storm", "earthquake", "quake",
+    "tsunami", "drought", "famine", "landslide", "wildfire", "volcan", "cholera", "outbreak",
+    "epidemic", "displace", "refugee", "evacuat", "emergenc", "emergên", "humanitar", "sitrep",
+    "crisis", "crise", "appeal", "inondation", "inundac", "inundaç", "séisme", "seisme", "sismo",
+    "terremoto", "sécheresse", "secheresse", "sequía", "sequia", "seca", "ciclón", "ciclon",
+    "desastre", "catastrophe", "catástrofe",
+];
+
+// Multi-token phrases ("situation-report", "flash-update").
+static DISASTER_PHRASES: &[&str] = &["situation-report", "flash-update", "flash-appeal"];
+
+// Index, navigation and account pages rarely hold reports.
+static LISTING_SEGMENTS: &[&str] = &["tag", "tags", "category", "categories", "page", "search", "archive", "author"];
+static JUNK_SEGMENTS: &[&str] = &[
+    "login", "logout", "signin", "signup", "register", "account", "cart", "wp-admin", "wp-login.php",
+    "privacy", "privacy-policy", "terms", "cookies", "contact", "about", "about-us", "careers", "jobs",
+];
+static DOCUMENT_EXTENSIONS: &[&str] = &["pdf", "doc", "docx", "xls", "xlsx"];
+static ASSET_EXTENSIONS: &[&str] = &[
+    "jpg", "jpeg", "png", "gif", "svg", "webp", "ico", "css", "js", "woff", "woff2", "mp4", "mp3", "zip",
+];
+
+/// Kind of page a URL points at, judged from its path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum UrlType {
+    Document,
+    Article,
+    Listing,
+    Home,
+    Junk,
+    Other,
+}
+
+impl UrlType {
+    fn weight(self) -> f64 {
+        match self {
+            UrlType::Document => 0.2,
+            UrlType::Article => 0.1,
+            UrlType::Home | UrlType::Other => 0.0,
+            UrlType::Listing => -0.2,
+            UrlType::Junk => -1.0,
+        }
+    }
+}
+
+fn classify(segments: &[String]) -> UrlType {
+    let last = match segments.last() {
+        Some(s) => s,
+        None => return UrlType::Home,
+    };
+    if let Some((_, ext)) = last.rsplit_once('.') {
+        if DOCUMENT_EXTENSIONS.contains(&ext) {
+            return UrlType::Document;
+        }
+        if ASSET_EXTENSIONS.contains(&ext) {
+            return UrlType::Junk;
+        }
+    }
+    if segments.iter().any(|s| JUNK_SEGMENTS.contains(&s.as_str())) {
+        return UrlType::Junk;
+    }
+    if segments.iter().any(|s| LISTING_SEGMENTS.contains(&s.as_str())) {
+        return UrlType::Listing;
+    }
+    // Slugs ("cyclone-freddy-flash-update-3") or numeric IDs
+    let stem = last.rsplit_once('.').map_or(last.as_str(), |(s, _)| s);
+    if stem.matches('-').count() >= 2 || (stem.len() >= 4 && stem.chars().all(|c| c.is_ascii_digit())) {
+        return UrlType::Article;
+    }
+    UrlType::Other
+}
+
+fn keyword_hits(path: &str) -> usize {
+    let phrases = DISASTER_PHRASES.iter().filter(|p| path.contains(*p)).count();
+    let tokens = path
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| DISASTER_STEMS.iter().any(|stem| t.starts_with(stem)))
+        .count();
+    phrases + tokens
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Bonus in `[-RECENCY_WEIGHT / 2, RECENCY_WEIGHT / 2]` for a `YYYY-MM-DD`
+/// or `YYYY-MM` date hint; month-precision dates count from mid-month.
+fn recency(date: &str, now: f64) -> f64 {
+    let mut parts = date.split('-').map(|p| p.parse::<i64>().unwrap_or(1));
+    let (year, month) = (parts.next().unwrap_or(1970), parts.next().unwrap_or(1));
+    let day = parts.next().unwrap_or(15);
+    let age_days = (now / 86_400.0 - days_from_civil(year, month, day) as f64).max(0.0);
+    RECENCY_WEIGHT * (0.5f64.powf(age_days / RECENCY_HALF_LIFE_DAYS) - 0.5)
+}
+
+pub(crate) fn score_at(url_str: &str, now: f64) -> f64 {
+    let parsed = match Url::parse(url_str.trim()) {
+        Ok(u) => u,
+        Err(_) => return 0.0,
+    };
+    let path = percent_encoding::percent_decode_str(parsed.path())
+        .decode_utf8_lossy()
+        .to_lowercase();
+    let segments: Vec<String> = path
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    let mut score = BASE_SCORE;
+    score += KEYWORD_WEIGHT * keyword_hits(&path).min(MAX_KEYWORD_HITS) as f64;
+    score += classify(&segments).weight();
+    score -= DEPTH_PENALTY * segments.len().saturating_sub(FREE_DEPTH) as f64;
+    if let Some(date) = hints_for(url_str).date {
+        score += recency(&date, now);
+    }
+    score.max(0.0)
+}
+
+/// Crawl priority for a URL; higher is fetched first.
+///
+/// Parameters
+/// ----------
+/// url : str
+///     The URL to score.
+/// now : float | None
+///     Unix timestamp that date hints are measured against. Defaults to
+///     the current time.
+///
+/// Returns
+/// -------
+/// float
+///     Non-negative score; 0.5 for a URL with no signal. Disaster keywords,
+///     documents, article slugs and recent path dates raise it; listing
+///     pages, deep paths, old dates and login/asset URLs lower it.
+#[pyfunction]
+#[pyo3(signature = (url, now=None))]
+pub fn score_url(url: &str, now: Option<f64>) -> PyResult<f64> {
+    guarded("score_url", || Ok(score_at(url, now.unwrap_or_else(now_secs))))
+}
+
+/// Crawl priority for many URLs, in parallel with the GIL released.
+///
+/// Parameters
+/// ----------
+/// urls : list[str] | numpy.ndarray | pandas.Series | polars.Series
+///     URLs to score; a missing value scores like an unparseable URL.
+/// now : float | None
+///     As for ``score_url``.
+///
+/// Returns
+/// -------
+/// list[float] | numpy.ndarray
+///     One score per URL; a ``float64`` array when ``urls`` is
+///     array-like.
+#[pyfunction]
+#[pyo3(signature = (urls, now=None))]
+pub fn score_url_batch(py: Python<'_>, urls: Texts, now: Option<f64>) -> PyResult<PyObject> {
+    let now = now.unwrap_or_else(now_secs);
+    let scores: Vec<f64> = unlocked(py, "score_url_batch", || urls.values.par_iter().map(|u| score_at(u, now)).collect())?;
+    f64_column(py, &scores, urls.array)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2025-03-20T00:00:00Z
+    const NOW: f64 = 1_742_428_800.0;
+
+    #[test]
+    fn test_days_from_civil() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2025, 3, 20) as f64 * 86_400.0, NOW);
+    }
+
+    #[test]
+    fn test_keywords_raise_score() {
+        let flood = score_at("https://example.org/news/cyclone-freddy-floods-malawi", NOW);
+        let sport = score_at("https://example.org/news/football-cup-final-report", NOW);
+        assert!(flood > sport);
+        assert!(score_at("https://example.org/es/emergencia-inundaciones-2", NOW) > sport);
+    }
+
+    #[test]
+    fn test_url_types() {
+        assert!(score_at("https://example.org/reports/sitrep.pdf", NOW) > score_at("https://example.org/reports/sitrep", NOW));
+        assert!(score_at("https://example.org/tag/floods", NOW) < score_at("https://example.org/floods", NOW));
+        assert_eq!(score_at("https://example.org/wp-login.php", NOW), 0.0);
+        assert_eq!(score_at("https://example.org/static/logo.png", NOW), 0.0);
+    }
+
+    #[test]
+    fn test_recency_and_depth() {
+        let recent = score_at("https://example.org/2025/03/18/update", NOW);
+        let old = score_at("https://example.org/2019/03/18/update", NOW);
+        assert!(recent > BASE_SCORE && old < BASE_SCORE);
+        assert!(score_at("https://example.org/a/b/c/d/e/f/g", NOW) < score_at("https://example.org/a/b", NOW));
+    }
+
+    #[test]
+    fn test_unparseable() {
+        assert_eq!(score_at("not a url", NOW), 0.0);
+    }
+}
@@ -0,0 +1,124 @@
+//! Slug-based URL similarity.
+//!
+//! Sites republish the same article under a new section, date folder or
+//! numeric ID (`/news/2025/03/cyclone-freddy-hits-malawi` vs
+//! `/africa/cyclone-freddy-hits-malawi-48213`). Comparing the slug words
+//! catches these before both copies are fetched.
+
+use pyo3::prelude::*;
+use std::collections::HashSet;
+use url::Url;
+
+use crate::stopwords::is_stopword;
+use crate::errors::guarded;
+
+// Page extensions left in slugs; function words come from `stopwords`.
+static SLUG_NOISE: &[&str] = &["html", "htm", "php", "aspx"];
+
+fn is_noise(token: &str) -> bool {
+    if token.len() < 2 || SLUG_NOISE.contains(&token) || is_stopword(token) {
+        return true;
+    }
+    // Numbers, dates and IDs
+    if token.chars().all(|c| c.is_ascii_digit()) {
+        return true;
+    }
+    let hex_digits = token.chars().filter(|c| c.is_ascii_digit()).count();
+    token.len() >= 8 && hex_digits > 0 && token.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn segment_tokens(segment: &str) -> Vec<String> {
+    let decoded = percent_encoding::percent_decode_str(segment).decode_utf8_lossy();
+    let stem = match decoded.rsplit_once('.') {
+        Some((stem, ext)) if ext.len() <= 5 && ext.chars().all(|c| c.is_ascii_alphanumeric()) => stem,
+        _ => &decoded,
+    };
+    stem.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !is_noise(t))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Words of a URL's article slug: the path segment with the most
+/// meaningful words, ignoring dates, numeric IDs and stopwords.
+pub(crate) fn slug_tokens(url_str: &str) -> HashSet<String> {
+    let parsed = match Url::parse(url_str.trim()) {
+        Ok(u) => u,
+        Err(_) => return HashSet::new(),
+    };
+    parsed
+        .path_segments()
+        .into_iter()
+        .flatten()
+        .map(segment_tokens)
+        .filter(|tokens| tokens.len() >= 2)
+        // Later segments win ties: the slug is usually last
+        .fold(Vec::new(), |best, tokens| if tokens.len() >= best.len() { tokens } else { best })
+        .into_iter()
+        .collect()
+}
+
+/// Similarity of two URLs' article slugs, from 0.0 to 1.0.
+///
+/// Slug words are compared as sets (Jaccard overlap) after dropping
+/// dates, numeric IDs, file extensions and stopwords, so the same article
+/// under different sections or IDs scores close to 1.0.
+///
+/// Parameters
+/// ----------
+/// url_a, url_b : str
+///     URLs to compare. Hosts are not compared.
+///
+/// Returns
+/// -------
+/// float
+///     0.0 if either URL has no slug of at least two words.
+#[pyfunction]
+pub fn slug_similarity(url_a: &str, url_b: &str) -> PyResult<f64> {
+    guarded("slug_similarity", || {
+        let a = slug_tokens(url_a);
+        let b = slug_tokens(url_b);
+        if a.is_empty() || b.is_empty() {
+            return Ok(0.0);
+        }
+        let shared = a.intersection(&b).count();
+        Ok(shared as f64 / (a.len() + b.len() - shared) as f64)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_republished_slug() {
+        let score = slug_similarity(
+            "https://example.org/news/2025/03/14/cyclone-freddy-hits-malawi",
+            "https://example.org/africa/cyclone-freddy-hits-malawi-48213.html",
+        )
+        .unwrap();
+        assert_eq!(score, 1.0);
+    }
+
+    #[test]
+    fn test_partial_overlap() {
+        let score = slug_similarity(
+            "https://example.org/floods-displace-thousands-in-beira",
+            "https://example.org/floods-displace-hundreds-in-tete",
+        )
+        .unwrap();
+        assert!((score - 2.0 / 6.0).abs() < 1e-9);
+        assert_eq!(
+            slug_similarity("https://example.org/cholera-outbreak", "https://example.org/drought-response").unwrap(),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_no_slug() {
+        assert_eq!(slug_similarity("https://example.org/node/12345", "https://example.org/node/12345").unwrap(), 0.0);
+        assert_eq!(slug_similarity("not a url", "https://example.org/a-b").unwrap(), 0.0);
+        assert!(slug_tokens("https://example.org/story/5f3a9c2e81b4/quake-kills-dozens").contains("quake"));
+    }
+}
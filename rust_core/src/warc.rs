@@ -0,0 +1,644 @@
+//! WARC archives — reading crawls in, writing crawls out.
+//!
+//! Historical pulls (Common Crawl, Internet Archive exports) arrive as
+//! WARC files. `WarcReader` walks their records, splits HTTP responses
+//! into status, headers and payload (undoing chunked transfer and gzip /
+//! deflate content encoding) and can yield `(title, text, url,
+//! published)` tuples that `iter_process` and `Pipeline.iter_run` take
+//! directly. `WarcWriter` archives our own fetches in the same format.
+//! Both sides can deduplicate by payload digest: the reader skips repeat
+//! payloads, the writer stores them as `revisit` records.
+//!
+//! `.gz` paths are read and written gzip-compressed, one member per
+//! record as the WARC spec recommends.
+
+use flate2::read::{DeflateDecoder, GzDecoder, ZlibDecoder};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use pyo3::exceptions::{PyOSError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufWriter, Read, Write};
+use xxhash_rust::xxh3::xxh3_128;
+
+use crate::charset;
+use crate::errors::{guarded, unlocked};
+use crate::html_meta;
+use crate::html_text;
+use crate::jsonl_batch::open_input;
+
+const WARC_VERSION: &str = "WARC/1.1";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct WarcRecord {
+    pub headers: Vec<(String, String)>,
+    pub block: Vec<u8>,
+}
+
+impl WarcRecord {
+    /// First header called `name`, case-insensitively.
+    pub(crate) fn header(&self, name: &str) -> Option<&str> {
+        header(&self.headers, name)
+    }
+
+    fn record_type(&self) -> &str {
+        self.header("WARC-Type").unwrap_or("")
+    }
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+}
+
+/// An HTTP message from a `response` record's block.
+#[derive(Debug, PartialEq)]
+pub(crate) struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    /// Entity body with any transfer encoding removed.
+    pub raw_body: Vec<u8>,
+    /// `raw_body` with any content encoding removed.
+    pub body: Vec<u8>,
+}
+
+fn trim_line(line: &[u8]) -> &[u8] {
+    line.trim_ascii_end()
+}
+
+/// Next record, or None at the end of the input.
+pub(crate) fn read_record(reader: &mut dyn BufRead) -> Result<Option<WarcRecord>, String> {
+    let io = |e: std::io::Error| format!("cannot read WARC: {e}");
+    let mut line = Vec::new();
+    // Records end with two CRLFs; tolerate more or fewer.
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line).map_err(io)? == 0 {
+            return Ok(None);
+        }
+        if !trim_line(&line).is_empty() {
+            break;
+        }
+    }
+    if !trim_line(&line).starts_with(b"WARC/") {
+        return Err(format!("not a WARC record: {:?}", String::from_utf8_lossy(trim_line(&line))));
+    }
+    let mut headers: Vec<(String, String)> = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line).map_err(io)? == 0 {
+            return Err("truncated WARC record headers".into());
+        }
+        let text = String::from_utf8_lossy(trim_line(&line)).into_owned();
+        if text.is_empty() {
+            break;
+        }
+        if text.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(text.trim());
+            }
+        } else if let Some((name, value)) = text.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    let length: u64 = header(&headers, "Content-Length")
+        .and_then(|v| v.parse().ok())
+        .ok_or("WARC record without a valid Content-Length")?;
+    let mut block = Vec::new();
+    reader.take(length).read_to_end(&mut block).map_err(io)?;
+    if (block.len() as u64) < length {
+        return Err("truncated WARC record block".into());
+    }
+    Ok(Some(WarcRecord { headers, block }))
+}
+
+fn dechunk(mut data: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    loop {
+        let end = data.windows(2).position(|w| w == b"\r\n")?;
+        let size = std::str::from_utf8(&data[..end]).ok()?;
+        let size = usize::from_str_radix(size.split(';').next()?.trim(), 16).ok()?;
+        data = &data[end + 2..];
+        if size == 0 {
+            return Some(out);
+        }
+        out.extend_from_slice(data.get(..size)?);
+        data = data.get(size + 2..)?;
+    }
+}
+
+fn decode_content(body: &[u8], encoding: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    match encoding.trim().to_ascii_lowercase().as_str() {
+        "gzip" | "x-gzip" => GzDecoder::new(body).read_to_end(&mut out).ok()?,
+        // "deflate" is zlib-wrapped by the spec but often raw in practice.
+        "deflate" => match ZlibDecoder::new(body).read_to_end(&mut out) {
+            Ok(n) => n,
+            Err(_) => {
+                out.clear();
+                DeflateDecoder::new(body).read_to_end(&mut out).ok()?
+            }
+        },
+        _ => return None,
+    };
+    Some(out)
+}
+
+/// Split an HTTP response block; None if it isn't one.
+pub(crate) fn parse_http(block: &[u8]) -> Option<HttpResponse> {
+    let (head_len, sep_len) = match block.windows(4).position(|w| w == b"\r\n\r\n") {
+        Some(i) => (i, 4),
+        None => (block.windows(2).position(|w| w == b"\n\n")?, 2),
+    };
+    let head = String::from_utf8_lossy(&block[..head_len]);
+    let mut lines = head.lines();
+    let status_line = lines.next()?;
+    if !status_line.starts_with("HTTP/") {
+        return None;
+    }
+    let status = status_line.split_whitespace().nth(1)?.parse().ok()?;
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect();
+    let mut raw_body = block[head_len + sep_len..].to_vec();
+    if header(&headers, "Transfer-Encoding").is_some_and(|te| te.to_ascii_lowercase().contains("chunked")) {
+        if let Some(body) = dechunk(&raw_body) {
+            raw_body = body;
+        }
+    }
+    let body = header(&headers, "Content-Encoding")
+        .and_then(|enc| decode_content(&raw_body, enc))
+        .unwrap_or_else(|| raw_body.clone());
+    Some(HttpResponse { status, headers, raw_body, body })
+}
+
+/// RFC 4648 base32, as WARC digests use.
+fn base32(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+    let mut out = String::with_capacity(data.len().div_ceil(5) * 8);
+    for chunk in data.chunks(5) {
+        let mut buf = [0u8; 5];
+        buf[..chunk.len()].copy_from_slice(chunk);
+        let bits = buf.iter().fold(0u64, |acc, b| acc << 8 | u64::from(*b));
+        let chars = (chunk.len() * 8).div_ceil(5);
+        for i in 0..8 {
+            if i < chars {
+                out.push(ALPHABET[(bits >> (35 - i * 5) & 31) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+pub(crate) fn sha1_digest(data: &[u8]) -> String {
+    format!("sha1:{}", base32(&sha1_smol::Sha1::from(data).digest().bytes()))
+}
+
+/// Payload digest of a record: its header, else computed.
+fn payload_digest(record: &WarcRecord, http: Option<&HttpResponse>) -> String {
+    match record.header("WARC-Payload-Digest") {
+        Some(digest) => digest.to_string(),
+        None => sha1_digest(http.map_or(&record.block, |h| &h.raw_body)),
+    }
+}
+
+fn charset_of(content_type: &str) -> Option<&str> {
+    content_type.split(';').find_map(|part| {
+        let (key, value) = part.split_once('=')?;
+        key.trim().eq_ignore_ascii_case("charset").then(|| value.trim().trim_matches('"'))
+    })
+}
+
+/// `(title, text, url, published)` for an HTML response record.
+pub(crate) fn to_article(record: &WarcRecord, http: &HttpResponse) -> Option<(String, String, String, Option<String>)> {
+    if !(200..300).contains(&http.status) {
+        return None;
+    }
+    let content_type = header(&http.headers, "Content-Type").unwrap_or("");
+    let decoded = charset::decode(&http.body, charset_of(content_type));
+    let sniffed = decoded.text.get(..1024).unwrap_or(&decoded.text).to_ascii_lowercase();
+    if !content_type.to_ascii_lowercase().contains("html") && !sniffed.contains("<html") {
+        return None;
+    }
+    let url = record.header("WARC-Target-URI")?.trim_matches(['<', '>']).to_string();
+    let meta = html_meta::extract(&decoded.text, Some(&url));
+    let text = html_text::convert(&decoded.text);
+    if text.is_empty() {
+        return None;
+    }
+    let published = meta.published_time.or_else(|| record.header("WARC-Date").map(String::from));
+    Some((meta.title.unwrap_or_default(), text, url, published))
+}
+
+enum Item {
+    Record(WarcRecord, Option<HttpResponse>, String),
+    Article((String, String, String, Option<String>)),
+}
+
+/// Iterator over the records of a WARC file.
+///
+/// Parameters
+/// ----------
+/// path : str
+///     ``.warc`` or ``.warc.gz`` file, or ``-`` for stdin.
+/// record_types : list[str] | None
+///     Only yield these ``WARC-Type`` values, e.g. ``["response"]``.
+///     Default: all.
+/// dedupe : bool
+///     Skip ``revisit`` records and records whose payload digest was
+///     already seen. Default False.
+/// articles : bool
+///     Yield ``(title, text, url, published)`` tuples for HTML responses
+///     with a 2xx status instead of record dicts, ready for
+///     ``iter_process`` or ``Pipeline.iter_run``. ``published`` is the
+///     page's own date, else the capture date. Default False.
+///
+/// Yields
+/// ------
+/// dict | tuple
+///     Records: ``type``, ``record_id``, ``target_uri``, ``date``,
+///     ``content_type``, ``headers`` (all WARC headers), ``http_status``
+///     and ``http_headers`` (None unless the block is an HTTP response),
+///     ``payload`` (bytes: the decoded HTTP body, else the whole block)
+///     and ``payload_digest`` (``"sha1:..."``).
+///
+/// Raises
+/// ------
+/// OSError
+///     If the file cannot be opened.
+/// ValueError
+///     On a malformed or truncated record.
+#[pyclass(module = "moltis_rust_core")]
+pub struct WarcReader {
+    input: Box<dyn BufRead + Send>,
+    record_types: Option<HashSet<String>>,
+    dedupe: bool,
+    articles: bool,
+    seen: HashSet<String>,
+    duplicates: usize,
+}
+
+impl WarcReader {
+    fn next_item(&mut self) -> Result<Option<Item>, String> {
+        while let Some(record) = read_record(&mut self.input)? {
+            let kind = record.record_type().to_ascii_lowercase();
+            if self.record_types.as_ref().is_some_and(|types| !types.contains(&kind)) {
+                continue;
+            }
+            if self.dedupe && kind == "revisit" {
+                self.duplicates += 1;
+                continue;
+            }
+            let http = if matches!(kind.as_str(), "response" | "revisit") { parse_http(&record.block) } else { None };
+            let digest = payload_digest(&record, http.as_ref());
+            if self.dedupe && matches!(kind.as_str(), "response" | "resource") && !self.seen.insert(digest.clone()) {
+                self.duplicates += 1;
+                continue;
+            }
+            if self.articles {
+                if let Some(article) = http.as_ref().and_then(|h| to_article(&record, h)) {
+                    return Ok(Some(Item::Article(article)));
+                }
+                continue;
+            }
+            return Ok(Some(Item::Record(record, http, digest)));
+        }
+        Ok(None)
+    }
+}
+
+fn pairs_dict<'py>(py: Python<'py>, pairs: &[(String, String)]) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new_bound(py);
+    for (k, v) in pairs {
+        dict.set_item(k, v)?;
+    }
+    Ok(dict)
+}
+
+#[pymethods]
+impl WarcReader {
+    #[new]
+    #[pyo3(signature = (path, record_types=None, dedupe=false, articles=false))]
+    fn py_new(path: &str, record_types: Option<Vec<String>>, dedupe: bool, articles: bool) -> PyResult<Self> {
+        guarded("WarcReader.__new__", || {
+            Ok(Self {
+                input: open_input(path).map_err(PyOSError::new_err)?,
+                record_types: record_types.map(|types| types.iter().map(|t| t.to_ascii_lowercase()).collect()),
+                dedupe,
+                articles,
+                seen: HashSet::new(),
+                duplicates: 0,
+            })
+        })
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyResult<PyRef<'_, Self>> {
+        guarded("WarcReader.__iter__", || Ok(slf))
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        let item = unlocked(py, "WarcReader.__next__", || self.next_item())?.map_err(PyValueError::new_err)?;
+        let (record, http, digest) = match item {
+            None => return Ok(None),
+            Some(Item::Article(article)) => return Ok(Some(article.into_py(py))),
+            Some(Item::Record(record, http, digest)) => (record, http, digest),
+        };
+        let dict = PyDict::new_bound(py);
+        dict.set_item("type", record.record_type())?;
+        dict.set_item("record_id", record.header("WARC-Record-ID"))?;
+        dict.set_item("target_uri", record.header("WARC-Target-URI"))?;
+        dict.set_item("date", record.header("WARC-Date"))?;
+        dict.set_item("content_type", record.header("Content-Type"))?;
+        dict.set_item("headers", pairs_dict(py, &record.headers)?)?;
+        dict.set_item("http_status", http.as_ref().map(|h| h.status))?;
+        dict.set_item("http_headers", http.as_ref().map(|h| pairs_dict(py, &h.headers)).transpose()?)?;
+        let payload = http.as_ref().map_or(&record.block, |h| &h.body);
+        dict.set_item("payload", PyBytes::new_bound(py, payload))?;
+        dict.set_item("payload_digest", digest)?;
+        Ok(Some(dict.into_any().unbind()))
+    }
+
+    /// Records skipped as duplicates so far (with ``dedupe``).
+    #[getter]
+    fn duplicates(&self) -> PyResult<usize> {
+        guarded("WarcReader.duplicates", || Ok(self.duplicates))
+    }
+}
+
+enum Sink {
+    Plain(BufWriter<File>),
+    /// One gzip member per record.
+    Gzip(BufWriter<File>),
+}
+
+/// Writes WARC records; the file starts with a ``warcinfo`` record.
+///
+/// Usable as a context manager; call ``close()`` otherwise.
+///
+/// Parameters
+/// ----------
+/// path : str
+///     Output file, overwritten if it exists; ``.gz`` compresses each
+///     record as its own gzip member.
+/// dedupe : bool
+///     Write a response whose payload digest was already written as a
+///     ``revisit`` record pointing at the first copy. Default False.
+///
+/// Raises
+/// ------
+/// OSError
+///     If the file cannot be written.
+#[pyclass(module = "moltis_rust_core")]
+pub struct WarcWriter {
+    sink: Option<Sink>,
+    dedupe: bool,
+    /// Payload digest -> (record ID, target URI, date) of the first copy.
+    written: HashMap<String, (String, String, String)>,
+    count: u64,
+}
+
+fn warc_date() -> String {
+    chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+impl WarcWriter {
+    pub(crate) fn create(path: &str, dedupe: bool) -> Result<Self, String> {
+        let file = BufWriter::new(File::create(path).map_err(|e| format!("cannot create {path}: {e}"))?);
+        let sink = if path.ends_with(".gz") { Sink::Gzip(file) } else { Sink::Plain(file) };
+        let mut writer = Self { sink: Some(sink), dedupe, written: HashMap::new(), count: 0 };
+        let info = format!("software: moltis_rust_core/{}\r\nformat: WARC File Format 1.1\r\n", env!("CARGO_PKG_VERSION"));
+        writer.write("warcinfo", None, &warc_date(), "application/warc-fields", info.as_bytes(), &[])?;
+        Ok(writer)
+    }
+
+    /// A `urn:uuid:` ID unique to this record.
+    fn record_id(&mut self, uri: &str, date: &str) -> String {
+        self.count += 1;
+        let nanos = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |d| d.as_nanos());
+        let seed = format!("{uri}\n{date}\n{}\n{nanos}\n{}", self.count, std::process::id());
+        let mut bytes = xxh3_128(seed.as_bytes()).to_be_bytes();
+        // Version 4, RFC 4122 variant.
+        bytes[6] = bytes[6] & 0x0f | 0x40;
+        bytes[8] = bytes[8] & 0x3f | 0x80;
+        let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+        format!("<urn:uuid:{}-{}-{}-{}-{}>", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+    }
+
+    pub(crate) fn write(
+        &mut self,
+        kind: &str,
+        uri: Option<&str>,
+        date: &str,
+        content_type: &str,
+        block: &[u8],
+        extra: &[(&str, String)],
+    ) -> Result<String, String> {
+        let id = self.record_id(uri.unwrap_or(""), date);
+        let mut head = format!("{WARC_VERSION}\r\nWARC-Type: {kind}\r\nWARC-Record-ID: {id}\r\nWARC-Date: {date}\r\n");
+        if let Some(uri) = uri {
+            head.push_str(&format!("WARC-Target-URI: {uri}\r\n"));
+        }
+        for (name, value) in extra {
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        head.push_str(&format!(
+            "WARC-Block-Digest: {}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n\r\n",
+            sha1_digest(block),
+            block.len()
+        ));
+        let mut record = head.into_bytes();
+        record.extend_from_slice(block);
+        record.extend_from_slice(b"\r\n\r\n");
+        let io = |e: std::io::Error| format!("cannot write WARC: {e}");
+        match self.sink.as_mut().ok_or("WARC writer is closed")? {
+            Sink::Plain(w) => w.write_all(&record).map_err(io)?,
+            Sink::Gzip(w) => {
+                let mut member = GzEncoder::new(Vec::new(), Compression::default());
+                member.write_all(&record).map_err(io)?;
+                w.write_all(&member.finish().map_err(io)?).map_err(io)?;
+            }
+        }
+        Ok(id)
+    }
+
+    pub(crate) fn append_response(
+        &mut self,
+        url: &str,
+        body: &[u8],
+        status: u16,
+        headers: &[(String, String)],
+        date: &str,
+    ) -> Result<String, String> {
+        let digest = sha1_digest(body);
+        let mut http = format!("HTTP/1.1 {status} {}\r\n", if status == 200 { "OK" } else { "" }).into_bytes();
+        for (name, value) in headers {
+            http.extend_from_slice(format!("{name}: {value}\r\n").as_bytes());
+        }
+        http.extend_from_slice(b"\r\n");
+        let mut extra = vec![("WARC-Payload-Digest", digest.clone())];
+        if self.dedupe {
+            if let Some((id, uri, first_date)) = self.written.get(&digest).cloned() {
+                extra.extend([
+                    ("WARC-Profile", "http://netpreserve.org/warc/1.1/revisit/identical-payload-digest".to_string()),
+                    ("WARC-Refers-To", id),
+                    ("WARC-Refers-To-Target-URI", uri),
+                    ("WARC-Refers-To-Date", first_date),
+                ]);
+                return self.write("revisit", Some(url), date, "application/http; msgtype=response", &http, &extra);
+            }
+        }
+        http.extend_from_slice(body);
+        let id = self.write("response", Some(url), date, "application/http; msgtype=response", &http, &extra)?;
+        if self.dedupe {
+            self.written.insert(digest, (id.clone(), url.to_string(), date.to_string()));
+        }
+        Ok(id)
+    }
+
+    pub(crate) fn finish(&mut self) -> Result<(), String> {
+        let io = |e: std::io::Error| format!("cannot write WARC: {e}");
+        match self.sink.take() {
+            Some(Sink::Plain(mut w) | Sink::Gzip(mut w)) => w.flush().map_err(io),
+            None => Ok(()),
+        }
+    }
+}
+
+#[pymethods]
+impl WarcWriter {
+    #[new]
+    #[pyo3(signature = (path, dedupe=false))]
+    fn py_new(path: &str, dedupe: bool) -> PyResult<Self> {
+        guarded("WarcWriter.__new__", || Self::create(path, dedupe).map_err(PyOSError::new_err))
+    }
+
+    /// Archive an HTTP response.
+    ///
+    /// Parameters
+    /// ----------
+    /// url : str
+    ///     URL fetched.
+    /// body : bytes
+    ///     Response body as received.
+    /// status : int
+    ///     HTTP status. Default 200.
+    /// headers : dict[str, str] | None
+    ///     Response headers.
+    /// date : str | None
+    ///     Capture time, ISO 8601 UTC. Default: now.
+    ///
+    /// Returns
+    /// -------
+    /// str
+    ///     The record's ``WARC-Record-ID``.
+    #[pyo3(signature = (url, body, status=200, headers=None, date=None))]
+    fn write_response(
+        &mut self,
+        url: &str,
+        body: &[u8],
+        status: u16,
+        headers: Option<Vec<(String, String)>>,
+        date: Option<String>,
+    ) -> PyResult<String> {
+        guarded("WarcWriter.write_response", || {
+            let date = date.unwrap_or_else(warc_date);
+            self.append_response(url, body, status, &headers.unwrap_or_default(), &date).map_err(PyOSError::new_err)
+        })
+    }
+
+    /// Archive any other record, e.g. a ``resource`` or ``metadata``
+    /// record, and return its ``WARC-Record-ID``.
+    #[pyo3(signature = (record_type, url, block, content_type="application/octet-stream", date=None))]
+    fn write_record(
+        &mut self,
+        record_type: &str,
+        url: Option<&str>,
+        block: &[u8],
+        content_type: &str,
+        date: Option<String>,
+    ) -> PyResult<String> {
+        guarded("WarcWriter.write_record", || {
+            let date = date.unwrap_or_else(warc_date);
+            self.write(record_type, url, &date, content_type, block, &[]).map_err(PyOSError::new_err)
+        })
+    }
+
+    /// Flush and close the file. Further writes raise OSError.
+    fn close(&mut self) -> PyResult<()> {
+        guarded("WarcWriter.close", || self.finish().map_err(PyOSError::new_err))
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyResult<PyRef<'_, Self>> {
+        guarded("WarcWriter.__enter__", || Ok(slf))
+    }
+
+    #[pyo3(signature = (*_args))]
+    fn __exit__(&mut self, _args: &Bound<'_, pyo3::types::PyTuple>) -> PyResult<bool> {
+        guarded("WarcWriter.__exit__", || {
+            self.finish().map_err(PyOSError::new_err)?;
+            Ok(false)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &[u8] = b"<html><head><title>Floods in Beira</title></head><body><p>Floods killed 12 people.</p></body></html>";
+
+    fn read_all(path: &str, dedupe: bool) -> Vec<WarcRecord> {
+        let mut input = open_input(path).unwrap();
+        let mut records = Vec::new();
+        while let Some(record) = read_record(&mut input).unwrap() {
+            if !(dedupe && record.record_type() == "revisit") {
+                records.push(record);
+            }
+        }
+        records
+    }
+
+    #[test]
+    fn test_write_then_read() {
+        let path = std::env::temp_dir().join(format!("moltis_warc_{}.warc.gz", std::process::id()));
+        let path = path.to_str().unwrap();
+        let mut writer = WarcWriter::create(path, true).unwrap();
+        let headers = vec![("Content-Type".to_string(), "text/html; charset=utf-8".to_string())];
+        writer.append_response("https://example.org/a", PAGE, 200, &headers, "2024-03-01T10:00:00Z").unwrap();
+        writer.append_response("https://mirror.example.net/a", PAGE, 200, &headers, "2024-03-01T11:00:00Z").unwrap();
+        writer.finish().unwrap();
+
+        let records = read_all(path, false);
+        let kinds: Vec<&str> = records.iter().map(WarcRecord::record_type).collect();
+        assert_eq!(kinds, vec!["warcinfo", "response", "revisit"]);
+        assert_eq!(records[2].header("WARC-Refers-To"), records[1].header("WARC-Record-ID"));
+        let http = parse_http(&records[1].block).unwrap();
+        assert_eq!((http.status, http.body.as_slice()), (200, PAGE));
+        assert_eq!(payload_digest(&records[1], Some(&http)), sha1_digest(PAGE));
+        let (title, text, url, published) = to_article(&records[1], &http).unwrap();
+        assert_eq!((title.as_str(), text.as_str(), url.as_str()), ("Floods in Beira", "Floods killed 12 people.", "https://example.org/a"));
+        assert_eq!(published.as_deref(), Some("2024-03-01T10:00:00Z"));
+        assert_eq!(read_all(path, true).len(), 2);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_http_decoding_and_digest() {
+        let chunked = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n6\r\n world\r\n0\r\n\r\n";
+        assert_eq!(parse_http(chunked).unwrap().body, b"hello world");
+        let mut gz = GzEncoder::new(Vec::new(), Compression::default());
+        gz.write_all(b"zipped").unwrap();
+        let mut block = b"HTTP/1.1 404 Not Found\r\nContent-Encoding: gzip\r\n\r\n".to_vec();
+        block.extend(gz.finish().unwrap());
+        let http = parse_http(&block).unwrap();
+        assert_eq!((http.status, http.body.as_slice()), (404, b"zipped".as_slice()));
+        assert!(parse_http(b"GET / HTTP/1.1\r\n\r\n").is_none());
+        // SHA-1 of "abc", as WARC tools write it
+        assert_eq!(sha1_digest(b"abc"), "sha1:VGMT4NSHA2AWVOR6EVYXQUGCNSONBWE5");
+    }
+}
@@ -0,0 +1,115 @@
+//! WebAssembly exports of the text functions.
+//!
+//! The review UI previews figures, labels and duplicates client-side as
+//! an editor types. Building the same code for the browser keeps those
+//! previews identical to what the crawler stores. Only pure functions are
+//! exported, so the build needs no Python:
+//!
+//! ```sh
+//! wasm-pack build --target web --no-default-features --features wasm
+//! ```
+//!
+//! Configuration set from Python (`configure_stopwords`, custom tracking
+//! parameters, URL rules) does not exist here; the built-in defaults
+//! apply.
+
+use wasm_bindgen::prelude::*;
+
+use crate::figure_extraction::{figures_in, Language, NEGATED_ZERO};
+use crate::fuzzy_dedupe::{normalize_text, similarity};
+use crate::text_classify::{dominant_impact_type, impact_types, is_risk, need_types, severity};
+use crate::url_canonical::canonicalize_url;
+
+/// Figures found in `text` as a JSON object, e.g. `{"deaths": 59}`.
+///
+/// `lang` picks the pattern set ("en", "fr", "pt" or "es"; default "en")
+/// and `negated_zero` gives 0 for keys a statement negates (default true),
+/// as `extract_figures` does in Python. Throws for a language without
+/// patterns.
+#[wasm_bindgen(js_name = extractFigures)]
+pub fn extract_figures(text: &str, lang: Option<String>, negated_zero: Option<bool>) -> Result<String, JsError> {
+    figures_json(text, lang.as_deref(), negated_zero).map_err(|e| JsError::new(&e))
+}
+
+fn figures_json(text: &str, lang: Option<&str>, negated_zero: Option<bool>) -> Result<String, String> {
+    let code = lang.unwrap_or("en");
+    let lang = Language::from_code(code).ok_or_else(|| format!("no figure patterns for language {code:?}"))?;
+    let figures = figures_in(text, lang, negated_zero.unwrap_or(NEGATED_ZERO));
+    Ok(serde_json::to_string(&figures).unwrap_or_else(|_| "{}".into()))
+}
+
+/// Dominant impact type, e.g. `"people_impact"`.
+#[wasm_bindgen(js_name = classifyImpactType)]
+pub fn classify_impact_type(text: &str) -> String {
+    dominant_impact_type(&text.to_lowercase()).to_string()
+}
+
+/// All impact types with keyword matches, best first.
+#[wasm_bindgen(js_name = classifyAllImpactTypes)]
+pub fn classify_all_impact_types(text: &str) -> Vec<String> {
+    impact_types(&text.to_lowercase()).into_iter().map(String::from).collect()
+}
+
+/// Need types with keyword matches, best first.
+#[wasm_bindgen(js_name = classifyNeedTypes)]
+pub fn classify_need_types(text: &str) -> Vec<String> {
+    need_types(&text.to_lowercase()).into_iter().map(String::from).collect()
+}
+
+/// Severity score 1-5.
+#[wasm_bindgen(js_name = severityFromText)]
+pub fn severity_from_text(text: &str) -> i32 {
+    severity(&text.to_lowercase())
+}
+
+/// Whether `text` describes a forecast or warning rather than an event.
+#[wasm_bindgen(js_name = isRiskText)]
+pub fn is_risk_text(text: &str) -> bool {
+    is_risk(&text.to_lowercase())
+}
+
+/// Similarity ratio of two strings, 0-1, after normalization.
+#[wasm_bindgen(js_name = similarityRatio)]
+pub fn similarity_ratio(a: &str, b: &str) -> f64 {
+    similarity(a, b)
+}
+
+/// Casefold and collapse whitespace, optionally dropping stopwords.
+#[wasm_bindgen(js_name = normalizeText)]
+pub fn normalize(text: &str, drop_stopwords: bool) -> String {
+    normalize_text(text, drop_stopwords)
+}
+
+/// Canonical form of a URL (tracking parameters stripped), or the input
+/// unchanged when it cannot be parsed.
+#[wasm_bindgen(js_name = canonicalizeUrl)]
+pub fn canonicalize(url: &str) -> String {
+    canonicalize_url(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_exports_match_core() {
+        let text = "Floods killed 12 people and displaced 3,000 in Beira.";
+        let parsed: HashMap<String, i64> = serde_json::from_str(&extract_figures(text, None, None).unwrap()).unwrap();
+        assert_eq!(parsed, figures_in(text, Language::English, NEGATED_ZERO));
+        assert_eq!(classify_impact_type("FLOODS KILLED 12 PEOPLE"), dominant_impact_type("floods killed 12 people"));
+        assert_eq!(similarity_ratio("Floods in Beira", "floods in beira"), 1.0);
+        assert_eq!(canonicalize("https://example.org/a?utm_source=x"), canonicalize_url("https://example.org/a?utm_source=x"));
+    }
+
+    #[test]
+    fn test_figure_options() {
+        let figures = |text, lang, negated_zero| {
+            serde_json::from_str::<HashMap<String, i64>>(&figures_json(text, lang, negated_zero).unwrap()).unwrap()
+        };
+        assert_eq!(figures("au moins 45 morts", Some("fr"), None), figures_in("au moins 45 morts", Language::French, true));
+        assert_eq!(figures("No deaths were reported", None, None).get("deaths"), Some(&0));
+        assert!(figures("No deaths were reported", Some("en"), Some(false)).is_empty());
+        assert!(figures_json("45 morts", Some("xx"), None).is_err());
+    }
+}
@@ -0,0 +1,440 @@
+//! WHO Disease Outbreak News (DON) parsing.
+//!
+//! DONs are the authoritative early record of outbreaks: a titled
+//! "Disease – Country" report with fixed sections (situation at a glance,
+//! description, epidemiology, response, WHO risk assessment, advice).
+//! Reports are read from the web page, the WHO news API (JSON) or the DON
+//! RSS feed into records with the disease, countries, case and death
+//! counts, case fatality ratio and assessed risk levels, which feed the
+//! epidemic pathway alongside `extract_figures`.
+
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::date_parse::parse_at;
+use crate::errors::{extraction_error, unlocked, Failure};
+use crate::figure_extraction::figures;
+use crate::html_meta;
+use crate::html_text::convert;
+use crate::pipeline::current_time;
+use crate::xml_tree;
+
+// Numbers may group thousands with commas or (WHO style) spaces
+const NUMBER: &str = r"\b(\d{1,3}(?:[,\u{a0}\u{202f} ]\d{3})+|\d+)";
+
+static DON_ID: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\b(\d{4}-DON\d+)\b").unwrap());
+static CASES: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?i){NUMBER}\s+(?:(?:suspected|confirmed|probable|laboratory-confirmed|new|additional|cumulative|human|and|or)\s+)*cases\b"
+    ))
+    .unwrap()
+});
+static DEATHS: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?i){NUMBER}\s+(?:(?:suspected|confirmed|probable|associated|related|new|additional|and|or)\s+)*deaths\b"
+    ))
+    .unwrap()
+});
+static CFR: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?:case[- ]fatality (?:ratio|rate)|\bCFR\b)[^0-9%.]{0,25}(\d+(?:\.\d+)?)\s*%").unwrap()
+});
+static RISK_LEVEL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(very high|high|moderate|low)\s+at\s+the\s+(national|sub-regional|regional|global)\s+levels?|\b(national|sub-regional|regional|global)\s+levels?\s+(?:is\s+|as\s+)?(?:assessed|considered)\s+(?:as\s+|to\s+be\s+)?(very high|high|moderate|low)\b",
+    )
+    .unwrap()
+});
+static BLOCKS: Lazy<Selector> = Lazy::new(|| Selector::parse("h1, h2, h3, h4, p, li").unwrap());
+static DATE_ELEMENTS: Lazy<Selector> =
+    Lazy::new(|| Selector::parse(r#"[class*="date"], [class*="timestamp"], time"#).unwrap());
+static CONTENT: Lazy<Selector> = Lazy::new(|| Selector::parse("article, main, body").unwrap());
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct DonReport {
+    pub title: String,
+    pub disease: Option<String>,
+    pub countries: Vec<String>,
+    pub don_id: Option<String>,
+    pub published: Option<String>,
+    pub url: Option<String>,
+    pub cases: Option<i64>,
+    pub deaths: Option<i64>,
+    /// Case fatality ratio, percent.
+    pub cfr: Option<f64>,
+    /// `(level, risk)`, e.g. `("national", "high")`, in text order.
+    pub risk_levels: Vec<(String, String)>,
+    /// `(section, text)` in page order.
+    pub sections: Vec<(String, String)>,
+    pub figures: HashMap<String, i64>,
+}
+
+/// Standard DON headings to section keys; other headings are dropped.
+fn section_key(heading: &str) -> Option<&'static str> {
+    let h = heading.trim().to_lowercase();
+    if h.contains("at a glance") || h == "overview" || h == "summary" {
+        Some("overview")
+    } else if h.starts_with("description of") {
+        Some("description")
+    } else if h.contains("epidemiology") {
+        Some("epidemiology")
+    } else if h.contains("response") {
+        Some("response")
+    } else if h.contains("risk assessment") {
+        Some("risk_assessment")
+    } else if h.contains("advice") {
+        Some("advice")
+    } else {
+        None
+    }
+}
+
+/// "Cholera – Mozambique" or "Mpox - Multi-country" into disease and
+/// countries.
+fn split_title(title: &str) -> (Option<String>, Vec<String>) {
+    let Some((disease, place)) = [" – ", " — ", " - "].iter().find_map(|sep| title.split_once(sep)) else {
+        return (None, Vec::new());
+    };
+    let countries = place
+        .split([',', ';'])
+        .flat_map(|part| part.split(" and "))
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .map(str::to_string)
+        .collect();
+    (Some(disease.trim().to_string()), countries)
+}
+
+fn parse_count(raw: &str) -> Option<i64> {
+    raw.chars().filter(char::is_ascii_digit).collect::<String>().parse().ok()
+}
+
+fn max_count(pattern: &Regex, text: &str) -> Option<i64> {
+    pattern.captures_iter(text).filter_map(|c| parse_count(&c[1])).max()
+}
+
+fn timestamp(text: &str) -> Option<String> {
+    parse_at(text, None, current_time()).map(|p| p.iso())
+}
+
+/// Fill the fields read from the report text.
+fn build(title: String, sections: Vec<(String, String)>, full_text: &str) -> DonReport {
+    let (disease, countries) = split_title(&title);
+    let figure_text = sections
+        .iter()
+        .filter(|(key, _)| matches!(key.as_str(), "overview" | "description" | "epidemiology"))
+        .map(|(_, text)| text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let figure_text = if figure_text.is_empty() { full_text } else { figure_text.as_str() };
+    let risk_text = sections
+        .iter()
+        .find(|(key, _)| key == "risk_assessment")
+        .map_or(full_text, |(_, text)| text.as_str());
+    let risk_levels = RISK_LEVEL
+        .captures_iter(risk_text)
+        .filter_map(|c| {
+            let (risk, level) = match (c.get(1), c.get(2)) {
+                (Some(risk), Some(level)) => (risk, level),
+                _ => (c.get(4)?, c.get(3)?),
+            };
+            Some((level.as_str().to_lowercase(), risk.as_str().to_lowercase()))
+        })
+        .collect();
+    DonReport {
+        disease,
+        countries,
+        cases: max_count(&CASES, figure_text),
+        deaths: max_count(&DEATHS, figure_text),
+        cfr: CFR.captures(figure_text).and_then(|c| c[1].parse().ok()),
+        risk_levels,
+        figures: figures(full_text),
+        sections,
+        title,
+        ..Default::default()
+    }
+}
+
+fn element_text(el: &ElementRef<'_>) -> String {
+    el.text().collect::<Vec<_>>().join(" ").split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn parse_page(html: &str, url: Option<&str>) -> DonReport {
+    let doc = Html::parse_document(html);
+    let root = doc.select(&CONTENT).next().unwrap_or_else(|| doc.root_element());
+    let mut title = String::new();
+    let mut sections: Vec<(String, String)> = Vec::new();
+    let mut current: Option<&'static str> = None;
+    for el in root.select(&BLOCKS) {
+        // Paragraphs inside list items (and vice versa) are read once
+        if el.ancestors().filter_map(ElementRef::wrap).any(|a| matches!(a.value().name(), "p" | "li")) {
+            continue;
+        }
+        let text = element_text(&el);
+        if text.is_empty() {
+            continue;
+        }
+        match el.value().name() {
+            "h1" if title.is_empty() => title = text,
+            "h1" | "h2" | "h3" | "h4" => current = section_key(&text),
+            _ => {
+                let Some(key) = current else { continue };
+                match sections.iter_mut().find(|(k, _)| k == key) {
+                    Some((_, body)) => {
+                        body.push('\n');
+                        body.push_str(&text);
+                    }
+                    None => sections.push((key.to_string(), text)),
+                }
+            }
+        }
+    }
+    let meta = html_meta::extract(html, url);
+    if title.is_empty() {
+        title = meta.title.clone().unwrap_or_default();
+    }
+    let mut report = build(title, sections, &convert(html));
+    report.published = meta
+        .published_time
+        .as_deref()
+        .and_then(timestamp)
+        .or_else(|| doc.select(&DATE_ELEMENTS).find_map(|el| timestamp(&element_text(&el))));
+    report.url = url.map(str::to_string).or(meta.canonical_url);
+    report.don_id = report
+        .url
+        .as_deref()
+        .and_then(|u| DON_ID.captures(u))
+        .map(|c| c[1].to_uppercase());
+    report
+}
+
+fn json_item(item: &Value) -> Option<DonReport> {
+    let title = item.get("Title")?.as_str()?.trim().to_string();
+    let field = |key: &str| item.get(key).and_then(Value::as_str).map(convert).filter(|t| !t.is_empty());
+    let sections: Vec<(String, String)> = [
+        ("Overview", "overview"),
+        ("Summary", "overview"),
+        ("Epidemiology", "epidemiology"),
+        ("Response", "response"),
+        ("Assessment", "risk_assessment"),
+        ("Advice", "advice"),
+    ]
+    .iter()
+    .filter_map(|(field_name, key)| Some((key.to_string(), field(field_name)?)))
+    .fold(Vec::new(), |mut acc, (key, text)| {
+        // Summary only stands in when there is no Overview
+        if !acc.iter().any(|(k, _)| *k == key) {
+            acc.push((key, text));
+        }
+        acc
+    });
+    let full_text = sections.iter().map(|(_, t)| t.as_str()).collect::<Vec<_>>().join("\n");
+    let mut report = build(title, sections, &full_text);
+    report.published = item.get("PublicationDate").and_then(Value::as_str).and_then(timestamp);
+    report.url = item
+        .get("UrlName")
+        .and_then(Value::as_str)
+        .map(|slug| format!("https://www.who.int/emergencies/disease-outbreak-news/item/{slug}"));
+    report.don_id = item
+        .get("DonId")
+        .and_then(Value::as_str)
+        .map(str::to_uppercase)
+        .or_else(|| report.url.as_deref().and_then(|u| DON_ID.captures(u)).map(|c| c[1].to_uppercase()));
+    Some(report)
+}
+
+fn parse_json(text: &str) -> Result<Vec<DonReport>, Failure> {
+    let value: Value = serde_json::from_str(text).map_err(|e| format!("malformed DON JSON: {e}"))?;
+    match value.get("value").and_then(Value::as_array) {
+        Some(items) => Ok(items.iter().filter_map(json_item).collect()),
+        None => json_item(&value)
+            .map(|r| vec![r])
+            .ok_or_else(|| Failure::new("wrong_format", "not a DON payload: no value list or Title")),
+    }
+}
+
+fn parse_rss(text: &str) -> Result<Vec<DonReport>, Failure> {
+    let root = xml_tree::parse(text.as_bytes())?;
+    let channel =
+        root.child("channel").ok_or_else(|| Failure::new("wrong_format", "not a DON feed: no <channel>"))?;
+    Ok(channel
+        .children_named("item")
+        .filter_map(|item| {
+            let title = item.child_text("title")?.to_string();
+            let summary = item.child_text("description").map(convert).unwrap_or_default();
+            let sections = if summary.is_empty() { Vec::new() } else { vec![("overview".to_string(), summary.clone())] };
+            let mut report = build(title, sections, &summary);
+            report.published = item.child_text("pubDate").and_then(timestamp);
+            report.url = item.child_text("link").map(str::to_string);
+            report.don_id = report.url.as_deref().and_then(|u| DON_ID.captures(u)).map(|c| c[1].to_uppercase());
+            Some(report)
+        })
+        .collect())
+}
+
+/// Parse a DON page, API response or RSS feed.
+pub(crate) fn parse_don(text: &str, url: Option<&str>) -> Result<Vec<DonReport>, Failure> {
+    let head = text.trim_start();
+    if head.starts_with('{') {
+        parse_json(text)
+    } else if head.starts_with("<?xml") || head.starts_with("<rss") {
+        parse_rss(text)
+    } else {
+        Ok(vec![parse_page(text, url)])
+    }
+}
+
+fn report_dict<'py>(py: Python<'py>, report: &DonReport) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new_bound(py);
+    dict.set_item("title", &report.title)?;
+    dict.set_item("disease", &report.disease)?;
+    dict.set_item("countries", &report.countries)?;
+    dict.set_item("don_id", &report.don_id)?;
+    dict.set_item("published", &report.published)?;
+    dict.set_item("url", &report.url)?;
+    dict.set_item("cases", report.cases)?;
+    dict.set_item("deaths", report.deaths)?;
+    dict.set_item("cfr", report.cfr)?;
+    let risk = PyDict::new_bound(py);
+    for (level, value) in &report.risk_levels {
+        risk.set_item(level, value)?;
+    }
+    dict.set_item("risk_levels", risk)?;
+    let sections = PyDict::new_bound(py);
+    for (key, text) in &report.sections {
+        sections.set_item(key, text)?;
+    }
+    dict.set_item("sections", sections)?;
+    dict.set_item("figures", &report.figures)?;
+    Ok(dict)
+}
+
+/// Parse WHO Disease Outbreak News into structured records.
+///
+/// Accepts a DON web page, a WHO news API response (a single item or an
+/// OData ``{"value": [...]}`` list) or the DON RSS feed. The title gives
+/// the disease and countries ("Cholera – Mozambique"); case and death
+/// counts (the largest stated), the case fatality ratio and the risk
+/// levels are read from the report sections.
+///
+/// Parameters
+/// ----------
+/// text : str
+///     Page HTML, API JSON or feed XML.
+/// url : str | None
+///     Page URL, used for ``don_id`` when parsing a page.
+///
+/// Returns
+/// -------
+/// list[dict]
+///     One dict per report: ``title``, ``disease``, ``countries``,
+///     ``don_id`` (e.g. ``"2024-DON512"``), ``published`` (UTC ISO 8601),
+///     ``url``, ``cases``, ``deaths``, ``cfr`` (percent), ``risk_levels``
+///     (e.g. ``{"national": "high", "global": "low"}``), ``sections``
+///     (``overview``, ``description``, ``epidemiology``, ``response``,
+///     ``risk_assessment``, ``advice`` as found) and ``figures`` (as
+///     ``extract_figures`` on the whole report). Missing values are None.
+///
+/// Raises
+/// ------
+/// ExtractionError
+///     If JSON or XML input is malformed (``reason`` ``"malformed"``) or
+///     not a DON payload (``"wrong_format"``). A ``ValueError`` subclass.
+#[pyfunction]
+#[pyo3(signature = (text, url=None))]
+pub fn parse_who_don(py: Python<'_>, text: &str, url: Option<&str>) -> PyResult<Py<PyList>> {
+    let reports = unlocked(py, "parse_who_don", || parse_don(text, url))?.map_err(|e| extraction_error("who_don", e))?;
+    let list = PyList::empty_bound(py);
+    for report in &reports {
+        list.append(report_dict(py, report)?)?;
+    }
+    Ok(list.unbind())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<html><head><title>Cholera - Mozambique | WHO</title>
+<meta property="article:published_time" content="2023-02-24T00:00:00Z"></head>
+<body><header><nav><ul><li>Home</li></ul></nav></header>
+<article>
+  <h1>Cholera – Mozambique</h1>
+  <h3>Situation at a glance</h3>
+  <p>Between 14 September 2022 and 12 February 2023, a cumulative total of 5 237 suspected cases and 37 deaths (CFR 0.7%) were reported.</p>
+  <h3>Description of the situation</h3>
+  <p>Of these, 1 124 new cases and 12 associated deaths were notified in Niassa and Sofala.</p>
+  <ul><li>Sofala: 2 300 cases</li></ul>
+  <h3>WHO risk assessment</h3>
+  <p>WHO assesses the risk as very high at the national level, moderate at the regional level and low at the global level.</p>
+  <h3>WHO advice</h3>
+  <p>Improve access to safe water.</p>
+  <h3>Further information</h3>
+  <p>Ignored references.</p>
+</article></body></html>"#;
+
+    #[test]
+    fn test_page() {
+        let reports = parse_don(
+            PAGE,
+            Some("https://www.who.int/emergencies/disease-outbreak-news/item/2023-DON435"),
+        )
+        .unwrap();
+        let r = &reports[0];
+        assert_eq!(r.title, "Cholera – Mozambique");
+        assert_eq!((r.disease.as_deref(), r.countries.clone()), (Some("Cholera"), vec!["Mozambique".to_string()]));
+        assert_eq!(r.don_id.as_deref(), Some("2023-DON435"));
+        assert_eq!(r.published.as_deref(), Some("2023-02-24T00:00:00Z"));
+        assert_eq!((r.cases, r.deaths, r.cfr), (Some(5237), Some(37), Some(0.7)));
+        assert_eq!(
+            r.risk_levels,
+            vec![
+                ("national".into(), "very high".into()),
+                ("regional".into(), "moderate".into()),
+                ("global".into(), "low".into())
+            ]
+        );
+        let keys: Vec<&str> = r.sections.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(keys, vec!["overview", "description", "risk_assessment", "advice"]);
+        assert!(r.sections[1].1.ends_with("Sofala: 2 300 cases"));
+    }
+
+    #[test]
+    fn test_api_json() {
+        let json = r#"{"value": [{
+            "Title": "Marburg virus disease - Rwanda",
+            "DonId": "2024-DON537",
+            "PublicationDate": "2024-10-01T12:00:00Z",
+            "UrlName": "2024-DON537",
+            "Overview": "<p>As of 29 September, 26 confirmed cases including 8 deaths have been reported.</p>",
+            "Assessment": "<p>The risk at the national level is assessed as very high; the regional level is considered high.</p>"
+        }]}"#;
+        let r = &parse_don(json, None).unwrap()[0];
+        assert_eq!(r.disease.as_deref(), Some("Marburg virus disease"));
+        assert_eq!(r.don_id.as_deref(), Some("2024-DON537"));
+        assert_eq!((r.cases, r.deaths), (Some(26), Some(8)));
+        assert_eq!(r.risk_levels[0], ("national".into(), "very high".into()));
+        assert_eq!(r.risk_levels[1], ("regional".into(), "high".into()));
+        assert!(r.url.as_deref().unwrap().ends_with("/item/2024-DON537"));
+    }
+
+    #[test]
+    fn test_rss_and_errors() {
+        let rss = r#"<?xml version="1.0"?><rss><channel><item>
+            <title>Mpox - Multi-country</title>
+            <link>https://www.who.int/emergencies/disease-outbreak-news/item/2024-DON528</link>
+            <pubDate>Fri, 14 Jun 2024 00:00:00 GMT</pubDate>
+            <description>&lt;p&gt;1,200 cases reported.&lt;/p&gt;</description>
+        </item></channel></rss>"#;
+        let r = &parse_don(rss, None).unwrap()[0];
+        assert_eq!(r.countries, vec!["Multi-country"]);
+        assert_eq!((r.don_id.as_deref(), r.cases), (Some("2024-DON528"), Some(1200)));
+        assert_eq!(max_count(&CASES, "In 2023 512 cases"), Some(512));
+        assert!(parse_don("{\"foo\": 1}", None).is_err());
+        assert!(parse_don("<rss><item/></rss>", None).is_err());
+    }
+}
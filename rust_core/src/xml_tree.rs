@@ -0,0 +1,132 @@
+//! Minimal element tree over quick-xml for alert feed parsing.
+//!
+//! Alert documents (CAP, GDACS RSS) are small and deeply nested; walking a tree is
+//! simpler than tracking state through a streaming reader. Names are local
+//! (namespace prefixes dropped), since feeds disagree on prefixes.
+
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+#[derive(Debug, Default)]
+pub(crate) struct Element {
+    pub name: String,
+    pub attrs: Vec<(String, String)>,
+    pub children: Vec<Element>,
+    pub text: String,
+}
+
+impl Element {
+    fn open(start: &BytesStart<'_>) -> Result<Element, String> {
+        let mut attrs = Vec::new();
+        for attr in start.attributes().with_checks(false).flatten() {
+            let key = String::from_utf8_lossy(attr.key.local_name().as_ref()).into_owned();
+            let value = attr.unescape_value().map_err(|e| e.to_string())?.into_owned();
+            attrs.push((key, value));
+        }
+        Ok(Element {
+            name: String::from_utf8_lossy(start.local_name().as_ref()).into_owned(),
+            attrs,
+            ..Default::default()
+        })
+    }
+
+    pub(crate) fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+    }
+
+    pub(crate) fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    pub(crate) fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> + 'a {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    /// Trimmed text of the first `name` child; None if missing or blank.
+    pub(crate) fn child_text(&self, name: &str) -> Option<&str> {
+        self.child(name).map(|c| c.text.trim()).filter(|t| !t.is_empty())
+    }
+}
+
+/// Parse a document into its root element.
+pub(crate) fn parse(data: &[u8]) -> Result<Element, String> {
+    let mut reader = Reader::from_reader(data);
+    let mut buf = Vec::new();
+    let mut stack: Vec<Element> = Vec::new();
+    let mut root = None;
+
+    loop {
+        let event = reader.read_event_into(&mut buf).map_err(|e| format!("malformed XML: {e}"))?;
+        match event {
+            Event::Start(e) => stack.push(Element::open(&e)?),
+            Event::Empty(e) => {
+                let element = Element::open(&e)?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => root = Some(element),
+                }
+            }
+            Event::End(_) => {
+                let element = stack.pop().ok_or("malformed XML: unbalanced end tag")?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => root = Some(element),
+                }
+            }
+            Event::Text(t) => {
+                if let Some(current) = stack.last_mut() {
+                    current.text.push_str(&t.decode().map_err(|e| e.to_string())?);
+                }
+            }
+            Event::CData(t) => {
+                if let Some(current) = stack.last_mut() {
+                    current.text.push_str(&t.decode().map_err(|e| e.to_string())?);
+                }
+            }
+            Event::GeneralRef(r) => {
+                if let Some(current) = stack.last_mut() {
+                    if let Some(ch) = r.resolve_char_ref().map_err(|e| e.to_string())? {
+                        current.text.push(ch);
+                    } else if let Some(s) = resolve_predefined_entity(&r.decode().map_err(|e| e.to_string())?) {
+                        current.text.push_str(s);
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+        if root.is_some() {
+            break;
+        }
+    }
+
+    root.ok_or_else(|| "malformed XML: no root element".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tree() {
+        let root = parse(
+            br#"<?xml version="1.0"?><rss xmlns:g="urn:x"><item><g:level value="3"/>
+                <title>A &amp; B</title><title><![CDATA[second]]></title></item></rss>"#,
+        )
+        .unwrap();
+        assert_eq!(root.name, "rss");
+        let item = root.child("item").unwrap();
+        assert_eq!(item.child("level").and_then(|l| l.attr("value")), Some("3"));
+        assert_eq!(item.child_text("title"), Some("A & B"));
+        assert_eq!(item.children_named("title").count(), 2);
+        assert_eq!(item.child_text("missing"), None);
+    }
+
+    #[test]
+    fn test_malformed() {
+        assert!(parse(b"").is_err());
+        assert!(parse(b"<a><b></a>").is_err());
+    }
+}
@@ -1,5 +1,6 @@
 param(
     [switch]$SkipCompile,
+    [switch]$SkipRust,
     [switch]$SkipE2E
 )
 
@@ -15,6 +16,18 @@ if (-not $SkipCompile) {
     python -m compileall -q src tests
 }
 
+if (-not $SkipRust) {
+    Write-Host "==> Running Rust core clippy + tests"
+    cargo clippy --manifest-path rust_core/Cargo.toml --all-targets --features cli -- -D warnings
+    if ($LASTEXITCODE -ne 0) { exit $LASTEXITCODE }
+    cargo test --manifest-path rust_core/Cargo.toml
+    if ($LASTEXITCODE -ne 0) { exit $LASTEXITCODE }
+
+    Write-Host "==> Checking the Rust core WebAssembly build"
+    cargo check --manifest-path rust_core/Cargo.toml --target wasm32-unknown-unknown --no-default-features --features wasm
+    if ($LASTEXITCODE -ne 0) { exit $LASTEXITCODE }
+}
+
 if (-not $SkipE2E) {
     Write-Host "==> Running deterministic E2E gate with artifact capture"
     python .\scripts\e2e_gate.py
@@ -9,6 +9,15 @@ python -m pytest -q
 echo "==> Running compileall on src/tests"
 python -m compileall -q src tests
 
+if [[ "${SKIP_RUST:-0}" != "1" ]]; then
+  echo "==> Running Rust core clippy + tests"
+  cargo clippy --manifest-path rust_core/Cargo.toml --all-targets --features cli -- -D warnings
+  cargo test --manifest-path rust_core/Cargo.toml
+
+  echo "==> Checking the Rust core WebAssembly build"
+  cargo check --manifest-path rust_core/Cargo.toml --target wasm32-unknown-unknown --no-default-features --features wasm
+fi
+
 if [[ "${SKIP_E2E:-0}" != "1" ]]; then
   echo "==> Running deterministic E2E gate with artifact capture"
   python ./scripts/e2e_gate.py
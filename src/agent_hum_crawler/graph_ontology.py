@@ -993,13 +993,73 @@ def build_auto_admin_hierarchy(
 
 
 # ── Number extraction patterns ────────────────────────────────────────
+# A number with an optional magnitude word: "48,000", "1.2 million",
+# "3.5m", "20k", "half a million".  The magnitude must end at a word
+# boundary, so "5 missing" and "4 killed" aren't read as 5m and 4k.
+_NUM = (
+    r"(\d[\d,]*(?:\.\d+)?(?:\s*(?:thousand|million|billion|mil|bn|m|k)\b)?"
+    r"|(?:half\s+a|a)\s+(?:thousand|million|billion)\b)"
+)
+_MAGNITUDES = {
+    "": 1, "thousand": 1_000, "k": 1_000,
+    "million": 1_000_000, "mil": 1_000_000, "m": 1_000_000,
+    "billion": 1_000_000_000, "bn": 1_000_000_000,
+}
+
+# Agricultural labels: crop area (converted to hectares) and livestock
+_AGRICULTURE = (
+    r"hectares?|ha\b|acres?|head\s+of\s+(?:cattle|livestock)|"
+    r"cattle|livestock|goats|sheep|camels"
+)
+_HECTARES_PER_ACRE = 0.404686
+
+# Infrastructure labels: bridges, road length (converted to km) and power
+# assets
+_INFRASTRUCTURE = (
+    r"bridges|(?:kms?|kilomet(?:er|re)s?|miles?|mi)\s+of\s+(?:the\s+)?"
+    r"(?:roads?|highways?)|substations|transformers|pylons|"
+    r"power\s+(?:lines|stations|plants|poles)|electricity\s+poles"
+)
+_KM_PER_MILE = 1.609344
+
+# Percentages: "80% of households", "60 percent of the district"
+_PERCENT_PATTERN = re.compile(
+    r"\b(\d{1,3}(?:\.\d+)?)\s*(?:%|per\s*cent\b)\s+of\s+(?:[a-z-]+\s+){0,2}?"
+    r"(households|families|people|persons|population|residents|inhabitants|"
+    r"individuals|houses|homes|buildings|dwellings|children|schools|"
+    r"health\s*facilities|crops|cropland|farmland|harvest|fields|livestock|"
+    r"cattle|district|area|territory|province|region|land|villages?|towns?|"
+    r"city|municipality|county)\b",
+    re.IGNORECASE,
+)
+
+# Negated statements: "no deaths have been reported", "no one was
+# killed", "nobody is missing"
+_NEGATION_PATTERN = re.compile(
+    r"\b(?:no|zero|nobody|not\s+a\s+single)\b"
+    r"(?:\s+(?:one|new|further|additional|immediate|reported|confirmed|known|"
+    r"reports?\s+of|person|people|was|were|has|have|had|is|are|been|yet)){0,5}"
+    r"\s+(deaths?|fatalities|casualties|injuries|killed|died|dead|injured|hurt|"
+    r"displaced|missing)\b",
+    re.IGNORECASE,
+)
+
+# Livestock or infrastructure right after a number: "kills 300 cattle",
+# "3,500 goats dead" and "14 bridges washed away, 3 killed" aren't death
+# tolls
+_NOT_PEOPLE = re.compile(
+    r"\s*(?:(?:head\s+of\s+)?(?:cattle|livestock|goats|sheep|camels)|"
+    + _INFRASTRUCTURE + r")\b",
+    re.IGNORECASE,
+)
+
 # Pattern 1: NUM + keyword (e.g. "48,000 displaced")
 _NUMBER_PATTERN = re.compile(
-    r"(\d[\d,]*(?:\.\d+)?)\s*"
+    _NUM + r"\s*"
     r"(people|persons|individuals|deaths|dead|killed|"
     r"displaced|injured|missing|houses|homes|affected|"
     r"families|households|children|schools|"
-    r"health\s*facilit)",
+    r"health\s*facilit|" + _AGRICULTURE + r"|" + _INFRASTRUCTURE + r")",
     re.IGNORECASE,
 )
 
@@ -1009,9 +1069,9 @@ _NUMBER_PATTERN = re.compile(
 _TOLL_PATTERN = re.compile(
     r"(?:death\s+toll|toll)"
     r"\s+(?:rises?\s+to|hits?|reaches?|climbs?\s+to|stands?\s+at|now)\s+"
-    r"(\d[\d,]*)"
-    r"|"
-    r"(?:kills?|killed)\s+(\d[\d,]*)",
+    + _NUM
+    + r"|"
+    r"(?:kills?|killed)\s+" + _NUM,
     re.IGNORECASE,
 )
 
@@ -1019,34 +1079,311 @@ _TOLL_PATTERN = re.compile(
 _ATLEAST_PATTERN = re.compile(
     r"(?:at\s+least|over|more\s+than|nearly|approximately|"
     r"about|up\s+to|around|some)\s+"
-    r"(\d[\d,]*(?:\.\d+)?)\s*"
+    + _NUM + r"\s*"
     r"(people|persons|dead|killed|deaths|displaced|injured|"
     r"missing|affected|houses|homes|children|families|"
-    r"schools|health)",
+    r"schools|health|" + _AGRICULTURE + r"|" + _INFRASTRUCTURE + r")",
     re.IGNORECASE,
 )
 
 # Pattern 4: "NUM killed/dead/deaths" at sentence level
 _SENTENCE_FIGURE_PATTERN = re.compile(
-    r"\b(\d[\d,]*)\b[^.]{0,30}\b"
+    r"\b" + _NUM + r"\b[^.]{0,30}\b"
     r"(killed|dead|deaths|drowned|perished|fatalities)",
     re.IGNORECASE,
 )
 
 
-def _extract_figures(text: str) -> dict[str, int]:
-    """Extract numeric figures from text using multiple patterns."""
+# Ranges: "between 50 and 70 people dead", "30–40 houses", "5 to 10 killed"
+_RANGE_PATTERN = re.compile(
+    r"(?:between\s+" + _NUM + r"\s+and\s+" + _NUM
+    + r"|" + _NUM + r"\s*(?:-|–|—|to)\s*" + _NUM + r")"
+    r"\s*(?:(?:people|persons)\s+)?"
+    r"(people|persons|individuals|deaths|dead|killed|"
+    r"displaced|injured|missing|houses|homes|affected|"
+    r"families|households|children|schools|"
+    r"health\s*facilit|" + _AGRICULTURE + r"|" + _INFRASTRUCTURE + r")",
+    re.IGNORECASE,
+)
+
+
+def _label_key(label: str) -> str:
+    """Figure key for a pattern label."""
+    label = label.strip().lower()
+    if label in ("deaths", "dead", "killed"):
+        return "deaths"
+    if label in ("displaced", "injured", "missing"):
+        return label
+    if label in ("houses", "homes"):
+        return "houses_affected"
+    if label == "children":
+        return "children_affected"
+    if label == "schools":
+        return "schools_affected"
+    sector_key = _agriculture_key(label) or _infrastructure_key(label)
+    if sector_key:
+        return sector_key
+    if label.startswith("health"):
+        return "health_facilities_affected"
+    return "people_affected"
+
+
+def _negated_keys(word: str) -> tuple[str, ...]:
+    """Keys a negated word says are zero: "no casualties" is no deaths
+    and no injuries."""
+    word = word.lower()
+    if word == "casualties":
+        return ("deaths", "injured")
+    if word in ("injuries", "injured", "hurt"):
+        return ("injured",)
+    if word in ("displaced", "missing"):
+        return (word,)
+    return ("deaths",)
+
+
+def _percent_key(subject: str) -> str:
+    """Key for a percentage of ``subject``: "80% of households" is
+    ``pct_households_affected``."""
+    subject = subject.lower()
+    if subject in ("households", "families"):
+        return "pct_households_affected"
+    if subject in ("houses", "homes", "buildings", "dwellings"):
+        return "pct_houses_affected"
+    if subject == "children":
+        return "pct_children_affected"
+    if subject == "schools":
+        return "pct_schools_affected"
+    if subject in ("crops", "cropland", "farmland", "harvest", "fields"):
+        return "pct_crops_affected"
+    if subject in ("livestock", "cattle"):
+        return "pct_livestock_lost"
+    if subject in ("people", "persons", "population", "residents", "inhabitants", "individuals"):
+        return "pct_people_affected"
+    if subject.startswith("health"):
+        return "pct_health_facilities_affected"
+    return "pct_area_affected"
+
+
+def _agriculture_key(label: str) -> str | None:
+    """Figure key for an agricultural label, or None."""
+    label = label.strip().lower()
+    if label.startswith(("hectare", "acre")) or label == "ha":
+        return "crops_hectares_affected"
+    if label.startswith("head") or label in ("cattle", "livestock", "goats", "sheep", "camels"):
+        return "livestock_lost"
+    return None
+
+
+def _infrastructure_key(label: str) -> str | None:
+    """Figure key for an infrastructure label, or None."""
+    label = label.strip().lower()
+    if label == "bridges":
+        return "bridges_damaged"
+    if label in ("substations", "transformers", "pylons") or label.startswith(("power", "electricity")):
+        return "power_assets_affected"
+    if label.endswith(("road", "roads")) or label.startswith("highway") or " highway" in label:
+        return "roads_km_damaged"
+    return None
+
+
+def _in_key_unit(label: str, value: int) -> int:
+    """``value`` in the unit of the label's key: acres become hectares and
+    miles kilometres."""
+    unit = label.lower().split()[0] if label.split() else ""
+    if unit.startswith("acre"):
+        return round(value * _HECTARES_PER_ACRE)
+    if unit in ("mi", "mile", "miles"):
+        return round(value * _KM_PER_MILE)
+    return value
+
+
+def _number_parts(raw: str) -> tuple[float, int] | None:
+    """A ``_NUM`` match as ``(base, magnitude)``: "1.2 million" → (1.2, 10**6)."""
+    match = re.match(r"([\d,.]*)\s*(.*)", raw.strip().lower())
+    digits, words = match.group(1), match.group(2).split()
+    if digits:
+        try:
+            base = float(digits.replace(",", ""))
+        except ValueError:
+            return None
+    elif words[:2] == ["half", "a"]:
+        base, words = 0.5, words[2:]
+    elif words[:1] == ["a"]:
+        base, words = 1.0, words[1:]
+    else:
+        return None
+    magnitude = _MAGNITUDES.get(" ".join(words))
+    if magnitude is None:
+        return None
+    return base, magnitude
+
+
+def _parse_number(raw: str) -> int | None:
+    """Value of a ``_NUM`` match: digits (commas ignored) times any magnitude."""
+    parts = _number_parts(raw)
+    return None if parts is None else round(parts[0] * parts[1])
+
+
+def _parse_range(lower: str, upper: str) -> tuple[int, int] | None:
+    """Bounds of a range; a bare lower bound takes the upper's magnitude."""
+    low_parts, high_parts = _number_parts(lower), _number_parts(upper)
+    if low_parts is None or high_parts is None:
+        return None
+    (low, low_mag), (high, high_mag) = low_parts, high_parts
+    if low_mag == 1 and low <= high:
+        low_mag = high_mag
+    low, high = round(low * low_mag), round(high * high_mag)
+    return (low, high) if low < high else None
+
+
+# ── Spelled-out numbers ───────────────────────────────────────────────
+# Sitreps often write figures as words ("five hundred families
+# displaced").  Runs of number words are rewritten as digits before the
+# patterns run, mirroring the Rust extractor.
+_UNITS = {w: i + 1 for i, w in enumerate(
+    "one two three four five six seven eight nine".split())}
+_TEENS = {w: i + 10 for i, w in enumerate(
+    "ten eleven twelve thirteen fourteen fifteen sixteen seventeen "
+    "eighteen nineteen".split())}
+_TENS = {w: i * 10 + 20 for i, w in enumerate(
+    "twenty thirty forty fifty sixty seventy eighty ninety".split())}
+_SCALES = {"thousand": 1_000, "million": 1_000_000, "billion": 1_000_000_000}
+# Words before a lone "one" that make it a pronoun: "no one was killed".
+_NOT_A_COUNT = {"no", "any", "every", "each", "the", "which", "this", "that"}
+
+
+def _number_word(word: str) -> tuple[str, int] | None:
+    word = word.lower()
+    for kind, table in (("unit", _UNITS), ("teen", _TEENS), ("ten", _TENS), ("scale", _SCALES)):
+        if word in table:
+            return kind, table[word]
+    if word in ("hundred", "and", "a"):
+        return word, 0
+    return None
+
+
+def _follows(prev: tuple[str, int] | None, nxt: tuple[str, int], last_scale: float) -> bool:
+    p, n = (prev[0] if prev else None), nxt[0]
+    if p is None:
+        return n in ("unit", "teen", "ten", "a")
+    if n == "scale":
+        return p in ("unit", "teen", "ten", "hundred") and nxt[1] < last_scale
+    return (
+        (p == "a" and n == "hundred")
+        or (p in ("unit", "teen") and n == "hundred")
+        or (p == "ten" and n == "unit")
+        or (p in ("hundred", "scale") and n in ("unit", "teen", "ten", "and"))
+        or (p == "and" and n in ("unit", "teen", "ten"))
+    )
+
+
+def _cardinal(words: list[tuple[str, int]]) -> int:
+    total = current = 0
+    for kind, value in words:
+        if kind in ("unit", "teen", "ten"):
+            current += value
+        elif kind == "a":
+            current = 1
+        elif kind == "hundred":
+            current = max(current, 1) * 100
+        elif kind == "scale":
+            total += max(current, 1) * value
+            current = 0
+    return total + current
+
+
+def _words_to_digits(text: str) -> str:
+    """Rewrite runs of number words as digits: "twelve people" → "12 people"."""
+    runs: list[dict] = []
+    run: dict | None = None
+    previous = ""
+    for m in re.finditer(r"[A-Za-z]+", text):
+        word = _number_word(m.group())
+        if run is not None and word is not None:
+            gap = text[run["end"]:m.start()]
+            if (not gap.strip() or gap == "-") and _follows(run["words"][-1], word, run["scale"]):
+                run["words"].append(word)
+                run["end"] = m.end()
+                if word[0] == "scale":
+                    run["scale"] = word[1]
+                continue
+        if run is not None:
+            runs.append(run)
+            run = None
+        if word is not None and _follows(None, word, float("inf")):
+            run = {"start": m.start(), "end": m.end(), "words": [word],
+                   "scale": float("inf"), "determiner": previous.lower() in _NOT_A_COUNT}
+        previous = m.group()
+    if run is not None:
+        runs.append(run)
+
+    out, copied = [], 0
+    for run in runs:
+        words, end = run["words"], run["end"]
+        # A trailing "and" joins the next clause, not the number
+        if words[-1][0] == "and":
+            words = words[:-1]
+            end = len(re.sub(r"\s*and$", "", text[:end], flags=re.IGNORECASE))
+        if [w[0] for w in words] == ["a"] or (words == [("unit", 1)] and run["determiner"]):
+            continue
+        out.append(text[copied:run["start"]])
+        out.append(str(_cardinal(words)))
+        copied = end
+    out.append(text[copied:])
+    return "".join(out)
+
+
+def _extract_figures(text: str, negated_zero: bool = True) -> dict[str, int]:
+    """Extract numeric figures from text using multiple patterns.
+
+    Numbers in negated statements ("2 districts reported no deaths") are
+    ignored; with ``negated_zero`` the statement gives its keys 0.
+    """
     figures: dict[str, int] = {}
+    text = _words_to_digits(text)
+    negations = [
+        (match.span(), _negated_keys(match.group(1)))
+        for match in _NEGATION_PATTERN.finditer(text)
+    ]
 
     def _accum(key: str, value: int) -> None:
         figures[key] = max(figures.get(key, 0), value)
 
+    def _negated(match: re.Match) -> bool:
+        return any(
+            match.start() < end and start < match.end()
+            for (start, end), _ in negations
+        )
+
+    # Ranges first, as <key>_min / <key>_max, and percentages, as
+    # pct_<subject>_...; both are then blanked so the patterns below don't
+    # take an endpoint or a share as a count.
+    spans = []
+    for match in _RANGE_PATTERN.finditer(text):
+        lower, upper = (match.group(1), match.group(2)) if match.group(1) else (match.group(3), match.group(4))
+        bounds = _parse_range(lower, upper)
+        if bounds is None:
+            continue
+        if not _negated(match):
+            key = _label_key(match.group(5))
+            _accum(f"{key}_min", _in_key_unit(match.group(5), bounds[0]))
+            _accum(f"{key}_max", _in_key_unit(match.group(5), bounds[1]))
+        spans.append(match.span())
+    for match in _PERCENT_PATTERN.finditer(text):
+        share = float(match.group(1))
+        if share <= 100:
+            if not _negated(match):
+                _accum(_percent_key(match.group(2)), int(share + 0.5))
+            spans.append(match.span())
+    for start, end in spans:
+        text = text[:start] + " " * (end - start) + text[end:]
+
     # Pattern 1: standard NUM + keyword
     for match in _NUMBER_PATTERN.finditer(text):
-        raw_num = match.group(1).replace(",", "")
-        try:
-            value = int(float(raw_num))
-        except ValueError:
+        if _negated(match):
+            continue
+        value = _parse_number(match.group(1))
+        if value is None:
             continue
         label = match.group(2).strip().lower()
         if label in ("deaths", "dead", "killed"):
@@ -1070,23 +1407,27 @@ def _extract_figures(text: str) -> dict[str, int]:
             _accum("schools_affected", value)
         elif label.startswith("health"):
             _accum("health_facilities_affected", value)
+        elif _agriculture_key(label):
+            _accum(_agriculture_key(label), _in_key_unit(label, value))
+        elif _infrastructure_key(label):
+            _accum(_infrastructure_key(label), _in_key_unit(label, value))
 
     # Pattern 2: "death toll rises to 59" / "kills 4"
     for match in _TOLL_PATTERN.finditer(text):
-        raw_num = (match.group(1) or match.group(2) or "").replace(",", "")
-        try:
-            value = int(float(raw_num))
-        except ValueError:
+        if _negated(match):
             continue
-        if value > 0:
+        if _NOT_PEOPLE.match(text, match.end()):
+            continue
+        value = _parse_number(match.group(1) or match.group(2) or "")
+        if value is not None and value > 0:
             _accum("deaths", value)
 
     # Pattern 3: "at least 48,000 displaced"
     for match in _ATLEAST_PATTERN.finditer(text):
-        raw_num = match.group(1).replace(",", "")
-        try:
-            value = int(float(raw_num))
-        except ValueError:
+        if _negated(match):
+            continue
+        value = _parse_number(match.group(1))
+        if value is None:
             continue
         label = match.group(2).strip().lower()
         if label in ("dead", "killed", "deaths"):
@@ -1110,17 +1451,25 @@ def _extract_figures(text: str) -> dict[str, int]:
             _accum("schools_affected", value)
         elif label.startswith("health"):
             _accum("health_facilities_affected", value)
+        elif _agriculture_key(label):
+            _accum(_agriculture_key(label), _in_key_unit(label, value))
+        elif _infrastructure_key(label):
+            _accum(_infrastructure_key(label), _in_key_unit(label, value))
 
     # Pattern 4: "59 killed" / "40 dead" in sentence context
     for match in _SENTENCE_FIGURE_PATTERN.finditer(text):
-        raw_num = match.group(1).replace(",", "")
-        try:
-            value = int(float(raw_num))
-        except ValueError:
+        if _negated(match):
+            continue
+        if _NOT_PEOPLE.match(text, match.end(1)):
             continue
-        if 0 < value < 1_000_000:
+        value = _parse_number(match.group(1))
+        if value is not None and 0 < value < 1_000_000:
             _accum("deaths", value)
 
+    if negated_zero:
+        for _, keys in negations:
+            for key in keys:
+                _accum(key, 0)
     return figures
 
 
@@ -2,6 +2,8 @@
 
 from __future__ import annotations
 
+from functools import lru_cache
+from pathlib import Path
 from urllib.parse import parse_qs, urlencode, urlparse, urlunparse
 
 import httpx
@@ -55,12 +57,97 @@ def _strip_tracking_params(url: str) -> str:
     return _strip_tracking_params_py(url)
 
 
+# (host, path prefix, target keys); mirrors the Rust defaults in
+# rust_core/src/url_canonical.rs (see configure_redirect_hosts there).
+# ``name.*`` is ``name`` under any public suffix (google.com, google.co.mz),
+# so look-alikes such as google.evil.test are not unwrapped.
+GOOGLE_REDIRECT_ENDPOINTS = (
+    ("news.google.*", "/", ("url", "u", "q")),
+    ("google.*", "/url", ("q", "url")),
+    ("www.google.*", "/url", ("q", "url")),
+    ("google.*", "/sorry/", ("continue",)),
+    ("www.google.*", "/sorry/", ("continue",)),
+)
+
+_PUBLIC_SUFFIX_LIST = Path(__file__).resolve().parents[2] / "config" / "public_suffix_list.dat"
+
+
+def _to_ascii(rule: str) -> str:
+    try:
+        return rule.encode("idna").decode("ascii")
+    except UnicodeError:
+        return rule.lower()
+
+
+@lru_cache(maxsize=1)
+def _suffix_rules() -> tuple[frozenset[str], frozenset[str], frozenset[str]]:
+    """(exact, wildcard, exception) rules of the public suffix list, as in
+    rust_core/src/public_suffix.rs."""
+    exact: set[str] = set()
+    wildcard: set[str] = set()
+    exception: set[str] = set()
+    for line in _PUBLIC_SUFFIX_LIST.read_text(encoding="utf-8").splitlines():
+        parts = line.split()
+        if not parts or parts[0].startswith("//"):
+            continue
+        rule = parts[0]
+        if rule.startswith("!"):
+            exception.add(_to_ascii(rule[1:]))
+        elif rule.startswith("*."):
+            wildcard.add(_to_ascii(rule[2:]))
+        else:
+            exact.add(_to_ascii(rule))
+    return frozenset(exact), frozenset(wildcard), frozenset(exception)
+
+
+def _registrable_domain(host: str) -> str | None:
+    """Registrable domain (eTLD+1) of a lowercase ASCII host, or None if
+    the host is itself a public suffix."""
+    exact, wildcard, exception = _suffix_rules()
+    labels = [label for label in host.rstrip(".").split(".") if label]
+    start = max(len(labels) - 1, 0)
+    for i in range(len(labels)):
+        candidate = ".".join(labels[i:])
+        if candidate in exception:
+            start = i + 1
+            break
+        if candidate in exact or (i + 1 < len(labels) and ".".join(labels[i + 1:]) in wildcard):
+            start = i
+            break
+    if start == 0:
+        return None
+    return ".".join(labels[start - 1:])
+
+
+def _is_public_suffix_under(name: str, suffix: str) -> bool:
+    """True if ``suffix`` is a public suffix, judged by ``name.suffix``
+    being registrable exactly at its last label (``google`` + ``co.mz``)."""
+    candidate = f"{name.rsplit('.', 1)[-1]}.{suffix}"
+    return _registrable_domain(candidate) == candidate
+
+
+def _endpoint_matches(endpoint_host: str, host: str) -> bool:
+    if endpoint_host.endswith(".*"):
+        name = endpoint_host[:-2]
+        return host.startswith(name + ".") and _is_public_suffix_under(name, host[len(name) + 1:])
+    return host == endpoint_host
+
+
 def _extract_google_target(url: str) -> str | None:
     parsed = urlparse(url)
-    if "news.google." not in parsed.netloc.lower():
+    host = (parsed.hostname or "").lower()
+    keys = next(
+        (
+            endpoint_keys
+            for endpoint_host, path, endpoint_keys in GOOGLE_REDIRECT_ENDPOINTS
+            if parsed.path.startswith(path) and _endpoint_matches(endpoint_host, host)
+        ),
+        None,
+    )
+    if keys is None:
         return None
     qs = parse_qs(parsed.query, keep_blank_values=False)
-    for key in ("url", "u", "q"):
+    for key in keys:
         values = qs.get(key, [])
         if values:
             candidate = values[0].strip()
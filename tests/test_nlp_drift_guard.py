@@ -5,10 +5,12 @@ canonical sentences and assert that the Rust-path result exactly matches the
 pure-Python implementation in ``graph_ontology``.
 
 These tests protect against silent keyword-table drift between the two
-implementations.  They run automatically when the Rust extension is built
-(``maturin develop``).  If the extension is absent, the entire module is
-skipped — it is never expected to break the CI of developers without a
-Rust toolchain.
+implementations. Google redirect unwrapping in ``url_canonical`` is checked
+the same way, since a look-alike host unwrapped by only one side would let
+an attacker-controlled target into citations.  They run automatically when
+the Rust extension is built (``maturin develop``).  If the extension is
+absent, the entire module is skipped — it is never expected to break the
+CI of developers without a Rust toolchain.
 
 Run:
     cd rust_core && maturin develop   # build once
@@ -38,6 +40,13 @@ def py_ontology():
     return go
 
 
+@pytest.fixture(scope="module")
+def py_url():
+    """URL canonicalization module for pure-Python reference."""
+    from agent_hum_crawler import url_canonical as uc  # noqa: PLC0415
+    return uc
+
+
 # ── Sentence corpora ──────────────────────────────────────────────────
 
 IMPACT_SENTENCES: list[tuple[str, str]] = [
@@ -196,3 +205,28 @@ def test_extract_figures_rust_matches_python(text, must_have_keys, rust_mod, py_
         assert key in rust_result, (
             f"Expected figure key {key!r} for: {text!r}  got={rust_result}"
         )
+
+
+# ── Tests: Google redirect unwrapping ─────────────────────────────────
+
+REDIRECT_URLS: list[tuple[str, bool]] = [
+    # (url, unwrapped to https://example.org/a)
+    ("https://www.google.com/url?q=https://example.org/a",          True),
+    ("https://google.co.mz/url?q=https://example.org/a",           True),
+    ("https://news.google.com/?url=https://example.org/a",         True),
+    # Look-alikes: "google." followed by a registrable domain, not a suffix
+    ("https://google.evil.test/url?q=https://example.org/a",       False),
+    ("https://www.google.evil.test/sorry/?continue=https://example.org/a", False),
+]
+
+
+@pytest.mark.parametrize("url,unwrapped", REDIRECT_URLS)
+def test_google_redirects_rust_matches_python(url, unwrapped, rust_mod, py_url):
+    rust_result = rust_mod.canonicalize_url(url)
+    py_result = py_url.canonicalize_url(url)
+    assert rust_result == py_result, (
+        f"Rust/Python canonical URL mismatch for: {url!r}\n"
+        f"  Rust  => {rust_result}\n"
+        f"  Python=> {py_result}"
+    )
+    assert (py_result == "https://example.org/a") == unwrapped